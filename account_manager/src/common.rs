@@ -1,7 +1,11 @@
+use account_utils::read_password_from_user;
 use clap::ArgMatches;
-use std::fs::create_dir_all;
+use eth2_wallet::bip39::{Language, Mnemonic};
+use std::fs::{self, create_dir_all};
 use std::path::{Path, PathBuf};
 
+pub const MNEMONIC_PROMPT: &str = "Enter the mnemonic phrase:";
+
 pub fn ensure_dir_exists<P: AsRef<Path>>(path: P) -> Result<(), String> {
     let path = path.as_ref();
 
@@ -19,3 +23,25 @@ pub fn base_wallet_dir(matches: &ArgMatches, arg: &'static str) -> Result<PathBu
         PathBuf::new().join(".lighthouse").join("wallets"),
     )
 }
+
+/// Reads a BIP-39 mnemonic from `mnemonic_path` if it is supplied, otherwise from the tty (or
+/// stdin if `stdin_inputs == true`).
+pub fn read_mnemonic_from_cli(
+    mnemonic_path: Option<PathBuf>,
+    stdin_inputs: bool,
+) -> Result<Mnemonic, String> {
+    let phrase = match mnemonic_path {
+        Some(path) => {
+            fs::read_to_string(&path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+        }
+        None => {
+            eprintln!("{}", MNEMONIC_PROMPT);
+            let phrase = read_password_from_user(stdin_inputs)?;
+            String::from_utf8(phrase.as_ref().to_vec())
+                .map_err(|e| format!("Mnemonic is not valid UTF-8: {:?}", e))?
+        }
+    };
+
+    Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|e| format!("Unable to derive mnemonic from phrase: {:?}", e))
+}
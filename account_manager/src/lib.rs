@@ -1,4 +1,5 @@
 mod common;
+pub mod migrate_datadir;
 pub mod validator;
 pub mod wallet;
 
@@ -18,6 +19,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .about("Utilities for generating and managing Ethereum 2.0 accounts.")
         .subcommand(wallet::cli_app())
         .subcommand(validator::cli_app())
+        .subcommand(migrate_datadir::cli_app())
 }
 
 /// Run the account manager, returning an error if the operation did not succeed.
@@ -25,6 +27,7 @@ pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<
     match matches.subcommand() {
         (wallet::CMD, Some(matches)) => wallet::cli_run(matches)?,
         (validator::CMD, Some(matches)) => validator::cli_run(matches, env)?,
+        (migrate_datadir::CMD, Some(matches)) => migrate_datadir::cli_run(matches)?,
         (unknown, _) => {
             return Err(format!(
                 "{} is not a valid {} command. See --help.",
@@ -0,0 +1,42 @@
+use account_utils::datadir::{migrate_datadir, SECRETS_DIR, VALIDATORS_DIR};
+use clap::{App, ArgMatches};
+use std::path::PathBuf;
+
+pub const CMD: &str = "migrate-datadir";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD).about(
+        "Moves validator directories, keystore passwords, the validator definitions and the \
+        slashing protection database from the root of the data directory (--datadir) into the \
+        validators and secrets directories, where the validator client expects them. The \
+        validator client must not be running.",
+    )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let datadir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        "datadir",
+        PathBuf::new().join(".lighthouse"),
+    )?;
+
+    let moved = migrate_datadir(&datadir)?;
+
+    for item in &moved {
+        println!("{:?} -> {:?}", item.path, item.destination);
+    }
+
+    if moved.is_empty() {
+        eprintln!("The data directory {:?} is already up to date.", datadir);
+    } else {
+        eprintln!(
+            "Moved {} files into the {} and {} directories of {:?}.",
+            moved.len(),
+            VALIDATORS_DIR,
+            SECRETS_DIR,
+            datadir
+        );
+    }
+
+    Ok(())
+}
@@ -4,11 +4,14 @@ use clap::{App, Arg, ArgMatches};
 use environment::Environment;
 use eth2_wallet::PlainText;
 use eth2_wallet_manager::WalletManager;
+use slashing_protection::SLASHING_PROTECTION_FILENAME;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use types::EthSpec;
-use validator_dir::Builder as ValidatorDirBuilder;
+use validator_dir::{
+    Builder as ValidatorDirBuilder, LaunchpadDeposit, LAUNCHPAD_DEPOSIT_DATA_FILE,
+};
 
 pub const CMD: &str = "create";
 pub const BASE_DIR_FLAG: &str = "base-dir";
@@ -18,6 +21,7 @@ pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
+pub const DEPOSIT_DATA_JSON_FLAG: &str = "deposit-data-json";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
@@ -67,7 +71,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("DEPOSIT_GWEI")
                 .help(
                     "The GWEI value of the deposit amount. Defaults to the minimum amount \
-                    required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                    required for an active validator (MAX_EFFECTIVE_BALANCE). Testnets which \
+                    accept smaller deposits may be given a lower value.",
                 )
                 .takes_value(true),
         )
@@ -99,6 +104,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .conflicts_with("count")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(DEPOSIT_DATA_JSON_FLAG)
+                .long(DEPOSIT_DATA_JSON_FLAG)
+                .value_name("DEPOSIT_DATA_PATH")
+                .help(
+                    "If present, the deposits of the created validators will be written to this \
+                    path in the JSON format accepted by the Eth2 launchpad. If the path is a \
+                    directory, the deposits are written to a deposit_data.json file inside it.",
+                )
+                .takes_value(true),
+        )
 }
 
 pub fn cli_run<T: EthSpec>(
@@ -125,6 +141,11 @@ pub fn cli_run<T: EthSpec>(
         .unwrap_or_else(|| spec.max_effective_balance);
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
+    // Resolve and check the deposit data path before creating any validators, so a bad path
+    // doesn't leave behind validators without their deposit data.
+    let deposit_data_json = clap_utils::parse_optional(matches, DEPOSIT_DATA_JSON_FLAG)?
+        .map(launchpad_deposits_path)
+        .transpose()?;
 
     ensure_dir_exists(&validator_dir)?;
     ensure_dir_exists(&secrets_dir)?;
@@ -163,6 +184,8 @@ pub fn cli_run<T: EthSpec>(
         .wallet_by_name(&name)
         .map_err(|e| format!("Unable to open wallet: {:?}", e))?;
 
+    let mut launchpad_deposits = Vec::with_capacity(n);
+
     for i in 0..n {
         let voting_password = random_password();
         let withdrawal_password = random_password();
@@ -177,7 +200,7 @@ pub fn cli_run<T: EthSpec>(
 
         let voting_pubkey = keystores.voting.pubkey().to_string();
 
-        ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
+        let created_dir = ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
             .create_eth1_tx_data(deposit_gwei, &spec)
@@ -185,22 +208,77 @@ pub fn cli_run<T: EthSpec>(
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
+        let deposit_data = created_dir
+            .eth1_deposit_data()
+            .map_err(|e| format!("Unable to read deposit data: {:?}", e))?
+            .ok_or_else(|| format!("Deposit data missing for 0x{}", voting_pubkey))?
+            .deposit_data;
+        launchpad_deposits.push(LaunchpadDeposit::new(&deposit_data, &spec));
+
         println!("{}/{}\t0x{}", i + 1, n, voting_pubkey);
     }
 
+    if let Some(path) = deposit_data_json {
+        write_launchpad_deposits(path, &launchpad_deposits)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the file that launchpad deposits should be written to for the user-supplied `path`.
+///
+/// If `path` is a directory, the deposits are written to `LAUNCHPAD_DEPOSIT_DATA_FILE` inside it.
+/// Returns an error if the file already exists or its parent directory does not.
+fn launchpad_deposits_path(path: PathBuf) -> Result<PathBuf, String> {
+    let path = if path.is_dir() {
+        path.join(LAUNCHPAD_DEPOSIT_DATA_FILE)
+    } else {
+        path
+    };
+
+    if path.exists() {
+        return Err(format!("Deposit data file already exists at {:?}", path));
+    }
+
+    match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() || parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "Parent directory of deposit data file {:?} does not exist",
+            path
+        )),
+    }
+}
+
+/// Writes `deposits` to `path` as a JSON list, in the format accepted by the Eth2 launchpad.
+///
+/// The `path` should have been resolved by `launchpad_deposits_path`.
+fn write_launchpad_deposits(path: PathBuf, deposits: &[LaunchpadDeposit]) -> Result<(), String> {
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+    serde_json::to_writer_pretty(file, deposits)
+        .map_err(|e| format!("Unable to write deposit data to {:?}: {:?}", path, e))?;
+
+    eprintln!("Deposit data written to {:?}", path);
+
     Ok(())
 }
 
 /// Returns the number of validators that exist in the given `validator_dir`.
 ///
-/// This function just assumes all files and directories, excluding the validator definitions YAML,
-/// are validator directories, making it likely to return a higher number than accurate
-/// but never a lower one.
+/// This function just assumes all files and directories, excluding the validator definitions YAML
+/// and the slashing protection database, are validator directories, making it likely to return a
+/// higher number than accurate but never a lower one.
 fn existing_validator_count<P: AsRef<Path>>(validator_dir: P) -> Result<usize, String> {
     fs::read_dir(validator_dir.as_ref())
         .map(|iter| {
             iter.filter_map(|e| e.ok())
-                .filter(|e| e.file_name() != OsStr::new(validator_definitions::CONFIG_FILENAME))
+                .filter(|e| {
+                    e.file_name() != OsStr::new(validator_definitions::CONFIG_FILENAME)
+                        && e.file_name() != OsStr::new(SLASHING_PROTECTION_FILENAME)
+                })
                 .count()
         })
         .map_err(|e| format!("Unable to read {:?}: {}", validator_dir.as_ref(), e))
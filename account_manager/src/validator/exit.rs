@@ -0,0 +1,199 @@
+use account_utils::{eth2_keystore::Keystore, read_password_from_user};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use remote_beacon_node::RemoteBeaconNode;
+use std::io::stdin;
+use std::path::PathBuf;
+use types::{ChainSpec, EthSpec, Keypair, SignedVoluntaryExit, VoluntaryExit};
+
+pub const CMD: &str = "exit";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const BEACON_SERVER_FLAG: &str = "beacon-node";
+pub const STDIN_INPUTS_FLAG: &str = "stdin-inputs";
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password:";
+pub const CONFIRMATION_PHRASE: &str = "Exit my validator";
+pub const EXIT_WARNING: &str = "WARNING: THIS IS AN IRREVERSIBLE OPERATION. AN EXITED VALIDATOR \
+                                CANNOT BE RE-ACTIVATED AND ITS BALANCE CANNOT BE WITHDRAWN UNTIL \
+                                WITHDRAWALS ARE ENABLED.";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Submits a voluntary exit for a validator, after which it will no longer be \
+            required to perform its duties. The exit is signed with the keystore's voting \
+            key and published to the network by the beacon node.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to the EIP-2335 voting keystore of the validator to exit.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address of the beacon node HTTP API.")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read the keystore password from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let server: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let spec = env.core_context().eth2_config.spec;
+    let spec_constants = env.core_context().eth2_config.spec_constants;
+
+    let keypair = load_voting_keypair(&keystore_path, stdin_inputs)?;
+    let beacon_node = RemoteBeaconNode::<T>::new(server)?;
+
+    env.runtime().block_on(async {
+        let eth2_config = beacon_node
+            .http
+            .spec()
+            .get_eth2_config()
+            .await
+            .map_err(|e| format!("Unable to connect to beacon node: {:?}", e))?;
+
+        // The fork is fetched from the beacon node, but the exit is signed with the local spec's
+        // domain, so the beacon node must use the same spec constants.
+        if eth2_config.spec_constants != spec_constants {
+            return Err(format!(
+                "Beacon node is using an incompatible spec. Got {}, expected {}",
+                eth2_config.spec_constants, spec_constants
+            ));
+        }
+
+        let signed_exit = sign_exit(&beacon_node, &keypair, &spec).await?;
+
+        eprintln!("");
+        eprintln!(
+            "Publishing a voluntary exit for validator {}:",
+            keypair.pk.to_hex_string()
+        );
+        eprintln!("");
+        eprintln!(
+            " - Validator index: {}",
+            signed_exit.message.validator_index
+        );
+        eprintln!(" - Exit epoch: {}", signed_exit.message.epoch);
+        eprintln!("");
+        eprintln!("{}", EXIT_WARNING);
+        eprintln!("");
+        eprintln!(
+            "Enter the phrase \"{}\" (without quotes) to confirm the exit:",
+            CONFIRMATION_PHRASE
+        );
+
+        let mut confirmation = String::new();
+        stdin()
+            .read_line(&mut confirmation)
+            .map_err(|e| format!("Unable to read confirmation: {:?}", e))?;
+
+        if confirmation.trim() != CONFIRMATION_PHRASE {
+            return Err("Confirmation phrase does not match, the exit was not published.".into());
+        }
+
+        beacon_node
+            .http
+            .beacon()
+            .voluntary_exit(signed_exit)
+            .await
+            .map_err(|e| format!("Unable to publish voluntary exit: {:?}", e))?;
+
+        eprintln!("");
+        eprintln!("Successfully published voluntary exit.");
+        eprintln!(
+            "The validator must continue to perform its duties until the exit epoch is reached."
+        );
+
+        Ok(())
+    })
+}
+
+/// Reads the keystore at `keystore_path`, prompting the user for its password until it decrypts.
+fn load_voting_keypair(keystore_path: &PathBuf, stdin_inputs: bool) -> Result<Keypair, String> {
+    let keystore = Keystore::from_json_file(keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+
+    loop {
+        eprintln!("");
+        eprintln!("{}", PASSWORD_PROMPT);
+
+        let password = read_password_from_user(stdin_inputs)?;
+
+        match keystore.decrypt_keypair(password.as_ref()) {
+            Ok(keypair) => {
+                eprintln!("Password is correct.");
+                break Ok(keypair);
+            }
+            Err(eth2_keystore::Error::InvalidPassword) => {
+                eprintln!("Invalid password");
+            }
+            Err(e) => break Err(format!("Error whilst decrypting keypair: {:?}", e)),
+        }
+    }
+}
+
+/// Signs an exit for the validator with the public key of `keypair`, at the current epoch of
+/// `beacon_node`.
+///
+/// Returns an error if the validator is unknown to the beacon node, or has already exited.
+async fn sign_exit<T: EthSpec>(
+    beacon_node: &RemoteBeaconNode<T>,
+    keypair: &Keypair,
+    spec: &ChainSpec,
+) -> Result<SignedVoluntaryExit, String> {
+    let beacon = beacon_node.http.beacon();
+
+    let head = beacon
+        .get_head()
+        .await
+        .map_err(|e| format!("Unable to get head from beacon node: {:?}", e))?;
+    let fork = beacon
+        .get_fork()
+        .await
+        .map_err(|e| format!("Unable to get fork from beacon node: {:?}", e))?;
+    let genesis_validators_root = beacon
+        .get_genesis_validators_root()
+        .await
+        .map_err(|e| format!("Unable to get genesis validators root: {:?}", e))?;
+    let validator = beacon
+        .get_validators(vec![keypair.pk.clone()], None)
+        .await
+        .map_err(|e| format!("Unable to get validator from beacon node: {:?}", e))?
+        .pop()
+        .ok_or_else(|| "Beacon node did not return the validator".to_string())?;
+
+    let validator_index = validator
+        .validator_index
+        .ok_or_else(|| "Validator is not known to the beacon node".to_string())?;
+
+    if let Some(validator) = validator.validator {
+        if validator.exit_epoch != spec.far_future_epoch {
+            return Err(format!(
+                "Validator has already exited, or is exiting, at epoch {}",
+                validator.exit_epoch
+            ));
+        }
+    }
+
+    let exit = VoluntaryExit {
+        epoch: head.slot.epoch(T::slots_per_epoch()),
+        validator_index: validator_index as u64,
+    };
+
+    Ok(exit.sign(&keypair.sk, &fork, genesis_validators_root, spec))
+}
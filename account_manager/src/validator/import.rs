@@ -8,6 +8,7 @@ use account_utils::{
     },
 };
 use clap::{App, Arg, ArgMatches};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::fs;
 use std::path::PathBuf;
 use std::thread::sleep;
@@ -28,7 +29,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             "Imports one or more EIP-2335 passwords into a Lighthouse VC directory, \
             requesting passwords interactively. The directory flag provides a convenient \
             method for importing a directory of keys generated by the eth2-deposit-cli \
-            Python utility.",
+            Python utility. Each imported key is registered with the slashing protection \
+            database of the validator client.",
         )
         .arg(
             Arg::with_name(KEYSTORE_FLAG)
@@ -85,6 +87,18 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
     let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
         .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
 
+    // The validator client holds an exclusive lock on the slashing protection database, so this
+    // will fail whilst it is running.
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection =
+        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
+            format!(
+                "Unable to open or create slashing protection database at {:?}, \
+                 ensure the validator client is not running: {:?}",
+                slashing_protection_path, e
+            )
+        })?;
+
     // Collect the paths for the keystores that should be imported.
     let keystore_paths = match (keystore, keystores_dir) {
         (Some(keystore), None) => vec![keystore],
@@ -114,6 +128,7 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
     // For each keystore:
     //
     // - Obtain the keystore password, if the user desires.
+    // - Register the public key with the slashing protection database.
     // - Copy the keystore into the `validator_dir`.
     // - Add the keystore to the validator definitions file.
     //
@@ -122,6 +137,9 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
     for src_keystore in &keystore_paths {
         let keystore = Keystore::from_json_file(src_keystore)
             .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", src_keystore, e))?;
+        let voting_pubkey = keystore
+            .public_key()
+            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
 
         eprintln!("");
         eprintln!("Keystore found at {:?}:", src_keystore);
@@ -161,6 +179,18 @@ pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
             }
         };
 
+        // Registering a key which is already known to the database has no effect, so this is
+        // safe to do even if the keystore is skipped below.
+        slashing_protection
+            .register_validator(&voting_pubkey)
+            .map_err(|e| {
+                format!(
+                    "Error registering validator 0x{} with slashing protection: {:?}",
+                    keystore.pubkey(),
+                    e
+                )
+            })?;
+
         // The keystore is placed in a directory that matches the name of the public key. This
         // provides some loose protection against adding the same keystore twice.
         let dest_dir = validator_dir.join(format!("0x{}", keystore.pubkey()));
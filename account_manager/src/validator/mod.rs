@@ -1,7 +1,11 @@
 pub mod create;
 pub mod deposit;
+pub mod exit;
 pub mod import;
 pub mod list;
+pub mod modify_password;
+pub mod recover;
+pub mod slashing_protection;
 
 use crate::common::base_wallet_dir;
 use clap::{App, Arg, ArgMatches};
@@ -22,8 +26,12 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         )
         .subcommand(create::cli_app())
         .subcommand(deposit::cli_app())
+        .subcommand(exit::cli_app())
         .subcommand(import::cli_app())
         .subcommand(list::cli_app())
+        .subcommand(modify_password::cli_app())
+        .subcommand(recover::cli_app())
+        .subcommand(slashing_protection::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -32,8 +40,14 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
     match matches.subcommand() {
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, base_wallet_dir),
         (deposit::CMD, Some(matches)) => deposit::cli_run::<T>(matches, env),
+        (exit::CMD, Some(matches)) => exit::cli_run::<T>(matches, env),
         (import::CMD, Some(matches)) => import::cli_run(matches),
         (list::CMD, Some(matches)) => list::cli_run(matches),
+        (modify_password::CMD, Some(matches)) => modify_password::cli_run(matches),
+        (recover::CMD, Some(matches)) => recover::cli_run(matches),
+        (slashing_protection::CMD, Some(matches)) => {
+            slashing_protection::cli_run::<T>(matches, env)
+        }
         (unknown, _) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
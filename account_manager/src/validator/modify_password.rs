@@ -0,0 +1,416 @@
+use crate::{SECRETS_DIR_FLAG, VALIDATOR_DIR_FLAG};
+use account_utils::{
+    create_with_600_perms, default_keystore_password_path,
+    eth2_keystore::{
+        default_kdf,
+        json_keystore::{Kdf, Pbkdf2, Prf},
+        Keystore, KeystoreBuilder, DKLEN, SALT_SIZE,
+    },
+    read_password, read_password_from_user,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+    PlainText, ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use rand::prelude::*;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use types::Keypair;
+
+pub const CMD: &str = "modify-password";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const KDF_FLAG: &str = "kdf";
+pub const STDIN_INPUTS_FLAG: &str = "stdin-inputs";
+
+pub const SCRYPT: &str = "scrypt";
+pub const PBKDF2: &str = "pbkdf2";
+
+pub const OLD_PASSWORD_PROMPT: &str = "Enter the current keystore password:";
+pub const NEW_PASSWORD_PROMPT: &str = "Enter the new keystore password:";
+pub const CONFIRM_PASSWORD_PROMPT: &str = "Re-enter the new keystore password:";
+
+/// The iteration count used when re-encrypting a keystore with PBKDF2, as recommended by
+/// EIP-2335.
+const PBKDF2_ITERATIONS: u32 = 262_144;
+
+/// The location of the password which the validator client uses to decrypt a keystore.
+enum PasswordStore {
+    /// The password is stored in a file, typically in the secrets directory.
+    File(PathBuf),
+    /// The password is stored in the validator definition at the given index.
+    Definition(usize),
+    /// The password is not stored, so must be entered whenever the validator client starts.
+    None,
+}
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Changes the password of an EIP-2335 voting keystore. The keystore is decrypted \
+            with the current password and re-encrypted with the new password, updating the \
+            password stored in the secrets directory or validator definitions file. The \
+            validator client must not be running.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to the EIP-2335 voting keystore.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help(
+                    "The key derivation function used to re-encrypt the keystore. Defaults to \
+                    the function and parameters used by the existing keystore.",
+                )
+                .possible_values(&[SCRYPT, PBKDF2])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to the directory containing the validator definitions file. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path to the directory containing keystore passwords, used if the \
+                    keystore is not in the validator definitions file. Defaults to \
+                    ~/.lighthouse/secrets",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read passwords from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let kdf_name: Option<String> = clap_utils::parse_optional(matches, KDF_FLAG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let secrets_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRETS_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("secrets"),
+    )?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let keystore = Keystore::from_json_file(&keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+    let voting_pubkey = keystore
+        .public_key()
+        .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+
+    // The validator client creates a lockfile adjacent to each keystore it has loaded.
+    let lockfile_path = keystore_path.with_file_name(format!(
+        "{}.lock",
+        keystore_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| format!("Badly formatted file name: {:?}", keystore_path))?
+    ));
+    if lockfile_path.exists() {
+        return Err(format!(
+            "The keystore is locked by {:?}, ensure the validator client is not running",
+            lockfile_path
+        ));
+    }
+
+    let mut defs = if validator_dir.join(CONFIG_FILENAME).exists() {
+        Some(
+            ValidatorDefinitions::open(&validator_dir)
+                .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?,
+        )
+    } else {
+        None
+    };
+
+    let def_index = defs.as_ref().and_then(|defs| {
+        defs.as_slice()
+            .iter()
+            .position(|def| def.voting_public_key == voting_pubkey)
+    });
+
+    let password_store = match (&defs, def_index) {
+        (Some(defs), Some(index)) => match &defs.as_slice()[index].signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => {
+                if !same_file(voting_keystore_path, &keystore_path)? {
+                    return Err(format!(
+                        "The validator definition for 0x{} refers to a different keystore: {:?}",
+                        keystore.pubkey(),
+                        voting_keystore_path
+                    ));
+                }
+
+                // The validator client prefers the password in the definition over the path.
+                match (voting_keystore_password, voting_keystore_password_path) {
+                    (Some(_), _) => PasswordStore::Definition(index),
+                    (None, Some(path)) => PasswordStore::File(path.clone()),
+                    (None, None) => PasswordStore::None,
+                }
+            }
+        },
+        _ => {
+            let path = default_keystore_password_path(&keystore, &secrets_dir);
+            if path.exists() {
+                PasswordStore::File(path)
+            } else {
+                PasswordStore::None
+            }
+        }
+    };
+
+    let stored_password = match &password_store {
+        PasswordStore::File(path) => Some(
+            read_password(path)
+                .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e))?,
+        ),
+        PasswordStore::Definition(index) => match defs.as_ref().map(|defs| defs.as_slice()) {
+            Some(defs) => match &defs[*index].signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_password: Some(password),
+                    ..
+                } => Some(PlainText::from(password.as_ref().to_vec())),
+                _ => None,
+            },
+            None => None,
+        },
+        PasswordStore::None => None,
+    };
+
+    let keypair = decrypt_keypair(&keystore, stored_password, stdin_inputs)?;
+    let new_password = read_new_password(stdin_inputs)?;
+
+    let kdf = new_kdf(keystore.kdf(), kdf_name.as_deref())?;
+    let new_keystore = KeystoreBuilder::new(
+        &keypair,
+        new_password.as_ref(),
+        keystore.path().unwrap_or_default(),
+    )
+    .map_err(|e| format!("Unable to build keystore: {:?}", e))?
+    .kdf(kdf)
+    .build()
+    .map_err(|e| format!("Unable to build keystore: {:?}", e))?;
+
+    // Write the new keystore alongside the existing one, so that it can replace it with a single
+    // rename once the new password has been stored.
+    let tmp_keystore_path = tmp_path(&keystore_path);
+    File::create(&tmp_keystore_path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", tmp_keystore_path, e))
+        .and_then(|mut file| {
+            new_keystore
+                .to_json_writer(&mut file)
+                .map_err(|e| format!("Unable to write {:?}: {:?}", tmp_keystore_path, e))
+        })?;
+
+    let result = match &password_store {
+        PasswordStore::File(path) => {
+            replace_password_file(path, new_password.as_ref()).and_then(|()| {
+                replace_file(&tmp_keystore_path, &keystore_path).map_err(|e| {
+                    // Restore the old password so that it still matches the old keystore.
+                    let _ = stored_password
+                        .as_ref()
+                        .map(|password| replace_password_file(path, password.as_bytes()));
+                    e
+                })
+            })
+        }
+        PasswordStore::Definition(index) => {
+            let index = *index;
+            let defs = defs
+                .as_mut()
+                .ok_or_else(|| "Validator definitions are not loaded".to_string())?;
+            let old_password = set_definition_password(defs, index, new_password.clone());
+
+            defs.save(&validator_dir)
+                .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))
+                .and_then(|()| {
+                    replace_file(&tmp_keystore_path, &keystore_path).map_err(|e| {
+                        // Restore the old password so that it still matches the old keystore.
+                        set_definition_password(defs, index, old_password);
+                        let _ = defs.save(&validator_dir);
+                        e
+                    })
+                })
+        }
+        PasswordStore::None => replace_file(&tmp_keystore_path, &keystore_path),
+    };
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_keystore_path);
+    }
+    result?;
+
+    eprintln!("");
+    eprintln!(
+        "Successfully changed the password of keystore {:?}.",
+        keystore_path
+    );
+    match password_store {
+        PasswordStore::File(path) => eprintln!("Updated the password file {:?}.", path),
+        PasswordStore::Definition(_) => eprintln!("Updated {}.", CONFIG_FILENAME),
+        PasswordStore::None => eprintln!(
+            "The password is not stored, it must be entered when the validator client starts."
+        ),
+    }
+
+    Ok(())
+}
+
+/// Decrypts `keystore` using `stored_password`, prompting the user for the password until it
+/// decrypts if there is no stored password or it is incorrect.
+fn decrypt_keypair(
+    keystore: &Keystore,
+    stored_password: Option<PlainText>,
+    stdin_inputs: bool,
+) -> Result<Keypair, String> {
+    if let Some(password) = stored_password {
+        match keystore.decrypt_keypair(password.as_bytes()) {
+            Ok(keypair) => return Ok(keypair),
+            Err(eth2_keystore::Error::InvalidPassword) => {
+                eprintln!("The stored password for the keystore is invalid.");
+            }
+            Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+        }
+    }
+
+    loop {
+        eprintln!("");
+        eprintln!("{}", OLD_PASSWORD_PROMPT);
+
+        let password = read_password_from_user(stdin_inputs)?;
+
+        match keystore.decrypt_keypair(password.as_ref()) {
+            Ok(keypair) => {
+                eprintln!("Password is correct.");
+                break Ok(keypair);
+            }
+            Err(eth2_keystore::Error::InvalidPassword) => {
+                eprintln!("Invalid password");
+            }
+            Err(e) => break Err(format!("Error whilst decrypting keypair: {:?}", e)),
+        }
+    }
+}
+
+/// Prompts the user for a new, non-empty password until it is entered identically twice.
+fn read_new_password(stdin_inputs: bool) -> Result<ZeroizeString, String> {
+    loop {
+        eprintln!("");
+        eprintln!("{}", NEW_PASSWORD_PROMPT);
+        let password = read_password_from_user(stdin_inputs)?;
+
+        if password.as_ref().is_empty() {
+            eprintln!("The password must not be empty.");
+            continue;
+        }
+
+        eprintln!("{}", CONFIRM_PASSWORD_PROMPT);
+        let confirmation = read_password_from_user(stdin_inputs)?;
+
+        if password == confirmation {
+            break Ok(password);
+        } else {
+            eprintln!("Passwords do not match.");
+        }
+    }
+}
+
+/// Returns the KDF used to re-encrypt a keystore, with a new random salt.
+///
+/// If `kdf_name` is `None`, the function and parameters of `existing` are retained.
+fn new_kdf(existing: &Kdf, kdf_name: Option<&str>) -> Result<Kdf, String> {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+
+    let kdf = match (kdf_name, existing) {
+        (None, Kdf::Scrypt(scrypt)) => {
+            let mut scrypt = scrypt.clone();
+            scrypt.salt = salt.into();
+            Kdf::Scrypt(scrypt)
+        }
+        (None, Kdf::Pbkdf2(pbkdf2)) => {
+            let mut pbkdf2 = pbkdf2.clone();
+            pbkdf2.salt = salt.into();
+            Kdf::Pbkdf2(pbkdf2)
+        }
+        (Some(SCRYPT), _) => default_kdf(salt),
+        (Some(PBKDF2), _) => Kdf::Pbkdf2(Pbkdf2 {
+            dklen: DKLEN,
+            c: PBKDF2_ITERATIONS,
+            prf: Prf::HmacSha256,
+            salt: salt.into(),
+        }),
+        (Some(unknown), _) => return Err(format!("Unknown KDF: {}", unknown)),
+    };
+
+    Ok(kdf)
+}
+
+/// Sets the password of the definition at `index`, returning the previous password.
+fn set_definition_password(
+    defs: &mut ValidatorDefinitions,
+    index: usize,
+    password: ZeroizeString,
+) -> Option<ZeroizeString> {
+    match &mut defs.as_mut_slice()[index].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password,
+            ..
+        } => voting_keystore_password.replace(password),
+    }
+}
+
+/// Atomically replaces the contents of the password file at `path` with `password`.
+fn replace_password_file(path: &Path, password: &[u8]) -> Result<(), String> {
+    let tmp = tmp_path(path);
+
+    create_with_600_perms(&tmp, password)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", tmp, e))
+        .and_then(|()| replace_file(&tmp, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            e
+        })
+}
+
+/// Atomically replaces the file at `to` with the file at `from`.
+fn replace_file(from: &Path, to: &Path) -> Result<(), String> {
+    fs::rename(from, to).map_err(|e| format!("Unable to move {:?} to {:?}: {:?}", from, to, e))
+}
+
+/// Returns the path of a temporary file adjacent to `path`.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Returns `true` if `a` and `b` refer to the same file.
+fn same_file(a: &Path, b: &Path) -> Result<bool, String> {
+    let canonicalize = |path: &Path| {
+        fs::canonicalize(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))
+    };
+
+    Ok(canonicalize(a)? == canonicalize(b)?)
+}
@@ -0,0 +1,214 @@
+use crate::VALIDATOR_DIR_FLAG;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use slashing_protection::{Interchange, SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fmt::Display;
+use std::fs::File;
+use std::path::PathBuf;
+use types::{ChainSpec, Epoch, EthSpec};
+
+pub const CMD: &str = "slashing-protection";
+pub const CHECK_CMD: &str = "check";
+pub const PRUNE_CMD: &str = "prune";
+pub const DISPLAY_CMD: &str = "display";
+pub const WEAK_SUBJECTIVITY_PERIOD_FLAG: &str = "weak-subjectivity-period";
+pub const INTERCHANGE_FILE_FLAG: &str = "interchange-file";
+
+/// The safety decay, as a percentage, used to compute the default weak subjectivity period.
+const SAFETY_DECAY: u64 = 10;
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about("Provides commands for maintaining the slashing protection database.")
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to the validator client data directory, which contains the \
+                    slashing protection database. Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            App::new(CHECK_CMD)
+                .about(
+                    "Checks that the slashing protection database contains no slashable or \
+                    invalid records, exiting with an error if any are found.",
+                )
+                .arg(
+                    Arg::with_name(INTERCHANGE_FILE_FLAG)
+                        .long(INTERCHANGE_FILE_FLAG)
+                        .value_name("FILE")
+                        .help(
+                            "An EIP-3076 interchange file to check the database against. Reports \
+                            validators, blocks and attestations in the file which the database \
+                            would not prevent from being contradicted by a slashable message.",
+                        )
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new(PRUNE_CMD)
+                .about(
+                    "Deletes the blocks and attestations of each validator which are older than \
+                    the weak subjectivity period, relative to its most recent block or \
+                    attestation. The most recent block and attestation of each validator are \
+                    always retained.",
+                )
+                .arg(
+                    Arg::with_name(WEAK_SUBJECTIVITY_PERIOD_FLAG)
+                        .long(WEAK_SUBJECTIVITY_PERIOD_FLAG)
+                        .value_name("EPOCHS")
+                        .help(
+                            "The number of epochs of history to retain. Defaults to the largest \
+                            weak subjectivity period possible for any number of validators.",
+                        )
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(App::new(DISPLAY_CMD).about(
+            "Displays the number of blocks and attestations recorded for each validator, \
+            along with the lowest and highest slots and epochs.",
+        ))
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+
+    // The validator client holds an exclusive lock on the database whilst it is running.
+    let path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection = SlashingDatabase::open(&path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection database at {:?}, ensure it exists and that \
+             the validator client is not running: {:?}",
+            path, e
+        )
+    })?;
+
+    match matches.subcommand() {
+        (CHECK_CMD, Some(matches)) => {
+            let interchange_file: Option<PathBuf> =
+                clap_utils::parse_optional(matches, INTERCHANGE_FILE_FLAG)?;
+            check(&slashing_protection, interchange_file)
+        }
+        (PRUNE_CMD, Some(matches)) => {
+            let spec = &env.core_context().eth2_config.spec;
+            let history_length =
+                clap_utils::parse_optional(matches, WEAK_SUBJECTIVITY_PERIOD_FLAG)?
+                    .map(Epoch::new)
+                    .unwrap_or_else(|| max_weak_subjectivity_period(spec));
+            prune::<T>(&slashing_protection, history_length)
+        }
+        (DISPLAY_CMD, Some(_)) => display(&slashing_protection),
+        (unknown, _) => Err(format!(
+            "{} does not have a {} command. See --help",
+            CMD, unknown
+        )),
+    }
+}
+
+fn check(
+    slashing_protection: &SlashingDatabase,
+    interchange_file: Option<PathBuf>,
+) -> Result<(), String> {
+    let mut violations = slashing_protection
+        .check_invariants()
+        .map_err(|e| format!("Unable to check slashing protection database: {:?}", e))?;
+
+    if let Some(path) = interchange_file {
+        let file = File::open(&path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        let interchange = Interchange::from_json_reader(file)
+            .map_err(|e| format!("Unable to parse interchange file {:?}: {:?}", path, e))?;
+        violations.extend(
+            slashing_protection
+                .check_interchange(&interchange)
+                .map_err(|e| format!("Unable to check interchange file {:?}: {:?}", path, e))?,
+        );
+    }
+
+    if violations.is_empty() {
+        eprintln!("No issues found in the slashing protection database.");
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!("{}", violation);
+    }
+
+    Err(format!(
+        "Found {} issues in the slashing protection database",
+        violations.len()
+    ))
+}
+
+fn prune<T: EthSpec>(
+    slashing_protection: &SlashingDatabase,
+    history_length: Epoch,
+) -> Result<(), String> {
+    let summary = slashing_protection
+        .prune(history_length, T::slots_per_epoch())
+        .map_err(|e| format!("Unable to prune slashing protection database: {:?}", e))?;
+
+    eprintln!(
+        "Deleted {} blocks and {} attestations older than {} epochs.",
+        summary.blocks, summary.attestations, history_length
+    );
+
+    Ok(())
+}
+
+fn display(slashing_protection: &SlashingDatabase) -> Result<(), String> {
+    let summaries = slashing_protection
+        .validator_summaries()
+        .map_err(|e| format!("Unable to read slashing protection database: {:?}", e))?;
+
+    if summaries.is_empty() {
+        eprintln!("No validators are registered in the slashing protection database.");
+        return Ok(());
+    }
+
+    for summary in summaries {
+        println!("{}", summary.public_key);
+        println!(
+            " - Blocks: {} (slots {} to {})",
+            summary.block_count,
+            display_opt(summary.min_slot),
+            display_opt(summary.max_slot)
+        );
+        println!(
+            " - Attestations: {} (source epochs {} to {}, target epochs {} to {})",
+            summary.attestation_count,
+            display_opt(summary.min_source_epoch),
+            display_opt(summary.max_source_epoch),
+            display_opt(summary.min_target_epoch),
+            display_opt(summary.max_target_epoch)
+        );
+    }
+
+    Ok(())
+}
+
+fn display_opt<T: Display>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Returns the weak subjectivity period for the largest validator set, which is an upper bound
+/// on the period for any number of validators.
+///
+/// The churn limit grows with the number of validators once it exceeds the minimum, so the
+/// period converges to `MIN_VALIDATOR_WITHDRAWABILITY_DELAY + CHURN_LIMIT_QUOTIENT *
+/// SAFETY_DECAY / 200` epochs.
+fn max_weak_subjectivity_period(spec: &ChainSpec) -> Epoch {
+    Epoch::new(
+        spec.min_validator_withdrawability_delay.as_u64()
+            + spec.churn_limit_quotient * SAFETY_DECAY / 200,
+    )
+}
@@ -29,7 +29,7 @@
 use crate::{
     beacon_chain::{
         ATTESTATION_CACHE_LOCK_TIMEOUT, HEAD_LOCK_TIMEOUT, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
-        VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
+        SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT, VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
     },
     metrics,
     observed_attestations::ObserveOutcome,
@@ -37,6 +37,7 @@ use crate::{
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use bls::verify_signature_sets;
+use slasher::SlasherEvent;
 use slog::debug;
 use slot_clock::SlotClock;
 use state_processing::{
@@ -226,6 +227,18 @@ pub enum Error {
         head_block_slot: Slot,
         attestation_slot: Slot,
     },
+    /// The attestation target block is too far behind the attestation slot, so obtaining the
+    /// committees for the attestation would require processing many skip slots. This is deemed a
+    /// DoS risk.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The attestation is not necessarily invalid, however it is very likely to be from a distant
+    /// minority fork or an attacker.
+    TooManyTargetSkippedSlots {
+        target_block_slot: Slot,
+        attestation_slot: Slot,
+    },
     /// There was an error whilst processing the attestation. It is not known if it is valid or invalid.
     ///
     /// ## Peer scoring
@@ -370,6 +383,9 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
             return Err(Error::InvalidSignature);
         }
 
+        // The signature is valid, so the attestation may be checked for slashings.
+        send_to_slasher(chain, &indexed_attestation);
+
         // Observe the valid attestation so we do not re-process it.
         //
         // It's important to double check that the attestation is not already known, otherwise two
@@ -470,11 +486,15 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
         /*
          * The attestation is the first valid attestation received for the participating validator
          * for the slot, attestation.data.slot.
+         *
+         * When the slasher is enabled this check is deferred until after the signature has been
+         * verified, so that a second attestation from the same validator reaches the slasher.
          */
-        if chain
-            .observed_attesters
-            .validator_has_been_observed(&attestation, validator_index as usize)
-            .map_err(BeaconChainError::from)?
+        if !chain.slasher_enabled()
+            && chain
+                .observed_attesters
+                .validator_has_been_observed(&attestation, validator_index as usize)
+                .map_err(BeaconChainError::from)?
         {
             return Err(Error::PriorAttestationKnown {
                 validator_index,
@@ -485,6 +505,8 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
         // The aggregate signature of the attestation is valid.
         verify_attestation_signature(chain, &indexed_attestation)?;
 
+        send_to_slasher(chain, &indexed_attestation);
+
         // Now that the attestation has been fully verified, store that we have received a valid
         // attestation from this validator.
         //
@@ -601,6 +623,20 @@ pub fn verify_propagation_slot_range<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Sends `indexed_attestation` to the slasher, if it is enabled.
+///
+/// The signature of the attestation must already have been verified.
+fn send_to_slasher<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    indexed_attestation: &IndexedAttestation<T::EthSpec>,
+) {
+    if chain.slasher_enabled() {
+        chain.send_to_slasher(SlasherEvent::Attestation(Box::new(
+            indexed_attestation.clone(),
+        )));
+    }
+}
+
 /// Verifies that the signature of the `indexed_attestation` is valid.
 pub fn verify_attestation_signature<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
@@ -783,6 +819,18 @@ where
         // required.
         drop(shuffling_cache);
 
+        // Obtaining the committees requires advancing the target state through each skip slot
+        // between the target block and the attestation. Refuse to do an unbounded amount of work
+        // for attestations on distant forks.
+        if let Some(max_skip_slots) = chain.config.import_max_skip_slots {
+            if attestation.data.slot > target_block.slot + max_skip_slots {
+                return Err(Error::TooManyTargetSkippedSlots {
+                    target_block_slot: target_block.slot,
+                    attestation_slot: attestation.data.slot,
+                });
+            }
+        }
+
         debug!(
             chain.log,
             "Attestation processing cache miss";
@@ -790,33 +838,65 @@ where
             "target_block_epoch" => target_block.slot.epoch(T::EthSpec::slots_per_epoch()).as_u64(),
         );
 
-        let state_read_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
+        // If the target state must be advanced through skip slots, it will be advanced to the
+        // first slot of the epoch prior to the attestation epoch. Other attestations with the
+        // same target are likely to require the same state, so check the skip-slot cache first.
+        let skip_slot =
+            if target_block.slot.epoch(T::EthSpec::slots_per_epoch()) + 1 < attestation_epoch {
+                Some((attestation_epoch - 1).start_slot(T::EthSpec::slots_per_epoch()))
+            } else {
+                None
+            };
+
+        let cached_state = skip_slot.and_then(|skip_slot| {
+            chain
+                .skip_slot_state_cache
+                .try_write_for(SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT)
+                .and_then(|mut cache| cache.get(target_block.state_root, skip_slot))
+        });
 
-        let mut state = chain
-            .store
-            .get_inconsistent_state_for_attestation_verification_only(
-                &target_block.state_root,
-                Some(target_block.slot),
-            )
-            .map_err(BeaconChainError::from)?
-            .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
-
-        metrics::stop_timer(state_read_timer);
-        let state_skip_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
-
-        while state.current_epoch() + 1 < attestation_epoch {
-            // Here we tell `per_slot_processing` to skip hashing the state and just
-            // use the zero hash instead.
-            //
-            // The state roots are not useful for the shuffling, so there's no need to
-            // compute them.
-            per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
-                .map_err(BeaconChainError::from)?;
-        }
+        let mut state = if let Some(state) = cached_state {
+            state
+        } else {
+            let state_read_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
+
+            let mut state = chain
+                .store
+                .get_inconsistent_state_for_attestation_verification_only(
+                    &target_block.state_root,
+                    Some(target_block.slot),
+                )
+                .map_err(BeaconChainError::from)?
+                .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
+
+            metrics::stop_timer(state_read_timer);
+            let state_skip_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
+
+            while state.current_epoch() + 1 < attestation_epoch {
+                // Here we tell `per_slot_processing` to skip hashing the state and just
+                // use the zero hash instead.
+                //
+                // The state roots are not useful for the shuffling, so there's no need to
+                // compute them.
+                per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
+                    .map_err(BeaconChainError::from)?;
+            }
+
+            metrics::stop_timer(state_skip_timer);
+
+            if skip_slot.is_some() {
+                if let Some(mut cache) = chain
+                    .skip_slot_state_cache
+                    .try_write_for(SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT)
+                {
+                    cache.insert(target_block.state_root, &state);
+                }
+            }
 
-        metrics::stop_timer(state_skip_timer);
+            state
+        };
         let committee_building_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_COMMITTEE_BUILDING_TIMES);
 
@@ -2,16 +2,20 @@ use crate::attestation_verification::{
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
 };
+use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::block_verification::{
     check_block_is_finalized_descendant, check_block_relevancy, get_block_root,
     signature_verify_chain_segment, BlockError, FullyVerifiedBlock, GossipVerifiedBlock,
     IntoFullyVerifiedBlock,
 };
+use crate::chain_alerts::{reorg_depth, ChainAlert, ChainAlertHook, ChainAlertTracker};
 use crate::chain_config::ChainConfig;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::events::{EventHandler, EventKind};
 use crate::head_tracker::HeadTracker;
+use crate::late_block_tracker::{LateBlockTracker, CONSISTENTLY_LATE_THRESHOLD};
+use crate::light_client_update_cache::{LightClientUpdateCache, PersistedLightClientUpdate};
 use crate::metrics;
 use crate::migrate::Migrate;
 use crate::naive_aggregation_pool::{Error as NaiveAggregationError, NaiveAggregationPool};
@@ -19,18 +23,29 @@ use crate::observed_attestations::{Error as AttestationObservationError, Observe
 use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
+use crate::observed_sync_contributors::{ObservedSyncAggregators, ObservedSyncContributors};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::ShufflingCache;
+use crate::skip_slot_state_cache::SkipSlotStateCache;
 use crate::snapshot_cache::SnapshotCache;
+use crate::sync_committee_verification::{
+    Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
+};
+use crate::sync_contribution_pool::{Error as SyncContributionPoolError, SyncContributionPool};
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::BeaconForkChoiceStore;
 use crate::BeaconSnapshot;
+use bus::Bus;
 use fork_choice::ForkChoice;
 use itertools::process_results;
+use lru::LruCache;
 use operation_pool::{OperationPool, PersistedOperationPool};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use rayon::prelude::*;
+use safe_arith::SafeArith;
+use slasher::{Slasher, SlasherEvent};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::{
@@ -43,6 +58,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::prelude::*;
+use std::sync::atomic::{self, AtomicUsize};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use store::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
@@ -51,6 +67,9 @@ use types::*;
 
 pub type ForkChoiceError = fork_choice::Error<crate::ForkChoiceStoreError>;
 
+/// A queue of verified attestations and blocks, which is read by slashers in other processes.
+pub type SlasherStream<E> = Arc<Mutex<Bus<SlasherEvent<E>>>>;
+
 /// The time-out before failure during an operation to take a read/write RwLock on the canonical
 /// head.
 pub const HEAD_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
@@ -66,9 +85,24 @@ pub const ATTESTATION_CACHE_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
 /// validator pubkey cache.
 pub const VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// The time-out before failure during an operation to take a read/write RwLock on the
+/// skip-slot state cache.
+pub const SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
 pub const BEACON_CHAIN_DB_KEY: [u8; 32] = [0; 32];
 pub const OP_POOL_DB_KEY: [u8; 32] = [0; 32];
+/// The number of slots between each periodic persistence of the operation pool.
+///
+/// The pool is also persisted when the `BeaconChain` is dropped.
+pub const OP_POOL_PERSISTENCE_INTERVAL_SLOTS: u64 = 32;
+/// The number of slots between each periodic persistence of the eth1 caches.
+///
+/// The caches are also persisted when the `BeaconChain` is dropped, the periodic persistence
+/// ensures an unclean shutdown does not require all deposit logs to be downloaded again.
+pub const ETH1_CACHE_PERSISTENCE_INTERVAL_SLOTS: u64 = 64;
 pub const ETH1_CACHE_DB_KEY: [u8; 32] = [0; 32];
+/// The number of sync committee periods for which committee indices are cached.
+pub const SYNC_COMMITTEE_CACHE_SIZE: usize = 4;
 pub const FORK_CHOICE_DB_KEY: [u8; 32] = [0; 32];
 
 /// The result of a chain segment processing.
@@ -88,6 +122,9 @@ pub enum ChainSegmentResult<T: EthSpec> {
 /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/p2p-interface.md#configuration
 pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
 
+/// The number of events which may be buffered by the `SlasherStream` for its slowest reader.
+pub const SLASHER_STREAM_CAPACITY: usize = 16_384;
+
 #[derive(Debug, PartialEq)]
 pub enum AttestationProcessingOutcome {
     Processed,
@@ -186,6 +223,23 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Maintains a record of which validators have been seen to create `SignedAggregateAndProofs`
     /// in recent epochs.
     pub observed_aggregators: ObservedAggregators<T::EthSpec>,
+    /// A pool of sync committee contributions, used both to aggregate `SyncCommitteeMessage`s and
+    /// to provide the best contributions for inclusion in blocks.
+    pub sync_contribution_pool: RwLock<SyncContributionPool<T::EthSpec>>,
+    /// Maintains a record of which validators have been seen to publish `SyncCommitteeMessage`s
+    /// in recent slots.
+    pub observed_sync_contributors: ObservedSyncContributors<T::EthSpec>,
+    /// Maintains a record of which validators have been seen to publish
+    /// `SignedContributionAndProof`s in recent slots.
+    pub observed_sync_aggregators: ObservedSyncAggregators<T::EthSpec>,
+    /// Caches the validator indices of the sync committee for recent sync committee periods.
+    ///
+    /// Only committees which were selected from finalized states are cached.
+    pub(crate) sync_committee_cache: Mutex<LruCache<u64, Vec<u64>>>,
+    /// The best `LightClientUpdate` for each recent sync committee period.
+    pub light_client_update_cache: RwLock<LightClientUpdateCache<T::EthSpec>>,
+    /// Maintains a record of proposers that have recently published late blocks.
+    pub(crate) late_block_tracker: RwLock<LateBlockTracker>,
     /// Maintains a record of which validators have proposed blocks for each slot.
     pub observed_block_producers: ObservedBlockProducers<T::EthSpec>,
     /// Maintains a record of which validators have submitted voluntary exits.
@@ -197,7 +251,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
     /// Stores a "snapshot" of the chain at the time the head-of-the-chain block was received.
-    pub(crate) canonical_head: TimeoutRwLock<BeaconSnapshot<T::EthSpec>>,
+    ///
+    /// The snapshot is immutable and shared via an `Arc`, so readers only hold the lock for long
+    /// enough to clone the pointer.
+    pub(crate) canonical_head: TimeoutRwLock<Arc<BeaconSnapshot<T::EthSpec>>>,
     /// The root of the genesis block.
     pub genesis_block_root: Hash256,
     /// The root of the list of genesis validators, used during syncing.
@@ -217,6 +274,11 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
     /// Caches the shuffling for a given epoch and state root.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
+    /// Caches the beacon block proposer shuffling for a given epoch and shuffling decision root.
+    pub beacon_proposer_cache: Mutex<BeaconProposerCache>,
+    /// Caches states that have been advanced through skip slots, keyed by the pre-advance state
+    /// root and the slot advanced to.
+    pub(crate) skip_slot_state_cache: TimeoutRwLock<SkipSlotStateCache<T::EthSpec>>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache>,
     /// A list of any hard-coded forks that have been disabled.
@@ -225,6 +287,17 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) log: Logger,
     /// Arbitrary bytes included in the blocks.
     pub(crate) graffiti: Graffiti,
+    /// Tracks the history required to detect finality stalls and justification flip-flopping.
+    pub(crate) chain_alert_tracker: Mutex<ChainAlertTracker>,
+    /// Functions to be called whenever a `ChainAlert` is raised.
+    pub(crate) alert_hooks: Vec<ChainAlertHook>,
+    /// Optional slasher, which is sent every verified attestation and block.
+    pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    /// Optional stream which is sent every verified attestation and block, for slashers running in
+    /// another process.
+    pub slasher_stream: Option<SlasherStream<T::EthSpec>>,
+    /// The number of events dropped from the `slasher_stream` since one was last sent.
+    pub(crate) slasher_stream_dropped: AtomicUsize,
 }
 
 type BeaconBlockAndState<T> = (BeaconBlock<T>, BeaconState<T>);
@@ -297,7 +370,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Persists `self.eth1_chain` and its caches to disk.
     pub fn persist_eth1_cache(&self) -> Result<(), Error> {
-        let _timer = metrics::start_timer(&metrics::PERSIST_OP_POOL);
+        let _timer = metrics::start_timer(&metrics::PERSIST_ETH1_CACHE);
 
         if let Some(eth1_chain) = self.eth1_chain.as_ref() {
             self.store.put_item(
@@ -342,8 +415,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn rev_iter_block_roots(
         &self,
     ) -> Result<impl Iterator<Item = Result<(Hash256, Slot), Error>>, Error> {
-        let head = self.head()?;
-        let iter = BlockRootsIterator::owned(self.store.clone(), head.beacon_state);
+        let head = self.cached_head()?;
+        let iter = BlockRootsIterator::owned(
+            self.store.clone(),
+            head.beacon_state.clone_with(CloneConfig::none()),
+        );
         Ok(
             std::iter::once(Ok((head.beacon_block_root, head.beacon_block.slot())))
                 .chain(iter)
@@ -355,12 +431,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         start_slot: Slot,
     ) -> Result<impl Iterator<Item = Result<(Hash256, Slot), Error>>, Error> {
-        let local_head = self.head()?;
+        let local_head = self.cached_head()?;
 
         let iter = HotColdDB::forwards_block_roots_iterator(
             self.store.clone(),
             start_slot,
-            local_head.beacon_state,
+            local_head.beacon_state.clone_with(CloneConfig::none()),
             local_head.beacon_block_root,
             &self.spec,
         )?;
@@ -417,9 +493,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn rev_iter_state_roots(
         &self,
     ) -> Result<impl Iterator<Item = Result<(Hash256, Slot), Error>>, Error> {
-        let head = self.head()?;
+        let head = self.cached_head()?;
         let slot = head.beacon_state.slot;
-        let iter = StateRootsIterator::owned(self.store.clone(), head.beacon_state);
+        let iter = StateRootsIterator::owned(
+            self.store.clone(),
+            head.beacon_state.clone_with(CloneConfig::none()),
+        );
         let iter = std::iter::once(Ok((head.beacon_state_root, slot)))
             .chain(iter)
             .map(|result| result.map_err(Into::into));
@@ -441,11 +520,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// ## Errors
     ///
-    /// May return a database error.
+    /// May return a database error, which is `HistoryUnavailable` if `slot` pre-dates the anchor
+    /// of the database.
     pub fn block_at_slot(
         &self,
         slot: Slot,
     ) -> Result<Option<SignedBeaconBlock<T::EthSpec>>, Error> {
+        self.store.check_block_history(slot)?;
+
         let root = process_results(self.rev_iter_block_roots()?, |mut iter| {
             iter.find(|(_, this_slot)| *this_slot == slot)
                 .map(|(root, _)| root)
@@ -489,20 +571,32 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// It is important to note that the `beacon_state` returned may not match the present slot. It
     /// is the state as it was when the head block was received, which could be some slots prior to
     /// now.
+    ///
+    /// This clones the head `BeaconState`, prefer `Self::cached_head` or `Self::head_info` when an
+    /// owned state is not required.
     pub fn head(&self) -> Result<BeaconSnapshot<T::EthSpec>, Error> {
         self.with_head(|head| Ok(head.clone_with_only_committee_caches()))
     }
 
+    /// Returns a shared reference to the canonical head snapshot.
+    ///
+    /// This is cheap: the head lock is only held whilst the `Arc` is cloned, so it is the preferred
+    /// way of reading the head when an owned `BeaconState` is not required.
+    pub fn cached_head(&self) -> Result<Arc<BeaconSnapshot<T::EthSpec>>, Error> {
+        self.canonical_head
+            .try_read_for(HEAD_LOCK_TIMEOUT)
+            .map(|head| head.clone())
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)
+    }
+
     /// Apply a function to the canonical head without cloning it.
+    ///
+    /// The head lock is not held whilst `f` is running.
     pub fn with_head<U>(
         &self,
         f: impl FnOnce(&BeaconSnapshot<T::EthSpec>) -> Result<U, Error>,
     ) -> Result<U, Error> {
-        let head_lock = self
-            .canonical_head
-            .try_read_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
-        f(&head_lock)
+        f(&self.cached_head()?)
     }
 
     /// Returns info representing the head block and state.
@@ -543,32 +637,49 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         config: StateSkipConfig,
     ) -> Result<BeaconState<T::EthSpec>, Error> {
-        let head_state = self.head()?.beacon_state;
+        let head = self.cached_head()?;
+        let head_state_root = head.beacon_state_root;
+        let head_state_slot = head.beacon_state.slot;
 
-        match slot.cmp(&head_state.slot) {
-            Ordering::Equal => Ok(head_state),
+        match slot.cmp(&head_state_slot) {
+            Ordering::Equal => Ok(head.beacon_state.clone_with_only_committee_caches()),
             Ordering::Greater => {
-                if slot > head_state.slot + T::EthSpec::slots_per_epoch() {
+                if slot > head_state_slot + T::EthSpec::slots_per_epoch() {
                     warn!(
                         self.log,
                         "Skipping more than an epoch";
-                        "head_slot" => head_state.slot,
+                        "head_slot" => head_state_slot,
                         "request_slot" => slot
                     )
                 }
 
-                let start_slot = head_state.slot;
+                let start_slot = head_state_slot;
                 let task_start = Instant::now();
                 let max_task_runtime = Duration::from_millis(self.spec.milliseconds_per_slot);
 
-                let head_state_slot = head_state.slot;
-                let mut state = head_state;
-
                 let skip_state_root = match config {
                     StateSkipConfig::WithStateRoots => None,
                     StateSkipConfig::WithoutStateRoots => Some(Hash256::zero()),
                 };
 
+                // States advanced without state roots are only suitable for shuffling and duty
+                // queries, so they are the only ones that can be served from (or added to) the
+                // skip-slot cache.
+                let use_skip_slot_cache = skip_state_root.is_some();
+
+                if use_skip_slot_cache {
+                    if let Some(state) = self
+                        .skip_slot_state_cache
+                        .try_write_for(SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT)
+                        .and_then(|mut cache| cache.get(head_state_root, slot))
+                    {
+                        return Ok(state);
+                    }
+                }
+
+                // The head state is only cloned once it is known that it must be advanced.
+                let mut state = head.beacon_state.clone_with_only_committee_caches();
+
                 while state.slot < slot {
                     // Do not allow and forward state skip that takes longer than the maximum task duration.
                     //
@@ -598,6 +709,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         }
                     };
                 }
+
+                if use_skip_slot_cache {
+                    if let Some(mut cache) = self
+                        .skip_slot_state_cache
+                        .try_write_for(SKIP_SLOT_STATE_CACHE_LOCK_TIMEOUT)
+                    {
+                        cache.insert(head_state_root, &state);
+                    }
+                }
+
                 Ok(state)
             }
             Ordering::Less => {
@@ -635,6 +756,62 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)
     }
 
+    /// Returns the root of the block which decided the proposer shuffling for `epoch` on the chain
+    /// which includes `block_root` (i.e., the block at the last slot of the epoch prior to
+    /// `epoch`).
+    ///
+    /// Only reads from fork choice, never from the database. Returns `None` if the block (or one of
+    /// its ancestors in `epoch`) is unknown to fork choice, or if `block_root` is from an epoch
+    /// later than `epoch`.
+    pub fn proposer_shuffling_decision_root(
+        &self,
+        epoch: Epoch,
+        block_root: Hash256,
+    ) -> Option<Hash256> {
+        let decision_slot = epoch.start_slot(T::EthSpec::slots_per_epoch()) - 1;
+        let fork_choice = self.fork_choice.read();
+
+        let mut block = fork_choice.get_block(&block_root)?;
+        if block.slot.epoch(T::EthSpec::slots_per_epoch()) > epoch {
+            return None;
+        }
+
+        while block.slot > decision_slot {
+            block = fork_choice.get_block(&block.parent_root?)?;
+        }
+
+        Some(block.root)
+    }
+
+    /// Returns the proposers for each slot in the current epoch of `state`, using the
+    /// `beacon_proposer_cache` where possible.
+    ///
+    /// The `state` must be on the canonical chain (e.g., the head state or the head state advanced
+    /// through skip slots) and must have its current committee cache built.
+    pub fn canonical_beacon_proposers(
+        &self,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Vec<usize>, Error> {
+        let epoch = state.current_epoch();
+        let head_block_root = self.head_info()?.block_root;
+        let decision_root = self.proposer_shuffling_decision_root(epoch, head_block_root);
+
+        if let Some(decision_root) = decision_root {
+            let mut cache = self.beacon_proposer_cache.lock();
+            if let Some(proposers) = cache.get_epoch(decision_root, epoch) {
+                return Ok(proposers.clone());
+            }
+
+            cache.insert_from_state(decision_root, state, &self.spec)?;
+        }
+
+        epoch
+            .slot_iter(T::EthSpec::slots_per_epoch())
+            .map(|slot| state.get_beacon_proposer_index(slot, &self.spec))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
     /// Returns the validator index (if any) for the given public key.
     ///
     /// ## Notes
@@ -693,10 +870,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// present epoch is available.
     pub fn block_proposer(&self, slot: Slot) -> Result<usize, Error> {
         let epoch = |slot: Slot| slot.epoch(T::EthSpec::slots_per_epoch());
-        let head_state = &self.head()?.beacon_state;
+        let head = self.cached_head()?;
 
-        let mut state = if epoch(slot) == epoch(head_state.slot) {
-            self.head()?.beacon_state
+        let mut state = if epoch(slot) == epoch(head.beacon_state.slot) {
+            head.beacon_state.clone_with_only_committee_caches()
         } else {
             // The block proposer shuffling is not affected by the state roots, so we don't need to
             // calculate them.
@@ -728,10 +905,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         epoch: Epoch,
     ) -> Result<Option<(Slot, u64)>, Error> {
         let as_epoch = |slot: Slot| slot.epoch(T::EthSpec::slots_per_epoch());
-        let head_state = &self.head()?.beacon_state;
+        let head = self.cached_head()?;
 
-        let mut state = if epoch == as_epoch(head_state.slot) {
-            self.head()?.beacon_state
+        let mut state = if epoch == as_epoch(head.beacon_state.slot) {
+            head.beacon_state.clone_with_only_committee_caches()
         } else {
             // The block proposer shuffling is not affected by the state roots, so we don't need to
             // calculate them.
@@ -784,12 +961,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         index: CommitteeIndex,
     ) -> Result<Attestation<T::EthSpec>, Error> {
-        // Note: we're taking a lock on the head. The work involved here should be trivial enough
-        // that the lock should not be held for long.
-        let head = self
-            .canonical_head
-            .try_read_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+        let head = self.cached_head()?;
 
         if slot >= head.beacon_block.slot() {
             self.produce_unaggregated_attestation_for_block(
@@ -925,6 +1097,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Accepts some `SyncCommitteeMessage` from the network and attempts to verify it, returning
+    /// `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
+    pub fn verify_sync_committee_message_for_gossip(
+        &self,
+        sync_message: SyncCommitteeMessage,
+        subnet_id: u64,
+    ) -> Result<VerifiedSyncCommitteeMessage, SyncCommitteeError> {
+        metrics::inc_counter(&metrics::SYNC_MESSAGE_PROCESSING_REQUESTS);
+        let _timer = metrics::start_timer(&metrics::SYNC_MESSAGE_GOSSIP_VERIFICATION_TIMES);
+
+        VerifiedSyncCommitteeMessage::verify(sync_message, subnet_id, self).map(|v| {
+            metrics::inc_counter(&metrics::SYNC_MESSAGE_PROCESSING_SUCCESSES);
+            v
+        })
+    }
+
+    /// Accepts some `SignedContributionAndProof` from the network and attempts to verify it,
+    /// returning `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
+    pub fn verify_sync_contribution_for_gossip(
+        &self,
+        signed_contribution: SignedContributionAndProof<T::EthSpec>,
+    ) -> Result<VerifiedSyncContribution<T>, SyncCommitteeError> {
+        metrics::inc_counter(&metrics::SYNC_CONTRIBUTION_PROCESSING_REQUESTS);
+        let _timer = metrics::start_timer(&metrics::SYNC_CONTRIBUTION_GOSSIP_VERIFICATION_TIMES);
+
+        VerifiedSyncContribution::verify(signed_contribution, self).map(|v| {
+            metrics::inc_counter(&metrics::SYNC_CONTRIBUTION_PROCESSING_SUCCESSES);
+            v
+        })
+    }
+
     /// Accepts some attestation-type object and attempts to verify it in the context of fork
     /// choice. If it is valid it is applied to `self.fork_choice`.
     ///
@@ -994,6 +1197,330 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(unaggregated_attestation)
     }
 
+    /// Inserts a `SyncCommitteeContribution` into `self.sync_contribution_pool`, aggregating it
+    /// with any existing contribution where possible.
+    ///
+    /// Single `SyncCommitteeMessage`s should be converted into single-participant contributions
+    /// before being provided to this function.
+    pub fn add_to_sync_contribution_pool(
+        &self,
+        contribution: &SyncCommitteeContribution<T::EthSpec>,
+    ) -> Result<(), Error> {
+        match self.sync_contribution_pool.write().insert(contribution) {
+            Ok(outcome) => trace!(
+                self.log,
+                "Stored sync committee contribution";
+                "outcome" => format!("{:?}", outcome),
+                "subcommittee_index" => contribution.subcommittee_index,
+                "slot" => contribution.slot.as_u64(),
+            ),
+            Err(SyncContributionPoolError::SlotTooLow {
+                slot,
+                lowest_permissible_slot,
+            }) => {
+                trace!(
+                    self.log,
+                    "Refused to store sync committee contribution";
+                    "lowest_permissible_slot" => lowest_permissible_slot.as_u64(),
+                    "slot" => slot.as_u64(),
+                );
+            }
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Failed to store sync committee contribution";
+                    "error" => format!("{:?}", e),
+                    "subcommittee_index" => contribution.subcommittee_index,
+                    "slot" => contribution.slot.as_u64(),
+                );
+                return Err(e.into());
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Returns the best known `SyncCommitteeContribution` for the given `slot`,
+    /// `beacon_block_root` and `subcommittee_index`, if any.
+    ///
+    /// Used by local sync committee aggregators.
+    pub fn get_aggregated_sync_committee_contribution(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+        subcommittee_index: u64,
+    ) -> Option<SyncCommitteeContribution<T::EthSpec>> {
+        self.sync_contribution_pool
+            .read()
+            .get(slot, beacon_block_root, subcommittee_index)
+    }
+
+    /// Returns the best known contribution from each sync subcommittee for `slot` and
+    /// `beacon_block_root`, suitable for inclusion in a block at `slot + 1`.
+    pub fn get_sync_contributions_for_block(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+    ) -> Vec<SyncCommitteeContribution<T::EthSpec>> {
+        self.sync_contribution_pool
+            .read()
+            .get_contributions_for_block(slot, beacon_block_root)
+    }
+
+    /// Returns the validator indices of the members of the `subcommittee_index`'th sync
+    /// subcommittee at `slot`, in order.
+    pub fn sync_subcommittee_indices(
+        &self,
+        slot: Slot,
+        subcommittee_index: u64,
+    ) -> Result<Vec<u64>, Error> {
+        let subcommittee_size = T::EthSpec::sync_subcommittee_size();
+        let start = (subcommittee_index as usize).safe_mul(subcommittee_size)?;
+        let end = start.safe_add(subcommittee_size)?;
+
+        self.sync_committee_indices(slot)?
+            .get(start..end)
+            .map(<[u64]>::to_vec)
+            .ok_or_else(|| Error::SyncCommitteeUnavailable(slot))
+    }
+
+    /// Returns each `(subcommittee_index, position)` pair at which `validator_index` is a member
+    /// of the sync committee at `slot`. A validator may appear in the committee more than once.
+    pub fn sync_subcommittee_positions(
+        &self,
+        slot: Slot,
+        validator_index: u64,
+    ) -> Result<Vec<(u64, usize)>, Error> {
+        let subcommittee_size = T::EthSpec::sync_subcommittee_size();
+
+        Ok(self
+            .sync_committee_indices(slot)?
+            .into_iter()
+            .enumerate()
+            .filter(|(_, index)| *index == validator_index)
+            .map(|(i, _)| ((i / subcommittee_size) as u64, i % subcommittee_size))
+            .collect())
+    }
+
+    /// Returns the validator indices of the sync committee which signs messages at `slot`, in
+    /// committee order.
+    ///
+    /// Messages from the last slot of a sync committee period are included in the first block of
+    /// the next period, so they are signed by the next period's committee.
+    ///
+    /// The committee for a period is selected at the start of the preceding period. The first two
+    /// periods following the fork have no preceding post-fork period, so both use the committee
+    /// selected at the fork epoch.
+    fn sync_committee_indices(&self, slot: Slot) -> Result<Vec<u64>, Error> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let fork_epoch = self
+            .spec
+            .altair_fork_epoch
+            .ok_or_else(|| Error::SyncCommitteeUnavailable(slot))?;
+        let epoch = (slot + 1).epoch(slots_per_epoch);
+        if epoch < fork_epoch {
+            return Err(Error::SyncCommitteeUnavailable(slot));
+        }
+
+        let epochs_per_period = self.spec.epochs_per_sync_committee_period.as_u64();
+        let period = epoch.as_u64().safe_div(epochs_per_period)?;
+        let fork_period = fork_epoch.as_u64().safe_div(epochs_per_period)?;
+
+        if let Some(indices) = self.sync_committee_cache.lock().get(&period) {
+            return Ok(indices.clone());
+        }
+
+        // The epoch of the state from which the committee is selected, and the epoch whose
+        // shuffling and seed are used.
+        let (state_epoch, selection_epoch) = if period <= fork_period.safe_add(1)? {
+            (fork_epoch, fork_epoch + 1)
+        } else {
+            let epoch = Epoch::new(period.safe_sub(1)?.safe_mul(epochs_per_period)?);
+            (epoch, epoch)
+        };
+        let state_slot = state_epoch.start_slot(slots_per_epoch);
+
+        // The shuffling does not depend on the state roots, so they are not calculated if the
+        // state needs to be advanced.
+        let state = self.state_at_slot(state_slot, StateSkipConfig::WithoutStateRoots)?;
+        let indices = state
+            .get_sync_committee_indices(selection_epoch, &self.spec)?
+            .into_iter()
+            .map(|index| index as u64)
+            .collect::<Vec<_>>();
+
+        // A committee selected from a state which is not yet finalized may still change.
+        let finalized_epoch = self.head_info()?.finalized_checkpoint.epoch;
+        if state_epoch <= finalized_epoch {
+            self.sync_committee_cache
+                .lock()
+                .put(period, indices.clone());
+        }
+
+        Ok(indices)
+    }
+
+    /// Offers a `LightClientUpdate` to `self.light_client_update_cache`. If it is the best update
+    /// known for its sync committee period it is also written to the database, replacing any
+    /// previous update for that period.
+    ///
+    /// Returns `Ok(true)` if the update was stored.
+    ///
+    /// ## Notes
+    ///
+    /// This function does not verify the update. Callers should only provide updates constructed
+    /// from blocks that have been imported, see `Self::produce_light_client_update`.
+    pub fn process_light_client_update(
+        &self,
+        update: LightClientUpdate<T::EthSpec>,
+    ) -> Result<bool, Error> {
+        let current_period = self
+            .epoch()?
+            .as_u64()
+            .safe_div(self.spec.epochs_per_sync_committee_period.as_u64())?;
+
+        let mut cache = self.light_client_update_cache.write();
+        if let Some(period) = cache.insert(update, current_period, &self.spec)? {
+            if let Some(best) = cache.get(period) {
+                self.store.put_item(
+                    &Hash256::from_low_u64_be(period),
+                    &PersistedLightClientUpdate(best),
+                )?;
+            }
+
+            trace!(
+                self.log,
+                "Stored light client update";
+                "period" => period,
+            );
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns the best known `LightClientUpdate` for the given sync committee `period`, reading
+    /// from the database if it is not held in memory.
+    pub fn get_light_client_update(
+        &self,
+        period: u64,
+    ) -> Result<Option<LightClientUpdate<T::EthSpec>>, Error> {
+        if let Some(update) = self.light_client_update_cache.read().get(period) {
+            return Ok(Some(update));
+        }
+
+        Ok(self
+            .store
+            .get_item::<PersistedLightClientUpdate<T::EthSpec>>(&Hash256::from_low_u64_be(period))?
+            .map(|persisted| persisted.0))
+    }
+
+    /// Builds a `LightClientUpdate` for `parent_block` after a child block at `block_slot` has
+    /// been imported, and offers it to `self.process_light_client_update`.
+    ///
+    /// Blocks do not carry a `SyncAggregate`, so the update uses the aggregate of the
+    /// contributions for `parent_block` in `self.sync_contribution_pool`. This is the aggregate
+    /// that a proposer at `block_slot` would have packed. The parent state is only loaded if the
+    /// update could improve upon the best known update for its period.
+    ///
+    /// ## Notes
+    ///
+    /// The finality branch is proven against `parent_block.state_root`. Phase 0 states do not
+    /// commit to a next sync committee, so `next_sync_committee` is the committee selected for
+    /// the next period, with an empty aggregate public key and a zeroed branch.
+    fn produce_light_client_update(
+        &self,
+        parent_block: &SignedBeaconBlock<T::EthSpec>,
+        parent_root: Hash256,
+        block_slot: Slot,
+    ) -> Result<bool, Error> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let message_slot = block_slot.saturating_sub(1u64);
+        if self.spec.fork_name_at_slot::<T::EthSpec>(message_slot) == ForkName::Base {
+            return Ok(false);
+        }
+
+        let contributions = self.get_sync_contributions_for_block(message_slot, parent_root);
+        let sync_committee_aggregate = SyncAggregate::from_contributions(&contributions)?;
+        let participants = sync_committee_aggregate.num_set_bits();
+        if participants == 0 {
+            return Ok(false);
+        }
+
+        let header = parent_block.message.block_header();
+        let epochs_per_period = self.spec.epochs_per_sync_committee_period.as_u64();
+        let period = header
+            .slot
+            .epoch(slots_per_epoch)
+            .as_u64()
+            .safe_div(epochs_per_period)?;
+        if let Some(best) = self.light_client_update_cache.read().get(period) {
+            if best.is_finality_update()
+                && best.sync_committee_aggregate.num_set_bits() >= participants
+            {
+                return Ok(false);
+            }
+        }
+
+        let mut state = self
+            .get_state(&header.state_root, Some(header.slot))?
+            .ok_or_else(|| Error::MissingBeaconState(header.state_root))?;
+
+        let (finalized_header, finality_branch) =
+            if state.finalized_checkpoint.root == Hash256::zero() {
+                (
+                    BeaconBlockHeader::default(),
+                    FixedVector::from_elem(Hash256::zero()),
+                )
+            } else {
+                let finalized_block = self
+                    .get_block(&state.finalized_checkpoint.root)?
+                    .ok_or_else(|| Error::MissingBeaconBlock(state.finalized_checkpoint.root))?;
+                (
+                    finalized_block.message.block_header(),
+                    state.compute_finalized_root_proof()?,
+                )
+            };
+
+        let next_period_slot = Epoch::new(period.safe_add(1)?.safe_mul(epochs_per_period)?)
+            .start_slot(slots_per_epoch);
+        let pubkeys = {
+            let pubkey_cache = self
+                .validator_pubkey_cache
+                .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::ValidatorPubkeyCacheLockTimeout)?;
+            self.sync_committee_indices(next_period_slot)?
+                .into_iter()
+                .map(|index| {
+                    pubkey_cache
+                        .get(index as usize)
+                        .cloned()
+                        .map(PublicKeyBytes::from)
+                        .ok_or_else(|| Error::ValidatorPubkeyCacheIncomplete(index as usize))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let update = LightClientUpdate {
+            header,
+            next_sync_committee: SyncCommittee {
+                pubkeys: pubkeys.into(),
+                aggregate_pubkey: PublicKeyBytes::empty(),
+            },
+            next_sync_committee_branch: FixedVector::from_elem(Hash256::zero()),
+            finalized_header,
+            finality_branch,
+            sync_committee_aggregate,
+            fork_version: self
+                .spec
+                .fork_at_epoch(message_slot.epoch(slots_per_epoch))
+                .current_version,
+        };
+
+        self.process_light_client_update(update)
+    }
+
     /// Accepts a `VerifiedAggregatedAttestation` and attempts to apply it to `self.op_pool`.
     ///
     /// The op pool is used by local block producers to pack blocks with operations.
@@ -1163,6 +1690,50 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Returns `true` if verified attestations and blocks are sent to a slasher, either in this
+    /// process or via the `slasher_stream`.
+    pub fn slasher_enabled(&self) -> bool {
+        self.slasher.is_some() || self.slasher_stream.is_some()
+    }
+
+    /// Sends `event` to the slasher and the `slasher_stream`, if they are enabled.
+    ///
+    /// The signature of the attestation or block in `event` must already have been verified.
+    pub fn send_to_slasher(&self, event: SlasherEvent<T::EthSpec>) {
+        if let Some(stream) = self.slasher_stream.as_ref() {
+            // Only log when the queue fills and drains, rather than for every event dropped.
+            if stream.lock().try_broadcast(event.clone()).is_err() {
+                metrics::inc_counter(&metrics::SLASHER_STREAM_EVENTS_DROPPED);
+                if self
+                    .slasher_stream_dropped
+                    .fetch_add(1, atomic::Ordering::Relaxed)
+                    == 0
+                {
+                    warn!(
+                        self.log,
+                        "Slasher stream queue full";
+                        "msg" => "a remote slasher is not keeping up with the beacon node",
+                    );
+                }
+            } else {
+                let dropped = self
+                    .slasher_stream_dropped
+                    .swap(0, atomic::Ordering::Relaxed);
+                if dropped > 0 {
+                    warn!(
+                        self.log,
+                        "Slasher stream events dropped";
+                        "count" => dropped,
+                    );
+                }
+            }
+        }
+
+        if let Some(slasher) = self.slasher.as_ref() {
+            slasher.accept_event(event);
+        }
+    }
+
     /// Attempt to verify and import a chain of blocks to `self`.
     ///
     /// The provided blocks _must_ each reference the previous block via `block.parent_root` (i.e.,
@@ -1189,9 +1760,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(|block| (block.parent_root(), block.slot()))
             .collect::<Vec<_>>();
 
-        for (i, block) in chain_segment.into_iter().enumerate() {
-            let block_root = get_block_root(&block);
+        // Computing block roots requires hashing each block, which is independent for every block
+        // in the segment. Do it in parallel.
+        let block_roots = chain_segment
+            .par_iter()
+            .map(get_block_root)
+            .collect::<Vec<_>>();
 
+        for (i, (block, block_root)) in chain_segment
+            .into_iter()
+            .zip(block_roots.into_iter())
+            .enumerate()
+        {
             if let Some((child_parent_root, child_slot)) = children.get(i) {
                 // If this block has a child in this chain segment, ensure that its parent root matches
                 // the root of this block.
@@ -1319,11 +1899,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: SignedBeaconBlock<T::EthSpec>,
     ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
         let slot = block.message.slot;
+        let proposer_index = block.message.proposer_index;
         let graffiti_string = String::from_utf8(block.message.body.graffiti[..].to_vec())
             .unwrap_or_else(|_| format!("{:?}", &block.message.body.graffiti[..]));
+        // Read the arrival delay before verification so that it does not include our own
+        // processing time.
+        let delay = self.slot_clock.seconds_from_slot_start(slot);
 
         match GossipVerifiedBlock::new(block, self) {
             Ok(verified) => {
+                if let Some(delay) = delay {
+                    self.register_block_arrival_delay(slot, proposer_index, delay);
+                }
+
                 debug!(
                     self.log,
                     "Successfully processed gossip block";
@@ -1348,6 +1936,51 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Records the delay between the start of `slot` and the arrival of a valid gossip block from
+    /// `proposer_index`.
+    ///
+    /// Blocks that arrive after the attestation deadline (one third of the way through the slot)
+    /// are considered late, since validators attesting at the deadline will not have seen them.
+    fn register_block_arrival_delay(&self, slot: Slot, proposer_index: u64, delay: Duration) {
+        metrics::observe(
+            &metrics::BLOCK_GOSSIP_SLOT_START_DELAY_TIME,
+            delay.as_secs_f64(),
+        );
+
+        let attestation_deadline = self.slot_clock.slot_duration() / 3;
+        if delay <= attestation_deadline {
+            return;
+        }
+
+        metrics::inc_counter(&metrics::BLOCK_GOSSIP_LATE_TOTAL);
+
+        let late_count = self.late_block_tracker.write().register_late_block(
+            proposer_index,
+            slot,
+            T::EthSpec::slots_per_epoch(),
+        );
+
+        if late_count >= CONSISTENTLY_LATE_THRESHOLD {
+            warn!(
+                self.log,
+                "Proposer is consistently late";
+                "info" => "blocks from this proposer may cause missed attestations",
+                "late_blocks" => late_count,
+                "delay_ms" => delay.as_millis() as u64,
+                "proposer_index" => proposer_index,
+                "slot" => slot,
+            );
+        } else {
+            debug!(
+                self.log,
+                "Late block received";
+                "delay_ms" => delay.as_millis() as u64,
+                "proposer_index" => proposer_index,
+                "slot" => slot,
+            );
+        }
+    }
+
     /// Returns `Ok(block_root)` if the given `unverified_block` was successfully verified and
     /// imported into the chain.
     ///
@@ -1448,7 +2081,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let state = fully_verified_block.state;
         let parent_block = fully_verified_block.parent_block;
         let current_slot = self.slot()?;
-        let mut ops = fully_verified_block.intermediate_states;
+        let mut ops = fully_verified_block.confirmation_db_batch;
 
         let attestation_observation_timer =
             metrics::start_timer(&metrics::BLOCK_PROCESSING_ATTESTATION_OBSERVATION);
@@ -1501,6 +2134,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
 
             shuffling_cache.insert(state.current_epoch(), target_root, committee_cache);
+            drop(shuffling_cache);
+
+            // The proposer shuffling for this epoch was decided by the block at the last slot of
+            // the previous epoch, cache it so that later blocks in this epoch can have their
+            // proposer checked without a state read.
+            let decision_root = *state.get_block_root(epoch_start_slot - 1)?;
+
+            self.beacon_proposer_cache.lock().insert_from_state(
+                decision_root,
+                &state,
+                &self.spec,
+            )?;
         }
 
         let mut fork_choice = self.fork_choice.write();
@@ -1545,7 +2190,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let db_write_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_WRITE);
 
-        // Store all the states between the parent block state and this block's slot, the block and state.
+        // Store the block and its state, and confirm the states between the parent block state
+        // and this block's slot.
         ops.push(StoreOp::PutBlock(block_root.into(), signed_block.clone()));
         ops.push(StoreOp::PutState(
             block.state_root.into(),
@@ -1584,6 +2230,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::stop_timer(db_write_timer);
 
+        // A failure to produce a light client update should not prevent the block from being
+        // imported.
+        if let Err(e) = self.produce_light_client_update(&parent_block, parent_root, slot) {
+            warn!(
+                self.log,
+                "Unable to produce light client update";
+                "error" => format!("{:?}", e),
+                "block_slot" => slot,
+            );
+        }
+
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
 
         Ok(block_root)
@@ -1647,28 +2304,6 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state.latest_block_header.canonical_root()
         };
 
-        let (proposer_slashings, attester_slashings) = self.op_pool.get_slashings(&state);
-
-        let eth1_data = eth1_chain.eth1_data_for_block_production(&state, &self.spec)?;
-        let deposits = eth1_chain
-            .deposits_for_block_inclusion(&state, &eth1_data, &self.spec)?
-            .into();
-
-        // Map from attestation head block root to shuffling compatibility.
-        // Used to memoize the `attestation_shuffling_is_compatible` function.
-        let mut shuffling_filter_cache = HashMap::new();
-        let attestation_filter = |att: &&Attestation<T::EthSpec>| -> bool {
-            *shuffling_filter_cache
-                .entry((att.data.beacon_block_root, att.data.target.epoch))
-                .or_insert_with(|| {
-                    self.shuffling_is_compatible(
-                        &att.data.beacon_block_root,
-                        att.data.target.epoch,
-                        &state,
-                    )
-                })
-        };
-
         // Iterate through the naive aggregation pool and ensure all the attestations from there
         // are included in the operation pool.
         for attestation in self.naive_aggregation_pool.read().iter() {
@@ -1687,6 +2322,38 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // The selection of operations and eth1 data are independent of each other, so they are
+        // computed concurrently on the rayon pool.
+        let packing_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PACKING_TIMES);
+        let (
+            (attestations, (proposer_slashings, attester_slashings)),
+            (eth1_data_and_deposits, voluntary_exits),
+        ) = rayon::join(
+            || {
+                rayon::join(
+                    || self.get_attestations_for_block(&state),
+                    || self.op_pool.get_slashings(&state),
+                )
+            },
+            || {
+                rayon::join(
+                    || {
+                        let _timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_ETH1_TIMES);
+                        let eth1_data =
+                            eth1_chain.eth1_data_for_block_production(&state, &self.spec)?;
+                        let deposits = eth1_chain
+                            .deposits_for_block_inclusion(&state, &eth1_data, &self.spec)?;
+                        Ok::<_, BlockProductionError>((eth1_data, deposits))
+                    },
+                    || self.op_pool.get_voluntary_exits(&state, &self.spec),
+                )
+            },
+        );
+        metrics::stop_timer(packing_timer);
+
+        let attestations = attestations?;
+        let (eth1_data, deposits) = eth1_data_and_deposits?;
+
         // Override the beacon node's graffiti with graffiti from the validator, if present.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
@@ -1705,13 +2372,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     graffiti,
                     proposer_slashings: proposer_slashings.into(),
                     attester_slashings: attester_slashings.into(),
-                    attestations: self
-                        .op_pool
-                        .get_attestations(&state, attestation_filter, &self.spec)
-                        .map_err(BlockProductionError::OpPoolError)?
-                        .into(),
-                    deposits,
-                    voluntary_exits: self.op_pool.get_voluntary_exits(&state, &self.spec).into(),
+                    attestations: attestations.into(),
+                    deposits: deposits.into(),
+                    voluntary_exits: voluntary_exits.into(),
                 },
             },
             // The block is not signed here, that is the task of a validator client.
@@ -1744,6 +2407,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok((block.message, state))
     }
 
+    /// Selects the attestations from `self.op_pool` that should be packed into a block built upon
+    /// `state`, discarding any that attest to a shuffling incompatible with `state`.
+    fn get_attestations_for_block(
+        &self,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Vec<Attestation<T::EthSpec>>, BlockProductionError> {
+        let _timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES);
+
+        // Map from attestation head block root to shuffling compatibility.
+        // Used to memoize the `attestation_shuffling_is_compatible` function.
+        let mut shuffling_filter_cache = HashMap::new();
+        let attestation_filter = |att: &&Attestation<T::EthSpec>| -> bool {
+            *shuffling_filter_cache
+                .entry((att.data.beacon_block_root, att.data.target.epoch))
+                .or_insert_with(|| {
+                    self.shuffling_is_compatible(
+                        &att.data.beacon_block_root,
+                        att.data.target.epoch,
+                        state,
+                    )
+                })
+        };
+
+        self.op_pool
+            .get_attestations(state, attestation_filter, &self.spec)
+            .map_err(BlockProductionError::OpPoolError)
+    }
+
     /// Execute the fork choice algorithm and enthrone the result as the canonical head.
     pub fn fork_choice(&self) -> Result<(), Error> {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
@@ -1820,7 +2511,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .unwrap_or_else(|_| Hash256::random());
 
         if is_reorg {
+            let depth = self.with_head(|old_head| {
+                Ok(reorg_depth(
+                    old_head.beacon_block_root,
+                    &old_head.beacon_state,
+                    beacon_block_root,
+                    &new_head.beacon_state,
+                ))
+            })?;
+
             metrics::inc_counter(&metrics::FORK_CHOICE_REORG_COUNT);
+            metrics::set_gauge(&metrics::FORK_CHOICE_REORG_DEPTH, depth as i64);
             warn!(
                 self.log,
                 "Beacon chain re-org";
@@ -1828,8 +2529,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "previous_slot" => current_head.slot,
                 "new_head_parent" => format!("{}", new_head.beacon_block.parent_root()),
                 "new_head" => format!("{}", beacon_block_root),
-                "new_slot" => new_head.beacon_block.slot()
+                "new_slot" => new_head.beacon_block.slot(),
+                "depth" => depth,
             );
+
+            if self
+                .config
+                .reorg_alert_depth
+                .map_or(false, |max_depth| depth > max_depth)
+            {
+                self.raise_chain_alert(ChainAlert::DeepReorg {
+                    depth,
+                    previous_head: current_head.block_root,
+                    previous_slot: current_head.slot,
+                    new_head: beacon_block_root,
+                    new_slot: new_head.beacon_block.slot(),
+                });
+            }
         } else {
             debug!(
                 self.log,
@@ -1844,6 +2560,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         };
 
         let new_finalized_checkpoint = new_head.beacon_state.finalized_checkpoint;
+        let new_justified_checkpoint = new_head.beacon_state.current_justified_checkpoint;
         // State root of the finalized state on the epoch boundary, NOT the state
         // of the finalized block. We need to use an iterator in case the state is beyond
         // the reach of the new head's `state_roots` array.
@@ -1872,6 +2589,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
+        // Refuse to adopt a head that has finalized beyond the weak subjectivity checkpoint
+        // without including it.
+        if let Some(wss_checkpoint) = self.config.weak_subjectivity_checkpoint {
+            if old_finalized_checkpoint.epoch < wss_checkpoint.epoch
+                && wss_checkpoint.epoch <= new_finalized_checkpoint.epoch
+            {
+                self.verify_weak_subjectivity_checkpoint(
+                    wss_checkpoint,
+                    beacon_block_root,
+                    &new_head.beacon_state,
+                )?;
+            }
+        }
+
         if current_head.slot.epoch(T::EthSpec::slots_per_epoch())
             < new_head
                 .beacon_state
@@ -1889,7 +2620,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         *self
             .canonical_head
             .try_write_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)? = new_head;
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)? = Arc::new(new_head);
 
         metrics::stop_timer(update_head_timer);
 
@@ -1921,16 +2652,195 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             current_head_beacon_block_root: beacon_block_root,
         });
 
+        if let Some(threshold) = self.config.justified_flip_alert_threshold {
+            let current_epoch = self.epoch()?;
+            let alert = self
+                .chain_alert_tracker
+                .lock()
+                .observe_justified_checkpoint(current_epoch, new_justified_checkpoint, threshold);
+
+            if let Some(alert) = alert {
+                self.raise_chain_alert(alert);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify operators of an anomalous consensus condition via the logs, metrics, event handler
+    /// and any registered alert hooks.
+    fn raise_chain_alert(&self, alert: ChainAlert) {
+        metrics::inc_counter_vec(&metrics::CHAIN_ALERTS_TOTAL, &[alert.name()]);
+
+        crit!(
+            self.log,
+            "Consensus alert";
+            "alert" => alert.name(),
+            "details" => format!("{:?}", alert),
+        );
+
+        for hook in &self.alert_hooks {
+            hook(&alert);
+        }
+
+        let _ = self.event_handler.register(EventKind::ChainAlert { alert });
+    }
+
+    /// Checks the distance between the current epoch and the finalized epoch of the head, raising
+    /// an alert if finality has stalled for longer than permitted by the `ChainConfig`.
+    fn check_finality_stall(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let head_info = self.head_info()?;
+        let head_epoch = head_info.slot.epoch(T::EthSpec::slots_per_epoch());
+        let finalized_epoch = head_info.finalized_checkpoint.epoch;
+
+        metrics::set_gauge(
+            &metrics::EPOCHS_SINCE_FINALIZATION,
+            (current_epoch - finalized_epoch).as_u64() as i64,
+        );
+
+        if let Some(max_epochs) = self.config.finality_stall_alert_epochs {
+            let alert = self.chain_alert_tracker.lock().check_finality(
+                current_epoch,
+                head_epoch,
+                finalized_epoch,
+                max_epochs,
+            );
+
+            if let Some(alert) = alert {
+                self.raise_chain_alert(alert);
+            }
+        }
+
         Ok(())
     }
 
+    /// Verifies that the chain identified by `beacon_block_root` and its `state` includes the
+    /// given weak subjectivity checkpoint.
+    ///
+    /// The checkpoint can only be verified once `state` has finalized the checkpoint epoch. If the
+    /// checkpoint is later than the finalized checkpoint of `state`, `Ok(())` is returned without
+    /// performing any verification.
+    pub fn verify_weak_subjectivity_checkpoint(
+        &self,
+        wss_checkpoint: Checkpoint,
+        beacon_block_root: Hash256,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<(), Error> {
+        let finalized_checkpoint = state.finalized_checkpoint;
+
+        if wss_checkpoint.epoch > finalized_checkpoint.epoch {
+            return Ok(());
+        }
+
+        let found_root = if wss_checkpoint.epoch == finalized_checkpoint.epoch {
+            Some(finalized_checkpoint.root)
+        } else {
+            // If the first slot of the epoch is a skip slot, this finds the root of the closest
+            // prior non-skipped slot, matching the definition of a checkpoint root.
+            let slot = wss_checkpoint
+                .epoch
+                .start_slot(T::EthSpec::slots_per_epoch());
+            process_results(
+                std::iter::once(Ok((beacon_block_root, state.slot)))
+                    .chain(BlockRootsIterator::new(self.store.clone(), state)),
+                |mut iter| {
+                    iter.find(|(_, ancestor_slot)| *ancestor_slot == slot)
+                        .map(|(root, _)| root)
+                },
+            )?
+        };
+
+        if found_root == Some(wss_checkpoint.root) {
+            info!(
+                self.log,
+                "Verified weak subjectivity checkpoint";
+                "epoch" => wss_checkpoint.epoch,
+                "root" => format!("{}", wss_checkpoint.root),
+            );
+            Ok(())
+        } else {
+            crit!(
+                self.log,
+                "Chain does not include the weak subjectivity checkpoint";
+                "info" => "you may be on a malicious chain, check your --wss-checkpoint",
+                "wss_epoch" => wss_checkpoint.epoch,
+                "wss_root" => format!("{}", wss_checkpoint.root),
+                "found_root" => format!("{:?}", found_root),
+                "head_block" => format!("{}", beacon_block_root),
+            );
+            Err(Error::WeakSubjectivityVerificationFailure {
+                wss_checkpoint,
+                found_root,
+            })
+        }
+    }
+
     /// Called by the timer on every slot.
     ///
-    /// Performs slot-based pruning.
+    /// Performs slot-based pruning and periodically persists the operation pool, so that an
+    /// unclean shutdown does not lose all pending operations.
     pub fn per_slot_task(&self) {
+        // A read-only node does not follow the chain, so there is nothing to prune or persist.
+        if self.store.get_config().read_only {
+            return;
+        }
+
         trace!(self.log, "Running beacon chain per slot tasks");
         if let Some(slot) = self.slot_clock.now() {
             self.naive_aggregation_pool.write().prune(slot);
+            self.sync_contribution_pool.write().prune(slot);
+
+            if slot % T::EthSpec::slots_per_epoch() == 0 {
+                self.late_block_tracker.write().prune(
+                    slot.epoch(T::EthSpec::slots_per_epoch()),
+                    T::EthSpec::slots_per_epoch(),
+                );
+
+                if let Err(e) = self.check_finality_stall(slot.epoch(T::EthSpec::slots_per_epoch()))
+                {
+                    error!(
+                        self.log,
+                        "Failed to check for finality stall";
+                        "error" => format!("{:?}", e),
+                    );
+                }
+            }
+
+            if slot % OP_POOL_PERSISTENCE_INTERVAL_SLOTS == 0 {
+                if let Err(e) = self.persist_op_pool() {
+                    error!(
+                        self.log,
+                        "Failed to persist op pool";
+                        "error" => format!("{:?}", e),
+                        "slot" => slot.as_u64(),
+                    );
+                } else {
+                    debug!(
+                        self.log,
+                        "Persisted op pool";
+                        "attestations" => self.op_pool.num_attestations(),
+                        "voluntary_exits" => self.op_pool.num_voluntary_exits(),
+                        "slot" => slot.as_u64(),
+                    );
+                }
+            }
+
+            if slot % ETH1_CACHE_PERSISTENCE_INTERVAL_SLOTS == 0 {
+                if let Err(e) = self.persist_eth1_cache() {
+                    error!(
+                        self.log,
+                        "Failed to persist eth1 cache";
+                        "error" => format!("{:?}", e),
+                        "slot" => slot.as_u64(),
+                    );
+                } else {
+                    debug!(
+                        self.log,
+                        "Persisted eth1 cache";
+                        "slot" => slot.as_u64(),
+                    );
+                }
+            }
         }
     }
 
@@ -1990,9 +2900,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Returns `true` if the given block root has not been processed.
     pub fn is_new_block_root(&self, beacon_block_root: &Hash256) -> Result<bool, Error> {
-        Ok(!self
-            .store
-            .item_exists::<SignedBeaconBlock<T::EthSpec>>(beacon_block_root)?)
+        Ok(!self.store.block_exists(beacon_block_root)?)
     }
 
     /// Dumps the entire canonical chain, from the head to genesis to a vector for analysis.
@@ -2002,12 +2910,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn chain_dump(&self) -> Result<Vec<BeaconSnapshot<T::EthSpec>>, Error> {
         let mut dump = vec![];
 
-        let mut last_slot = BeaconSnapshot {
-            beacon_block: self.head()?.beacon_block,
-            beacon_block_root: self.head()?.beacon_block_root,
-            beacon_state: self.head()?.beacon_state,
-            beacon_state_root: self.head()?.beacon_state_root,
-        };
+        let mut last_slot = self.cached_head()?.clone_with_only_committee_caches();
 
         dump.push(last_slot.clone());
 
@@ -2051,16 +2954,28 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // therefore use the genesis slot.
         let slot = self.slot().unwrap_or_else(|_| self.spec.genesis_slot);
 
-        self.spec.enr_fork_id(slot, self.genesis_validators_root)
+        self.spec
+            .enr_fork_id::<T::EthSpec>(slot, self.genesis_validators_root)
     }
 
     /// Calculates the `Duration` to the next fork, if one exists.
     pub fn duration_to_next_fork(&self) -> Option<Duration> {
-        let epoch = self.spec.next_fork_epoch()?;
+        let slot = self.slot().unwrap_or_else(|_| self.spec.genesis_slot);
+        let (_, epoch) = self.spec.next_fork_epoch::<T::EthSpec>(slot)?;
         self.slot_clock
             .duration_to_slot(epoch.start_slot(T::EthSpec::slots_per_epoch()))
     }
 
+    /// Returns the fork digest that will be used after the next fork, if one exists.
+    pub fn next_fork_digest(&self) -> Option<[u8; 4]> {
+        let slot = self.slot().unwrap_or_else(|_| self.spec.genesis_slot);
+        let (fork_name, _) = self.spec.next_fork_epoch::<T::EthSpec>(slot)?;
+        Some(ChainSpec::compute_fork_digest(
+            self.spec.fork_version_for_name(fork_name),
+            self.genesis_validators_root,
+        ))
+    }
+
     pub fn dump_as_dot<W: Write>(&self, output: &mut W) {
         let canonical_head_hash = self
             .canonical_head
@@ -2174,6 +3089,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
 impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
     fn drop(&mut self) {
+        if self.store.get_config().read_only {
+            return;
+        }
+
         let drop = || -> Result<(), Error> {
             self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
@@ -0,0 +1,195 @@
+use crate::metrics;
+use lru::LruCache;
+use types::{BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, Fork, Hash256, Slot};
+
+/// The number of sets of proposer indices that should be cached.
+///
+/// Each entry is tiny (one index per slot), so we can afford to keep entries for several epochs
+/// and forks.
+const CACHE_SIZE: usize = 16;
+
+/// The proposer for some slot, along with the `Fork` required to verify their signature.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Proposer {
+    pub index: usize,
+    pub fork: Fork,
+}
+
+/// The proposers for each slot of a single epoch.
+struct EpochBlockProposers {
+    /// The epoch to which the proposers pertain.
+    epoch: Epoch,
+    /// The fork that should be used to verify proposer signatures.
+    fork: Fork,
+    /// A list of length `T::EthSpec::slots_per_epoch()`, representing the proposers for each slot
+    /// in that epoch.
+    proposers: Vec<usize>,
+}
+
+/// A cache of the beacon proposer indices for each epoch, so that proposer lookups (e.g., during
+/// gossip block verification or when serving duties) do not require a state read.
+///
+/// Entries are keyed by `(epoch, shuffling_decision_block)`, where `shuffling_decision_block` is
+/// the root of the block at the last slot of the epoch prior to `epoch`. All chains which share
+/// that block also share the same proposer shuffling for `epoch`.
+pub struct BeaconProposerCache {
+    cache: LruCache<(Epoch, Hash256), EpochBlockProposers>,
+}
+
+impl Default for BeaconProposerCache {
+    fn default() -> Self {
+        Self {
+            cache: LruCache::new(CACHE_SIZE),
+        }
+    }
+}
+
+impl BeaconProposerCache {
+    /// If it is cached, returns the proposer for the block at `slot` where the block has the
+    /// given `shuffling_decision_block`.
+    pub fn get_slot<T: EthSpec>(
+        &mut self,
+        shuffling_decision_block: Hash256,
+        slot: Slot,
+    ) -> Option<Proposer> {
+        let epoch = slot.epoch(T::slots_per_epoch());
+        let key = (epoch, shuffling_decision_block);
+
+        let opt = self.cache.get(&key).and_then(|cache| {
+            // This `if` statement is likely unnecessary, but it feels like good practice.
+            if epoch == cache.epoch {
+                cache
+                    .proposers
+                    .get((slot.as_u64() % T::slots_per_epoch()) as usize)
+                    .map(|&index| Proposer {
+                        index,
+                        fork: cache.fork,
+                    })
+            } else {
+                None
+            }
+        });
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::BEACON_PROPOSER_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::BEACON_PROPOSER_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    /// If it is cached, returns the proposers for every slot of `epoch`, where the shuffling was
+    /// decided by `shuffling_decision_block`.
+    pub fn get_epoch(
+        &mut self,
+        shuffling_decision_block: Hash256,
+        epoch: Epoch,
+    ) -> Option<&Vec<usize>> {
+        let opt = self
+            .cache
+            .get(&(epoch, shuffling_decision_block))
+            .map(|cache| &cache.proposers);
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::BEACON_PROPOSER_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::BEACON_PROPOSER_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    /// Returns `true` if the proposers for `epoch` and `shuffling_decision_block` are cached.
+    pub fn contains(&self, shuffling_decision_block: Hash256, epoch: Epoch) -> bool {
+        self.cache.contains(&(epoch, shuffling_decision_block))
+    }
+
+    /// Computes the proposers for the current epoch of `state` and stores them in the cache under
+    /// `shuffling_decision_block`.
+    ///
+    /// The committee cache for the current epoch of `state` must be built.
+    pub fn insert_from_state<T: EthSpec>(
+        &mut self,
+        shuffling_decision_block: Hash256,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Result<(), BeaconStateError> {
+        let epoch = state.current_epoch();
+        let key = (epoch, shuffling_decision_block);
+
+        if !self.cache.contains(&key) {
+            let proposers = epoch
+                .slot_iter(T::slots_per_epoch())
+                .map(|slot| state.get_beacon_proposer_index(slot, spec))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            self.cache.put(
+                key,
+                EpochBlockProposers {
+                    epoch,
+                    fork: state.fork,
+                    proposers,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+    use types::{MinimalEthSpec, RelativeEpoch};
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn insert_and_get() {
+        let spec = E::default_spec();
+        let (mut state, _keypairs) =
+            TestingBeaconStateBuilder::<E>::from_deterministic_keypairs(16, &spec).build();
+        state.slot = Slot::new(E::slots_per_epoch());
+        state
+            .build_committee_cache(RelativeEpoch::Current, &spec)
+            .expect("should build committee cache");
+
+        let root = Hash256::repeat_byte(1);
+        let epoch = state.current_epoch();
+        let mut cache = BeaconProposerCache::default();
+
+        assert!(!cache.contains(root, epoch));
+        assert_eq!(cache.get_slot::<E>(root, state.slot), None);
+
+        cache
+            .insert_from_state(root, &state, &spec)
+            .expect("should insert");
+
+        assert!(cache.contains(root, epoch));
+        assert!(!cache.contains(Hash256::zero(), epoch));
+
+        for slot in epoch.slot_iter(E::slots_per_epoch()) {
+            let expected = state
+                .get_beacon_proposer_index(slot, &spec)
+                .expect("should get proposer");
+            assert_eq!(
+                cache.get_slot::<E>(root, slot),
+                Some(Proposer {
+                    index: expected,
+                    fork: state.fork,
+                })
+            );
+        }
+
+        assert_eq!(
+            cache.get_epoch(root, epoch).map(Vec::len),
+            Some(E::slots_per_epoch() as usize)
+        );
+        assert_eq!(
+            cache.get_slot::<E>(root, state.slot + E::slots_per_epoch()),
+            None
+        );
+    }
+}
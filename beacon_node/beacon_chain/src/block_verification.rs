@@ -50,6 +50,7 @@ use crate::{
 };
 use fork_choice::{ForkChoice, ForkChoiceStore};
 use parking_lot::RwLockReadGuard;
+use slasher::SlasherEvent;
 use slog::{error, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
@@ -66,7 +67,7 @@ use std::io::Write;
 use store::{Error as DBError, HotColdDB, HotStateSummary, StoreOp};
 use tree_hash::TreeHash;
 use types::{
-    BeaconBlock, BeaconState, BeaconStateError, ChainSpec, CloneConfig, EthSpec, Hash256,
+    BeaconBlock, BeaconState, BeaconStateError, ChainSpec, CloneConfig, EthSpec, Fork, Hash256,
     PublicKey, RelativeEpoch, SignedBeaconBlock, Slot,
 };
 
@@ -298,9 +299,8 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
     let pubkey_cache = get_validator_pubkey_cache(chain)?;
     let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
 
-    for (block_root, block) in &chain_segment {
-        signature_verifier.include_all_signatures(block, Some(*block_root))?;
-    }
+    // Collect the signatures from all blocks in parallel, then verify them as a single batch.
+    signature_verifier.include_all_signatures_from_blocks(&chain_segment)?;
 
     if signature_verifier.verify().is_err() {
         return Err(BlockError::InvalidSignature);
@@ -356,7 +356,9 @@ pub struct FullyVerifiedBlock<'a, T: BeaconChainTypes> {
     pub block_root: Hash256,
     pub state: BeaconState<T::EthSpec>,
     pub parent_block: SignedBeaconBlock<T::EthSpec>,
-    pub intermediate_states: Vec<StoreOp<'a, T::EthSpec>>,
+    /// Removes the temporary flags from the intermediate states stored whilst verifying the
+    /// block. Must be applied atomically with the import of the block.
+    pub confirmation_db_batch: Vec<StoreOp<'a, T::EthSpec>>,
 }
 
 /// Implemented on types that can be converted into a `FullyVerifiedBlock`.
@@ -409,10 +411,14 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         }
 
         // Check that we have not already received a block with a valid signature for this slot.
-        if chain
-            .observed_block_producers
-            .proposer_has_been_observed(&block.message)
-            .map_err(|e| BlockError::BeaconChainError(e.into()))?
+        //
+        // When the slasher is enabled this check is deferred until after the signature has been
+        // verified, so that a second block from the same proposer reaches the slasher.
+        if !chain.slasher_enabled()
+            && chain
+                .observed_block_producers
+                .proposer_has_been_observed(&block.message)
+                .map_err(|e| BlockError::BeaconChainError(e.into()))?
         {
             return Err(BlockError::RepeatProposal {
                 proposer: block.message.proposer_index,
@@ -429,33 +435,61 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             &chain.store,
         )?;
 
-        let (mut parent, block) = load_parent(block, chain)?;
+        let block_epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
+        let proposer_shuffling_decision_block =
+            chain.proposer_shuffling_decision_root(block_epoch, block.parent_root());
+
+        // Try to obtain the proposer from the cache, so that blocks from the wrong proposer or with
+        // invalid signatures can be rejected without a state read.
+        let cached_proposer = proposer_shuffling_decision_block.and_then(|decision_block| {
+            chain
+                .beacon_proposer_cache
+                .lock()
+                .get_slot::<T::EthSpec>(decision_block, block.slot())
+        });
 
-        // Reject any block that exceeds our limit on skipped slots.
-        check_block_skip_slots(chain, &parent.beacon_block.message, &block.message)?;
+        let (parent, block, expected_proposer) = if let Some(proposer) = cached_proposer {
+            verify_proposer_signature(&block, block_root, &proposer.fork, chain)?;
 
-        let state = cheap_state_advance_to_obtain_committees(
-            &mut parent.beacon_state,
-            block.slot(),
-            &chain.spec,
-        )?;
+            let (parent, block) = load_parent(block, chain)?;
+
+            // Reject any block that exceeds our limit on skipped slots.
+            check_block_skip_slots(chain, &parent.beacon_block.message, &block.message)?;
 
-        let signature_is_valid = {
-            let pubkey_cache = get_validator_pubkey_cache(chain)?;
-            let pubkey = pubkey_cache
-                .get(block.message.proposer_index as usize)
-                .ok_or_else(|| BlockError::UnknownValidator(block.message.proposer_index))?;
-            block.verify_signature(
-                Some(block_root),
-                pubkey,
-                &state.fork,
-                chain.genesis_validators_root,
+            (parent, block, proposer.index as u64)
+        } else {
+            let (mut parent, block) = load_parent(block, chain)?;
+
+            // Reject any block that exceeds our limit on skipped slots.
+            check_block_skip_slots(chain, &parent.beacon_block.message, &block.message)?;
+
+            let state = cheap_state_advance_to_obtain_committees(
+                &mut parent.beacon_state,
+                block.slot(),
                 &chain.spec,
-            )
+            )?;
+
+            if let Some(decision_block) = proposer_shuffling_decision_block {
+                chain.beacon_proposer_cache.lock().insert_from_state(
+                    decision_block,
+                    &state,
+                    &chain.spec,
+                )?;
+            }
+
+            verify_proposer_signature(&block, block_root, &state.fork, chain)?;
+
+            let expected_proposer =
+                state.get_beacon_proposer_index(block.message.slot, &chain.spec)? as u64;
+
+            drop(state);
+
+            (parent, block, expected_proposer)
         };
 
-        if !signature_is_valid {
-            return Err(BlockError::ProposalSignatureInvalid);
+        // The signature is valid, so the block may be checked for slashings.
+        if chain.slasher_enabled() {
+            chain.send_to_slasher(SlasherEvent::Block(block.signed_block_header()));
         }
 
         // Now the signature is valid, store the proposal so we don't accept another from this
@@ -474,8 +508,6 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             });
         }
 
-        let expected_proposer =
-            state.get_beacon_proposer_index(block.message.slot, &chain.spec)? as u64;
         if block.message.proposer_index != expected_proposer {
             return Err(BlockError::IncorrectBlockProposer {
                 block: block.message.proposer_index,
@@ -669,9 +701,14 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
 
         let catchup_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_CATCHUP_STATE);
 
-        // Keep a batch of any states that were "skipped" (block-less) in between the parent state
-        // slot and the block slot. These will be stored in the database.
-        let mut intermediate_states: Vec<StoreOp<T::EthSpec>> = Vec::new();
+        // Any states that were "skipped" (block-less) in between the parent state slot and the
+        // block slot are written to the database immediately, flagged as temporary. The flags are
+        // removed by `confirmation_db_batch` when the block is imported, whilst the states of a
+        // block which is never imported are deleted at startup by `delete_temp_states`.
+        //
+        // States which are already in the database (e.g., the skip slots of a canonical sibling
+        // block) are not flagged again, since they may not be deleted if this import fails.
+        let mut confirmation_db_batch: Vec<StoreOp<T::EthSpec>> = Vec::new();
 
         // The block must have a higher slot than its parent.
         if block.slot() <= parent.beacon_state.slot {
@@ -690,23 +727,32 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
             let state_root = if i == 0 {
                 parent.beacon_block.state_root()
             } else {
-                // This is a new state we've reached, so stage it for storage in the DB.
+                // This is a new state we've reached, so store it in the DB.
                 // Computing the state root here is time-equivalent to computing it during slot
                 // processing, but we get early access to it.
                 let state_root = state.update_tree_hash_cache()?;
 
-                let op = if state.slot % T::EthSpec::slots_per_epoch() == 0 {
-                    StoreOp::PutState(
-                        state_root.into(),
-                        Cow::Owned(state.clone_with(CloneConfig::committee_caches_only())),
-                    )
-                } else {
-                    StoreOp::PutStateSummary(
-                        state_root.into(),
-                        HotStateSummary::new(&state_root, &state)?,
-                    )
-                };
-                intermediate_states.push(op);
+                if chain
+                    .store
+                    .load_hot_state_summary(&state_root.into())?
+                    .is_none()
+                {
+                    let op = if state.slot % T::EthSpec::slots_per_epoch() == 0 {
+                        StoreOp::PutState(state_root.into(), Cow::Borrowed(&state))
+                    } else {
+                        StoreOp::PutStateSummary(
+                            state_root.into(),
+                            HotStateSummary::new(&state_root, &state)?,
+                        )
+                    };
+                    chain.store.do_atomically(vec![
+                        StoreOp::PutStateTemporaryFlag(state_root.into()),
+                        op,
+                    ])?;
+                }
+                // The state may have been written and flagged by a concurrent import, in which
+                // case whichever import completes first confirms it.
+                confirmation_db_batch.push(StoreOp::DeleteStateTemporaryFlag(state_root.into()));
                 state_root
             };
 
@@ -794,7 +840,7 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
             block_root,
             state,
             parent_block: parent.beacon_block,
-            intermediate_states,
+            confirmation_db_batch,
         })
     }
 }
@@ -865,7 +911,7 @@ pub fn check_block_is_finalized_descendant<T: BeaconChainTypes, F: ForkChoiceSto
         // 2. The parent is unknown to us, we probably want to download it since it might actually
         //    descend from the finalized root.
         if store
-            .item_exists::<SignedBeaconBlock<T::EthSpec>>(&block.parent_root())
+            .block_exists(&block.parent_root())
             .map_err(|e| BlockError::BeaconChainError(e.into()))?
         {
             Err(BlockError::NotFinalizedDescendant {
@@ -1014,6 +1060,35 @@ fn load_parent<T: BeaconChainTypes>(
     result
 }
 
+/// Checks that the proposer signature on `block` is valid with respect to the pubkey of
+/// `block.message.proposer_index`.
+fn verify_proposer_signature<T: BeaconChainTypes>(
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+    fork: &Fork,
+    chain: &BeaconChain<T>,
+) -> Result<(), BlockError<T::EthSpec>> {
+    let signature_is_valid = {
+        let pubkey_cache = get_validator_pubkey_cache(chain)?;
+        let pubkey = pubkey_cache
+            .get(block.message.proposer_index as usize)
+            .ok_or_else(|| BlockError::UnknownValidator(block.message.proposer_index))?;
+        block.verify_signature(
+            Some(block_root),
+            pubkey,
+            fork,
+            chain.genesis_validators_root,
+            &chain.spec,
+        )
+    };
+
+    if signature_is_valid {
+        Ok(())
+    } else {
+        Err(BlockError::ProposalSignatureInvalid)
+    }
+}
+
 /// Performs a cheap (time-efficient) state advancement so the committees for `slot` can be
 /// obtained from `state`.
 ///
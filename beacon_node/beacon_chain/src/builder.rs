@@ -1,6 +1,8 @@
 use crate::beacon_chain::{
-    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    SlasherStream, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    SYNC_COMMITTEE_CACHE_SIZE,
 };
+use crate::chain_alerts::ChainAlertHook;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::events::NullEventHandler;
 use crate::head_tracker::HeadTracker;
@@ -8,6 +10,7 @@ use crate::migrate::Migrate;
 use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::ShufflingCache;
+use crate::skip_slot_state_cache::SkipSlotStateCache;
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
@@ -18,12 +21,15 @@ use crate::{
 };
 use eth1::Config as Eth1Config;
 use fork_choice::ForkChoice;
+use lru::LruCache;
 use operation_pool::{OperationPool, PersistedOperationPool};
-use parking_lot::RwLock;
-use slog::{info, Logger};
+use parking_lot::{Mutex, RwLock};
+use slasher::Slasher;
+use slog::{info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::marker::PhantomData;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use std::time::Duration;
 use store::{HotColdDB, ItemStore};
@@ -32,7 +38,10 @@ use types::{
     Slot,
 };
 
-pub const PUBKEY_CACHE_FILENAME: &str = "pubkey_cache.ssz";
+pub const PUBKEY_CACHE_FILENAME: &str = "pubkey_cache_v2.bin";
+/// The name of the pubkey cache file used by previous versions, which stored keys in compressed
+/// form. It is replaced by `PUBKEY_CACHE_FILENAME` when the chain is resumed.
+pub const LEGACY_PUBKEY_CACHE_FILENAME: &str = "pubkey_cache.ssz";
 
 /// An empty struct used to "witness" all the `BeaconChainTypes` traits. It has no user-facing
 /// functionality and only exists to satisfy the type system.
@@ -115,6 +124,9 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     disabled_forks: Vec<String>,
     log: Option<Logger>,
     graffiti: Graffiti,
+    alert_hooks: Vec<ChainAlertHook>,
+    slasher: Option<Arc<Slasher<TEthSpec>>>,
+    slasher_stream: Option<SlasherStream<TEthSpec>>,
 }
 
 impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
@@ -162,6 +174,9 @@ where
             chain_config: ChainConfig::default(),
             log: None,
             graffiti: Graffiti::default(),
+            alert_hooks: Vec::new(),
+            slasher: None,
+            slasher_stream: None,
         }
     }
 
@@ -286,7 +301,7 @@ where
 
         let head_block_root = chain.canonical_head_block_root;
         let head_block = store
-            .get_item::<SignedBeaconBlock<TEthSpec>>(&head_block_root)
+            .get_block(&head_block_root)
             .map_err(|e| format!("DB error when reading head block: {:?}", e))?
             .ok_or_else(|| "Head block not found in store".to_string())?;
         let head_state_root = head_block.state_root();
@@ -295,17 +310,26 @@ where
             .map_err(|e| format!("DB error when reading head state: {:?}", e))?
             .ok_or_else(|| "Head state not found in store".to_string())?;
 
-        self.op_pool = Some(
-            store
-                .get_item::<PersistedOperationPool<TEthSpec>>(&Hash256::from_slice(&OP_POOL_DB_KEY))
-                .map_err(|e| format!("DB error whilst reading persisted op pool: {:?}", e))?
-                .map(PersistedOperationPool::into_operation_pool)
-                .unwrap_or_else(OperationPool::new),
+        let op_pool = store
+            .get_item::<PersistedOperationPool<TEthSpec>>(&Hash256::from_slice(&OP_POOL_DB_KEY))
+            .map_err(|e| format!("DB error whilst reading persisted op pool: {:?}", e))?
+            .map(PersistedOperationPool::into_operation_pool)
+            .unwrap_or_else(OperationPool::new);
+
+        info!(
+            log,
+            "Restored op pool from disk";
+            "attestations" => op_pool.num_attestations(),
+            "attester_slashings" => op_pool.num_attester_slashings(),
+            "proposer_slashings" => op_pool.num_proposer_slashings(),
+            "voluntary_exits" => op_pool.num_voluntary_exits(),
         );
 
+        self.op_pool = Some(op_pool);
+
         let finalized_block_root = head_state.finalized_checkpoint.root;
         let finalized_block = store
-            .get_item::<SignedBeaconBlock<TEthSpec>>(&finalized_block_root)
+            .get_block(&finalized_block_root)
             .map_err(|e| format!("DB error when reading finalized block: {:?}", e))?
             .ok_or_else(|| "Finalized block not found in store".to_string())?;
         let finalized_state_root = finalized_block.state_root();
@@ -321,6 +345,39 @@ where
             beacon_state: finalized_state,
         });
 
+        let pubkey_cache = if pubkey_cache_path.exists() {
+            ValidatorPubkeyCache::load_from_file(pubkey_cache_path)
+                .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?
+        } else {
+            // The cache file is either missing or in the legacy format. Rebuild it from the head
+            // state, which is the only time a full scan of the validator set is required.
+            info!(
+                log,
+                "Rebuilding validator pubkey cache";
+                "path" => format!("{:?}", pubkey_cache_path),
+            );
+
+            let cache = ValidatorPubkeyCache::new(&head_state, pubkey_cache_path)
+                .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))?;
+
+            if let Some(legacy_path) = pubkey_cache_path
+                .parent()
+                .map(|dir| dir.join(LEGACY_PUBKEY_CACHE_FILENAME))
+                .filter(|path| path.exists())
+            {
+                if let Err(e) = std::fs::remove_file(&legacy_path) {
+                    warn!(
+                        log,
+                        "Unable to remove legacy pubkey cache";
+                        "path" => format!("{:?}", legacy_path),
+                        "error" => format!("{:?}", e),
+                    );
+                }
+            }
+
+            cache
+        };
+
         self.canonical_head = Some(BeaconSnapshot {
             beacon_block_root: head_block_root,
             beacon_block: head_block,
@@ -328,9 +385,6 @@ where
             beacon_state: head_state,
         });
 
-        let pubkey_cache = ValidatorPubkeyCache::load_from_file(pubkey_cache_path)
-            .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?;
-
         self.validator_pubkey_cache = Some(pubkey_cache);
 
         Ok(self)
@@ -364,6 +418,16 @@ where
             .put_item(&beacon_block_root, &beacon_block)
             .map_err(|e| format!("Failed to store genesis block: {:?}", e))?;
 
+        // A chain started from a state other than genesis has no prior history.
+        if beacon_state.slot != self.spec.genesis_slot {
+            store
+                .init_anchor_info(
+                    beacon_state.slot,
+                    beacon_state.latest_block_header.parent_root,
+                )
+                .map_err(|e| format!("Failed to store anchor info: {:?}", e))?;
+        }
+
         // Store the genesis block under the `ZERO_HASH` key.
         store
             .put_item(&Hash256::zero(), &beacon_block)
@@ -418,6 +482,24 @@ where
         self
     }
 
+    /// Sets the `slasher` field.
+    pub fn slasher(mut self, slasher: Arc<Slasher<TEthSpec>>) -> Self {
+        self.slasher = Some(slasher);
+        self
+    }
+
+    /// Sets the `slasher_stream` field.
+    pub fn slasher_stream(mut self, slasher_stream: SlasherStream<TEthSpec>) -> Self {
+        self.slasher_stream = Some(slasher_stream);
+        self
+    }
+
+    /// Adds a function that will be called each time the `BeaconChain` raises a `ChainAlert`.
+    pub fn alert_hook(mut self, hook: ChainAlertHook) -> Self {
+        self.alert_hooks.push(hook);
+        self
+    }
+
     /// Sets the `ChainConfig` that determines `BeaconChain` runtime behaviour.
     pub fn chain_config(mut self, config: ChainConfig) -> Self {
         self.chain_config = config;
@@ -524,6 +606,12 @@ where
             observed_attesters: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_aggregators: <_>::default(),
+            sync_contribution_pool: <_>::default(),
+            observed_sync_contributors: <_>::default(),
+            observed_sync_aggregators: <_>::default(),
+            sync_committee_cache: Mutex::new(LruCache::new(SYNC_COMMITTEE_CACHE_SIZE)),
+            light_client_update_cache: <_>::default(),
+            late_block_tracker: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_block_producers: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
@@ -532,7 +620,7 @@ where
             observed_attester_slashings: <_>::default(),
             eth1_chain: self.eth1_chain,
             genesis_validators_root: canonical_head.beacon_state.genesis_validators_root,
-            canonical_head: TimeoutRwLock::new(canonical_head.clone()),
+            canonical_head: TimeoutRwLock::new(Arc::new(canonical_head.clone())),
             genesis_block_root: self
                 .genesis_block_root
                 .ok_or_else(|| "Cannot build without a genesis block root".to_string())?,
@@ -546,16 +634,33 @@ where
                 canonical_head,
             )),
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
+            beacon_proposer_cache: <_>::default(),
+            skip_slot_state_cache: TimeoutRwLock::new(SkipSlotStateCache::new()),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             disabled_forks: self.disabled_forks,
             log: log.clone(),
             graffiti: self.graffiti,
+            chain_alert_tracker: <_>::default(),
+            alert_hooks: self.alert_hooks,
+            slasher: self.slasher,
+            slasher_stream: self.slasher_stream,
+            slasher_stream_dropped: AtomicUsize::new(0),
         };
 
         let head = beacon_chain
-            .head()
+            .cached_head()
             .map_err(|e| format!("Failed to get head: {:?}", e))?;
 
+        if let Some(wss_checkpoint) = beacon_chain.config.weak_subjectivity_checkpoint {
+            beacon_chain
+                .verify_weak_subjectivity_checkpoint(
+                    wss_checkpoint,
+                    head.beacon_block_root,
+                    &head.beacon_state,
+                )
+                .map_err(|e| format!("Weak subjectivity verification failed: {:?}", e))?;
+        }
+
         info!(
             log,
             "Beacon chain initialized";
@@ -607,6 +712,22 @@ where
 
         Ok(self)
     }
+
+    /// Sets the `BeaconChain` eth1 back-end to vote for the existing `Eth1Data` and never include
+    /// deposits when producing blocks.
+    pub fn disabled_eth1_backend(mut self) -> Result<Self, String> {
+        let log = self
+            .log
+            .as_ref()
+            .ok_or_else(|| "disabled_eth1_backend requires a log".to_string())?;
+
+        let backend =
+            CachingEth1Backend::new(Eth1Config::default(), log.clone(), self.spec.clone());
+
+        self.eth1_chain = Some(Eth1Chain::new_disabled(backend));
+
+        Ok(self)
+    }
 }
 
 impl<TStoreMigrator, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
@@ -0,0 +1,298 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use types::{BeaconState, Checkpoint, Epoch, EthSpec, Hash256, Slot};
+
+/// The number of epochs over which changes to the justified checkpoint are counted when detecting
+/// justification flip-flopping.
+const JUSTIFIED_FLIP_WINDOW_EPOCHS: u64 = 4;
+/// Finality stalls are not reported while the head is more than this many epochs behind the
+/// current epoch, since the finalized checkpoint of a syncing node always lags the current epoch.
+const FINALITY_STALL_SYNC_TOLERANCE_EPOCHS: u64 = 2;
+/// An ongoing finality stall is reported at most once per this many epochs.
+const FINALITY_STALL_REALERT_EPOCHS: u64 = 16;
+
+/// A function which is called with each `ChainAlert` raised by the `BeaconChain`.
+pub type ChainAlertHook = Box<dyn Fn(&ChainAlert) + Send + Sync>;
+
+/// An anomalous consensus condition that an operator should be made aware of.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "alert")]
+pub enum ChainAlert {
+    /// The head switched to a chain that diverged from the previous head more than the configured
+    /// number of slots ago.
+    DeepReorg {
+        depth: u64,
+        previous_head: Hash256,
+        previous_slot: Slot,
+        new_head: Hash256,
+        new_slot: Slot,
+    },
+    /// The finalized checkpoint has fallen more than the configured number of epochs behind the
+    /// current epoch.
+    FinalityStall {
+        current_epoch: Epoch,
+        finalized_epoch: Epoch,
+    },
+    /// The justified checkpoint of the head has regressed or changed root without advancing
+    /// several times in quick succession.
+    JustificationFlipFlop {
+        flips: u64,
+        current_epoch: Epoch,
+        justified_checkpoint: Checkpoint,
+    },
+}
+
+impl ChainAlert {
+    /// A short, static name for the alert, suitable for logs and metric labels.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChainAlert::DeepReorg { .. } => "deep_reorg",
+            ChainAlert::FinalityStall { .. } => "finality_stall",
+            ChainAlert::JustificationFlipFlop { .. } => "justification_flip_flop",
+        }
+    }
+}
+
+/// Tracks the state required to detect anomalies that span multiple calls to fork choice or
+/// multiple slots.
+#[derive(Default)]
+pub struct ChainAlertTracker {
+    /// The most recent justified checkpoint of the head.
+    justified_checkpoint: Option<Checkpoint>,
+    /// The epochs at which the justified checkpoint flip-flopped, within the flip window.
+    justified_flips: VecDeque<Epoch>,
+    /// The epoch at which the last finality stall alert was raised.
+    last_finality_stall_alert: Option<Epoch>,
+}
+
+impl ChainAlertTracker {
+    /// Observe the justified checkpoint of a new head.
+    ///
+    /// The checkpoint is considered to have "flipped" if it has a lower epoch than the previous
+    /// checkpoint, or the same epoch but a different root. An alert is returned if at least
+    /// `threshold` flips have been observed within `JUSTIFIED_FLIP_WINDOW_EPOCHS`.
+    pub fn observe_justified_checkpoint(
+        &mut self,
+        current_epoch: Epoch,
+        justified_checkpoint: Checkpoint,
+        threshold: u64,
+    ) -> Option<ChainAlert> {
+        let previous = self.justified_checkpoint.replace(justified_checkpoint);
+
+        let lowest_permissible_epoch = current_epoch - JUSTIFIED_FLIP_WINDOW_EPOCHS;
+        while self
+            .justified_flips
+            .front()
+            .map_or(false, |epoch| *epoch < lowest_permissible_epoch)
+        {
+            self.justified_flips.pop_front();
+        }
+
+        let flipped = previous.map_or(false, |previous| {
+            justified_checkpoint.epoch < previous.epoch
+                || (justified_checkpoint.epoch == previous.epoch
+                    && justified_checkpoint.root != previous.root)
+        });
+
+        if !flipped {
+            return None;
+        }
+
+        self.justified_flips.push_back(current_epoch);
+        let flips = self.justified_flips.len() as u64;
+
+        if flips >= threshold {
+            Some(ChainAlert::JustificationFlipFlop {
+                flips,
+                current_epoch,
+                justified_checkpoint,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Check whether finality has stalled for more than `max_epochs`.
+    ///
+    /// Nothing is reported while the node is syncing (i.e., `head_epoch` is well behind
+    /// `current_epoch`). An ongoing stall is reported again every `FINALITY_STALL_REALERT_EPOCHS`.
+    pub fn check_finality(
+        &mut self,
+        current_epoch: Epoch,
+        head_epoch: Epoch,
+        finalized_epoch: Epoch,
+        max_epochs: u64,
+    ) -> Option<ChainAlert> {
+        if head_epoch + FINALITY_STALL_SYNC_TOLERANCE_EPOCHS < current_epoch {
+            return None;
+        }
+
+        if current_epoch <= finalized_epoch + max_epochs {
+            // Finality has recovered, so the next stall should be reported immediately.
+            self.last_finality_stall_alert = None;
+            return None;
+        }
+
+        if self.last_finality_stall_alert.map_or(false, |last| {
+            current_epoch < last + FINALITY_STALL_REALERT_EPOCHS
+        }) {
+            return None;
+        }
+
+        self.last_finality_stall_alert = Some(current_epoch);
+
+        Some(ChainAlert::FinalityStall {
+            current_epoch,
+            finalized_epoch,
+        })
+    }
+}
+
+/// Returns the number of slots between the previous head and the most recent block it shares with
+/// the new head.
+///
+/// If no common ancestor can be found within the `block_roots` of both states, the distance to the
+/// oldest slot that could be checked is returned (i.e., the true depth is at least this large).
+pub fn reorg_depth<E: EthSpec>(
+    previous_head_root: Hash256,
+    previous_state: &BeaconState<E>,
+    new_head_root: Hash256,
+    new_state: &BeaconState<E>,
+) -> u64 {
+    let root_at = |state: &BeaconState<E>, head_root: Hash256, slot: Slot| {
+        if slot >= state.slot {
+            Some(head_root)
+        } else {
+            state.get_block_root(slot).ok().copied()
+        }
+    };
+
+    let previous_slot = previous_state.slot;
+    let mut slot = previous_slot;
+
+    loop {
+        match (
+            root_at(previous_state, previous_head_root, slot),
+            root_at(new_state, new_head_root, slot),
+        ) {
+            (Some(previous_root), Some(new_root)) if previous_root == new_root => break,
+            (Some(_), Some(_)) if slot > Slot::new(0) => slot -= 1,
+            _ => break,
+        }
+    }
+
+    (previous_slot - slot).as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(epoch: u64, root: u8) -> Checkpoint {
+        Checkpoint {
+            epoch: Epoch::new(epoch),
+            root: Hash256::repeat_byte(root),
+        }
+    }
+
+    #[test]
+    fn justified_checkpoint_advancing_is_not_a_flip() {
+        let mut tracker = ChainAlertTracker::default();
+
+        for epoch in 0..16 {
+            assert_eq!(
+                tracker.observe_justified_checkpoint(Epoch::new(epoch), checkpoint(epoch, 1), 1),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn justified_checkpoint_flip_flop() {
+        let mut tracker = ChainAlertTracker::default();
+        let epoch = Epoch::new(10);
+
+        assert_eq!(
+            tracker.observe_justified_checkpoint(epoch, checkpoint(8, 1), 2),
+            None
+        );
+        assert_eq!(
+            tracker.observe_justified_checkpoint(epoch, checkpoint(8, 2), 2),
+            None,
+            "first flip is below the threshold"
+        );
+        assert_eq!(
+            tracker.observe_justified_checkpoint(epoch, checkpoint(7, 1), 2),
+            Some(ChainAlert::JustificationFlipFlop {
+                flips: 2,
+                current_epoch: epoch,
+                justified_checkpoint: checkpoint(7, 1),
+            })
+        );
+
+        // Flips outside of the window are forgotten.
+        let later = epoch + JUSTIFIED_FLIP_WINDOW_EPOCHS + 1;
+        assert_eq!(
+            tracker.observe_justified_checkpoint(later, checkpoint(7, 2), 2),
+            None
+        );
+    }
+
+    #[test]
+    fn finality_stall() {
+        let mut tracker = ChainAlertTracker::default();
+        let check = |tracker: &mut ChainAlertTracker, current_epoch: u64, finalized_epoch: u64| {
+            tracker.check_finality(
+                Epoch::new(current_epoch),
+                Epoch::new(current_epoch),
+                Epoch::new(finalized_epoch),
+                4,
+            )
+        };
+
+        assert_eq!(check(&mut tracker, 6, 2), None);
+        assert_eq!(
+            check(&mut tracker, 7, 2),
+            Some(ChainAlert::FinalityStall {
+                current_epoch: Epoch::new(7),
+                finalized_epoch: Epoch::new(2),
+            })
+        );
+        assert_eq!(
+            check(&mut tracker, 7, 2),
+            None,
+            "should only alert once per epoch"
+        );
+        assert_eq!(
+            check(&mut tracker, 7 + FINALITY_STALL_REALERT_EPOCHS - 1, 2),
+            None,
+            "should not re-alert within the interval"
+        );
+        assert!(check(&mut tracker, 7 + FINALITY_STALL_REALERT_EPOCHS, 2).is_some());
+
+        // Once finality recovers, a new stall is reported immediately.
+        assert_eq!(check(&mut tracker, 30, 28), None);
+        assert!(check(&mut tracker, 33, 28).is_some());
+    }
+
+    #[test]
+    fn finality_stall_while_syncing() {
+        let mut tracker = ChainAlertTracker::default();
+        let current_epoch = Epoch::new(100);
+        let finalized_epoch = Epoch::new(2);
+
+        assert_eq!(
+            tracker.check_finality(current_epoch, Epoch::new(4), finalized_epoch, 4),
+            None,
+            "should not alert whilst the head is far behind the current epoch"
+        );
+        assert!(tracker
+            .check_finality(
+                current_epoch,
+                current_epoch - FINALITY_STALL_SYNC_TOLERANCE_EPOCHS,
+                finalized_epoch,
+                4
+            )
+            .is_some());
+    }
+}
@@ -1,7 +1,13 @@
 use serde_derive::{Deserialize, Serialize};
+use types::Checkpoint;
 
 /// There is a 693 block skip in the current canonical Medalla chain, we use 700 to be safe.
 pub const DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS: u64 = 700;
+/// Re-orgs of one or two slots are routine on a healthy network, anything deeper is noteworthy.
+pub const DEFAULT_REORG_ALERT_DEPTH: u64 = 2;
+/// Finality normally lags the current epoch by two epochs.
+pub const DEFAULT_FINALITY_STALL_ALERT_EPOCHS: u64 = 4;
+pub const DEFAULT_JUSTIFIED_FLIP_ALERT_THRESHOLD: u64 = 2;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
@@ -10,12 +16,34 @@ pub struct ChainConfig {
     ///
     /// If `None`, there is no limit.
     pub import_max_skip_slots: Option<u64>,
+    /// A user-input `Checkpoint` that must exist in the beacon chain's sync path.
+    ///
+    /// If `None`, there is no weak subjectivity verification.
+    pub weak_subjectivity_checkpoint: Option<Checkpoint>,
+    /// Re-orgs deeper than this many slots will raise a `ChainAlert`.
+    ///
+    /// If `None`, no re-org alerts are raised.
+    pub reorg_alert_depth: Option<u64>,
+    /// A `ChainAlert` is raised if the finalized epoch falls more than this many epochs behind the
+    /// current epoch.
+    ///
+    /// If `None`, no finality stall alerts are raised.
+    pub finality_stall_alert_epochs: Option<u64>,
+    /// A `ChainAlert` is raised if the justified checkpoint of the head regresses (or changes root
+    /// without advancing) at least this many times within a few epochs.
+    ///
+    /// If `None`, no justification alerts are raised.
+    pub justified_flip_alert_threshold: Option<u64>,
 }
 
 impl Default for ChainConfig {
     fn default() -> Self {
         Self {
             import_max_skip_slots: Some(DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS),
+            weak_subjectivity_checkpoint: None,
+            reorg_alert_depth: Some(DEFAULT_REORG_ALERT_DEPTH),
+            finality_stall_alert_epochs: Some(DEFAULT_FINALITY_STALL_ALERT_EPOCHS),
+            justified_flip_alert_threshold: Some(DEFAULT_JUSTIFIED_FLIP_ALERT_THRESHOLD),
         }
     }
 }
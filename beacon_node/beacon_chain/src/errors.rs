@@ -5,6 +5,8 @@ use crate::naive_aggregation_pool::Error as NaiveAggregationError;
 use crate::observed_attestations::Error as ObservedAttestationsError;
 use crate::observed_attesters::Error as ObservedAttestersError;
 use crate::observed_block_producers::Error as ObservedBlockProducersError;
+use crate::observed_sync_contributors::Error as ObservedSyncContributorsError;
+use crate::sync_contribution_pool::Error as SyncContributionPoolError;
 use operation_pool::OpPoolError;
 use safe_arith::ArithError;
 use ssz_types::Error as SszTypesError;
@@ -63,6 +65,11 @@ pub enum BeaconChainError {
         max_task_runtime: Duration,
     },
     MissingFinalizedStateRoot(Slot),
+    /// Returned when the chain does not include the configured weak subjectivity checkpoint.
+    WeakSubjectivityVerificationFailure {
+        wss_checkpoint: Checkpoint,
+        found_root: Option<Hash256>,
+    },
     /// Returned when an internal check fails, indicating corrupt data.
     InvariantViolated(String),
     SszTypesError(SszTypesError),
@@ -81,6 +88,12 @@ pub enum BeaconChainError {
     ObservedAttestationsError(ObservedAttestationsError),
     ObservedAttestersError(ObservedAttestersError),
     ObservedBlockProducersError(ObservedBlockProducersError),
+    ObservedSyncContributorsError(ObservedSyncContributorsError),
+    SyncContributionPoolError(SyncContributionPoolError),
+    SyncCommitteeContributionError(SyncCommitteeContributionError),
+    SyncAggregateError(SyncAggregateError),
+    /// No sync committee is defined for the given slot (i.e., it is prior to the Altair fork).
+    SyncCommitteeUnavailable(Slot),
     PruningError(PruningError),
     ArithError(ArithError),
 }
@@ -96,6 +109,10 @@ easy_from_to!(NaiveAggregationError, BeaconChainError);
 easy_from_to!(ObservedAttestationsError, BeaconChainError);
 easy_from_to!(ObservedAttestersError, BeaconChainError);
 easy_from_to!(ObservedBlockProducersError, BeaconChainError);
+easy_from_to!(ObservedSyncContributorsError, BeaconChainError);
+easy_from_to!(SyncContributionPoolError, BeaconChainError);
+easy_from_to!(SyncCommitteeContributionError, BeaconChainError);
+easy_from_to!(SyncAggregateError, BeaconChainError);
 easy_from_to!(BlockSignatureVerifierError, BeaconChainError);
 easy_from_to!(PruningError, BeaconChainError);
 easy_from_to!(ArithError, BeaconChainError);
@@ -1,12 +1,13 @@
 use crate::metrics;
 use environment::TaskExecutor;
 use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
+pub use eth1::{EndpointHealth, Eth1Health};
 use eth2_hashing::hash;
 use slog::{debug, error, trace, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use state_processing::per_block_processing::get_new_eth1_data;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::iter::DoubleEndedIterator;
 use std::marker::PhantomData;
@@ -83,6 +84,9 @@ where
     /// When `true`, the backend will be ignored and dummy data from the 2019 Canada interop method
     /// will be used instead.
     use_dummy_backend: bool,
+    /// When `true`, the backend will be ignored and eth1 will not be used at all (see
+    /// `DisabledEth1ChainBackend`).
+    use_disabled_backend: bool,
     _phantom: PhantomData<E>,
 }
 
@@ -95,6 +99,7 @@ where
         Self {
             backend,
             use_dummy_backend: false,
+            use_disabled_backend: false,
             _phantom: PhantomData,
         }
     }
@@ -106,6 +111,13 @@ where
         }
     }
 
+    pub fn new_disabled(backend: T) -> Self {
+        Self {
+            use_disabled_backend: true,
+            ..Self::new(backend)
+        }
+    }
+
     /// Returns the `Eth1Data` that should be included in a block being produced for the given
     /// `state`.
     pub fn eth1_data_for_block_production(
@@ -113,7 +125,10 @@ where
         state: &BeaconState<E>,
         spec: &ChainSpec,
     ) -> Result<Eth1Data, Error> {
-        if self.use_dummy_backend {
+        if self.use_disabled_backend {
+            let disabled_backend: DisabledEth1ChainBackend<E> = DisabledEth1ChainBackend::default();
+            disabled_backend.eth1_data(state, spec)
+        } else if self.use_dummy_backend {
             let dummy_backend: DummyEth1ChainBackend<E> = DummyEth1ChainBackend::default();
             dummy_backend.eth1_data(state, spec)
         } else {
@@ -135,7 +150,10 @@ where
         eth1_data_vote: &Eth1Data,
         spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error> {
-        if self.use_dummy_backend {
+        if self.use_disabled_backend {
+            let disabled_backend: DisabledEth1ChainBackend<E> = DisabledEth1ChainBackend::default();
+            disabled_backend.queued_deposits(state, eth1_data_vote, spec)
+        } else if self.use_dummy_backend {
             let dummy_backend: DummyEth1ChainBackend<E> = DummyEth1ChainBackend::default();
             dummy_backend.queued_deposits(state, eth1_data_vote, spec)
         } else {
@@ -156,6 +174,7 @@ where
             Eth1ChainBackend::from_bytes(&ssz_container.backend_bytes, config, log.clone(), spec)?;
         Ok(Self {
             use_dummy_backend: ssz_container.use_dummy_backend,
+            use_disabled_backend: false,
             backend,
             _phantom: PhantomData,
         })
@@ -169,6 +188,16 @@ where
         }
     }
 
+    /// Returns the health of the eth1 endpoints, or `None` if dummy data is used instead or eth1
+    /// is disabled.
+    pub fn health(&self) -> Option<Eth1Health> {
+        if self.use_dummy_backend || self.use_disabled_backend {
+            None
+        } else {
+            self.backend.health()
+        }
+    }
+
     /// Consumes `self`, returning the backend.
     pub fn into_backend(self) -> T {
         self.backend
@@ -195,6 +224,9 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
         spec: &ChainSpec,
     ) -> Result<Vec<Deposit>, Error>;
 
+    /// Returns the health of the eth1 endpoints used by the backend, if it uses any.
+    fn health(&self) -> Option<Eth1Health>;
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -215,11 +247,10 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
 pub struct DummyEth1ChainBackend<T: EthSpec>(PhantomData<T>);
 
 impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
-    /// Produce some deterministic junk based upon the current epoch.
+    /// Produce some deterministic junk based upon the current voting period.
     fn eth1_data(&self, state: &BeaconState<T>, _spec: &ChainSpec) -> Result<Eth1Data, Error> {
-        let current_epoch = state.current_epoch();
         let slots_per_voting_period = T::slots_per_eth1_voting_period() as u64;
-        let current_voting_period: u64 = current_epoch.as_u64() / slots_per_voting_period;
+        let current_voting_period: u64 = state.slot.as_u64() / slots_per_voting_period;
 
         let deposit_root = hash(&int_to_bytes32(current_voting_period));
         let block_hash = hash(&deposit_root);
@@ -241,6 +272,11 @@ impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
         Ok(vec![])
     }
 
+    /// The dummy back-end does not use any eth1 endpoints.
+    fn health(&self) -> Option<Eth1Health> {
+        None
+    }
+
     /// Return empty Vec<u8> for dummy backend.
     fn as_bytes(&self) -> Vec<u8> {
         Vec::new()
@@ -263,6 +299,63 @@ impl<T: EthSpec> Default for DummyEth1ChainBackend<T> {
     }
 }
 
+/// Provides a testing-only backend which does not use eth1 at all.
+///
+/// Always votes for the existing `state.eth1_data` and never creates deposits, therefore the
+/// validator set is static and the `Eth1Data` never changes after genesis.
+pub struct DisabledEth1ChainBackend<T: EthSpec>(PhantomData<T>);
+
+impl<T: EthSpec> Eth1ChainBackend<T> for DisabledEth1ChainBackend<T> {
+    /// Vote for the `Eth1Data` already in the state.
+    fn eth1_data(&self, state: &BeaconState<T>, _spec: &ChainSpec) -> Result<Eth1Data, Error> {
+        Ok(state.eth1_data.clone())
+    }
+
+    /// The disabled back-end never produces deposits.
+    ///
+    /// Returns an error if the state requires deposits, since a block without them is invalid.
+    fn queued_deposits(
+        &self,
+        state: &BeaconState<T>,
+        _: &Eth1Data,
+        _: &ChainSpec,
+    ) -> Result<Vec<Deposit>, Error> {
+        if state.eth1_deposit_index < state.eth1_data.deposit_count {
+            Err(Error::BackendError(
+                "Eth1 is disabled but the state requires deposits".to_string(),
+            ))
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// The disabled back-end does not use any eth1 endpoints.
+    fn health(&self) -> Option<Eth1Health> {
+        None
+    }
+
+    /// Return empty Vec<u8> for disabled backend.
+    fn as_bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Create disabled eth1 backend.
+    fn from_bytes(
+        _bytes: &[u8],
+        _config: Eth1Config,
+        _log: Logger,
+        _spec: ChainSpec,
+    ) -> Result<Self, String> {
+        Ok(Self(PhantomData))
+    }
+}
+
+impl<T: EthSpec> Default for DisabledEth1ChainBackend<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
 /// Maintains a cache of eth1 blocks and deposits and provides functions to allow block producers
 /// to include new deposits and vote on `Eth1Data`.
 ///
@@ -314,8 +407,12 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
 
         let blocks = self.core.blocks().read();
 
-        let votes_to_consider =
-            get_votes_to_consider(blocks.iter(), voting_period_start_seconds, spec);
+        let votes_to_consider = get_votes_to_consider(
+            blocks.iter(),
+            voting_period_start_seconds,
+            state.eth1_data.deposit_count,
+            spec,
+        );
 
         trace!(
             self.log,
@@ -324,7 +421,28 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         );
         let valid_votes = collect_valid_votes(state, &votes_to_consider);
 
-        let eth1_data = if let Some(eth1_data) = find_winning_vote(valid_votes) {
+        let winning_vote = find_winning_vote(valid_votes, &state.eth1_data_votes);
+        let majority_vote = || {
+            find_majority_vote(state, |block_hash| {
+                blocks.iter().any(|block| block.hash == block_hash)
+            })
+        };
+
+        let eth1_data = if let Some(eth1_data) = winning_vote {
+            eth1_data
+        } else if let Some(eth1_data) = majority_vote() {
+            // In this case, none of the votes in the state are for blocks in our voting window,
+            // however a majority of the votes in this period are for a block which is not in our
+            // cache.
+            //
+            // Our cache is likely missing blocks (e.g., it is still filling or our eth1 node is
+            // behind), so join the majority rather than splitting the vote.
+            debug!(
+                self.log,
+                "No valid eth1_data votes";
+                "outcome" => "Casting vote corresponding to the majority of votes in the state",
+            );
+            metrics::inc_counter(&metrics::MAJORITY_ETH1_VOTES);
             eth1_data
         } else {
             // In this case, there are no valid votes available.
@@ -400,6 +518,10 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         }
     }
 
+    fn health(&self) -> Option<Eth1Health> {
+        Some(self.core.health())
+    }
+
     /// Return encoded byte representation of the block and deposit caches.
     fn as_bytes(&self) -> Vec<u8> {
         self.core.as_bytes()
@@ -424,10 +546,14 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
 /// Get all votes from eth1 blocks which are in the list of candidate blocks for the
 /// current eth1 voting period.
 ///
+/// Votes with a `deposit_count` lower than `state_deposit_count` are excluded, since they would
+/// move the state back to an earlier deposit contract state.
+///
 /// Returns a hashmap of `Eth1Data` to its associated eth1 `block_number`.
 fn get_votes_to_consider<'a, I>(
     blocks: I,
     voting_period_start_seconds: u64,
+    state_deposit_count: u64,
     spec: &ChainSpec,
 ) -> HashMap<Eth1Data, u64>
 where
@@ -443,6 +569,7 @@ where
                 .eth1_data()
                 .map(|eth1_data| (eth1_data, eth1_block.number))
         })
+        .filter(|(eth1_data, _)| eth1_data.deposit_count >= state_deposit_count)
         .collect()
 }
 
@@ -473,13 +600,50 @@ fn collect_valid_votes<T: EthSpec>(
 }
 
 /// Selects the winning vote from `valid_votes`.
-fn find_winning_vote(valid_votes: Eth1DataVoteCount) -> Option<Eth1Data> {
+///
+/// As per the spec, ties are broken in favour of the vote which was first cast in `state_votes`.
+/// Votes which are not in `state_votes` are ordered by their block number.
+fn find_winning_vote(valid_votes: Eth1DataVoteCount, state_votes: &[Eth1Data]) -> Option<Eth1Data> {
+    let first_cast = |eth1_data: &Eth1Data| {
+        state_votes
+            .iter()
+            .position(|vote| vote == eth1_data)
+            .unwrap_or_else(usize::max_value)
+    };
+
     valid_votes
         .iter()
-        .max_by_key(|((_eth1_data, block_number), vote_count)| (*vote_count, block_number))
+        .max_by_key(|((eth1_data, block_number), vote_count)| {
+            (*vote_count, Reverse(first_cast(eth1_data)), *block_number)
+        })
         .map(|((eth1_data, _), _)| eth1_data.clone())
 }
 
+/// Returns the vote which was cast by a strict majority of the blocks in the current voting period,
+/// if its block is unknown to us (i.e., `is_known_block` returns `false`).
+///
+/// Votes which would move the state back to an earlier deposit contract state are ignored.
+fn find_majority_vote<T: EthSpec>(
+    state: &BeaconState<T>,
+    is_known_block: impl Fn(Hash256) -> bool,
+) -> Option<Eth1Data> {
+    let votes_cast = state.eth1_data_votes.len() as u64;
+
+    let mut vote_counts: HashMap<&Eth1Data, u64> = HashMap::new();
+    for vote in state.eth1_data_votes.iter() {
+        *vote_counts.entry(vote).or_default() += 1;
+    }
+
+    vote_counts
+        .into_iter()
+        .find(|(vote, count)| {
+            count * 2 > votes_cast
+                && vote.deposit_count >= state.eth1_data.deposit_count
+                && !is_known_block(vote.block_hash)
+        })
+        .map(|(vote, _)| vote.clone())
+}
+
 /// Returns `int` as little-endian bytes with a length of 32.
 fn int_to_bytes32(int: u64) -> Vec<u8> {
     let mut vec = int.to_le_bytes().to_vec();
@@ -499,14 +663,12 @@ fn slot_start_seconds<T: EthSpec>(
 /// Returns a boolean denoting if a given `Eth1Block` is a candidate for `Eth1Data` calculation
 /// at the timestamp `period_start`.
 ///
-/// Note: `period_start` needs to be atleast (`spec.seconds_per_eth1_block * spec.eth1_follow_distance * 2`)
-/// for this function to return meaningful values.
+/// Blocks which are fewer than `spec.eth1_follow_distance` (or more than twice that many) blocks
+/// of time prior to `period_start` are not candidates.
 fn is_candidate_block(block: &Eth1Block, period_start: u64, spec: &ChainSpec) -> bool {
-    block.timestamp
-        <= period_start.saturating_sub(spec.seconds_per_eth1_block * spec.eth1_follow_distance)
-        && block.timestamp
-            >= period_start
-                .saturating_sub(spec.seconds_per_eth1_block * spec.eth1_follow_distance * 2)
+    let follow_distance_seconds = spec.seconds_per_eth1_block * spec.eth1_follow_distance;
+    block.timestamp.saturating_add(follow_distance_seconds) <= period_start
+        && block.timestamp.saturating_add(follow_distance_seconds * 2) >= period_start
 }
 
 #[cfg(test)]
@@ -794,6 +956,52 @@ mod test {
         }
     }
 
+    mod disabled_backend {
+        use super::*;
+
+        #[test]
+        fn votes_for_state_eth1_data() {
+            let spec = &E::default_spec();
+            let backend: DisabledEth1ChainBackend<E> = DisabledEth1ChainBackend::default();
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), spec);
+            state.eth1_data = get_eth1_data(42);
+
+            assert_eq!(
+                backend
+                    .eth1_data(&state, spec)
+                    .expect("should produce eth1 data"),
+                get_eth1_data(42),
+                "should vote for the state eth1 data"
+            );
+        }
+
+        #[test]
+        fn no_deposits() {
+            let spec = &E::default_spec();
+            let backend: DisabledEth1ChainBackend<E> = DisabledEth1ChainBackend::default();
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), spec);
+            state.eth1_deposit_index = 4;
+            state.eth1_data.deposit_count = 4;
+
+            assert_eq!(
+                backend
+                    .queued_deposits(&state, &state.eth1_data.clone(), spec)
+                    .expect("should succeed if no deposits are required"),
+                vec![]
+            );
+
+            state.eth1_data.deposit_count = 5;
+            assert!(
+                backend
+                    .queued_deposits(&state, &state.eth1_data.clone(), spec)
+                    .is_err(),
+                "should fail if deposits are required"
+            );
+        }
+    }
+
     mod eth1_data_sets {
         use super::*;
 
@@ -808,6 +1016,7 @@ mod test {
                 get_votes_to_consider(
                     blocks.iter(),
                     get_voting_period_start_seconds(&state, spec),
+                    state.eth1_data.deposit_count,
                     &spec,
                 ),
                 HashMap::new()
@@ -833,8 +1042,12 @@ mod test {
                 .map(|i| get_eth1_block(i, i))
                 .collect::<Vec<_>>();
 
-            let votes_to_consider =
-                get_votes_to_consider(blocks.iter(), voting_period_start, &spec);
+            let votes_to_consider = get_votes_to_consider(
+                blocks.iter(),
+                voting_period_start,
+                state.eth1_data.deposit_count,
+                &spec,
+            );
             assert_eq!(
                 votes_to_consider.len() as u64,
                 end_eth1_block - start_eth1_block,
@@ -852,6 +1065,47 @@ mod test {
                     )
                 });
         }
+
+        #[test]
+        fn earlier_deposit_count() {
+            let spec = E::default_spec();
+
+            let slots_per_eth1_voting_period = <E as EthSpec>::SlotsPerEth1VotingPeriod::to_u64();
+            let eth1_follow_distance = spec.eth1_follow_distance;
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), &spec);
+            state.genesis_time = 0;
+            state.slot = Slot::from(slots_per_eth1_voting_period * 10);
+
+            let follow_distance_seconds = eth1_follow_distance * spec.seconds_per_eth1_block;
+            let voting_period_start = get_voting_period_start_seconds(&state, &spec);
+            let start_eth1_block = voting_period_start - follow_distance_seconds * 2;
+            let end_eth1_block = voting_period_start - follow_distance_seconds;
+            let blocks = (start_eth1_block..end_eth1_block)
+                .map(|i| get_eth1_block(i, i))
+                .collect::<Vec<_>>();
+
+            let middle_eth1_block = (start_eth1_block + end_eth1_block) / 2;
+            state.eth1_data.deposit_count = middle_eth1_block;
+
+            let votes_to_consider = get_votes_to_consider(
+                blocks.iter(),
+                voting_period_start,
+                state.eth1_data.deposit_count,
+                &spec,
+            );
+            assert_eq!(
+                votes_to_consider.len() as u64,
+                end_eth1_block - middle_eth1_block,
+                "only blocks with at least the state deposit count should be considered"
+            );
+            assert!(
+                votes_to_consider
+                    .keys()
+                    .all(|eth1_data| eth1_data.deposit_count >= middle_eth1_block),
+                "should not consider votes with a lower deposit count"
+            );
+        }
     }
 
     mod collect_valid_votes {
@@ -995,7 +1249,7 @@ mod test {
             assert_eq!(
                 // Favour the highest block number when there are no votes.
                 vote_data(&no_votes[2]),
-                find_winning_vote(Eth1DataVoteCount::from_iter(no_votes.into_iter()))
+                find_winning_vote(Eth1DataVoteCount::from_iter(no_votes.into_iter()), &[])
                     .expect("should find winner")
             );
         }
@@ -1007,7 +1261,7 @@ mod test {
             assert_eq!(
                 // Favour the highest block number when there are equal votes.
                 vote_data(&votes[2]),
-                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()))
+                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()), &[])
                     .expect("should find winner")
             );
         }
@@ -1019,7 +1273,7 @@ mod test {
             assert_eq!(
                 // Favour the highest vote over the highest block number.
                 vote_data(&votes[3]),
-                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()))
+                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()), &[])
                     .expect("should find winner")
             );
         }
@@ -1031,11 +1285,31 @@ mod test {
             assert_eq!(
                 // Favour the highest block number for tying votes.
                 vote_data(&votes[3]),
-                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()))
+                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()), &[])
                     .expect("should find winner")
             );
         }
 
+        #[test]
+        fn tying_votes_in_state() {
+            let votes = vec![vote(0, 0), vote(1, 2), vote(2, 2), vote(3, 2)];
+            let state_votes = vec![
+                vote_data(&votes[2]),
+                vote_data(&votes[3]),
+                vote_data(&votes[1]),
+            ];
+
+            assert_eq!(
+                // Favour the vote which was cast first for tying votes.
+                vote_data(&votes[2]),
+                find_winning_vote(
+                    Eth1DataVoteCount::from_iter(votes.into_iter()),
+                    &state_votes
+                )
+                .expect("should find winner")
+            );
+        }
+
         #[test]
         fn all_tying_votes() {
             let votes = vec![vote(3, 42), vote(2, 42), vote(1, 42), vote(0, 42)];
@@ -1043,9 +1317,56 @@ mod test {
             assert_eq!(
                 // Favour the highest block number for tying votes.
                 vote_data(&votes[0]),
-                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()))
+                find_winning_vote(Eth1DataVoteCount::from_iter(votes.into_iter()), &[])
                     .expect("should find winner")
             );
         }
     }
+
+    mod majority_vote {
+        use super::*;
+
+        fn state_with_votes(votes: Vec<Eth1Data>) -> BeaconState<E> {
+            let spec = &E::default_spec();
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(1), spec);
+            state.eth1_data_votes = votes.into();
+            state
+        }
+
+        #[test]
+        fn unknown_majority() {
+            let state =
+                state_with_votes(vec![get_eth1_data(2), get_eth1_data(3), get_eth1_data(2)]);
+
+            assert_eq!(
+                find_majority_vote(&state, |_| false),
+                Some(get_eth1_data(2)),
+                "should find the majority vote"
+            );
+            assert_eq!(
+                find_majority_vote(&state, |_| true),
+                None,
+                "should ignore the majority vote if its block is known"
+            );
+        }
+
+        #[test]
+        fn no_majority() {
+            let state = state_with_votes(vec![get_eth1_data(2), get_eth1_data(3)]);
+            assert_eq!(find_majority_vote(&state, |_| false), None);
+
+            let state = state_with_votes(vec![]);
+            assert_eq!(find_majority_vote(&state, |_| false), None);
+        }
+
+        #[test]
+        fn earlier_deposit_count() {
+            let state = state_with_votes(vec![get_eth1_data(0), get_eth1_data(0)]);
+            assert_eq!(
+                find_majority_vote(&state, |_| false),
+                None,
+                "should not move back to an earlier deposit count"
+            );
+        }
+    }
 }
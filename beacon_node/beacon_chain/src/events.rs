@@ -1,3 +1,4 @@
+use crate::chain_alerts::ChainAlert;
 use bus::Bus;
 use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
@@ -144,4 +145,7 @@ pub enum EventKind<T: EthSpec> {
         reason: String,
         attestation: Box<Attestation<T>>,
     },
+    ChainAlert {
+        alert: ChainAlert,
+    },
 }
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use types::{Epoch, Slot};
+
+/// The number of epochs for which late blocks are remembered when determining if a proposer is
+/// consistently late.
+const RETAINED_EPOCHS: u64 = 256;
+
+/// The number of late blocks within `RETAINED_EPOCHS` after which a proposer is considered to be
+/// consistently late.
+pub const CONSISTENTLY_LATE_THRESHOLD: usize = 3;
+
+/// Maintains a record of the slots at which each proposer has published a late block.
+///
+/// Used to identify proposers that are *consistently* late (rather than just suffering an
+/// occasional network delay), since these are a common cause of missed attestations on nodes
+/// that attest to their blocks.
+#[derive(Default)]
+pub struct LateBlockTracker {
+    late_slots: HashMap<u64, Vec<Slot>>,
+}
+
+impl LateBlockTracker {
+    /// Record that `proposer_index` published a late block at `slot`.
+    ///
+    /// Returns the number of late blocks the proposer has published within the retained epochs,
+    /// including this one.
+    pub fn register_late_block(
+        &mut self,
+        proposer_index: u64,
+        slot: Slot,
+        slots_per_epoch: u64,
+    ) -> usize {
+        let lowest_permissible_slot =
+            (slot.epoch(slots_per_epoch) - RETAINED_EPOCHS).start_slot(slots_per_epoch);
+
+        let slots = self
+            .late_slots
+            .entry(proposer_index)
+            .or_insert_with(Vec::new);
+        slots.retain(|late_slot| *late_slot >= lowest_permissible_slot);
+        if !slots.contains(&slot) {
+            slots.push(slot);
+        }
+
+        slots.len()
+    }
+
+    /// Removes any records of late blocks prior to `RETAINED_EPOCHS` before `current_epoch`.
+    pub fn prune(&mut self, current_epoch: Epoch, slots_per_epoch: u64) {
+        let lowest_permissible_slot = (current_epoch - RETAINED_EPOCHS).start_slot(slots_per_epoch);
+
+        self.late_slots.retain(|_, slots| {
+            slots.retain(|late_slot| *late_slot >= lowest_permissible_slot);
+            !slots.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOTS_PER_EPOCH: u64 = 32;
+
+    #[test]
+    fn counts_late_blocks_per_proposer() {
+        let mut tracker = LateBlockTracker::default();
+
+        assert_eq!(
+            tracker.register_late_block(1, Slot::new(1), SLOTS_PER_EPOCH),
+            1
+        );
+        assert_eq!(
+            tracker.register_late_block(1, Slot::new(1), SLOTS_PER_EPOCH),
+            1
+        );
+        assert_eq!(
+            tracker.register_late_block(2, Slot::new(2), SLOTS_PER_EPOCH),
+            1
+        );
+        assert_eq!(
+            tracker.register_late_block(1, Slot::new(3), SLOTS_PER_EPOCH),
+            2
+        );
+    }
+
+    #[test]
+    fn forgets_old_late_blocks() {
+        let mut tracker = LateBlockTracker::default();
+        let later_slot = Epoch::new(RETAINED_EPOCHS + 1).start_slot(SLOTS_PER_EPOCH);
+
+        tracker.register_late_block(1, Slot::new(1), SLOTS_PER_EPOCH);
+        assert_eq!(
+            tracker.register_late_block(1, later_slot, SLOTS_PER_EPOCH),
+            1
+        );
+
+        tracker.prune(Epoch::new(RETAINED_EPOCHS * 3), SLOTS_PER_EPOCH);
+        assert!(tracker.late_slots.is_empty());
+    }
+}
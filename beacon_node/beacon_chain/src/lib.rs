@@ -9,14 +9,18 @@ extern crate slog_term;
 pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_fork_choice_store;
+mod beacon_proposer_cache;
 mod beacon_snapshot;
 mod block_verification;
 pub mod builder;
+pub mod chain_alerts;
 pub mod chain_config;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
 mod head_tracker;
+mod late_block_tracker;
+mod light_client_update_cache;
 mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
@@ -24,17 +28,21 @@ mod observed_attestations;
 mod observed_attesters;
 mod observed_block_producers;
 pub mod observed_operations;
+mod observed_sync_contributors;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
 mod shuffling_cache;
+mod skip_slot_state_cache;
 mod snapshot_cache;
+pub mod sync_committee_verification;
+mod sync_contribution_pool;
 pub mod test_utils;
 mod timeout_rw_lock;
 mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    ForkChoiceError, StateSkipConfig,
+    ForkChoiceError, SlasherStream, StateSkipConfig, SLASHER_STREAM_CAPACITY,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::chain_config::ChainConfig;
@@ -52,4 +60,5 @@ pub use state_processing::per_block_processing::errors::{
     ExitValidationError, ProposerSlashingValidationError,
 };
 pub use store;
+pub use sync_committee_verification::Error as SyncCommitteeError;
 pub use types;
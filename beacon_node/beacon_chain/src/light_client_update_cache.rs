@@ -0,0 +1,140 @@
+use safe_arith::ArithError;
+use ssz::{Decode, Encode};
+use std::collections::HashMap;
+use store::{DBColumn, Error as StoreError, StoreItem};
+use types::{ChainSpec, EthSpec, LightClientUpdate};
+
+/// The number of sync committee periods for which updates are held in memory.
+///
+/// Older periods are served from the database.
+const PERIODS_RETAINED: u64 = 2;
+
+/// A `LightClientUpdate` wrapper which may be stored in the database, keyed by its sync committee
+/// period.
+pub struct PersistedLightClientUpdate<E: EthSpec>(pub LightClientUpdate<E>);
+
+impl<E: EthSpec> StoreItem for PersistedLightClientUpdate<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::LightClientUpdate
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        LightClientUpdate::from_ssz_bytes(bytes)
+            .map(Self)
+            .map_err(Into::into)
+    }
+}
+
+/// Tracks the best `LightClientUpdate` seen for each recent sync committee period.
+///
+/// "Best" is defined by `LightClientUpdate::is_better_than`.
+pub struct LightClientUpdateCache<E: EthSpec> {
+    best_updates: HashMap<u64, LightClientUpdate<E>>,
+}
+
+impl<E: EthSpec> Default for LightClientUpdateCache<E> {
+    fn default() -> Self {
+        Self {
+            best_updates: HashMap::new(),
+        }
+    }
+}
+
+impl<E: EthSpec> LightClientUpdateCache<E> {
+    /// Offers `update` to the cache, returning `Ok(Some(period))` if it became the best known
+    /// update for its sync committee period.
+    ///
+    /// `current_period` is used to prune updates from periods that are no longer retained.
+    pub fn insert(
+        &mut self,
+        update: LightClientUpdate<E>,
+        current_period: u64,
+        spec: &ChainSpec,
+    ) -> Result<Option<u64>, ArithError> {
+        let period = update.sync_committee_period(spec)?;
+        let lowest_permissible_period = current_period.saturating_sub(PERIODS_RETAINED - 1);
+
+        self.best_updates
+            .retain(|period, _| *period >= lowest_permissible_period);
+
+        if period < lowest_permissible_period {
+            return Ok(None);
+        }
+
+        let is_best = self
+            .best_updates
+            .get(&period)
+            .map_or(true, |existing| update.is_better_than(existing));
+
+        if is_best {
+            self.best_updates.insert(period, update);
+            Ok(Some(period))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the best known update for `period`, if it is held in memory.
+    pub fn get(&self, period: u64) -> Option<LightClientUpdate<E>> {
+        self.best_updates.get(&period).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{test_utils::test_random_instance, BeaconBlockHeader, Slot};
+
+    type E = types::MinimalEthSpec;
+
+    fn get_update(slot: u64, participants: usize) -> LightClientUpdate<E> {
+        let mut update: LightClientUpdate<E> = test_random_instance();
+        update.header.slot = Slot::new(slot);
+        update.finalized_header = BeaconBlockHeader::default();
+        update.sync_committee_aggregate = types::SyncAggregate::new();
+        for i in 0..participants {
+            update
+                .sync_committee_aggregate
+                .sync_committee_bits
+                .set(i, true)
+                .unwrap();
+        }
+        update
+    }
+
+    #[test]
+    fn keeps_best_update_per_period() {
+        let spec = E::default_spec();
+        let mut cache = LightClientUpdateCache::<E>::default();
+
+        assert_eq!(cache.insert(get_update(1, 4), 0, &spec), Ok(Some(0)));
+        assert_eq!(cache.insert(get_update(2, 2), 0, &spec), Ok(None));
+        assert_eq!(cache.insert(get_update(3, 8), 0, &spec), Ok(Some(0)));
+        assert_eq!(cache.get(0).map(|u| u.header.slot), Some(Slot::new(3)));
+
+        let mut finalized = get_update(4, 1);
+        finalized.finalized_header.slot = Slot::new(1);
+        assert_eq!(cache.insert(finalized, 0, &spec), Ok(Some(0)));
+        assert_eq!(cache.get(0).map(|u| u.header.slot), Some(Slot::new(4)));
+    }
+
+    #[test]
+    fn prunes_old_periods() {
+        let spec = E::default_spec();
+        let mut cache = LightClientUpdateCache::<E>::default();
+        let slots_per_period =
+            spec.epochs_per_sync_committee_period.as_u64() * E::slots_per_epoch();
+
+        assert_eq!(cache.insert(get_update(1, 1), 0, &spec), Ok(Some(0)));
+        assert_eq!(
+            cache.insert(get_update(slots_per_period * 2, 1), 2, &spec),
+            Ok(Some(2))
+        );
+        assert_eq!(cache.get(0), None);
+        assert_eq!(cache.insert(get_update(1, 2), 2, &spec), Ok(None));
+    }
+}
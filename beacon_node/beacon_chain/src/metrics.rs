@@ -54,6 +54,31 @@ lazy_static! {
         "Time spent hashing and remembering all the attestations in the block"
     );
 
+    /*
+     * Block Arrival
+     */
+    pub static ref BLOCK_GOSSIP_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_slot_start_delay_time",
+        "Duration between the start of the block's slot and the time the block was received via gossip"
+    );
+    pub static ref BLOCK_GOSSIP_LATE_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_gossip_late_total",
+        "Count of gossip blocks that arrived after the attestation deadline of their slot"
+    );
+
+    /*
+     * Chain Alerts
+     */
+    pub static ref CHAIN_ALERTS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_chain_alerts_total",
+        "Count of anomalous consensus conditions detected, by type of alert",
+        &["alert"]
+    );
+    pub static ref EPOCHS_SINCE_FINALIZATION: Result<IntGauge> = try_create_int_gauge(
+        "beacon_epochs_since_finalization",
+        "The number of epochs between the current epoch and the finalized epoch of the head"
+    );
+
     /*
      * Block Production
      */
@@ -67,6 +92,18 @@ lazy_static! {
     );
     pub static ref BLOCK_PRODUCTION_TIMES: Result<Histogram> =
         try_create_histogram("beacon_block_production_seconds", "Full runtime of block production");
+    pub static ref BLOCK_PRODUCTION_PACKING_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_production_packing_seconds",
+        "Time spent selecting the operations and eth1 data to pack into a block"
+    );
+    pub static ref BLOCK_PRODUCTION_ATTESTATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_production_attestation_seconds",
+        "Time spent selecting attestations for inclusion in a block"
+    );
+    pub static ref BLOCK_PRODUCTION_ETH1_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_production_eth1_seconds",
+        "Time spent computing the eth1 vote and deposits for inclusion in a block"
+    );
 
     /*
      * Block Statistics
@@ -108,6 +145,38 @@ lazy_static! {
         "Full runtime of aggregated attestation gossip verification"
     );
 
+    /*
+     * Sync Committee Message Verification
+     */
+    pub static ref SYNC_MESSAGE_PROCESSING_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_sync_committee_message_processing_requests_total",
+        "Count of all sync committee messages submitted for processing"
+    );
+    pub static ref SYNC_MESSAGE_PROCESSING_SUCCESSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_sync_committee_message_processing_successes_total",
+        "Number of sync committee messages verified for gossip"
+    );
+    pub static ref SYNC_MESSAGE_GOSSIP_VERIFICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_gossip_verification_seconds",
+        "Full runtime of sync committee message gossip verification"
+    );
+
+    /*
+     * Sync Committee Contribution Verification
+     */
+    pub static ref SYNC_CONTRIBUTION_PROCESSING_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_sync_contribution_processing_requests_total",
+        "Count of all sync committee contributions submitted for processing"
+    );
+    pub static ref SYNC_CONTRIBUTION_PROCESSING_SUCCESSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_sync_contribution_processing_successes_total",
+        "Number of sync committee contributions verified for gossip"
+    );
+    pub static ref SYNC_CONTRIBUTION_GOSSIP_VERIFICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_contribution_gossip_verification_seconds",
+        "Full runtime of sync committee contribution gossip verification"
+    );
+
     /*
      * General Attestation Processing
      */
@@ -180,6 +249,28 @@ lazy_static! {
     pub static ref SHUFFLING_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_misses_total", "Count of times shuffling cache fulfils request");
 
+    /*
+     * Skip-slot state cache
+     */
+    pub static ref SKIP_SLOT_STATE_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_skip_slot_state_cache_hits_total", "Count of times the skip-slot state cache fulfils request");
+    pub static ref SKIP_SLOT_STATE_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_skip_slot_state_cache_misses_total", "Count of times the skip-slot state cache does not fulfil request");
+
+    /*
+     * Beacon proposer cache
+     */
+    pub static ref BEACON_PROPOSER_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_proposer_cache_hits_total", "Count of times the beacon proposer cache fulfils request");
+    pub static ref BEACON_PROPOSER_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_proposer_cache_misses_total", "Count of times the beacon proposer cache does not fulfil request");
+
+    /*
+     * Slasher stream
+     */
+    pub static ref SLASHER_STREAM_EVENTS_DROPPED: Result<IntCounter> =
+        try_create_int_counter("beacon_slasher_stream_events_dropped_total", "Count of slasher events dropped because the queue of a remote slasher was full");
+
     /*
      * Attestation Production
      */
@@ -218,6 +309,10 @@ lazy_static! {
         "beacon_fork_choice_reorg_total",
         "Count of occasions fork choice has switched to a different chain"
     );
+    pub static ref FORK_CHOICE_REORG_DEPTH: Result<IntGauge> = try_create_int_gauge(
+        "beacon_fork_choice_reorg_depth",
+        "The distance in slots between the previous head and the common ancestor of the most recent re-org"
+    );
     pub static ref FORK_CHOICE_TIMES: Result<Histogram> =
         try_create_histogram("beacon_fork_choice_seconds", "Full runtime of fork choice");
     pub static ref FORK_CHOICE_FIND_HEAD_TIMES: Result<Histogram> =
@@ -247,11 +342,25 @@ lazy_static! {
     pub static ref PERSIST_FORK_CHOICE: Result<Histogram> =
         try_create_histogram("beacon_persist_fork_choice", "Time taken to persist the fork choice struct");
 
+    /*
+     * Database Pruning
+     */
+    pub static ref PRUNING_TIMES: Result<Histogram> =
+        try_create_histogram("beacon_pruning_seconds", "Time taken to prune abandoned forks");
+    pub static ref PRUNED_BLOCKS_TOTAL: Result<IntCounter> =
+        try_create_int_counter("beacon_pruned_blocks_total", "Count of blocks deleted from abandoned forks");
+    pub static ref PRUNED_STATES_TOTAL: Result<IntCounter> =
+        try_create_int_counter("beacon_pruned_states_total", "Count of states deleted from abandoned forks");
+    pub static ref PRUNED_HEADS_TOTAL: Result<IntCounter> =
+        try_create_int_counter("beacon_pruned_heads_total", "Count of heads removed from the head tracker by pruning");
+
     /*
      * Eth1
      */
     pub static ref DEFAULT_ETH1_VOTES: Result<IntCounter> =
         try_create_int_counter("beacon_eth1_default_votes", "Count of times we have voted default value for eth1 data");
+    pub static ref MAJORITY_ETH1_VOTES: Result<IntCounter> =
+        try_create_int_counter("beacon_eth1_majority_votes", "Count of times we have voted for a majority eth1 data vote which is not in our cache");
 
     /*
      * Chain Head
@@ -333,7 +442,7 @@ lazy_static! {
 /// Scrape the `beacon_chain` for metrics that are not constantly updated (e.g., the present slot,
 /// head state info, etc) and update the Prometheus `DEFAULT_REGISTRY`.
 pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
-    if let Ok(head) = beacon_chain.head() {
+    if let Ok(head) = beacon_chain.cached_head() {
         scrape_head_state::<T>(&head.beacon_state, head.beacon_state_root)
     }
 
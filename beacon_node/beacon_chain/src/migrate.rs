@@ -1,7 +1,8 @@
 use crate::errors::BeaconChainError;
 use crate::head_tracker::HeadTracker;
+use crate::metrics;
 use parking_lot::Mutex;
-use slog::{debug, warn, Logger};
+use slog::{debug, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::sync::mpsc;
@@ -12,10 +13,15 @@ use store::iter::RootsIterator;
 use store::{Error, ItemStore, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
-    BeaconState, BeaconStateError, BeaconStateHash, Checkpoint, EthSpec, Hash256,
-    SignedBeaconBlockHash, Slot,
+    BeaconState, BeaconStateHash, Checkpoint, EthSpec, Hash256, SignedBeaconBlockHash, Slot,
 };
 
+/// Compact the database each time the finalized epoch crosses a multiple of this value.
+///
+/// Compaction is relatively expensive, so it is only run after a significant amount of data has
+/// been pruned or migrated to the freezer.
+const COMPACTION_PERIOD_EPOCHS: u64 = 256;
+
 /// Logic errors that can occur during pruning, none of these should ever happen.
 #[derive(Debug)]
 pub enum PruningError {
@@ -67,6 +73,8 @@ pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
             return Ok(());
         }
 
+        let _timer = metrics::start_timer(&metrics::PRUNING_TIMES);
+
         let old_finalized_slot = old_finalized_checkpoint
             .epoch
             .start_slot(E::slots_per_epoch());
@@ -124,10 +132,21 @@ pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
             let mut potentially_abandoned_head = Some(head_hash);
             let mut potentially_abandoned_blocks = vec![];
 
-            let head_state_hash = store
-                .get_block(&head_hash)?
-                .ok_or_else(|| BeaconStateError::MissingBeaconBlock(head_hash.into()))?
-                .state_root();
+            let head_state_hash = if let Some(head_block) = store.get_block(&head_hash)? {
+                head_block.state_root()
+            } else {
+                // The head's block has already been deleted, e.g. because the node shut down
+                // uncleanly after a previous pruning and the head tracker wasn't persisted.
+                // Remove the dangling head, rather than failing to prune forever.
+                warn!(
+                    log,
+                    "Removing head with missing block";
+                    "head_block_root" => format!("{:?}", head_hash),
+                    "head_slot" => head_slot,
+                );
+                abandoned_heads.insert(head_hash);
+                continue;
+            };
 
             // Iterate backwards from this head, staging blocks and states for deletion.
             let iter = std::iter::once(Ok((head_hash, head_state_hash, head_slot)))
@@ -229,6 +248,18 @@ pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
             }
         }
 
+        metrics::inc_counter_by(&metrics::PRUNED_BLOCKS_TOTAL, abandoned_blocks.len() as i64);
+        metrics::inc_counter_by(&metrics::PRUNED_STATES_TOTAL, abandoned_states.len() as i64);
+        metrics::inc_counter_by(&metrics::PRUNED_HEADS_TOTAL, abandoned_heads.len() as i64);
+
+        debug!(
+            log,
+            "Pruning abandoned forks";
+            "blocks" => abandoned_blocks.len(),
+            "states" => abandoned_states.len(),
+            "heads" => abandoned_heads.len(),
+        );
+
         let batch: Vec<StoreOp<E>> = abandoned_blocks
             .into_iter()
             .map(StoreOp::DeleteBlock)
@@ -250,6 +281,34 @@ pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
     }
 }
 
+/// Compact the database if the finalized epoch has crossed a multiple of
+/// `COMPACTION_PERIOD_EPOCHS`, so that the space used by deleted blocks and states is reclaimed.
+///
+/// LevelDB does not free the space used by deleted keys until the range they lie in is compacted.
+fn run_compaction<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: &HotColdDB<E, Hot, Cold>,
+    old_finalized_checkpoint: Checkpoint,
+    new_finalized_checkpoint: Checkpoint,
+    log: &Logger,
+) -> Result<(), Error> {
+    let old_period = old_finalized_checkpoint.epoch.as_u64() / COMPACTION_PERIOD_EPOCHS;
+    let new_period = new_finalized_checkpoint.epoch.as_u64() / COMPACTION_PERIOD_EPOCHS;
+
+    if !db.get_config().compact_on_prune || old_period == new_period {
+        return Ok(());
+    }
+
+    info!(
+        log,
+        "Starting database compaction";
+        "finalized_epoch" => new_finalized_checkpoint.epoch,
+    );
+    db.compact()?;
+    info!(log, "Database compaction complete");
+
+    Ok(())
+}
+
 /// Migrator that does nothing, for stores that don't need migration.
 pub struct NullMigrator;
 
@@ -308,7 +367,13 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> Migrate<E, Hot, Cold>
             finalized_state_root.into(),
             &new_finalized_state,
         ) {
-            Ok(()) => Ok(()),
+            Ok(()) => run_compaction(
+                &self.db,
+                old_finalized_checkpoint,
+                new_finalized_checkpoint,
+                &self.log,
+            )
+            .map_err(Into::into),
             Err(Error::HotColdDBError(HotColdDBError::FreezeSlotUnaligned(slot))) => {
                 debug!(
                     self.log,
@@ -417,7 +482,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 }
 
                 match migrate_database(db.clone(), state_root.into(), &state) {
-                    Ok(()) => {}
+                    Ok(()) => {
+                        if let Err(e) = run_compaction(
+                            &db,
+                            old_finalized_checkpoint,
+                            new_finalized_checkpoint,
+                            &log,
+                        ) {
+                            warn!(
+                                log,
+                                "Database compaction failed";
+                                "error" => format!("{:?}", e)
+                            );
+                        }
+                    }
                     Err(Error::HotColdDBError(HotColdDBError::FreezeSlotUnaligned(slot))) => {
                         debug!(
                             log,
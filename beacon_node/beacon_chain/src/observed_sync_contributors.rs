@@ -0,0 +1,172 @@
+//! Provides two structs that help us filter out sync committee gossip from validators that have
+//! already published messages:
+//!
+//! - `ObservedSyncContributors`: allows filtering `SyncCommitteeMessage`s from the same validator
+//!   in the same slot and subcommittee.
+//! - `ObservedSyncAggregators`: allows filtering `SignedContributionAndProof`s from the same
+//!   aggregator in the same slot and subcommittee.
+
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use types::{EthSpec, Slot, Unsigned};
+
+pub type ObservedSyncContributors<E> = SlotSubcommitteeCache<E>;
+pub type ObservedSyncAggregators<E> = SlotSubcommitteeCache<E>;
+
+/// The number of slots retained by the cache.
+///
+/// The current slot and the previous slot are sufficient whilst `MAXIMUM_GOSSIP_CLOCK_DISPARITY`
+/// is less than a slot.
+const SLOTS_RETAINED: u64 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    SlotTooLow {
+        slot: Slot,
+        lowest_permissible_slot: Slot,
+    },
+    /// The validator index is higher than `VALIDATOR_REGISTRY_LIMIT`.
+    ValidatorIndexTooHigh(usize),
+}
+
+/// Stores the validator indices observed for each `(slot, subcommittee_index)` pair, pruning slots
+/// that are older than `SLOTS_RETAINED`.
+pub struct SlotSubcommitteeCache<E: EthSpec> {
+    lowest_permissible_slot: RwLock<Slot>,
+    items: RwLock<HashMap<(Slot, u64), HashSet<usize>>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Default for SlotSubcommitteeCache<E> {
+    fn default() -> Self {
+        Self {
+            lowest_permissible_slot: RwLock::new(Slot::new(0)),
+            items: RwLock::new(HashMap::new()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> SlotSubcommitteeCache<E> {
+    /// Observe that `validator_index` has produced a message for `slot` and `subcommittee_index`.
+    /// Returns `Ok(true)` if the validator had already been observed.
+    pub fn observe_validator(
+        &self,
+        slot: Slot,
+        subcommittee_index: u64,
+        validator_index: usize,
+    ) -> Result<bool, Error> {
+        self.sanitize_request(slot, validator_index)?;
+
+        self.prune(slot);
+
+        let already_known = !self
+            .items
+            .write()
+            .entry((slot, subcommittee_index))
+            .or_insert_with(HashSet::new)
+            .insert(validator_index);
+
+        Ok(already_known)
+    }
+
+    /// Returns `Ok(true)` if `validator_index` has already produced a message for `slot` and
+    /// `subcommittee_index`.
+    pub fn validator_has_been_observed(
+        &self,
+        slot: Slot,
+        subcommittee_index: u64,
+        validator_index: usize,
+    ) -> Result<bool, Error> {
+        self.sanitize_request(slot, validator_index)?;
+
+        let exists = self
+            .items
+            .read()
+            .get(&(slot, subcommittee_index))
+            .map_or(false, |set| set.contains(&validator_index));
+
+        Ok(exists)
+    }
+
+    fn sanitize_request(&self, slot: Slot, validator_index: usize) -> Result<(), Error> {
+        if validator_index > E::ValidatorRegistryLimit::to_usize() {
+            return Err(Error::ValidatorIndexTooHigh(validator_index));
+        }
+
+        let lowest_permissible_slot = *self.lowest_permissible_slot.read();
+        if slot < lowest_permissible_slot {
+            return Err(Error::SlotTooLow {
+                slot,
+                lowest_permissible_slot,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes all entries that are older than `SLOTS_RETAINED` slots prior to `current_slot`.
+    pub fn prune(&self, current_slot: Slot) {
+        // Taking advantage of saturating subtraction on `Slot`.
+        let lowest_permissible_slot = current_slot - (SLOTS_RETAINED - 1);
+
+        {
+            let mut lowest = self.lowest_permissible_slot.write();
+            if *lowest >= lowest_permissible_slot {
+                return;
+            }
+            *lowest = lowest_permissible_slot;
+        }
+
+        self.items
+            .write()
+            .retain(|(slot, _), _| *slot >= lowest_permissible_slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type E = types::MainnetEthSpec;
+
+    #[test]
+    fn observe_and_prune() {
+        let cache = SlotSubcommitteeCache::<E>::default();
+
+        assert_eq!(cache.observe_validator(Slot::new(1), 0, 42), Ok(false));
+        assert_eq!(cache.observe_validator(Slot::new(1), 0, 42), Ok(true));
+        assert_eq!(
+            cache.validator_has_been_observed(Slot::new(1), 1, 42),
+            Ok(false),
+            "different subcommittee should not be observed"
+        );
+        assert_eq!(cache.observe_validator(Slot::new(2), 0, 42), Ok(false));
+
+        // Moving to slot 3 should prune slot 1.
+        assert_eq!(cache.observe_validator(Slot::new(3), 0, 42), Ok(false));
+        assert_eq!(
+            cache.validator_has_been_observed(Slot::new(1), 0, 42),
+            Err(Error::SlotTooLow {
+                slot: Slot::new(1),
+                lowest_permissible_slot: Slot::new(2),
+            })
+        );
+        assert_eq!(
+            cache.validator_has_been_observed(Slot::new(2), 0, 42),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn validator_index_too_high() {
+        let cache = SlotSubcommitteeCache::<E>::default();
+        let index = <E as EthSpec>::ValidatorRegistryLimit::to_usize() + 1;
+
+        assert_eq!(
+            cache.observe_validator(Slot::new(0), 0, index),
+            Err(Error::ValidatorIndexTooHigh(index))
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::metrics;
+use lru::LruCache;
+use types::{BeaconState, CloneConfig, EthSpec, Hash256, Slot};
+
+/// The size of the LRU cache that stores states which have been advanced through skip slots.
+///
+/// States are large, so only a handful are kept. In practice the only states that are requested
+/// repeatedly are those at the next epoch boundary of the head (or of a recent target), so a small
+/// cache is sufficient.
+const CACHE_SIZE: usize = 4;
+
+/// Provides an LRU cache of states which have been advanced through empty slots.
+///
+/// Entries are keyed by `(state_root, slot)`, where `state_root` is the root of the state *before*
+/// it was advanced and `slot` is the slot it was advanced to.
+///
+/// ## Notes
+///
+/// States are advanced without computing state roots (i.e., by supplying `Hash256::zero()` to
+/// `per_slot_processing`), so the cached states are only suitable for shuffling and duty queries.
+/// They must never be used for block production or imported into the database.
+pub struct SkipSlotStateCache<E: EthSpec> {
+    cache: LruCache<(Hash256, Slot), BeaconState<E>>,
+}
+
+impl<E: EthSpec> SkipSlotStateCache<E> {
+    pub fn new() -> Self {
+        Self {
+            cache: LruCache::new(CACHE_SIZE),
+        }
+    }
+
+    /// Returns a clone of the state produced by advancing the state with `state_root` to `slot`,
+    /// if it is known.
+    pub fn get(&mut self, state_root: Hash256, slot: Slot) -> Option<BeaconState<E>> {
+        let opt = self
+            .cache
+            .get(&(state_root, slot))
+            .map(|state| state.clone_with(CloneConfig::committee_caches_only()));
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::SKIP_SLOT_STATE_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::SKIP_SLOT_STATE_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    /// Stores `state`, which was produced by advancing the state with `state_root` through skip
+    /// slots to `state.slot`.
+    pub fn insert(&mut self, state_root: Hash256, state: &BeaconState<E>) {
+        let key = (state_root, state.slot);
+
+        if !self.cache.contains(&key) {
+            self.cache
+                .put(key, state.clone_with(CloneConfig::committee_caches_only()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+    use types::{EthSpec, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn insert_and_get() {
+        let spec = E::default_spec();
+        let (mut state, _keypairs) =
+            TestingBeaconStateBuilder::<E>::from_deterministic_keypairs(8, &spec).build();
+        let root = Hash256::repeat_byte(1);
+        state.slot = Slot::new(8);
+
+        let mut cache = SkipSlotStateCache::<E>::new();
+        assert!(cache.get(root, Slot::new(8)).is_none());
+
+        cache.insert(root, &state);
+        assert_eq!(
+            cache.get(root, Slot::new(8)).map(|s| s.slot),
+            Some(Slot::new(8))
+        );
+        assert!(cache.get(root, Slot::new(9)).is_none());
+        assert!(cache.get(Hash256::zero(), Slot::new(8)).is_none());
+    }
+}
@@ -0,0 +1,488 @@
+//! Provides verification for the following sync committee messages:
+//!
+//! - `SyncCommitteeMessage` received from either gossip or the HTTP API.
+//! - `SignedContributionAndProof` received from gossip or the HTTP API.
+//!
+//! Verification follows the same pattern as `crate::attestation_verification`: each message type
+//! is wrapped in a type that can only be constructed once the message has been verified for
+//! propagation on the gossip network.
+//!
+//! ```ignore
+//!      types::SyncCommitteeMessage         types::SignedContributionAndProof
+//!              |                                    |
+//!              ▼                                    ▼
+//!  VerifiedSyncCommitteeMessage           VerifiedSyncContribution
+//!              |                                    |
+//!              -------------------------------------
+//!                                |
+//!                                ▼
+//!                       SyncContributionPool
+//! ```
+
+use crate::{
+    beacon_chain::{MAXIMUM_GOSSIP_CLOCK_DISPARITY, VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT},
+    observed_sync_contributors::Error as ObservedSyncContributorsError,
+    BeaconChain, BeaconChainError, BeaconChainTypes,
+};
+use slot_clock::SlotClock;
+use types::{
+    Domain, EthSpec, ForkName, PublicKey, SignedContributionAndProof, SignedRoot, Slot,
+    SyncCommitteeContribution, SyncCommitteeMessage, SyncSelectionProof,
+};
+
+/// Returned when a sync committee message was not successfully verified. It might not have been
+/// verified for two reasons:
+///
+/// - The message is malformed or inappropriate for the context (indicated by all variants
+///   other than `BeaconChainError`).
+/// - The application encountered an internal error whilst attempting to determine validity
+///   (the `BeaconChainError` variant)
+#[derive(Debug)]
+pub enum Error {
+    /// The message is from a slot that is later than the current slot (with respect to the gossip
+    /// clock disparity).
+    ///
+    /// ## Peer scoring
+    ///
+    /// Assuming the local clock is correct, the peer has sent an invalid message.
+    FutureSlot {
+        message_slot: Slot,
+        latest_permissible_slot: Slot,
+    },
+    /// The message is from a slot that is prior to the earliest permissible slot (with respect to
+    /// the gossip clock disparity).
+    ///
+    /// ## Peer scoring
+    ///
+    /// Assuming the local clock is correct, the peer has sent an invalid message.
+    PastSlot {
+        message_slot: Slot,
+        earliest_permissible_slot: Slot,
+    },
+    /// The message is from a slot prior to the Altair fork, when sync committees did not exist.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    PreAltairSlot(Slot),
+    /// The subcommittee index is not less than `SYNC_COMMITTEE_SUBNET_COUNT`.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    InvalidSubcommittee {
+        subcommittee_index: u64,
+        subcommittee_count: u64,
+    },
+    /// The contribution's aggregation bits were empty when they shouldn't be.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    EmptyAggregationBitfield,
+    /// The `selection_proof` on the contribution does not elect it as an aggregator.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    InvalidSelectionProof { aggregator_index: u64 },
+    /// The aggregator is not a member of the subcommittee for which it is aggregating.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    AggregatorNotInCommittee { aggregator_index: u64 },
+    /// The validator is not a member of the sync subcommittee for the subnet it published on.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    ValidatorNotInSubcommittee {
+        validator_index: u64,
+        subcommittee_index: u64,
+    },
+    /// The validator index refers to a validator that we have not seen.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    UnknownValidatorIndex(u64),
+    /// There has already been a contribution observed for this aggregator, we refuse to process a
+    /// second.
+    ///
+    /// ## Peer scoring
+    ///
+    /// It's unclear if this contribution is valid, however we have already observed a
+    /// contribution from this aggregator for this slot and subcommittee.
+    AggregatorAlreadyKnown(u64),
+    /// We have already observed a message from `validator_index` for this slot and subcommittee.
+    ///
+    /// ## Peer scoring
+    ///
+    /// It's unclear if this message is valid, however we have already observed a message from
+    /// this validator and should not observe another.
+    PriorSyncCommitteeMessageKnown { validator_index: u64, slot: Slot },
+    /// The validator index is higher than the maximum possible validator count.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    ValidatorIndexTooHigh(usize),
+    /// A signature on the message is invalid.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer has sent an invalid message.
+    InvalidSignature,
+    /// There was an error whilst processing the message. It is not known if it is valid or
+    /// invalid.
+    ///
+    /// ## Peer scoring
+    ///
+    /// We were unable to process this message due to an internal error. It's unclear if the
+    /// message is valid.
+    BeaconChainError(BeaconChainError),
+}
+
+impl From<BeaconChainError> for Error {
+    fn from(e: BeaconChainError) -> Self {
+        Error::BeaconChainError(e)
+    }
+}
+
+impl From<ObservedSyncContributorsError> for Error {
+    fn from(e: ObservedSyncContributorsError) -> Self {
+        match e {
+            ObservedSyncContributorsError::ValidatorIndexTooHigh(i) => {
+                Error::ValidatorIndexTooHigh(i)
+            }
+            e => Error::BeaconChainError(e.into()),
+        }
+    }
+}
+
+/// Wraps a `SyncCommitteeMessage` that has been verified for propagation on the gossip network.
+pub struct VerifiedSyncCommitteeMessage {
+    message: SyncCommitteeMessage,
+    /// The subcommittee index and the validator's position within that subcommittee.
+    subcommittee_positions: Vec<(u64, usize)>,
+}
+
+/// Wraps a `SignedContributionAndProof` that has been verified for propagation on the gossip
+/// network.
+pub struct VerifiedSyncContribution<T: BeaconChainTypes> {
+    signed_contribution: SignedContributionAndProof<T::EthSpec>,
+}
+
+impl VerifiedSyncCommitteeMessage {
+    /// Returns `Ok(Self)` if the `message` is valid to be (re)published on the gossip network.
+    ///
+    /// `subnet_id` is the sync committee subnet from which we received this message. This
+    /// function will verify that the validator is a member of that subcommittee.
+    pub fn verify<T: BeaconChainTypes>(
+        message: SyncCommitteeMessage,
+        subnet_id: u64,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, Error> {
+        verify_propagation_slot_range(chain, message.slot)?;
+        verify_subcommittee_index(chain, subnet_id)?;
+
+        let validator_index = message.validator_index;
+
+        // Ensure the validator is a member of the subcommittee for the subnet.
+        let subcommittee_positions =
+            chain.sync_subcommittee_positions(message.slot, validator_index)?;
+        if !subcommittee_positions
+            .iter()
+            .any(|(subcommittee_index, _)| *subcommittee_index == subnet_id)
+        {
+            return Err(Error::ValidatorNotInSubcommittee {
+                validator_index,
+                subcommittee_index: subnet_id,
+            });
+        }
+
+        // The message is the first valid message received for the participating validator for
+        // the slot and subnet.
+        if chain
+            .observed_sync_contributors
+            .validator_has_been_observed(message.slot, subnet_id, validator_index as usize)?
+        {
+            return Err(Error::PriorSyncCommitteeMessageKnown {
+                validator_index,
+                slot: message.slot,
+            });
+        }
+
+        // The signature of the message is valid.
+        let pubkey = get_pubkey(chain, validator_index)?;
+        let domain = get_domain(chain, message.slot, Domain::SyncCommittee);
+        let signing_root = message.beacon_block_root.signing_root(domain);
+        if !message.signature.verify(&pubkey, signing_root) {
+            return Err(Error::InvalidSignature);
+        }
+
+        // Now that the message has been fully verified, store that we have received a valid
+        // message from this validator.
+        //
+        // It's important to double check that the message still hasn't been observed, since
+        // there can be a race-condition if we receive two messages at the same time and process
+        // them in different threads.
+        if chain.observed_sync_contributors.observe_validator(
+            message.slot,
+            subnet_id,
+            validator_index as usize,
+        )? {
+            return Err(Error::PriorSyncCommitteeMessageKnown {
+                validator_index,
+                slot: message.slot,
+            });
+        }
+
+        Ok(Self {
+            message,
+            subcommittee_positions: subcommittee_positions
+                .into_iter()
+                .filter(|(subcommittee_index, _)| *subcommittee_index == subnet_id)
+                .collect(),
+        })
+    }
+
+    /// A helper function to add this message to `beacon_chain.sync_contribution_pool`.
+    pub fn add_to_pool<T: BeaconChainTypes>(self, chain: &BeaconChain<T>) -> Result<Self, Error> {
+        for (subcommittee_index, position) in &self.subcommittee_positions {
+            let contribution = SyncCommitteeContribution::from_message(
+                &self.message,
+                *subcommittee_index,
+                *position,
+            )
+            .map_err(BeaconChainError::from)?;
+            chain.add_to_sync_contribution_pool(&contribution)?;
+        }
+        Ok(self)
+    }
+
+    /// Returns the wrapped `message`.
+    pub fn message(&self) -> &SyncCommitteeMessage {
+        &self.message
+    }
+}
+
+impl<T: BeaconChainTypes> VerifiedSyncContribution<T> {
+    /// Returns `Ok(Self)` if the `signed_contribution` is valid to be (re)published on the gossip
+    /// network.
+    pub fn verify(
+        signed_contribution: SignedContributionAndProof<T::EthSpec>,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, Error> {
+        let contribution = &signed_contribution.message.contribution;
+        let aggregator_index = signed_contribution.message.aggregator_index;
+        let subcommittee_index = contribution.subcommittee_index;
+
+        verify_propagation_slot_range(chain, contribution.slot)?;
+        verify_subcommittee_index(chain, subcommittee_index)?;
+
+        // Ensure that the contribution has participants.
+        if contribution.aggregation_bits.is_zero() {
+            return Err(Error::EmptyAggregationBitfield);
+        }
+
+        // Ensure the selection proof elects the validator as an aggregator.
+        let selection_proof =
+            SyncSelectionProof::from(signed_contribution.message.selection_proof.clone());
+        if !selection_proof
+            .is_aggregator::<T::EthSpec>(&chain.spec)
+            .map_err(BeaconChainError::from)?
+        {
+            return Err(Error::InvalidSelectionProof { aggregator_index });
+        }
+
+        // Ensure the aggregator is a member of the subcommittee for which it is aggregating.
+        let subcommittee =
+            chain.sync_subcommittee_indices(contribution.slot, subcommittee_index)?;
+        if !subcommittee.contains(&aggregator_index) {
+            return Err(Error::AggregatorNotInCommittee { aggregator_index });
+        }
+
+        // Ensure there has been no other observed contribution for the given `aggregator_index`.
+        //
+        // Note: do not observe yet, only observe once the contribution has been verified.
+        if chain
+            .observed_sync_aggregators
+            .validator_has_been_observed(
+                contribution.slot,
+                subcommittee_index,
+                aggregator_index as usize,
+            )?
+        {
+            return Err(Error::AggregatorAlreadyKnown(aggregator_index));
+        }
+
+        // Ensure that all signatures are valid.
+        verify_signed_contribution_signatures(chain, &signed_contribution, &subcommittee)?;
+
+        // Observe the aggregator so we don't process another contribution from them.
+        //
+        // It's important to double check that the contribution is not already known, otherwise
+        // two contributions processed at the same time could be published.
+        if chain.observed_sync_aggregators.observe_validator(
+            contribution.slot,
+            subcommittee_index,
+            aggregator_index as usize,
+        )? {
+            return Err(Error::AggregatorAlreadyKnown(aggregator_index));
+        }
+
+        Ok(Self {
+            signed_contribution,
+        })
+    }
+
+    /// A helper function to add this contribution to `beacon_chain.sync_contribution_pool`.
+    pub fn add_to_pool(self, chain: &BeaconChain<T>) -> Result<Self, Error> {
+        chain.add_to_sync_contribution_pool(self.contribution())?;
+        Ok(self)
+    }
+
+    /// Returns the underlying `contribution` for the `signed_contribution`.
+    pub fn contribution(&self) -> &SyncCommitteeContribution<T::EthSpec> {
+        &self.signed_contribution.message.contribution
+    }
+
+    /// Returns the wrapped `signed_contribution`.
+    pub fn signed_contribution(&self) -> &SignedContributionAndProof<T::EthSpec> {
+        &self.signed_contribution
+    }
+}
+
+/// Verify that the `message_slot` is the current slot, with respect to the gossip clock
+/// disparity, and that it is post-Altair.
+pub fn verify_propagation_slot_range<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    message_slot: Slot,
+) -> Result<(), Error> {
+    let latest_permissible_slot = chain
+        .slot_clock
+        .now_with_future_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+        .ok_or_else(|| BeaconChainError::UnableToReadSlot)?;
+    if message_slot > latest_permissible_slot {
+        return Err(Error::FutureSlot {
+            message_slot,
+            latest_permissible_slot,
+        });
+    }
+
+    let earliest_permissible_slot = chain
+        .slot_clock
+        .now_with_past_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+        .ok_or_else(|| BeaconChainError::UnableToReadSlot)?;
+    if message_slot < earliest_permissible_slot {
+        return Err(Error::PastSlot {
+            message_slot,
+            earliest_permissible_slot,
+        });
+    }
+
+    if chain.spec.fork_name_at_slot::<T::EthSpec>(message_slot) == ForkName::Base {
+        return Err(Error::PreAltairSlot(message_slot));
+    }
+
+    Ok(())
+}
+
+/// Verify that `subcommittee_index` is less than `SYNC_COMMITTEE_SUBNET_COUNT`.
+fn verify_subcommittee_index<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    subcommittee_index: u64,
+) -> Result<(), Error> {
+    let subcommittee_count = chain.spec.sync_committee_subnet_count;
+    if subcommittee_index >= subcommittee_count {
+        Err(Error::InvalidSubcommittee {
+            subcommittee_index,
+            subcommittee_count,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies all the signatures in a `SignedContributionAndProof`. This includes three signatures:
+///
+/// - `signed_contribution.signature`
+/// - `signed_contribution.message.selection_proof`
+/// - `signed_contribution.message.contribution.signature`
+///
+/// `subcommittee` must contain the validator indices of the subcommittee, in order.
+pub fn verify_signed_contribution_signatures<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    signed_contribution: &SignedContributionAndProof<T::EthSpec>,
+    subcommittee: &[u64],
+) -> Result<(), Error> {
+    let contribution = &signed_contribution.message.contribution;
+    let aggregator_pubkey = get_pubkey(chain, signed_contribution.message.aggregator_index)?;
+
+    // The head state may be on the other side of a fork boundary, so the fork is taken from the
+    // schedule instead.
+    let fork = chain
+        .spec
+        .fork_at_epoch(contribution.slot.epoch(T::EthSpec::slots_per_epoch()));
+
+    if !signed_contribution.is_valid(
+        &aggregator_pubkey,
+        &fork,
+        chain.genesis_validators_root,
+        &chain.spec,
+    ) {
+        return Err(Error::InvalidSignature);
+    }
+
+    let participant_pubkeys = contribution
+        .aggregation_bits
+        .iter()
+        .zip(subcommittee.iter())
+        .filter(|(bit, _)| *bit)
+        .map(|(_, validator_index)| get_pubkey(chain, *validator_index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let domain = get_domain(chain, contribution.slot, Domain::SyncCommittee);
+    let signing_root = contribution.beacon_block_root.signing_root(domain);
+
+    if contribution.signature.fast_aggregate_verify(
+        signing_root,
+        &participant_pubkeys.iter().collect::<Vec<_>>(),
+    ) {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// Returns the public key of `validator_index` from the chain's pubkey cache.
+fn get_pubkey<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    validator_index: u64,
+) -> Result<PublicKey, Error> {
+    chain
+        .validator_pubkey_cache
+        .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+        .ok_or_else(|| BeaconChainError::ValidatorPubkeyCacheLockTimeout)?
+        .get(validator_index as usize)
+        .cloned()
+        .ok_or_else(|| Error::UnknownValidatorIndex(validator_index))
+}
+
+/// Returns the signature domain for `domain_type` at `slot`, using the fork scheduled for `slot`.
+fn get_domain<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    slot: Slot,
+    domain_type: Domain,
+) -> types::Hash256 {
+    let epoch = slot.epoch(T::EthSpec::slots_per_epoch());
+
+    chain.spec.get_domain(
+        epoch,
+        domain_type,
+        &chain.spec.fork_at_epoch(epoch),
+        chain.genesis_validators_root,
+    )
+}
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use types::{EthSpec, Hash256, Slot, SyncCommitteeContribution};
+
+/// The number of slots that will be stored in the pool.
+///
+/// Sync committee messages are only useful for the block in the following slot, so a short
+/// retention window is sufficient.
+const SLOTS_RETAINED: u64 = 3;
+
+/// The maximum number of distinct `(beacon_block_root, subcommittee_index)` pairs that will be
+/// stored in each slot.
+///
+/// This is a DoS protection measure.
+const MAX_CONTRIBUTIONS_PER_SLOT: usize = 1_024;
+
+/// Returned upon successfully inserting a contribution into the pool.
+#[derive(Debug, PartialEq)]
+pub enum InsertOutcome {
+    /// No contribution was known for this block root and subcommittee, it was added to the pool.
+    NewContribution,
+    /// The contribution was disjoint from the one known and was aggregated into it.
+    Aggregated,
+    /// The contribution had more participants than the one known, so it replaced it.
+    Replaced,
+    /// The contribution did not add any new information to the pool. No changes were made.
+    AlreadyKnown,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The given `contribution.slot` was too low to be stored. No changes were made.
+    SlotTooLow {
+        slot: Slot,
+        lowest_permissible_slot: Slot,
+    },
+    /// The given `contribution.aggregation_bits` field was empty.
+    NoAggregationBitsSet,
+    /// We have reached the maximum number of unique contributions that can be stored in a slot.
+    /// This is a DoS protection function.
+    ReachedMaxContributionsPerSlot(usize),
+}
+
+/// A pool of `SyncCommitteeContribution` objects, used both to aggregate `SyncCommitteeMessage`s
+/// (by inserting them as single-participant contributions) and to store the best contributions for
+/// inclusion in blocks.
+///
+/// **The `SyncContributionPool` does not do any signature or membership verification. It assumes
+/// that all contributions provided are valid.**
+///
+/// Contributions are keyed by `(slot, beacon_block_root, subcommittee_index)`. When a contribution
+/// is inserted it is aggregated into the existing one if their participants are disjoint,
+/// otherwise the contribution with the most participants is kept.
+pub struct SyncContributionPool<E: EthSpec> {
+    lowest_permissible_slot: Slot,
+    maps: HashMap<Slot, HashMap<(Hash256, u64), SyncCommitteeContribution<E>>>,
+}
+
+impl<E: EthSpec> Default for SyncContributionPool<E> {
+    fn default() -> Self {
+        Self {
+            lowest_permissible_slot: Slot::new(0),
+            maps: HashMap::new(),
+        }
+    }
+}
+
+impl<E: EthSpec> SyncContributionPool<E> {
+    /// Insert a contribution into `self`, aggregating it with any existing contribution where
+    /// possible.
+    ///
+    /// The pool may be pruned if the given contribution has a slot higher than any previously
+    /// seen.
+    pub fn insert(
+        &mut self,
+        contribution: &SyncCommitteeContribution<E>,
+    ) -> Result<InsertOutcome, Error> {
+        let slot = contribution.slot;
+        let lowest_permissible_slot = self.lowest_permissible_slot;
+
+        if slot < lowest_permissible_slot {
+            return Err(Error::SlotTooLow {
+                slot,
+                lowest_permissible_slot,
+            });
+        }
+
+        if contribution.aggregation_bits.is_zero() {
+            return Err(Error::NoAggregationBitsSet);
+        }
+
+        let map = self.maps.entry(slot).or_insert_with(HashMap::new);
+        let key = (
+            contribution.beacon_block_root,
+            contribution.subcommittee_index,
+        );
+
+        let outcome = if let Some(existing) = map.get_mut(&key) {
+            if existing.signers_disjoint_from(contribution) {
+                existing.aggregate(contribution);
+                InsertOutcome::Aggregated
+            } else if contribution.num_set_bits() > existing.num_set_bits() {
+                *existing = contribution.clone();
+                InsertOutcome::Replaced
+            } else {
+                InsertOutcome::AlreadyKnown
+            }
+        } else {
+            if map.len() >= MAX_CONTRIBUTIONS_PER_SLOT {
+                return Err(Error::ReachedMaxContributionsPerSlot(
+                    MAX_CONTRIBUTIONS_PER_SLOT,
+                ));
+            }
+
+            map.insert(key, contribution.clone());
+            InsertOutcome::NewContribution
+        };
+
+        self.prune(slot);
+
+        Ok(outcome)
+    }
+
+    /// Returns the contribution for the given `slot`, `beacon_block_root` and
+    /// `subcommittee_index`, if any.
+    pub fn get(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+        subcommittee_index: u64,
+    ) -> Option<SyncCommitteeContribution<E>> {
+        self.maps
+            .get(&slot)
+            .and_then(|map| map.get(&(beacon_block_root, subcommittee_index)))
+            .cloned()
+    }
+
+    /// Returns the best known contribution from each subcommittee for the given `slot` and
+    /// `beacon_block_root`, sorted by subcommittee index.
+    ///
+    /// These are suitable for packing into a block at `slot + 1` which builds upon
+    /// `beacon_block_root`.
+    pub fn get_contributions_for_block(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+    ) -> Vec<SyncCommitteeContribution<E>> {
+        let mut contributions = self
+            .maps
+            .get(&slot)
+            .map(|map| {
+                map.iter()
+                    .filter(|((root, _), _)| *root == beacon_block_root)
+                    .map(|(_, contribution)| contribution.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        contributions.sort_by_key(|contribution| contribution.subcommittee_index);
+        contributions
+    }
+
+    /// Removes any contributions with a slot lower than `current_slot - SLOTS_RETAINED` and bars
+    /// any future contributions with such a slot.
+    pub fn prune(&mut self, current_slot: Slot) {
+        // Taking advantage of saturating subtraction on `Slot`.
+        let lowest_permissible_slot = current_slot - SLOTS_RETAINED;
+
+        if lowest_permissible_slot <= self.lowest_permissible_slot {
+            return;
+        }
+
+        self.lowest_permissible_slot = lowest_permissible_slot;
+        self.maps
+            .retain(|slot, _map| *slot >= lowest_permissible_slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{
+        test_utils::{generate_deterministic_keypair, test_random_instance},
+        BitVector, SyncCommitteeMessage,
+    };
+
+    type E = types::MainnetEthSpec;
+
+    fn get_contribution(slot: Slot, positions: &[usize]) -> SyncCommitteeContribution<E> {
+        let mut contribution: SyncCommitteeContribution<E> = test_random_instance();
+        contribution.slot = slot;
+        contribution.beacon_block_root = Hash256::repeat_byte(42);
+        contribution.subcommittee_index = 0;
+        contribution.aggregation_bits = BitVector::new();
+        for i in positions {
+            contribution.aggregation_bits.set(*i, true).unwrap();
+        }
+        contribution
+    }
+
+    #[test]
+    fn aggregates_messages() {
+        let mut pool = SyncContributionPool::<E>::default();
+        let slot = Slot::new(10);
+
+        for i in 0..4 {
+            let keypair = generate_deterministic_keypair(i);
+            let mut message: SyncCommitteeMessage = test_random_instance();
+            message.slot = slot;
+            message.beacon_block_root = Hash256::repeat_byte(42);
+            message.signature = keypair.sk.sign(message.beacon_block_root);
+
+            let contribution =
+                SyncCommitteeContribution::from_message(&message, 0, i).expect("should convert");
+            let expected = if i == 0 {
+                InsertOutcome::NewContribution
+            } else {
+                InsertOutcome::Aggregated
+            };
+            assert_eq!(pool.insert(&contribution), Ok(expected));
+        }
+
+        let aggregate = pool
+            .get(slot, Hash256::repeat_byte(42), 0)
+            .expect("should have aggregate");
+        assert_eq!(aggregate.num_set_bits(), 4);
+    }
+
+    #[test]
+    fn keeps_best_overlapping_contribution() {
+        let mut pool = SyncContributionPool::<E>::default();
+        let slot = Slot::new(10);
+
+        assert_eq!(
+            pool.insert(&get_contribution(slot, &[0, 1])),
+            Ok(InsertOutcome::NewContribution)
+        );
+        assert_eq!(
+            pool.insert(&get_contribution(slot, &[1])),
+            Ok(InsertOutcome::AlreadyKnown)
+        );
+        assert_eq!(
+            pool.insert(&get_contribution(slot, &[1, 2, 3])),
+            Ok(InsertOutcome::Replaced)
+        );
+        assert_eq!(
+            pool.get_contributions_for_block(slot, Hash256::repeat_byte(42))
+                .len(),
+            1
+        );
+        assert!(pool
+            .get_contributions_for_block(slot, Hash256::zero())
+            .is_empty());
+    }
+
+    #[test]
+    fn prunes_old_slots() {
+        let mut pool = SyncContributionPool::<E>::default();
+
+        pool.insert(&get_contribution(Slot::new(1), &[0])).unwrap();
+        pool.prune(Slot::new(10));
+
+        assert_eq!(pool.get(Slot::new(1), Hash256::repeat_byte(42), 0), None);
+        assert_eq!(
+            pool.insert(&get_contribution(Slot::new(1), &[0])),
+            Err(Error::SlotTooLow {
+                slot: Slot::new(1),
+                lowest_permissible_slot: Slot::new(7),
+            })
+        );
+        assert_eq!(
+            pool.insert(&get_contribution(Slot::new(1), &[])),
+            Err(Error::SlotTooLow {
+                slot: Slot::new(1),
+                lowest_permissible_slot: Slot::new(7),
+            })
+        );
+        assert_eq!(
+            pool.insert(&get_contribution(Slot::new(10), &[])),
+            Err(Error::NoAggregationBitsSet)
+        );
+    }
+}
@@ -17,12 +17,12 @@ use rand_core::SeedableRng;
 use rayon::prelude::*;
 use sloggers::{null::NullLoggerBuilder, Build};
 use slot_clock::TestingSlotClock;
-use state_processing::per_slot_processing;
+use state_processing::{per_slot_processing, StateRootStrategy};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use store::{config::StoreConfig, BlockReplay, HotColdDB, ItemStore, LevelDB, MemoryStore};
+use store::{config::StoreConfig, BeaconNodeBackend, HotColdDB, ItemStore, MemoryStore};
 use tempfile::{tempdir, TempDir};
 use tree_hash::TreeHash;
 use types::{
@@ -50,8 +50,12 @@ pub type BaseHarnessType<TStoreMigrator, TEthSpec, THotStore, TColdStore> = Witn
 
 pub type NullMigratorEphemeralHarnessType<E> =
     BaseHarnessType<NullMigrator, E, MemoryStore<E>, MemoryStore<E>>;
-pub type BlockingMigratorDiskHarnessType<E> =
-    BaseHarnessType<BlockingMigrator<E, LevelDB<E>, LevelDB<E>>, E, LevelDB<E>, LevelDB<E>>;
+pub type BlockingMigratorDiskHarnessType<E> = BaseHarnessType<
+    BlockingMigrator<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>,
+    E,
+    BeaconNodeBackend<E>,
+    BeaconNodeBackend<E>,
+>;
 pub type BlockingMigratorEphemeralHarnessType<E> = BaseHarnessType<
     BlockingMigrator<E, MemoryStore<E>, MemoryStore<E>>,
     E,
@@ -186,11 +190,22 @@ impl<E: EthSpec> BeaconChainHarness<NullMigratorEphemeralHarnessType<E>> {
         target_aggregators_per_committee: u64,
         config: StoreConfig,
     ) -> Self {
-        let data_dir = tempdir().expect("should create temporary data_dir");
         let mut spec = E::default_spec();
 
         spec.target_aggregators_per_committee = target_aggregators_per_committee;
 
+        Self::new_with_spec(eth_spec_instance, validators_keypairs, spec, config)
+    }
+
+    /// Instantiate a new harness with `validator_count` initial validators and a custom `spec`.
+    pub fn new_with_spec(
+        eth_spec_instance: E,
+        validators_keypairs: Vec<Keypair>,
+        spec: ChainSpec,
+        config: StoreConfig,
+    ) -> Self {
+        let data_dir = tempdir().expect("should create temporary data_dir");
+
         let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
         let drain = slog_term::FullFormat::new(decorator).build();
         let debug_level = slog::LevelFilter::new(drain, slog::Level::Debug);
@@ -230,7 +245,7 @@ impl<E: EthSpec> BeaconChainHarness<BlockingMigratorDiskHarnessType<E>> {
     /// Instantiate a new harness with `validator_count` initial validators.
     pub fn new_with_disk_store(
         eth_spec_instance: E,
-        store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+        store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
         validators_keypairs: Vec<Keypair>,
     ) -> Self {
         let data_dir = tempdir().expect("should create temporary data_dir");
@@ -275,7 +290,7 @@ impl<E: EthSpec> BeaconChainHarness<BlockingMigratorDiskHarnessType<E>> {
     /// Instantiate a new harness with `validator_count` initial validators.
     pub fn resume_from_disk_store(
         eth_spec_instance: E,
-        store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+        store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
         validators_keypairs: Vec<Keypair>,
         data_dir: TempDir,
     ) -> Self {
@@ -352,7 +367,7 @@ where
     pub fn get_hot_state(&self, state_hash: BeaconStateHash) -> Option<BeaconState<E>> {
         self.chain
             .store
-            .load_hot_state(&state_hash.into(), BlockReplay::Accurate)
+            .load_hot_state(&state_hash.into(), StateRootStrategy::Accurate)
             .unwrap()
     }
 
@@ -1,5 +1,5 @@
 use crate::errors::BeaconChainError;
-use ssz::{Decode, DecodeError, Encode};
+use bls::PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fs::{File, OpenOptions};
@@ -7,6 +7,13 @@ use std::io::{self, Read, Write};
 use std::path::Path;
 use types::{BeaconState, EthSpec, PublicKey, PublicKeyBytes, Validator};
 
+/// The number of bytes used to store the validator index of each entry in the persistence file.
+const INDEX_BYTES_LEN: usize = 8;
+
+/// The number of bytes used by each entry in the persistence file.
+const ENTRY_BYTES_LEN: usize =
+    INDEX_BYTES_LEN + bls::PUBLIC_KEY_BYTES_LEN + PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN;
+
 /// Provides a mapping of `validator_index -> validator_publickey`.
 ///
 /// This cache exists for two reasons:
@@ -17,7 +24,8 @@ use types::{BeaconState, EthSpec, PublicKey, PublicKeyBytes, Validator};
 ///    Decompression is expensive when many keys are involved.
 ///
 /// The cache has a `persistence_file` that it uses to maintain a persistent, on-disk
-/// copy of itself. This allows it to be restored between process invocations.
+/// copy of itself. This allows it to be restored between process invocations. Keys are persisted
+/// in decompressed form, so restoring the cache does not require any point decompression.
 pub struct ValidatorPubkeyCache {
     pubkeys: Vec<PublicKey>,
     indices: HashMap<PublicKeyBytes, usize>,
@@ -92,13 +100,13 @@ impl ValidatorPubkeyCache {
             // The motivation behind this ordering is that we do not want to have states that
             // reference a pubkey that is not in our cache. However, it's fine to have pubkeys
             // that are never referenced in a state.
-            self.persitence_file.append(i, &v.pubkey)?;
+            let pubkey: PublicKey = (&v.pubkey)
+                .try_into()
+                .map_err(BeaconChainError::InvalidValidatorPubkeyBytes)?;
+
+            self.persitence_file.append(i, &v.pubkey, &pubkey)?;
 
-            self.pubkeys.push(
-                (&v.pubkey)
-                    .try_into()
-                    .map_err(BeaconChainError::InvalidValidatorPubkeyBytes)?,
-            );
+            self.pubkeys.push(pubkey);
 
             self.indices.insert(v.pubkey.clone(), i);
         }
@@ -122,24 +130,24 @@ impl ValidatorPubkeyCache {
     }
 }
 
-/// Allows for maintaining an on-disk copy of the `ValidatorPubkeyCache`. The file is raw SSZ bytes
+/// Allows for maintaining an on-disk copy of the `ValidatorPubkeyCache`. The file is raw bytes
 /// (not ASCII encoded).
 ///
 /// ## Writes
 ///
-/// Each entry is simply appended to the file.
+/// Each entry is simply appended to the file. An entry is the little-endian `u64` validator index,
+/// followed by the compressed public key, followed by the uncompressed public key.
 ///
 /// ## Reads
 ///
-/// The whole file is parsed as an SSZ "variable list" of objects.
-///
-/// This parsing method is possible because the items in the list are fixed-length SSZ objects.
+/// The whole file is split into fixed-length entries which are parsed in order.
 struct ValidatorPubkeyCacheFile(File);
 
 #[derive(Debug)]
 enum Error {
     Io(io::Error),
-    Ssz(DecodeError),
+    /// The file length is not a multiple of the entry length. The file has become corrupted.
+    InvalidLength(usize),
     PubkeyDecode(bls::Error),
     /// The file read from disk does not have a contiguous list of validator public keys. The file
     /// has become corrupted.
@@ -182,8 +190,13 @@ impl ValidatorPubkeyCacheFile {
     ///
     /// The provided `index` should each be one greater than the previous and start at 0.
     /// Otherwise, the file will become corrupted and unable to be converted into a cache .
-    pub fn append(&mut self, index: usize, pubkey: &PublicKeyBytes) -> Result<(), Error> {
-        append_to_file(&mut self.0, index, pubkey)
+    pub fn append(
+        &mut self,
+        index: usize,
+        pubkey_bytes: &PublicKeyBytes,
+        pubkey: &PublicKey,
+    ) -> Result<(), Error> {
+        append_to_file(&mut self.0, index, pubkey_bytes, pubkey)
     }
 
     /// Creates a `ValidatorPubkeyCache` by reading and parsing the underlying file.
@@ -191,18 +204,34 @@ impl ValidatorPubkeyCacheFile {
         let mut bytes = vec![];
         self.0.read_to_end(&mut bytes).map_err(Error::Io)?;
 
-        let list: Vec<(usize, PublicKeyBytes)> = Vec::from_ssz_bytes(&bytes).map_err(Error::Ssz)?;
+        if bytes.len() % ENTRY_BYTES_LEN != 0 {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
 
+        let num_entries = bytes.len() / ENTRY_BYTES_LEN;
         let mut last = None;
-        let mut pubkeys = Vec::with_capacity(list.len());
-        let mut indices = HashMap::new();
+        let mut pubkeys = Vec::with_capacity(num_entries);
+        let mut indices = HashMap::with_capacity(num_entries);
+
+        for entry in bytes.chunks_exact(ENTRY_BYTES_LEN) {
+            let (index_bytes, pubkey_bytes) = entry.split_at(INDEX_BYTES_LEN);
+            let (compressed, uncompressed) = pubkey_bytes.split_at(bls::PUBLIC_KEY_BYTES_LEN);
+
+            let mut index_array = [0; INDEX_BYTES_LEN];
+            index_array.copy_from_slice(index_bytes);
+            let index = u64::from_le_bytes(index_array) as usize;
 
-        for (index, pubkey) in list {
             let expected = last.map(|n| n + 1);
             if expected.map_or(true, |expected| index == expected) {
                 last = Some(index);
-                pubkeys.push((&pubkey).try_into().map_err(Error::PubkeyDecode)?);
-                indices.insert(pubkey, index);
+                pubkeys.push(
+                    PublicKey::deserialize_uncompressed(uncompressed)
+                        .map_err(Error::PubkeyDecode)?,
+                );
+                indices.insert(
+                    PublicKeyBytes::deserialize(compressed).map_err(Error::PubkeyDecode)?,
+                    index,
+                );
             } else {
                 return Err(Error::InconsistentIndex {
                     expected,
@@ -219,11 +248,17 @@ impl ValidatorPubkeyCacheFile {
     }
 }
 
-fn append_to_file(file: &mut File, index: usize, pubkey: &PublicKeyBytes) -> Result<(), Error> {
-    let mut line = Vec::with_capacity(index.ssz_bytes_len() + pubkey.ssz_bytes_len());
+fn append_to_file(
+    file: &mut File,
+    index: usize,
+    pubkey_bytes: &PublicKeyBytes,
+    pubkey: &PublicKey,
+) -> Result<(), Error> {
+    let mut line = Vec::with_capacity(ENTRY_BYTES_LEN);
 
-    index.ssz_append(&mut line);
-    pubkey.ssz_append(&mut line);
+    line.extend_from_slice(&(index as u64).to_le_bytes());
+    line.extend_from_slice(&pubkey_bytes.serialize());
+    line.extend_from_slice(&pubkey.serialize_uncompressed());
 
     file.write_all(&line).map_err(Error::Io)
 }
@@ -337,10 +372,11 @@ mod test {
     fn invalid_persisted_file() {
         let dir = tempdir().expect("should create tempdir");
         let path = dir.path().join("cache.ssz");
-        let pubkey = generate_deterministic_keypair(0).pk.into();
+        let pubkey = generate_deterministic_keypair(0).pk;
+        let pubkey_bytes = pubkey.clone().into();
 
         let mut file = File::create(&path).expect("should create file");
-        append_to_file(&mut file, 0, &pubkey).expect("should write to file");
+        append_to_file(&mut file, 0, &pubkey_bytes, &pubkey).expect("should write to file");
         drop(file);
 
         let cache = ValidatorPubkeyCache::load_from_file(&path).expect("should open cache");
@@ -352,7 +388,8 @@ mod test {
             .open(&path)
             .expect("should open file");
 
-        append_to_file(&mut file, 42, &pubkey).expect("should write bad data to file");
+        append_to_file(&mut file, 42, &pubkey_bytes, &pubkey)
+            .expect("should write bad data to file");
         drop(file);
 
         assert!(
@@ -360,4 +397,22 @@ mod test {
             "should not parse invalid file"
         );
     }
+
+    #[test]
+    fn truncated_persisted_file() {
+        let dir = tempdir().expect("should create tempdir");
+        let path = dir.path().join("cache.ssz");
+        let pubkey = generate_deterministic_keypair(0).pk;
+        let pubkey_bytes = pubkey.clone().into();
+
+        let mut file = File::create(&path).expect("should create file");
+        append_to_file(&mut file, 0, &pubkey_bytes, &pubkey).expect("should write to file");
+        file.write_all(&[0; 3]).expect("should write partial entry");
+        drop(file);
+
+        assert!(
+            ValidatorPubkeyCache::load_from_file(&path).is_err(),
+            "should not parse truncated file"
+        );
+    }
 }
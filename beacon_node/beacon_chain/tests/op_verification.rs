@@ -11,7 +11,7 @@ use beacon_chain::test_utils::{
 };
 use sloggers::{null::NullLoggerBuilder, Build};
 use std::sync::Arc;
-use store::{LevelDB, StoreConfig};
+use store::{BeaconNodeBackend, StoreConfig};
 use tempfile::{tempdir, TempDir};
 use types::test_utils::{
     AttesterSlashingTestTask, ProposerSlashingTestTask, TestingAttesterSlashingBuilder,
@@ -29,7 +29,7 @@ lazy_static! {
 
 type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<BlockingMigratorDiskHarnessType<E>>;
-type HotColdDB = store::HotColdDB<E, LevelDB<E>, LevelDB<E>>;
+type HotColdDB = store::HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>;
 
 fn get_store(db_path: &TempDir) -> Arc<HotColdDB> {
     let spec = E::default_spec();
@@ -15,13 +15,19 @@ use beacon_chain::test_utils::{
 use beacon_chain::BeaconSnapshot;
 use maplit::hashset;
 use rand::Rng;
+use sloggers::{null::NullLoggerBuilder, Build};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use store::{
+    config::StoreConfigError,
+    hot_cold_store::HotColdDBError,
     iter::{BlockRootsIterator, StateRootsIterator},
-    HotColdDB, LevelDB, StoreConfig,
+    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, FREEZER_PATH_KEY},
+    schema_change::migrate_schema,
+    BeaconNodeBackend, DBColumn, HotColdDB, KeyValueStore, StoreConfig, StoreOp,
 };
 use tempfile::{tempdir, TempDir};
 use tree_hash::TreeHash;
@@ -40,7 +46,7 @@ lazy_static! {
 type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<BlockingMigratorDiskHarnessType<E>>;
 
-fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>> {
     let spec = MinimalEthSpec::default_spec();
     let hot_path = db_path.path().join("hot_db");
     let cold_path = db_path.path().join("cold_db");
@@ -57,7 +63,7 @@ fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
 }
 
 fn get_harness(
-    store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
     validator_count: usize,
 ) -> TestHarness {
     let harness = BeaconChainHarness::new_with_disk_store(
@@ -262,6 +268,437 @@ fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+// Check that a freezer DB cannot be re-opened with a different `slots_per_restore_point`.
+#[test]
+fn slots_per_restore_point_mismatch() {
+    let db_path = tempdir().unwrap();
+
+    // Initialize the DB with the default config.
+    get_store(&db_path);
+
+    let spec = MinimalEthSpec::default_spec();
+    let hot_path = db_path.path().join("hot_db");
+    let cold_path = db_path.path().join("cold_db");
+    let default_config = StoreConfig::default();
+    let config = StoreConfig {
+        slots_per_restore_point: default_config.slots_per_restore_point / 2,
+        ..default_config.clone()
+    };
+    let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
+    let drain = slog_term::FullFormat::new(decorator).build();
+    let log = slog::Logger::root(std::sync::Mutex::new(drain).fuse(), o!());
+
+    match HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path, &cold_path, config, spec, log,
+    ) {
+        Err(store::Error::ConfigError(StoreConfigError::MismatchedSlotsPerRestorePoint {
+            config,
+            on_disk,
+        })) => {
+            assert_eq!(config, default_config.slots_per_restore_point / 2);
+            assert_eq!(on_disk, default_config.slots_per_restore_point);
+        }
+        other => panic!("expected config mismatch, got {:?}", other.map(|_| ())),
+    }
+
+    // Re-opening with the original config should still work.
+    get_store(&db_path);
+}
+
+// Check that a new database records the current schema version, and that a database written by a
+// newer version of Lighthouse is rejected.
+#[test]
+fn schema_version() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    assert_eq!(
+        store.load_schema_version().unwrap(),
+        Some(CURRENT_SCHEMA_VERSION)
+    );
+    migrate_schema(store.clone(), db_path.path()).expect("should be a no-op");
+
+    let newer = SchemaVersion(CURRENT_SCHEMA_VERSION.as_u64() + 1);
+    store.store_schema_version(newer).unwrap();
+
+    match migrate_schema(store, db_path.path()) {
+        Err(store::Error::HotColdDBError(HotColdDBError::SchemaVersionTooNew {
+            on_disk,
+            supported,
+        })) => {
+            assert_eq!(on_disk, newer);
+            assert_eq!(supported, CURRENT_SCHEMA_VERSION);
+        }
+        other => panic!("expected schema version error, got {:?}", other),
+    }
+}
+
+#[test]
+fn freezer_path_mismatch() {
+    let db_path = tempdir().unwrap();
+    let hot_path = db_path.path().join("hot_db");
+    let cold_path = db_path.path().join("cold_db");
+    let other_cold_path = db_path.path().join("other_cold_db");
+    let spec = MinimalEthSpec::default_spec();
+    let log = NullLoggerBuilder.build().expect("logger should build");
+
+    let open = |cold_path: &std::path::Path| {
+        HotColdDB::<E, _, _>::open(
+            &hot_path,
+            cold_path,
+            StoreConfig::default(),
+            spec.clone(),
+            log.clone(),
+        )
+    };
+
+    // Re-opening with the same freezer path is fine.
+    drop(open(&cold_path).expect("should open new database"));
+    drop(open(&cold_path).expect("should re-open database"));
+
+    // Opening with a different freezer path whilst the original still exists is an error.
+    assert!(matches!(
+        open(&other_cold_path),
+        Err(store::Error::FreezerPathMismatch { .. })
+    ));
+    assert!(
+        !other_cold_path.exists(),
+        "should not create a freezer database at the mismatched path"
+    );
+
+    // Moving the freezer database is allowed.
+    std::fs::rename(&cold_path, &other_cold_path).unwrap();
+    drop(open(&other_cold_path).expect("should open moved freezer database"));
+    assert_eq!(
+        open(&other_cold_path)
+            .unwrap()
+            .load_freezer_path()
+            .unwrap()
+            .map(|path| path.0),
+        Some(std::fs::canonicalize(&other_cold_path).unwrap())
+    );
+
+    // Opening without the freezer database (e.g., an unmounted disk) is an error.
+    let unmounted_cold_path = db_path.path().join("unmounted_cold_db");
+    std::fs::rename(&other_cold_path, &unmounted_cold_path).unwrap();
+    assert!(matches!(
+        open(&other_cold_path),
+        Err(store::Error::MissingFreezerDatabase(_))
+    ));
+    assert!(!other_cold_path.exists());
+}
+
+#[test]
+fn freezer_path_missing_for_existing_database() {
+    let db_path = tempdir().unwrap();
+    let hot_path = db_path.path().join("hot_db");
+    let cold_path = db_path.path().join("cold_db");
+    let spec = MinimalEthSpec::default_spec();
+    let log = NullLoggerBuilder.build().expect("logger should build");
+
+    let open = || {
+        HotColdDB::<E, _, _>::open(
+            &hot_path,
+            &cold_path,
+            StoreConfig::default(),
+            spec.clone(),
+            log.clone(),
+        )
+    };
+
+    drop(open().expect("should open new database"));
+
+    // Simulate a database which pre-dates recording of the freezer path.
+    let hot_db = BeaconNodeBackend::<E>::open(Default::default(), &hot_path).unwrap();
+    hot_db
+        .key_delete(DBColumn::BeaconMeta.into(), FREEZER_PATH_KEY.as_bytes())
+        .unwrap();
+    drop(hot_db);
+
+    std::fs::remove_dir_all(&cold_path).unwrap();
+    assert!(matches!(
+        open(),
+        Err(store::Error::MissingFreezerDatabase(_))
+    ));
+    assert!(!cold_path.exists());
+}
+
+#[test]
+fn anchor_info_limits_history() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    assert_eq!(store.get_anchor_info(), None);
+
+    let anchor_slot = Slot::new(4 * E::slots_per_epoch());
+    store
+        .init_anchor_info(anchor_slot, Hash256::repeat_byte(1))
+        .unwrap();
+    drop(store);
+
+    // The anchor should persist across restarts.
+    let store = get_store(&db_path);
+    let anchor = store.get_anchor_info().expect("anchor should be stored");
+    assert_eq!(anchor.oldest_block_slot, anchor_slot);
+    assert_eq!(anchor.oldest_state_slot, anchor_slot);
+
+    assert!(store.check_block_history(anchor_slot).is_ok());
+    for result in &[
+        store.check_block_history(anchor_slot - 1),
+        store.load_cold_state_by_slot(Slot::new(0)).map(|_| ()),
+    ] {
+        assert!(matches!(
+            result,
+            Err(store::Error::HotColdDBError(
+                HotColdDBError::HistoryUnavailable { .. }
+            ))
+        ));
+    }
+}
+
+#[test]
+fn reconstruct_historic_states() {
+    let source_path = tempdir().unwrap();
+    let source_store = get_store(&source_path);
+    let harness = get_harness(source_store.clone(), LOW_VALIDATOR_COUNT);
+    let slots_per_restore_point = source_store.get_config().slots_per_restore_point;
+
+    // Build a chain spanning several restore points, with a few skipped slots.
+    let initial_blocks = slots_per_restore_point;
+    let skip_slots = 3;
+    harness.extend_chain(
+        initial_blocks as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    for _ in 0..skip_slots {
+        harness.advance_slot();
+    }
+    harness.extend_chain(
+        slots_per_restore_point as usize,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: Slot::new(initial_blocks),
+            first_slot: Slot::new(initial_blocks + skip_slots + 1),
+        },
+        AttestationStrategy::AllValidators,
+    );
+
+    let state_roots = harness
+        .chain
+        .rev_iter_state_roots()
+        .unwrap()
+        .map(|result| result.map(|(state_root, slot)| (slot, state_root)))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .unwrap();
+    let dump = harness.chain.chain_dump().unwrap();
+    let (anchor, history) = dump.split_last().unwrap();
+    let genesis_state = dump[0].beacon_state.clone();
+    let blocks = dump
+        .iter()
+        .map(|snapshot| snapshot.beacon_block.clone())
+        .collect::<Vec<_>>();
+
+    // Initialize a database from the head state, as the beacon chain builder would.
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let anchor_slot = anchor.beacon_state.slot;
+    store
+        .init_anchor_info(anchor_slot, anchor.beacon_block.parent_root())
+        .unwrap();
+    let anchor_block = |state_root| {
+        let mut block = anchor.beacon_block.clone();
+        block.message.state_root = state_root;
+        block
+    };
+    for snapshot in history {
+        store
+            .store_reconstructed_block(&snapshot.beacon_block)
+            .unwrap();
+    }
+    store
+        .put_block(&Hash256::zero(), anchor.beacon_block.clone())
+        .unwrap();
+
+    // Blocks must be applied in slot order.
+    let reconstruction = store
+        .start_state_reconstruction(genesis_state.clone())
+        .unwrap()
+        .apply_blocks(&*store, blocks[1..4].to_vec())
+        .unwrap();
+    assert!(matches!(
+        reconstruction.apply_blocks(&*store, blocks[2..3].to_vec()),
+        Err(store::Error::HotColdDBError(
+            HotColdDBError::ReconstructionBlockOutOfOrder { .. }
+        ))
+    ));
+
+    // A replay which doesn't arrive at the anchor state must leave the anchor in place.
+    store
+        .put_block(&Hash256::zero(), anchor_block(Hash256::repeat_byte(1)))
+        .unwrap();
+    let result = store
+        .start_state_reconstruction(genesis_state.clone())
+        .unwrap()
+        .apply_blocks(&*store, blocks[1..].to_vec())
+        .unwrap()
+        .finish(&*store);
+    assert!(matches!(
+        result,
+        Err(store::Error::HotColdDBError(
+            HotColdDBError::ReconstructionAnchorMismatch { .. }
+        ))
+    ));
+    assert!(store.get_anchor_info().is_some());
+
+    // An interrupted replay should resume from the last restore point it stored.
+    store
+        .put_block(&Hash256::zero(), anchor_block(anchor.beacon_state_root))
+        .unwrap();
+    let first_blocks = blocks[1..]
+        .iter()
+        .filter(|block| block.message.slot <= slots_per_restore_point + 2)
+        .cloned()
+        .collect::<Vec<_>>();
+    store
+        .start_state_reconstruction(genesis_state)
+        .unwrap()
+        .apply_blocks(&*store, first_blocks)
+        .unwrap();
+    let mut reconstruction = store
+        .resume_state_reconstruction()
+        .unwrap()
+        .expect("reconstruction should be resumable");
+    assert_eq!(reconstruction.slot(), slots_per_restore_point);
+
+    // Replaying the rest of the blocks in batches should make every historic block and state
+    // available.
+    let remaining_blocks = blocks
+        .iter()
+        .filter(|block| block.message.slot > reconstruction.slot())
+        .cloned()
+        .collect::<Vec<_>>();
+    for batch in remaining_blocks.chunks(16) {
+        reconstruction = reconstruction
+            .apply_blocks(&*store, batch.to_vec())
+            .unwrap();
+    }
+    reconstruction.finish(&*store).unwrap();
+    assert_eq!(store.get_anchor_info(), None);
+    assert!(store.resume_state_reconstruction().unwrap().is_none());
+
+    for snapshot in history {
+        assert_eq!(
+            store.get_block(&snapshot.beacon_block_root).unwrap(),
+            Some(snapshot.beacon_block.clone())
+        );
+    }
+    let last_restore_point =
+        (anchor_slot.as_u64() - 1) / slots_per_restore_point * slots_per_restore_point;
+    for slot in (0..=last_restore_point).map(Slot::new) {
+        let state_root = state_roots[&slot];
+        let state = store
+            .load_cold_state(&state_root)
+            .unwrap()
+            .expect("state should be reconstructed");
+        assert_eq!(state.slot, slot);
+        assert_eq!(state.canonical_root(), state_root);
+    }
+}
+
+#[test]
+fn delete_temp_states() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let state = harness.chain.head().unwrap().beacon_state;
+
+    let temp_root = Hash256::repeat_byte(1);
+    let confirmed_root = Hash256::repeat_byte(2);
+    for root in &[temp_root, confirmed_root] {
+        store
+            .do_atomically(vec![
+                StoreOp::PutStateTemporaryFlag((*root).into()),
+                StoreOp::PutState((*root).into(), Cow::Borrowed(&state)),
+            ])
+            .unwrap();
+    }
+    store
+        .do_atomically(vec![StoreOp::DeleteStateTemporaryFlag(
+            confirmed_root.into(),
+        )])
+        .unwrap();
+
+    store.delete_temp_states().unwrap();
+
+    assert!(store.get_state(&temp_root, None).unwrap().is_none());
+    assert!(store.get_state(&confirmed_root, None).unwrap().is_some());
+}
+
+#[test]
+fn failed_import_keeps_existing_skip_states() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let mut harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let genesis_state = harness.get_current_state();
+
+    // A canonical block at slot 4 writes the skipped states at slots 2 and 3.
+    let (_, _, state_1) = harness.add_block_at_slot(Slot::new(1), genesis_state);
+    let (_, _, state_4) = harness.add_block_at_slot(Slot::new(4), state_1.clone());
+    let skip_roots = [
+        *state_4.get_state_root(Slot::new(2)).unwrap(),
+        *state_4.get_state_root(Slot::new(3)).unwrap(),
+    ];
+
+    // A competing block at slot 3 passes through the same skipped state at slot 2, but fails
+    // after the state transition due to its invalid state root.
+    let (block, state) = harness.make_block(state_1, Slot::new(3));
+    let mut invalid_block = block.message;
+    invalid_block.state_root = Hash256::repeat_byte(42);
+    let proposer_index = invalid_block.proposer_index as usize;
+    let invalid_block = invalid_block.sign(
+        &harness.validators_keypairs[proposer_index].sk,
+        &state.fork,
+        state.genesis_validators_root,
+        &harness.spec,
+    );
+    assert!(harness.chain.process_block(invalid_block).is_err());
+
+    store.delete_temp_states().unwrap();
+
+    for state_root in &skip_roots {
+        assert!(
+            store.load_hot_state_summary(state_root).unwrap().is_some(),
+            "canonical skipped states should not be deleted"
+        );
+    }
+}
+
+#[test]
+fn state_cache_invalidation() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        E::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head().unwrap();
+    let state_root = head.beacon_state_root;
+    let slot = head.beacon_state.slot;
+
+    // The second load is served from the cache, and must be identical.
+    let loaded = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+    let cached = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+    assert_eq!(loaded.tree_hash_root(), state_root);
+    assert_eq!(cached.tree_hash_root(), state_root);
+
+    // Deleting the state must also remove it from the cache.
+    store.delete_state(&state_root, slot).unwrap();
+    assert_eq!(store.get_state(&state_root, Some(slot)).unwrap(), None);
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -1653,7 +2090,10 @@ fn check_finalization(harness: &TestHarness, expected_slot: u64) {
 }
 
 /// Check that the HotColdDB's split_slot is equal to the start slot of the last finalized epoch.
-fn check_split_slot(harness: &TestHarness, store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>) {
+fn check_split_slot(
+    harness: &TestHarness,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
+) {
     let split_slot = store.get_split_slot();
     assert_eq!(
         harness
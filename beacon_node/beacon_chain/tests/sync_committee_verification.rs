@@ -0,0 +1,289 @@
+#![cfg(not(debug_assertions))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use beacon_chain::{
+    test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, NullMigratorEphemeralHarnessType,
+    },
+    BeaconChain, BeaconChainTypes, SyncCommitteeError,
+};
+use store::config::StoreConfig;
+use types::{
+    test_utils::generate_deterministic_keypair, Epoch, EthSpec, Hash256, Keypair, MinimalEthSpec,
+    SignedContributionAndProof, Slot, SyncCommitteeContribution, SyncCommitteeMessage,
+};
+
+pub type E = MinimalEthSpec;
+
+pub const VALIDATOR_COUNT: usize = 64;
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> = types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+/// Returns a beacon chain harness which is past the Altair fork.
+fn get_harness() -> BeaconChainHarness<NullMigratorEphemeralHarnessType<E>> {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+
+    let harness = BeaconChainHarness::new_with_spec(
+        MinimalEthSpec,
+        KEYPAIRS[..].to_vec(),
+        spec,
+        StoreConfig::default(),
+    );
+
+    harness.advance_slot();
+
+    harness
+}
+
+/// Returns a `SyncCommitteeMessage` that is valid for the current slot, along with the subnet on
+/// which it should be published and the position of the signer in that subcommittee.
+fn get_valid_sync_committee_message<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> (SyncCommitteeMessage, u64, usize) {
+    let slot = chain.slot().expect("should get slot");
+    let head_root = chain.head_info().expect("should get head").block_root;
+    let subcommittee_index = 0;
+    let position = 0;
+
+    let validator_index = chain
+        .sync_subcommittee_indices(slot, subcommittee_index)
+        .expect("should get subcommittee")[position];
+
+    let message = sign_message(chain, slot, head_root, validator_index);
+
+    (message, subcommittee_index, position)
+}
+
+fn sign_message<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    slot: Slot,
+    beacon_block_root: Hash256,
+    validator_index: u64,
+) -> SyncCommitteeMessage {
+    SyncCommitteeMessage::new::<T::EthSpec>(
+        slot,
+        beacon_block_root,
+        validator_index,
+        &generate_deterministic_keypair(validator_index as usize).sk,
+        &chain
+            .spec
+            .fork_at_epoch(slot.epoch(T::EthSpec::slots_per_epoch())),
+        chain.genesis_validators_root,
+        &chain.spec,
+    )
+}
+
+/// Returns a `SignedContributionAndProof` that is valid for the current slot, aggregated from a
+/// single message.
+fn get_valid_contribution<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> SignedContributionAndProof<T::EthSpec> {
+    let (message, subcommittee_index, position) = get_valid_sync_committee_message(chain);
+    let aggregator_index = message.validator_index;
+    let slot = message.slot;
+
+    let contribution =
+        SyncCommitteeContribution::from_message(&message, subcommittee_index, position)
+            .expect("should build contribution");
+
+    SignedContributionAndProof::from_aggregate(
+        aggregator_index,
+        contribution,
+        None,
+        &generate_deterministic_keypair(aggregator_index as usize).sk,
+        &chain
+            .spec
+            .fork_at_epoch(slot.epoch(T::EthSpec::slots_per_epoch())),
+        chain.genesis_validators_root,
+        &chain.spec,
+    )
+}
+
+/// Tests verification of `SyncCommitteeMessage` from the gossip network.
+#[test]
+fn sync_committee_message_gossip_verification() {
+    let harness = get_harness();
+    let chain = &harness.chain;
+
+    let (valid_message, subnet_id, _) = get_valid_sync_committee_message(chain);
+
+    /*
+     * The message's slot is for the current slot, with a clock disparity allowance.
+     */
+
+    let mut future_message = valid_message.clone();
+    future_message.slot = chain.slot().unwrap() + 1;
+    assert!(matches!(
+        chain.verify_sync_committee_message_for_gossip(future_message, subnet_id),
+        Err(SyncCommitteeError::FutureSlot { .. })
+    ));
+
+    /*
+     * The subnet must be less than `SYNC_COMMITTEE_SUBNET_COUNT`.
+     */
+
+    assert!(matches!(
+        chain.verify_sync_committee_message_for_gossip(
+            valid_message.clone(),
+            chain.spec.sync_committee_subnet_count
+        ),
+        Err(SyncCommitteeError::InvalidSubcommittee { .. })
+    ));
+
+    /*
+     * The validator must be a member of the subcommittee for the subnet.
+     */
+
+    let wrong_subnet = (1..chain.spec.sync_committee_subnet_count)
+        .find(|subnet| {
+            !chain
+                .sync_subcommittee_indices(valid_message.slot, *subnet)
+                .unwrap()
+                .contains(&valid_message.validator_index)
+        })
+        .expect("the validator should be absent from some subnet");
+    assert!(matches!(
+        chain.verify_sync_committee_message_for_gossip(valid_message.clone(), wrong_subnet),
+        Err(SyncCommitteeError::ValidatorNotInSubcommittee { .. })
+    ));
+
+    /*
+     * The signature must be valid for the validator.
+     */
+
+    let mut bad_signature = valid_message.clone();
+    bad_signature.beacon_block_root = Hash256::repeat_byte(42);
+    assert!(matches!(
+        chain.verify_sync_committee_message_for_gossip(bad_signature, subnet_id),
+        Err(SyncCommitteeError::InvalidSignature)
+    ));
+
+    /*
+     * A valid message is accepted and can be added to the pool.
+     */
+
+    chain
+        .verify_sync_committee_message_for_gossip(valid_message.clone(), subnet_id)
+        .expect("valid message should be verified")
+        .add_to_pool(chain)
+        .expect("valid message should be added to the pool");
+
+    /*
+     * Only the first valid message from a validator is propagated.
+     */
+
+    assert!(matches!(
+        chain.verify_sync_committee_message_for_gossip(valid_message, subnet_id),
+        Err(SyncCommitteeError::PriorSyncCommitteeMessageKnown { .. })
+    ));
+}
+
+/// Tests verification of `SignedContributionAndProof` from the gossip network.
+#[test]
+fn sync_contribution_gossip_verification() {
+    let harness = get_harness();
+    let chain = &harness.chain;
+
+    let valid_contribution = get_valid_contribution(chain);
+
+    /*
+     * The contribution must have at least one participant.
+     */
+
+    let mut empty = valid_contribution.clone();
+    empty.message.contribution.aggregation_bits = Default::default();
+    assert!(matches!(
+        chain.verify_sync_contribution_for_gossip(empty),
+        Err(SyncCommitteeError::EmptyAggregationBitfield)
+    ));
+
+    /*
+     * The subcommittee index must be less than `SYNC_COMMITTEE_SUBNET_COUNT`.
+     */
+
+    let mut bad_subcommittee = valid_contribution.clone();
+    bad_subcommittee.message.contribution.subcommittee_index =
+        chain.spec.sync_committee_subnet_count;
+    assert!(matches!(
+        chain.verify_sync_contribution_for_gossip(bad_subcommittee),
+        Err(SyncCommitteeError::InvalidSubcommittee { .. })
+    ));
+
+    /*
+     * The outer signature must be valid for the aggregator.
+     */
+
+    let mut bad_signature = valid_contribution.clone();
+    bad_signature.signature = valid_contribution.message.selection_proof.clone();
+    assert!(matches!(
+        chain.verify_sync_contribution_for_gossip(bad_signature),
+        Err(SyncCommitteeError::InvalidSignature)
+    ));
+
+    /*
+     * A valid contribution is accepted and can be added to the pool.
+     */
+
+    chain
+        .verify_sync_contribution_for_gossip(valid_contribution.clone())
+        .expect("valid contribution should be verified")
+        .add_to_pool(chain)
+        .expect("valid contribution should be added to the pool");
+
+    /*
+     * Only the first valid contribution from an aggregator is propagated.
+     */
+
+    assert!(matches!(
+        chain.verify_sync_contribution_for_gossip(valid_contribution),
+        Err(SyncCommitteeError::AggregatorAlreadyKnown(_))
+    ));
+}
+
+/// Tests that importing a block produces a `LightClientUpdate` for its parent from the
+/// contributions in the pool.
+#[test]
+fn light_client_update_from_contribution_pool() {
+    let mut harness = get_harness();
+
+    let genesis_root = harness.chain.genesis_block_root;
+    let validator_index = harness
+        .chain
+        .sync_subcommittee_indices(Slot::new(0), 0)
+        .expect("should get subcommittee")[0];
+    let message = sign_message(&harness.chain, Slot::new(0), genesis_root, validator_index);
+    let contribution =
+        SyncCommitteeContribution::from_message(&message, 0, 0).expect("should build contribution");
+    harness
+        .chain
+        .add_to_sync_contribution_pool(&contribution)
+        .expect("should add contribution to the pool");
+
+    assert!(harness
+        .chain
+        .get_light_client_update(0)
+        .expect("should read update")
+        .is_none());
+
+    harness.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+
+    let update = harness
+        .chain
+        .get_light_client_update(0)
+        .expect("should read update")
+        .expect("should produce update");
+
+    assert_eq!(update.header.slot, Slot::new(0));
+    assert_eq!(update.sync_committee_aggregate.num_set_bits(), 1);
+    assert!(!update.is_finality_update());
+}
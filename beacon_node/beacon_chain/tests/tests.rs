@@ -15,7 +15,10 @@ use state_processing::{
     per_slot_processing, per_slot_processing::Error as SlotProcessingError, EpochProcessingError,
 };
 use store::config::StoreConfig;
-use types::{BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Slot};
+use types::{
+    BeaconStateError, Checkpoint, Epoch, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch,
+    Slot,
+};
 
 // Should ideally be divisible by 3.
 pub const VALIDATOR_COUNT: usize = 24;
@@ -224,6 +227,67 @@ fn finalizes_with_full_participation() {
     );
 }
 
+#[test]
+fn verifies_weak_subjectivity_checkpoint() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let mut harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head = harness.chain.head().expect("should get head");
+    let wss_epoch = Epoch::new(1);
+    let wss_root = harness
+        .chain
+        .root_at_slot(wss_epoch.start_slot(MinimalEthSpec::slots_per_epoch()))
+        .expect("should read db")
+        .expect("should find root");
+
+    let valid = Checkpoint {
+        epoch: wss_epoch,
+        root: wss_root,
+    };
+    assert!(
+        harness
+            .chain
+            .verify_weak_subjectivity_checkpoint(valid, head.beacon_block_root, &head.beacon_state)
+            .is_ok(),
+        "canonical checkpoint should verify"
+    );
+
+    let invalid = Checkpoint {
+        epoch: wss_epoch,
+        root: Hash256::repeat_byte(42),
+    };
+    assert!(
+        harness
+            .chain
+            .verify_weak_subjectivity_checkpoint(
+                invalid,
+                head.beacon_block_root,
+                &head.beacon_state
+            )
+            .is_err(),
+        "unknown checkpoint root should fail verification"
+    );
+
+    let future = Checkpoint {
+        epoch: head.beacon_state.finalized_checkpoint.epoch + 1,
+        root: Hash256::repeat_byte(42),
+    };
+    assert!(
+        harness
+            .chain
+            .verify_weak_subjectivity_checkpoint(future, head.beacon_block_root, &head.beacon_state)
+            .is_ok(),
+        "checkpoints beyond finalization cannot be verified yet"
+    );
+}
+
 #[test]
 fn finalizes_with_two_thirds_participation() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
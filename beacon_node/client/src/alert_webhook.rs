@@ -0,0 +1,52 @@
+use beacon_chain::chain_alerts::{ChainAlert, ChainAlertHook};
+use environment::TaskExecutor;
+use reqwest::header::CONTENT_TYPE;
+use slog::{debug, warn, Logger};
+
+/// Returns a `ChainAlertHook` which POSTs each alert to `url` as JSON.
+///
+/// Requests are sent asynchronously on `executor` so that a slow or unresponsive webhook cannot
+/// delay the beacon chain.
+pub fn webhook_alert_hook(url: String, executor: TaskExecutor, log: Logger) -> ChainAlertHook {
+    let client = reqwest::Client::new();
+
+    Box::new(move |alert: &ChainAlert| {
+        let body = match serde_json::to_string(alert) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to serialize chain alert";
+                    "error" => e.to_string(),
+                );
+                return;
+            }
+        };
+
+        let request = client
+            .post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body);
+        let log = log.clone();
+        let alert_name = alert.name();
+
+        executor.spawn(
+            async move {
+                match request
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                {
+                    Ok(_) => debug!(log, "Sent alert webhook"; "alert" => alert_name),
+                    Err(e) => warn!(
+                        log,
+                        "Failed to send alert webhook";
+                        "alert" => alert_name,
+                        "error" => e.to_string(),
+                    ),
+                }
+            },
+            "alert_webhook",
+        );
+    })
+}
@@ -1,5 +1,7 @@
+use crate::alert_webhook::webhook_alert_hook;
 use crate::config::{ClientGenesis, Config as ClientConfig};
 use crate::notifier::spawn_notifier;
+use crate::state_reconstruction::reconstruct_historic_states;
 use crate::Client;
 use beacon_chain::events::TeeEventHandler;
 use beacon_chain::{
@@ -7,33 +9,42 @@ use beacon_chain::{
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     migrate::{BackgroundMigrator, Migrate},
     slot_clock::{SlotClock, SystemTimeSlotClock},
-    store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
-    BeaconChain, BeaconChainTypes, Eth1ChainBackend, EventHandler,
+    store::{
+        metadata::CURRENT_SCHEMA_VERSION, schema_change::migrate_schema, BeaconNodeBackend,
+        HotColdDB, ItemStore, StoreConfig,
+    },
+    BeaconChain, BeaconChainTypes, Eth1ChainBackend, EventHandler, SLASHER_STREAM_CAPACITY,
 };
 use bus::Bus;
 use environment::RuntimeContext;
-use eth1::{Config as Eth1Config, Service as Eth1Service};
+use eth1::{redact_endpoint, Config as Eth1Config, DepositTreeSnapshot, Service as Eth1Service};
 use eth2_config::Eth2Config;
+use eth2_libp2p::discovery::{build_enr, CombinedKey, CombinedKeyExt, Keypair};
 use eth2_libp2p::NetworkGlobals;
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
 use parking_lot::Mutex;
-use slog::info;
+use slasher::Slasher;
+use slasher_service::SlasherService;
+use slog::{error, info};
 use ssz::Decode;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use timer::spawn_timer;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tree_hash::TreeHash;
 use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
+    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec, Hash256,
     SignedBeaconBlockHash,
 };
 use websocket_server::{Config as WebSocketConfig, WebSocketSender};
 
 /// Interval between polling the eth1 node for genesis information.
 pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
+/// Timeout for downloading the genesis state from a URL.
+pub const GENESIS_STATE_DOWNLOAD_TIMEOUT_SECS: u64 = 300;
 
 /// Builds a `Client` instance.
 ///
@@ -137,6 +148,10 @@ where
         let disabled_forks = config.disabled_forks.clone();
         let chain_config = config.chain.clone();
         let graffiti = config.graffiti;
+        let alert_webhook_url = config.alert_webhook_url.clone();
+        let slasher_config = config.slasher.clone();
+        let slasher_stream = config.slasher_stream;
+        let read_only = config.store.read_only;
 
         let store =
             store.ok_or_else(|| "beacon_chain_start_method requires a store".to_string())?;
@@ -148,7 +163,7 @@ where
         let spec = chain_spec
             .ok_or_else(|| "beacon_chain_start_method requires a chain spec".to_string())?;
 
-        let builder = BeaconChainBuilder::new(eth_spec_instance)
+        let mut builder = BeaconChainBuilder::new(eth_spec_instance)
             .logger(context.log().clone())
             .store(store)
             .store_migrator(store_migrator)
@@ -158,10 +173,50 @@ where
             .disabled_forks(disabled_forks)
             .graffiti(graffiti);
 
+        if let Some(url) = alert_webhook_url {
+            info!(
+                context.log(),
+                "Sending chain alerts to webhook";
+                "url" => &url,
+            );
+            builder = builder.alert_hook(webhook_alert_hook(
+                url,
+                context.executor.clone(),
+                context.log().clone(),
+            ));
+        }
+
+        if let Some(slasher_config) = slasher_config {
+            info!(
+                context.log(),
+                "Starting slasher";
+                "database_path" => format!("{:?}", slasher_config.database_path),
+            );
+            let slasher = Slasher::open(slasher_config, context.log().clone())
+                .map_err(|e| format!("Failed to open slasher: {:?}", e))?;
+            builder = builder.slasher(Arc::new(slasher));
+        }
+
+        if slasher_stream {
+            info!(
+                context.log(),
+                "Streaming attestations and blocks to remote slashers";
+                "capacity" => SLASHER_STREAM_CAPACITY,
+            );
+            builder =
+                builder.slasher_stream(Arc::new(Mutex::new(Bus::new(SLASHER_STREAM_CAPACITY))));
+        }
+
         let chain_exists = builder
             .store_contains_beacon_chain()
             .unwrap_or_else(|_| false);
 
+        if read_only && !chain_exists {
+            return Err(
+                "A read-only beacon node requires an existing beacon chain database".into(),
+            );
+        }
+
         // If the client is expect to resume but there's no beacon chain in the database,
         // use the `DepositContract` method. This scenario is quite common when the client
         // is shutdown before finding genesis via eth1.
@@ -200,11 +255,40 @@ where
 
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
+            ClientGenesis::GenesisStateUrl {
+                url,
+                genesis_state_root,
+            } => {
+                info!(
+                    context.log(),
+                    "Downloading genesis state";
+                    "url" => &url,
+                );
+
+                let genesis_state = download_genesis_state(&url, genesis_state_root).await?;
+
+                info!(
+                    context.log(),
+                    "Downloaded genesis state";
+                    "genesis_time" => genesis_state.genesis_time,
+                    "validator_count" => genesis_state.validators.len(),
+                );
+
+                builder.genesis_state(genesis_state).map(|v| (v, None))?
+            }
             ClientGenesis::DepositContract => {
                 info!(
                     context.log(),
                     "Waiting for eth2 genesis from eth1";
-                    "eth1_endpoint" => &config.eth1.endpoint,
+                    "eth1_endpoints" => format!(
+                        "{:?}",
+                        config
+                            .eth1
+                            .endpoints
+                            .iter()
+                            .map(|endpoint| redact_endpoint(endpoint))
+                            .collect::<Vec<_>>()
+                    ),
                     "contract_deploy_block" => config.eth1.deposit_contract_deploy_block,
                     "deposit_contract" => &config.eth1.deposit_contract_address
                 );
@@ -257,6 +341,32 @@ where
         Ok(self)
     }
 
+    /// Provides the network state required by other services without starting the networking
+    /// stack, for a node which does not participate in the network (e.g., a read-only node).
+    ///
+    /// Sending a message to the network fails, since there is no network service to receive it.
+    pub fn offline_network(mut self, config: &NetworkConfig) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "offline_network requires a beacon chain")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "offline_network requires a runtime_context")?
+            .service_context("network".into());
+
+        let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1())?;
+        let enr = build_enr::<TEthSpec>(&enr_key, config, beacon_chain.enr_fork_id())?;
+        let network_globals = NetworkGlobals::new(enr, 0, 0, vec![], context.log());
+        let (network_send, _network_recv) = mpsc::unbounded_channel();
+
+        self.network_globals = Some(Arc::new(network_globals));
+        self.network_send = Some(network_send);
+
+        Ok(self)
+    }
+
     /// Immediately starts the timer service.
     fn timer(self) -> Result<Self, String> {
         let context = self
@@ -331,6 +441,75 @@ where
         Ok(self)
     }
 
+    /// Immediately starts the slasher service, if the beacon chain has a slasher.
+    ///
+    /// The slashings found by the slasher are published on the network.
+    pub fn slasher_service(self) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "slasher service requires a beacon chain")?;
+
+        if beacon_chain.slasher.is_none() {
+            return Ok(self);
+        }
+
+        let network_send = self
+            .network_send
+            .clone()
+            .ok_or_else(|| "slasher service requires a network sender")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "slasher service requires a runtime_context")?
+            .service_context("slasher_service".into());
+
+        SlasherService::new(beacon_chain, network_send).run(&context.executor)?;
+
+        Ok(self)
+    }
+
+    /// Immediately starts rebuilding the historic states prior to the anchor of the database in
+    /// the background, by replaying the blocks of the archive beacon node at `url`.
+    pub fn historic_state_reconstruction(self, url: &str) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "state reconstruction requires a beacon chain")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "state reconstruction requires a runtime_context")?
+            .service_context("state_reconstruction".into());
+        let log = context.log().clone();
+
+        if beacon_chain.store.get_anchor_info().is_none() {
+            info!(
+                log,
+                "Historic states are available";
+                "msg" => "the database was initialized from genesis, no reconstruction required"
+            );
+            return Ok(self);
+        }
+
+        let store = beacon_chain.store.clone();
+        let url = url.to_string();
+        context.executor.spawn(
+            async move {
+                if let Err(e) = reconstruct_historic_states(store, url, log.clone()).await {
+                    error!(
+                        log,
+                        "Historic state reconstruction failed";
+                        "error" => e,
+                    );
+                }
+            },
+            "state_reconstruction",
+        );
+
+        Ok(self)
+    }
+
     /// Immediately starts the service that periodically logs information each slot.
     pub fn notifier(self) -> Result<Self, String> {
         let context = self
@@ -492,13 +671,14 @@ impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler>
             TEth1Backend,
             TEthSpec,
             TEventHandler,
-            LevelDB<TEthSpec>,
-            LevelDB<TEthSpec>,
+            BeaconNodeBackend<TEthSpec>,
+            BeaconNodeBackend<TEthSpec>,
         >,
     >
 where
     TSlotClock: SlotClock + 'static,
-    TStoreMigrator: Migrate<TEthSpec, LevelDB<TEthSpec>, LevelDB<TEthSpec>> + 'static,
+    TStoreMigrator:
+        Migrate<TEthSpec, BeaconNodeBackend<TEthSpec>, BeaconNodeBackend<TEthSpec>> + 'static,
     TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
     TEthSpec: EthSpec + 'static,
     TEventHandler: EventHandler<TEthSpec> + 'static,
@@ -521,8 +701,35 @@ where
             .ok_or_else(|| "disk_store requires a chain spec".to_string())?;
 
         let store = HotColdDB::open(hot_path, cold_path, config, spec, context.log().clone())
+            .map(Arc::new)
             .map_err(|e| format!("Unable to open database: {:?}", e))?;
-        self.store = Some(Arc::new(store));
+
+        if store.get_config().read_only {
+            // A read-only database cannot be migrated, so it must already be up to date.
+            let schema_version = store
+                .load_schema_version()
+                .map_err(|e| format!("Unable to read database schema version: {:?}", e))?;
+            if schema_version != Some(CURRENT_SCHEMA_VERSION) {
+                return Err(format!(
+                    "Read-only database has schema version {:?}, expected {}. Start the beacon \
+                     node without --read-only to migrate it.",
+                    schema_version.map(|v| v.as_u64()),
+                    CURRENT_SCHEMA_VERSION.as_u64()
+                ));
+            }
+        } else {
+            // Upgrade the database to the current schema, backing up its metadata alongside it.
+            let backup_dir = hot_path.parent().unwrap_or(hot_path);
+            migrate_schema(store.clone(), backup_dir)
+                .map_err(|e| format!("Unable to migrate database schema: {:?}", e))?;
+
+            // Remove any states left behind by a block import that did not complete.
+            store
+                .delete_temp_states()
+                .map_err(|e| format!("Unable to delete temporary states: {:?}", e))?;
+        }
+
+        self.store = Some(store);
         Ok(self)
     }
 }
@@ -598,14 +805,18 @@ where
             .clone()
             .ok_or_else(|| "caching_eth1_backend requires a chain spec".to_string())?;
 
-        // Check if the eth1 endpoint we connect to is on the correct network id.
-        let network_id =
-            eth1::http::get_network_id(&config.endpoint, Duration::from_millis(15_000)).await?;
-
-        if network_id != config.network_id {
+        // Check that at least one of the eth1 endpoints is usable (e.g., is on the correct network
+        // id). The eth1 service falls back between the endpoints whilst it is running.
+        let statuses =
+            eth1::check_endpoints(&config.endpoints, &config.network_id, config.chain_id).await;
+        if !statuses.iter().any(Result::is_ok) {
             return Err(format!(
-                "Invalid eth1 network id. Expected {:?}, got {:?}",
-                config.network_id, network_id
+                "No usable eth1 endpoint. Errors: {:?}",
+                config
+                    .endpoints
+                    .iter()
+                    .zip(statuses.iter())
+                    .collect::<Vec<_>>()
             ));
         }
 
@@ -623,25 +834,42 @@ where
             eth1_service_from_genesis.drop_block_cache();
 
             CachingEth1Backend::from_service(eth1_service_from_genesis)
+        } else if let Some(persisted) = beacon_chain_builder.get_persisted_eth1_backend()? {
+            let backend = Eth1Chain::from_ssz_container(
+                &persisted,
+                config.clone(),
+                &context.log().clone(),
+                spec.clone(),
+            )?
+            .into_backend();
+
+            info!(
+                context.log(),
+                "Loaded eth1 cache from disk";
+                "deposits" => backend.core.deposit_cache_len(),
+                "blocks" => backend.core.block_cache_len(),
+                "last_processed_block" => backend.core.deposits().read().last_processed_block,
+            );
+
+            backend
+        } else if let Some(location) = config.deposit_snapshot.clone() {
+            let snapshot = DepositTreeSnapshot::load(&location).await?;
+
+            info!(
+                context.log(),
+                "Loaded deposit snapshot";
+                "deposits" => snapshot.deposit_count,
+                "eth1_block" => snapshot.execution_block_height,
+            );
+
+            CachingEth1Backend::from_service(Eth1Service::from_deposit_snapshot(
+                config,
+                snapshot,
+                context.log().clone(),
+                spec.clone(),
+            )?)
         } else {
-            beacon_chain_builder
-                .get_persisted_eth1_backend()?
-                .map(|persisted| {
-                    Eth1Chain::from_ssz_container(
-                        &persisted,
-                        config.clone(),
-                        &context.log().clone(),
-                        spec.clone(),
-                    )
-                    .map(|chain| chain.into_backend())
-                })
-                .unwrap_or_else(|| {
-                    Ok(CachingEth1Backend::new(
-                        config,
-                        context.log().clone(),
-                        spec.clone(),
-                    ))
-                })?
+            CachingEth1Backend::new(config, context.log().clone(), spec.clone())
         };
 
         self.eth1_service = None;
@@ -683,6 +911,25 @@ where
 
         Ok(self)
     }
+
+    /// Use an eth1 backend that can produce blocks without using eth1 at all.
+    ///
+    /// This backend will never produce deposits so it's impossible to add validators after
+    /// genesis. The `Eth1Data` votes will always match the `Eth1Data` in the state.
+    ///
+    /// ## Notes
+    ///
+    /// The client is given the `CachingEth1Backend` type, but the http backend is never started and the
+    /// caches are never used.
+    pub fn disabled_eth1_backend(mut self) -> Result<Self, String> {
+        let beacon_chain_builder = self
+            .beacon_chain_builder
+            .ok_or_else(|| "disabled_eth1_backend requires a beacon_chain_builder")?;
+
+        self.beacon_chain_builder = Some(beacon_chain_builder.disabled_eth1_backend()?);
+
+        Ok(self)
+    }
 }
 
 impl<TStoreMigrator, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
@@ -734,3 +981,39 @@ where
         Ok(self)
     }
 }
+
+/// Download an SSZ-encoded `BeaconState` from `url`.
+///
+/// If `expected_root` is supplied, returns an error unless it matches the tree hash root of the
+/// downloaded state.
+async fn download_genesis_state<E: EthSpec>(
+    url: &str,
+    expected_root: Option<Hash256>,
+) -> Result<BeaconState<E>, String> {
+    let bytes = reqwest::Client::new()
+        .get(url)
+        .header("Accept", "application/octet-stream")
+        .timeout(Duration::from_secs(GENESIS_STATE_DOWNLOAD_TIMEOUT_SECS))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Unable to download genesis state from {}: {:?}", url, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Unable to read genesis state from {}: {:?}", url, e))?;
+
+    let genesis_state = BeaconState::from_ssz_bytes(&bytes)
+        .map_err(|e| format!("Unable to parse downloaded genesis state SSZ: {:?}", e))?;
+
+    if let Some(expected_root) = expected_root {
+        let root = genesis_state.tree_hash_root();
+        if root != expected_root {
+            return Err(format!(
+                "Downloaded genesis state has root {:?}, expected {:?}",
+                root, expected_root
+            ));
+        }
+    }
+
+    Ok(genesis_state)
+}
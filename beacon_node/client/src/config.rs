@@ -2,7 +2,7 @@ use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use types::Graffiti;
+use types::{Graffiti, Hash256};
 
 pub const DEFAULT_DATADIR: &str = ".lighthouse";
 
@@ -30,6 +30,14 @@ pub enum ClientGenesis {
     /// We include the bytes instead of the `BeaconState<E>` because the `EthSpec` type
     /// parameter would be very annoying.
     SszBytes { genesis_state_bytes: Vec<u8> },
+    /// Downloads the SSZ-encoded `BeaconState` from `url`.
+    ///
+    /// If `genesis_state_root` is supplied, the tree hash root of the downloaded state must match
+    /// it.
+    GenesisStateUrl {
+        url: String,
+        genesis_state_root: Option<Hash256>,
+    },
 }
 
 impl Default for ClientGenesis {
@@ -53,10 +61,23 @@ pub struct Config {
     /// This is the method used for the 2019 client interop in Canada.
     pub dummy_eth1_backend: bool,
     pub sync_eth1_chain: bool,
+    /// If true, the node will not use eth1 at all. Blocks will vote for the existing eth1 data
+    /// and never include deposits.
+    pub disable_eth1: bool,
     /// A list of hard-coded forks that will be disabled.
     pub disabled_forks: Vec<String>,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Graffiti,
+    /// If set, each `ChainAlert` raised by the beacon chain is POSTed to this URL as JSON.
+    pub alert_webhook_url: Option<String>,
+    /// If set, the slasher is run using this config.
+    pub slasher: Option<slasher::Config>,
+    /// If true, verified attestations and blocks are streamed to slashers in other processes via
+    /// the HTTP API.
+    pub slasher_stream: bool,
+    /// If set, the historic states prior to the anchor of the database are rebuilt in the
+    /// background by replaying the blocks of the archive beacon node at this URL.
+    pub reconstruct_historic_states: Option<String>,
     #[serde(skip)]
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
@@ -85,9 +106,14 @@ impl Default for Config {
             spec_constants: TESTNET_SPEC_CONSTANTS.into(),
             dummy_eth1_backend: false,
             sync_eth1_chain: false,
+            disable_eth1: false,
             eth1: <_>::default(),
             disabled_forks: Vec::new(),
             graffiti: Graffiti::default(),
+            alert_webhook_url: None,
+            slasher: None,
+            slasher_stream: false,
+            reconstruct_historic_states: None,
         }
     }
 }
@@ -1,14 +1,16 @@
 extern crate slog;
 
+mod alert_webhook;
 pub mod config;
 mod metrics;
 mod notifier;
+mod state_reconstruction;
 
 pub mod builder;
 pub mod error;
 
 use beacon_chain::BeaconChain;
-use eth2_libp2p::{Enr, Multiaddr, NetworkGlobals};
+use eth2_libp2p::{Enr, Multiaddr, NetworkGlobals, PeerId};
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -16,6 +18,7 @@ pub use beacon_chain::{BeaconChainTypes, Eth1ChainBackend};
 pub use builder::ClientBuilder;
 pub use config::{ClientGenesis, Config as ClientConfig};
 pub use eth2_config::Eth2Config;
+pub use state_reconstruction::reconstruct_historic_states;
 
 /// The core "beacon node" client.
 ///
@@ -57,4 +60,9 @@ impl<T: BeaconChainTypes> Client<T> {
     pub fn enr(&self) -> Option<Enr> {
         self.network_globals.as_ref().map(|n| n.local_enr())
     }
+
+    /// Returns the libp2p peer id of this node.
+    pub fn local_peer_id(&self) -> Option<PeerId> {
+        self.network_globals.as_ref().map(|n| n.local_peer_id())
+    }
 }
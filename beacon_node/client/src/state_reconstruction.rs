@@ -0,0 +1,140 @@
+use remote_beacon_node::RemoteBeaconNode;
+use slog::{debug, info, Logger};
+use std::sync::Arc;
+use store::{HotColdDB, ItemStore};
+use types::{EthSpec, SignedBeaconBlock};
+
+/// The number of blocks loaded from the database at a time to be replayed.
+const BLOCKS_PER_BATCH: usize = 64;
+
+/// The number of blocks downloaded from the archive node between progress logs.
+const BLOCKS_PER_DOWNLOAD_LOG: usize = 1024;
+
+/// Rebuild the historic states of a database which was not initialized from genesis, by
+/// replaying the blocks served by the archive beacon node at `url`.
+///
+/// The blocks are found by following parent roots back from the anchor block, so only the blocks
+/// which aren't already stored are downloaded, and skipped slots cost nothing. An interrupted
+/// reconstruction resumes from the last restore point it stored.
+///
+/// The archive node need not be trusted: each block must have the root its child refers to, and
+/// no historic state becomes available unless the replay arrives at the anchor state.
+pub async fn reconstruct_historic_states<E, Hot, Cold>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    url: String,
+    log: Logger,
+) -> Result<(), String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let node = RemoteBeaconNode::<E>::new(url.clone())?;
+    let beacon = node.http.beacon();
+
+    let resumed = store
+        .resume_state_reconstruction()
+        .map_err(|e| format!("Unable to resume state reconstruction: {:?}", e))?;
+    let mut reconstruction = match resumed {
+        Some(reconstruction) => reconstruction,
+        None => {
+            let genesis_slot = E::genesis_epoch().start_slot(E::slots_per_epoch());
+            info!(log, "Downloading genesis state"; "beacon_node" => &url);
+            let (genesis_state, _) = beacon
+                .get_state_by_slot(genesis_slot)
+                .await
+                .map_err(|e| format!("Unable to download genesis state: {:?}", e))?;
+
+            store
+                .start_state_reconstruction(genesis_state)
+                .map_err(|e| format!("Unable to start state reconstruction: {:?}", e))?
+        }
+    };
+    let start_slot = reconstruction.slot();
+    let anchor_block = reconstruction.anchor_block().clone();
+
+    // Walk back from the anchor block to the replayed state, storing any missing blocks.
+    let mut block_roots = vec![];
+    let mut block_root = anchor_block.message.parent_root;
+    let mut downloaded = 0;
+    loop {
+        let stored_block = store
+            .get_block(&block_root)
+            .map_err(|e| format!("Unable to read block {:?}: {:?}", block_root, e))?;
+        let block = match stored_block {
+            Some(block) => block,
+            None => {
+                let (block, _) = beacon
+                    .get_block_by_root(block_root)
+                    .await
+                    .map_err(|e| format!("Unable to download block {:?}: {:?}", block_root, e))?;
+                if block.canonical_root() != block_root {
+                    return Err(format!(
+                        "Archive node served a block with the wrong root: expected {:?}, got {:?}",
+                        block_root,
+                        block.canonical_root()
+                    ));
+                }
+                store
+                    .store_reconstructed_block(&block)
+                    .map_err(|e| format!("Unable to store block {:?}: {:?}", block_root, e))?;
+
+                downloaded += 1;
+                if downloaded % BLOCKS_PER_DOWNLOAD_LOG == 0 {
+                    info!(
+                        log,
+                        "Downloading historic blocks";
+                        "slot" => block.message.slot,
+                        "downloaded" => downloaded,
+                    );
+                }
+                block
+            }
+        };
+
+        if block.message.slot <= start_slot {
+            break;
+        }
+        block_roots.push(block_root);
+        block_root = block.message.parent_root;
+    }
+    debug!(
+        log,
+        "Found historic blocks";
+        "blocks" => block_roots.len(),
+        "downloaded" => downloaded,
+    );
+
+    // Replay the blocks in slot order, finishing with the anchor block.
+    block_roots.reverse();
+    for batch in block_roots.chunks(BLOCKS_PER_BATCH) {
+        let blocks = batch
+            .iter()
+            .map(|block_root| {
+                store
+                    .get_block(block_root)
+                    .map_err(|e| format!("Unable to read block {:?}: {:?}", block_root, e))?
+                    .ok_or_else(|| format!("Missing block {:?}", block_root))
+            })
+            .collect::<Result<Vec<SignedBeaconBlock<E>>, _>>()?;
+
+        reconstruction = reconstruction
+            .apply_blocks(&*store, blocks)
+            .map_err(|e| format!("Unable to replay blocks: {:?}", e))?;
+
+        info!(
+            log,
+            "Reconstructing historic states";
+            "slot" => reconstruction.slot(),
+            "anchor_slot" => reconstruction.anchor_slot(),
+        );
+    }
+
+    reconstruction = reconstruction
+        .apply_blocks(&*store, vec![anchor_block])
+        .map_err(|e| format!("Unable to replay the anchor block: {:?}", e))?;
+
+    reconstruction
+        .finish(&*store)
+        .map_err(|e| format!("Unable to complete state reconstruction: {:?}", e))
+}
@@ -95,6 +95,16 @@ impl BlockCache {
         }
     }
 
+    /// Removes all blocks with a number greater than or equal to `block_number`, returning the
+    /// number of blocks removed.
+    ///
+    /// Used to remove blocks which are no longer in the canonical eth1 chain.
+    pub fn remove_blocks_from(&mut self, block_number: u64) -> usize {
+        let len = self.blocks.len();
+        self.blocks.retain(|block| block.number < block_number);
+        len - self.blocks.len()
+    }
+
     /// Returns the range of block numbers stored in the block cache. All blocks in this range can
     /// be accessed.
     fn available_block_numbers(&self) -> Option<RangeInclusive<u64>> {
@@ -233,6 +243,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remove_blocks_from() {
+        let n = 16;
+        let blocks = get_blocks(n, 10);
+
+        let mut cache = BlockCache::default();
+
+        for block in blocks.clone() {
+            insert(&mut cache, block).expect("should add consecutive blocks");
+        }
+
+        assert_eq!(cache.clone().remove_blocks_from(n as u64), 0);
+        assert_eq!(cache.clone().remove_blocks_from(0), n);
+
+        assert_eq!(cache.remove_blocks_from(10), 6);
+        assert_eq!(cache.highest_block_number(), Some(9));
+
+        // Blocks can be inserted again after they have been removed.
+        for block in blocks.into_iter().skip(10) {
+            insert(&mut cache, block).expect("should add consecutive blocks");
+        }
+        assert_eq!(cache.len(), n);
+    }
+
     #[test]
     fn inserts() {
         let n = 16;
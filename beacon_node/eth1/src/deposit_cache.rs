@@ -1,4 +1,4 @@
-use crate::DepositLog;
+use crate::{DepositLog, DepositTreeSnapshot};
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
@@ -27,16 +27,54 @@ pub enum Error {
     DepositCountInvalid { deposit_count: u64, range_end: u64 },
     /// Error with the merkle tree for deposits.
     DepositTreeError(merkle_proof::MerkleTreeError),
+    /// The requested deposits are part of the snapshot the cache was initialized from, so their
+    /// proofs cannot be generated.
+    DepositsInSnapshot {
+        requested: u64,
+        snapshot_deposit_count: u64,
+    },
+    /// Deposits were to be removed from a block which is part of the snapshot, which is finalized.
+    SnapshotBlockRemoved {
+        block_number: u64,
+        snapshot_block_number: u64,
+    },
+    /// The deposit root computed from a snapshot does not match the root in the snapshot.
+    InvalidSnapshotRoot {
+        expected: Hash256,
+        computed: Hash256,
+    },
     /// An unexpected condition was encountered.
     InternalError(String),
 }
 
+/// The `SszDepositCache` format used prior to the introduction of deposit snapshots.
+#[derive(Encode, Decode, Clone)]
+pub struct LegacySszDepositCache {
+    logs: Vec<DepositLog>,
+    leaves: Vec<Hash256>,
+    deposit_contract_deploy_block: u64,
+    deposit_roots: Vec<Hash256>,
+}
+
+impl From<LegacySszDepositCache> for SszDepositCache {
+    fn from(legacy: LegacySszDepositCache) -> Self {
+        Self {
+            logs: legacy.logs,
+            leaves: legacy.leaves,
+            deposit_contract_deploy_block: legacy.deposit_contract_deploy_block,
+            deposit_roots: legacy.deposit_roots,
+            snapshot: None,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Clone)]
 pub struct SszDepositCache {
     logs: Vec<DepositLog>,
     leaves: Vec<Hash256>,
     deposit_contract_deploy_block: u64,
     deposit_roots: Vec<Hash256>,
+    snapshot: Option<DepositTreeSnapshot>,
 }
 
 impl SszDepositCache {
@@ -46,12 +84,14 @@ impl SszDepositCache {
             leaves: cache.leaves.clone(),
             deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
             deposit_roots: cache.deposit_roots.clone(),
+            snapshot: cache.snapshot.clone(),
         }
     }
 
     pub fn to_deposit_cache(&self) -> Result<DepositCache, String> {
         let deposit_tree =
-            DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH);
+            create_deposit_tree(self.snapshot.as_ref(), &self.leaves, DEPOSIT_TREE_DEPTH)
+                .map_err(|e| format!("Invalid SszDepositCache: {:?}", e))?;
         // Check for invalid SszDepositCache conditions
         if self.leaves.len() != self.logs.len() {
             return Err("Invalid SszDepositCache: logs and leaves should have equal length".into());
@@ -69,13 +109,39 @@ impl SszDepositCache {
             deposit_contract_deploy_block: self.deposit_contract_deploy_block,
             deposit_tree,
             deposit_roots: self.deposit_roots.clone(),
+            snapshot: self.snapshot.clone(),
         })
     }
 }
 
+/// Creates a deposit tree containing the deposits in `snapshot` (if any), followed by `leaves`.
+fn create_deposit_tree(
+    snapshot: Option<&DepositTreeSnapshot>,
+    leaves: &[Hash256],
+    tree_depth: usize,
+) -> Result<DepositDataTree, Error> {
+    if let Some(snapshot) = snapshot {
+        let mut tree = DepositDataTree::from_finalized(
+            &snapshot.finalized,
+            snapshot.deposit_count as usize,
+            tree_depth,
+        )
+        .map_err(Error::DepositTreeError)?;
+        for leaf in leaves {
+            tree.push_leaf(*leaf).map_err(Error::DepositTreeError)?;
+        }
+        Ok(tree)
+    } else {
+        Ok(DepositDataTree::create(leaves, leaves.len(), tree_depth))
+    }
+}
+
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
+///
+/// If the cache was initialized from a `DepositTreeSnapshot`, `logs` and `leaves` only contain
+/// the deposits which follow the snapshot.
 pub struct DepositCache {
     logs: Vec<DepositLog>,
     leaves: Vec<Hash256>,
@@ -84,8 +150,10 @@ pub struct DepositCache {
     /// deposit contract tree.
     deposit_tree: DepositDataTree,
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
-    /// `deposit_index` `i`.
+    /// `deposit_index` `i` (offset by the snapshot deposit count, if any).
     deposit_roots: Vec<Hash256>,
+    /// The snapshot the cache was initialized from, if any.
+    snapshot: Option<DepositTreeSnapshot>,
 }
 
 impl Default for DepositCache {
@@ -98,6 +166,7 @@ impl Default for DepositCache {
             deposit_contract_deploy_block: 1,
             deposit_tree,
             deposit_roots,
+            snapshot: None,
         }
     }
 }
@@ -112,42 +181,98 @@ impl DepositCache {
         }
     }
 
-    /// Returns the number of deposits available in the cache.
+    /// Create a new `DepositCache` containing the deposits in a finalized `snapshot`.
+    ///
+    /// ## Errors
+    ///
+    /// - If the snapshot is not a valid deposit tree.
+    /// - If the root of the snapshot's tree does not match the snapshot's `deposit_root`.
+    pub fn from_snapshot(
+        deposit_contract_deploy_block: u64,
+        snapshot: DepositTreeSnapshot,
+    ) -> Result<Self, Error> {
+        let deposit_tree = create_deposit_tree(Some(&snapshot), &[], DEPOSIT_TREE_DEPTH)?;
+        if deposit_tree.root() != snapshot.deposit_root {
+            return Err(Error::InvalidSnapshotRoot {
+                expected: snapshot.deposit_root,
+                computed: deposit_tree.root(),
+            });
+        }
+
+        Ok(DepositCache {
+            logs: Vec::new(),
+            leaves: Vec::new(),
+            deposit_contract_deploy_block,
+            deposit_roots: vec![deposit_tree.root()],
+            deposit_tree,
+            snapshot: Some(snapshot),
+        })
+    }
+
+    /// Returns the snapshot the cache was initialized from, if any.
+    pub fn snapshot(&self) -> Option<&DepositTreeSnapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Returns the number of deposits in the snapshot the cache was initialized from.
+    fn snapshot_deposit_count(&self) -> u64 {
+        self.snapshot
+            .as_ref()
+            .map_or(0, |snapshot| snapshot.deposit_count)
+    }
+
+    /// Returns `true` if `block_number` precedes the snapshot the cache was initialized from, in
+    /// which case the deposits up to that block are unknown.
+    fn precedes_snapshot(&self, block_number: u64) -> bool {
+        self.snapshot.as_ref().map_or(false, |snapshot| {
+            block_number < snapshot.execution_block_height
+        })
+    }
+
+    /// Returns the number of deposits available in the cache, including any in the snapshot.
     pub fn len(&self) -> usize {
-        self.logs.len()
+        self.snapshot_deposit_count() as usize + self.logs.len()
     }
 
     /// True if the cache does not store any blocks.
     pub fn is_empty(&self) -> bool {
-        self.logs.is_empty()
+        self.len() == 0
     }
 
     /// Returns the block number for the most recent deposit in the cache.
     pub fn latest_block_number(&self) -> Option<u64> {
-        self.logs.last().map(|log| log.block_number)
+        self.logs.last().map(|log| log.block_number).or_else(|| {
+            self.snapshot
+                .as_ref()
+                .map(|snapshot| snapshot.execution_block_height)
+        })
     }
 
-    /// Returns an iterator over all the logs in `self`.
+    /// Returns an iterator over all the logs in `self`, excluding those in the snapshot.
     pub fn iter(&self) -> impl Iterator<Item = &DepositLog> {
         self.logs.iter()
     }
 
-    /// Returns the i'th deposit log.
+    /// Returns the log for the deposit with index `i`, or `None` if it is unknown or part of
+    /// the snapshot.
     pub fn get(&self, i: usize) -> Option<&DepositLog> {
-        self.logs.get(i)
+        self.logs
+            .get(i.checked_sub(self.snapshot_deposit_count() as usize)?)
     }
 
     /// Adds `log` to self.
     ///
     /// This function enforces that `logs` are imported one-by-one with no gaps between
-    /// `log.index`, starting at `log.index == 0`.
+    /// `log.index`, starting at `log.index == 0` (or the deposit count of the snapshot). Logs
+    /// which are part of the snapshot are ignored.
     ///
     /// ## Errors
     ///
     /// - If a log with index `log.index - 1` is not already present in `self` (ignored when empty).
     /// - If a log with `log.index` is already known, but the given `log` is distinct to it.
     pub fn insert_log(&mut self, log: DepositLog) -> Result<(), Error> {
-        match log.index.cmp(&(self.logs.len() as u64)) {
+        let snapshot_deposit_count = self.snapshot_deposit_count();
+        match log.index.cmp(&(self.len() as u64)) {
             Ordering::Equal => {
                 let deposit = log.deposit_data.tree_hash_root();
                 self.leaves.push(deposit);
@@ -158,8 +283,9 @@ impl DepositCache {
                 self.deposit_roots.push(self.deposit_tree.root());
                 Ok(())
             }
+            Ordering::Less if log.index < snapshot_deposit_count => Ok(()),
             Ordering::Less => {
-                if self.logs[log.index as usize] == log {
+                if self.logs[(log.index - snapshot_deposit_count) as usize] == log {
                     Ok(())
                 } else {
                     Err(Error::DuplicateDistinctLog(log.index))
@@ -167,11 +293,48 @@ impl DepositCache {
             }
             Ordering::Greater => Err(Error::NonConsecutive {
                 log_index: log.index,
-                expected: self.logs.len(),
+                expected: self.len(),
             }),
         }
     }
 
+    /// Removes all deposits in blocks with a number greater than or equal to `block_number`,
+    /// returning the number of deposits removed.
+    ///
+    /// Used to remove deposits which are no longer in the canonical eth1 chain.
+    ///
+    /// ## Errors
+    ///
+    /// - If `block_number` is not after the block of the snapshot the cache was initialized from.
+    pub fn remove_logs_from(&mut self, block_number: u64) -> Result<usize, Error> {
+        if let Some(snapshot) = &self.snapshot {
+            if block_number <= snapshot.execution_block_height {
+                return Err(Error::SnapshotBlockRemoved {
+                    block_number,
+                    snapshot_block_number: snapshot.execution_block_height,
+                });
+            }
+        }
+
+        let retained = self
+            .logs
+            .iter()
+            .take_while(|log| log.block_number < block_number)
+            .count();
+        let removed = self.logs.len() - retained;
+
+        if removed > 0 {
+            self.logs.truncate(retained);
+            self.leaves.truncate(retained);
+            // `deposit_roots` also includes the root prior to the first deposit.
+            self.deposit_roots.truncate(retained + 1);
+            self.deposit_tree =
+                create_deposit_tree(self.snapshot.as_ref(), &self.leaves, DEPOSIT_TREE_DEPTH)?;
+        }
+
+        Ok(removed)
+    }
+
     /// Returns a list of `Deposit` objects, within the given deposit index `range`.
     ///
     /// The `deposit_count` is used to generate the proofs for the `Deposits`. For example, if we
@@ -183,6 +346,7 @@ impl DepositCache {
     ///
     /// - If `deposit_count` is larger than `end`.
     /// - There are not sufficient deposits in the tree to generate the proof.
+    /// - If any of the deposits in the range (or the `deposit_count`) are part of the snapshot.
     pub fn get_deposits(
         &self,
         start: u64,
@@ -190,29 +354,38 @@ impl DepositCache {
         deposit_count: u64,
         tree_depth: usize,
     ) -> Result<(Hash256, Vec<Deposit>), Error> {
+        let snapshot_deposit_count = self.snapshot_deposit_count();
         if deposit_count < end {
             // It's invalid to ask for more deposits than should exist.
             Err(Error::DepositCountInvalid {
                 deposit_count,
                 range_end: end,
             })
-        } else if end > self.logs.len() as u64 {
+        } else if end > self.len() as u64 {
             // The range of requested deposits exceeds the deposits stored locally.
             Err(Error::InsufficientDeposits {
                 requested: end,
-                known_deposits: self.logs.len(),
+                known_deposits: self.len(),
             })
-        } else if deposit_count > self.leaves.len() as u64 {
+        } else if deposit_count > self.len() as u64 {
             // There are not `deposit_count` known deposit roots, so we can't build the merkle tree
             // to prove into.
             Err(Error::InsufficientDeposits {
                 requested: deposit_count,
-                known_deposits: self.logs.len(),
+                known_deposits: self.len(),
+            })
+        } else if (start < end && start < snapshot_deposit_count)
+            || deposit_count < snapshot_deposit_count
+        {
+            // Only the roots of the snapshot's subtrees are known, so we can't prove into them.
+            Err(Error::DepositsInSnapshot {
+                requested: std::cmp::min(start, deposit_count),
+                snapshot_deposit_count,
             })
         } else {
             let leaves = self
                 .leaves
-                .get(0..deposit_count as usize)
+                .get(0..(deposit_count - snapshot_deposit_count) as usize)
                 .ok_or_else(|| Error::InternalError("Unable to get known leaves".into()))?;
 
             // Note: there is likely a more optimal solution than recreating the `DepositDataTree`
@@ -222,11 +395,13 @@ impl DepositCache {
             // last finalized eth1 deposit count. Then, that tree could be cloned and extended for
             // each of these calls.
 
-            let tree = DepositDataTree::create(leaves, deposit_count as usize, tree_depth);
+            let tree = create_deposit_tree(self.snapshot.as_ref(), leaves, tree_depth)?;
 
+            let first_log = (start - snapshot_deposit_count) as usize;
+            let last_log = (end - snapshot_deposit_count) as usize;
             let deposits = self
                 .logs
-                .get(start as usize..end as usize)
+                .get(first_log..last_log)
                 .ok_or_else(|| Error::InternalError("Unable to get known log".into()))?
                 .iter()
                 .map(|deposit_log| {
@@ -246,17 +421,25 @@ impl DepositCache {
     /// Returns the number of deposits with valid signatures that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// The deposits in the snapshot are assumed to have valid signatures, since they are unknown.
+    ///
+    /// Returns `None` if the `block_number` is zero, prior to contract deployment or prior to the
+    /// snapshot.
     pub fn get_valid_signature_count(&self, block_number: u64) -> Option<usize> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || self.precedes_snapshot(block_number)
+        {
             None
         } else {
             Some(
-                self.logs
-                    .iter()
-                    .take_while(|deposit| deposit.block_number <= block_number)
-                    .filter(|deposit| deposit.signature_is_valid)
-                    .count(),
+                self.snapshot_deposit_count() as usize
+                    + self
+                        .logs
+                        .iter()
+                        .take_while(|deposit| deposit.block_number <= block_number)
+                        .filter(|deposit| deposit.signature_is_valid)
+                        .count(),
             )
         }
     }
@@ -264,16 +447,22 @@ impl DepositCache {
     /// Returns the number of deposits that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// Returns `None` if the `block_number` is zero, prior to contract deployment or prior to the
+    /// snapshot.
     pub fn get_deposit_count_from_cache(&self, block_number: u64) -> Option<u64> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || self.precedes_snapshot(block_number)
+        {
             None
         } else {
             Some(
-                self.logs
-                    .iter()
-                    .take_while(|deposit| deposit.block_number <= block_number)
-                    .count() as u64,
+                self.snapshot_deposit_count()
+                    + self
+                        .logs
+                        .iter()
+                        .take_while(|deposit| deposit.block_number <= block_number)
+                        .count() as u64,
             )
         }
     }
@@ -283,7 +472,8 @@ impl DepositCache {
     /// Fetches the `deposit_count` on or just before the queried `block_number`
     /// and queries the `deposit_roots` map to get the corresponding `deposit_root`.
     pub fn get_deposit_root_from_cache(&self, block_number: u64) -> Option<Hash256> {
-        let index = self.get_deposit_count_from_cache(block_number)?;
+        let index =
+            self.get_deposit_count_from_cache(block_number)? - self.snapshot_deposit_count();
         Some(*self.deposit_roots.get(index as usize)?)
     }
 }
@@ -293,6 +483,7 @@ pub mod tests {
     use super::*;
     use crate::deposit_log::tests::EXAMPLE_LOG;
     use crate::http::Log;
+    use crate::inner::DepositUpdater;
     use types::{EthSpec, MainnetEthSpec};
 
     pub const TREE_DEPTH: usize = 32;
@@ -345,6 +536,46 @@ pub mod tests {
         assert!(tree.insert_log(log).is_err());
     }
 
+    #[test]
+    fn remove_logs_from() {
+        let n = 16;
+        let mut tree = DepositCache::default();
+        let mut logs = vec![];
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            logs.push(log.clone());
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        let (full_root, _) = tree
+            .get_deposits(0, n, n, TREE_DEPTH)
+            .expect("should get the full tree");
+
+        assert_eq!(tree.remove_logs_from(n), Ok(0));
+        assert_eq!(tree.remove_logs_from(10), Ok(6));
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.latest_block_number(), Some(9));
+        assert_eq!(
+            tree.deposit_tree.root(),
+            DepositDataTree::create(&tree.leaves, 10, TREE_DEPTH).root(),
+            "should rebuild the deposit tree"
+        );
+
+        // Logs can be inserted again after they have been removed.
+        for log in logs.into_iter().skip(10) {
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+        let (root, _) = tree
+            .get_deposits(0, n, n, TREE_DEPTH)
+            .expect("should get the full tree");
+        assert_eq!(root, full_root);
+        assert_eq!(tree.deposit_roots.len(), n as usize + 1);
+    }
+
     #[test]
     fn get_deposit_valid() {
         let n = 1_024;
@@ -444,4 +675,164 @@ pub mod tests {
         // Range higher than count.
         assert!(tree.get_deposits(0, 4, 2, TREE_DEPTH).is_err());
     }
+
+    /// Returns a snapshot of the first `count` deposits in `cache`.
+    fn snapshot(cache: &DepositCache, count: usize) -> DepositTreeSnapshot {
+        let leaves = &cache.leaves[..count];
+        let mut start = 0;
+        let finalized = (0..=TREE_DEPTH)
+            .rev()
+            .filter(|i| count & (1 << i) != 0)
+            .map(|i| {
+                let end = start + (1 << i);
+                let root = merkle_proof::MerkleTree::create(&leaves[start..end], i).hash();
+                start = end;
+                root
+            })
+            .collect();
+
+        DepositTreeSnapshot {
+            finalized,
+            deposit_root: DepositDataTree::create(leaves, count, TREE_DEPTH).root(),
+            deposit_count: count as u64,
+            execution_block_hash: Hash256::zero(),
+            execution_block_height: cache.logs[count - 1].block_number,
+        }
+    }
+
+    #[test]
+    fn get_deposits_from_snapshot() {
+        let n = 16;
+        let snapshot_count = 5;
+        let mut full_tree = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            full_tree
+                .insert_log(log)
+                .expect("should add consecutive logs")
+        }
+
+        let mut tree = DepositCache::from_snapshot(0, snapshot(&full_tree, snapshot_count))
+            .expect("should create cache from snapshot");
+        assert_eq!(tree.len(), snapshot_count);
+
+        for log in full_tree.iter() {
+            tree.insert_log(log.clone())
+                .expect("should add consecutive logs")
+        }
+        assert_eq!(tree.len(), n as usize);
+        assert_eq!(tree.iter().count(), n as usize - snapshot_count);
+
+        // Deposits after the snapshot match those from the full tree.
+        for deposit_count in snapshot_count as u64..=n {
+            assert_eq!(
+                tree.get_deposits(
+                    snapshot_count as u64,
+                    deposit_count,
+                    deposit_count,
+                    TREE_DEPTH
+                ),
+                full_tree.get_deposits(
+                    snapshot_count as u64,
+                    deposit_count,
+                    deposit_count,
+                    TREE_DEPTH
+                ),
+                "should match full tree with deposit count {}",
+                deposit_count
+            );
+        }
+
+        // Deposits in the snapshot are unavailable.
+        assert!(tree.get_deposits(0, n, n, TREE_DEPTH).is_err());
+        assert!(tree.get_deposits(0, 0, 0, TREE_DEPTH).is_err());
+
+        // Deposit roots are only known from the snapshot block onwards.
+        assert_eq!(tree.get_deposit_root_from_cache(3), None);
+        for block_number in snapshot_count as u64 - 1..n {
+            assert_eq!(
+                tree.get_deposit_root_from_cache(block_number),
+                full_tree.get_deposit_root_from_cache(block_number),
+                "should match full tree at block {}",
+                block_number
+            );
+        }
+    }
+
+    #[test]
+    fn remove_deposits_below_snapshot() {
+        let n = 16;
+        let snapshot_count = 5;
+        let mut full_tree = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            full_tree
+                .insert_log(log)
+                .expect("should add consecutive logs")
+        }
+
+        let mut updater = DepositUpdater {
+            cache: DepositCache::from_snapshot(0, snapshot(&full_tree, snapshot_count))
+                .expect("should create cache from snapshot"),
+            last_processed_block: Some(n - 1),
+        };
+        for log in full_tree.iter() {
+            updater
+                .cache
+                .insert_log(log.clone())
+                .expect("should add consecutive logs")
+        }
+
+        // Deposits after the snapshot block can be removed.
+        assert_eq!(updater.remove_deposits_from(10), Ok((10, 6)));
+        assert_eq!(updater.cache.len(), 10);
+        assert_eq!(updater.last_processed_block, Some(9));
+
+        // Removing deposits from the snapshot block or earlier resets the cache to the snapshot.
+        let snapshot_block = snapshot_count as u64 - 1;
+        assert_eq!(
+            updater.remove_deposits_from(snapshot_block - 2),
+            Ok((snapshot_block + 1, 5))
+        );
+        assert_eq!(updater.cache.len(), snapshot_count);
+        assert_eq!(updater.cache.iter().count(), 0);
+        assert_eq!(updater.last_processed_block, Some(snapshot_block));
+
+        // The deposits after the snapshot can be imported again.
+        for log in full_tree.iter() {
+            updater
+                .cache
+                .insert_log(log.clone())
+                .expect("should add consecutive logs")
+        }
+        assert_eq!(updater.cache.len(), n as usize);
+        assert_eq!(
+            updater.cache.get_deposit_root_from_cache(n - 1),
+            full_tree.get_deposit_root_from_cache(n - 1)
+        );
+    }
+
+    #[test]
+    fn from_snapshot_invalid_root() {
+        let mut full_tree = DepositCache::default();
+        for i in 0..4 {
+            let mut log = example_log();
+            log.index = i;
+            full_tree
+                .insert_log(log)
+                .expect("should add consecutive logs")
+        }
+
+        let mut invalid = snapshot(&full_tree, 3);
+        invalid.deposit_root = Hash256::zero();
+        assert!(DepositCache::from_snapshot(0, invalid).is_err());
+    }
 }
@@ -0,0 +1,126 @@
+//! A finalized snapshot of the deposit contract tree, in the format described by EIP-4881.
+//!
+//! A snapshot allows the deposit cache to be initialized without downloading every deposit log
+//! since the deployment of the deposit contract. Only the logs after the snapshot's execution
+//! block are downloaded, however proofs cannot be produced for the deposits in the snapshot.
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use ssz_derive::{Decode, Encode};
+use std::path::Path;
+use std::time::Duration;
+use types::Hash256;
+
+/// Timeout when downloading a snapshot from a URL.
+const DOWNLOAD_TIMEOUT_MILLIS: u64 = 30_000;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct DepositTreeSnapshot {
+    /// The roots of the full subtrees of the deposit tree, from left to right.
+    pub finalized: Vec<Hash256>,
+    /// The deposit root (with the deposit count mixed in) of the tree.
+    pub deposit_root: Hash256,
+    /// The number of deposits in the tree.
+    #[serde(deserialize_with = "u64_from_str_or_int")]
+    pub deposit_count: u64,
+    /// The hash of the eth1 block which contains the last deposit in the tree.
+    pub execution_block_hash: Hash256,
+    /// The number of the eth1 block which contains the last deposit in the tree.
+    #[serde(deserialize_with = "u64_from_str_or_int")]
+    pub execution_block_height: u64,
+}
+
+/// A snapshot served by a beacon node API, wrapped in a `data` field.
+#[derive(Deserialize)]
+struct ApiResponse {
+    data: DepositTreeSnapshot,
+}
+
+impl DepositTreeSnapshot {
+    /// Parses a JSON snapshot, which may be wrapped in a `data` field.
+    pub fn from_json_bytes(bytes: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice::<ApiResponse>(bytes)
+            .map(|response| response.data)
+            .or_else(|_| serde_json::from_slice(bytes))
+            .map_err(|e| format!("Unable to parse deposit snapshot: {:?}", e))
+    }
+
+    /// Loads a snapshot from `location`, which is either a HTTP(S) URL or a path to a file.
+    pub async fn load(location: &str) -> Result<Self, String> {
+        let bytes = if location.starts_with("http://") || location.starts_with("https://") {
+            reqwest::Client::new()
+                .get(location)
+                .header("Accept", "application/json")
+                .timeout(Duration::from_millis(DOWNLOAD_TIMEOUT_MILLIS))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| format!("Unable to download deposit snapshot: {:?}", e))?
+                .bytes()
+                .await
+                .map_err(|e| format!("Unable to read deposit snapshot: {:?}", e))?
+                .to_vec()
+        } else {
+            std::fs::read(Path::new(location))
+                .map_err(|e| format!("Unable to read deposit snapshot {}: {:?}", location, e))?
+        };
+
+        Self::from_json_bytes(&bytes)
+    }
+}
+
+/// Deserializes a `u64` from either a JSON number or a decimal string.
+fn u64_from_str_or_int<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrInt {
+        Str(String),
+        Int(u64),
+    }
+
+    match StrOrInt::deserialize(deserializer)? {
+        StrOrInt::Str(s) => s.parse().map_err(D::Error::custom),
+        StrOrInt::Int(n) => Ok(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = r#"{
+        "finalized": [
+            "0x7af7da533b0dc64b690cb0604f5a81e40ed83796dd14037ea3a55383b8f0976a"
+        ],
+        "deposit_root": "0xe70b7e45a7b3e4e1d2a1a8cfae9b8f1d8d9f8d81e7ab4ab8e9b0d0a2a3d4e5f6",
+        "deposit_count": "1",
+        "execution_block_hash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "execution_block_height": 42
+    }"#;
+
+    #[test]
+    fn parses_snapshot() {
+        let snapshot =
+            DepositTreeSnapshot::from_json_bytes(SNAPSHOT.as_bytes()).expect("should parse");
+        assert_eq!(snapshot.finalized.len(), 1);
+        assert_eq!(snapshot.deposit_count, 1);
+        assert_eq!(snapshot.execution_block_height, 42);
+    }
+
+    #[test]
+    fn parses_wrapped_snapshot() {
+        let wrapped = format!(r#"{{"data": {}}}"#, SNAPSHOT);
+        assert_eq!(
+            DepositTreeSnapshot::from_json_bytes(wrapped.as_bytes()),
+            DepositTreeSnapshot::from_json_bytes(SNAPSHOT.as_bytes())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_snapshot() {
+        assert!(DepositTreeSnapshot::from_json_bytes(b"{}").is_err());
+        assert!(DepositTreeSnapshot::from_json_bytes(b"{\"data\": {}}").is_err());
+    }
+}
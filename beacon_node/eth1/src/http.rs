@@ -43,14 +43,18 @@ impl FromStr for Eth1NetworkId {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "1" => Ok(Eth1NetworkId::Mainnet),
-            "5" => Ok(Eth1NetworkId::Goerli),
-            custom => {
-                let network_id = u64::from_str_radix(custom, 10)
-                    .map_err(|e| format!("Failed to parse eth1 network id {}", e))?;
-                Ok(Eth1NetworkId::Custom(network_id))
-            }
+        u64::from_str_radix(s, 10)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to parse eth1 network id {}", e))
+    }
+}
+
+impl From<u64> for Eth1NetworkId {
+    fn from(network_id: u64) -> Self {
+        match network_id {
+            1 => Eth1NetworkId::Mainnet,
+            5 => Eth1NetworkId::Goerli,
+            custom => Eth1NetworkId::Custom(custom),
         }
     }
 }
@@ -66,6 +70,47 @@ pub async fn get_network_id(endpoint: &str, timeout: Duration) -> Result<Eth1Net
     )
 }
 
+impl Eth1NetworkId {
+    /// Returns the EIP-155 chain id which is expected to be served alongside this network id.
+    ///
+    /// Returns `None` for custom networks, where the chain id may differ from the network id.
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            Eth1NetworkId::Mainnet => Some(1),
+            Eth1NetworkId::Goerli => Some(5),
+            Eth1NetworkId::Custom(_) => None,
+        }
+    }
+}
+
+/// Get the EIP-155 chain id of the given endpoint.
+pub async fn get_chain_id(endpoint: &str, timeout: Duration) -> Result<u64, String> {
+    let response_body = send_rpc_request(endpoint, "eth_chainId", json!([]), timeout).await?;
+    hex_to_u64_be(
+        response_result(&response_body)?
+            .ok_or_else(|| "No result was returned for chain id".to_string())?
+            .as_str()
+            .ok_or_else(|| "Data was not string")?,
+    )
+    .map_err(|e| format!("Failed to get chain id: {}", e))
+}
+
+/// Returns `true` if the given endpoint reports that it is still syncing the eth1 chain.
+///
+/// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
+pub async fn is_syncing(endpoint: &str, timeout: Duration) -> Result<bool, String> {
+    let response_body = send_rpc_request(endpoint, "eth_syncing", json!([]), timeout).await?;
+    match response_result(&response_body)?
+        .ok_or_else(|| "No result was returned for sync status".to_string())?
+    {
+        // A node which is not syncing returns `false`, otherwise it returns an object describing
+        // its sync progress.
+        Value::Bool(syncing) => Ok(syncing),
+        Value::Object(_) => Ok(true),
+        other => Err(format!("Unexpected sync status: {}", other)),
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Block {
     pub hash: Hash256,
@@ -1,7 +1,11 @@
+use crate::metrics;
 use crate::Config;
 use crate::{
     block_cache::BlockCache,
-    deposit_cache::{DepositCache, SszDepositCache},
+    deposit_cache::{
+        DepositCache, Error as DepositCacheError, LegacySszDepositCache, SszDepositCache,
+    },
+    endpoints::EndpointHealth,
 };
 use parking_lot::RwLock;
 use ssz::{Decode, Encode};
@@ -22,6 +26,32 @@ impl DepositUpdater {
             last_processed_block: None,
         }
     }
+
+    /// Removes the deposits in blocks with a number greater than or equal to `block_number`, such
+    /// that they are downloaded again. Returns the number of the first block whose deposits were
+    /// removed, along with the number of deposits removed.
+    ///
+    /// The deposits in the finalized snapshot the cache was initialized from cannot be removed. If
+    /// `block_number` is not after the snapshot block, all deposits after the snapshot are removed
+    /// instead, restoring the cache to the snapshot.
+    pub fn remove_deposits_from(
+        &mut self,
+        block_number: u64,
+    ) -> Result<(u64, usize), DepositCacheError> {
+        let block_number = match self.cache.snapshot() {
+            Some(snapshot) => std::cmp::max(block_number, snapshot.execution_block_height + 1),
+            None => block_number,
+        };
+
+        let removed = self.cache.remove_logs_from(block_number)?;
+        if self
+            .last_processed_block
+            .map_or(false, |last_processed| last_processed >= block_number)
+        {
+            self.last_processed_block = Some(block_number.saturating_sub(1));
+        }
+        Ok((block_number, removed))
+    }
 }
 
 #[derive(Default)]
@@ -29,19 +59,55 @@ pub struct Inner {
     pub block_cache: RwLock<BlockCache>,
     pub deposit_cache: RwLock<DepositUpdater>,
     pub config: RwLock<Config>,
+    /// The index of the endpoint in `config.endpoints` which is currently used.
+    pub current_endpoint: RwLock<usize>,
+    /// The health of each endpoint in `config.endpoints`, in the same order.
+    pub endpoint_health: RwLock<Vec<EndpointHealth>>,
     pub spec: ChainSpec,
 }
 
 impl Inner {
-    /// Prunes the block cache to `self.target_block_cache_len`.
+    /// Prunes the block cache to `self.config.block_cache_truncation`.
     ///
-    /// Is a no-op if `self.target_block_cache_len` is `None`.
+    /// Is a no-op if `self.config.block_cache_truncation` is `None`.
     pub fn prune_blocks(&self) {
         if let Some(block_cache_truncation) = self.config.read().block_cache_truncation {
-            self.block_cache.write().truncate(block_cache_truncation);
+            let mut block_cache = self.block_cache.write();
+            let len = block_cache.len();
+            block_cache.truncate(block_cache_truncation);
+
+            metrics::inc_counter_by(
+                &metrics::BLOCK_CACHE_PRUNED,
+                len.saturating_sub(block_cache.len()) as i64,
+            );
+            metrics::set_gauge(&metrics::BLOCK_CACHE_LEN, block_cache.len() as i64);
+            metrics::set_gauge(
+                &metrics::BLOCK_CACHE_TARGET_LEN,
+                block_cache_truncation as i64,
+            );
         }
     }
 
+    /// Returns the endpoint which is currently used, falling back to the first endpoint if the
+    /// current index is not valid.
+    pub fn endpoint(&self) -> Option<String> {
+        let config = self.config.read();
+        config
+            .endpoints
+            .get(*self.current_endpoint.read())
+            .or_else(|| config.endpoints.first())
+            .cloned()
+    }
+
+    /// Returns the health of each of the configured endpoints, prior to them being checked.
+    pub fn initial_endpoint_health(config: &Config) -> Vec<EndpointHealth> {
+        config
+            .endpoints
+            .iter()
+            .map(|endpoint| EndpointHealth::new(endpoint))
+            .collect()
+    }
+
     /// Encode the eth1 block and deposit cache as bytes.
     pub fn as_bytes(&self) -> Vec<u8> {
         let ssz_eth1_cache = SszEth1Cache::from_inner(&self);
@@ -49,8 +115,15 @@ impl Inner {
     }
 
     /// Recover `Inner` given byte representation of eth1 deposit and block caches.
+    ///
+    /// Caches persisted prior to the introduction of deposit snapshots are also accepted.
     pub fn from_bytes(bytes: &[u8], config: Config, spec: ChainSpec) -> Result<Self, String> {
         let ssz_cache = SszEth1Cache::from_ssz_bytes(bytes)
+            .or_else(|e| {
+                LegacySszEth1Cache::from_ssz_bytes(bytes)
+                    .map(Into::into)
+                    .map_err(|_| e)
+            })
             .map_err(|e| format!("Ssz decoding error: {:?}", e))?;
         Ok(ssz_cache.to_inner(config, spec)?)
     }
@@ -61,6 +134,24 @@ impl Inner {
     }
 }
 
+/// The `SszEth1Cache` format used prior to the introduction of deposit snapshots.
+#[derive(Encode, Decode, Clone)]
+struct LegacySszEth1Cache {
+    block_cache: BlockCache,
+    deposit_cache: LegacySszDepositCache,
+    last_processed_block: Option<u64>,
+}
+
+impl From<LegacySszEth1Cache> for SszEth1Cache {
+    fn from(legacy: LegacySszEth1Cache) -> Self {
+        Self {
+            block_cache: legacy.block_cache,
+            deposit_cache: legacy.deposit_cache.into(),
+            last_processed_block: legacy.last_processed_block,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Clone)]
 pub struct SszEth1Cache {
     block_cache: BlockCache,
@@ -86,7 +177,9 @@ impl SszEth1Cache {
                 cache: self.deposit_cache.to_deposit_cache()?,
                 last_processed_block: self.last_processed_block,
             }),
+            endpoint_health: RwLock::new(Inner::initial_endpoint_health(&config)),
             config: RwLock::new(config),
+            current_endpoint: RwLock::new(0),
             spec,
         })
     }
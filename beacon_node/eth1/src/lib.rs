@@ -4,13 +4,20 @@ extern crate lazy_static;
 mod block_cache;
 mod deposit_cache;
 mod deposit_log;
+mod deposit_snapshot;
+mod endpoints;
 pub mod http;
 mod inner;
 mod metrics;
 mod service;
+mod subscription;
 
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::DepositCache;
 pub use deposit_log::DepositLog;
+pub use deposit_snapshot::DepositTreeSnapshot;
+pub use endpoints::{check_endpoints, redact_endpoint, EndpointHealth};
 pub use inner::SszEth1Cache;
-pub use service::{BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Service};
+pub use service::{
+    BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Eth1Health, Service,
+};
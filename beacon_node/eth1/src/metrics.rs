@@ -6,8 +6,44 @@ lazy_static! {
      */
     pub static ref BLOCK_CACHE_LEN: Result<IntGauge> =
         try_create_int_gauge("eth1_block_cache_len", "Count of eth1 blocks in cache");
+    pub static ref BLOCK_CACHE_TARGET_LEN: Result<IntGauge> =
+        try_create_int_gauge("eth1_block_cache_target_len", "Maximum count of eth1 blocks retained in cache");
+    pub static ref BLOCK_CACHE_PRUNED: Result<IntCounter> =
+        try_create_int_counter("eth1_block_cache_pruned_total", "Count of eth1 blocks pruned from cache");
     pub static ref LATEST_CACHED_BLOCK_TIMESTAMP: Result<IntGauge> =
         try_create_int_gauge("eth1_latest_cached_block_timestamp", "Timestamp of latest block in eth1 cache");
+    pub static ref REORGS: Result<IntCounter> =
+        try_create_int_counter("eth1_reorgs_total", "Count of eth1 reorgs which removed blocks from the eth1 cache");
+    pub static ref REORG_DEPTH: Result<IntGauge> =
+        try_create_int_gauge("eth1_reorg_depth_blocks", "Number of cached blocks removed by the most recent eth1 reorg");
+
+    /*
+     * Eth1 endpoints
+     */
+    pub static ref ENDPOINT_FALLBACKS: Result<IntCounter> =
+        try_create_int_counter("eth1_endpoint_fallbacks_total", "Count of times a fallback eth1 endpoint was selected");
+    pub static ref ENDPOINT_ERRORS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "eth1_endpoint_errors_total",
+        "Count of failed health checks and cache updates per eth1 endpoint",
+        &["endpoint_index"]
+    );
+    pub static ref ENDPOINT_HEALTHY: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "eth1_endpoint_healthy",
+        "Set to 1 if the eth1 endpoint passed its most recent health check, otherwise 0",
+        &["endpoint_index"]
+    );
+    pub static ref ENDPOINT_LATENCY: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "eth1_endpoint_latency_milliseconds",
+        "Time taken by the most recent health check of the eth1 endpoint",
+        &["endpoint_index"]
+    );
+    pub static ref ENDPOINT_HEAD_LAG: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "eth1_endpoint_head_lag_blocks",
+        "Number of blocks the eth1 endpoint's head is behind the highest head of all endpoints",
+        &["endpoint_index"]
+    );
+    pub static ref SUBSCRIPTION_FAILURES: Result<IntCounter> =
+        try_create_int_counter("eth1_subscription_failures_total", "Count of failed eth1 WebSocket subscriptions");
 
     /*
      * Eth1 deposits
@@ -16,4 +52,6 @@ lazy_static! {
         try_create_int_gauge("eth1_deposit_cache_len", "Number of deposits in the eth1 cache");
     pub static ref HIGHEST_PROCESSED_DEPOSIT_BLOCK: Result<IntGauge> =
         try_create_int_gauge("eth1_highest_processed_deposit_block", "Number of the last block checked for deposits");
+    pub static ref DEPOSIT_SYNC_DISTANCE: Result<IntGauge> =
+        try_create_int_gauge("eth1_deposit_sync_distance_blocks", "Number of blocks between the last block checked for deposits and the follow distance block");
 }
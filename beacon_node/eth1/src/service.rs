@@ -1,22 +1,28 @@
 use crate::metrics;
 use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
-    deposit_cache::Error as DepositCacheError,
-    http::{
-        get_block, get_block_number, get_deposit_logs_in_range, get_network_id, Eth1NetworkId, Log,
+    deposit_cache::{DepositCache, Error as DepositCacheError},
+    endpoints::{
+        check_endpoint, redact_endpoint, redact_error, select_endpoint, EndpointHealth,
+        MAX_HEAD_LAG_BLOCKS,
     },
+    http::{get_block, get_block_number, get_deposit_logs_in_range, Eth1NetworkId, Log},
     inner::{DepositUpdater, Inner},
-    DepositLog,
+    subscription::{subscribe, Notification},
+    DepositLog, DepositTreeSnapshot,
 };
 use futures::{future::TryFutureExt, stream, stream::TryStreamExt, StreamExt};
 use parking_lot::{RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
-use slog::{debug, error, info, trace, Logger};
+use slog::{crit, debug, error, info, trace, warn, Logger};
+use std::collections::HashSet;
 use std::ops::{Range, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::time::{interval_at, Duration, Instant};
-use types::ChainSpec;
+use tokio::sync::mpsc;
+use tokio::time::{delay_for, interval_at, Duration, Instant};
+use types::{ChainSpec, EthSpec, Unsigned};
 
 /// Indicates the default eth1 network we use for the deposit contract.
 pub const DEFAULT_NETWORK_ID: Eth1NetworkId = Eth1NetworkId::Goerli;
@@ -30,6 +36,15 @@ const GET_BLOCK_TIMEOUT_MILLIS: u64 = STANDARD_TIMEOUT_MILLIS;
 /// Timeout when doing an eth_getLogs to read the deposit contract logs.
 const GET_DEPOSIT_LOG_TIMEOUT_MILLIS: u64 = STANDARD_TIMEOUT_MILLIS;
 
+/// Whilst subscribed to a WebSocket endpoint, only one in this many polling intervals triggers an
+/// update. These updates are a safety net in case notifications are missed.
+const SUBSCRIBED_POLL_INTERVAL_MULTIPLIER: u32 = 8;
+/// The delay before re-subscribing to a WebSocket endpoint after the subscription failed.
+const SUBSCRIPTION_RETRY_DELAY_SECS: u64 = 30;
+/// Only one in this many updates checks the fallback endpoints whilst a more preferred endpoint is
+/// usable. The fallbacks are always checked when none of the more preferred endpoints are usable.
+const FALLBACK_CHECK_INTERVAL_MULTIPLIER: u32 = 8;
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     /// The remote node is less synced that we expect, it is not useful until has done more
@@ -53,15 +68,40 @@ pub enum Error {
     FailedToInsertEth1Block(BlockCacheError),
     /// There was an inconsistency when adding a deposit to the cache.
     FailedToInsertDeposit(DepositCacheError),
+    /// Deposits which are no longer in the canonical eth1 chain could not be removed.
+    FailedToRemoveDeposits(DepositCacheError),
     /// A log downloaded from the eth1 contract was not well formed.
     FailedToParseDepositLog {
         block_range: Range<u64>,
         error: String,
     },
+    /// No eth1 endpoints have been configured.
+    NoEndpoints,
     /// There was an unexpected internal error.
     Internal(String),
 }
 
+/// The health of the eth1 endpoints and the progress of the eth1 caches.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Eth1Health {
+    /// The endpoint currently used to update the caches, without any path or credentials.
+    pub current_endpoint: Option<String>,
+    /// The health of each endpoint, in order of preference.
+    pub endpoints: Vec<EndpointHealth>,
+    /// The number of deposits in the deposit cache.
+    pub deposit_count: usize,
+    /// The number of the last block which has been checked for deposits.
+    pub highest_processed_deposit_block: Option<u64>,
+    /// The number of blocks between `highest_processed_deposit_block` and the block at the follow
+    /// distance of the current endpoint's head. Blocks cannot be proposed with the latest
+    /// deposits whilst this is non-zero.
+    pub deposit_sync_distance: Option<u64>,
+    /// The number of the latest block in the block cache.
+    pub latest_cached_block_number: Option<u64>,
+    /// The timestamp of the latest block in the block cache.
+    pub latest_cached_block_timestamp: Option<u64>,
+}
+
 /// The success message for an Eth1Data cache update.
 #[derive(Debug, PartialEq, Clone)]
 pub struct BlockCacheUpdateOutcome {
@@ -77,12 +117,23 @@ pub struct DepositCacheUpdateOutcome {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// An Eth1 node (e.g., Geth) running a HTTP JSON-RPC endpoint.
-    pub endpoint: String,
+    /// Eth1 nodes (e.g., Geth) running a HTTP JSON-RPC endpoint, in order of preference.
+    ///
+    /// The first healthy endpoint is used, the others are only used as fallbacks.
+    pub endpoints: Vec<String>,
+    /// An optional Eth1 node WebSocket endpoint, used to subscribe to new heads and deposit logs.
+    ///
+    /// Whilst subscribed, the caches are updated upon each notification rather than by polling
+    /// the HTTP endpoints.
+    pub ws_endpoint: Option<String>,
     /// The address the `BlockCache` and `DepositCache` should assume is the canonical deposit contract.
     pub deposit_contract_address: String,
     /// The eth1 network id where the deposit contract is deployed (Goerli/Mainnet).
     pub network_id: Eth1NetworkId,
+    /// The EIP-155 chain id which the eth1 node is expected to serve.
+    ///
+    /// The chain id is not checked if `None`.
+    pub chain_id: Option<u64>,
     /// Defines the first block that the `DepositCache` will start searching for deposit logs.
     ///
     /// Setting too high can result in missed logs. Setting too low will result in unnecessary
@@ -97,6 +148,9 @@ pub struct Config {
     /// Defines the number of blocks that should be retained each time the `BlockCache` calls truncate on
     /// itself.
     pub block_cache_truncation: Option<usize>,
+    /// The number of blocks retained in the `BlockCache` in addition to those which may be voted
+    /// upon, see `Config::set_block_cache_truncation`.
+    pub block_cache_margin: u64,
     /// The interval between updates when using the `auto_update` function.
     pub auto_update_interval_millis: u64,
     /// The span of blocks we should query for logs, per request.
@@ -105,26 +159,55 @@ pub struct Config {
     pub max_log_requests_per_update: Option<usize>,
     /// The maximum number of log requests per update.
     pub max_blocks_per_update: Option<usize>,
+    /// A path or URL to a finalized `DepositTreeSnapshot`, used to initialize the deposit cache
+    /// instead of downloading every deposit log since the deployment of the deposit contract.
+    ///
+    /// Ignored whilst waiting for genesis or if the eth1 caches are loaded from disk.
+    pub deposit_snapshot: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            endpoint: "http://localhost:8545".into(),
+            endpoints: vec!["http://localhost:8545".into()],
+            ws_endpoint: None,
             deposit_contract_address: "0x0000000000000000000000000000000000000000".into(),
             network_id: DEFAULT_NETWORK_ID,
+            chain_id: DEFAULT_NETWORK_ID.chain_id(),
             deposit_contract_deploy_block: 1,
             lowest_cached_block_number: 1,
             follow_distance: 128,
             block_cache_truncation: Some(4_096),
+            block_cache_margin: 256,
             auto_update_interval_millis: 7_000,
             blocks_per_log_query: 1_000,
             max_log_requests_per_update: None,
             max_blocks_per_update: None,
+            deposit_snapshot: None,
         }
     }
 }
 
+impl Config {
+    /// Sets `block_cache_truncation` so the `BlockCache` only holds the blocks which may be voted
+    /// upon, plus `block_cache_margin` blocks.
+    ///
+    /// A block can only be voted upon whilst it is between one and two follow distances behind
+    /// the start of the current voting period. The head of the cache is one follow distance behind
+    /// the eth1 head, so blocks more than a follow distance plus a voting period behind the head of
+    /// the cache are never required.
+    pub fn set_block_cache_truncation<E: EthSpec>(&mut self, spec: &ChainSpec) {
+        let seconds_per_voting_period =
+            E::SlotsPerEth1VotingPeriod::to_u64() * spec.milliseconds_per_slot / 1_000;
+        let blocks_per_voting_period = seconds_per_voting_period
+            .checked_div(spec.seconds_per_eth1_block)
+            .unwrap_or(seconds_per_voting_period);
+
+        let len = self.follow_distance + blocks_per_voting_period + self.block_cache_margin;
+        self.block_cache_truncation = Some(len as usize);
+    }
+}
+
 /// Provides a set of Eth1 caches and async functions to update them.
 ///
 /// Stores the following caches:
@@ -146,13 +229,42 @@ impl Service {
                 deposit_cache: RwLock::new(DepositUpdater::new(
                     config.deposit_contract_deploy_block,
                 )),
+                endpoint_health: RwLock::new(Inner::initial_endpoint_health(&config)),
                 config: RwLock::new(config),
+                current_endpoint: RwLock::new(0),
                 spec,
             }),
             log,
         }
     }
 
+    /// Creates a new service with a deposit cache initialized from a finalized `snapshot`. Does
+    /// not attempt to connect to the eth1 node.
+    ///
+    /// Only the deposit logs after the snapshot's execution block will be downloaded.
+    pub fn from_deposit_snapshot(
+        mut config: Config,
+        snapshot: DepositTreeSnapshot,
+        log: Logger,
+        spec: ChainSpec,
+    ) -> Result<Self, String> {
+        let last_processed_block = snapshot.execution_block_height;
+        let cache = DepositCache::from_snapshot(config.deposit_contract_deploy_block, snapshot)
+            .map_err(|e| format!("Invalid deposit snapshot: {:?}", e))?;
+
+        // The deposit roots of blocks prior to the snapshot are unknown.
+        config.lowest_cached_block_number =
+            std::cmp::max(config.lowest_cached_block_number, last_processed_block);
+
+        let service = Self::new(config, log, spec);
+        *service.deposits().write() = DepositUpdater {
+            cache,
+            last_processed_block: Some(last_processed_block),
+        };
+
+        Ok(service)
+    }
+
     /// Return byte representation of deposit and block caches.
     pub fn as_bytes(&self) -> Vec<u8> {
         self.inner.as_bytes()
@@ -260,6 +372,35 @@ impl Service {
         self.inner.config.read()
     }
 
+    /// Returns the eth1 endpoint which is currently used, if any endpoints are configured.
+    pub fn current_endpoint(&self) -> Option<String> {
+        self.inner.endpoint()
+    }
+
+    /// Returns the health of the eth1 endpoints and the progress of the caches.
+    pub fn health(&self) -> Eth1Health {
+        let endpoints = self.inner.endpoint_health.read().clone();
+        let current_endpoint = endpoints.get(*self.inner.current_endpoint.read()).cloned();
+        let highest_processed_deposit_block = self.deposits().read().last_processed_block;
+        let deposit_sync_distance = current_endpoint
+            .as_ref()
+            .and_then(|health| health.head_block_number)
+            .map(|head| {
+                head.saturating_sub(self.config().follow_distance)
+                    .saturating_sub(highest_processed_deposit_block.unwrap_or(0))
+            });
+
+        Eth1Health {
+            current_endpoint: current_endpoint.map(|health| health.endpoint),
+            endpoints,
+            deposit_count: self.deposit_cache_len(),
+            highest_processed_deposit_block,
+            deposit_sync_distance,
+            latest_cached_block_number: self.blocks().read().highest_block_number(),
+            latest_cached_block_timestamp: self.latest_block_timestamp(),
+        }
+    }
+
     /// Updates the configuration in `self to be `new_config`.
     ///
     /// Will truncate the block cache if the new configure specifies truncation.
@@ -270,6 +411,10 @@ impl Service {
             // This may be possible, I just haven't looked into the details to ensure it's safe.
             Err("Updating deposit_contract_deploy_block is not supported".to_string())
         } else {
+            if new_config.endpoints != old_config.endpoints {
+                *self.inner.current_endpoint.write() = 0;
+                *self.inner.endpoint_health.write() = Inner::initial_endpoint_health(&new_config);
+            }
             *old_config = new_config;
 
             // Prevents a locking condition when calling prune_blocks.
@@ -299,6 +444,10 @@ impl Service {
     pub async fn update(
         &self,
     ) -> Result<(DepositCacheUpdateOutcome, BlockCacheUpdateOutcome), String> {
+        self.repair_reorg()
+            .await
+            .map_err(|e| format!("Failed to check for eth1 reorg: {:?}", e))?;
+
         let update_deposit_cache = async {
             let outcome = self
                 .update_deposit_cache()
@@ -334,9 +483,121 @@ impl Service {
         futures::try_join!(update_deposit_cache, update_block_cache)
     }
 
+    /// Checks that the highest block in the block cache is still in the canonical eth1 chain of
+    /// the current endpoint.
+    ///
+    /// If it is not, the eth1 chain has reorged beyond the follow distance. The blocks and deposit
+    /// logs from the first non-canonical block onwards are removed from the caches so that they
+    /// are downloaded again by the next update, rather than being used to produce invalid eth1
+    /// data.
+    ///
+    /// Returns the number of the first removed block, if a reorg was detected.
+    pub async fn repair_reorg(&self) -> Result<Option<u64>, Error> {
+        let endpoint = self.inner.endpoint().ok_or(Error::NoEndpoints)?;
+
+        let (lowest, highest) = {
+            let blocks = self.blocks().read();
+            match (blocks.lowest_block_number(), blocks.highest_block_number()) {
+                (Some(lowest), Some(highest)) => (lowest, highest),
+                _ => return Ok(None),
+            }
+        };
+
+        if self.is_canonical(&endpoint, highest).await? {
+            return Ok(None);
+        }
+
+        // Find the first non-canonical block. All prior blocks are canonical, so a binary search
+        // only requires a few blocks to be downloaded.
+        let (mut low, mut high) = (lowest, highest);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.is_canonical(&endpoint, middle).await? {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        let first_removed = low;
+
+        let blocks_removed = self.blocks().write().remove_blocks_from(first_removed);
+        let (first_removed_deposit_block, logs_removed) = self
+            .deposits()
+            .write()
+            .remove_deposits_from(first_removed)
+            .map_err(Error::FailedToRemoveDeposits)?;
+
+        metrics::inc_counter(&metrics::REORGS);
+        metrics::set_gauge(&metrics::REORG_DEPTH, blocks_removed as i64);
+        metrics::set_gauge(&metrics::BLOCK_CACHE_LEN, self.block_cache_len() as i64);
+        metrics::set_gauge(&metrics::DEPOSIT_CACHE_LEN, self.deposit_cache_len() as i64);
+
+        if first_removed_deposit_block > first_removed {
+            // The snapshot is finalized, so the endpoint is likely following a different chain.
+            crit!(
+                self.log,
+                "Eth1 reorg deeper than the deposit snapshot";
+                "msg" => "the deposit cache has been reset to the snapshot, check that the eth1 \
+                          endpoint is on the correct chain",
+                "first_removed_block" => first_removed,
+                "snapshot_block" => first_removed_deposit_block - 1,
+                "blocks_removed" => blocks_removed,
+                "deposits_removed" => logs_removed,
+            );
+        } else if first_removed == lowest {
+            // None of the cached blocks are canonical, so deposit logs prior to the block cache
+            // may also have been reorged.
+            crit!(
+                self.log,
+                "Eth1 reorg deeper than the block cache";
+                "msg" => "deposits prior to the cached blocks may be invalid, consider \
+                          removing the eth1 cache",
+                "lowest_cached_block" => lowest,
+                "blocks_removed" => blocks_removed,
+                "deposits_removed" => logs_removed,
+            );
+        } else {
+            error!(
+                self.log,
+                "Eth1 reorg detected";
+                "msg" => "the affected blocks and deposits will be downloaded again",
+                "first_removed_block" => first_removed,
+                "blocks_removed" => blocks_removed,
+                "deposits_removed" => logs_removed,
+            );
+        }
+
+        Ok(Some(first_removed))
+    }
+
+    /// Returns `true` if the cached block with number `block_number` has the same hash as the
+    /// block with that number on `endpoint`.
+    async fn is_canonical(&self, endpoint: &str, block_number: u64) -> Result<bool, Error> {
+        let cached_hash = self
+            .blocks()
+            .read()
+            .block_by_number(block_number)
+            .map(|block| block.hash)
+            .ok_or_else(|| Error::Internal(format!("Block {} is not cached", block_number)))?;
+
+        let remote_block = get_block(
+            endpoint,
+            block_number,
+            Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+        )
+        .await
+        .map_err(Error::BlockDownloadFailed)?;
+
+        Ok(remote_block.hash == cached_hash)
+    }
+
     /// A looping future that updates the cache, then waits `config.auto_update_interval` before
     /// updating it again.
     ///
+    /// If `config.ws_endpoint` is set, the cache is instead updated each time a new head or
+    /// deposit log is announced by the WebSocket endpoint. Polling resumes at the usual interval
+    /// whenever the subscription fails.
+    ///
     /// ## Returns
     ///
     /// - Ok(_) if the update was successful (the cache may or may not have been modified).
@@ -346,58 +607,303 @@ impl Service {
     pub fn auto_update(self, handle: environment::TaskExecutor) {
         let update_interval = Duration::from_millis(self.config().auto_update_interval_millis);
 
-        let mut interval = interval_at(Instant::now(), update_interval);
+        // A single slot is sufficient, since notifications received whilst an update is pending
+        // are covered by that update.
+        let (notification_tx, notification_rx) = mpsc::channel(1);
+        let subscribed = Arc::new(AtomicBool::new(false));
+
+        if let Some(ws_endpoint) = self.config().ws_endpoint.clone() {
+            let service = self.clone();
+            let subscribed = subscribed.clone();
+            let subscription_future = async move {
+                loop {
+                    let deposit_contract_address =
+                        service.config().deposit_contract_address.clone();
+                    let result = subscribe(
+                        ws_endpoint.clone(),
+                        &deposit_contract_address,
+                        notification_tx.clone(),
+                        &subscribed,
+                    )
+                    .await;
+                    subscribed.store(false, Ordering::Relaxed);
+
+                    match result {
+                        // The update loop has stopped.
+                        Ok(()) => break,
+                        Err(e) => {
+                            metrics::inc_counter(&metrics::SUBSCRIPTION_FAILURES);
+                            warn!(
+                                service.log,
+                                "Eth1 subscription failed";
+                                "msg" => "polling HTTP endpoints instead",
+                                "retry_secs" => SUBSCRIPTION_RETRY_DELAY_SECS,
+                                "endpoint" => redact_endpoint(&ws_endpoint),
+                                "error" => redact_error(&e, &ws_endpoint),
+                            );
+                        }
+                    }
+
+                    delay_for(Duration::from_secs(SUBSCRIPTION_RETRY_DELAY_SECS)).await;
+                }
+            };
+
+            handle.spawn(subscription_future, "eth1_subscription");
+        }
+
+        let interval =
+            interval_at(Instant::now(), update_interval).map(|_| UpdateTrigger::Interval);
+        let notifications = notification_rx.map(UpdateTrigger::Notification);
+        let mut triggers = stream::select(interval, notifications);
 
         let update_future = async move {
-            while interval.next().await.is_some() {
-                self.do_update(update_interval).await.ok();
+            let mut skipped_polls = 0;
+            let mut updates_since_fallback_check = 0;
+            while let Some(trigger) = triggers.next().await {
+                match trigger {
+                    UpdateTrigger::Interval => {
+                        if subscribed.load(Ordering::Relaxed)
+                            && skipped_polls + 1 < SUBSCRIBED_POLL_INTERVAL_MULTIPLIER
+                        {
+                            skipped_polls += 1;
+                            continue;
+                        }
+                    }
+                    UpdateTrigger::Notification(notification) => trace!(
+                        self.log,
+                        "Eth1 subscription notification";
+                        "notification" => format!("{:?}", notification),
+                    ),
+                }
+
+                skipped_polls = 0;
+                let check_fallbacks = updates_since_fallback_check == 0;
+                updates_since_fallback_check =
+                    (updates_since_fallback_check + 1) % FALLBACK_CHECK_INTERVAL_MULTIPLIER;
+                self.do_update(update_interval, check_fallbacks).await.ok();
             }
         };
 
         handle.spawn(update_future, "eth1");
     }
 
-    async fn do_update(&self, update_interval: Duration) -> Result<(), ()> {
-        let endpoint = self.config().endpoint.clone();
-        let config_network = self.config().network_id.clone();
-        let result =
-            get_network_id(&endpoint, Duration::from_millis(STANDARD_TIMEOUT_MILLIS)).await;
-        match result {
-            Ok(network_id) => {
-                if network_id != config_network {
+    /// Updates the caches using the first healthy endpoint.
+    ///
+    /// Unless `check_fallbacks` is set, only the endpoints up to and including the current one are
+    /// checked at first. The remaining endpoints are only checked if none of those are usable.
+    async fn do_update(&self, update_interval: Duration, check_fallbacks: bool) -> Result<(), ()> {
+        let mut statuses = if check_fallbacks {
+            self.check_endpoints(usize::MAX).await
+        } else {
+            let current_endpoint = *self.inner.current_endpoint.read();
+            self.check_endpoints(current_endpoint + 1).await
+        };
+
+        // Try each healthy endpoint in order of preference, until one of them succeeds.
+        let mut failed_endpoints = HashSet::new();
+        loop {
+            let (index, endpoint) = match self.use_endpoint(&statuses, &failed_endpoints) {
+                Ok(selected) => selected,
+                Err(_) if statuses.len() < self.config().endpoints.len() => {
+                    debug!(
+                        self.log,
+                        "Checking fallback eth1 endpoints";
+                        "checked" => statuses.len(),
+                    );
+                    statuses = self.check_endpoints(usize::MAX).await;
+                    continue;
+                }
+                Err(e) => {
                     error!(
                         self.log,
                         "Failed to update eth1 cache";
-                        "reason" => "Invalid eth1 network id",
-                        "expected" => format!("{:?}",DEFAULT_NETWORK_ID),
-                        "got" => format!("{:?}",network_id),
+                        "retry_millis" => update_interval.as_millis(),
+                        "error" => e,
+                    );
+                    return Ok(());
+                }
+            };
+
+            match self.update().await {
+                Err(e) => {
+                    let e = redact_error(&e, &endpoint);
+                    self.record_endpoint_error(index, e.clone());
+                    error!(
+                        self.log,
+                        "Failed to update eth1 cache";
+                        "endpoint" => redact_endpoint(&endpoint),
+                        "error" => e,
+                    );
+                    failed_endpoints.insert(index);
+                }
+                Ok((deposit, block)) => {
+                    if let Some(distance) = self.health().deposit_sync_distance {
+                        metrics::set_gauge(&metrics::DEPOSIT_SYNC_DISTANCE, distance as i64);
+                    }
+                    debug!(
+                        self.log,
+                        "Updated eth1 cache";
+                        "retry_millis" => update_interval.as_millis(),
+                        "blocks" => format!("{:?}", block),
+                        "deposits" => format!("{:?}", deposit),
                     );
                     return Ok(());
                 }
-            }
-            Err(e) => {
-                error!(self.log, "Failed to get eth1 network id"; "error" => e);
-                return Ok(());
             }
         }
+    }
 
-        let update_result = self.update().await;
-        match update_result {
-            Err(e) => error!(
-                self.log,
-                "Failed to update eth1 cache";
-                "retry_millis" => update_interval.as_millis(),
-                "error" => e,
-            ),
-            Ok((deposit, block)) => debug!(
-                self.log,
-                "Updated eth1 cache";
-                "retry_millis" => update_interval.as_millis(),
-                "blocks" => format!("{:?}", block),
-                "deposits" => format!("{:?}", deposit),
-            ),
+    /// Checks the health of all of the configured endpoints and selects the first healthy one
+    /// for subsequent updates.
+    ///
+    /// Returns the selected endpoint, or an error if none of the endpoints are healthy.
+    pub async fn select_endpoint(&self) -> Result<String, String> {
+        let statuses = self.check_endpoints(usize::MAX).await;
+        self.use_endpoint(&statuses, &HashSet::new())
+            .map(|(_, endpoint)| endpoint)
+    }
+
+    /// Checks the health of the first `count` configured endpoints, recording the outcomes and
+    /// logging any endpoints which are unhealthy.
+    ///
+    /// Returns the outcome of each check, in order of preference. The heads of the endpoints are
+    /// only compared against the other endpoints checked at the same time.
+    async fn check_endpoints(&self, count: usize) -> Vec<Result<u64, String>> {
+        let (endpoints, network_id, chain_id) = {
+            let config = self.config();
+            (
+                config.endpoints.clone(),
+                config.network_id.clone(),
+                config.chain_id,
+            )
         };
-        Ok(())
+        let checked_endpoints = &endpoints[..std::cmp::min(count, endpoints.len())];
+        let checks = futures::future::join_all(checked_endpoints.iter().map(|endpoint| async {
+            let start = Instant::now();
+            let status = check_endpoint(endpoint, &network_id, chain_id)
+                .await
+                .map_err(|e| redact_error(&e, endpoint));
+            (status, start.elapsed())
+        }))
+        .await;
+
+        let highest_head = checks
+            .iter()
+            .filter_map(|(status, _)| status.as_ref().ok())
+            .max()
+            .copied();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|now| now.as_secs());
+
+        let mut endpoint_health = self.inner.endpoint_health.write();
+        let statuses = checked_endpoints
+            .iter()
+            .zip(checks.into_iter())
+            .enumerate()
+            .map(|(index, (endpoint, (status, latency)))| {
+                if endpoint_health.len() <= index {
+                    endpoint_health.push(EndpointHealth::new(endpoint));
+                }
+                let health = &mut endpoint_health[index];
+                let label = index.to_string();
+
+                health.endpoint = redact_endpoint(endpoint);
+                health.latency_millis = Some(latency.as_millis() as u64);
+                health.head_block_number = status.as_ref().ok().copied();
+                health.head_lag = health
+                    .head_block_number
+                    .and_then(|head| Some(highest_head?.saturating_sub(head)));
+                health.last_checked = now;
+                health.error = match (&status, health.head_lag) {
+                    (Err(e), _) => Some(e.clone()),
+                    (Ok(_), Some(head_lag)) if head_lag > MAX_HEAD_LAG_BLOCKS => Some(format!(
+                        "Head is {} blocks behind the highest head",
+                        head_lag
+                    )),
+                    (Ok(_), _) => None,
+                };
+                health.healthy = health.error.is_none();
+
+                metrics::set_int_gauge(
+                    &metrics::ENDPOINT_HEALTHY,
+                    &[&label],
+                    health.healthy as i64,
+                );
+                metrics::set_int_gauge(
+                    &metrics::ENDPOINT_LATENCY,
+                    &[&label],
+                    latency.as_millis() as i64,
+                );
+                if let Some(head_lag) = health.head_lag {
+                    metrics::set_int_gauge(&metrics::ENDPOINT_HEAD_LAG, &[&label], head_lag as i64);
+                }
+
+                if let Err(e) = &status {
+                    health.error_count += 1;
+                    metrics::inc_counter_vec(&metrics::ENDPOINT_ERRORS, &[&label]);
+                    warn!(
+                        self.log,
+                        "Eth1 endpoint is unhealthy";
+                        "endpoint" => redact_endpoint(endpoint),
+                        "error" => e,
+                    );
+                }
+
+                status
+            })
+            .collect();
+        endpoint_health.truncate(endpoints.len());
+
+        statuses
+    }
+
+    /// Records that a cache update using the endpoint at `index` failed with `error`.
+    fn record_endpoint_error(&self, index: usize, error: String) {
+        if let Some(health) = self.inner.endpoint_health.write().get_mut(index) {
+            health.healthy = false;
+            health.error = Some(error);
+            health.error_count += 1;
+        }
+        metrics::inc_counter_vec(&metrics::ENDPOINT_ERRORS, &[&index.to_string()]);
+    }
+
+    /// Selects the first healthy endpoint given the outcomes of `check_endpoints`, ignoring the
+    /// endpoints in `excluded`.
+    fn use_endpoint(
+        &self,
+        statuses: &[Result<u64, String>],
+        excluded: &HashSet<usize>,
+    ) -> Result<(usize, String), String> {
+        let index = select_endpoint(statuses, excluded)
+            .ok_or_else(|| "No healthy eth1 endpoint is available".to_string())?;
+        let endpoint = self
+            .config()
+            .endpoints
+            .get(index)
+            .cloned()
+            .ok_or_else(|| "Eth1 endpoints were updated during selection".to_string())?;
+
+        let previous = std::mem::replace(&mut *self.inner.current_endpoint.write(), index);
+        if index != previous {
+            if index == 0 {
+                info!(
+                    self.log,
+                    "Using primary eth1 endpoint";
+                    "endpoint" => redact_endpoint(&endpoint),
+                );
+            } else {
+                metrics::inc_counter(&metrics::ENDPOINT_FALLBACKS);
+                warn!(
+                    self.log,
+                    "Using fallback eth1 endpoint";
+                    "endpoint" => redact_endpoint(&endpoint),
+                );
+            }
+        }
+
+        Ok((index, endpoint))
     }
 
     /// Contacts the remote eth1 node and attempts to import deposit logs up to the configured
@@ -413,7 +919,7 @@ impl Service {
     ///
     /// Emits logs for debugging and errors.
     pub async fn update_deposit_cache(&self) -> Result<DepositCacheUpdateOutcome, Error> {
-        let endpoint = self.config().endpoint.clone();
+        let endpoint = self.inner.endpoint().ok_or(Error::NoEndpoints)?;
         let follow_distance = self.config().follow_distance;
         let deposit_contract_address = self.config().deposit_contract_address.clone();
 
@@ -562,7 +1068,7 @@ impl Service {
             .map(|n| n + 1)
             .unwrap_or_else(|| self.config().lowest_cached_block_number);
 
-        let endpoint = self.config().endpoint.clone();
+        let endpoint = self.inner.endpoint().ok_or(Error::NoEndpoints)?;
         let follow_distance = self.config().follow_distance;
 
         let range = get_new_block_numbers(&endpoint, next_required_block, follow_distance).await?;
@@ -697,6 +1203,14 @@ impl Service {
     }
 }
 
+/// The events which cause the caches to be updated by `Service::auto_update`.
+enum UpdateTrigger {
+    /// The polling interval elapsed.
+    Interval,
+    /// A notification was received from a WebSocket subscription.
+    Notification(Notification),
+}
+
 /// Determine the range of blocks that need to be downloaded, given the remotes best block and
 /// the locally stored best block.
 async fn get_new_block_numbers<'a>(
@@ -734,7 +1248,7 @@ async fn get_new_block_numbers<'a>(
 ///
 /// Performs three async calls to an Eth1 HTTP JSON RPC endpoint.
 async fn download_eth1_block(cache: Arc<Inner>, block_number: u64) -> Result<Eth1Block, Error> {
-    let endpoint = cache.config.read().endpoint.clone();
+    let endpoint = cache.endpoint().ok_or(Error::NoEndpoints)?;
 
     let deposit_root = cache
         .deposit_cache
@@ -770,6 +1284,7 @@ async fn download_eth1_block(cache: Arc<Inner>, block_number: u64) -> Result<Eth
 mod tests {
     use super::*;
     use toml;
+    use types::MainnetEthSpec;
 
     #[test]
     fn serde_serialize() {
@@ -777,4 +1292,58 @@ mod tests {
             toml::to_string(&Config::default()).expect("Should serde encode default config");
         toml::from_str::<Config>(&serialized).expect("Should serde decode default config");
     }
+
+    #[test]
+    fn block_cache_truncation() {
+        let spec = MainnetEthSpec::default_spec();
+        let mut config = Config {
+            follow_distance: spec.eth1_follow_distance,
+            block_cache_margin: 100,
+            ..Config::default()
+        };
+        config.set_block_cache_truncation::<MainnetEthSpec>(&spec);
+
+        // 1,024 slots of 12 seconds is 877 blocks of 14 seconds.
+        assert_eq!(config.block_cache_truncation, Some(1_024 + 877 + 100));
+    }
+
+    fn unreachable_endpoints_service() -> Service {
+        let config = Config {
+            endpoints: vec!["http://127.0.0.1:1".into(), "http://127.0.0.1:2".into()],
+            ..Config::default()
+        };
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        Service::new(config, log, MainnetEthSpec::default_spec())
+    }
+
+    fn checked_endpoints(service: &Service) -> Vec<bool> {
+        service
+            .health()
+            .endpoints
+            .iter()
+            .map(|health| health.last_checked.is_some())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fallbacks_checked_only_when_requested() {
+        let service = unreachable_endpoints_service();
+
+        service.check_endpoints(1).await;
+        assert_eq!(checked_endpoints(&service), vec![true, false]);
+
+        service.check_endpoints(usize::MAX).await;
+        assert_eq!(checked_endpoints(&service), vec![true, true]);
+    }
+
+    #[tokio::test]
+    async fn fallbacks_checked_when_primary_fails() {
+        let service = unreachable_endpoints_service();
+
+        service
+            .do_update(Duration::from_secs(1), false)
+            .await
+            .unwrap();
+        assert_eq!(checked_endpoints(&service), vec![true, true]);
+    }
 }
@@ -0,0 +1,80 @@
+//! Subscribes to new heads and deposit contract logs via an eth1 WebSocket endpoint.
+//!
+//! The notifications are only used to decide _when_ the caches are updated, the blocks and logs
+//! themselves are still downloaded from the HTTP endpoints. This avoids polling a remote node
+//! whilst no new eth1 blocks are being produced.
+
+use futures::compat::{Future01CompatExt, Stream01CompatExt};
+use futures::{stream, StreamExt};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::mpsc::{error::TrySendError, Sender};
+use web3::transports::WebSocket;
+use web3::types::{Address, FilterBuilder};
+use web3::Web3;
+
+/// An event received from an eth1 WebSocket subscription.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notification {
+    /// A new head block, with its block number if known.
+    NewHead(Option<u64>),
+    /// A new log from the deposit contract, with the number of the block which contains it.
+    DepositLog(Option<u64>),
+}
+
+/// Subscribes to new heads and deposit contract logs on the WebSocket `endpoint`, forwarding each
+/// notification to `sender`.
+///
+/// Sets `subscribed` once both subscriptions have been established. Resolves with `Ok(())` once
+/// `sender` is closed, or with an error if the connection or either of the subscriptions fails.
+pub async fn subscribe(
+    endpoint: String,
+    deposit_contract_address: &str,
+    mut sender: Sender<Notification>,
+    subscribed: &AtomicBool,
+) -> Result<(), String> {
+    let address = Address::from_str(deposit_contract_address.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid deposit contract address: {:?}", e))?;
+
+    // Connecting to the endpoint blocks until the connection has been established.
+    let (_event_loop, transport) = tokio::task::spawn_blocking(move || WebSocket::new(&endpoint))
+        .await
+        .map_err(|e| format!("Failed to spawn WebSocket connection: {:?}", e))?
+        .map_err(|e| format!("Failed to connect to eth1 WebSocket endpoint: {:?}", e))?;
+    let web3 = Web3::new(transport);
+
+    let heads = web3
+        .eth_subscribe()
+        .subscribe_new_heads()
+        .compat()
+        .await
+        .map_err(|e| format!("Failed to subscribe to new heads: {:?}", e))?;
+    let logs = web3
+        .eth_subscribe()
+        .subscribe_logs(FilterBuilder::default().address(vec![address]).build())
+        .compat()
+        .await
+        .map_err(|e| format!("Failed to subscribe to deposit logs: {:?}", e))?;
+
+    subscribed.store(true, Ordering::Relaxed);
+
+    let heads = heads.compat().map(|result| {
+        result.map(|head| Notification::NewHead(head.number.map(|number| number.as_u64())))
+    });
+    let logs = logs.compat().map(|result| {
+        result.map(|log| Notification::DepositLog(log.block_number.map(|number| number.as_u64())))
+    });
+    let mut notifications = stream::select(heads, logs);
+
+    while let Some(result) = notifications.next().await {
+        let notification = result.map_err(|e| format!("Subscription failed: {:?}", e))?;
+        match sender.try_send(notification) {
+            Err(TrySendError::Closed(_)) => return Ok(()),
+            // A full channel means an update has already been triggered, so this notification
+            // would not cause any additional work.
+            Err(TrySendError::Full(_)) | Ok(()) => {}
+        }
+    }
+
+    Err("Subscription ended".to_string())
+}
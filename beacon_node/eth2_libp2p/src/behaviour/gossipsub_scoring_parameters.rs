@@ -0,0 +1,381 @@
+//! Gossipsub v1.1 peer scoring parameters.
+//!
+//! The per-topic parameters are derived from the chain spec. The expected message rates of the
+//! block, aggregate and attestation subnet topics depend on the number of active validators, so
+//! these parameters must be updated as the validator set changes.
+use crate::types::{GossipEncoding, GossipKind, GossipTopic};
+use crate::{error, TopicHash};
+use libp2p::gossipsub::{
+    GossipsubConfig, IdentTopic as Topic, PeerScoreParams, PeerScoreThresholds, TopicScoreParams,
+};
+use std::cmp::max;
+use std::marker::PhantomData;
+use std::time::Duration;
+use types::{ChainSpec, EnrForkId, EthSpec, Slot, SubnetId};
+
+const MAX_IN_MESH_SCORE: f64 = 10.0;
+const MAX_FIRST_MESSAGE_DELIVERIES_SCORE: f64 = 40.0;
+const BEACON_BLOCK_WEIGHT: f64 = 0.5;
+const BEACON_AGGREGATE_PROOF_WEIGHT: f64 = 0.5;
+const VOLUNTARY_EXIT_WEIGHT: f64 = 0.05;
+const PROPOSER_SLASHING_WEIGHT: f64 = 0.05;
+const ATTESTER_SLASHING_WEIGHT: f64 = 0.05;
+
+/// The score thresholds used by Lighthouse.
+///
+/// Peers below the gossip threshold receive no gossip, peers below the publish threshold do not
+/// receive our published messages and peers below the graylist threshold are ignored entirely.
+pub fn lighthouse_gossip_thresholds() -> PeerScoreThresholds {
+    PeerScoreThresholds {
+        gossip_threshold: -4000.0,
+        publish_threshold: -8000.0,
+        graylist_threshold: -16000.0,
+        accept_px_threshold: 100.0,
+        opportunistic_graft_threshold: 5.0,
+    }
+}
+
+/// Computes gossipsub scoring parameters from the chain spec.
+pub struct PeerScoreSettings<TSpec: EthSpec> {
+    slot: Duration,
+    epoch: Duration,
+    beacon_attestation_subnet_weight: f64,
+    max_positive_score: f64,
+    decay_interval: Duration,
+    decay_to_zero: f64,
+    mesh_n: usize,
+    max_committees_per_slot: usize,
+    target_committee_size: usize,
+    target_aggregators_per_committee: usize,
+    attestation_subnet_count: u64,
+    phantom: PhantomData<TSpec>,
+}
+
+impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
+    pub fn new(chain_spec: &ChainSpec, gs_config: &GossipsubConfig) -> PeerScoreSettings<TSpec> {
+        let slot = Duration::from_millis(chain_spec.milliseconds_per_slot);
+        let beacon_attestation_subnet_weight = 1.0 / chain_spec.attestation_subnet_count as f64;
+        let max_positive_score = (MAX_IN_MESH_SCORE + MAX_FIRST_MESSAGE_DELIVERIES_SCORE)
+            * (BEACON_BLOCK_WEIGHT
+                + BEACON_AGGREGATE_PROOF_WEIGHT
+                + beacon_attestation_subnet_weight * chain_spec.attestation_subnet_count as f64
+                + VOLUNTARY_EXIT_WEIGHT
+                + PROPOSER_SLASHING_WEIGHT
+                + ATTESTER_SLASHING_WEIGHT);
+
+        PeerScoreSettings {
+            slot,
+            epoch: slot * TSpec::slots_per_epoch() as u32,
+            beacon_attestation_subnet_weight,
+            max_positive_score,
+            decay_interval: slot,
+            decay_to_zero: 0.01,
+            mesh_n: gs_config.mesh_n(),
+            max_committees_per_slot: chain_spec.max_committees_per_slot,
+            target_committee_size: chain_spec.target_committee_size,
+            target_aggregators_per_committee: chain_spec.target_aggregators_per_committee as usize,
+            attestation_subnet_count: chain_spec.attestation_subnet_count,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The global (topic independent) score parameters.
+    ///
+    /// The topic parameters are set separately with `Self::topic_params`, as the topics change
+    /// with the fork digest.
+    pub fn peer_score_params(&self, thresholds: &PeerScoreThresholds) -> PeerScoreParams {
+        let mut params = PeerScoreParams::default();
+
+        params.decay_interval = self.decay_interval;
+        params.decay_to_zero = self.decay_to_zero;
+        params.retain_score = self.epoch * 100;
+        params.app_specific_weight = 1.0;
+        params.ip_colocation_factor_threshold = 3.0;
+        params.behaviour_penalty_threshold = 6.0;
+        params.behaviour_penalty_decay = self.score_parameter_decay(self.epoch * 10);
+
+        let target_value = Self::decay_convergence(
+            params.behaviour_penalty_decay,
+            10.0 / TSpec::slots_per_epoch() as f64,
+        ) - params.behaviour_penalty_threshold;
+        params.behaviour_penalty_weight = thresholds.gossip_threshold / target_value.powi(2);
+
+        params.topic_score_cap = self.max_positive_score * 0.5;
+        params.ip_colocation_factor_weight = -params.topic_score_cap;
+
+        params
+    }
+
+    /// The score parameters of every topic for the fork given by `enr_fork_id`.
+    ///
+    /// The parameters of the block, aggregate and attestation subnet topics are scaled by the
+    /// number of `active_validators`.
+    pub fn topic_params(
+        &self,
+        enr_fork_id: &EnrForkId,
+        active_validators: usize,
+        current_slot: Slot,
+    ) -> error::Result<Vec<(TopicHash, TopicScoreParams)>> {
+        let slots_per_epoch = TSpec::slots_per_epoch();
+        let topic_hash = |kind: GossipKind| -> TopicHash {
+            let topic: Topic =
+                GossipTopic::new(kind, GossipEncoding::default(), enr_fork_id.fork_digest).into();
+            topic.hash()
+        };
+
+        let (aggregators_per_slot, committees_per_slot) =
+            self.expected_aggregator_count_per_slot(active_validators)?;
+        let multiple_bursts_per_subnet_per_epoch =
+            committees_per_slot as u64 >= 2 * self.attestation_subnet_count / slots_per_epoch;
+
+        let mut topics = vec![
+            (
+                topic_hash(GossipKind::VoluntaryExit),
+                self.get_topic_params(
+                    VOLUNTARY_EXIT_WEIGHT,
+                    4.0 / slots_per_epoch as f64,
+                    self.epoch * 100,
+                    None,
+                ),
+            ),
+            (
+                topic_hash(GossipKind::AttesterSlashing),
+                self.get_topic_params(
+                    ATTESTER_SLASHING_WEIGHT,
+                    1.0 / 5.0 / slots_per_epoch as f64,
+                    self.epoch * 100,
+                    None,
+                ),
+            ),
+            (
+                topic_hash(GossipKind::ProposerSlashing),
+                self.get_topic_params(
+                    PROPOSER_SLASHING_WEIGHT,
+                    1.0 / 5.0 / slots_per_epoch as f64,
+                    self.epoch * 100,
+                    None,
+                ),
+            ),
+            (
+                topic_hash(GossipKind::BeaconBlock),
+                self.get_topic_params(
+                    BEACON_BLOCK_WEIGHT,
+                    1.0,
+                    self.epoch * 20,
+                    Some((slots_per_epoch * 5, 3.0, self.epoch, current_slot)),
+                ),
+            ),
+            (
+                topic_hash(GossipKind::BeaconAggregateAndProof),
+                self.get_topic_params(
+                    BEACON_AGGREGATE_PROOF_WEIGHT,
+                    aggregators_per_slot,
+                    self.epoch,
+                    Some((slots_per_epoch * 2, 4.0, self.epoch, current_slot)),
+                ),
+            ),
+        ];
+
+        let (decay_slots, activation_window) = if multiple_bursts_per_subnet_per_epoch {
+            (4, self.slot * (slots_per_epoch as u32 / 2 + 1))
+        } else {
+            (16, self.epoch * 3)
+        };
+        let subnet_params = self.get_topic_params(
+            self.beacon_attestation_subnet_weight,
+            active_validators as f64
+                / self.attestation_subnet_count as f64
+                / slots_per_epoch as f64,
+            self.epoch
+                * if multiple_bursts_per_subnet_per_epoch {
+                    1
+                } else {
+                    4
+                },
+            Some((
+                slots_per_epoch * decay_slots,
+                16.0,
+                activation_window,
+                current_slot,
+            )),
+        );
+        for subnet_id in 0..self.attestation_subnet_count {
+            topics.push((
+                topic_hash(GossipKind::Attestation(SubnetId::new(subnet_id))),
+                subnet_params.clone(),
+            ));
+        }
+
+        Ok(topics)
+    }
+
+    fn score_parameter_decay(&self, decay_time: Duration) -> f64 {
+        let ticks = decay_time.as_secs_f64() / self.decay_interval.as_secs_f64();
+        self.decay_to_zero.powf(1.0 / ticks)
+    }
+
+    /// The value a counter which grows by `rate` and decays by `decay` each interval converges to.
+    fn decay_convergence(decay: f64, rate: f64) -> f64 {
+        rate / (1.0 - decay)
+    }
+
+    fn threshold(decay: f64, rate: f64) -> f64 {
+        Self::decay_convergence(decay, rate) * decay
+    }
+
+    /// Returns the expected number of aggregators per slot and the number of committees per slot.
+    fn expected_aggregator_count_per_slot(
+        &self,
+        active_validators: usize,
+    ) -> error::Result<(f64, usize)> {
+        let slots_per_epoch = TSpec::slots_per_epoch() as usize;
+        let committees_per_slot = max(
+            1,
+            (active_validators / slots_per_epoch / self.target_committee_size)
+                .min(self.max_committees_per_slot),
+        );
+        let committees = committees_per_slot * slots_per_epoch;
+        if active_validators < committees {
+            return Err(format!(
+                "Too few active validators ({}) for {} committees",
+                active_validators, committees
+            )
+            .into());
+        }
+
+        let smaller_committee_size = active_validators / committees;
+        let num_larger_committees = active_validators - smaller_committee_size * committees;
+
+        let modulo_smaller = max(
+            1,
+            smaller_committee_size / self.target_aggregators_per_committee,
+        );
+        let modulo_larger = max(
+            1,
+            (smaller_committee_size + 1) / self.target_aggregators_per_committee,
+        );
+
+        Ok((
+            ((num_larger_committees * (smaller_committee_size + 1)) as f64 / modulo_larger as f64
+                + ((committees - num_larger_committees) * smaller_committee_size) as f64
+                    / modulo_smaller as f64)
+                / slots_per_epoch as f64,
+            committees_per_slot,
+        ))
+    }
+
+    /// `mesh_message_info` is the mesh delivery decay time in slots, the cap factor, the
+    /// activation window and the current slot.
+    fn get_topic_params(
+        &self,
+        topic_weight: f64,
+        expected_message_rate: f64,
+        first_message_decay_time: Duration,
+        mesh_message_info: Option<(u64, f64, Duration, Slot)>,
+    ) -> TopicScoreParams {
+        let mut t_params = TopicScoreParams::default();
+
+        t_params.topic_weight = topic_weight;
+
+        t_params.time_in_mesh_quantum = self.slot;
+        t_params.time_in_mesh_cap = 3600.0 / t_params.time_in_mesh_quantum.as_secs_f64();
+        t_params.time_in_mesh_weight = MAX_IN_MESH_SCORE / t_params.time_in_mesh_cap;
+
+        t_params.first_message_deliveries_decay =
+            self.score_parameter_decay(first_message_decay_time);
+        t_params.first_message_deliveries_cap = Self::decay_convergence(
+            t_params.first_message_deliveries_decay,
+            2.0 * expected_message_rate / self.mesh_n as f64,
+        );
+        t_params.first_message_deliveries_weight =
+            MAX_FIRST_MESSAGE_DELIVERIES_SCORE / t_params.first_message_deliveries_cap;
+
+        if let Some((decay_slots, cap_factor, activation_window, current_slot)) = mesh_message_info
+        {
+            let decay_time = self.slot * decay_slots as u32;
+            t_params.mesh_message_deliveries_decay = self.score_parameter_decay(decay_time);
+            t_params.mesh_message_deliveries_threshold = Self::threshold(
+                t_params.mesh_message_deliveries_decay,
+                expected_message_rate / 50.0,
+            );
+            t_params.mesh_message_deliveries_cap =
+                (cap_factor * t_params.mesh_message_deliveries_threshold).max(2.0);
+            t_params.mesh_message_deliveries_activation = activation_window;
+            t_params.mesh_message_deliveries_window = Duration::from_secs(2);
+            t_params.mesh_failure_penalty_decay = t_params.mesh_message_deliveries_decay;
+            t_params.mesh_message_deliveries_weight = -self.max_positive_score
+                / (t_params.topic_weight * t_params.mesh_message_deliveries_threshold.powi(2));
+            t_params.mesh_failure_penalty_weight = t_params.mesh_message_deliveries_weight;
+
+            // Early in the chain there are too few messages for the delivery rate to be
+            // meaningful.
+            if decay_slots >= current_slot.as_u64() {
+                t_params.mesh_message_deliveries_threshold = 0.0;
+                t_params.mesh_message_deliveries_weight = 0.0;
+            }
+        } else {
+            t_params.mesh_message_deliveries_weight = 0.0;
+            t_params.mesh_message_deliveries_threshold = 0.0;
+            t_params.mesh_message_deliveries_decay = 0.0;
+            t_params.mesh_message_deliveries_cap = 0.0;
+            t_params.mesh_message_deliveries_window = Duration::from_secs(0);
+            t_params.mesh_message_deliveries_activation = Duration::from_secs(0);
+            t_params.mesh_failure_penalty_decay = 0.0;
+            t_params.mesh_failure_penalty_weight = 0.0;
+        }
+
+        t_params.invalid_message_deliveries_weight =
+            -self.max_positive_score / t_params.topic_weight;
+        t_params.invalid_message_deliveries_decay = self.score_parameter_decay(self.epoch * 50);
+
+        t_params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::gossipsub::GossipsubConfigBuilder;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn settings() -> PeerScoreSettings<E> {
+        let gs_config = GossipsubConfigBuilder::new()
+            .build()
+            .expect("valid gossipsub configuration");
+        PeerScoreSettings::new(&E::default_spec(), &gs_config)
+    }
+
+    #[test]
+    fn topic_params_cover_all_topics() {
+        let spec = E::default_spec();
+        let params = settings()
+            .topic_params(&EnrForkId::default(), 100_000, Slot::new(1_000))
+            .expect("should compute topic params");
+
+        assert_eq!(params.len(), 5 + spec.attestation_subnet_count as usize);
+        for (_, topic_params) in params {
+            assert!(topic_params.topic_weight > 0.0);
+            assert!(topic_params.invalid_message_deliveries_weight < 0.0);
+        }
+    }
+
+    #[test]
+    fn mesh_delivery_penalties_disabled_at_genesis() {
+        let params = settings()
+            .topic_params(&EnrForkId::default(), 100_000, Slot::new(0))
+            .expect("should compute topic params");
+
+        for (_, topic_params) in params {
+            assert_eq!(topic_params.mesh_message_deliveries_weight, 0.0);
+        }
+    }
+
+    #[test]
+    fn too_few_validators_is_an_error() {
+        assert!(settings()
+            .topic_params(&EnrForkId::default(), 1, Slot::new(0))
+            .is_err());
+    }
+}
@@ -1,14 +1,19 @@
-use crate::peer_manager::{score::PeerAction, PeerManager, PeerManagerEvent};
+use crate::peer_manager::{score::PeerAction, BanTarget, PeerManager, PeerManagerEvent};
 use crate::rpc::*;
-use crate::types::{EnrBitfield, GossipEncoding, GossipKind, GossipTopic, SubnetDiscovery};
-use crate::Eth2Enr;
+use crate::types::{
+    EnrBitfield, EnrSyncCommitteeBitfield, GossipEncoding, GossipFilter, GossipKind, GossipTopic,
+    SubnetDiscovery,
+};
 use crate::{error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
+use crate::{EnrExt, Eth2Enr};
 use futures::prelude::*;
+use gossipsub_scoring_parameters::{lighthouse_gossip_thresholds, PeerScoreSettings};
 use handler::{BehaviourHandler, BehaviourHandlerIn, BehaviourHandlerOut, DelegateIn, DelegateOut};
 use libp2p::{
     core::{
         connection::{ConnectedPoint, ConnectionId, ListenerId},
         identity::Keypair,
+        multiaddr::Protocol as MProtocol,
         Multiaddr,
     },
     gossipsub::{
@@ -30,11 +35,14 @@ use std::path::PathBuf;
 use std::{
     collections::VecDeque,
     marker::PhantomData,
+    net::IpAddr,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
-use types::{EnrForkId, EthSpec, SignedBeaconBlock, SubnetId};
+use types::{ChainSpec, EnrForkId, EthSpec, SignedBeaconBlock, Slot, SubnetId};
 
+pub mod gossipsub_scoring_parameters;
 mod handler;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
@@ -66,10 +74,18 @@ pub struct Behaviour<TSpec: EthSpec> {
     // NOTE: This can be accessed via the network_globals ENR. However we keep it here for quick
     // lookups for every gossipsub message send.
     enr_fork_id: EnrForkId,
+    /// Derives the gossipsub score parameters from the chain spec.
+    score_settings: PeerScoreSettings<TSpec>,
+    /// The number of active validators the topic score parameters were last computed with.
+    active_validators: usize,
+    /// The slot the topic score parameters were last computed at.
+    current_slot: Slot,
     /// The waker for the current thread.
     waker: Option<std::task::Waker>,
     /// Directory where metadata is stored
     network_dir: PathBuf,
+    /// The topics subscribed to on start-up, re-subscribed to if the gossip filter is relaxed.
+    core_topics: Vec<GossipKind>,
     /// Logger for behaviour actions.
     log: slog::Logger,
 }
@@ -80,6 +96,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         local_key: &Keypair,
         net_conf: &NetworkConfig,
         network_globals: Arc<NetworkGlobals<TSpec>>,
+        chain_spec: &ChainSpec,
         log: &slog::Logger,
     ) -> error::Result<Self> {
         let behaviour_log = log.new(o!());
@@ -97,17 +114,17 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
         let meta_data = load_or_build_metadata(&net_conf.network_dir, &log);
 
-        let gossipsub = Gossipsub::new(MessageAuthenticity::Anonymous, net_conf.gs_config.clone())
-            .map_err(|e| format!("Could not construct gossipsub: {:?}", e))?;
+        let mut gossipsub =
+            Gossipsub::new(MessageAuthenticity::Anonymous, net_conf.gs_config.clone())
+                .map_err(|e| format!("Could not construct gossipsub: {:?}", e))?;
 
-        // Temporarily disable scoring until parameters are tested.
-        /*
+        let score_settings = PeerScoreSettings::new(chain_spec, &net_conf.gs_config);
+        let thresholds = lighthouse_gossip_thresholds();
         gossipsub
-            .with_peer_score(PeerScoreParams::default(), PeerScoreThresholds::default())
-            .expect("Valid score params and thresholds");
-        */
+            .with_peer_score(score_settings.peer_score_params(&thresholds), thresholds)
+            .map_err(|e| format!("Invalid gossipsub score parameters: {:?}", e))?;
 
-        Ok(Behaviour {
+        let mut behaviour = Behaviour {
             eth2_rpc: RPC::new(log.clone()),
             gossipsub,
             identify,
@@ -118,10 +135,19 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             meta_data,
             network_globals,
             enr_fork_id,
+            score_settings,
+            // Until the network service informs us of the validator set, assume the minimum
+            // number of validators required for genesis.
+            active_validators: TSpec::minimum_validator_count(),
+            current_slot: Slot::new(0),
             waker: None,
             network_dir: net_conf.network_dir.clone(),
+            core_topics: net_conf.topics.clone(),
             log: behaviour_log,
-        })
+        };
+        behaviour.set_topic_score_params()?;
+
+        Ok(behaviour)
     }
 
     /// Attempts to connect to a libp2p peer.
@@ -139,6 +165,16 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.network_globals.local_enr()
     }
 
+    /// Returns the meta data which is sent to our peers.
+    pub fn local_metadata(&self) -> &MetaData<TSpec> {
+        &self.meta_data
+    }
+
+    /// Returns the `EnrForkId` of the fork we are currently following.
+    pub fn enr_fork_id(&self) -> &EnrForkId {
+        &self.enr_fork_id
+    }
+
     /// Obtain a reference to the gossipsub protocol.
     pub fn gs(&self) -> &Gossipsub {
         &self.gossipsub
@@ -155,9 +191,6 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             self.enr_fork_id.fork_digest,
         );
 
-        // TODO: Implement scoring
-        // let topic: Topic = gossip_topic.into();
-        // self.gossipsub.set_topic_params(t.hash(), TopicScoreParams::default());
         self.subscribe(gossip_topic)
     }
 
@@ -172,6 +205,20 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.unsubscribe(gossip_topic)
     }
 
+    /// Subscribes to a gossipsub topic kind which only exists from some fork onwards, under the
+    /// given `fork_digest`.
+    ///
+    /// The kind is added to the core topics, so that it is subscribed to again whenever the gossip
+    /// filter is relaxed.
+    pub fn subscribe_fork_topic(&mut self, kind: GossipKind, fork_digest: [u8; 4]) -> bool {
+        if !self.core_topics.contains(&kind) {
+            self.core_topics.push(kind.clone());
+        }
+
+        let gossip_topic = GossipTopic::new(kind, GossipEncoding::default(), fork_digest);
+        self.subscribe(gossip_topic)
+    }
+
     /// Subscribes to a specific subnet id;
     pub fn subscribe_to_subnet(&mut self, subnet_id: SubnetId) -> bool {
         let topic = GossipTopic::new(
@@ -179,12 +226,6 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             GossipEncoding::default(),
             self.enr_fork_id.fork_digest,
         );
-        // TODO: Implement scoring
-        /*
-        let t: Topic = topic.clone().into();
-        self.gossipsub
-            .set_topic_params(t.hash(), TopicScoreParams::default());
-        */
         self.subscribe(topic)
     }
 
@@ -198,8 +239,65 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.unsubscribe(topic)
     }
 
+    /// Replaces the gossip filter, unsubscribing from any topics it no longer allows and
+    /// re-subscribing to any start-up topics it now allows.
+    ///
+    /// Attestation subnets which become allowed are subscribed to again when they are next
+    /// required by the attestation service.
+    pub fn update_gossip_filter(&mut self, filter: GossipFilter) {
+        *self.network_globals.gossip_filter.write() = filter.clone();
+
+        let disallowed_topics = self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .filter(|topic| !filter.allows(topic.kind()))
+            .cloned()
+            .collect::<Vec<_>>();
+        for topic in disallowed_topics {
+            // we no longer participate in this subnet, stop advertising it
+            match topic.kind() {
+                GossipKind::Attestation(subnet_id) => self.update_enr_subnet(*subnet_id, false),
+                GossipKind::SyncCommitteeMessage(subnet_id) => {
+                    self.update_enr_sync_committee_subnet(*subnet_id, false)
+                }
+                _ => {}
+            }
+            self.unsubscribe(topic);
+        }
+
+        for kind in self.core_topics.clone() {
+            let topic = GossipTopic::new(
+                kind,
+                GossipEncoding::default(),
+                self.enr_fork_id.fork_digest,
+            );
+            if !self
+                .network_globals
+                .gossipsub_subscriptions
+                .read()
+                .contains(&topic)
+            {
+                self.subscribe(topic);
+            }
+        }
+    }
+
     /// Subscribes to a gossipsub topic.
+    ///
+    /// Topics which are not allowed by the gossip filter are not subscribed to.
     fn subscribe(&mut self, topic: GossipTopic) -> bool {
+        if !self
+            .network_globals
+            .gossip_filter
+            .read()
+            .allows(topic.kind())
+        {
+            debug!(self.log, "Gossip topic disabled, not subscribing"; "topic" => topic.kind().to_string());
+            return false;
+        }
+
         // update the network globals
         self.network_globals
             .gossipsub_subscriptions
@@ -243,12 +341,28 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         }
     }
 
+    /// The fork digests of all topics we are subscribed to. Around a fork this contains both the
+    /// old and the new fork digest.
+    fn subscribed_fork_digests(&self) -> Vec<[u8; 4]> {
+        let mut fork_digests = self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .map(GossipTopic::fork_digest)
+            .collect::<Vec<_>>();
+        fork_digests.sort_unstable();
+        fork_digests.dedup();
+        fork_digests
+    }
+
     /// Publishes a list of messages on the pubsub (gossipsub) behaviour, choosing the encoding.
     pub fn publish(&mut self, messages: Vec<PubsubMessage<TSpec>>) {
         for message in messages {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 match message.encode(GossipEncoding::default()) {
                     Ok(message_data) => {
+                        let message_len = message_data.len();
                         if let Err(e) = self.gossipsub.publish(topic.clone().into(), message_data) {
                             slog::warn!(self.log, "Could not publish message"; "error" => format!("{:?}", e));
 
@@ -271,6 +385,16 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                                     };
                                 }
                             }
+                        } else {
+                            metrics::inc_counter_vec(
+                                &metrics::GOSSIP_MESSAGES_TX_PER_TOPIC,
+                                &[&topic.kind().to_string()],
+                            );
+                            metrics::inc_counter_vec_by(
+                                &metrics::OUTBOUND_BYTES_PER_PROTOCOL,
+                                &["gossipsub"],
+                                message_len as i64,
+                            );
                         }
                     }
                     Err(e) => crit!(self.log, "Could not publish message"; "error" => e),
@@ -300,8 +424,10 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
     /// Send a request to a peer over RPC.
     pub fn send_request(&mut self, peer_id: PeerId, request_id: RequestId, request: Request) {
-        self.eth2_rpc
-            .send_request(peer_id, request_id, request.into())
+        let request: RPCRequest<TSpec> = request.into();
+        self.peer_manager
+            .request_sent(&peer_id, request_id, request.protocol());
+        self.eth2_rpc.send_request(peer_id, request_id, request)
     }
 
     /// Send a successful response to a peer over RPC.
@@ -335,12 +461,27 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
     /// Disconnects from a peer providing a reason.
     ///
-    /// This will send a goodbye, disconnect and then ban the peer.
-    /// This is fatal for a peer, and should be used in unrecoverable circumstances.
+    /// This will send a goodbye and disconnect the peer. The peer will not be re-connected to
+    /// until its re-connection backoff has elapsed.
     pub fn goodbye_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason) {
         self.peer_manager.goodbye_peer(peer_id, reason);
     }
 
+    /// Sends a goodbye with the given reason to all connected peers and disconnects them. This
+    /// does not apply any re-connection backoff and is used when shutting down.
+    pub fn goodbye_all_peers(&mut self, reason: GoodbyeReason) {
+        let connected_peers = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peer_ids()
+            .cloned()
+            .collect::<Vec<_>>();
+        for peer_id in connected_peers {
+            self.peers_to_dc.push_back((peer_id, Some(reason.clone())));
+        }
+    }
+
     /// Returns an iterator over all enr entries in the DHT.
     pub fn enr_entries(&mut self) -> Vec<Enr> {
         self.peer_manager.discovery_mut().table_entries_enr()
@@ -351,10 +492,52 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.peer_manager.discovery_mut().add_enr(enr);
     }
 
+    /// Adds the ENR of a peer known from a previous run to the routing table and dials it, if it
+    /// is not banned.
+    pub fn dial_known_peer(&mut self, enr: Enr) {
+        let peer_id = enr.peer_id();
+        self.add_enr(enr);
+        let peers = self.network_globals.peers.read();
+        let should_dial = !peers.is_banned(&peer_id)
+            && !peers.is_connected_or_dialing(&peer_id)
+            && !peers.is_in_dial_backoff(&peer_id);
+        drop(peers);
+        if should_dial {
+            debug!(self.log, "Dialing known peer"; "peer_id" => peer_id.to_string());
+            self.peer_manager.dial_peer(&peer_id);
+        }
+    }
+
+    /// Adds a peer or IP address to the user's ban list, disconnecting any affected peers.
+    pub fn ban(&mut self, target: BanTarget) {
+        self.peer_manager.ban(target);
+    }
+
+    /// Removes a peer or IP address from the user's ban list. Returns false if it was not banned.
+    pub fn unban(&mut self, target: &BanTarget) -> bool {
+        self.peer_manager.unban(target)
+    }
+
+    /// Returns the ENR, the time since it was last seen and the score of each healthy peer we
+    /// know of.
+    pub fn known_good_peers(&mut self) -> Vec<(Enr, Duration, f64)> {
+        self.peer_manager.known_good_peers()
+    }
+
     /// Updates a subnet value to the ENR bitfield.
     ///
     /// The `value` is `true` if a subnet is being added and false otherwise.
     pub fn update_enr_subnet(&mut self, subnet_id: SubnetId, value: bool) {
+        // never advertise a subnet we will not subscribe to
+        if value
+            && !self
+                .network_globals
+                .gossip_filter
+                .read()
+                .allows(&GossipKind::Attestation(subnet_id))
+        {
+            return;
+        }
         if let Err(e) = self
             .peer_manager
             .discovery_mut()
@@ -366,6 +549,31 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.update_metadata();
     }
 
+    /// Updates a sync committee subnet value in the ENR `syncnets` bitfield.
+    ///
+    /// The `value` is `true` if a sync committee subnet is being added and false otherwise.
+    pub fn update_enr_sync_committee_subnet(&mut self, subnet_id: u64, value: bool) {
+        // never advertise a subnet we will not subscribe to
+        if value
+            && !self
+                .network_globals
+                .gossip_filter
+                .read()
+                .allows(&GossipKind::SyncCommitteeMessage(subnet_id))
+        {
+            return;
+        }
+        if let Err(e) = self
+            .peer_manager
+            .discovery_mut()
+            .update_enr_sync_committee_bitfield(subnet_id, value)
+        {
+            crit!(self.log, "Could not update ENR sync committee bitfield"; "error" => e);
+        }
+        // update the local meta data which informs our peers of the update during PINGS
+        self.update_metadata();
+    }
+
     /// Attempts to discover new peers for a given subnet. The `min_ttl` gives the time at which we
     /// would like to retain the peers for.
     pub fn discover_subnet_peers(&mut self, subnet_subscriptions: Vec<SubnetDiscovery>) {
@@ -373,38 +581,118 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             .discover_subnet_peers(subnet_subscriptions)
     }
 
-    /// Updates the local ENR's "eth2" field with the latest EnrForkId.
-    pub fn update_fork_version(&mut self, enr_fork_id: EnrForkId) {
-        self.peer_manager
-            .discovery_mut()
-            .update_eth2_enr(enr_fork_id.clone());
+    /// Subscribes to a sync committee subnet and advertises it in the ENR `syncnets` bitfield.
+    ///
+    /// Unlike attestation subnets, a node only subscribes to a sync committee subnet while one of
+    /// its validators is a member of the sync committee, so the subscription is always advertised.
+    pub fn subscribe_to_sync_subnet(&mut self, subnet_id: u64) -> bool {
+        let subscribed = self.subscribe_kind(GossipKind::SyncCommitteeMessage(subnet_id));
+        if subscribed {
+            self.update_enr_sync_committee_subnet(subnet_id, true);
+        }
+        subscribed
+    }
 
-        // unsubscribe from all gossip topics and re-subscribe to their new fork counterparts
+    /// Un-Subscribes from a sync committee subnet and stops advertising it in the ENR.
+    pub fn unsubscribe_from_sync_subnet(&mut self, subnet_id: u64) -> bool {
+        let unsubscribed = self.unsubscribe_kind(GossipKind::SyncCommitteeMessage(subnet_id));
+        if unsubscribed {
+            self.update_enr_sync_committee_subnet(subnet_id, false);
+        }
+        unsubscribed
+    }
+
+    /// Subscribes to the counterparts of all our current gossip topics under `fork_digest`.
+    ///
+    /// This is done shortly before a fork so that we are part of the new meshes by the time the
+    /// fork activates. The old topics remain subscribed until `unsubscribe_from_fork_topics`.
+    pub fn subscribe_new_fork_topics(&mut self, fork_digest: [u8; 4]) {
         let subscribed_topics = self
             .network_globals
             .gossipsub_subscriptions
             .read()
             .iter()
+            .filter(|topic| topic.fork_digest() != fork_digest)
             .cloned()
             .collect::<Vec<GossipTopic>>();
 
-        //  unsubscribe from all topics
-        for topic in &subscribed_topics {
-            self.unsubscribe(topic.clone());
-        }
-
-        // re-subscribe modifying the fork version
         for mut topic in subscribed_topics {
-            *topic.digest() = enr_fork_id.fork_digest;
+            *topic.digest() = fork_digest;
             self.subscribe(topic);
         }
+    }
+
+    /// Unsubscribes from all gossip topics of the fork identified by `fork_digest`.
+    pub fn unsubscribe_from_fork_topics(&mut self, fork_digest: [u8; 4]) {
+        let old_topics = self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .filter(|topic| topic.fork_digest() == fork_digest)
+            .cloned()
+            .collect::<Vec<GossipTopic>>();
+
+        for topic in old_topics {
+            self.unsubscribe(topic);
+        }
+    }
+
+    /// Updates the local ENR's "eth2" field with the latest EnrForkId.
+    ///
+    /// The topics of the new fork are subscribed to if that hasn't happened already. Topics of the
+    /// previous fork are kept so that messages still in flight are received, the caller is
+    /// responsible for calling `unsubscribe_from_fork_topics` after a grace period.
+    pub fn update_fork_version(&mut self, enr_fork_id: EnrForkId) {
+        self.peer_manager
+            .discovery_mut()
+            .update_eth2_enr(enr_fork_id.clone());
+
+        self.subscribe_new_fork_topics(enr_fork_id.fork_digest);
 
         // update the local reference
         self.enr_fork_id = enr_fork_id;
+
+        // the topic hashes include the fork digest, so the score parameters must be set again
+        if let Err(e) = self.set_topic_score_params() {
+            warn!(self.log, "Failed to set gossipsub topic score parameters"; "error" => e.to_string());
+        }
+    }
+
+    /// Updates the gossipsub topic score parameters to reflect the number of active validators
+    /// and the current slot.
+    pub fn update_gossipsub_parameters(
+        &mut self,
+        active_validators: usize,
+        current_slot: Slot,
+    ) -> error::Result<()> {
+        self.active_validators = active_validators;
+        self.current_slot = current_slot;
+        self.set_topic_score_params()
+    }
+
+    /// Obtain the gossipsub score of a peer, if scoring is enabled and the peer is known.
+    pub fn gossipsub_peer_score(&self, peer_id: &PeerId) -> Option<f64> {
+        self.gossipsub.peer_score(peer_id)
     }
 
     /* Private internal functions */
 
+    /// Sets the score parameters of every gossipsub topic of the current fork.
+    fn set_topic_score_params(&mut self) -> error::Result<()> {
+        let topic_params = self.score_settings.topic_params(
+            &self.enr_fork_id,
+            self.active_validators,
+            self.current_slot,
+        )?;
+        for (topic_hash, params) in topic_params {
+            self.gossipsub
+                .set_topic_params(topic_hash, params)
+                .map_err(|e| format!("Invalid topic score parameters: {:?}", e))?;
+        }
+        Ok(())
+    }
+
     /// Updates the current meta data of the node to match the local ENR.
     fn update_metadata(&mut self) {
         self.meta_data.seq_number += 1;
@@ -414,6 +702,12 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             .local_enr()
             .bitfield::<TSpec>()
             .expect("Local discovery must have bitfield");
+        self.meta_data.syncnets = self
+            .peer_manager
+            .discovery()
+            .local_enr()
+            .sync_committee_bitfield::<TSpec>()
+            .expect("Local discovery must have sync committee bitfield");
         // Save the updated metadata to disk
         save_metadata_to_disk(&self.network_dir, self.meta_data.clone(), &self.log);
     }
@@ -465,9 +759,18 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                 message_id: id,
                 message: gs_msg,
             } => {
+                metrics::inc_counter_vec_by(
+                    &metrics::INBOUND_BYTES_PER_PROTOCOL,
+                    &["gossipsub"],
+                    gs_msg.data.len() as i64,
+                );
                 // Note: We are keeping track here of the peer that sent us the message, not the
                 // peer that originally published the message.
-                match PubsubMessage::decode(&gs_msg.topics, &gs_msg.data) {
+                match PubsubMessage::decode(
+                    &gs_msg.topics,
+                    &gs_msg.data,
+                    &self.subscribed_fork_digests(),
+                ) {
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "error" => e);
                         //reject the message
@@ -480,6 +783,10 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                         }
                     }
                     Ok(msg) => {
+                        metrics::inc_counter_vec(
+                            &metrics::GOSSIP_MESSAGES_RX_PER_TOPIC,
+                            &[&msg.kind().to_string()],
+                        );
                         // Notify the network
                         self.add_event(BehaviourEvent::PubsubMessage {
                             id,
@@ -541,6 +848,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                     HandlerErr::Outbound { id, proto, error } => {
                         // Inform the peer manager that a request we sent to the peer failed
                         self.peer_manager.handle_rpc_error(&peer_id, proto, &error);
+                        self.peer_manager.request_failed(&peer_id, id, proto);
                         // inform failures of requests comming outside the behaviour
                         if !matches!(id, RequestId::Behaviour) {
                             self.add_event(BehaviourEvent::RPCFailed { peer_id, id, error });
@@ -566,6 +874,8 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                     RPCRequest::Goodbye(reason) => {
                         // let the peer manager know this peer is in the process of disconnecting
                         self.peer_manager._disconnecting_peer(&peer_id);
+                        // back off from re-dialing the peer according to the reason given
+                        self.peer_manager.goodbye_received(&peer_id, &reason);
                         // queue for disconnection without a goodbye message
                         debug!(
                             self.log, "Peer sent Goodbye";
@@ -608,6 +918,8 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                     RPCResponse::Status(msg) => {
                         // inform the peer manager that we have received a status from a peer
                         self.peer_manager.peer_statusd(&peer_id);
+                        self.peer_manager
+                            .request_completed(&peer_id, id, Protocol::Status);
                         // propagate the STATUS message upwards
                         self.propagate_response(id, peer_id, Response::Status(msg));
                     }
@@ -620,10 +932,15 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                 }
             }
             Ok(RPCReceived::EndOfStream(id, termination)) => {
-                let response = match termination {
-                    ResponseTermination::BlocksByRange => Response::BlocksByRange(None),
-                    ResponseTermination::BlocksByRoot => Response::BlocksByRoot(None),
+                let (response, protocol) = match termination {
+                    ResponseTermination::BlocksByRange => {
+                        (Response::BlocksByRange(None), Protocol::BlocksByRange)
+                    }
+                    ResponseTermination::BlocksByRoot => {
+                        (Response::BlocksByRoot(None), Protocol::BlocksByRoot)
+                    }
                 };
+                self.peer_manager.request_completed(&peer_id, id, protocol);
                 self.propagate_response(id, peer_id, response);
             }
         }
@@ -636,6 +953,9 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
     ) -> Poll<NBAction<BehaviourHandlerIn<TSpec>, BehaviourEvent<TSpec>>> {
         // handle pending disconnections to perform
         if let Some((peer_id, reason)) = self.peers_to_dc.pop_front() {
+            if let Some(reason) = &reason {
+                metrics::inc_counter_vec(&metrics::GOODBYE_SENT, &[reason.metrics_label()]);
+            }
             return Poll::Ready(NBAction::NotifyHandler {
                 peer_id,
                 handler: NotifyHandler::All,
@@ -675,6 +995,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                     PeerManagerEvent::DisconnectPeer(peer_id, reason) => {
                         debug!(self.log, "PeerManager disconnecting peer";
                             "peer_id" => peer_id.to_string(), "reason" => reason.to_string());
+                        metrics::inc_counter_vec(&metrics::GOODBYE_SENT, &[reason.metrics_label()]);
                         // send one goodbye
                         return Poll::Ready(NBAction::NotifyHandler {
                             peer_id,
@@ -803,19 +1124,34 @@ impl<TSpec: EthSpec> NetworkBehaviour for Behaviour<TSpec> {
         conn_id: &ConnectionId,
         endpoint: &ConnectedPoint,
     ) {
-        let goodbye_reason: Option<GoodbyeReason> = if self.peer_manager.is_banned(peer_id) {
-            // If the peer is banned, send goodbye with reason banned.
+        let remote_ip = endpoint
+            .get_remote_address()
+            .iter()
+            .find_map(|protocol| match protocol {
+                MProtocol::Ip4(ip) => Some(IpAddr::from(ip)),
+                MProtocol::Ip6(ip) => Some(IpAddr::from(ip)),
+                _ => None,
+            });
+
+        let goodbye_reason: Option<GoodbyeReason> = if self.peer_manager.is_banned(peer_id)
+            || remote_ip.map_or(false, |ip| self.peer_manager.is_ip_banned(&ip))
+        {
+            // If the peer or its IP address is banned, send goodbye with reason banned.
             Some(GoodbyeReason::Banned)
+        } else if self.peer_manager.is_in_reconnect_backoff(peer_id) {
+            // If we recently disconnected the peer for a fault, it must wait for its backoff to
+            // elapse before re-connecting.
+            Some(GoodbyeReason::BadScore)
         } else if self.peer_manager.peer_limit_reached()
             && self
                 .network_globals
                 .peers
                 .read()
                 .peer_info(peer_id)
-                .map_or(true, |i| !i.has_future_duty())
+                .map_or(true, |i| !i.has_future_duty() && !i.is_trusted)
         {
             // If we are at our peer limit and we don't need the peer for a future validator
-            // duty, send goodbye with reason TooManyPeers
+            // duty and it isn't trusted, send goodbye with reason TooManyPeers
             Some(GoodbyeReason::TooManyPeers)
         } else {
             None
@@ -1113,17 +1449,23 @@ fn load_or_build_metadata<E: EthSpec>(network_dir: &PathBuf, log: &slog::Logger)
     let mut meta_data = MetaData {
         seq_number: 0,
         attnets: EnrBitfield::<E>::default(),
+        syncnets: EnrSyncCommitteeBitfield::<E>::default(),
     };
     // Read metadata from persisted file if available
     let metadata_path = network_dir.join(METADATA_FILENAME);
     if let Ok(mut metadata_file) = File::open(metadata_path) {
         let mut metadata_ssz = Vec::new();
         if metadata_file.read_to_end(&mut metadata_ssz).is_ok() {
-            match MetaData::<E>::from_ssz_bytes(&metadata_ssz) {
+            // Files written before `syncnets` was added hold the V1 layout
+            match MetaData::<E>::from_ssz_bytes(&metadata_ssz)
+                .or_else(|_| MetaDataV1::<E>::from_ssz_bytes(&metadata_ssz).map(Into::into))
+            {
                 Ok(persisted_metadata) => {
                     meta_data.seq_number = persisted_metadata.seq_number;
-                    // Increment seq number if persisted attnet is not default
-                    if persisted_metadata.attnets != meta_data.attnets {
+                    // Increment seq number if persisted attnet or syncnet is not default
+                    if persisted_metadata.attnets != meta_data.attnets
+                        || persisted_metadata.syncnets != meta_data.syncnets
+                    {
                         meta_data.seq_number += 1;
                     }
                     debug!(log, "Loaded metadata from disk");
@@ -1,17 +1,25 @@
-use crate::types::GossipKind;
+use crate::types::{GossipFilter, GossipKind};
 use crate::Enr;
 use discv5::{Discv5Config, Discv5ConfigBuilder};
 use libp2p::gossipsub::{
     GossipsubConfig, GossipsubConfigBuilder, GossipsubMessage, MessageId, ValidationMode,
 };
-use libp2p::Multiaddr;
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
 use serde_derive::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use snap::raw::{decompress_len, Decoder};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
 use std::time::Duration;
 
 pub const GOSSIP_MAX_SIZE: usize = 1_048_576;
 
+/// Domain prepended to the decompressed payload when computing a gossipsub message-id.
+const MESSAGE_DOMAIN_VALID_SNAPPY: [u8; 4] = [1, 0, 0, 0];
+/// Domain prepended to the raw payload if it could not be snappy decompressed.
+const MESSAGE_DOMAIN_INVALID_SNAPPY: [u8; 4] = [0, 0, 0, 0];
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 /// Network configuration for lighthouse.
@@ -22,9 +30,16 @@ pub struct Config {
     /// IP address to listen on.
     pub listen_address: std::net::IpAddr,
 
+    /// An IPv6 address to listen on in addition to `listen_address`. Setting this alongside an
+    /// IPv4 `listen_address` runs libp2p in dual-stack mode.
+    pub listen_address_ipv6: Option<Ipv6Addr>,
+
     /// The TCP port that libp2p listens on.
     pub libp2p_port: u16,
 
+    /// The TCP port that libp2p listens on for `listen_address_ipv6`. Defaults to `libp2p_port`.
+    pub libp2p_port_ipv6: Option<u16>,
+
     /// UDP port that discovery listens on.
     pub discovery_port: u16,
 
@@ -38,9 +53,22 @@ pub struct Config {
     /// The tcp port to broadcast to peers in order to reach back for libp2p services.
     pub enr_tcp_port: Option<u16>,
 
+    /// The IPv6 address to broadcast to peers in the `ip6` field of our ENR.
+    pub enr_address_ipv6: Option<Ipv6Addr>,
+
+    /// The tcp port to broadcast to peers in the `tcp6` field of our ENR.
+    pub enr_tcp6_port: Option<u16>,
+
     /// Target number of connected peers.
     pub target_peers: usize,
 
+    /// The maximum concurrent inbound connections from a single IP address.
+    pub max_inbound_connections_per_ip: usize,
+
+    /// The maximum concurrent inbound connections from a single /24 (IPv4) or /64 (IPv6) network
+    /// prefix.
+    pub max_inbound_connections_per_prefix: usize,
+
     /// Gossipsub configuration parameters.
     #[serde(skip)]
     pub gs_config: GossipsubConfig,
@@ -58,14 +86,125 @@ pub struct Config {
     /// List of libp2p nodes to initially connect to.
     pub libp2p_nodes: Vec<Multiaddr>,
 
+    /// List of trusted peers, as multiaddrs including the `p2p` protocol. Trusted peers are
+    /// always dialed, are never scored down or banned and are never pruned.
+    pub trusted_peers: Vec<Multiaddr>,
+
     /// Client version
     pub client_version: String,
 
     /// Disables the discovery protocol from starting.
     pub disable_discovery: bool,
 
+    /// Attempt to construct external port mappings with UPnP.
+    pub upnp_enabled: bool,
+
+    /// Only make outbound connections. No inbound ports are bound and no address is advertised in
+    /// the local ENR.
+    pub disable_listen: bool,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
+
+    /// Restricts the gossip topics which are subscribed to.
+    pub gossip_filter: GossipFilter,
+
+    /// Delays the processing of received gossip messages by this duration.
+    ///
+    /// Only intended for simulating a poorly connected node.
+    #[cfg(feature = "fault-injection")]
+    pub gossip_delay: Option<Duration>,
+
+    /// Serves blocks with invalid signatures in response to `BlocksByRange` requests.
+    ///
+    /// Only intended for simulating a faulty peer.
+    #[cfg(feature = "fault-injection")]
+    pub serve_invalid_blocks: bool,
+}
+
+impl Config {
+    /// Returns the `PeerId` of each trusted peer along with the addresses it may be dialed on.
+    ///
+    /// Multiaddrs without a `p2p` protocol are ignored.
+    pub fn trusted_peer_addresses(&self) -> HashMap<PeerId, Vec<Multiaddr>> {
+        let mut trusted_peers: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
+        for multiaddr in &self.trusted_peers {
+            let peer_id = multiaddr.iter().find_map(|protocol| match protocol {
+                Protocol::P2p(multihash) => PeerId::from_multihash(multihash).ok(),
+                _ => None,
+            });
+            if let Some(peer_id) = peer_id {
+                // the `p2p` protocol is not understood by the transport when dialing
+                let address = multiaddr
+                    .iter()
+                    .filter(|protocol| !matches!(protocol, Protocol::P2p(_)))
+                    .collect();
+                trusted_peers.entry(peer_id).or_default().push(address);
+            }
+        }
+        trusted_peers
+    }
+
+    /// Returns the TCP multiaddrs libp2p should listen on. In dual-stack mode this contains both
+    /// the IPv4 and the IPv6 listening address. This is empty in outbound-only mode.
+    pub fn listen_multiaddrs(&self) -> Vec<Multiaddr> {
+        if self.disable_listen {
+            return vec![];
+        }
+
+        let mut multiaddrs = Vec::with_capacity(2);
+
+        let mut multiaddr = Multiaddr::from(self.listen_address);
+        multiaddr.push(Protocol::Tcp(self.libp2p_port));
+        multiaddrs.push(multiaddr);
+
+        if let Some(address) = self.listen_address_ipv6 {
+            // a single IPv6 listen address has already been added above
+            if !self.listen_address.is_ipv6() {
+                let mut multiaddr = Multiaddr::from(IpAddr::V6(address));
+                multiaddr.push(Protocol::Tcp(self.tcp6_port()));
+                multiaddrs.push(multiaddr);
+            }
+        }
+        multiaddrs
+    }
+
+    /// The TCP port libp2p is reachable on over IPv6, if it listens on IPv6 at all.
+    pub fn tcp6_port(&self) -> u16 {
+        if self.listen_address.is_ipv6() {
+            self.libp2p_port
+        } else {
+            self.libp2p_port_ipv6.unwrap_or(self.libp2p_port)
+        }
+    }
+
+    /// Returns true if libp2p listens on an IPv6 address.
+    pub fn listens_on_ipv6(&self) -> bool {
+        self.listen_address.is_ipv6() || self.listen_address_ipv6.is_some()
+    }
+
+    /// The socket discovery listens on.
+    ///
+    /// Discv5 only supports a single socket, so in dual-stack mode discovery runs over IPv4 and
+    /// IPv6 peers are found through the `ip6` and `tcp6` fields of our ENR.
+    ///
+    /// In outbound-only mode discovery still requires a UDP socket to send queries from, so an
+    /// ephemeral port is used.
+    pub fn discovery_listen_socket(&self) -> SocketAddr {
+        if self.disable_listen {
+            return SocketAddr::new(self.listen_address, 0);
+        }
+        SocketAddr::new(self.listen_address, self.discovery_port)
+    }
+}
+
+/// Decompresses a snappy block-format gossip payload, refusing payloads that would decompress to
+/// more than `GOSSIP_MAX_SIZE`.
+fn snappy_decompress_gossip(data: &[u8]) -> Option<Vec<u8>> {
+    match decompress_len(data) {
+        Ok(n) if n <= GOSSIP_MAX_SIZE => Decoder::new().decompress_vec(data).ok(),
+        _ => None,
+    }
 }
 
 impl Default for Config {
@@ -85,12 +224,14 @@ impl Default for Config {
         ];
 
         // The function used to generate a gossipsub message id
-        // We use base64(SHA256(data)) for content addressing
+        // We use the first 20 bytes of SHA256(domain ++ data) for content addressing, as per the
+        // spec. `data` is the decompressed payload, or the raw payload if it isn't valid snappy.
         let gossip_message_id = |message: &GossipsubMessage| {
-            MessageId::from(base64::encode_config(
-                &Sha256::digest(&message.data),
-                base64::URL_SAFE_NO_PAD,
-            ))
+            let preimage = match snappy_decompress_gossip(&message.data) {
+                Some(decompressed) => [&MESSAGE_DOMAIN_VALID_SNAPPY[..], &decompressed].concat(),
+                None => [&MESSAGE_DOMAIN_INVALID_SNAPPY[..], &message.data].concat(),
+            };
+            MessageId::from(&Sha256::digest(&preimage)[..20])
         };
 
         // gossipsub configuration
@@ -132,20 +273,34 @@ impl Default for Config {
         Config {
             network_dir,
             listen_address: "0.0.0.0".parse().expect("valid ip address"),
+            listen_address_ipv6: None,
             libp2p_port: 9000,
+            libp2p_port_ipv6: None,
             discovery_port: 9000,
             enr_address: None,
             enr_udp_port: None,
             enr_tcp_port: None,
+            enr_address_ipv6: None,
+            enr_tcp6_port: None,
             target_peers: 50,
+            max_inbound_connections_per_ip: 5,
+            max_inbound_connections_per_prefix: 20,
             gs_config,
             discv5_config,
             boot_nodes_enr: vec![],
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
+            trusted_peers: vec![],
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
+            upnp_enabled: true,
+            disable_listen: false,
             topics,
+            gossip_filter: GossipFilter::default(),
+            #[cfg(feature = "fault-injection")]
+            gossip_delay: None,
+            #[cfg(feature = "fault-injection")]
+            serve_invalid_blocks: false,
         }
     }
 }
@@ -0,0 +1,286 @@
+//! Limits on the number of concurrent inbound connections from a single origin.
+//!
+//! The limits are applied at the transport layer, before any security or multiplexing upgrades
+//! are negotiated, so that excess connections from a single IP address or network prefix are
+//! refused as cheaply as possible. IPv4 addresses are grouped by their /24 prefix and IPv6
+//! addresses by their /64 prefix. Loopback addresses are exempt so that local testnets are
+//! unaffected.
+
+use crate::metrics;
+use futures::future;
+use futures::io::{AsyncRead, AsyncWrite};
+use libp2p::core::{multiaddr::Protocol, ConnectedPoint};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// The reason an inbound connection was refused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitExceeded {
+    /// Too many connections from the same IP address.
+    Ip,
+    /// Too many connections from the same network prefix.
+    Prefix,
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::Ip => write!(f, "ip"),
+            LimitExceeded::Prefix => write!(f, "prefix"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ConnectionCounts {
+    per_ip: HashMap<IpAddr, usize>,
+    per_prefix: HashMap<IpAddr, usize>,
+}
+
+struct LimitsInner {
+    /// The maximum concurrent inbound connections from a single IP address.
+    max_per_ip: usize,
+    /// The maximum concurrent inbound connections from a single network prefix.
+    max_per_prefix: usize,
+    counts: Mutex<ConnectionCounts>,
+}
+
+impl LimitsInner {
+    /// Releases a connection previously acquired for `ip`.
+    fn release(&self, ip: IpAddr) {
+        let mut counts = self.counts.lock();
+        decrement(&mut counts.per_ip, ip);
+        decrement(&mut counts.per_prefix, network_prefix(ip));
+    }
+}
+
+/// Tracks the concurrent inbound connections per IP address and per network prefix.
+///
+/// This is a cheaply cloneable handle to shared state.
+#[derive(Clone)]
+pub struct InboundConnectionLimits {
+    inner: Arc<LimitsInner>,
+}
+
+impl InboundConnectionLimits {
+    pub fn new(max_per_ip: usize, max_per_prefix: usize) -> Self {
+        InboundConnectionLimits {
+            inner: Arc::new(LimitsInner {
+                max_per_ip,
+                max_per_prefix,
+                counts: Mutex::new(ConnectionCounts::default()),
+            }),
+        }
+    }
+
+    /// Registers a new inbound connection from `ip`.
+    ///
+    /// Returns a guard which releases the connection when dropped, or the limit which would be
+    /// exceeded by accepting the connection.
+    pub fn try_acquire(&self, ip: IpAddr) -> Result<ConnectionGuard, LimitExceeded> {
+        let mut counts = self.inner.counts.lock();
+        let prefix = network_prefix(ip);
+
+        if counts.per_ip.get(&ip).copied().unwrap_or(0) >= self.inner.max_per_ip {
+            return Err(LimitExceeded::Ip);
+        }
+        if counts.per_prefix.get(&prefix).copied().unwrap_or(0) >= self.inner.max_per_prefix {
+            return Err(LimitExceeded::Prefix);
+        }
+
+        *counts.per_ip.entry(ip).or_default() += 1;
+        *counts.per_prefix.entry(prefix).or_default() += 1;
+
+        Ok(ConnectionGuard {
+            limits: self.inner.clone(),
+            ip,
+        })
+    }
+
+    /// Checks a newly established raw connection against the limits.
+    ///
+    /// Outbound connections and inbound connections from loopback addresses are never limited.
+    pub fn limit_connection<S>(
+        &self,
+        stream: S,
+        endpoint: ConnectedPoint,
+    ) -> future::Ready<Result<LimitedStream<S>, io::Error>> {
+        let remote_ip = match &endpoint {
+            ConnectedPoint::Dialer { .. } => None,
+            ConnectedPoint::Listener { send_back_addr, .. } => {
+                send_back_addr.iter().find_map(|protocol| match protocol {
+                    Protocol::Ip4(ip) => Some(IpAddr::from(ip)),
+                    Protocol::Ip6(ip) => Some(IpAddr::from(ip)),
+                    _ => None,
+                })
+            }
+        };
+
+        let guard = match remote_ip {
+            Some(ip) if !ip.is_loopback() => match self.try_acquire(ip) {
+                Ok(guard) => Some(guard),
+                Err(limit) => {
+                    metrics::inc_counter_vec(
+                        &metrics::INBOUND_CONNECTIONS_REJECTED,
+                        &[&limit.to_string()],
+                    );
+                    return future::ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionRefused,
+                        format!("Inbound connection limit per {} reached for {}", limit, ip),
+                    )));
+                }
+            },
+            _ => None,
+        };
+
+        future::ready(Ok(LimitedStream {
+            inner: stream,
+            _guard: guard,
+        }))
+    }
+}
+
+fn decrement(counts: &mut HashMap<IpAddr, usize>, key: IpAddr) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+/// Returns the /24 prefix of an IPv4 address or the /64 prefix of an IPv6 address.
+fn network_prefix(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, c, 0))
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(
+                segments[0],
+                segments[1],
+                segments[2],
+                segments[3],
+                0,
+                0,
+                0,
+                0,
+            ))
+        }
+    }
+}
+
+/// Releases an inbound connection slot when dropped.
+pub struct ConnectionGuard {
+    limits: Arc<LimitsInner>,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.limits.release(self.ip);
+    }
+}
+
+/// A raw connection which holds its inbound connection slot for as long as it is open.
+pub struct LimitedStream<S> {
+    inner: S,
+    _guard: Option<ConnectionGuard>,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for LimitedStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for LimitedStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn limits_per_ip() {
+        let limits = InboundConnectionLimits::new(2, 10);
+
+        let first = limits.try_acquire(ip("1.2.3.4")).unwrap();
+        let _second = limits.try_acquire(ip("1.2.3.4")).unwrap();
+        assert_eq!(
+            limits.try_acquire(ip("1.2.3.4")).err(),
+            Some(LimitExceeded::Ip)
+        );
+
+        // other addresses are unaffected
+        assert!(limits.try_acquire(ip("1.2.3.5")).is_ok());
+
+        // dropping a connection frees its slot
+        drop(first);
+        assert!(limits.try_acquire(ip("1.2.3.4")).is_ok());
+    }
+
+    #[test]
+    fn limits_per_prefix() {
+        let limits = InboundConnectionLimits::new(10, 2);
+
+        let _first = limits.try_acquire(ip("1.2.3.4")).unwrap();
+        let _second = limits.try_acquire(ip("1.2.3.5")).unwrap();
+        assert_eq!(
+            limits.try_acquire(ip("1.2.3.6")).err(),
+            Some(LimitExceeded::Prefix)
+        );
+
+        // a different /24 is unaffected
+        assert!(limits.try_acquire(ip("1.2.4.4")).is_ok());
+    }
+
+    #[test]
+    fn ipv6_prefix() {
+        assert_eq!(
+            network_prefix(ip("2001:db8:1:2:3:4:5:6")),
+            ip("2001:db8:1:2::")
+        );
+        assert_eq!(network_prefix(ip("10.0.1.255")), ip("10.0.1.0"));
+    }
+
+    #[test]
+    fn released_entries_are_removed() {
+        let limits = InboundConnectionLimits::new(1, 1);
+        drop(limits.try_acquire(ip("1.2.3.4")).unwrap());
+
+        let counts = limits.inner.counts.lock();
+        assert!(counts.per_ip.is_empty());
+        assert!(counts.per_prefix.is_empty());
+    }
+}
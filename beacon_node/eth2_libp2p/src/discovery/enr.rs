@@ -4,7 +4,7 @@ pub use discv5::enr::{self, CombinedKey, EnrBuilder};
 
 use super::enr_ext::CombinedKeyExt;
 use super::ENR_FILENAME;
-use crate::types::{Enr, EnrBitfield};
+use crate::types::{Enr, EnrBitfield, EnrSyncCommitteeBitfield};
 use crate::NetworkConfig;
 use discv5::enr::EnrKey;
 use libp2p::core::identity::Keypair;
@@ -21,12 +21,19 @@ use types::{EnrForkId, EthSpec};
 pub const ETH2_ENR_KEY: &str = "eth2";
 /// The ENR field specifying the subnet bitfield.
 pub const BITFIELD_ENR_KEY: &str = "attnets";
+/// The ENR field specifying the sync committee subnet bitfield.
+pub const SYNC_COMMITTEE_BITFIELD_ENR_KEY: &str = "syncnets";
 
 /// Extension trait for ENR's within Eth2.
 pub trait Eth2Enr {
     /// The subnet bitfield associated with the ENR.
     fn bitfield<TSpec: EthSpec>(&self) -> Result<EnrBitfield<TSpec>, &'static str>;
 
+    /// The sync committee subnet bitfield associated with the ENR.
+    fn sync_committee_bitfield<TSpec: EthSpec>(
+        &self,
+    ) -> Result<EnrSyncCommitteeBitfield<TSpec>, &'static str>;
+
     fn eth2(&self) -> Result<EnrForkId, &'static str>;
 }
 
@@ -40,6 +47,17 @@ impl Eth2Enr for Enr {
             .map_err(|_| "Could not decode the ENR SSZ bitfield")
     }
 
+    fn sync_committee_bitfield<TSpec: EthSpec>(
+        &self,
+    ) -> Result<EnrSyncCommitteeBitfield<TSpec>, &'static str> {
+        let bitfield_bytes = self
+            .get(SYNC_COMMITTEE_BITFIELD_ENR_KEY)
+            .ok_or_else(|| "ENR sync committee bitfield non-existent")?;
+
+        BitVector::<TSpec::SyncCommitteeSubnetCount>::from_ssz_bytes(bitfield_bytes)
+            .map_err(|_| "Could not decode the ENR SSZ sync committee bitfield")
+    }
+
     fn eth2(&self) -> Result<EnrForkId, &'static str> {
         let eth2_bytes = self
             .get(ETH2_ENR_KEY)
@@ -122,16 +140,38 @@ pub fn build_or_load_enr<T: EthSpec>(
 
 pub fn create_enr_builder_from_config<T: EnrKey>(config: &NetworkConfig) -> EnrBuilder<T> {
     let mut builder = EnrBuilder::new("v4");
+    // in outbound-only mode we are not reachable, so advertise no address
+    if config.disable_listen {
+        return builder;
+    }
     if let Some(enr_address) = config.enr_address {
         builder.ip(enr_address);
     }
+    if let Some(enr_address_ipv6) = config.enr_address_ipv6 {
+        builder.ip(enr_address_ipv6.into());
+    }
+    // discovery only listens on a single socket, the udp port belongs to its address family
     if let Some(udp_port) = config.enr_udp_port {
-        builder.udp(udp_port);
+        if config.discovery_listen_socket().is_ipv6() {
+            builder.udp6(udp_port);
+        } else {
+            builder.udp(udp_port);
+        }
     }
     // we always give it our listening tcp port
     // TODO: Add uPnP support to map udp and tcp ports
-    let tcp_port = config.enr_tcp_port.unwrap_or_else(|| config.libp2p_port);
-    builder.tcp(tcp_port).tcp(config.libp2p_port);
+    if config.listen_address.is_ipv4() {
+        let tcp_port = config.enr_tcp_port.unwrap_or_else(|| config.libp2p_port);
+        builder.tcp(tcp_port).tcp(config.libp2p_port);
+    }
+    if config.listens_on_ipv6() {
+        let tcp6_port = if config.listen_address.is_ipv6() {
+            config.enr_tcp_port.unwrap_or_else(|| config.tcp6_port())
+        } else {
+            config.enr_tcp6_port.unwrap_or_else(|| config.tcp6_port())
+        };
+        builder.tcp6(tcp6_port);
+    }
     builder
 }
 
@@ -151,6 +191,14 @@ pub fn build_enr<T: EthSpec>(
 
     builder.add_value(BITFIELD_ENR_KEY.into(), bitfield.as_ssz_bytes());
 
+    // set the "syncnets" field on our ENR
+    let sync_bitfield = EnrSyncCommitteeBitfield::<T>::new();
+
+    builder.add_value(
+        SYNC_COMMITTEE_BITFIELD_ENR_KEY.into(),
+        sync_bitfield.as_ssz_bytes(),
+    );
+
     builder
         .build(enr_key)
         .map_err(|e| format!("Could not build Local ENR: {:?}", e))
@@ -167,9 +215,15 @@ fn compare_enr(local_enr: &Enr, disk_enr: &Enr) -> bool {
         && local_enr.get(ETH2_ENR_KEY) == disk_enr.get(ETH2_ENR_KEY)
         // take preference over disk udp port if one is not specified
         && (local_enr.udp().is_none() || local_enr.udp() == disk_enr.udp())
+        // the same rules apply to the IPv6 fields
+        && (local_enr.ip6().is_none() || local_enr.ip6() == disk_enr.ip6())
+        && local_enr.tcp6() == disk_enr.tcp6()
+        && (local_enr.udp6().is_none() || local_enr.udp6() == disk_enr.udp6())
         // we need the BITFIELD_ENR_KEY key to match, otherwise we use a new ENR. This will likely only
         // be true for non-validating nodes
         && local_enr.get(BITFIELD_ENR_KEY) == disk_enr.get(BITFIELD_ENR_KEY)
+        && local_enr.get(SYNC_COMMITTEE_BITFIELD_ENR_KEY)
+            == disk_enr.get(SYNC_COMMITTEE_BITFIELD_ENR_KEY)
 }
 
 /// Saves an ENR to disk
@@ -3,14 +3,17 @@ pub(crate) mod enr;
 pub mod enr_ext;
 
 // Allow external use of the lighthouse ENR builder
-pub use enr::{build_enr, create_enr_builder_from_config, use_or_load_enr, CombinedKey, Eth2Enr};
+pub use enr::{
+    build_enr, create_enr_builder_from_config, save_enr_to_disk, use_or_load_enr, CombinedKey,
+    Eth2Enr,
+};
 pub use enr_ext::{CombinedKeyExt, EnrExt};
 pub use libp2p::core::identity::Keypair;
 
 use crate::metrics;
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, SubnetDiscovery};
 use discv5::{enr::NodeId, Discv5, Discv5Event};
-use enr::{BITFIELD_ENR_KEY, ETH2_ENR_KEY};
+use enr::{BITFIELD_ENR_KEY, ETH2_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use libp2p::core::PeerId;
@@ -101,6 +104,16 @@ impl QueryType {
     }
 }
 
+impl GroupedQueryType {
+    /// The label used to identify this type of query in metrics.
+    fn metrics_label(&self) -> &'static str {
+        match self {
+            Self::Subnet(_) => "subnet",
+            Self::FindPeers => "find_peers",
+        }
+    }
+}
+
 /// The result of a query.
 struct QueryResult(GroupedQueryType, Result<Vec<Enr>, discv5::QueryError>);
 
@@ -178,9 +191,9 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         let local_enr = network_globals.local_enr.read().clone();
 
-        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()));
+        info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> format!("{}",local_enr.node_id()), "ip" => format!("{:?}", local_enr.ip()), "udp"=> format!("{:?}", local_enr.udp()), "tcp" => format!("{:?}", local_enr.tcp()), "ip6" => format!("{:?}", local_enr.ip6()), "udp6"=> format!("{:?}", local_enr.udp6()), "tcp6" => format!("{:?}", local_enr.tcp6()));
 
-        let listen_socket = SocketAddr::new(config.listen_address, config.discovery_port);
+        let listen_socket = config.discovery_listen_socket();
 
         // convert the keypair into an ENR key
         let enr_key: CombinedKey = CombinedKey::from_libp2p(&local_key)?;
@@ -263,6 +276,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                     error!(log, "No ENR found for MultiAddr"; "addr" => original_addr.to_string())
                 }
                 Err(e) => {
+                    observe_request_failure(&e);
                     error!(log, "Error getting mapping to ENR"; "multiaddr" => original_addr.to_string(), "error" => e.to_string())
                 }
             }
@@ -397,6 +411,46 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Ok(())
     }
 
+    /// Adds/Removes a sync committee subnet from the ENR `syncnets` bitfield.
+    pub fn update_enr_sync_committee_bitfield(
+        &mut self,
+        subnet_id: u64,
+        value: bool,
+    ) -> Result<(), String> {
+        let id = subnet_id as usize;
+
+        let local_enr = self.discv5.local_enr();
+        let mut current_bitfield = local_enr.sync_committee_bitfield::<TSpec>()?;
+
+        if current_bitfield
+            .get(id)
+            .map_err(|_| String::from("Sync committee subnet ID out of bounds"))?
+            == value
+        {
+            return Err(format!(
+                "Sync committee subnet id: {} in the local ENR already has value: {}",
+                id, value
+            ));
+        }
+
+        current_bitfield.set(id, value).map_err(|_| {
+            String::from("Sync committee subnet ID out of bounds, could not set subnet ID")
+        })?;
+
+        // insert the bitfield into the ENR record
+        let _ = self.discv5.enr_insert(
+            SYNC_COMMITTEE_BITFIELD_ENR_KEY,
+            current_bitfield.as_ssz_bytes(),
+        );
+
+        // replace the global version
+        *self.network_globals.local_enr.write() = self.discv5.local_enr();
+
+        // persist modified enr to disk
+        enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
+        Ok(())
+    }
+
     /// Updates the `eth2` field of our local ENR.
     pub fn update_eth2_enr(&mut self, enr_fork_id: EnrForkId) {
         // to avoid having a reference to the spec constant, for the logging we assume
@@ -637,11 +691,18 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         let predicate: Box<dyn Fn(&Enr) -> bool + Send> =
             Box::new(move |enr: &Enr| eth2_fork_predicate(enr) && additional_predicate(enr));
 
+        let query_type = grouped_query.metrics_label();
+        metrics::inc_counter_vec(&metrics::DISCOVERY_QUERIES, &[query_type]);
+        let timer = metrics::start_timer_vec(&metrics::DISCOVERY_QUERY_TIMES, &[query_type]);
+
         // Build the future
         let query_future = self
             .discv5
             .find_node_predicate(random_node, predicate, target_peers)
-            .map(|v| QueryResult(grouped_query, v));
+            .map(move |v| {
+                metrics::stop_timer(timer);
+                QueryResult(grouped_query, v)
+            });
 
         // Add the future to active queries, to be executed.
         self.active_queries.push(Box::pin(query_future));
@@ -650,6 +711,19 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
     /// Drives the queries returning any results from completed queries.
     fn poll_queries(&mut self, cx: &mut Context) -> Option<HashMap<PeerId, Option<Instant>>> {
         while let Poll::Ready(Some(query_future)) = self.active_queries.poll_next_unpin(cx) {
+            let query_type = query_future.0.metrics_label();
+            match &query_future.1 {
+                Ok(r) => {
+                    if let Some(histogram) =
+                        metrics::get_histogram(&metrics::DISCOVERY_QUERY_PEERS_FOUND, &[query_type])
+                    {
+                        histogram.observe(r.len() as f64);
+                    }
+                }
+                Err(_) => {
+                    metrics::inc_counter_vec(&metrics::DISCOVERY_QUERIES_FAILED, &[query_type])
+                }
+            }
             match query_future.0 {
                 GroupedQueryType::FindPeers => {
                     self.find_peer_active = false;
@@ -675,6 +749,15 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                 GroupedQueryType::Subnet(queries) => {
                     let subnets_searched_for: Vec<SubnetId> =
                         queries.iter().map(|query| query.subnet_id).collect();
+
+                    // A subnet query has completed. Add each subnet back to the queue,
+                    // incrementing its retries. Subnets which now have sufficient peers are
+                    // filtered out before the next query starts, so this only retries subnets
+                    // for which the query failed or found too few peers.
+                    for query in queries.iter() {
+                        self.add_subnet_query(query.subnet_id, query.min_ttl, query.retries + 1);
+                    }
+
                     match query_future.1 {
                         Ok(r) if r.is_empty() => {
                             debug!(self.log, "Grouped subnet discovery query yielded no results."; "subnets_searched_for" => format!("{:?}",subnets_searched_for));
@@ -692,13 +775,6 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
                             // Map each subnet query's min_ttl to the set of ENR's returned for that subnet.
                             queries.iter().for_each(|query| {
-                                // A subnet query has completed. Add back to the queue, incrementing retries.
-                                self.add_subnet_query(
-                                    query.subnet_id,
-                                    query.min_ttl,
-                                    query.retries + 1,
-                                );
-
                                 // Check the specific subnet against the enr
                                 let subnet_predicate =
                                     subnet_predicate::<TSpec>(vec![query.subnet_id], &self.log);
@@ -793,6 +869,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                         // We filter out unwanted discv5 events here and only propagate useful results to
                         // the peer manager.
                         Discv5Event::Discovered(_enr) => {
+                            metrics::inc_counter(&metrics::DISCOVERY_UNRETURNED_PEERS);
                             // Peers that get discovered during a query but are not contactable or
                             // don't match a predicate can end up here. For debugging purposes we
                             // log these to see if we are unnecessarily dropping discovered peers
@@ -824,3 +901,28 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Poll::Pending
     }
 }
+
+/// Count a failed discv5 request against the session or handshake failure metrics.
+///
+/// Discv5 doesn't report the session failures of its own queries, so only the requests made
+/// directly by lighthouse are counted.
+fn observe_request_failure(error: &discv5::RequestError) {
+    use discv5::RequestError;
+
+    match error {
+        RequestError::Timeout => {
+            metrics::inc_counter_vec(&metrics::DISCOVERY_SESSION_FAILURES, &["timeout"])
+        }
+        RequestError::EncryptionFailed(_) => {
+            metrics::inc_counter_vec(&metrics::DISCOVERY_SESSION_FAILURES, &["encryption"])
+        }
+        RequestError::InvalidRemotePacket => {
+            metrics::inc_counter_vec(&metrics::DISCOVERY_HANDSHAKE_FAILURES, &["invalid_packet"])
+        }
+        RequestError::InvalidRemoteEnr => {
+            metrics::inc_counter_vec(&metrics::DISCOVERY_HANDSHAKE_FAILURES, &["invalid_enr"])
+        }
+        // The remaining errors are local and never reach a session.
+        _ => {}
+    }
+}
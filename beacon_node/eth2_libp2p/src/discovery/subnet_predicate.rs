@@ -50,3 +50,55 @@ where
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::enr::EnrBuilder;
+    use crate::discovery::enr_ext::CombinedKeyExt;
+    use libp2p::core::identity::Keypair;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn build_log() -> slog::Logger {
+        slog::Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn enr_on_subnets(subnets: &[usize]) -> Enr {
+        let key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1())
+            .expect("secp256k1 keys are supported");
+        let mut bitfield = BitVector::<<E as EthSpec>::SubnetBitfieldLength>::new();
+        for subnet in subnets {
+            bitfield.set(*subnet, true).expect("subnet is in range");
+        }
+        let mut builder = EnrBuilder::new("v4");
+        builder.add_value(BITFIELD_ENR_KEY.into(), bitfield.as_ssz_bytes());
+        builder.build(&key).expect("valid enr")
+    }
+
+    #[test]
+    fn matches_any_of_the_requested_subnets() {
+        let enr = enr_on_subnets(&[1, 5]);
+        let log = build_log();
+
+        assert!(subnet_predicate::<E>(vec![SubnetId::new(5)], &log)(&enr));
+        assert!(subnet_predicate::<E>(
+            vec![SubnetId::new(2), SubnetId::new(1)],
+            &log
+        )(&enr));
+        assert!(!subnet_predicate::<E>(
+            vec![SubnetId::new(2), SubnetId::new(3)],
+            &log
+        )(&enr));
+    }
+
+    #[test]
+    fn rejects_enrs_without_a_bitfield() {
+        let key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1())
+            .expect("secp256k1 keys are supported");
+        let enr = EnrBuilder::new("v4").build(&key).expect("valid enr");
+
+        assert!(!subnet_predicate::<E>(vec![SubnetId::new(0)], &build_log())(&enr));
+    }
+}
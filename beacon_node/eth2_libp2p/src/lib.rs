@@ -7,6 +7,7 @@ extern crate lazy_static;
 
 pub mod behaviour;
 mod config;
+mod connection_limits;
 pub mod discovery;
 mod metrics;
 mod peer_manager;
@@ -20,10 +21,10 @@ pub use config::Config as NetworkConfig;
 pub use discovery::{CombinedKeyExt, EnrExt, Eth2Enr};
 pub use discv5;
 pub use libp2p::gossipsub::{Gossipsub, MessageAcceptance, MessageId, Topic, TopicHash};
-pub use libp2p::{core::ConnectedPoint, PeerId, Swarm};
+pub use libp2p::{core::connection::ConnectionId, core::ConnectedPoint, PeerId, Swarm};
 pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
-    client::Client, score::PeerAction, PeerDB, PeerInfo, PeerSyncStatus, SyncInfo,
+    client::Client, score::PeerAction, BanTarget, PeerDB, PeerInfo, PeerSyncStatus, SyncInfo,
 };
 pub use service::{load_private_key, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
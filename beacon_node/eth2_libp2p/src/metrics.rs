@@ -39,12 +39,96 @@ lazy_static! {
         "The connected peers via client implementation",
         &["Client"]
     );
+    pub static ref PEERS_PER_SUBNET: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "libp2p_peers_per_subnet",
+        "The connected peers advertising each attestation subnet",
+        &["subnet"]
+    );
     pub static ref FAILED_ATTESTATION_PUBLISHES_PER_SUBNET: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "gossipsub_failed_attestation_publishes_per_subnet",
             "Failed attestation publishes per subnet",
             &["subnet"]
         );
+    pub static ref RPC_RATE_LIMITED_REQUESTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_rpc_rate_limited_requests_total",
+        "Count of inbound RPC requests rejected by the rate limiter",
+        &["protocol"]
+    );
+    pub static ref DISCOVERY_QUERIES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "discovery_queries_total",
+        "Count of discovery queries started per query type",
+        &["type"]
+    );
+    pub static ref DISCOVERY_QUERIES_FAILED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "discovery_queries_failed_total",
+        "Count of discovery queries which failed per query type",
+        &["type"]
+    );
+    pub static ref DISCOVERY_QUERY_PEERS_FOUND: Result<HistogramVec> = try_create_histogram_vec(
+        "discovery_query_peers_found",
+        "The number of peers found by each successful discovery query per query type",
+        &["type"]
+    );
+    pub static ref DISCOVERY_QUERY_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "discovery_query_seconds",
+        "Time taken to complete a discovery query per query type",
+        &["type"]
+    );
+    pub static ref DISCOVERY_UNRETURNED_PEERS: Result<IntCounter> = try_create_int_counter(
+        "discovery_unreturned_peers_total",
+        "Count of peers found during discovery queries which were not contactable or did not match the query"
+    );
+    pub static ref DISCOVERY_SESSION_FAILURES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "discovery_session_failures_total",
+        "Count of discovery requests which failed on an established session per reason",
+        &["reason"]
+    );
+    pub static ref DISCOVERY_HANDSHAKE_FAILURES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "discovery_handshake_failures_total",
+        "Count of discovery sessions which failed to be established per reason",
+        &["reason"]
+    );
+    pub static ref INBOUND_BYTES_PER_PROTOCOL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_inbound_bytes_per_protocol_total",
+        "Count of bytes received per protocol (gossipsub or RPC method)",
+        &["protocol"]
+    );
+    pub static ref OUTBOUND_BYTES_PER_PROTOCOL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_outbound_bytes_per_protocol_total",
+        "Count of bytes sent per protocol (gossipsub or RPC method)",
+        &["protocol"]
+    );
+    pub static ref GOSSIP_MESSAGES_RX_PER_TOPIC: Result<IntCounterVec> = try_create_int_counter_vec(
+        "gossipsub_messages_rx_per_topic_total",
+        "Count of gossip messages received per topic kind",
+        &["topic"]
+    );
+    pub static ref GOSSIP_MESSAGES_TX_PER_TOPIC: Result<IntCounterVec> = try_create_int_counter_vec(
+        "gossipsub_messages_tx_per_topic_total",
+        "Count of gossip messages published per topic kind",
+        &["topic"]
+    );
+    pub static ref RPC_RESPONSE_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "libp2p_rpc_response_seconds",
+        "Time taken for a peer to complete its response to our RPC request per protocol",
+        &["protocol"]
+    );
+    pub static ref INBOUND_CONNECTIONS_REJECTED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_inbound_connections_rejected_total",
+        "Count of inbound connections refused by the per-IP or per-prefix connection limits",
+        &["limit"]
+    );
+    pub static ref GOODBYE_SENT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_goodbye_sent_total",
+        "Count of goodbye messages sent to peers per reason",
+        &["reason"]
+    );
+    pub static ref GOODBYE_RECEIVED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_goodbye_received_total",
+        "Count of goodbye messages received from peers per reason",
+        &["reason"]
+    );
     pub static ref FAILED_PUBLISHES_PER_MAIN_TOPIC: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "gossipsub_failed_publishes_per_main_topic",
         "Failed gossip publishes",
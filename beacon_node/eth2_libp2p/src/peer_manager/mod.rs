@@ -1,10 +1,24 @@
 //! Implementation of a Lighthouse's peer management system.
+//!
+//! Every peer has a decaying score (see `score`) which combines protocol violations, RPC failures
+//! and slow responses. Peers whose score falls below the disconnection threshold are disconnected
+//! and those below the ban threshold are banned until their score decays. Peers which are
+//! disconnected for a fault are not re-connected to until a backoff, which grows with each such
+//! disconnection, has elapsed.
+//!
+//! Trusted peers, specified by the user, are exempt from scoring, banning and pruning and are
+//! re-dialed whenever they are not connected.
+//!
+//! When more than the target number of peers are connected, excess peers are pruned beginning
+//! with the lowest scores. Amongst equally scored peers, those whose subnets are best covered by
+//! other peers are pruned first and we never prune the last peer of a subnet we are subscribed to.
 
 pub use self::peerdb::*;
 use crate::discovery::{Discovery, DiscoveryEvent};
-use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
+use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode, RequestId};
+use crate::types::GossipKind;
 use crate::{error, metrics};
-use crate::{EnrExt, NetworkConfig, NetworkGlobals, PeerId, SubnetDiscovery};
+use crate::{Enr, EnrExt, NetworkConfig, NetworkGlobals, PeerId, SubnetDiscovery};
 use futures::prelude::*;
 use futures::Stream;
 use hashset_delay::HashSetDelay;
@@ -13,13 +27,13 @@ use libp2p::identify::IdentifyInfo;
 use slog::{crit, debug, error};
 use smallvec::SmallVec;
 use std::{
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
     time::{Duration, Instant},
 };
-use types::EthSpec;
+use types::{EthSpec, SubnetId};
 
 pub use libp2p::core::{identity::Keypair, Multiaddr};
 
@@ -32,7 +46,8 @@ pub(crate) mod score;
 pub use peer_info::{PeerConnectionStatus::*, PeerInfo};
 pub use peer_sync_status::{PeerSyncStatus, SyncInfo};
 use score::{PeerAction, ScoreState};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 /// The time in seconds between re-status's peers.
 const STATUS_INTERVAL: u64 = 300;
 /// The time in seconds between PING events. We do not send a ping if the other peer as PING'd us within
@@ -48,6 +63,17 @@ const HEARTBEAT_INTERVAL: u64 = 30;
 /// PEER_EXCESS_FACTOR = 0.1 we allow 10% more nodes, i.e 55.
 const PEER_EXCESS_FACTOR: f32 = 0.1;
 
+/// The width of the score ranges within which peers are considered equally good when pruning, so
+/// that their subnet coverage decides which is pruned.
+const PRUNE_SCORE_BUCKET_SIZE: f64 = 5.0;
+
+/// The time in seconds after which a STATUS response is considered slow.
+const SLOW_STATUS_RESPONSE: u64 = 3;
+/// The time in seconds after which a BlocksByRoot response is considered slow.
+const SLOW_BLOCKS_BY_ROOT_RESPONSE: u64 = 5;
+/// The time in seconds after which a complete BlocksByRange response is considered slow.
+const SLOW_BLOCKS_BY_RANGE_RESPONSE: u64 = 10;
+
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<TSpec: EthSpec> {
     /// Storage of network globals to access the `PeerDB`.
@@ -66,10 +92,34 @@ pub struct PeerManager<TSpec: EthSpec> {
     discovery: Discovery<TSpec>,
     /// The heartbeat interval to perform routine maintenance.
     heartbeat: tokio::time::Interval,
+    /// The start times of requests we have sent to peers and are awaiting a complete response for.
+    ///
+    /// Request ids are not unique (e.g., all requests from the router share an id), so requests
+    /// with the same peer, id and protocol are matched to their responses in the order they were
+    /// sent.
+    outbound_requests: HashMap<(PeerId, RequestId, Protocol), VecDeque<Instant>>,
+    /// The user-specified trusted peers and the addresses they may be dialed on.
+    trusted_peers: HashMap<PeerId, Vec<Multiaddr>>,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
 
+/// A peer or an IP address which the user may ban.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BanTarget {
+    Peer(PeerId),
+    Ip(IpAddr),
+}
+
+impl std::fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BanTarget::Peer(peer_id) => write!(f, "{}", peer_id),
+            BanTarget::Ip(ip) => write!(f, "{}", ip),
+        }
+    }
+}
+
 /// The events that the `PeerManager` outputs (requests).
 pub enum PeerManagerEvent {
     /// Dial a PeerId.
@@ -111,6 +161,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             max_peers: (config.target_peers as f32 * (1.0 + PEER_EXCESS_FACTOR)).ceil() as usize,
             discovery,
             heartbeat,
+            outbound_requests: HashMap::new(),
+            trusted_peers: config.trusted_peer_addresses(),
             log: log.clone(),
         })
     }
@@ -127,15 +179,14 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
     /// The application layer wants to disconnect from a peer for a particular reason.
     ///
-    /// All instant disconnections are fatal and we ban the associated peer.
-    ///
-    /// This will send a goodbye and disconnect the peer if it is connected or dialing.
+    /// This will send a goodbye and disconnect the peer if it is connected or dialing. The peer's
+    /// score is not modified, misbehaving peers should be reported via `report_peer` instead. We
+    /// do not re-connect to the peer until its re-connection backoff has elapsed.
     pub fn goodbye_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason) {
         // get the peer info
         if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
             debug!(self.log, "Sending goodbye to peer"; "peer_id" => peer_id.to_string(), "reason" => reason.to_string(), "score" => info.score.to_string());
-            // Goodbye's are fatal
-            info.score.apply_peer_action(PeerAction::Fatal);
+            info.apply_reconnect_backoff();
             if info.connection_status.is_connected_or_dialing() {
                 self.events
                     .push(PeerManagerEvent::DisconnectPeer(peer_id.clone(), reason));
@@ -155,6 +206,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         let mut unban_peer = None;
 
         if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            if info.is_trusted {
+                debug!(self.log, "Ignoring report of trusted peer"; "peer_id" => peer_id.to_string(), "action" => action.to_string());
+                return;
+            }
             let previous_state = info.score.state();
             info.score.apply_peer_action(action);
             if previous_state != info.score.state() {
@@ -162,6 +217,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     ScoreState::Banned => {
                         debug!(self.log, "Peer has been banned"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string());
                         ban_peer = Some(peer_id.clone());
+                        info.apply_reconnect_backoff();
                         if info.connection_status.is_connected_or_dialing() {
                             self.events.push(PeerManagerEvent::DisconnectPeer(
                                 peer_id.clone(),
@@ -173,6 +229,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                         debug!(self.log, "Peer transitioned to disconnect state"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string(), "past_state" => previous_state.to_string());
                         // disconnect the peer if it's currently connected or dialing
                         unban_peer = Some(peer_id.clone());
+                        info.apply_reconnect_backoff();
                         if info.connection_status.is_connected_or_dialing() {
                             self.events.push(PeerManagerEvent::DisconnectPeer(
                                 peer_id.clone(),
@@ -200,6 +257,83 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
+    /// Returns the ENR, the time since it was last seen and the score of each healthy peer we
+    /// have connected to or discovered, so that they may be persisted across restarts.
+    pub fn known_good_peers(&mut self) -> Vec<(Enr, Duration, f64)> {
+        let good_peers = self
+            .network_globals
+            .peers
+            .read()
+            .peers()
+            .filter(|(_, info)| info.score.state() == ScoreState::Healthy)
+            .filter_map(|(peer_id, info)| {
+                info.connection_status
+                    .last_seen()
+                    .map(|last_seen| (peer_id.clone(), last_seen, info.score.score()))
+            })
+            .collect::<Vec<_>>();
+
+        good_peers
+            .into_iter()
+            .filter_map(|(peer_id, last_seen, score)| {
+                self.discovery
+                    .enr_of_peer(&peer_id)
+                    .map(|enr| (enr, last_seen, score))
+            })
+            .collect()
+    }
+
+    /// Adds a peer or IP address to the user's ban list, disconnecting any affected peers.
+    ///
+    /// These bans do not expire and are not affected by a peer's score.
+    pub fn ban(&mut self, target: BanTarget) {
+        let to_disconnect = {
+            let mut pdb = self.network_globals.peers.write();
+            match &target {
+                BanTarget::Peer(peer_id) => {
+                    pdb.ban_manually(peer_id.clone());
+                }
+                BanTarget::Ip(ip) => {
+                    pdb.ban_ip(*ip);
+                }
+            }
+            pdb.peers()
+                .filter(|(peer_id, info)| {
+                    info.connection_status.is_connected_or_dialing() && pdb.is_banned(peer_id)
+                })
+                .map(|(peer_id, _)| peer_id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        debug!(self.log, "Banned by the user"; "target" => target.to_string(), "disconnecting" => to_disconnect.len());
+        for peer_id in to_disconnect {
+            self.events.push(PeerManagerEvent::DisconnectPeer(
+                peer_id,
+                GoodbyeReason::Banned,
+            ));
+        }
+    }
+
+    /// Removes a peer or IP address from the user's ban list.
+    ///
+    /// Returns false if the target was not on the ban list.
+    pub fn unban(&mut self, target: &BanTarget) -> bool {
+        let mut pdb = self.network_globals.peers.write();
+        let removed = match target {
+            BanTarget::Peer(peer_id) => pdb.unban_manually(peer_id),
+            BanTarget::Ip(ip) => pdb.unban_ip(ip),
+        };
+        debug!(self.log, "Unbanned by the user"; "target" => target.to_string(), "was_banned" => removed);
+        removed
+    }
+
+    /// Reports if the IP address is banned.
+    ///
+    /// This is used to determine if we should accept incoming connections.
+    pub fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.network_globals.peers.read().is_ip_banned(ip)
+    }
+
     /* Discovery Requests */
 
     /// Provides a reference to the underlying discovery service.
@@ -265,6 +399,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // remove the ping and status timer for the peer
         self.ping_peers.remove(peer_id);
         self.status_peers.remove(peer_id);
+        // requests to the peer can no longer complete
+        self.outbound_requests
+            .retain(|(request_peer, _, _), _| request_peer != peer_id);
         metrics::inc_counter(&metrics::PEER_DISCONNECT_EVENT_COUNT);
         metrics::set_gauge(
             &metrics::PEERS_CONNECTED,
@@ -295,6 +432,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.connect_peer(peer_id, ConnectingType::OutgoingConnected)
     }
 
+    /// A peer has sent us a goodbye.
+    ///
+    /// We refrain from dialing the peer for a period depending on the given reason. The peer is not
+    /// penalized, as the disconnection is not necessarily its fault.
+    pub fn goodbye_received(&mut self, peer_id: &PeerId, reason: &GoodbyeReason) {
+        metrics::inc_counter_vec(&metrics::GOODBYE_RECEIVED, &[reason.metrics_label()]);
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.apply_goodbye_backoff(reason);
+        }
+    }
+
     /// Updates the database informing that a peer is being disconnected.
     pub fn _disconnecting_peer(&mut self, _peer_id: &PeerId) -> bool {
         // TODO: implement
@@ -308,6 +456,16 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.network_globals.peers.read().is_banned(peer_id)
     }
 
+    /// Reports if we are waiting for the re-connection backoff of a peer to elapse.
+    ///
+    /// This is used to determine if we should accept incoming connections.
+    pub fn is_in_reconnect_backoff(&self, peer_id: &PeerId) -> bool {
+        self.network_globals
+            .peers
+            .read()
+            .is_in_reconnect_backoff(peer_id)
+    }
+
     /// Reports whether the peer limit is reached in which case we stop allowing new incoming
     /// connections.
     pub fn peer_limit_reached(&self) -> bool {
@@ -413,6 +571,61 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.report_peer(peer_id, peer_action);
     }
 
+    /// A request has been sent to a peer. The time taken for the peer to respond is tracked so that
+    /// slow peers can be penalised.
+    pub fn request_sent(&mut self, peer_id: &PeerId, id: RequestId, protocol: Protocol) {
+        self.outbound_requests
+            .entry((peer_id.clone(), id, protocol))
+            .or_default()
+            .push_back(Instant::now());
+    }
+
+    /// A peer has completed its response to a request.
+    ///
+    /// Peers which take longer than the threshold for the protocol are penalised.
+    pub fn request_completed(&mut self, peer_id: &PeerId, id: RequestId, protocol: Protocol) {
+        if let Some(sent) = self.take_outbound_request(peer_id, id, protocol) {
+            if let Some(histogram) =
+                metrics::get_histogram(&metrics::RPC_RESPONSE_TIMES, &[&protocol.to_string()])
+            {
+                histogram.observe(sent.elapsed().as_secs_f64());
+            }
+            let threshold = match protocol {
+                Protocol::Status => SLOW_STATUS_RESPONSE,
+                Protocol::BlocksByRoot => SLOW_BLOCKS_BY_ROOT_RESPONSE,
+                Protocol::BlocksByRange => SLOW_BLOCKS_BY_RANGE_RESPONSE,
+                Protocol::Goodbye | Protocol::Ping | Protocol::MetaData => return,
+            };
+            let elapsed = sent.elapsed();
+            if elapsed > Duration::from_secs(threshold) {
+                debug!(self.log, "Slow RPC response"; "protocol" => protocol.to_string(), "elapsed_ms" => elapsed.as_millis() as u64, "peer_id" => peer_id.to_string());
+                self.report_peer(peer_id, PeerAction::HighToleranceError);
+            }
+        }
+    }
+
+    /// A request sent to a peer has failed. The failure itself is scored by `handle_rpc_error`.
+    pub fn request_failed(&mut self, peer_id: &PeerId, id: RequestId, protocol: Protocol) {
+        self.take_outbound_request(peer_id, id, protocol);
+    }
+
+    /// Stop tracking the oldest outstanding request with the given peer, id and protocol,
+    /// returning the time it was sent.
+    fn take_outbound_request(
+        &mut self,
+        peer_id: &PeerId,
+        id: RequestId,
+        protocol: Protocol,
+    ) -> Option<Instant> {
+        let key = (peer_id.clone(), id, protocol);
+        let requests = self.outbound_requests.get_mut(&key)?;
+        let sent = requests.pop_front();
+        if requests.is_empty() {
+            self.outbound_requests.remove(&key);
+        }
+        sent
+    }
+
     /// A ping request has been received.
     // NOTE: The behaviour responds with a PONG automatically
     // TODO: Update last seen
@@ -497,12 +710,14 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
     // Handles the libp2p request to obtain multiaddrs for peer_id's in order to dial them.
     pub fn addresses_of_peer(&mut self, peer_id: &PeerId) -> Vec<Multiaddr> {
+        let mut out_list = self.trusted_peers.get(peer_id).cloned().unwrap_or_default();
+
         if let Some(enr) = self.discovery.enr_of_peer(peer_id) {
             // ENR's may have multiple Multiaddrs. The multi-addr associated with the UDP
             // port is removed, which is assumed to be associated with the discv5 protocol (and
             // therefore irrelevant for other libp2p components).
-            let mut out_list = enr.multiaddr();
-            out_list.retain(|addr| {
+            let mut enr_list = enr.multiaddr();
+            enr_list.retain(|addr| {
                 addr.iter()
                     .find(|v| match v {
                         MProtocol::Udp(_) => true,
@@ -511,11 +726,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     .is_none()
             });
 
-            out_list
-        } else {
-            // PeerId is not known
-            Vec::new()
+            out_list.extend(enr_list);
         }
+        // If the PeerId is not known, this list is empty
+        out_list
     }
 
     /* Internal functions */
@@ -555,6 +769,11 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     .peers
                     .read()
                     .is_banned_or_disconnected(&peer_id)
+                && !self
+                    .network_globals
+                    .peers
+                    .read()
+                    .is_in_dial_backoff(&peer_id)
             {
                 // TODO: Update output
                 // This should be updated with the peer dialing. In fact created once the peer is
@@ -627,6 +846,27 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         true
     }
 
+    /// Dials all trusted peers which are neither connected nor being dialed.
+    fn dial_trusted_peers(&mut self) {
+        let to_dial_peers = self
+            .trusted_peers
+            .keys()
+            .filter(|peer_id| {
+                !self
+                    .network_globals
+                    .peers
+                    .read()
+                    .is_connected_or_dialing(peer_id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for peer_id in to_dial_peers {
+            debug!(self.log, "Dialing trusted peer"; "peer_id" => peer_id.to_string());
+            self.dial_peer(&peer_id);
+        }
+    }
+
     /// Updates the scores of known peers according to their connection
     /// status and the time that has passed.
     /// NOTE: This is experimental and will likely be adjusted
@@ -638,6 +878,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         let mut to_unban_peers = Vec::new();
 
         for (peer_id, info) in pdb.peers_mut() {
+            // trusted peers keep their maximum score
+            if info.is_trusted {
+                continue;
+            }
             let previous_state = info.score.state();
             // Update scores
             info.score.update();
@@ -700,6 +944,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     ScoreState::Banned => {
                         debug!(self.log, "Peer has been banned"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string());
                         to_ban_peers.push(peer_id.clone());
+                        info.apply_reconnect_backoff();
                         if info.connection_status.is_connected_or_dialing() {
                             self.events.push(PeerManagerEvent::DisconnectPeer(
                                 peer_id.clone(),
@@ -711,6 +956,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                         debug!(self.log, "Peer transitioned to disconnect state"; "peer_id" => peer_id.to_string(), "score" => info.score.to_string(), "past_state" => previous_state.to_string());
                         // disconnect the peer if it's currently connected or dialing
                         to_unban_peers.push(peer_id.clone());
+                        info.apply_reconnect_backoff();
                         if info.connection_status.is_connected_or_dialing() {
                             self.events.push(PeerManagerEvent::DisconnectPeer(
                                 peer_id.clone(),
@@ -754,33 +1000,161 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             self.discovery.discover_peers();
         }
 
+        // Re-dial any trusted peers that are not connected.
+        self.dial_trusted_peers();
+
         // Updates peer's scores.
         self.update_peer_scores();
 
-        let connected_peer_count = self.network_globals.connected_peers();
-        if connected_peer_count > self.target_peers {
-            //remove excess peers with the worst scores, but keep subnet peers
-            for (peer_id, _) in self
-                .network_globals
-                .peers
-                .read()
-                .worst_connected_peers()
-                .iter()
-                .filter(|(_, info)| !info.has_future_duty())
-                .take(connected_peer_count - self.target_peers)
-                //we only need to disconnect peers with healthy scores, since the others got already
-                //disconnected in update_peer_scores
-                .filter(|(_, info)| info.score.state() == ScoreState::Healthy)
-            {
-                self.events.push(PeerManagerEvent::DisconnectPeer(
-                    (*peer_id).clone(),
-                    GoodbyeReason::TooManyPeers,
-                ));
+        // Remove any excess peers.
+        self.prune_excess_peers();
+    }
+
+    /// Disconnects connected peers in excess of `target_peers`, as chosen by
+    /// `select_peers_to_prune`.
+    fn prune_excess_peers(&mut self) {
+        // The attestation subnets we currently require peers for.
+        let required_subnets = self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .filter_map(|topic| match topic.kind() {
+                GossipKind::Attestation(subnet_id) => Some(*subnet_id),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let peers_to_prune = {
+            let peer_db = self.network_globals.peers.read();
+
+            if let Ok(gauge_vec) = &metrics::PEERS_PER_SUBNET {
+                gauge_vec.reset();
+                for (subnet_id, count) in peers_per_subnet(&peer_db).iter() {
+                    metrics::set_int_gauge(
+                        &metrics::PEERS_PER_SUBNET,
+                        &[&(**subnet_id).to_string()],
+                        *count as i64,
+                    );
+                }
             }
+
+            select_peers_to_prune(&peer_db, &required_subnets, self.target_peers)
+        };
+
+        for peer_id in peers_to_prune {
+            debug!(self.log, "Pruning excess peer"; "peer_id" => peer_id.to_string());
+            self.events.push(PeerManagerEvent::DisconnectPeer(
+                peer_id,
+                GoodbyeReason::TooManyPeers,
+            ));
         }
     }
 }
 
+/// Returns the connected peers to disconnect to bring the number of connected peers down to
+/// `target_peers`.
+///
+/// Peers are pruned beginning with the worst scores, compared in buckets of
+/// `PRUNE_SCORE_BUCKET_SIZE` so that similarly scored peers are ordered by their subnet coverage:
+/// peers whose best-covered required subnet has the most other peers are pruned first. Trusted
+/// peers, peers with future validator duties and the last remaining peer of any of the
+/// `required_subnets` are never pruned.
+///
+/// Peers with unhealthy scores count towards the excess but are not returned, since they are
+/// already being disconnected.
+fn select_peers_to_prune<TSpec: EthSpec>(
+    peer_db: &PeerDB<TSpec>,
+    required_subnets: &HashSet<SubnetId>,
+    target_peers: usize,
+) -> Vec<PeerId> {
+    let connected_peer_count = peer_db.connected_peer_ids().count();
+    if connected_peer_count <= target_peers {
+        return vec![];
+    }
+    let mut excess_peer_count = connected_peer_count - target_peers;
+
+    let mut peers_per_subnet = peers_per_subnet(peer_db);
+
+    // The fewest peers on any required subnet this peer is on. Peers on no required subnets are
+    // the most redundant.
+    let min_subnet_coverage = |info: &PeerInfo<TSpec>| {
+        subnets_of_peer(info)
+            .filter(|subnet_id| required_subnets.contains(subnet_id))
+            .map(|subnet_id| peers_per_subnet.get(&subnet_id).copied().unwrap_or(0))
+            .min()
+            .unwrap_or(usize::max_value())
+    };
+    let score_bucket =
+        |info: &PeerInfo<TSpec>| (info.score.score() / PRUNE_SCORE_BUCKET_SIZE).floor() as i64;
+
+    let mut candidates = peer_db
+        .worst_connected_peers()
+        .into_iter()
+        .filter(|(_, info)| !info.has_future_duty() && !info.is_trusted)
+        .map(|(peer_id, info)| {
+            let key = (
+                score_bucket(info),
+                Reverse(min_subnet_coverage(info)),
+                info.score,
+            );
+            (peer_id, info, key)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(_, _, key)| *key);
+
+    let mut peers_to_prune = vec![];
+    for (peer_id, info, _) in candidates {
+        if excess_peer_count == 0 {
+            break;
+        }
+
+        // Never remove the last peer of a subnet we require.
+        let peer_subnets = subnets_of_peer(info)
+            .filter(|subnet_id| required_subnets.contains(subnet_id))
+            .collect::<Vec<_>>();
+        if peer_subnets
+            .iter()
+            .any(|subnet_id| peers_per_subnet.get(subnet_id).copied().unwrap_or(0) <= 1)
+        {
+            continue;
+        }
+        for subnet_id in peer_subnets {
+            if let Some(count) = peers_per_subnet.get_mut(&subnet_id) {
+                *count -= 1;
+            }
+        }
+        excess_peer_count -= 1;
+
+        // We only need to disconnect peers with healthy scores, since the others got already
+        // disconnected in update_peer_scores
+        if info.score.state() == ScoreState::Healthy {
+            peers_to_prune.push(peer_id.clone());
+        }
+    }
+    peers_to_prune
+}
+
+/// Counts the connected peers on each attestation subnet.
+fn peers_per_subnet<TSpec: EthSpec>(peer_db: &PeerDB<TSpec>) -> HashMap<SubnetId, usize> {
+    let mut peers_per_subnet: HashMap<SubnetId, usize> = HashMap::new();
+    for (_, info) in peer_db.connected_peers() {
+        for subnet_id in subnets_of_peer(info) {
+            *peers_per_subnet.entry(subnet_id).or_default() += 1;
+        }
+    }
+    peers_per_subnet
+}
+
+/// Returns the attestation subnets a peer has advertised in its metadata.
+fn subnets_of_peer<TSpec: EthSpec>(info: &PeerInfo<TSpec>) -> impl Iterator<Item = SubnetId> + '_ {
+    info.meta_data
+        .iter()
+        .flat_map(|meta_data| meta_data.attnets.iter().enumerate())
+        .filter(|(_, is_set)| *is_set)
+        .map(|(index, _)| SubnetId::new(index as u64))
+}
+
 impl<TSpec: EthSpec> Stream for PeerManager<TSpec> {
     type Item = PeerManagerEvent;
 
@@ -847,3 +1221,107 @@ enum ConnectingType {
     /// We have successfully dialed a peer.
     OutgoingConnected,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EnrBitfield, EnrSyncCommitteeBitfield};
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn get_db() -> PeerDB<E> {
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        PeerDB::new(vec![], &log)
+    }
+
+    /// Connects a new peer with `score` which has advertised the given attestation `subnets`.
+    fn add_peer(db: &mut PeerDB<E>, score: f64, subnets: &[u64]) -> PeerId {
+        let peer_id = PeerId::random();
+        db.connect_ingoing(&peer_id);
+
+        let mut attnets = EnrBitfield::<E>::new();
+        for subnet in subnets {
+            attnets.set(*subnet as usize, true).unwrap();
+        }
+        let info = db.peer_info_mut(&peer_id).unwrap();
+        info.score.add(score);
+        info.meta_data = Some(MetaData {
+            seq_number: 0,
+            attnets,
+            syncnets: EnrSyncCommitteeBitfield::<E>::new(),
+        });
+        peer_id
+    }
+
+    fn required(subnets: &[u64]) -> HashSet<SubnetId> {
+        subnets.iter().copied().map(SubnetId::new).collect()
+    }
+
+    #[test]
+    fn no_peers_pruned_within_target() {
+        let mut db = get_db();
+        for _ in 0..3 {
+            add_peer(&mut db, 0.0, &[]);
+        }
+
+        assert!(select_peers_to_prune(&db, &required(&[]), 3).is_empty());
+    }
+
+    #[test]
+    fn prunes_lowest_score_first() {
+        let mut db = get_db();
+        let worst = add_peer(&mut db, -15.0, &[]);
+        let bad = add_peer(&mut db, -8.0, &[]);
+        let good = add_peer(&mut db, 10.0, &[]);
+        let best = add_peer(&mut db, 20.0, &[]);
+
+        let pruned = select_peers_to_prune(&db, &required(&[]), 2);
+        assert_eq!(pruned, vec![worst, bad]);
+        assert!(!pruned.contains(&good) && !pruned.contains(&best));
+    }
+
+    #[test]
+    fn similar_scores_prune_redundant_subnet_coverage_first() {
+        let mut db = get_db();
+        // the lowest scoring peer shares a subnet with only one other peer
+        let scarce = add_peer(&mut db, 1.0, &[1]);
+        add_peer(&mut db, 2.0, &[1]);
+        let redundant = (0..4)
+            .map(|_| add_peer(&mut db, 2.0, &[2]))
+            .collect::<Vec<_>>();
+
+        let pruned = select_peers_to_prune(&db, &required(&[1, 2]), 5);
+        assert_eq!(pruned.len(), 1);
+        assert!(!pruned.contains(&scarce));
+        assert!(redundant.contains(&pruned[0]));
+    }
+
+    #[test]
+    fn never_prunes_last_peer_on_required_subnet() {
+        let mut db = get_db();
+        let last_on_subnet = add_peer(&mut db, -15.0, &[1]);
+        let other_a = add_peer(&mut db, 0.0, &[]);
+        let other_b = add_peer(&mut db, 0.0, &[]);
+
+        let pruned = select_peers_to_prune(&db, &required(&[1]), 1);
+        assert_eq!(
+            pruned.into_iter().collect::<HashSet<_>>(),
+            vec![other_a, other_b].into_iter().collect::<HashSet<_>>()
+        );
+
+        // once the subnet is no longer required, the peer is pruned for its score
+        let pruned = select_peers_to_prune(&db, &required(&[]), 2);
+        assert_eq!(pruned, vec![last_on_subnet]);
+    }
+
+    #[test]
+    fn never_prunes_trusted_peers() {
+        let mut db = get_db();
+        let trusted = add_peer(&mut db, -15.0, &[]);
+        db.peer_info_mut(&trusted).unwrap().is_trusted = true;
+        let other = add_peer(&mut db, 0.0, &[]);
+
+        assert_eq!(select_peers_to_prune(&db, &required(&[]), 1), vec![other]);
+    }
+}
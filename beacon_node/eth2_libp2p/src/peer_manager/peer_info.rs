@@ -1,17 +1,31 @@
 use super::client::Client;
 use super::score::Score;
 use super::PeerSyncStatus;
-use crate::rpc::MetaData;
+use crate::rpc::{GoodbyeReason, MetaData};
 use crate::Multiaddr;
 use serde::{
     ser::{SerializeStruct, Serializer},
     Serialize,
 };
 use std::net::IpAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use types::{EthSpec, SubnetId};
 use PeerConnectionStatus::*;
 
+/// The time in seconds we wait before re-connecting to a peer after its first disconnection for a
+/// fault. Each subsequent disconnection doubles this time.
+const RECONNECT_BACKOFF_BASE: u64 = 30;
+/// The maximum time in seconds we wait before re-connecting to a peer.
+const RECONNECT_BACKOFF_MAX: u64 = 3600;
+/// The time in seconds we wait before dialing a peer which has told us it is shutting down.
+const GOODBYE_SHUTDOWN_BACKOFF: u64 = 60;
+/// The time in seconds we wait before dialing a peer which disconnected us because it has too
+/// many peers.
+const GOODBYE_TOO_MANY_PEERS_BACKOFF: u64 = 300;
+/// The time in seconds we wait before dialing a peer which has banned us or considers us to be on
+/// a different network.
+const GOODBYE_BANNED_BACKOFF: u64 = 3600;
+
 /// Information about a given connected peer.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -36,6 +50,21 @@ pub struct PeerInfo<T: EthSpec> {
     /// necessary.
     #[serde(skip)]
     pub min_ttl: Option<Instant>,
+    /// The time before which we refuse to re-connect to this peer after disconnecting it for a
+    /// fault.
+    #[serde(skip)]
+    pub reconnect_after: Option<Instant>,
+    /// The number of times this peer has been disconnected for a fault.
+    #[serde(skip)]
+    fault_disconnections: u32,
+    /// The time before which we do not dial this peer, as indicated by the reason of the last
+    /// goodbye it sent us. Unlike `reconnect_after`, this does not prevent the peer from
+    /// connecting to us.
+    #[serde(skip)]
+    pub dial_after: Option<Instant>,
+    /// Whether this peer was specified as trusted by the user. Trusted peers are never scored
+    /// down, banned or pruned.
+    pub is_trusted: bool,
 }
 
 impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
@@ -49,11 +78,24 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             sync_status: PeerSyncStatus::Unknown,
             meta_data: None,
             min_ttl: None,
+            reconnect_after: None,
+            fault_disconnections: 0,
+            dial_after: None,
+            is_trusted: false,
         }
     }
 }
 
 impl<T: EthSpec> PeerInfo<T> {
+    /// Creates a `PeerInfo` for a peer that the user has specified as trusted.
+    pub fn trusted() -> Self {
+        PeerInfo {
+            score: Score::max_score(),
+            is_trusted: true,
+            ..Default::default()
+        }
+    }
+
     /// Returns if the peer is subscribed to a given `SubnetId`
     pub fn on_subnet(&self, subnet_id: SubnetId) -> bool {
         if let Some(meta_data) = &self.meta_data {
@@ -69,6 +111,54 @@ impl<T: EthSpec> PeerInfo<T> {
     pub fn has_future_duty(&self) -> bool {
         self.min_ttl.map_or(false, |i| i >= Instant::now())
     }
+
+    /// Prevents re-connections to this peer for a period which grows exponentially with the
+    /// number of times it has been disconnected for a fault. Trusted peers have no backoff.
+    pub fn apply_reconnect_backoff(&mut self) {
+        if self.is_trusted {
+            return;
+        }
+        // The exponent is bounded, the backoff reaches its maximum long before it could overflow.
+        let backoff = (RECONNECT_BACKOFF_BASE << self.fault_disconnections.min(16))
+            .min(RECONNECT_BACKOFF_MAX);
+        self.fault_disconnections = self.fault_disconnections.saturating_add(1);
+        self.reconnect_after = Some(Instant::now() + Duration::from_secs(backoff));
+    }
+
+    /// Reports if we are still waiting for the re-connection backoff of this peer to elapse.
+    pub fn in_reconnect_backoff(&self) -> bool {
+        self.reconnect_after.map_or(false, |i| i > Instant::now())
+    }
+
+    /// Prevents dialing this peer for a period depending on the reason of a goodbye it has sent
+    /// us. Peers which have banned us are not dialed for a long time, whereas peers which are
+    /// full or restarting are retried sooner. Trusted peers have no backoff.
+    pub fn apply_goodbye_backoff(&mut self, reason: &GoodbyeReason) {
+        if self.is_trusted {
+            return;
+        }
+        let backoff = match reason {
+            GoodbyeReason::ClientShutdown => GOODBYE_SHUTDOWN_BACKOFF,
+            GoodbyeReason::TooManyPeers => GOODBYE_TOO_MANY_PEERS_BACKOFF,
+            GoodbyeReason::IrrelevantNetwork | GoodbyeReason::BadScore | GoodbyeReason::Banned => {
+                GOODBYE_BANNED_BACKOFF
+            }
+            GoodbyeReason::Fault
+            | GoodbyeReason::UnableToVerifyNetwork
+            | GoodbyeReason::Unknown => RECONNECT_BACKOFF_BASE,
+        };
+        let dial_after = Instant::now() + Duration::from_secs(backoff);
+        // never shorten an existing backoff
+        if self.dial_after.map_or(true, |i| i < dial_after) {
+            self.dial_after = Some(dial_after);
+        }
+    }
+
+    /// Reports if we should refrain from dialing this peer, either because of its re-connection
+    /// backoff or because of the reason of a goodbye it has sent us.
+    pub fn in_dial_backoff(&self) -> bool {
+        self.in_reconnect_backoff() || self.dial_after.map_or(false, |i| i > Instant::now())
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -263,4 +353,15 @@ impl PeerConnectionStatus {
             _ => (0, 0),
         }
     }
+
+    /// Returns the time elapsed since the peer was last connected or discovered, if it has been.
+    ///
+    /// A connected peer is considered to have been seen now.
+    pub fn last_seen(&self) -> Option<Duration> {
+        match self {
+            Connected { .. } => Some(Duration::from_secs(0)),
+            Disconnected { since } => Some(since.elapsed()),
+            Banned { .. } | Dialing { .. } | Unknown => None,
+        }
+    }
 }
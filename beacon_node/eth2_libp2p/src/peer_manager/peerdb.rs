@@ -6,7 +6,7 @@ use crate::rpc::methods::MetaData;
 use crate::PeerId;
 use rand::seq::SliceRandom;
 use slog::{crit, debug, trace, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::time::Instant;
 use types::{EthSpec, SubnetId};
@@ -27,6 +27,10 @@ pub struct PeerDB<TSpec: EthSpec> {
     disconnected_peers: usize,
     /// Counts banned peers in total and per ip
     banned_peers_count: BannedPeersCount,
+    /// Peers which have been banned by the user. These bans never expire.
+    manually_banned_peers: HashSet<PeerId>,
+    /// IP addresses which have been banned by the user. These bans never expire.
+    manually_banned_ips: HashSet<IpAddr>,
     /// PeerDB's logger
     log: slog::Logger,
 }
@@ -86,12 +90,19 @@ impl BannedPeersCount {
 }
 
 impl<TSpec: EthSpec> PeerDB<TSpec> {
-    pub fn new(log: &slog::Logger) -> Self {
+    pub fn new(trusted_peers: Vec<PeerId>, log: &slog::Logger) -> Self {
+        // Initialize the peers hashmap with trusted peers
+        let peers = trusted_peers
+            .into_iter()
+            .map(|peer_id| (peer_id, PeerInfo::trusted()))
+            .collect();
         Self {
             log: log.clone(),
             disconnected_peers: 0,
             banned_peers_count: BannedPeersCount::new(),
-            peers: HashMap::new(),
+            manually_banned_peers: HashSet::new(),
+            manually_banned_ips: HashSet::new(),
+            peers,
         }
     }
 
@@ -130,6 +141,13 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         self.peers.get_mut(peer_id)
     }
 
+    /// Returns true if the peer was specified as trusted by the user.
+    pub fn is_trusted(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(false, |info| info.is_trusted)
+    }
+
     /// Returns if the peer is already connected.
     pub fn is_connected(&self, peer_id: &PeerId) -> bool {
         if let Some(PeerConnectionStatus::Connected { .. }) = self.connection_status(peer_id) {
@@ -158,7 +176,13 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
 
     /// Returns true if the Peer is banned.
     pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        if self.manually_banned_peers.contains(peer_id) {
+            return true;
+        }
         if let Some(peer) = self.peers.get(peer_id) {
+            if peer.is_trusted {
+                return false;
+            }
             match peer.score.state() {
                 ScoreState::Banned => true,
                 _ => self.ip_is_banned(peer),
@@ -171,16 +195,38 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
     fn ip_is_banned(&self, peer: &PeerInfo<TSpec>) -> bool {
         peer.listening_addresses.iter().any(|addr| {
             addr.iter().any(|p| match p {
-                Protocol::Ip4(ip) => self.banned_peers_count.ip_is_banned(&ip.into()),
-                Protocol::Ip6(ip) => self.banned_peers_count.ip_is_banned(&ip.into()),
+                Protocol::Ip4(ip) => self.is_ip_banned(&ip.into()),
+                Protocol::Ip6(ip) => self.is_ip_banned(&ip.into()),
                 _ => false,
             })
         })
     }
 
+    /// Returns true if the IP address has been banned by the user or is shared by too many banned
+    /// peers.
+    pub fn is_ip_banned(&self, ip: &IpAddr) -> bool {
+        self.manually_banned_ips.contains(ip) || self.banned_peers_count.ip_is_banned(ip)
+    }
+
+    /// Returns the peers which have been banned by the user.
+    pub fn manually_banned_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.manually_banned_peers.iter()
+    }
+
+    /// Returns the IP addresses which have been banned by the user.
+    pub fn manually_banned_ips(&self) -> impl Iterator<Item = &IpAddr> {
+        self.manually_banned_ips.iter()
+    }
+
     /// Returns true if the Peer is either banned or in the disconnected state.
     pub fn is_banned_or_disconnected(&self, peer_id: &PeerId) -> bool {
+        if self.manually_banned_peers.contains(peer_id) {
+            return true;
+        }
         if let Some(peer) = self.peers.get(peer_id) {
+            if peer.is_trusted {
+                return false;
+            }
             match peer.score.state() {
                 ScoreState::Banned | ScoreState::Disconnected => true,
                 _ => self.ip_is_banned(peer),
@@ -190,6 +236,21 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
+    /// Returns true if we are waiting for the peer's re-connection backoff to elapse.
+    pub fn is_in_reconnect_backoff(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(false, |peer| peer.in_reconnect_backoff())
+    }
+
+    /// Returns true if we should not dial the peer, either because of its re-connection backoff or
+    /// because of the reason of a goodbye it has sent us.
+    pub fn is_in_dial_backoff(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(false, |peer| peer.in_dial_backoff())
+    }
+
     /// Gives the ids of all known connected peers.
     pub fn connected_peers(&self) -> impl Iterator<Item = (&PeerId, &PeerInfo<TSpec>)> {
         self.peers
@@ -401,6 +462,11 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             PeerInfo::default()
         });
 
+        if info.is_trusted {
+            debug!(log_ref, "Not banning trusted peer"; "peer_id" => peer_id.to_string());
+            return;
+        }
+
         if info.connection_status.is_disconnected() {
             self.disconnected_peers = self.disconnected_peers.saturating_sub(1);
         }
@@ -426,6 +492,32 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         self.shrink_to_fit();
     }
 
+    /// Adds a peer to the user's ban list. Returns false if the peer was already on the list.
+    pub fn ban_manually(&mut self, peer_id: PeerId) -> bool {
+        self.manually_banned_peers.insert(peer_id)
+    }
+
+    /// Removes a peer from the user's ban list. Returns false if the peer was not on the list.
+    ///
+    /// Any goodbye backoff of the peer is cleared, as the peer likely sent us a goodbye in
+    /// response to being banned and the user now wishes to re-connect to it.
+    pub fn unban_manually(&mut self, peer_id: &PeerId) -> bool {
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            info.dial_after = None;
+        }
+        self.manually_banned_peers.remove(peer_id)
+    }
+
+    /// Adds an IP address to the user's ban list. Returns false if it was already on the list.
+    pub fn ban_ip(&mut self, ip: IpAddr) -> bool {
+        self.manually_banned_ips.insert(ip)
+    }
+
+    /// Removes an IP address from the user's ban list. Returns false if it was not on the list.
+    pub fn unban_ip(&mut self, ip: &IpAddr) -> bool {
+        self.manually_banned_ips.remove(ip)
+    }
+
     /// Unbans a peer.
     pub fn unban(&mut self, peer_id: &PeerId) {
         let log_ref = &self.log;
@@ -482,7 +574,7 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             if let Some(to_drop) = self
                 .peers
                 .iter()
-                .filter(|(_, info)| info.connection_status.is_disconnected())
+                .filter(|(_, info)| info.connection_status.is_disconnected() && !info.is_trusted)
                 .min_by(|(_, info_a), (_, info_b)| {
                     info_a
                         .score
@@ -522,6 +614,7 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rpc::GoodbyeReason;
     use libp2p::core::Multiaddr;
     use slog::{o, Drain};
     use std::net::{Ipv4Addr, Ipv6Addr};
@@ -549,7 +642,7 @@ mod tests {
 
     fn get_db() -> PeerDB<M> {
         let log = build_log(slog::Level::Debug, false);
-        PeerDB::new(&log)
+        PeerDB::new(vec![], &log)
     }
 
     #[test]
@@ -581,6 +674,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reconnect_backoff_grows() {
+        let mut pdb = get_db();
+        let random_peer = PeerId::random();
+
+        pdb.connect_ingoing(&random_peer);
+        assert!(!pdb.is_in_reconnect_backoff(&random_peer));
+
+        let info = pdb.peer_info_mut(&random_peer).unwrap();
+        info.apply_reconnect_backoff();
+        let first = info.reconnect_after.unwrap();
+        info.apply_reconnect_backoff();
+        let second = info.reconnect_after.unwrap();
+        assert!(second > first);
+
+        pdb.disconnect(&random_peer);
+        assert!(pdb.is_in_reconnect_backoff(&random_peer));
+        assert!(!pdb.is_in_reconnect_backoff(&PeerId::random()));
+    }
+
+    #[test]
+    fn test_goodbye_backoff_only_prevents_dialing() {
+        let mut pdb = get_db();
+        let random_peer = PeerId::random();
+
+        pdb.connect_ingoing(&random_peer);
+        pdb.peer_info_mut(&random_peer)
+            .unwrap()
+            .apply_goodbye_backoff(&GoodbyeReason::TooManyPeers);
+        pdb.disconnect(&random_peer);
+
+        assert!(pdb.is_in_dial_backoff(&random_peer));
+        assert!(!pdb.is_in_reconnect_backoff(&random_peer));
+    }
+
+    #[test]
+    fn test_goodbye_backoff_is_never_shortened() {
+        let mut info = PeerInfo::<M>::default();
+        info.apply_goodbye_backoff(&GoodbyeReason::Banned);
+        let banned = info.dial_after.unwrap();
+        info.apply_goodbye_backoff(&GoodbyeReason::ClientShutdown);
+        assert_eq!(info.dial_after.unwrap(), banned);
+    }
+
     #[test]
     fn test_disconnected_are_bounded() {
         let mut pdb = get_db();
@@ -938,4 +1075,62 @@ mod tests {
         assert!(pdb.is_banned(&p1));
         assert!(!pdb.is_banned(&p2));
     }
+
+    #[test]
+    fn test_trusted_peers_are_never_banned_or_pruned() {
+        let trusted_peer = PeerId::random();
+        let log = build_log(slog::Level::Debug, false);
+        let mut pdb: PeerDB<M> = PeerDB::new(vec![trusted_peer.clone()], &log);
+
+        assert!(pdb.is_trusted(&trusted_peer));
+        assert_eq!(pdb.score(&trusted_peer).score(), Score::max_score().score());
+
+        pdb.connect_ingoing(&trusted_peer);
+        pdb.ban(&trusted_peer);
+        assert!(!pdb.is_banned(&trusted_peer));
+
+        // fill the database with disconnected peers so that it must be pruned
+        pdb.disconnect(&trusted_peer);
+        for _ in 0..MAX_DC_PEERS + 1 {
+            let p = PeerId::random();
+            pdb.connect_ingoing(&p);
+            pdb.disconnect(&p);
+        }
+
+        assert!(pdb.peer_info(&trusted_peer).is_some());
+    }
+
+    #[test]
+    fn test_manual_bans() {
+        let mut pdb = get_db();
+
+        let banned_peer = PeerId::random();
+        let unknown_peer = PeerId::random();
+        let other_peer = PeerId::random();
+        let ip: IpAddr = Ipv4Addr::new(10, 0, 0, 1).into();
+
+        // unknown peers may be banned
+        assert!(pdb.ban_manually(unknown_peer.clone()));
+        assert!(!pdb.ban_manually(unknown_peer.clone()));
+        assert!(pdb.is_banned(&unknown_peer));
+
+        // a manually banned peer remains banned regardless of its score
+        pdb.connect_ingoing(&banned_peer);
+        pdb.ban_manually(banned_peer.clone());
+        add_score(&mut pdb, &banned_peer, 100.0);
+        assert!(pdb.is_banned(&banned_peer));
+        assert!(pdb.unban_manually(&banned_peer));
+        assert!(!pdb.is_banned(&banned_peer));
+
+        // peers listening on a banned IP are banned
+        pdb.connect_ingoing(&other_peer);
+        pdb.peers.get_mut(&other_peer).unwrap().listening_addresses =
+            vec![Multiaddr::empty().with(Protocol::from(Ipv4Addr::new(10, 0, 0, 1)))];
+        assert!(!pdb.is_banned(&other_peer));
+        assert!(pdb.ban_ip(ip));
+        assert!(pdb.is_ip_banned(&ip));
+        assert!(pdb.is_banned(&other_peer));
+        assert!(pdb.unban_ip(&ip));
+        assert!(!pdb.is_banned(&other_peer));
+    }
 }
@@ -145,6 +145,11 @@ impl std::fmt::Display for ScoreState {
 }
 
 impl Score {
+    /// Returns the maximum score a peer can obtain.
+    pub fn max_score() -> Self {
+        Self::from(MAX_SCORE)
+    }
+
     /// Access to the underlying score.
     pub fn score(&self) -> f64 {
         self.score
@@ -1,3 +1,4 @@
+use crate::metrics;
 use crate::rpc::methods::*;
 use crate::rpc::{
     codec::base::OutboundCodec,
@@ -62,7 +63,10 @@ impl<TSpec: EthSpec> Encoder<RPCCodedResponse<TSpec>> for SSZSnappyInboundCodec<
                 RPCResponse::BlocksByRange(res) => res.as_ssz_bytes(),
                 RPCResponse::BlocksByRoot(res) => res.as_ssz_bytes(),
                 RPCResponse::Pong(res) => res.data.as_ssz_bytes(),
-                RPCResponse::MetaData(res) => res.as_ssz_bytes(),
+                RPCResponse::MetaData(res) => match self.protocol.version {
+                    Version::V1 => MetaDataV1::from(res).as_ssz_bytes(),
+                    Version::V2 => res.as_ssz_bytes(),
+                },
             },
             RPCCodedResponse::Error(_, err) => err.as_ssz_bytes(),
             RPCCodedResponse::StreamTermination(_) => {
@@ -87,6 +91,11 @@ impl<TSpec: EthSpec> Encoder<RPCCodedResponse<TSpec>> for SSZSnappyInboundCodec<
 
         // Write compressed bytes to `dst`
         dst.extend_from_slice(writer.get_ref());
+        metrics::inc_counter_vec_by(
+            &metrics::OUTBOUND_BYTES_PER_PROTOCOL,
+            &[&self.protocol.message_name.to_string()],
+            writer.get_ref().len() as i64,
+        );
         Ok(())
     }
 }
@@ -122,6 +131,11 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                 let n = reader.get_ref().position();
                 self.len = None;
                 let _read_bytes = src.split_to(n as usize);
+                metrics::inc_counter_vec_by(
+                    &metrics::INBOUND_BYTES_PER_PROTOCOL,
+                    &[&self.protocol.message_name.to_string()],
+                    n as i64,
+                );
                 match self.protocol.message_name {
                     Protocol::Status => match self.protocol.version {
                         Version::V1 => {
@@ -133,6 +147,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::Goodbye => match self.protocol.version {
                         Version::V1 => {
@@ -144,6 +159,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::BlocksByRange => match self.protocol.version {
                         Version::V1 => {
@@ -157,6 +173,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::BlocksByRoot => match self.protocol.version {
                         Version::V1 => {
@@ -170,6 +187,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::Ping => match self.protocol.version {
                         Version::V1 => {
@@ -181,16 +199,16 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
-                    Protocol::MetaData => match self.protocol.version {
-                        Version::V1 => {
-                            if !decoded_buffer.is_empty() {
-                                Err(RPCError::InvalidData)
-                            } else {
-                                Ok(Some(RPCRequest::MetaData(PhantomData)))
-                            }
+                    // MetaData requests are empty in every version
+                    Protocol::MetaData => {
+                        if !decoded_buffer.is_empty() {
+                            Err(RPCError::InvalidData)
+                        } else {
+                            Ok(Some(RPCRequest::MetaData(PhantomData)))
                         }
-                    },
+                    }
                 }
             }
             Err(e) => match e.kind() {
@@ -261,6 +279,11 @@ impl<TSpec: EthSpec> Encoder<RPCRequest<TSpec>> for SSZSnappyOutboundCodec<TSpec
 
         // Write compressed bytes to `dst`
         dst.extend_from_slice(writer.get_ref());
+        metrics::inc_counter_vec_by(
+            &metrics::OUTBOUND_BYTES_PER_PROTOCOL,
+            &[&self.protocol.message_name.to_string()],
+            writer.get_ref().len() as i64,
+        );
         Ok(())
     }
 }
@@ -298,7 +321,12 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                 // `n` is how many bytes the reader read in the compressed stream
                 let n = reader.get_ref().position();
                 self.len = None;
-                let _read_byts = src.split_to(n as usize);
+                let _read_bytes = src.split_to(n as usize);
+                metrics::inc_counter_vec_by(
+                    &metrics::INBOUND_BYTES_PER_PROTOCOL,
+                    &[&self.protocol.message_name.to_string()],
+                    n as i64,
+                );
                 match self.protocol.message_name {
                     Protocol::Status => match self.protocol.version {
                         Version::V1 => {
@@ -310,6 +338,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::Goodbye => Err(RPCError::InvalidData),
                     Protocol::BlocksByRange => match self.protocol.version {
@@ -324,6 +353,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::BlocksByRoot => match self.protocol.version {
                         Version::V1 => {
@@ -337,6 +367,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::Ping => match self.protocol.version {
                         Version::V1 => {
@@ -348,9 +379,21 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                                 Err(RPCError::InvalidData)
                             }
                         }
+                        Version::V2 => Err(RPCError::InvalidData),
                     },
                     Protocol::MetaData => match self.protocol.version {
                         Version::V1 => {
+                            if decoded_buffer.len()
+                                == <MetaDataV1<TSpec> as Encode>::ssz_fixed_len()
+                            {
+                                Ok(Some(RPCResponse::MetaData(
+                                    MetaDataV1::from_ssz_bytes(&decoded_buffer)?.into(),
+                                )))
+                            } else {
+                                Err(RPCError::InvalidData)
+                            }
+                        }
+                        Version::V2 => {
                             if decoded_buffer.len() == <MetaData<TSpec> as Encode>::ssz_fixed_len()
                             {
                                 Ok(Some(RPCResponse::MetaData(MetaData::from_ssz_bytes(
@@ -401,6 +444,11 @@ impl<TSpec: EthSpec> OutboundCodec<RPCRequest<TSpec>> for SSZSnappyOutboundCodec
                 let n = reader.get_ref().position();
                 self.len = None;
                 let _read_bytes = src.split_to(n as usize);
+                metrics::inc_counter_vec_by(
+                    &metrics::INBOUND_BYTES_PER_PROTOCOL,
+                    &[&self.protocol.message_name.to_string()],
+                    n as i64,
+                );
                 Ok(Some(
                     String::from_utf8_lossy(&<Vec<u8>>::from_ssz_bytes(&decoded_buffer)?).into(),
                 ))
@@ -414,3 +462,86 @@ impl<TSpec: EthSpec> OutboundCodec<RPCRequest<TSpec>> for SSZSnappyOutboundCodec
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EnrBitfield, EnrSyncCommitteeBitfield};
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    const MAX_PACKET_SIZE: usize = 1_048_576;
+
+    fn metadata() -> MetaData<E> {
+        let mut attnets = EnrBitfield::<E>::default();
+        attnets.set(3, true).unwrap();
+        let mut syncnets = EnrSyncCommitteeBitfield::<E>::default();
+        syncnets.set(1, true).unwrap();
+
+        MetaData {
+            seq_number: 42,
+            attnets,
+            syncnets,
+        }
+    }
+
+    fn protocol(version: Version) -> ProtocolId {
+        ProtocolId::new(Protocol::MetaData, version, Encoding::SSZSnappy)
+    }
+
+    fn encode_metadata(version: Version, metadata: MetaData<E>) -> BytesMut {
+        let mut codec = SSZSnappyInboundCodec::<E>::new(protocol(version), MAX_PACKET_SIZE);
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                RPCCodedResponse::Success(RPCResponse::MetaData(metadata)),
+                &mut buf,
+            )
+            .unwrap();
+        buf
+    }
+
+    fn decode_metadata(
+        version: Version,
+        mut buf: BytesMut,
+    ) -> Result<Option<RPCResponse<E>>, RPCError> {
+        SSZSnappyOutboundCodec::<E>::new(protocol(version), MAX_PACKET_SIZE).decode(&mut buf)
+    }
+
+    #[test]
+    fn metadata_v2_round_trip() {
+        let buf = encode_metadata(Version::V2, metadata());
+
+        match decode_metadata(Version::V2, buf) {
+            Ok(Some(RPCResponse::MetaData(decoded))) => assert_eq!(decoded, metadata()),
+            other => panic!("expected metadata, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn metadata_v1_omits_syncnets() {
+        let buf = encode_metadata(Version::V1, metadata());
+
+        match decode_metadata(Version::V1, buf) {
+            Ok(Some(RPCResponse::MetaData(decoded))) => {
+                assert_eq!(decoded.seq_number, metadata().seq_number);
+                assert_eq!(decoded.attnets, metadata().attnets);
+                assert_eq!(decoded.syncnets, EnrSyncCommitteeBitfield::<E>::default());
+            }
+            other => panic!("expected metadata, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn metadata_version_mismatch() {
+        assert!(matches!(
+            decode_metadata(Version::V2, encode_metadata(Version::V1, metadata())),
+            Err(RPCError::InvalidData)
+        ));
+        assert!(matches!(
+            decode_metadata(Version::V1, encode_metadata(Version::V2, metadata())),
+            Err(RPCError::InvalidData)
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 //! Available RPC methods types and ids.
 
-use crate::types::EnrBitfield;
+use crate::types::{EnrBitfield, EnrSyncCommitteeBitfield};
 use serde::Serialize;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
@@ -57,7 +57,7 @@ impl ToString for ErrorType {
 ///
 // NOTE: The handler stores the `RequestId` to inform back of responses and errors, but it's execution
 // is independent of the contents on this type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RequestId {
     Router,
     Sync(usize),
@@ -98,6 +98,37 @@ pub struct MetaData<T: EthSpec> {
     pub seq_number: u64,
     /// The persistent subnet bitfield.
     pub attnets: EnrBitfield<T>,
+    /// The persistent sync committee subnet bitfield. Peers that only support the V1 protocol
+    /// are recorded with an empty bitfield.
+    pub syncnets: EnrSyncCommitteeBitfield<T>,
+}
+
+/// The METADATA response structure of the V1 protocol, which predates `syncnets`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct MetaDataV1<T: EthSpec> {
+    /// A sequential counter indicating when data gets modified.
+    pub seq_number: u64,
+    /// The persistent subnet bitfield.
+    pub attnets: EnrBitfield<T>,
+}
+
+impl<T: EthSpec> From<MetaDataV1<T>> for MetaData<T> {
+    fn from(metadata: MetaDataV1<T>) -> Self {
+        MetaData {
+            seq_number: metadata.seq_number,
+            attnets: metadata.attnets,
+            syncnets: EnrSyncCommitteeBitfield::<T>::default(),
+        }
+    }
+}
+
+impl<T: EthSpec> From<MetaData<T>> for MetaDataV1<T> {
+    fn from(metadata: MetaData<T>) -> Self {
+        MetaDataV1 {
+            seq_number: metadata.seq_number,
+            attnets: metadata.attnets,
+        }
+    }
 }
 
 /// The reason given for a `Goodbye` message.
@@ -153,6 +184,22 @@ impl Into<u64> for GoodbyeReason {
     }
 }
 
+impl GoodbyeReason {
+    /// The label used to identify this reason in metrics.
+    pub fn metrics_label(&self) -> &'static str {
+        match self {
+            GoodbyeReason::ClientShutdown => "client_shutdown",
+            GoodbyeReason::IrrelevantNetwork => "irrelevant_network",
+            GoodbyeReason::Fault => "fault",
+            GoodbyeReason::UnableToVerifyNetwork => "unable_to_verify_network",
+            GoodbyeReason::TooManyPeers => "too_many_peers",
+            GoodbyeReason::BadScore => "bad_score",
+            GoodbyeReason::Banned => "banned",
+            GoodbyeReason::Unknown => "unknown",
+        }
+    }
+}
+
 impl ssz::Encode for GoodbyeReason {
     fn is_ssz_fixed_len() -> bool {
         <u64 as ssz::Encode>::is_ssz_fixed_len()
@@ -4,6 +4,7 @@
 //! direct peer-to-peer communication primarily for sending/receiving chain information for
 //! syncing.
 
+use crate::metrics;
 use futures::future::FutureExt;
 use handler::RPCHandler;
 use libp2p::core::{connection::ConnectionId, ConnectedPoint};
@@ -13,14 +14,14 @@ use libp2p::swarm::{
 };
 use libp2p::{Multiaddr, PeerId};
 use rate_limiter::{RPCRateLimiter as RateLimiter, RPCRateLimiterBuilder, RateLimitedErr};
-use slog::{crit, debug, o};
+use slog::{debug, o};
 use std::marker::PhantomData;
 use std::task::{Context, Poll};
 use std::time::Duration;
 use types::EthSpec;
 
 pub(crate) use handler::HandlerErr;
-pub(crate) use methods::{MetaData, Ping, RPCCodedResponse, RPCResponse};
+pub(crate) use methods::{MetaData, MetaDataV1, Ping, RPCCodedResponse, RPCResponse};
 pub(crate) use protocol::{RPCProtocol, RPCRequest};
 
 pub use handler::SubstreamId;
@@ -233,13 +234,30 @@ where
                         }))
                 }
                 Err(RateLimitedErr::TooLarge) => {
-                    // we set the batch sizes, so this is a coding/config err
-                    crit!(self.log, "Batch too large to ever be processed";
-                        "protocol" => format!("{}", req.protocol()));
+                    // the request asks for more than the quota of the protocol allows in a single
+                    // batch, it can never be served
+                    debug!(self.log, "Request exceeds the maximum batch size";
+                        "request" => req.to_string(), "peer_id" => peer_id.to_string());
+                    metrics::inc_counter_vec(
+                        &metrics::RPC_RATE_LIMITED_REQUESTS,
+                        &[&req.protocol().to_string()],
+                    );
+                    self.send_response(
+                        peer_id,
+                        (conn_id, *id),
+                        RPCCodedResponse::Error(
+                            RPCResponseErrorCode::InvalidRequest,
+                            "Request exceeds the maximum batch size".into(),
+                        ),
+                    );
                 }
                 Err(RateLimitedErr::TooSoon(wait_time)) => {
                     debug!(self.log, "Request exceeds the rate limit";
                         "request" => req.to_string(), "peer_id" => peer_id.to_string(), "wait_time_ms" => wait_time.as_millis());
+                    metrics::inc_counter_vec(
+                        &metrics::RPC_RATE_LIMITED_REQUESTS,
+                        &[&req.protocol().to_string()],
+                    );
                     // send an error code to the peer.
                     // the handler upon receiving the error code will send it back to the behaviour
                     self.send_response(
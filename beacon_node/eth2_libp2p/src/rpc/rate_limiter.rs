@@ -306,7 +306,7 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::rate_limiter::{Limiter, Quota, RateLimitedErr};
     use std::time::Duration;
 
     #[test]
@@ -374,4 +374,23 @@ mod tests {
             .allows(Duration::from_secs_f32(0.4), &key, 1)
             .is_err());
     }
+
+    #[test]
+    fn batches_larger_than_the_quota_are_never_allowed() {
+        let mut limiter = Limiter::from_quota(Quota {
+            replenish_all_every: Duration::from_secs(2),
+            max_tokens: 4,
+        })
+        .unwrap();
+        let key = 10;
+
+        assert!(matches!(
+            limiter.allows(Duration::from_secs_f32(0.0), &key, 5),
+            Err(RateLimitedErr::TooLarge)
+        ));
+        // the rejected batch does not consume any tokens
+        assert!(limiter
+            .allows(Duration::from_secs_f32(0.0), &key, 4)
+            .is_ok());
+    }
 }
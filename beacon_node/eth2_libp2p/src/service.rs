@@ -1,4 +1,5 @@
 use crate::behaviour::{Behaviour, BehaviourEvent, PeerRequestId, Request, Response};
+use crate::connection_limits::InboundConnectionLimits;
 use crate::discovery::enr;
 use crate::multiaddr::Protocol;
 use crate::rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId};
@@ -7,10 +8,15 @@ use crate::EnrExt;
 use crate::{NetworkConfig, NetworkGlobals, PeerAction};
 use futures::prelude::*;
 use libp2p::core::{
-    identity::Keypair, multiaddr::Multiaddr, muxing::StreamMuxerBox, transport::boxed::Boxed,
+    either::EitherOutput,
+    identity::Keypair,
+    multiaddr::Multiaddr,
+    muxing::StreamMuxerBox,
+    transport::boxed::Boxed,
+    upgrade::{InboundUpgradeExt, OutboundUpgradeExt, SelectUpgrade},
 };
 use libp2p::{
-    core, noise,
+    core, noise, secio,
     swarm::{SwarmBuilder, SwarmEvent},
     PeerId, Swarm, Transport,
 };
@@ -21,7 +27,7 @@ use std::io::{Error, ErrorKind};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
-use types::{EnrForkId, EthSpec};
+use types::{ChainSpec, EnrForkId, EthSpec};
 
 pub const NETWORK_KEY_FILENAME: &str = "key";
 /// The maximum simultaneous libp2p connections per peer.
@@ -57,6 +63,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
         executor: environment::TaskExecutor,
         config: &NetworkConfig,
         enr_fork_id: EnrForkId,
+        chain_spec: &ChainSpec,
         log: &slog::Logger,
     ) -> error::Result<(Arc<NetworkGlobals<TSpec>>, Self)> {
         let log = log.new(o!("service"=> "libp2p"));
@@ -75,24 +82,41 @@ impl<TSpec: EthSpec> Service<TSpec> {
             enr.clone(),
             config.libp2p_port,
             config.discovery_port,
+            config
+                .trusted_peer_addresses()
+                .into_iter()
+                .map(|(peer_id, _)| peer_id)
+                .collect(),
             &log,
         ));
 
+        *network_globals.gossip_filter.write() = config.gossip_filter.clone();
+
         info!(log, "Libp2p Service"; "peer_id" => enr.peer_id().to_string());
         let discovery_string = if config.disable_discovery {
             "None".into()
         } else {
             config.discovery_port.to_string()
         };
-        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "tcp_port" => config.libp2p_port, "udp_port" => discovery_string);
+        debug!(log, "Attempting to open listening ports"; "address" => format!("{}", config.listen_address), "tcp_port" => config.libp2p_port, "udp_port" => discovery_string, "ipv6_address" => format!("{:?}", config.listen_address_ipv6));
 
         let mut swarm = {
             // Set up the transport - tcp/ws with noise and mplex
-            let transport = build_transport(local_keypair.clone())
+            let inbound_limits = InboundConnectionLimits::new(
+                config.max_inbound_connections_per_ip,
+                config.max_inbound_connections_per_prefix,
+            );
+            let transport = build_transport(local_keypair.clone(), inbound_limits)
                 .map_err(|e| format!("Failed to build transport: {:?}", e))?;
             // Lighthouse network behaviour
-            let behaviour =
-                Behaviour::new(&local_keypair, config, network_globals.clone(), &log).await?;
+            let behaviour = Behaviour::new(
+                &local_keypair,
+                config,
+                network_globals.clone(),
+                chain_spec,
+                &log,
+            )
+            .await?;
 
             // use the executor for libp2p
             struct Executor(environment::TaskExecutor);
@@ -111,29 +135,32 @@ impl<TSpec: EthSpec> Service<TSpec> {
                 .build()
         };
 
-        // listen on the specified address
-        let listen_multiaddr = {
-            let mut m = Multiaddr::from(config.listen_address);
-            m.push(Protocol::Tcp(config.libp2p_port));
-            m
-        };
+        if config.disable_listen {
+            info!(
+                log,
+                "Listening disabled, only outbound connections will be made"
+            );
+        }
 
-        match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
-            Ok(_) => {
-                let mut log_address = listen_multiaddr;
-                log_address.push(Protocol::P2p(local_peer_id.clone().into()));
-                info!(log, "Listening established"; "address" => format!("{}", log_address));
-            }
-            Err(err) => {
-                crit!(
-                    log,
-                    "Unable to listen on libp2p address";
-                    "error" => format!("{:?}", err),
-                    "listen_multiaddr" => format!("{}", listen_multiaddr),
-                );
-                return Err("Libp2p was unable to listen on the given listen address.".into());
-            }
-        };
+        // listen on the specified addresses, one per address family in dual-stack mode
+        for listen_multiaddr in config.listen_multiaddrs() {
+            match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
+                Ok(_) => {
+                    let mut log_address = listen_multiaddr;
+                    log_address.push(Protocol::P2p(local_peer_id.clone().into()));
+                    info!(log, "Listening established"; "address" => format!("{}", log_address));
+                }
+                Err(err) => {
+                    crit!(
+                        log,
+                        "Unable to listen on libp2p address";
+                        "error" => format!("{:?}", err),
+                        "listen_multiaddr" => format!("{}", listen_multiaddr),
+                    );
+                    return Err("Libp2p was unable to listen on the given listen address.".into());
+                }
+            };
+        }
 
         // helper closure for dialing peers
         let mut dial_addr = |mut multiaddr: Multiaddr| {
@@ -309,18 +336,29 @@ impl<TSpec: EthSpec> Service<TSpec> {
 /// mplex as the multiplexing layer.
 fn build_transport(
     local_private_key: Keypair,
+    inbound_limits: InboundConnectionLimits,
 ) -> Result<Boxed<(PeerId, StreamMuxerBox), Error>, Error> {
-    let transport = libp2p::tcp::TokioTcpConfig::new().nodelay(true);
+    // Excess inbound connections from a single origin are refused before any upgrades.
+    let transport = libp2p::tcp::TokioTcpConfig::new()
+        .nodelay(true)
+        .and_then(move |stream, endpoint| inbound_limits.limit_connection(stream, endpoint));
     let transport = libp2p::dns::DnsConfig::new(transport)?;
     #[cfg(feature = "libp2p-websocket")]
     let transport = {
         let trans_clone = transport.clone();
         transport.or_transport(libp2p::websocket::WsConfig::new(trans_clone))
     };
-    // Authentication
+    // Authentication. Noise is listed first so that it is preferred during negotiation, secio is
+    // kept as a fallback for peers that do not yet support Noise.
+    let authentication = SelectUpgrade::new(
+        generate_noise_config(&local_private_key),
+        secio::SecioConfig::new(local_private_key),
+    )
+    .map_inbound(flatten_authentication)
+    .map_outbound(flatten_authentication);
     Ok(transport
         .upgrade(core::upgrade::Version::V1)
-        .authenticate(generate_noise_config(&local_private_key))
+        .authenticate(authentication)
         .multiplex(libp2p::mplex::MplexConfig::new())
         .map(|(peer, muxer), _| (peer, core::muxing::StreamMuxerBox::new(muxer)))
         .timeout(Duration::from_secs(10))
@@ -410,6 +448,17 @@ fn generate_noise_config(
     noise::NoiseConfig::xx(static_dh_keys).into_authenticated()
 }
 
+/// Moves the remote `PeerId` out of whichever security protocol was negotiated, so that the
+/// combined upgrade can be used to authenticate the transport.
+fn flatten_authentication<A, B>(
+    output: EitherOutput<(PeerId, A), (PeerId, B)>,
+) -> (PeerId, EitherOutput<A, B>) {
+    match output {
+        EitherOutput::First((peer_id, stream)) => (peer_id, EitherOutput::First(stream)),
+        EitherOutput::Second((peer_id, stream)) => (peer_id, EitherOutput::Second(stream)),
+    }
+}
+
 /// For a multiaddr that ends with a peer id, this strips this suffix. Rust-libp2p
 /// only supports dialing to an address without providing the peer id.
 fn strip_peer_id(addr: &mut Multiaddr) {
@@ -4,11 +4,14 @@ mod pubsub;
 mod subnet;
 mod sync_state;
 mod topics;
+mod upnp_status;
 
 use types::{BitVector, EthSpec};
 
 #[allow(type_alias_bounds)]
 pub type EnrBitfield<T: EthSpec> = BitVector<T::SubnetBitfieldLength>;
+#[allow(type_alias_bounds)]
+pub type EnrSyncCommitteeBitfield<T: EthSpec> = BitVector<T::SyncCommitteeSubnetCount>;
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
@@ -16,4 +19,5 @@ pub use globals::NetworkGlobals;
 pub use pubsub::PubsubMessage;
 pub use subnet::SubnetDiscovery;
 pub use sync_state::SyncState;
-pub use topics::{GossipEncoding, GossipKind, GossipTopic};
+pub use topics::{GossipEncoding, GossipFilter, GossipKind, GossipTopic};
+pub use upnp_status::UPnPStatus;
@@ -3,13 +3,13 @@
 use crate::config::GOSSIP_MAX_SIZE;
 use crate::types::{GossipEncoding, GossipKind, GossipTopic};
 use crate::TopicHash;
-use snap::raw::{decompress_len, Decoder, Encoder};
+use snap::raw::{decompress_len, max_compress_len, Decoder, Encoder};
 use ssz::{Decode, Encode};
 use std::boxed::Box;
 use types::SubnetId;
 use types::{
     Attestation, AttesterSlashing, EthSpec, ProposerSlashing, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedVoluntaryExit,
+    SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit, SyncCommitteeMessage,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,6 +26,11 @@ pub enum PubsubMessage<T: EthSpec> {
     ProposerSlashing(Box<ProposerSlashing>),
     /// Gossipsub message providing notification of a new attester slashing.
     AttesterSlashing(Box<AttesterSlashing<T>>),
+    /// Gossipsub message providing notification of an aggregated sync committee contribution and
+    /// its proof.
+    SignedContributionAndProof(Box<SignedContributionAndProof<T>>),
+    /// Gossipsub message providing notification of a sync committee message with its subnet id.
+    SyncCommitteeMessage(Box<(u64, SyncCommitteeMessage)>),
 }
 
 impl<T: EthSpec> PubsubMessage<T> {
@@ -46,20 +51,28 @@ impl<T: EthSpec> PubsubMessage<T> {
             PubsubMessage::VoluntaryExit(_) => GossipKind::VoluntaryExit,
             PubsubMessage::ProposerSlashing(_) => GossipKind::ProposerSlashing,
             PubsubMessage::AttesterSlashing(_) => GossipKind::AttesterSlashing,
+            PubsubMessage::SignedContributionAndProof(_) => GossipKind::SignedContributionAndProof,
+            PubsubMessage::SyncCommitteeMessage(data) => GossipKind::SyncCommitteeMessage(data.0),
         }
     }
 
     /// This decodes `data` into a `PubsubMessage` given a list of topics.
     ///
     /// The topics are checked
-    /// in order and as soon as one topic matches the decoded data, we return the data.
+    /// in order and as soon as one topic matches the decoded data, we return the data. Topics
+    /// whose fork digest is not one of `fork_digests` are treated as unknown, as their messages
+    /// may not be valid for the forks we follow.
     /* Note: This is assuming we are not hashing topics. If we choose to hash topics, these will
      * need to be modified.
      *
      * Also note that a message can be associated with many topics. As soon as one of the topics is
      * known we match. If none of the topics are known we return an unknown state.
      */
-    pub fn decode(topics: &[TopicHash], data: &[u8]) -> Result<Self, String> {
+    pub fn decode(
+        topics: &[TopicHash],
+        data: &[u8],
+        fork_digests: &[[u8; 4]],
+    ) -> Result<Self, String> {
         let mut unknown_topics = Vec::new();
         for topic in topics {
             match GossipTopic::decode(topic.as_str()) {
@@ -67,9 +80,18 @@ impl<T: EthSpec> PubsubMessage<T> {
                     unknown_topics.push(topic);
                     continue;
                 }
+                Ok(gossip_topic) if !fork_digests.contains(&gossip_topic.fork_digest()) => {
+                    unknown_topics.push(topic);
+                    continue;
+                }
                 Ok(gossip_topic) => {
                     let decompressed_data = &(match gossip_topic.encoding() {
                         GossipEncoding::SSZSnappy => {
+                            // A valid compressed payload can never exceed this, regardless of
+                            // what its header claims
+                            if data.len() > max_compress_len(GOSSIP_MAX_SIZE) {
+                                return Err("ssz_snappy encoded data > max compressed size".into());
+                            }
                             // Exit early if uncompressed data is > GOSSIP_MAX_SIZE
                             match decompress_len(data) {
                                 Ok(n) if n > GOSSIP_MAX_SIZE => {
@@ -132,6 +154,23 @@ impl<T: EthSpec> PubsubMessage<T> {
                                 attester_slashing,
                             )));
                         }
+                        GossipKind::SignedContributionAndProof => {
+                            let contribution_and_proof =
+                                SignedContributionAndProof::from_ssz_bytes(decompressed_data)
+                                    .map_err(|e| format!("{:?}", e))?;
+                            return Ok(PubsubMessage::SignedContributionAndProof(Box::new(
+                                contribution_and_proof,
+                            )));
+                        }
+                        GossipKind::SyncCommitteeMessage(subnet_id) => {
+                            let sync_committee_message =
+                                SyncCommitteeMessage::from_ssz_bytes(decompressed_data)
+                                    .map_err(|e| format!("{:?}", e))?;
+                            return Ok(PubsubMessage::SyncCommitteeMessage(Box::new((
+                                *subnet_id,
+                                sync_committee_message,
+                            ))));
+                        }
                     }
                 }
             }
@@ -149,6 +188,8 @@ impl<T: EthSpec> PubsubMessage<T> {
             PubsubMessage::ProposerSlashing(data) => data.as_ssz_bytes(),
             PubsubMessage::AttesterSlashing(data) => data.as_ssz_bytes(),
             PubsubMessage::Attestation(data) => data.1.as_ssz_bytes(),
+            PubsubMessage::SignedContributionAndProof(data) => data.as_ssz_bytes(),
+            PubsubMessage::SyncCommitteeMessage(data) => data.1.as_ssz_bytes(),
         };
         match encoding {
             GossipEncoding::SSZSnappy => {
@@ -188,6 +229,18 @@ impl<T: EthSpec> std::fmt::Display for PubsubMessage<T> {
             PubsubMessage::VoluntaryExit(_data) => write!(f, "Voluntary Exit"),
             PubsubMessage::ProposerSlashing(_data) => write!(f, "Proposer Slashing"),
             PubsubMessage::AttesterSlashing(_data) => write!(f, "Attester Slashing"),
+            PubsubMessage::SignedContributionAndProof(data) => write!(
+                f,
+                "Sync Contribution and Proof: slot: {}, subcommittee_index: {}, aggregator_index: {}",
+                data.message.contribution.slot,
+                data.message.contribution.subcommittee_index,
+                data.message.aggregator_index,
+            ),
+            PubsubMessage::SyncCommitteeMessage(data) => write!(
+                f,
+                "Sync Committee Message: subnet_id: {}, slot: {}, validator_index: {}",
+                data.0, data.1.slot, data.1.validator_index,
+            ),
         }
     }
 }
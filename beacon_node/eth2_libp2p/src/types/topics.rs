@@ -1,5 +1,6 @@
 use libp2p::gossipsub::IdentTopic as Topic;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use types::SubnetId;
 
 /// The gossipsub topic names.
@@ -13,6 +14,8 @@ pub const BEACON_ATTESTATION_PREFIX: &str = "beacon_attestation_";
 pub const VOLUNTARY_EXIT_TOPIC: &str = "voluntary_exit";
 pub const PROPOSER_SLASHING_TOPIC: &str = "proposer_slashing";
 pub const ATTESTER_SLASHING_TOPIC: &str = "attester_slashing";
+pub const SIGNED_CONTRIBUTION_AND_PROOF_TOPIC: &str = "sync_committee_contribution_and_proof";
+pub const SYNC_COMMITTEE_PREFIX_TOPIC: &str = "sync_committee_";
 
 /// A gossipsub topic which encapsulates the type of messages that should be sent and received over
 /// the pubsub protocol and the way the messages should be encoded.
@@ -42,6 +45,10 @@ pub enum GossipKind {
     ProposerSlashing,
     /// Topic for publishing attester slashings.
     AttesterSlashing,
+    /// Topic for publishing aggregated sync committee contributions and proofs.
+    SignedContributionAndProof,
+    /// Topic for publishing unaggregated sync committee messages on a particular subnet.
+    SyncCommitteeMessage(u64),
 }
 
 impl std::fmt::Display for GossipKind {
@@ -53,6 +60,12 @@ impl std::fmt::Display for GossipKind {
             GossipKind::VoluntaryExit => write!(f, "voluntary_exit"),
             GossipKind::ProposerSlashing => write!(f, "proposer_slashing"),
             GossipKind::AttesterSlashing => write!(f, "attester_slashing"),
+            GossipKind::SignedContributionAndProof => {
+                write!(f, "sync_committee_contribution_and_proof")
+            }
+            GossipKind::SyncCommitteeMessage(subnet_id) => {
+                write!(f, "sync_committee_{}", subnet_id)
+            }
         }
     }
 }
@@ -70,6 +83,29 @@ impl Default for GossipEncoding {
     }
 }
 
+/// Restricts the gossip topics the node subscribes to, for nodes which only need to follow the
+/// head of the chain rather than relay all gossip.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GossipFilter {
+    /// Only subscribe to the beacon block topic.
+    pub blocks_only: bool,
+    /// Attestation subnets which are never subscribed to.
+    pub disabled_subnets: BTreeSet<u64>,
+}
+
+impl GossipFilter {
+    /// Returns true if the node may subscribe to topics of this kind.
+    pub fn allows(&self, kind: &GossipKind) -> bool {
+        match kind {
+            GossipKind::BeaconBlock => true,
+            _ if self.blocks_only => false,
+            GossipKind::Attestation(subnet_id) => !self.disabled_subnets.contains(&**subnet_id),
+            _ => true,
+        }
+    }
+}
+
 impl GossipTopic {
     pub fn new(kind: GossipKind, encoding: GossipEncoding, fork_digest: [u8; 4]) -> Self {
         GossipTopic {
@@ -84,6 +120,11 @@ impl GossipTopic {
         &self.encoding
     }
 
+    /// Returns the fork digest of the gossipsub topic.
+    pub fn fork_digest(&self) -> [u8; 4] {
+        self.fork_digest
+    }
+
     /// Returns a mutable reference to the fork digest of the gossipsub topic.
     pub fn digest(&mut self) -> &mut [u8; 4] {
         &mut self.fork_digest
@@ -120,9 +161,13 @@ impl GossipTopic {
                 VOLUNTARY_EXIT_TOPIC => GossipKind::VoluntaryExit,
                 PROPOSER_SLASHING_TOPIC => GossipKind::ProposerSlashing,
                 ATTESTER_SLASHING_TOPIC => GossipKind::AttesterSlashing,
+                SIGNED_CONTRIBUTION_AND_PROOF_TOPIC => GossipKind::SignedContributionAndProof,
                 topic => match committee_topic_index(topic) {
                     Some(subnet_id) => GossipKind::Attestation(subnet_id),
-                    None => return Err(format!("Unknown topic: {}", topic)),
+                    None => match sync_committee_topic_index(topic) {
+                        Some(subnet_id) => GossipKind::SyncCommitteeMessage(subnet_id),
+                        None => return Err(format!("Unknown topic: {}", topic)),
+                    },
                 },
             };
 
@@ -156,6 +201,10 @@ impl Into<String> for GossipTopic {
             GossipKind::ProposerSlashing => PROPOSER_SLASHING_TOPIC.into(),
             GossipKind::AttesterSlashing => ATTESTER_SLASHING_TOPIC.into(),
             GossipKind::Attestation(index) => format!("{}{}", BEACON_ATTESTATION_PREFIX, *index,),
+            GossipKind::SignedContributionAndProof => SIGNED_CONTRIBUTION_AND_PROOF_TOPIC.into(),
+            GossipKind::SyncCommitteeMessage(index) => {
+                format!("{}{}", SYNC_COMMITTEE_PREFIX_TOPIC, index)
+            }
         };
         format!(
             "/{}/{}/{}/{}",
@@ -184,3 +233,101 @@ fn committee_topic_index(topic: &str) -> Option<SubnetId> {
     }
     None
 }
+
+// Determines if a string is a sync committee topic.
+fn sync_committee_topic_index(topic: &str) -> Option<u64> {
+    if topic.starts_with(SYNC_COMMITTEE_PREFIX_TOPIC) {
+        return u64::from_str_radix(topic.trim_start_matches(SYNC_COMMITTEE_PREFIX_TOPIC), 10).ok();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_kinds() -> Vec<GossipKind> {
+        vec![
+            GossipKind::BeaconBlock,
+            GossipKind::BeaconAggregateAndProof,
+            GossipKind::Attestation(SubnetId::new(0)),
+            GossipKind::Attestation(SubnetId::new(1)),
+            GossipKind::VoluntaryExit,
+            GossipKind::ProposerSlashing,
+            GossipKind::AttesterSlashing,
+            GossipKind::SignedContributionAndProof,
+            GossipKind::SyncCommitteeMessage(0),
+        ]
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for kind in all_kinds() {
+            let topic = GossipTopic::new(kind, GossipEncoding::SSZSnappy, [1, 2, 3, 4]);
+            let topic_str: String = topic.clone().into();
+            assert_eq!(GossipTopic::decode(&topic_str), Ok(topic));
+        }
+    }
+
+    #[test]
+    fn sync_committee_topic_names() {
+        let topic: String = GossipTopic::new(
+            GossipKind::SyncCommitteeMessage(3),
+            GossipEncoding::SSZSnappy,
+            [0; 4],
+        )
+        .into();
+        assert_eq!(topic, "/eth2/00000000/sync_committee_3/ssz_snappy");
+
+        let topic: String = GossipTopic::new(
+            GossipKind::SignedContributionAndProof,
+            GossipEncoding::SSZSnappy,
+            [0; 4],
+        )
+        .into();
+        assert_eq!(
+            topic,
+            "/eth2/00000000/sync_committee_contribution_and_proof/ssz_snappy"
+        );
+    }
+
+    #[test]
+    fn default_filter_allows_all() {
+        let filter = GossipFilter::default();
+        for kind in all_kinds() {
+            assert!(filter.allows(&kind), "{} should be allowed", kind);
+        }
+    }
+
+    #[test]
+    fn blocks_only_filter() {
+        let filter = GossipFilter {
+            blocks_only: true,
+            disabled_subnets: BTreeSet::new(),
+        };
+        for kind in all_kinds() {
+            assert_eq!(
+                filter.allows(&kind),
+                kind == GossipKind::BeaconBlock,
+                "unexpected result for {}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_subnets_filter() {
+        let filter = GossipFilter {
+            blocks_only: false,
+            disabled_subnets: vec![1].into_iter().collect(),
+        };
+        for kind in all_kinds() {
+            assert_eq!(
+                filter.allows(&kind),
+                kind != GossipKind::Attestation(SubnetId::new(1)),
+                "unexpected result for {}",
+                kind
+            );
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// The result of attempting to establish external port mappings via UPnP.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UPnPStatus {
+    /// UPnP has been disabled by the user.
+    Disabled,
+    /// The search for a UPnP gateway is in progress.
+    Pending,
+    /// No gateway could be found or no mappings could be constructed.
+    Failed { reason: String },
+    /// At least one port mapping was established on the gateway.
+    Established {
+        /// The external TCP socket of the libp2p service, if mapped.
+        tcp_socket: Option<SocketAddr>,
+        /// The external UDP socket of the discovery service, if mapped.
+        udp_socket: Option<SocketAddr>,
+    },
+}
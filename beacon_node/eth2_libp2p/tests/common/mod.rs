@@ -106,10 +106,16 @@ pub async fn build_libp2p_instance(boot_nodes: Vec<Enr>, log: slog::Logger) -> L
         shutdown_tx,
     );
     Libp2pInstance(
-        LibP2PService::new(executor, &config, EnrForkId::default(), &log)
-            .await
-            .expect("should build libp2p instance")
-            .1,
+        LibP2PService::new(
+            executor,
+            &config,
+            EnrForkId::default(),
+            &E::default_spec(),
+            &log,
+        )
+        .await
+        .expect("should build libp2p instance")
+        .1,
         signal,
     )
 }
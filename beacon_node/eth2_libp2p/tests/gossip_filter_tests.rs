@@ -0,0 +1,136 @@
+#![cfg(test)]
+use eth2_libp2p::types::{GossipFilter, GossipKind};
+use eth2_libp2p::Eth2Enr;
+use slog::Level;
+use types::{MinimalEthSpec, SubnetId};
+
+mod common;
+
+type E = MinimalEthSpec;
+
+#[tokio::test]
+// Tests that updating the gossip filter unsubscribes from, and re-subscribes to, topics
+async fn test_update_gossip_filter() {
+    let log = common::build_log(Level::Debug, false);
+    let mut node = common::build_libp2p_instance(vec![], log).await;
+
+    let subnet_id = SubnetId::new(1);
+    assert!(node.swarm.subscribe_to_subnet(subnet_id));
+    node.swarm.update_enr_subnet(subnet_id, true);
+    assert!(node
+        .swarm
+        .local_enr()
+        .bitfield::<E>()
+        .unwrap()
+        .get(*subnet_id as usize)
+        .unwrap());
+
+    node.swarm.update_gossip_filter(GossipFilter {
+        blocks_only: true,
+        disabled_subnets: Default::default(),
+    });
+
+    // the block topic is still subscribed to
+    assert!(!node.swarm.subscribe_kind(GossipKind::BeaconBlock));
+    // all other topics were unsubscribed from and can't be subscribed to again
+    assert!(!node.swarm.unsubscribe_kind(GossipKind::VoluntaryExit));
+    assert!(!node.swarm.unsubscribe_from_subnet(subnet_id));
+    assert!(!node
+        .swarm
+        .subscribe_kind(GossipKind::BeaconAggregateAndProof));
+    assert!(!node.swarm.subscribe_to_subnet(subnet_id));
+    // the subnet is no longer advertised
+    assert!(!node
+        .swarm
+        .local_enr()
+        .bitfield::<E>()
+        .unwrap()
+        .get(*subnet_id as usize)
+        .unwrap());
+
+    node.swarm.update_gossip_filter(GossipFilter::default());
+
+    // the start-up topics are subscribed to again
+    assert!(node.swarm.unsubscribe_kind(GossipKind::VoluntaryExit));
+    assert!(!node.swarm.subscribe_kind(GossipKind::BeaconBlock));
+    // subnets are only re-subscribed to when requested
+    assert!(node.swarm.subscribe_to_subnet(subnet_id));
+}
+
+#[tokio::test]
+// Tests that disabled subnets are unsubscribed from while other topics are kept
+async fn test_update_gossip_filter_disabled_subnets() {
+    let log = common::build_log(Level::Debug, false);
+    let mut node = common::build_libp2p_instance(vec![], log).await;
+
+    assert!(node.swarm.subscribe_to_subnet(SubnetId::new(1)));
+    assert!(node.swarm.subscribe_to_subnet(SubnetId::new(2)));
+
+    node.swarm.update_gossip_filter(GossipFilter {
+        blocks_only: false,
+        disabled_subnets: vec![1].into_iter().collect(),
+    });
+
+    assert!(!node.swarm.unsubscribe_from_subnet(SubnetId::new(1)));
+    assert!(!node.swarm.subscribe_to_subnet(SubnetId::new(1)));
+    assert!(node.swarm.unsubscribe_from_subnet(SubnetId::new(2)));
+    assert!(node.swarm.unsubscribe_kind(GossipKind::VoluntaryExit));
+}
+
+#[tokio::test]
+// Tests that sync committee subnet subscriptions are advertised in the ENR and the meta data
+async fn test_sync_committee_subnet_advertisement() {
+    let log = common::build_log(Level::Debug, false);
+    let mut node = common::build_libp2p_instance(vec![], log).await;
+
+    let subnet_id = 1;
+    let seq_number = node.swarm.local_metadata().seq_number;
+    assert!(node.swarm.subscribe_to_sync_subnet(subnet_id));
+    assert!(node
+        .swarm
+        .local_enr()
+        .sync_committee_bitfield::<E>()
+        .unwrap()
+        .get(subnet_id as usize)
+        .unwrap());
+    assert!(node
+        .swarm
+        .local_metadata()
+        .syncnets
+        .get(subnet_id as usize)
+        .unwrap());
+    assert!(node.swarm.local_metadata().seq_number > seq_number);
+
+    assert!(node.swarm.unsubscribe_from_sync_subnet(subnet_id));
+    assert!(!node
+        .swarm
+        .local_enr()
+        .sync_committee_bitfield::<E>()
+        .unwrap()
+        .get(subnet_id as usize)
+        .unwrap());
+    assert!(!node
+        .swarm
+        .local_metadata()
+        .syncnets
+        .get(subnet_id as usize)
+        .unwrap());
+
+    // a subnet which is unsubscribed from by the gossip filter is no longer advertised
+    assert!(node.swarm.subscribe_to_sync_subnet(subnet_id));
+    node.swarm.update_gossip_filter(GossipFilter {
+        blocks_only: true,
+        disabled_subnets: Default::default(),
+    });
+    assert!(!node.swarm.subscribe_to_sync_subnet(subnet_id));
+    assert!(!node
+        .swarm
+        .local_metadata()
+        .syncnets
+        .get(subnet_id as usize)
+        .unwrap());
+    assert_eq!(
+        node.swarm.local_enr().sync_committee_bitfield::<E>(),
+        Ok(node.swarm.local_metadata().syncnets.clone())
+    );
+}
@@ -113,6 +113,15 @@ impl Eth1GenesisService {
         );
 
         loop {
+            // Select the first healthy eth1 endpoint, falling back to the others if required.
+            if let Err(e) = eth1_service.select_endpoint().await {
+                error!(
+                    log,
+                    "Failed to select eth1 endpoint";
+                    "error" => e
+                )
+            }
+
             let update_result = eth1_service
                 .update_deposit_cache()
                 .await
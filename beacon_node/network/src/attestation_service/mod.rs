@@ -6,10 +6,10 @@ use std::collections::{HashMap, VecDeque};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use futures::prelude::*;
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 use slog::{crit, debug, error, o, trace, warn};
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -72,7 +72,9 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// A reference to the beacon chain to process received attestations.
     beacon_chain: Arc<BeaconChain<T>>,
 
-    /// The collection of currently subscribed random subnets mapped to their expiry deadline.
+    /// The collection of currently subscribed long-lived random subnets mapped to their expiry
+    /// deadline. These are maintained independently of the short-lived subscriptions required for
+    /// validator duties and are advertised in our ENR.
     random_subnets: HashSetDelay<SubnetId>,
 
     /// A collection of timeouts for when to subscribe to a shard subnet.
@@ -107,13 +109,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ) -> Self {
         let log = log.new(o!("service" => "attestation_service"));
 
-        // calculate the random subnet duration from the spec constants
-        let spec = &beacon_chain.spec;
         let slot_duration = beacon_chain.slot_clock.slot_duration();
-        let random_subnet_duration_millis = spec
-            .epochs_per_random_subnet_subscription
-            .saturating_mul(T::EthSpec::slots_per_epoch())
-            .saturating_mul(slot_duration.as_millis() as u64);
 
         // Panics on overflow. Ensure LAST_SEEN_VALIDATOR_TIMEOUT is not too large.
         let last_seen_val_timeout = slot_duration
@@ -127,7 +123,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             events: VecDeque::with_capacity(10),
             network_globals,
             beacon_chain,
-            random_subnets: HashSetDelay::new(Duration::from_millis(random_subnet_duration_millis)),
+            // Random subnets are always inserted with a randomised duration (see
+            // `random_subnet_duration`), the default timeout is never used.
+            random_subnets: HashSetDelay::new(default_timeout),
             subscriptions: HashSetDelay::new(default_timeout),
             unsubscriptions: HashSetDelay::new(default_timeout),
             aggregate_validators_on_subnet: HashSetDelay::new(default_timeout),
@@ -365,9 +363,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
 
         // Checks on current subscriptions
         // Note: We may be connected to a long-lived random subnet. In this case we still add the
-        // subscription timeout and check this case when the timeout fires. This is because a
-        // long-lived random subnet can be unsubscribed at any time when a validator becomes
-        // in-active. This case is checked on the subscription event (see `handle_subscriptions`).
+        // subscription timeout and check this case when the timeout fires. Long-lived random
+        // subnets are rotated and removed independently of these short-lived subscriptions (see
+        // `handle_subscriptions` and `remove_random_subnet`).
 
         // Return if we already have a subscription for this subnet_id and slot
         if self.subscriptions.contains(&exact_subnet) {
@@ -396,30 +394,67 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// This also updates the ENR to indicate our long-lived subscription to the subnet
     fn add_known_validator(&mut self, validator_index: u64) {
-        if self.known_validators.get(&validator_index).is_none() {
+        let is_new_validator = !self.known_validators.contains(&validator_index);
+        // add the new validator or update the current timeout for a known validator
+        self.known_validators.insert(validator_index);
+
+        if is_new_validator {
             // New validator has subscribed
             // Subscribe to random topics and update the ENR if needed.
-
-            let spec = &self.beacon_chain.spec;
-
-            if self.random_subnets.len() < spec.attestation_subnet_count as usize {
-                // Still room for subscriptions
-                self.subscribe_to_random_subnets(
-                    self.beacon_chain.spec.random_subnets_per_validator as usize,
-                );
+            let missing_subnets = self
+                .required_random_subnet_count()
+                .saturating_sub(self.random_subnets.len());
+            if missing_subnets > 0 {
+                self.subscribe_to_random_subnets(missing_subnets);
             }
         }
-        // add the new validator or update the current timeout for a known validator
-        self.known_validators.insert(validator_index);
     }
 
-    /// Subscribe to long-lived random subnets and update the local ENR bitfield.
-    fn subscribe_to_random_subnets(&mut self, no_subnets_to_subscribe: usize) {
-        let subnet_count = self.beacon_chain.spec.attestation_subnet_count;
+    /// The number of long-lived random subnets we should be subscribed to.
+    ///
+    /// As per the spec, this is `RANDOM_SUBNETS_PER_VALIDATOR` for each known validator, capped at
+    /// `ATTESTATION_SUBNET_COUNT`.
+    fn required_random_subnet_count(&self) -> usize {
+        let spec = &self.beacon_chain.spec;
+        std::cmp::min(
+            (self.known_validators.len() as u64).saturating_mul(spec.random_subnets_per_validator),
+            spec.attestation_subnet_count,
+        ) as usize
+    }
+
+    /// Returns the duration of a new long-lived random subnet subscription.
+    ///
+    /// As per the spec, this is a random number of epochs in the range
+    /// `[EPOCHS_PER_RANDOM_SUBNET_SUBSCRIPTION, 2 * EPOCHS_PER_RANDOM_SUBNET_SUBSCRIPTION)`.
+    fn random_subnet_duration(&self) -> Duration {
+        let min_epochs = std::cmp::max(
+            self.beacon_chain.spec.epochs_per_random_subnet_subscription,
+            1,
+        );
+        let epochs = rand::thread_rng().gen_range(min_epochs, min_epochs.saturating_mul(2));
+        let slot_duration = self.beacon_chain.slot_clock.slot_duration();
+        Duration::from_millis(
+            epochs
+                .saturating_mul(T::EthSpec::slots_per_epoch())
+                .saturating_mul(slot_duration.as_millis() as u64),
+        )
+    }
 
-        // Build a list of random subnets that we are not currently subscribed to.
-        let available_subnets = (0..subnet_count)
+    /// The subnets which the gossip filter allows us to subscribe to.
+    fn allowed_subnets(&self) -> Vec<SubnetId> {
+        let gossip_filter = self.network_globals.gossip_filter.read();
+        (0..self.beacon_chain.spec.attestation_subnet_count)
             .map(SubnetId::new)
+            .filter(|subnet_id| gossip_filter.allows(&GossipKind::Attestation(*subnet_id)))
+            .collect()
+    }
+
+    /// Subscribe to long-lived random subnets and update the local ENR bitfield.
+    fn subscribe_to_random_subnets(&mut self, no_subnets_to_subscribe: usize) {
+        // Build a list of allowed random subnets that we are not currently subscribed to.
+        let available_subnets = self
+            .allowed_subnets()
+            .into_iter()
             .filter(|subnet_id| self.random_subnets.get(subnet_id).is_none())
             .collect::<Vec<_>>();
 
@@ -437,14 +472,10 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         };
 
         for subnet_id in to_subscribe_subnets {
-            // remove this subnet from any immediate subscription/un-subscription events
-            self.subscriptions
-                .retain(|exact_subnet| exact_subnet.subnet_id != subnet_id);
-            self.unsubscriptions
-                .retain(|exact_subnet| exact_subnet.subnet_id != subnet_id);
-
-            // insert a new random subnet
-            self.random_subnets.insert(subnet_id);
+            // insert a new random subnet. Any short-lived subscriptions to this subnet are left
+            // untouched, their unsubscription events are ignored whilst the random subnet exists.
+            let duration = self.random_subnet_duration();
+            self.random_subnets.insert_at(subnet_id, duration);
 
             // if we are not already subscribed, then subscribe
             let topic_kind = &GossipKind::Attestation(subnet_id);
@@ -476,43 +507,26 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// A queued subscription is ready.
     ///
     /// We add subscriptions events even if we are already subscribed to a random subnet (as these
-    /// can be unsubscribed at any time by inactive validators). If we are
-    /// still subscribed at the time the event fires, we don't re-subscribe.
+    /// can be rotated or removed at any time). If we are still subscribed at the time the event
+    /// fires, we don't re-subscribe.
     fn handle_subscriptions(&mut self, exact_subnet: ExactSubnet) {
-        // Check if the subnet currently exists as a long-lasting random subnet
-        if let Some(expiry) = self.random_subnets.get(&exact_subnet.subnet_id) {
-            // we are subscribed via a random subnet, if this is to expire during the time we need
-            // to be subscribed, just extend the expiry
-            let slot_duration = self.beacon_chain.slot_clock.slot_duration();
-            let advance_subscription_duration = slot_duration
-                .checked_div(ADVANCE_SUBSCRIBE_TIME)
-                .expect("ADVANCE_SUBSCRIPTION_TIME cannot be too large");
-            // we require the subnet subscription for at least a slot on top of the initial
-            // subscription time
-            let expected_end_subscription_duration = slot_duration + advance_subscription_duration;
-
-            if expiry < &(Instant::now() + expected_end_subscription_duration) {
-                self.random_subnets
-                    .update_timeout(&exact_subnet.subnet_id, expected_end_subscription_duration);
-            }
-        } else {
-            // we are also not un-subscribing from a subnet if the next slot requires us to be
-            // subscribed. Therefore there could be the case that we are already still subscribed
-            // to the required subnet. In which case we do not issue another subscription request.
-            let topic_kind = &GossipKind::Attestation(exact_subnet.subnet_id);
-            if self
-                .network_globals
-                .gossipsub_subscriptions
-                .read()
-                .iter()
-                .find(|topic| topic.kind() == topic_kind)
-                .is_none()
-            {
-                // we are not already subscribed
-                debug!(self.log, "Subscribing to subnet"; "subnet" => *exact_subnet.subnet_id, "target_slot" => exact_subnet.slot.as_u64());
-                self.events
-                    .push_back(AttServiceMessage::Subscribe(exact_subnet.subnet_id));
-            }
+        // we are also not un-subscribing from a subnet if the next slot requires us to be
+        // subscribed. Therefore there could be the case that we are already still subscribed
+        // to the required subnet (either from a previous slot or via a long-lived random subnet).
+        // In which case we do not issue another subscription request.
+        let topic_kind = &GossipKind::Attestation(exact_subnet.subnet_id);
+        if self
+            .network_globals
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .find(|topic| topic.kind() == topic_kind)
+            .is_none()
+        {
+            // we are not already subscribed
+            debug!(self.log, "Subscribing to subnet"; "subnet" => *exact_subnet.subnet_id, "target_slot" => exact_subnet.slot.as_u64());
+            self.events
+                .push_back(AttServiceMessage::Subscribe(exact_subnet.subnet_id));
         }
     }
 
@@ -541,24 +555,26 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// This function selects a new subnet to join, or extends the expiry if there are no more
     /// available subnets to choose from.
     fn handle_random_subnet_expiry(&mut self, subnet_id: SubnetId) {
-        let subnet_count = self.beacon_chain.spec.attestation_subnet_count;
-        if self.random_subnets.len() == (subnet_count - 1) as usize {
+        let allowed_subnets = self.allowed_subnets();
+        let required_subnets = self.required_random_subnet_count();
+        if required_subnets >= allowed_subnets.len()
+            && self.random_subnets.len() + 1 == allowed_subnets.len()
+            && allowed_subnets.contains(&subnet_id)
+        {
             // We are at capacity, simply increase the timeout of the current subnet
-            self.random_subnets.insert(subnet_id);
+            let duration = self.random_subnet_duration();
+            self.random_subnets.insert_at(subnet_id, duration);
             return;
         }
 
-        // we are not at capacity, unsubscribe from the current subnet, remove the ENR bitfield bit and choose a new random one
-        // from the available subnets
-        // Note: This should not occur during a required subnet as subscriptions update the timeout
-        // to last as long as they are needed.
-
-        debug!(self.log, "Unsubscribing from random subnet"; "subnet_id" => *subnet_id);
-        self.events
-            .push_back(AttServiceMessage::Unsubscribe(subnet_id));
-        self.events
-            .push_back(AttServiceMessage::EnrRemove(subnet_id));
-        self.subscribe_to_random_subnets(1);
+        // we are not at capacity, unsubscribe from the current subnet, remove the ENR bitfield bit
+        // and choose a new random one from the available subnets if one is still required
+        debug!(self.log, "Rotating random subnet"; "subnet_id" => *subnet_id);
+        self.remove_random_subnet(subnet_id);
+        let missing_subnets = required_subnets.saturating_sub(self.random_subnets.len());
+        if missing_subnets > 0 {
+            self.subscribe_to_random_subnets(missing_subnets);
+        }
     }
 
     /// A known validator has not sent a subscription in a while. They are considered offline and the
@@ -566,56 +582,44 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// We don't keep track of a specific validator to random subnet, rather the ratio of active
     /// validators to random subnets. So when a validator goes offline, we can simply remove the
-    /// allocated amount of random subnets.
-    fn handle_known_validator_expiry(&mut self) -> Result<(), ()> {
-        let spec = &self.beacon_chain.spec;
-        let subnet_count = spec.attestation_subnet_count;
-        let random_subnets_per_validator = spec.random_subnets_per_validator;
-        if self.known_validators.len() as u64 * random_subnets_per_validator >= subnet_count {
-            // have too many validators, ignore
-            return Ok(());
+    /// excess random subnets.
+    fn handle_known_validator_expiry(&mut self) {
+        let excess_subnets = self
+            .random_subnets
+            .len()
+            .saturating_sub(self.required_random_subnet_count());
+        if excess_subnets == 0 {
+            return;
         }
 
         let subscribed_subnets = self.random_subnets.keys().cloned().collect::<Vec<_>>();
-        let to_remove_subnets = subscribed_subnets.choose_multiple(
-            &mut rand::thread_rng(),
-            random_subnets_per_validator as usize,
-        );
-        let current_slot = self.beacon_chain.slot_clock.now().ok_or_else(|| {
-            warn!(self.log, "Could not get the current slot");
-        })?;
+        let to_remove_subnets = subscribed_subnets
+            .choose_multiple(&mut rand::thread_rng(), excess_subnets)
+            .cloned()
+            .collect::<Vec<_>>();
 
         for subnet_id in to_remove_subnets {
-            // If a subscription is queued for two slots in the future, it's associated unsubscription
-            // will unsubscribe from the expired subnet.
-            // If there is no unsubscription for this subnet,slot it is safe to add one, without
-            // unsubscribing early from a required subnet
-            let subnet = ExactSubnet {
-                subnet_id: *subnet_id,
-                slot: current_slot + 2,
-            };
-            if self.subscriptions.get(&subnet).is_none() {
-                // set an unsubscribe event
-                let duration_to_next_slot = self
-                    .beacon_chain
-                    .slot_clock
-                    .duration_to_next_slot()
-                    .ok_or_else(|| {
-                        warn!(self.log, "Unable to determine duration to next slot");
-                    })?;
-                let slot_duration = self.beacon_chain.slot_clock.slot_duration();
-                // Set the unsubscription timeout
-                let unsubscription_duration = duration_to_next_slot + slot_duration * 2;
-                self.unsubscriptions
-                    .insert_at(subnet, unsubscription_duration);
-            }
-            // as the long lasting subnet subscription is being removed, remove the subnet_id from
-            // the ENR bitfield
+            debug!(self.log, "Removing random subnet"; "subnet_id" => *subnet_id);
+            self.remove_random_subnet(subnet_id);
+        }
+    }
+
+    /// Removes a long-lived random subnet, removing it from the ENR bitfield.
+    ///
+    /// If a short-lived subscription for a validator duty is currently active on this subnet, we
+    /// remain subscribed and let its queued unsubscription event remove us from the topic.
+    fn remove_random_subnet(&mut self, subnet_id: SubnetId) {
+        self.random_subnets.remove(&subnet_id);
+        self.events
+            .push_back(AttServiceMessage::EnrRemove(subnet_id));
+
+        let required_by_duty = self.unsubscriptions.keys().any(|exact_subnet| {
+            exact_subnet.subnet_id == subnet_id && !self.subscriptions.contains(exact_subnet)
+        });
+        if !required_by_duty {
             self.events
-                .push_back(AttServiceMessage::EnrRemove(*subnet_id));
-            self.random_subnets.remove(subnet_id);
+                .push_back(AttServiceMessage::Unsubscribe(subnet_id));
         }
-        Ok(())
     }
 }
 
@@ -661,9 +665,7 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
 
         // process any known validator expiries
         match self.known_validators.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok(_validator_index))) => {
-                let _ = self.handle_known_validator_expiry();
-            }
+            Poll::Ready(Some(Ok(_validator_index))) => self.handle_known_validator_expiry(),
             Poll::Ready(Some(Err(e))) => {
                 error!(self.log, "Failed to check for random subnet cycles"; "error"=> e);
             }
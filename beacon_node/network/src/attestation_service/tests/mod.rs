@@ -102,7 +102,8 @@ mod tests {
         let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
         let enr = build_enr::<MinimalEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap();
 
-        let network_globals: NetworkGlobals<MinimalEthSpec> = NetworkGlobals::new(enr, 0, 0, &log);
+        let network_globals: NetworkGlobals<MinimalEthSpec> =
+            NetworkGlobals::new(enr, 0, 0, vec![], &log);
         AttestationService::new(beacon_chain, Arc::new(network_globals), &log)
     }
 
@@ -602,6 +603,57 @@ mod tests {
         assert_eq!(unexpected_msg_count, 0);
     }
 
+    #[tokio::test]
+    async fn random_subnets_exclude_disabled_subnets() {
+        // subscribe 10 slots ahead so we do not produce any exact subnet messages
+        let subscription_slot = 10;
+        let subscription_count = 64;
+        let committee_count = 1;
+        let disabled_subnets = vec![0, 1];
+
+        // create the attestation service with some subnets disabled
+        let mut attestation_service = get_attestation_service();
+        attestation_service
+            .network_globals
+            .gossip_filter
+            .write()
+            .disabled_subnets = disabled_subnets.iter().copied().collect();
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        let subscriptions = get_subscriptions(
+            subscription_count,
+            current_slot + subscription_slot,
+            committee_count,
+        );
+
+        // submit the subscriptions
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        let events = get_events(attestation_service, 186, 3).await;
+        let mut subscribed_subnets = vec![];
+        let mut enr_add_count = 0;
+
+        for event in events {
+            match event {
+                AttServiceMessage::Subscribe(subnet_id) => subscribed_subnets.push(*subnet_id),
+                AttServiceMessage::EnrAdd(_any_subnet) => enr_add_count += 1,
+                _ => {}
+            }
+        }
+
+        assert_eq!(subscribed_subnets.len(), 62);
+        assert_eq!(enr_add_count, 62);
+        assert!(subscribed_subnets
+            .iter()
+            .all(|subnet_id| !disabled_subnets.contains(subnet_id)));
+    }
+
     #[tokio::test]
     async fn test_discovery_peers_count() {
         let subscription_slot = 10;
@@ -637,4 +689,20 @@ mod tests {
             panic!("Unexpected event {:?}", event);
         }
     }
+
+    #[tokio::test]
+    async fn random_subnet_duration_within_spec_bounds() {
+        let attestation_service = get_attestation_service();
+        let spec = &attestation_service.beacon_chain.spec;
+        let epoch_duration =
+            Duration::from_millis(SLOT_DURATION_MILLIS) * MinimalEthSpec::slots_per_epoch() as u32;
+        let min_duration = epoch_duration * spec.epochs_per_random_subnet_subscription as u32;
+        let max_duration = min_duration * 2;
+
+        for _ in 0..100 {
+            let duration = attestation_service.random_subnet_duration();
+            assert!(duration >= min_duration);
+            assert!(duration < max_duration);
+        }
+    }
 }
@@ -25,12 +25,18 @@
 //! - Indication that a worker has finished a parcel of work (worker idle).
 //!
 //! Then, there is a maximum of `n` "worker" blocking threads, where `n` is the CPU count.
+//! Unaggregated attestations are verified by a bounded pool of at most `m` of these workers,
+//! where `m < n` (when `n > 1`), so that there is always capacity to process blocks and
+//! aggregates, even when the network is flooded with unaggregated attestations.
 //!
 //! Whenever the manager receives a new parcel of work, it either:
 //!
 //! - Provided to a newly-spawned worker tasks (if we are not already at `n` workers).
 //! - Added to a queue.
 //!
+//! Unaggregated attestations are queued per-subnet and the subnet queues are drained in a
+//! round-robin fashion, so a burst of attestations on one subnet cannot starve the others.
+//!
 //! Whenever the manager receives a notification that a worker has finished a parcel of work, it
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
@@ -40,13 +46,14 @@ use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use environment::TaskExecutor;
 use eth2_libp2p::{MessageId, NetworkGlobals, PeerId};
 use slog::{crit, debug, error, trace, warn, Logger};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
 use types::{
     Attestation, AttesterSlashing, EthSpec, Hash256, ProposerSlashing, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
+    SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit, SubnetId,
+    SyncCommitteeMessage,
 };
 use worker::Worker;
 
@@ -66,14 +73,22 @@ pub const MAX_WORK_EVENT_QUEUE_LEN: usize = 16_384;
 /// set to the CPU count, but we set it high to be safe.
 const MAX_IDLE_QUEUE_LEN: usize = 16_384;
 
-/// The maximum number of queued `Attestation` objects that will be stored before we start dropping
-/// them.
-const MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN: usize = 16_384;
+/// The maximum number of queued `Attestation` objects that will be stored for each subnet before
+/// we start dropping them.
+const MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN_PER_SUBNET: usize = 1_024;
 
 /// The maximum number of queued `SignedAggregateAndProof` objects that will be stored before we
 /// start dropping them.
 const MAX_AGGREGATED_ATTESTATION_QUEUE_LEN: usize = 1_024;
 
+/// The maximum number of queued `SyncCommitteeMessage` objects that will be stored before we start
+/// dropping them.
+const MAX_SYNC_MESSAGE_QUEUE_LEN: usize = 2_048;
+
+/// The maximum number of queued `SignedContributionAndProof` objects that will be stored before we
+/// start dropping them.
+const MAX_SYNC_CONTRIBUTION_QUEUE_LEN: usize = 1_024;
+
 /// The maximum number of queued `SignedBeaconBlock` objects received on gossip that will be stored
 /// before we start dropping them.
 const MAX_GOSSIP_BLOCK_QUEUE_LEN: usize = 1_024;
@@ -106,6 +121,18 @@ const WORKER_TASK_NAME: &str = "beacon_gossip_processor_worker";
 /// The minimum interval between log messages indicating that a queue is full.
 const LOG_DEBOUNCE_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Unique IDs used for metrics and testing.
+pub const GOSSIP_ATTESTATION: &str = "gossip_attestation";
+pub const GOSSIP_AGGREGATE: &str = "gossip_aggregate";
+pub const GOSSIP_BLOCK: &str = "gossip_block";
+pub const GOSSIP_VOLUNTARY_EXIT: &str = "gossip_voluntary_exit";
+pub const GOSSIP_PROPOSER_SLASHING: &str = "gossip_proposer_slashing";
+pub const GOSSIP_ATTESTER_SLASHING: &str = "gossip_attester_slashing";
+pub const GOSSIP_SYNC_SIGNATURE: &str = "gossip_sync_signature";
+pub const GOSSIP_SYNC_CONTRIBUTION: &str = "gossip_sync_contribution";
+pub const RPC_BLOCK: &str = "rpc_block";
+pub const CHAIN_SEGMENT: &str = "chain_segment";
+
 /// Used to send/receive results from a rpc block import in a blocking task.
 pub type BlockResultSender<E> = oneshot::Sender<Result<Hash256, BlockError<E>>>;
 pub type BlockResultReceiver<E> = oneshot::Receiver<Result<Hash256, BlockError<E>>>;
@@ -194,6 +221,70 @@ impl<T> LifoQueue<T> {
     }
 }
 
+/// A set of `LifoQueue`s, one for each subnet, which are drained in a round-robin fashion.
+///
+/// Queues are created lazily, the first time an item is pushed for some subnet.
+struct SubnetQueues<T> {
+    queues: BTreeMap<u64, LifoQueue<T>>,
+    max_length_per_subnet: usize,
+    /// The subnet from which the next item should be popped (if it has any items).
+    next_subnet: u64,
+}
+
+impl<T> SubnetQueues<T> {
+    /// Create a new, empty set of queues where each queue has the given length.
+    pub fn new(max_length_per_subnet: usize) -> Self {
+        Self {
+            queues: BTreeMap::new(),
+            max_length_per_subnet,
+            next_subnet: 0,
+        }
+    }
+
+    /// Add a new item to the front of the queue for `subnet_id`.
+    ///
+    /// If that queue is full, the item at the back of it is dropped.
+    pub fn push(&mut self, subnet_id: u64, item: T) {
+        let max_length = self.max_length_per_subnet;
+        self.queues
+            .entry(subnet_id)
+            .or_insert_with(|| LifoQueue::new(max_length))
+            .push(item)
+    }
+
+    /// Remove the next item from the first non-empty queue at or after `self.next_subnet`,
+    /// wrapping around to the lowest subnet if required.
+    pub fn pop(&mut self) -> Option<T> {
+        let next_subnet = self.next_subnet;
+        let subnet_id = self
+            .queues
+            .range(next_subnet..)
+            .chain(self.queues.range(..next_subnet))
+            .find(|(_, queue)| queue.len() > 0)
+            .map(|(subnet_id, _)| *subnet_id)?;
+
+        self.next_subnet = subnet_id.saturating_add(1);
+        self.queues.get_mut(&subnet_id).and_then(LifoQueue::pop)
+    }
+
+    /// Returns the total number of items across all queues.
+    pub fn len(&self) -> usize {
+        self.queues.values().map(LifoQueue::len).sum()
+    }
+
+    /// Returns the number of subnets which have a full queue.
+    pub fn full_queues(&self) -> usize {
+        self.queues.values().filter(|queue| queue.is_full()).count()
+    }
+
+    /// Returns the subnet id and queue length of each queue.
+    pub fn queue_lengths(&self) -> impl Iterator<Item = (u64, usize)> + '_ {
+        self.queues
+            .iter()
+            .map(|(subnet_id, queue)| (*subnet_id, queue.len()))
+    }
+}
+
 /// An event to be processed by the manager task.
 #[derive(Debug)]
 pub struct WorkEvent<E: EthSpec> {
@@ -238,6 +329,40 @@ impl<E: EthSpec> WorkEvent<E> {
         }
     }
 
+    /// Create a new `Work` event for some sync committee message.
+    pub fn gossip_sync_signature(
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_signature: SyncCommitteeMessage,
+        subnet_id: u64,
+    ) -> Self {
+        Self {
+            drop_during_sync: true,
+            work: Work::GossipSyncSignature {
+                message_id,
+                peer_id,
+                sync_signature: Box::new(sync_signature),
+                subnet_id,
+            },
+        }
+    }
+
+    /// Create a new `Work` event for some sync committee contribution.
+    pub fn gossip_sync_contribution(
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_contribution: SignedContributionAndProof<E>,
+    ) -> Self {
+        Self {
+            drop_during_sync: true,
+            work: Work::GossipSyncContribution {
+                message_id,
+                peer_id,
+                sync_contribution: Box::new(sync_contribution),
+            },
+        }
+    }
+
     /// Create a new `Work` event for some block.
     pub fn gossip_beacon_block(
         message_id: MessageId,
@@ -337,6 +462,17 @@ pub enum Work<E: EthSpec> {
         peer_id: PeerId,
         aggregate: Box<SignedAggregateAndProof<E>>,
     },
+    GossipSyncSignature {
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_signature: Box<SyncCommitteeMessage>,
+        subnet_id: u64,
+    },
+    GossipSyncContribution {
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_contribution: Box<SignedContributionAndProof<E>>,
+    },
     GossipBlock {
         message_id: MessageId,
         peer_id: PeerId,
@@ -371,14 +507,16 @@ impl<E: EthSpec> Work<E> {
     /// Provides a `&str` that uniquely identifies each enum variant.
     fn str_id(&self) -> &'static str {
         match self {
-            Work::GossipAttestation { .. } => "gossip_attestation",
-            Work::GossipAggregate { .. } => "gossip_aggregate",
-            Work::GossipBlock { .. } => "gossip_block",
-            Work::GossipVoluntaryExit { .. } => "gossip_voluntary_exit",
-            Work::GossipProposerSlashing { .. } => "gossip_proposer_slashing",
-            Work::GossipAttesterSlashing { .. } => "gossip_attester_slashing",
-            Work::RpcBlock { .. } => "rpc_block",
-            Work::ChainSegment { .. } => "chain_segment",
+            Work::GossipAttestation { .. } => GOSSIP_ATTESTATION,
+            Work::GossipAggregate { .. } => GOSSIP_AGGREGATE,
+            Work::GossipSyncSignature { .. } => GOSSIP_SYNC_SIGNATURE,
+            Work::GossipSyncContribution { .. } => GOSSIP_SYNC_CONTRIBUTION,
+            Work::GossipBlock { .. } => GOSSIP_BLOCK,
+            Work::GossipVoluntaryExit { .. } => GOSSIP_VOLUNTARY_EXIT,
+            Work::GossipProposerSlashing { .. } => GOSSIP_PROPOSER_SLASHING,
+            Work::GossipAttesterSlashing { .. } => GOSSIP_ATTESTER_SLASHING,
+            Work::RpcBlock { .. } => RPC_BLOCK,
+            Work::ChainSegment { .. } => CHAIN_SEGMENT,
         }
     }
 }
@@ -414,6 +552,11 @@ pub struct BeaconProcessor<T: BeaconChainTypes> {
     pub executor: TaskExecutor,
     pub max_workers: usize,
     pub current_workers: usize,
+    /// The maximum number of workers that may be verifying unaggregated attestations at once.
+    ///
+    /// Should be less than `max_workers` so that attestations cannot starve other work.
+    pub max_attestation_workers: usize,
+    pub current_attestation_workers: usize,
     pub log: Logger,
 }
 
@@ -424,19 +567,27 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
     /// - Performed immediately, if a worker is available.
     /// - Queued for later processing, if no worker is currently available.
     ///
-    /// Only `self.max_workers` will ever be spawned at one time. Each worker is a `tokio` task
-    /// started with `spawn_blocking`.
+    /// Only `self.max_workers` will ever be spawned at one time, of which at most
+    /// `self.max_attestation_workers` will be processing unaggregated attestations. Each worker is
+    /// a `tokio` task started with `spawn_blocking`.
     pub fn spawn_manager(mut self, mut event_rx: mpsc::Receiver<WorkEvent<T::EthSpec>>) {
-        let (idle_tx, mut idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
+        // Idle events contain the `str_id` of the work that the worker was performing.
+        let (idle_tx, mut idle_rx) = mpsc::channel::<&'static str>(MAX_IDLE_QUEUE_LEN);
 
         // Using LIFO queues for attestations since validator profits rely upon getting fresh
         // attestations into blocks. Additionally, later attestations contain more information than
         // earlier ones, so we consider them more valuable.
         let mut aggregate_queue = LifoQueue::new(MAX_AGGREGATED_ATTESTATION_QUEUE_LEN);
         let mut aggregate_debounce = TimeLatch::default();
-        let mut attestation_queue = LifoQueue::new(MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN);
+        let mut attestation_queues =
+            SubnetQueues::new(MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN_PER_SUBNET);
         let mut attestation_debounce = TimeLatch::default();
 
+        // Sync committee messages are only useful for the current slot, so the freshest are
+        // preferred for the same reasons as attestations.
+        let mut sync_contribution_queue = LifoQueue::new(MAX_SYNC_CONTRIBUTION_QUEUE_LEN);
+        let mut sync_message_queue = LifoQueue::new(MAX_SYNC_MESSAGE_QUEUE_LEN);
+
         // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't have
         // a strong feeling about queue type for exits.
         let mut gossip_voluntary_exit_queue = FifoQueue::new(MAX_GOSSIP_EXIT_QUEUE_LEN);
@@ -467,8 +618,12 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 let work_event = tokio::select! {
                     // A worker has finished some work.
                     new_idle_opt = idle_rx.recv() => {
-                        if new_idle_opt.is_some() {
+                        if let Some(work_id) = new_idle_opt {
                             self.current_workers = self.current_workers.saturating_sub(1);
+                            if work_id == GOSSIP_ATTESTATION {
+                                self.current_attestation_workers =
+                                    self.current_attestation_workers.saturating_sub(1);
+                            }
                             None
                         } else {
                             // Exit if all idle senders have been dropped.
@@ -512,6 +667,8 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 }
 
                 let can_spawn = self.current_workers < self.max_workers;
+                let can_spawn_attestation =
+                    can_spawn && self.current_attestation_workers < self.max_attestation_workers;
                 let drop_during_sync = work_event
                     .as_ref()
                     .map_or(false, |event| event.drop_during_sync);
@@ -540,7 +697,20 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         // verification time.
                         } else if let Some(item) = aggregate_queue.pop() {
                             self.spawn_worker(idle_tx.clone(), item);
-                        } else if let Some(item) = attestation_queue.pop() {
+                        // Only verify unaggregated attestations if there is room in the
+                        // attestation worker pool. Otherwise, they'll be picked up when an
+                        // attestation worker becomes idle.
+                        } else if let Some(item) = if can_spawn_attestation {
+                            attestation_queues.pop()
+                        } else {
+                            None
+                        } {
+                            self.spawn_worker(idle_tx.clone(), item);
+                        // Check sync committee contributions before the messages, for the same
+                        // reason as aggregates.
+                        } else if let Some(item) = sync_contribution_queue.pop() {
+                            self.spawn_worker(idle_tx.clone(), item);
+                        } else if let Some(item) = sync_message_queue.pop() {
                             self.spawn_worker(idle_tx.clone(), item);
                         // Check slashings after all other consensus messages so we prioritize
                         // following head.
@@ -587,9 +757,18 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     Some(WorkEvent { work, .. }) => {
                         let work_id = work.str_id();
                         match work {
+                            Work::GossipAttestation { .. } if can_spawn_attestation => {
+                                self.spawn_worker(idle_tx.clone(), work)
+                            }
+                            Work::GossipAttestation { subnet_id, .. } => {
+                                attestation_queues.push(*subnet_id, work)
+                            }
                             _ if can_spawn => self.spawn_worker(idle_tx.clone(), work),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
                             Work::GossipAggregate { .. } => aggregate_queue.push(work),
+                            Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
+                            Work::GossipSyncContribution { .. } => {
+                                sync_contribution_queue.push(work)
+                            }
                             Work::GossipBlock { .. } => {
                                 gossip_block_queue.push(work, work_id, &self.log)
                             }
@@ -614,14 +793,33 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     &metrics::BEACON_PROCESSOR_WORKERS_ACTIVE_TOTAL,
                     self.current_workers as i64,
                 );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_ATTESTATION_WORKERS_ACTIVE_TOTAL,
+                    self.current_attestation_workers as i64,
+                );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_QUEUE_TOTAL,
-                    attestation_queue.len() as i64,
+                    attestation_queues.len() as i64,
                 );
+                for (subnet_id, len) in attestation_queues.queue_lengths() {
+                    metrics::set_int_gauge(
+                        &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_SUBNET_QUEUE_TOTAL,
+                        &[&subnet_id.to_string()],
+                        len as i64,
+                    );
+                }
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_QUEUE_TOTAL,
                     aggregate_queue.len() as i64,
                 );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_SYNC_MESSAGE_QUEUE_TOTAL,
+                    sync_message_queue.len() as i64,
+                );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_QUEUE_TOTAL,
+                    sync_contribution_queue.len() as i64,
+                );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_QUEUE_TOTAL,
                     gossip_block_queue.len() as i64,
@@ -656,12 +854,14 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     )
                 }
 
-                if attestation_queue.is_full() && attestation_debounce.elapsed() {
+                let full_attestation_queues = attestation_queues.full_queues();
+                if full_attestation_queues > 0 && attestation_debounce.elapsed() {
                     error!(
                         self.log,
                         "Attestation queue full";
                         "msg" => "the system has insufficient resources for load",
-                        "queue_len" => attestation_queue.max_length,
+                        "queue_len" => attestation_queues.max_length_per_subnet,
+                        "full_subnets" => full_attestation_queues,
                     )
                 }
             }
@@ -674,7 +874,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
     /// Spawns a blocking worker thread to process some `Work`.
     ///
     /// Sends an message on `idle_tx` when the work is complete and the task is stopping.
-    fn spawn_worker(&mut self, mut idle_tx: mpsc::Sender<()>, work: Work<T::EthSpec>) {
+    fn spawn_worker(&mut self, mut idle_tx: mpsc::Sender<&'static str>, work: Work<T::EthSpec>) {
         let work_id = work.str_id();
         let worker_timer =
             metrics::start_timer_vec(&metrics::BEACON_PROCESSOR_WORKER_TIME, &[work_id]);
@@ -686,6 +886,9 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
 
         let worker_id = self.current_workers;
         self.current_workers = self.current_workers.saturating_add(1);
+        if work_id == GOSSIP_ATTESTATION {
+            self.current_attestation_workers = self.current_attestation_workers.saturating_add(1);
+        }
 
         let chain = if let Some(chain) = self.beacon_chain.upgrade() {
             chain
@@ -743,6 +946,32 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         peer_id,
                         aggregate,
                     } => worker.process_gossip_aggregate(message_id, peer_id, *aggregate),
+                    /*
+                     * Sync committee message verification.
+                     */
+                    Work::GossipSyncSignature {
+                        message_id,
+                        peer_id,
+                        sync_signature,
+                        subnet_id,
+                    } => worker.process_gossip_sync_committee_signature(
+                        message_id,
+                        peer_id,
+                        *sync_signature,
+                        subnet_id,
+                    ),
+                    /*
+                     * Sync committee contribution verification.
+                     */
+                    Work::GossipSyncContribution {
+                        message_id,
+                        peer_id,
+                        sync_contribution,
+                    } => worker.process_sync_committee_contribution(
+                        message_id,
+                        peer_id,
+                        *sync_contribution,
+                    ),
                     /*
                      * Verification for beacon blocks received on gossip.
                      */
@@ -804,7 +1033,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     "worker" => worker_id,
                 );
 
-                idle_tx.try_send(()).unwrap_or_else(|e| {
+                idle_tx.try_send(work_id).unwrap_or_else(|e| {
                     crit!(
                         log,
                         "Unable to free worker";
@@ -817,3 +1046,29 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subnet_queues_round_robin() {
+        let mut queues = SubnetQueues::new(2);
+
+        queues.push(3, "3a");
+        queues.push(3, "3b");
+        queues.push(3, "3c");
+        queues.push(1, "1a");
+        queues.push(5, "5a");
+
+        assert_eq!(queues.len(), 4, "oldest item on subnet 3 should be dropped");
+        assert_eq!(queues.full_queues(), 1);
+
+        assert_eq!(queues.pop(), Some("1a"));
+        assert_eq!(queues.pop(), Some("3c"));
+        assert_eq!(queues.pop(), Some("5a"));
+        assert_eq!(queues.pop(), Some("3b"));
+        assert_eq!(queues.pop(), None);
+        assert_eq!(queues.len(), 0);
+    }
+}
@@ -6,6 +6,7 @@ use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
 use beacon_chain::{
     attestation_verification::Error as AttnError, observed_operations::ObservationOutcome,
     BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
+    SyncCommitteeError,
 };
 use eth2_libp2p::{MessageAcceptance, MessageId, PeerId};
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -14,7 +15,8 @@ use std::sync::Arc;
 use tokio::sync::mpsc;
 use types::{
     Attestation, AttesterSlashing, Hash256, ProposerSlashing, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
+    SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit, Slot, SubnetId,
+    SyncCommitteeMessage,
 };
 
 /// Contains the context necessary to import blocks, attestations, etc to the beacon chain.
@@ -177,6 +179,109 @@ impl<T: BeaconChainTypes> Worker<T> {
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_IMPORTED_TOTAL);
     }
 
+    /// Process the sync committee message received from the gossip network and:
+    ///
+    /// - If it passes gossip propagation criteria, tell the network thread to forward it.
+    /// - Attempt to add it to the sync contribution pool.
+    ///
+    /// Raises a log if there are errors.
+    pub fn process_gossip_sync_committee_signature(
+        self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_signature: SyncCommitteeMessage,
+        subnet_id: u64,
+    ) {
+        let message_slot = sync_signature.slot;
+
+        let sync_signature = match self
+            .chain
+            .verify_sync_committee_message_for_gossip(sync_signature, subnet_id)
+        {
+            Ok(sync_signature) => sync_signature,
+            Err(e) => {
+                self.handle_sync_committee_message_failure(
+                    peer_id,
+                    message_id,
+                    message_slot,
+                    "sync_signature",
+                    e,
+                );
+                return;
+            }
+        };
+
+        // Indicate to the `Network` service that this message is valid and can be
+        // propagated on the gossip network.
+        self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_MESSAGE_VERIFIED_TOTAL);
+
+        if let Err(e) = sync_signature.add_to_pool(&self.chain) {
+            debug!(
+                self.log,
+                "Sync committee signature invalid for agg pool";
+                "reason" => format!("{:?}", e),
+                "peer" => peer_id.to_string(),
+                "slot" => message_slot,
+            );
+            return;
+        }
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_MESSAGE_IMPORTED_TOTAL);
+    }
+
+    /// Process the sync committee contribution received from the gossip network and:
+    ///
+    /// - If it passes gossip propagation criteria, tell the network thread to forward it.
+    /// - Attempt to add it to the sync contribution pool, for inclusion in a block.
+    ///
+    /// Raises a log if there are errors.
+    pub fn process_sync_committee_contribution(
+        self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_contribution: SignedContributionAndProof<T::EthSpec>,
+    ) {
+        let contribution_slot = sync_contribution.message.contribution.slot;
+
+        let sync_contribution = match self
+            .chain
+            .verify_sync_contribution_for_gossip(sync_contribution)
+        {
+            Ok(sync_contribution) => sync_contribution,
+            Err(e) => {
+                self.handle_sync_committee_message_failure(
+                    peer_id,
+                    message_id,
+                    contribution_slot,
+                    "sync_contribution",
+                    e,
+                );
+                return;
+            }
+        };
+
+        // Indicate to the `Network` service that this message is valid and can be
+        // propagated on the gossip network.
+        self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_VERIFIED_TOTAL);
+
+        if let Err(e) = sync_contribution.add_to_pool(&self.chain) {
+            debug!(
+                self.log,
+                "Sync contribution invalid for op pool";
+                "reason" => format!("{:?}", e),
+                "peer" => peer_id.to_string(),
+                "slot" => contribution_slot,
+            );
+            return;
+        }
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_IMPORTED_TOTAL);
+    }
+
     /// Process the beacon block received from the gossip network and:
     ///
     /// - If it passes gossip propagation criteria, tell the network thread to forward it.
@@ -816,6 +921,30 @@ impl<T: BeaconChainTypes> Worker<T> {
                     MessageAcceptance::Reject,
                 );
             }
+            AttnError::TooManyTargetSkippedSlots {
+                target_block_slot,
+                attestation_slot,
+            } => {
+                /*
+                 * The attestation references a target block that is too far behind the
+                 * attestation slot.
+                 *
+                 * The message is not necessarily invalid, but we choose to ignore it. Unlike a
+                 * long skip to the head block, an honest peer on a chain with a long run of empty
+                 * slots will publish these, so the peer is not penalized.
+                 */
+                debug!(
+                    self.log,
+                    "Ignored long skip slot attestation target";
+                    "target_block_slot" => target_block_slot,
+                    "attestation_slot" => attestation_slot,
+                );
+                self.propagate_validation_result(
+                    message_id,
+                    peer_id.clone(),
+                    MessageAcceptance::Ignore,
+                );
+            }
             AttnError::BeaconChainError(e) => {
                 /*
                  * Lighthouse hit an unexpected error whilst processing the attestation. It
@@ -847,4 +976,149 @@ impl<T: BeaconChainTypes> Worker<T> {
             "type" => format!("{:?}", attestation_type),
         );
     }
+
+    /// Handle an error whilst verifying a `SyncCommitteeMessage` or `SignedContributionAndProof`
+    /// from the network.
+    pub fn handle_sync_committee_message_failure(
+        &self,
+        peer_id: PeerId,
+        message_id: MessageId,
+        message_slot: Slot,
+        message_type: &str,
+        error: SyncCommitteeError,
+    ) {
+        match &error {
+            SyncCommitteeError::FutureSlot { .. } | SyncCommitteeError::PastSlot { .. } => {
+                /*
+                 * These errors can be triggered by a mismatch between our slot and the peer.
+                 *
+                 * The message may be valid for a peer whose clock differs from ours, so it is
+                 * ignored rather than penalized.
+                 */
+                self.propagate_validation_result(
+                    message_id,
+                    peer_id.clone(),
+                    MessageAcceptance::Ignore,
+                );
+            }
+            SyncCommitteeError::PreAltairSlot(_)
+            | SyncCommitteeError::InvalidSubcommittee { .. }
+            | SyncCommitteeError::EmptyAggregationBitfield
+            | SyncCommitteeError::InvalidSelectionProof { .. }
+            | SyncCommitteeError::AggregatorNotInCommittee { .. }
+            | SyncCommitteeError::ValidatorNotInSubcommittee { .. }
+            | SyncCommitteeError::UnknownValidatorIndex(_)
+            | SyncCommitteeError::ValidatorIndexTooHigh(_)
+            | SyncCommitteeError::InvalidSignature => {
+                /*
+                 * The message is malformed, was published on the wrong subnet or carries an
+                 * invalid signature.
+                 *
+                 * The peer has published an invalid consensus message.
+                 */
+                self.propagate_validation_result(
+                    message_id,
+                    peer_id.clone(),
+                    MessageAcceptance::Reject,
+                );
+            }
+            SyncCommitteeError::AggregatorAlreadyKnown(_)
+            | SyncCommitteeError::PriorSyncCommitteeMessageKnown { .. } => {
+                /*
+                 * We have already seen a message from this validator for this slot and
+                 * subcommittee.
+                 *
+                 * The peer is not necessarily faulty.
+                 */
+                trace!(
+                    self.log,
+                    "Sync committee message already known";
+                    "peer_id" => peer_id.to_string(),
+                    "slot" => message_slot,
+                    "type" => message_type,
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return;
+            }
+            SyncCommitteeError::BeaconChainError(e) => {
+                /*
+                 * Lighthouse hit an unexpected error whilst processing the message. It should be
+                 * impossible to trigger a `BeaconChainError` from the network, so we have a bug.
+                 *
+                 * It's not clear if the message is invalid/malicious.
+                 */
+                error!(
+                    self.log,
+                    "Unable to validate sync committee message";
+                    "peer_id" => peer_id.to_string(),
+                    "error" => format!("{:?}", e),
+                );
+                self.propagate_validation_result(
+                    message_id,
+                    peer_id.clone(),
+                    MessageAcceptance::Ignore,
+                );
+            }
+        }
+
+        debug!(
+            self.log,
+            "Invalid sync committee message from network";
+            "reason" => format!("{:?}", error),
+            "peer_id" => peer_id.to_string(),
+            "slot" => message_slot,
+            "type" => message_type,
+        );
+    }
+}
+
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::BeaconChainHarness;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+
+    #[test]
+    fn too_many_target_skipped_slots_is_ignored() {
+        let chain = Arc::new(
+            BeaconChainHarness::new_with_store_config(
+                MinimalEthSpec,
+                generate_deterministic_keypairs(8),
+                StoreConfig::default(),
+            )
+            .chain,
+        );
+        let (network_tx, mut network_rx) = mpsc::unbounded_channel();
+        let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
+        let worker = Worker {
+            chain,
+            network_tx,
+            sync_tx,
+            log: NullLoggerBuilder.build().expect("should build logger"),
+        };
+
+        worker.handle_attestation_verification_failure(
+            PeerId::random(),
+            MessageId::from(&[42][..]),
+            Hash256::zero(),
+            "unaggregated",
+            AttnError::TooManyTargetSkippedSlots {
+                target_block_slot: Slot::new(0),
+                attestation_slot: Slot::new(1_000),
+            },
+        );
+
+        match network_rx.try_recv() {
+            Ok(NetworkMessage::ValidationResult {
+                validation_result, ..
+            }) => assert!(
+                matches!(validation_result, MessageAcceptance::Ignore),
+                "the attestation should be ignored, not rejected"
+            ),
+            _ => panic!("should send a validation result"),
+        }
+    }
 }
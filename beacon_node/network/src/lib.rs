@@ -3,12 +3,15 @@ extern crate lazy_static;
 
 /// This crate provides the network server for Lighthouse.
 pub mod error;
+pub mod persisted_bans;
 pub mod service;
 
 mod attestation_service;
 mod beacon_processor;
 mod metrics;
+mod nat;
 mod persisted_dht;
+mod persisted_peers;
 mod router;
 mod sync;
 
@@ -48,6 +48,12 @@ lazy_static! {
         &["subnet"]
     );
 
+    pub static ref GOSSIPSUB_PEERS_BELOW_SCORE_THRESHOLD: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "gossipsub_peers_below_score_threshold",
+        "The number of peers with a gossipsub score below each threshold",
+        &["threshold"]
+    );
+
     pub static ref ATTESTATIONS_PUBLISHED_PER_SUBNET_PER_SLOT: Result<IntCounterVec> = try_create_int_counter_vec(
         "gossipsub_attestations_published_per_subnet_per_slot",
         "Failed attestation publishes per subnet",
@@ -73,6 +79,14 @@ lazy_static! {
     );
 
 
+    /*
+     * RPC responses
+     */
+    pub static ref BLOCKS_BY_RANGE_TRUNCATED_RESPONSES: Result<IntCounter> = try_create_int_counter(
+        "network_blocks_by_range_truncated_responses_total",
+        "Count of BlocksByRange responses cut short by the per-request byte or time budget"
+    );
+
     /*
      * Gossip Tx
      */
@@ -132,6 +146,10 @@ lazy_static! {
         "beacon_processor_workers_active_total",
         "Count of active workers in the gossip processing pool."
     );
+    pub static ref BEACON_PROCESSOR_ATTESTATION_WORKERS_ACTIVE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_attestation_workers_active_total",
+        "Count of active workers verifying unaggregated attestations."
+    );
     pub static ref BEACON_PROCESSOR_IDLE_EVENTS_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_idle_events_total",
         "Count of idle events processed by the gossip processor manager."
@@ -223,6 +241,11 @@ lazy_static! {
         "beacon_processor_unaggregated_attestation_queue_total",
         "Count of unagg. attestations waiting to be processed."
     );
+    pub static ref BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_SUBNET_QUEUE_TOTAL: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_processor_unaggregated_attestation_subnet_queue_total",
+        "Count of unagg. attestations waiting to be processed, per subnet.",
+        &["subnet"]
+    );
     pub static ref BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_unaggregated_attestation_verified_total",
         "Total number of unaggregated attestations verified for gossip."
@@ -244,6 +267,32 @@ lazy_static! {
         "beacon_processor_aggregated_attestation_imported_total",
         "Total number of aggregated attestations imported to fork choice, etc."
     );
+    // Sync committee messages.
+    pub static ref BEACON_PROCESSOR_SYNC_MESSAGE_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_sync_message_queue_total",
+        "Count of sync committee messages waiting to be processed."
+    );
+    pub static ref BEACON_PROCESSOR_SYNC_MESSAGE_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_sync_message_verified_total",
+        "Total number of sync committee messages verified for gossip."
+    );
+    pub static ref BEACON_PROCESSOR_SYNC_MESSAGE_IMPORTED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_sync_message_imported_total",
+        "Total number of sync committee messages imported to the sync contribution pool."
+    );
+    // Sync committee contributions.
+    pub static ref BEACON_PROCESSOR_SYNC_CONTRIBUTION_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_sync_contribution_queue_total",
+        "Count of sync committee contributions waiting to be processed."
+    );
+    pub static ref BEACON_PROCESSOR_SYNC_CONTRIBUTION_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_sync_contribution_verified_total",
+        "Total number of sync committee contributions verified for gossip."
+    );
+    pub static ref BEACON_PROCESSOR_SYNC_CONTRIBUTION_IMPORTED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_sync_contribution_imported_total",
+        "Total number of sync committee contributions imported to the sync contribution pool."
+    );
 
     /*
      * Attestation Errors
@@ -393,7 +442,7 @@ pub fn register_attestation_error(error: &AttnError) {
             inc_counter(&GOSSIP_ATTESTATION_ERROR_INVALID_SUBNET_ID)
         }
         AttnError::Invalid(_) => inc_counter(&GOSSIP_ATTESTATION_ERROR_INVALID_STATE_PROCESSING),
-        AttnError::TooManySkippedSlots { .. } => {
+        AttnError::TooManySkippedSlots { .. } | AttnError::TooManyTargetSkippedSlots { .. } => {
             inc_counter(&GOSSIP_ATTESTATION_ERROR_INVALID_TOO_MANY_SKIPPED_SLOTS)
         }
         AttnError::BeaconChainError(_) => inc_counter(&GOSSIP_ATTESTATION_ERROR_BEACON_CHAIN_ERROR),
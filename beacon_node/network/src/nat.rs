@@ -0,0 +1,205 @@
+//! This houses various NAT hole punching strategies.
+//!
+//! Currently supported strategies:
+//! - UPnP
+
+use crate::{NetworkConfig, NetworkMessage};
+use if_addrs::{get_if_addrs, IfAddr};
+use slog::{debug, info, warn};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use tokio::sync::mpsc;
+use types::EthSpec;
+
+/// The description given to the port mappings on the gateway.
+const MAPPING_DESCRIPTION: &str = "lighthouse";
+
+/// Configuration required to construct the UPnP port mappings.
+pub struct UPnPConfig {
+    /// The local tcp port.
+    tcp_port: u16,
+    /// The local udp port.
+    udp_port: u16,
+    /// Whether discovery is enabled or not.
+    disable_discovery: bool,
+}
+
+impl From<&NetworkConfig> for UPnPConfig {
+    fn from(config: &NetworkConfig) -> Self {
+        UPnPConfig {
+            tcp_port: config.libp2p_port,
+            udp_port: config.discovery_port,
+            disable_discovery: config.disable_discovery,
+        }
+    }
+}
+
+/// Attempts to construct external port mappings with UPnP.
+///
+/// This blocks whilst searching for a gateway and should be run on a blocking thread. The outcome
+/// is reported to the network service via `NetworkMessage::UPnPMappingEstablished` or
+/// `NetworkMessage::UPnPMappingFailed`.
+pub fn construct_upnp_mappings<T: EthSpec>(
+    config: UPnPConfig,
+    network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
+    log: slog::Logger,
+) {
+    info!(log, "UPnP attempting to initialise routes");
+
+    let message = match try_construct_mappings(&config, &log) {
+        Ok((tcp_socket, udp_socket)) => NetworkMessage::UPnPMappingEstablished {
+            tcp_socket,
+            udp_socket,
+        },
+        Err(reason) => {
+            info!(log, "UPnP not available"; "reason" => &reason);
+            NetworkMessage::UPnPMappingFailed { reason }
+        }
+    };
+
+    // report the outcome to the network service.
+    if let Err(e) = network_send.send(message) {
+        debug!(log, "Could not send UPnP result to the network service"; "error" => e.to_string());
+    }
+}
+
+/// Searches for a gateway and maps the TCP (and, if discovery is enabled, the UDP) port.
+///
+/// Returns the external sockets that were successfully mapped, or an error if no mapping could be
+/// established.
+fn try_construct_mappings(
+    config: &UPnPConfig,
+    log: &slog::Logger,
+) -> Result<(Option<SocketAddr>, Option<SocketAddr>), String> {
+    let gateway =
+        igd::search_gateway(Default::default()).map_err(|e| format!("No gateway found: {}", e))?;
+
+    let local_ip = local_ipv4_for_gateway(*gateway.addr.ip())
+        .ok_or_else(|| "No local IPv4 address on the gateway's subnet".to_string())?;
+
+    let external_ip = match gateway.get_external_ip() {
+        Ok(ip) => Some(ip),
+        Err(e) => {
+            debug!(log, "UPnP could not determine the external IP"; "error" => e.to_string());
+            None
+        }
+    };
+
+    // We add specific port mappings rather than letting the gateway assign arbitrary ones. If
+    // multiple nodes are behind a single gateway, they should be configured with different ports.
+    let tcp_socket = add_port_mapping(
+        &gateway,
+        igd::PortMappingProtocol::TCP,
+        SocketAddrV4::new(local_ip, config.tcp_port),
+        log,
+    )
+    .ok()
+    .map(|_| external_socket(external_ip, local_ip, config.tcp_port));
+
+    let udp_socket = if !config.disable_discovery {
+        add_port_mapping(
+            &gateway,
+            igd::PortMappingProtocol::UDP,
+            SocketAddrV4::new(local_ip, config.udp_port),
+            log,
+        )
+        .ok()
+        .map(|_| external_socket(external_ip, local_ip, config.udp_port))
+    } else {
+        None
+    };
+
+    if tcp_socket.is_none() && udp_socket.is_none() {
+        return Err("The gateway refused all port mappings".into());
+    }
+
+    if let Some(socket) = tcp_socket {
+        info!(log, "UPnP TCP route established"; "external_socket" => socket.to_string());
+    }
+    if let Some(socket) = udp_socket {
+        info!(log, "UPnP UDP route established"; "external_socket" => socket.to_string());
+    }
+
+    Ok((tcp_socket, udp_socket))
+}
+
+/// Adds a port mapping for `protocol` from the external port to the same port on `local_socket`.
+fn add_port_mapping(
+    gateway: &igd::Gateway,
+    protocol: igd::PortMappingProtocol,
+    local_socket: SocketAddrV4,
+    log: &slog::Logger,
+) -> Result<(), ()> {
+    // Some gateways only accept indefinite leases, so no lease duration is requested. The
+    // mappings are removed when the network service shuts down.
+    gateway
+        .add_port(
+            protocol,
+            local_socket.port(),
+            local_socket,
+            0,
+            MAPPING_DESCRIPTION,
+        )
+        .map_err(|e| {
+            warn!(
+                log,
+                "UPnP could not construct port mapping";
+                "protocol" => format!("{:?}", protocol),
+                "port" => local_socket.port(),
+                "error" => e.to_string()
+            )
+        })
+}
+
+/// Returns the external socket for a mapped port, falling back to the local address if the
+/// gateway did not report its external IP.
+fn external_socket(external_ip: Option<Ipv4Addr>, local_ip: Ipv4Addr, port: u16) -> SocketAddr {
+    SocketAddr::new(external_ip.unwrap_or(local_ip).into(), port)
+}
+
+/// Finds the local IPv4 address of the interface which shares a subnet with the gateway.
+fn local_ipv4_for_gateway(gateway_ip: Ipv4Addr) -> Option<Ipv4Addr> {
+    get_if_addrs()
+        .ok()?
+        .into_iter()
+        .filter(|interface| !interface.is_loopback())
+        .filter_map(|interface| match interface.addr {
+            IfAddr::V4(addr) => Some(addr),
+            IfAddr::V6(_) => None,
+        })
+        .find(|addr| {
+            let mask = u32::from(addr.netmask);
+            u32::from(addr.ip) & mask == u32::from(gateway_ip) & mask
+        })
+        .map(|addr| addr.ip)
+}
+
+/// Removes the specified TCP and UDP port mappings from the gateway.
+pub fn remove_mappings(tcp_port: Option<u16>, udp_port: Option<u16>, log: &slog::Logger) {
+    if tcp_port.is_none() && udp_port.is_none() {
+        return;
+    }
+
+    let gateway = match igd::search_gateway(Default::default()) {
+        Ok(gateway) => gateway,
+        Err(e) => {
+            debug!(log, "UPnP gateway not found. Unable to remove mappings"; "error" => e.to_string());
+            return;
+        }
+    };
+
+    let mappings = tcp_port
+        .map(|port| (igd::PortMappingProtocol::TCP, port))
+        .into_iter()
+        .chain(udp_port.map(|port| (igd::PortMappingProtocol::UDP, port)));
+
+    for (protocol, port) in mappings {
+        match gateway.remove_port(protocol, port) {
+            Ok(()) => {
+                debug!(log, "UPnP removed port mapping"; "protocol" => format!("{:?}", protocol), "port" => port)
+            }
+            Err(e) => {
+                debug!(log, "UPnP failed to remove port mapping"; "protocol" => format!("{:?}", protocol), "port" => port, "error" => e.to_string())
+            }
+        }
+    }
+}
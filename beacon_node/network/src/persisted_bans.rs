@@ -0,0 +1,133 @@
+//! Persistence of the peers and IP addresses which have been banned by the user.
+//!
+//! Unlike bans resulting from a peer's score, these bans never expire and are restored when the
+//! beacon node restarts.
+
+use eth2_libp2p::{PeerDB, PeerId};
+use rlp::RlpStream;
+use std::net::IpAddr;
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedBans`.
+pub const BANS_DB_KEY: &str = "PERSISTEDBANSPERSISTEDBANSPERSIS";
+
+/// Load the user's ban list from the store, returning an empty list if none exists.
+pub fn load_bans<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Result<PersistedBans, store::Error> {
+    let key = Hash256::from_slice(&BANS_DB_KEY.as_bytes());
+    Ok(store.get_item(&key)?.unwrap_or_default())
+}
+
+/// Attempt to persist the user's ban list to `store`.
+pub fn persist_bans<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    bans: &PersistedBans,
+) -> Result<(), store::Error> {
+    let key = Hash256::from_slice(&BANS_DB_KEY.as_bytes());
+    store.put_item(&key, bans)?;
+    Ok(())
+}
+
+/// The peers and IP addresses banned by the user.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PersistedBans {
+    pub peers: Vec<PeerId>,
+    pub ips: Vec<IpAddr>,
+}
+
+impl PersistedBans {
+    /// Collects the user's ban list from the `PeerDB`.
+    pub fn from_peer_db<T: EthSpec>(peer_db: &PeerDB<T>) -> Self {
+        let mut bans = PersistedBans {
+            peers: peer_db.manually_banned_peers().cloned().collect(),
+            ips: peer_db.manually_banned_ips().cloned().collect(),
+        };
+        // keep the ordering stable between restarts
+        bans.peers.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        bans.ips.sort();
+        bans
+    }
+}
+
+impl StoreItem for PersistedBans {
+    fn db_column() -> DBColumn {
+        DBColumn::BanList
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        let peers = self
+            .peers
+            .iter()
+            .map(|peer_id| peer_id.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let ips = self.ips.iter().map(|ip| ip.to_string()).collect::<Vec<_>>();
+
+        let mut stream = RlpStream::new_list(2);
+        stream.append_list::<Vec<u8>, _>(&peers);
+        stream.append_list::<String, _>(&ips);
+        stream.out()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let rlp = rlp::Rlp::new(bytes);
+        let peers: Vec<Vec<u8>> = rlp
+            .list_at(0)
+            .map_err(|e| StoreError::RlpError(format!("{}", e)))?;
+        let ips: Vec<String> = rlp
+            .list_at(1)
+            .map_err(|e| StoreError::RlpError(format!("{}", e)))?;
+
+        Ok(PersistedBans {
+            peers: peers
+                .into_iter()
+                .map(|bytes| {
+                    PeerId::from_bytes(bytes)
+                        .map_err(|_| StoreError::RlpError("Invalid banned PeerId".into()))
+                })
+                .collect::<Result<_, _>>()?,
+            ips: ips
+                .into_iter()
+                .map(|ip| {
+                    ip.parse()
+                        .map_err(|_| StoreError::RlpError(format!("Invalid banned IP: {}", ip)))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_bans() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+
+        assert_eq!(load_bans(store.clone()).unwrap(), PersistedBans::default());
+
+        let bans = PersistedBans {
+            peers: vec![PeerId::random(), PeerId::random()],
+            ips: vec![
+                Ipv4Addr::new(10, 0, 0, 1).into(),
+                Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1).into(),
+            ],
+        };
+        persist_bans(store.clone(), &bans).unwrap();
+        assert_eq!(load_bans(store).unwrap(), bans);
+    }
+}
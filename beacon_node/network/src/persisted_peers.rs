@@ -0,0 +1,170 @@
+use eth2_libp2p::Enr;
+use rlp::{DecoderError, Rlp, RlpStream};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedPeers`.
+pub const KNOWN_PEERS_DB_KEY: &str = "PERSISTEDPEERSPERSISTEDPEERSPERS";
+
+/// The maximum number of peers that are persisted.
+const MAX_PERSISTED_PEERS: usize = 200;
+
+/// Peers which have not been seen for this many seconds are not persisted or dialed.
+const MAX_PEER_AGE: u64 = 60 * 60 * 24 * 7;
+
+/// Load the known good peers from the store, ordered from the highest score to the lowest.
+///
+/// Peers which have not been seen for `MAX_PEER_AGE` are ignored.
+pub fn load_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Vec<PersistedPeer> {
+    let key = Hash256::from_slice(&KNOWN_PEERS_DB_KEY.as_bytes());
+    let mut peers = match store.get_item(&key) {
+        Ok(Some(p)) => {
+            let p: PersistedPeers = p;
+            p.peers
+        }
+        _ => Vec::new(),
+    };
+
+    let now = unix_time_now();
+    peers.retain(|peer| now.saturating_sub(peer.last_seen) <= MAX_PEER_AGE);
+    sort_by_score(&mut peers);
+    peers
+}
+
+/// Attempt to persist the known good peers to `store`.
+///
+/// Each peer is provided with the time since it was last seen and its score. Only the
+/// `MAX_PERSISTED_PEERS` peers with the highest scores are persisted.
+pub fn persist_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peers: Vec<(Enr, Duration, f64)>,
+) -> Result<usize, store::Error> {
+    let now = unix_time_now();
+    let mut peers = peers
+        .into_iter()
+        .filter(|(_, last_seen, _)| last_seen.as_secs() <= MAX_PEER_AGE)
+        .map(|(enr, last_seen, score)| PersistedPeer {
+            enr,
+            last_seen: now.saturating_sub(last_seen.as_secs()),
+            score,
+        })
+        .collect::<Vec<_>>();
+    sort_by_score(&mut peers);
+    peers.truncate(MAX_PERSISTED_PEERS);
+    let peers_len = peers.len();
+
+    let key = Hash256::from_slice(&KNOWN_PEERS_DB_KEY.as_bytes());
+    store.put_item(&key, &PersistedPeers { peers })?;
+    Ok(peers_len)
+}
+
+fn sort_by_score(peers: &mut Vec<PersistedPeer>) {
+    peers.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+fn unix_time_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A peer which was known to be good when the node last shut down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistedPeer {
+    /// The ENR of the peer.
+    pub enr: Enr,
+    /// The unix timestamp (in seconds) at which the peer was last seen.
+    pub last_seen: u64,
+    /// The score of the peer when it was persisted.
+    pub score: f64,
+}
+
+impl rlp::Encodable for PersistedPeer {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(3);
+        s.append(&self.enr);
+        s.append(&self.last_seen);
+        s.append(&self.score.to_bits());
+    }
+}
+
+impl rlp::Decodable for PersistedPeer {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 3 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(PersistedPeer {
+            enr: rlp.val_at(0)?,
+            last_seen: rlp.val_at(1)?,
+            score: f64::from_bits(rlp.val_at(2)?),
+        })
+    }
+}
+
+/// Wrapper around the known good peers for persistence to disk.
+pub struct PersistedPeers {
+    pub peers: Vec<PersistedPeer>,
+}
+
+impl StoreItem for PersistedPeers {
+    fn db_column() -> DBColumn {
+        DBColumn::KnownPeers
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        rlp::encode_list(&self.peers)
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let rlp = rlp::Rlp::new(bytes);
+        let peers: Vec<PersistedPeer> = rlp
+            .as_list()
+            .map_err(|e| StoreError::RlpError(format!("{}", e)))?;
+        Ok(PersistedPeers { peers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use std::str::FromStr;
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+        let enr = Enr::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").unwrap();
+
+        let peers = vec![
+            (enr.clone(), Duration::from_secs(10), 1.5),
+            (enr.clone(), Duration::from_secs(0), 12.25),
+            // too old to be persisted
+            (enr.clone(), Duration::from_secs(MAX_PEER_AGE + 1), 50.0),
+        ];
+        assert_eq!(persist_peers(store.clone(), peers).unwrap(), 2);
+
+        let loaded = load_peers(store);
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.iter().all(|peer| peer.enr == enr));
+        // peers are ordered by score
+        assert!(loaded[0].score > loaded[1].score);
+    }
+}
@@ -75,6 +75,7 @@ impl<T: BeaconChainTypes> Router<T> {
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
         executor: environment::TaskExecutor,
+        #[cfg(feature = "fault-injection")] serve_invalid_blocks: bool,
         log: slog::Logger,
     ) -> error::Result<mpsc::UnboundedSender<RouterMessage<T::EthSpec>>> {
         let message_handler_log = log.new(o!("service"=> "router"));
@@ -90,6 +91,8 @@ impl<T: BeaconChainTypes> Router<T> {
             network_send,
             &log,
         );
+        #[cfg(feature = "fault-injection")]
+        let processor = processor.serve_invalid_blocks(serve_invalid_blocks);
 
         // generate the Message handler
         let mut handler = Router {
@@ -249,6 +252,21 @@ impl<T: BeaconChainTypes> Router<T> {
                 self.processor
                     .on_attester_slashing_gossip(id, peer_id, attester_slashing);
             }
+            PubsubMessage::SignedContributionAndProof(contribution_and_proof) => {
+                self.processor.on_sync_committee_contribution_gossip(
+                    id,
+                    peer_id,
+                    *contribution_and_proof,
+                );
+            }
+            PubsubMessage::SyncCommitteeMessage(sync_committee_msg) => {
+                self.processor.on_sync_committee_signature_gossip(
+                    id,
+                    peer_id,
+                    sync_committee_msg.1,
+                    sync_committee_msg.0,
+                );
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::beacon_processor::{
     BeaconProcessor, WorkEvent as BeaconWorkEvent, MAX_WORK_EVENT_QUEUE_LEN,
 };
+use crate::metrics;
 use crate::service::NetworkMessage;
 use crate::sync::{PeerSyncInfo, SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -10,18 +11,28 @@ use eth2_libp2p::{
 };
 use itertools::process_results;
 use slog::{debug, error, o, trace, warn};
+use ssz::Encode;
 use std::cmp;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::{
     Attestation, AttesterSlashing, ChainSpec, Epoch, EthSpec, Hash256, ProposerSlashing,
-    SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit, Slot, SubnetId,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit,
+    Slot, SubnetId, SyncCommitteeMessage,
 };
 
 /// If a block is more than `FUTURE_SLOT_TOLERANCE` slots ahead of our slot clock, we drop it.
 /// Otherwise we queue it.
 pub(crate) const FUTURE_SLOT_TOLERANCE: u64 = 1;
 
+/// The maximum number of SSZ bytes of blocks sent in response to a single `BlocksByRange` request.
+const BLOCKS_BY_RANGE_BYTE_BUDGET: usize = 32 * 1_048_576;
+/// The maximum time spent serving a single `BlocksByRange` request.
+const BLOCKS_BY_RANGE_TIME_BUDGET: Duration = Duration::from_secs(5);
+/// The number of `BlocksByRange` response chunks read from the store ahead of the network service.
+const BLOCKS_BY_RANGE_CHANNEL_LEN: usize = 8;
+
 /// Processes validated messages from the network. It relays necessary data to the syncing thread
 /// and processes blocks from the pubsub network.
 pub struct Processor<T: BeaconChainTypes> {
@@ -33,6 +44,11 @@ pub struct Processor<T: BeaconChainTypes> {
     network: HandlerNetworkContext<T::EthSpec>,
     /// A multi-threaded, non-blocking processor for applying messages to the beacon chain.
     beacon_processor_send: mpsc::Sender<BeaconWorkEvent<T::EthSpec>>,
+    /// The executor used to serve block requests off the router task.
+    executor: environment::TaskExecutor,
+    /// Serve blocks with invalid signatures to simulate a faulty peer.
+    #[cfg(feature = "fault-injection")]
+    serve_invalid_blocks: bool,
     /// The `RPCHandler` logger.
     log: slog::Logger,
 }
@@ -60,14 +76,20 @@ impl<T: BeaconChainTypes> Processor<T> {
             sync_logger,
         );
 
+        let max_workers = cmp::max(1, num_cpus::get());
+        // Reserve at least one worker for blocks, aggregates, etc.
+        let max_attestation_workers = cmp::max(1, max_workers.saturating_sub(1));
+
         BeaconProcessor {
             beacon_chain: Arc::downgrade(&beacon_chain),
             network_tx: network_send.clone(),
             sync_tx: sync_send.clone(),
             network_globals,
-            executor,
-            max_workers: cmp::max(1, num_cpus::get()),
+            executor: executor.clone(),
+            max_workers,
             current_workers: 0,
+            max_attestation_workers,
+            current_attestation_workers: 0,
             log: log.clone(),
         }
         .spawn_manager(beacon_processor_receive);
@@ -77,10 +99,47 @@ impl<T: BeaconChainTypes> Processor<T> {
             sync_send,
             network: HandlerNetworkContext::new(network_send, log.clone()),
             beacon_processor_send,
+            executor,
+            #[cfg(feature = "fault-injection")]
+            serve_invalid_blocks: false,
             log: log.clone(),
         }
     }
 
+    /// Serve blocks with invalid signatures in response to `BlocksByRange` requests.
+    #[cfg(feature = "fault-injection")]
+    pub fn serve_invalid_blocks(mut self, serve_invalid_blocks: bool) -> Self {
+        self.serve_invalid_blocks = serve_invalid_blocks;
+        self
+    }
+
+    /// Strips the signatures of the blocks in `responses` if this node simulates a faulty peer.
+    #[cfg(feature = "fault-injection")]
+    fn invalidate_blocks(
+        &self,
+        mut responses: mpsc::Receiver<Response<T::EthSpec>>,
+    ) -> mpsc::Receiver<Response<T::EthSpec>> {
+        if !self.serve_invalid_blocks {
+            return responses;
+        }
+
+        let (mut invalid_send, invalid_recv) = mpsc::channel(BLOCKS_BY_RANGE_CHANNEL_LEN);
+        self.executor.spawn(
+            async move {
+                while let Some(mut response) = responses.recv().await {
+                    if let Response::BlocksByRange(Some(block)) = &mut response {
+                        block.signature = types::Signature::empty();
+                    }
+                    if invalid_send.send(response).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            "invalid_blocks_by_range",
+        );
+        invalid_recv
+    }
+
     fn send_to_sync(&mut self, message: SyncMessage<T::EthSpec>) {
         self.sync_send.send(message).unwrap_or_else(|_| {
             warn!(
@@ -266,7 +325,7 @@ impl<T: BeaconChainTypes> Processor<T> {
         } else if self
             .chain
             .store
-            .item_exists::<SignedBeaconBlock<T::EthSpec>>(&remote.head_root)
+            .block_exists(&remote.head_root)
             .unwrap_or_else(|_| false)
         {
             debug!(
@@ -337,7 +396,7 @@ impl<T: BeaconChainTypes> Processor<T> {
         &mut self,
         peer_id: PeerId,
         request_id: PeerRequestId,
-        mut req: BlocksByRangeRequest,
+        req: BlocksByRangeRequest,
     ) {
         debug!(
             self.log,
@@ -348,112 +407,55 @@ impl<T: BeaconChainTypes> Processor<T> {
             "step" => req.step,
         );
 
-        // Should not send more than max request blocks
-        if req.count > MAX_REQUEST_BLOCKS {
-            req.count = MAX_REQUEST_BLOCKS;
-        }
         if req.step == 0 {
             warn!(self.log,
                 "Peer sent invalid range request";
                 "error" => "Step sent was 0");
-            self.network.goodbye_peer(peer_id, GoodbyeReason::Fault);
+            self.network
+                .report_peer(peer_id, PeerAction::LowToleranceError);
             return;
         }
 
-        let forwards_block_root_iter = match self
-            .chain
-            .forwards_iter_block_roots(Slot::from(req.start_slot))
-        {
-            Ok(iter) => iter,
-            Err(e) => {
-                return error!(
-                    self.log,
-                    "Unable to obtain root iter";
-                    "error" => format!("{:?}", e)
-                )
-            }
-        };
-
-        // pick out the required blocks, ignoring skip-slots and stepping by the step parameter;
-        let mut last_block_root = None;
-        let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
-            iter.take_while(|(_, slot)| slot.as_u64() < req.start_slot + req.count * req.step)
-                // map skip slots to None
-                .map(|(root, _)| {
-                    let result = if Some(root) == last_block_root {
-                        None
-                    } else {
-                        Some(root)
-                    };
-                    last_block_root = Some(root);
-                    result
-                })
-                .step_by(req.step as usize)
-                .collect::<Vec<Option<Hash256>>>()
-        });
-
-        let block_roots = match maybe_block_roots {
-            Ok(block_roots) => block_roots,
-            Err(e) => {
-                error!(self.log, "Error during iteration over blocks"; "error" => format!("{:?}", e));
-                return;
-            }
-        };
-
-        // remove all skip slots
-        let block_roots = block_roots
-            .into_iter()
-            .filter_map(|root| root)
-            .collect::<Vec<_>>();
-
-        let mut blocks_sent = 0;
-        for root in block_roots {
-            if let Ok(Some(block)) = self.chain.store.get_block(&root) {
-                // Due to skip slots, blocks could be out of the range, we ensure they are in the
-                // range before sending
-                if block.slot() >= req.start_slot
-                    && block.slot() < req.start_slot + req.count * req.step
-                {
-                    blocks_sent += 1;
-                    self.network.send_response(
-                        peer_id.clone(),
-                        Response::BlocksByRange(Some(Box::new(block))),
-                        request_id,
-                    );
-                }
-            } else {
-                error!(
-                    self.log,
-                    "Block in the chain is not in the store";
-                    "request_root" => format!("{:}", root),
-                );
-            }
-        }
-
-        if blocks_sent < (req.count as usize) {
-            debug!(
-                self.log,
-                "BlocksByRange Response Sent";
-                "peer" => peer_id.to_string(),
-                "msg" => "Failed to return all requested blocks",
-                "start_slot" => req.start_slot,
-                "current_slot" => self.chain.slot().unwrap_or_else(|_| Slot::from(0_u64)).as_u64(),
-                "requested" => req.count,
-                "returned" => blocks_sent);
-        } else {
-            debug!(
-                self.log,
-                "Sending BlocksByRange Response";
-                "peer" => peer_id.to_string(),
-                "start_slot" => req.start_slot,
-                "current_slot" => self.chain.slot().unwrap_or_else(|_| Slot::from(0_u64)).as_u64(),
-                "requested" => req.count,
-                "returned" => blocks_sent);
+        // Requests for more than the spec maximum are invalid
+        if req.count == 0 || req.count > MAX_REQUEST_BLOCKS {
+            debug!(self.log,
+                "Peer sent invalid range request";
+                "error" => "Count outside of the allowed range",
+                "count" => req.count);
+            self.network.send_error_response(
+                peer_id,
+                request_id,
+                RPCResponseErrorCode::InvalidRequest,
+                format!("Count must be between 1 and {}", MAX_REQUEST_BLOCKS),
+            );
+            return;
         }
 
-        // send the stream terminator
+        // Reading blocks from the store may take a while, serve the request from a blocking task
+        // so that it can't hold up the processing of other network messages. The blocks are only
+        // read as fast as the network service takes them from the bounded channel.
+        let (response_send, response_recv) = mpsc::channel(BLOCKS_BY_RANGE_CHANNEL_LEN);
+        #[cfg(feature = "fault-injection")]
+        let response_recv = self.invalidate_blocks(response_recv);
         self.network
-            .send_response(peer_id, Response::BlocksByRange(None), request_id);
+            .send_response_stream(peer_id.clone(), response_recv, request_id);
+
+        let chain = self.chain.clone();
+        let log = self.log.clone();
+        self.executor.spawn_blocking(
+            move || {
+                serve_blocks_by_range(
+                    chain,
+                    response_send,
+                    peer_id,
+                    req,
+                    BLOCKS_BY_RANGE_BYTE_BUDGET,
+                    BLOCKS_BY_RANGE_TIME_BUDGET,
+                    &log,
+                )
+            },
+            "blocks_by_range",
+        );
     }
 
     /// Handle a `BlocksByRange` response from the peer.
@@ -582,6 +584,52 @@ impl<T: BeaconChainTypes> Processor<T> {
             })
     }
 
+    pub fn on_sync_committee_signature_gossip(
+        &mut self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_signature: SyncCommitteeMessage,
+        subnet_id: u64,
+    ) {
+        self.beacon_processor_send
+            .try_send(BeaconWorkEvent::gossip_sync_signature(
+                message_id,
+                peer_id,
+                sync_signature,
+                subnet_id,
+            ))
+            .unwrap_or_else(|e| {
+                error!(
+                    &self.log,
+                    "Unable to send to gossip processor";
+                    "type" => "sync committee signature gossip",
+                    "error" => e.to_string(),
+                )
+            })
+    }
+
+    pub fn on_sync_committee_contribution_gossip(
+        &mut self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        sync_contribution: SignedContributionAndProof<T::EthSpec>,
+    ) {
+        self.beacon_processor_send
+            .try_send(BeaconWorkEvent::gossip_sync_contribution(
+                message_id,
+                peer_id,
+                sync_contribution,
+            ))
+            .unwrap_or_else(|e| {
+                error!(
+                    &self.log,
+                    "Unable to send to gossip processor";
+                    "type" => "sync committee contribution gossip",
+                    "error" => e.to_string(),
+                )
+            })
+    }
+
     pub fn on_voluntary_exit_gossip(
         &mut self,
         message_id: MessageId,
@@ -649,6 +697,142 @@ impl<T: BeaconChainTypes> Processor<T> {
     }
 }
 
+/// Streams the blocks requested by a `BlocksByRange` request to the peer via `responses`.
+///
+/// Blocks are read from the store one at a time, each waiting for capacity in the bounded
+/// `responses` channel before the next is read. The response is cut short once `byte_budget`
+/// bytes have been sent or `time_budget` has elapsed, which the spec permits. The stream is
+/// always terminated, unless the receiver has gone away.
+fn serve_blocks_by_range<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    mut responses: mpsc::Sender<Response<T::EthSpec>>,
+    peer_id: PeerId,
+    req: BlocksByRangeRequest,
+    byte_budget: usize,
+    time_budget: Duration,
+    log: &slog::Logger,
+) {
+    // Blocks until the network service has room for the response, returning false if it has
+    // stopped receiving.
+    let mut send = |response| futures::executor::block_on(responses.send(response)).is_ok();
+
+    let started = Instant::now();
+    let end_slot = req
+        .start_slot
+        .saturating_add(req.count.saturating_mul(req.step));
+
+    let forwards_block_root_iter = match chain.forwards_iter_block_roots(Slot::from(req.start_slot))
+    {
+        Ok(iter) => iter,
+        Err(e) => {
+            error!(
+                log,
+                "Unable to obtain root iter";
+                "error" => format!("{:?}", e)
+            );
+            send(Response::BlocksByRange(None));
+            return;
+        }
+    };
+
+    // pick out the required blocks, ignoring skip-slots and stepping by the step parameter;
+    let mut last_block_root = None;
+    let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
+        iter.take_while(|(_, slot)| slot.as_u64() < end_slot)
+            // map skip slots to None
+            .map(|(root, _)| {
+                let result = if Some(root) == last_block_root {
+                    None
+                } else {
+                    Some(root)
+                };
+                last_block_root = Some(root);
+                result
+            })
+            .step_by(req.step as usize)
+            .collect::<Vec<Option<Hash256>>>()
+    });
+
+    let block_roots = match maybe_block_roots {
+        Ok(block_roots) => block_roots,
+        Err(e) => {
+            error!(log, "Error during iteration over blocks"; "error" => format!("{:?}", e));
+            send(Response::BlocksByRange(None));
+            return;
+        }
+    };
+
+    let mut blocks_sent = 0;
+    let mut bytes_sent = 0;
+    let mut truncated = false;
+    // remove all skip slots
+    for root in block_roots.into_iter().filter_map(|root| root) {
+        if bytes_sent >= byte_budget || started.elapsed() >= time_budget {
+            truncated = true;
+            break;
+        }
+
+        if let Ok(Some(block)) = chain.store.get_block(&root) {
+            // Due to skip slots, blocks could be out of the range, we ensure they are in the
+            // range before sending
+            if block.slot() >= req.start_slot && block.slot() < end_slot {
+                blocks_sent += 1;
+                bytes_sent += block.ssz_bytes_len();
+                if !send(Response::BlocksByRange(Some(Box::new(block)))) {
+                    debug!(
+                        log,
+                        "BlocksByRange Response abandoned";
+                        "peer" => peer_id.to_string(),
+                        "msg" => "The network service is no longer receiving responses",
+                    );
+                    return;
+                }
+            }
+        } else {
+            error!(
+                log,
+                "Block in the chain is not in the store";
+                "request_root" => format!("{:}", root),
+            );
+        }
+    }
+
+    if truncated {
+        metrics::inc_counter(&metrics::BLOCKS_BY_RANGE_TRUNCATED_RESPONSES);
+        debug!(
+            log,
+            "BlocksByRange Response truncated";
+            "peer" => peer_id.to_string(),
+            "start_slot" => req.start_slot,
+            "requested" => req.count,
+            "returned" => blocks_sent,
+            "bytes" => bytes_sent,
+            "elapsed_ms" => started.elapsed().as_millis() as u64);
+    } else if blocks_sent < (req.count as usize) {
+        debug!(
+            log,
+            "BlocksByRange Response Sent";
+            "peer" => peer_id.to_string(),
+            "msg" => "Failed to return all requested blocks",
+            "start_slot" => req.start_slot,
+            "current_slot" => chain.slot().unwrap_or_else(|_| Slot::from(0_u64)).as_u64(),
+            "requested" => req.count,
+            "returned" => blocks_sent);
+    } else {
+        debug!(
+            log,
+            "Sending BlocksByRange Response";
+            "peer" => peer_id.to_string(),
+            "start_slot" => req.start_slot,
+            "current_slot" => chain.slot().unwrap_or_else(|_| Slot::from(0_u64)).as_u64(),
+            "requested" => req.count,
+            "returned" => blocks_sent);
+    }
+
+    // send the stream terminator
+    send(Response::BlocksByRange(None));
+}
+
 /// Build a `StatusMessage` representing the state of the given `beacon_chain`.
 pub(crate) fn status_message<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
@@ -670,6 +854,7 @@ pub(crate) fn status_message<T: BeaconChainTypes>(
 
 /// Wraps a Network Channel to employ various RPC related network functionality for the
 /// processor.
+#[derive(Clone)]
 pub struct HandlerNetworkContext<T: EthSpec> {
     /// The network channel to relay messages to the Network service.
     network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
@@ -689,13 +874,14 @@ impl<T: EthSpec> HandlerNetworkContext<T> {
             .unwrap_or_else(|_| warn!(self.log, "Could not send message to the network service"))
     }
 
-    /// Disconnects and ban's a peer, sending a Goodbye request with the associated reason.
+    /// Disconnects a peer, sending a Goodbye request with the associated reason. The peer is not
+    /// re-connected to until its re-connection backoff has elapsed.
     pub fn goodbye_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
         self.inform_network(NetworkMessage::GoodbyePeer { peer_id, reason });
     }
 
     /// Reports a peer's action, adjusting the peer's score.
-    pub fn _report_peer(&mut self, peer_id: PeerId, action: PeerAction) {
+    pub fn report_peer(&mut self, peer_id: PeerId, action: PeerAction) {
         self.inform_network(NetworkMessage::ReportPeer { peer_id, action });
     }
 
@@ -717,8 +903,23 @@ impl<T: EthSpec> HandlerNetworkContext<T> {
         })
     }
 
+    /// Sends the responses received on the bounded `responses` channel to the network task, as
+    /// fast as it sends them.
+    pub fn send_response_stream(
+        &mut self,
+        peer_id: PeerId,
+        responses: mpsc::Receiver<Response<T>>,
+        id: PeerRequestId,
+    ) {
+        self.inform_network(NetworkMessage::SendResponseStream {
+            peer_id,
+            responses,
+            id,
+        })
+    }
+
     /// Sends an error response to the network task.
-    pub fn _send_error_response(
+    pub fn send_error_response(
         &mut self,
         peer_id: PeerId,
         id: PeerRequestId,
@@ -733,3 +934,149 @@ impl<T: EthSpec> HandlerNetworkContext<T> {
         })
     }
 }
+
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, NullMigratorEphemeralHarnessType,
+    };
+    use eth2_libp2p::ConnectionId;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use tokio::runtime::Runtime;
+    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+    type TestChain = BeaconChain<NullMigratorEphemeralHarnessType<E>>;
+
+    const CHAIN_LENGTH: usize = 16;
+
+    fn get_logger() -> slog::Logger {
+        NullLoggerBuilder.build().expect("should build logger")
+    }
+
+    fn get_chain() -> Arc<TestChain> {
+        let mut harness = BeaconChainHarness::new_with_store_config(
+            MinimalEthSpec,
+            generate_deterministic_keypairs(8),
+            StoreConfig::default(),
+        );
+        harness.extend_chain(
+            CHAIN_LENGTH,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        Arc::new(harness.chain)
+    }
+
+    fn range_request(count: u64) -> BlocksByRangeRequest {
+        BlocksByRangeRequest {
+            start_slot: 1,
+            count,
+            step: 1,
+        }
+    }
+
+    /// Serves a request for every block in the chain with the given budgets, returning the
+    /// number of blocks sent and whether the stream was terminated.
+    fn serve_with_budget(byte_budget: usize, time_budget: Duration) -> (usize, bool) {
+        let (response_send, mut response_recv) = mpsc::channel(CHAIN_LENGTH + 1);
+        serve_blocks_by_range(
+            get_chain(),
+            response_send,
+            PeerId::random(),
+            range_request(CHAIN_LENGTH as u64),
+            byte_budget,
+            time_budget,
+            &get_logger(),
+        );
+
+        let mut blocks = 0;
+        let mut terminated = false;
+        while let Ok(response) = response_recv.try_recv() {
+            match response {
+                Response::BlocksByRange(Some(_)) => blocks += 1,
+                Response::BlocksByRange(None) => terminated = true,
+                _ => panic!("should only send BlocksByRange responses"),
+            }
+        }
+        (blocks, terminated)
+    }
+
+    /// Sends a `BlocksByRange` request with `count` to a processor, asserting that it is rejected
+    /// as an invalid request.
+    fn assert_invalid_count(count: u64) {
+        let runtime = Runtime::new().unwrap();
+        let log = get_logger();
+        let (_signal, exit) = exit_future::signal();
+        let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+        let executor = environment::TaskExecutor::new(
+            runtime.handle().clone(),
+            exit,
+            log.clone(),
+            shutdown_tx,
+        );
+        let (network_send, mut network_recv) = mpsc::unbounded_channel();
+        let (sync_send, _sync_recv) = mpsc::unbounded_channel();
+        let (beacon_processor_send, _beacon_processor_recv) = mpsc::channel(1);
+
+        let mut processor = Processor {
+            chain: get_chain(),
+            sync_send,
+            network: HandlerNetworkContext::new(network_send, log.clone()),
+            beacon_processor_send,
+            executor,
+            #[cfg(feature = "fault-injection")]
+            serve_invalid_blocks: false,
+            log,
+        };
+        processor.on_blocks_by_range_request(
+            PeerId::random(),
+            (ConnectionId::new(0), SubstreamId::new(0)),
+            range_request(count),
+        );
+
+        match network_recv.try_recv() {
+            Ok(NetworkMessage::SendError { error, .. }) => assert!(
+                matches!(error, RPCResponseErrorCode::InvalidRequest),
+                "count {} should be an invalid request",
+                count
+            ),
+            _ => panic!("count {} should be answered with an error", count),
+        }
+    }
+
+    #[test]
+    fn zero_count_is_invalid() {
+        assert_invalid_count(0);
+    }
+
+    #[test]
+    fn count_above_max_request_blocks_is_invalid() {
+        assert_invalid_count(MAX_REQUEST_BLOCKS + 1);
+    }
+
+    #[test]
+    fn serves_all_blocks_within_budget() {
+        assert_eq!(
+            serve_with_budget(BLOCKS_BY_RANGE_BYTE_BUDGET, BLOCKS_BY_RANGE_TIME_BUDGET),
+            (CHAIN_LENGTH, true)
+        );
+    }
+
+    #[test]
+    fn byte_budget_truncates_response() {
+        // the budget is checked before each block, so a single byte allows exactly one block
+        assert_eq!(serve_with_budget(1, BLOCKS_BY_RANGE_TIME_BUDGET), (1, true));
+    }
+
+    #[test]
+    fn time_budget_truncates_response() {
+        assert_eq!(
+            serve_with_budget(BLOCKS_BY_RANGE_BYTE_BUDGET, Duration::from_secs(0)),
+            (0, true)
+        );
+    }
+}
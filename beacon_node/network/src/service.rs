@@ -1,32 +1,46 @@
+use crate::persisted_bans::{load_bans, persist_bans, PersistedBans};
 use crate::persisted_dht::{load_dht, persist_dht};
+use crate::persisted_peers::{load_peers, persist_peers};
 use crate::router::{Router, RouterMessage};
 use crate::{
     attestation_service::{AttServiceMessage, AttestationService},
     NetworkConfig,
 };
-use crate::{error, metrics};
-use beacon_chain::{BeaconChain, BeaconChainTypes};
+use crate::{error, metrics, nat};
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2_libp2p::{
+    behaviour::gossipsub_scoring_parameters::lighthouse_gossip_thresholds, MessageAcceptance,
+    Service as LibP2PService,
+};
 use eth2_libp2p::{
     rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId},
-    Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
+    BanTarget, Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
 };
 use eth2_libp2p::{
-    types::GossipKind, BehaviourEvent, GossipTopic, MessageId, NetworkGlobals, PeerId, TopicHash,
+    types::{GossipFilter, GossipKind, UPnPStatus},
+    BehaviourEvent, GossipTopic, MessageId, NetworkGlobals, PeerId, TopicHash,
 };
-use eth2_libp2p::{MessageAcceptance, Service as LibP2PService};
 use futures::prelude::*;
+use futures::stream::SelectAll;
 use rest_types::ValidatorSubscription;
 use slog::{debug, error, info, o, trace, warn};
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use slot_clock::SlotClock;
+use std::{cmp, collections::HashMap, net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use tokio::sync::mpsc;
 use tokio::time::Delay;
-use types::EthSpec;
+use types::{EthSpec, ForkName, RelativeEpoch};
 
 mod tests;
 
 /// The interval (in seconds) that various network metrics will update.
 const METRIC_UPDATE_INTERVAL: u64 = 1;
+/// The number of slots before a fork at which we subscribe to the new fork's gossip topics.
+const SUBSCRIBE_DELAY_SLOTS: u64 = 2;
+/// The number of epochs after a fork during which we remain subscribed to the old fork's topics.
+const UNSUBSCRIBE_DELAY_EPOCHS: u64 = 2;
+/// The time (in milliseconds) allowed for goodbye messages to be sent to peers on shutdown.
+const SHUTDOWN_GOODBYE_TIMEOUT: u64 = 500;
 
 /// Types of messages that the network service can receive.
 #[derive(Debug)]
@@ -47,6 +61,15 @@ pub enum NetworkMessage<T: EthSpec> {
         response: Response<T>,
         id: PeerRequestId,
     },
+    /// Send the successful Responses received on `responses` to the libp2p service.
+    ///
+    /// The responses are only received as fast as the network service sends them, so a producer
+    /// awaiting capacity on the bounded channel cannot queue more responses than it holds.
+    SendResponseStream {
+        peer_id: PeerId,
+        responses: mpsc::Receiver<Response<T>>,
+        id: PeerRequestId,
+    },
     /// Respond to a peer's request with an error.
     SendError {
         // TODO: note that this is never used, we just say goodbye without nicely closing the
@@ -74,8 +97,26 @@ pub enum NetworkMessage<T: EthSpec> {
         peer_id: PeerId,
         reason: GoodbyeReason,
     },
+    /// Add a peer or IP address to the user's ban list, which is persisted across restarts.
+    Ban(BanTarget),
+    /// Remove a peer or IP address from the user's ban list.
+    Unban(BanTarget),
+    /// Replace the filter restricting which gossip topics are subscribed to.
+    UpdateGossipFilter(GossipFilter),
+    /// The UPnP task established port mappings on the local gateway.
+    UPnPMappingEstablished {
+        /// The external TCP socket, if the TCP port was mapped.
+        tcp_socket: Option<SocketAddr>,
+        /// The external UDP socket, if the UDP port was mapped.
+        udp_socket: Option<SocketAddr>,
+    },
+    /// The UPnP task failed to establish any port mappings.
+    UPnPMappingFailed { reason: String },
 }
 
+/// A stream of the responses to a peer's request, as sent by `NetworkMessage::SendResponseStream`.
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = (PeerId, PeerRequestId, Response<T>)> + Send>>;
+
 /// Service that handles communication between internal services and the `eth2_libp2p` network service.
 pub struct NetworkService<T: BeaconChainTypes> {
     /// A reference to the underlying beacon chain.
@@ -89,14 +130,34 @@ pub struct NetworkService<T: BeaconChainTypes> {
     /// The sending channel for the network service to send messages to be routed throughout
     /// lighthouse.
     router_send: mpsc::UnboundedSender<RouterMessage<T::EthSpec>>,
+    /// The streams of responses which are being sent to peers.
+    response_streams: SelectAll<ResponseStream<T::EthSpec>>,
     /// A reference to lighthouse's database to persist the DHT.
     store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
     /// A collection of global variables, accessible outside of the network service.
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     /// A delay that expires when a new fork takes place.
     next_fork_update: Option<Delay>,
+    /// A delay that expires shortly before a new fork, when we subscribe to its topics.
+    next_fork_subscriptions: Option<Delay>,
+    /// A delay that expires after the grace period following a fork, along with the fork digest
+    /// whose topics should be unsubscribed from.
+    next_unsubscribe: Option<(Delay, [u8; 4])>,
     /// A timer for updating various network metrics.
     metrics_update: tokio::time::Interval,
+    /// A timer for updating the gossipsub score parameters to reflect the validator set.
+    gossipsub_parameter_update: tokio::time::Interval,
+    /// The error from the last failed update of the gossipsub score parameters. Repeated errors are
+    /// only logged at debug level, since the same error is likely to recur every epoch.
+    gossipsub_parameter_error: Option<String>,
+    /// The TCP and UDP ports which have been mapped on the local gateway via UPnP, if any.
+    upnp_mappings: (Option<u16>, Option<u16>),
+    /// Delays the processing of received gossip messages, to simulate a poorly connected node.
+    #[cfg(feature = "fault-injection")]
+    gossip_delay: Option<Duration>,
+    /// The executor used to deliver delayed gossip messages.
+    #[cfg(feature = "fault-injection")]
+    executor: environment::TaskExecutor,
     /// The logger for the network service.
     log: slog::Logger,
 }
@@ -122,10 +183,17 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
         // keep track of when our fork_id needs to be updated
         let next_fork_update = next_fork_delay(&beacon_chain);
+        let next_fork_subscriptions = next_fork_subscriptions_delay(&beacon_chain);
 
         // launch libp2p service
-        let (network_globals, mut libp2p) =
-            LibP2PService::new(executor.clone(), config, enr_fork_id, &network_log).await?;
+        let (network_globals, mut libp2p) = LibP2PService::new(
+            executor.clone(),
+            config,
+            enr_fork_id,
+            &beacon_chain.spec,
+            &network_log,
+        )
+        .await?;
 
         // Repopulate the DHT with stored ENR's.
         let enrs_to_load = load_dht::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
@@ -137,9 +205,75 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             libp2p.swarm.add_enr(enr.clone()); //TODO change?
         }
 
+        // Restore the user's ban list.
+        match load_bans::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone()) {
+            Ok(bans) => {
+                debug!(network_log, "Restoring banned peers"; "peers" => bans.peers.len(), "ips" => bans.ips.len());
+                for peer_id in bans.peers {
+                    libp2p.swarm.ban(BanTarget::Peer(peer_id));
+                }
+                for ip in bans.ips {
+                    libp2p.swarm.ban(BanTarget::Ip(ip));
+                }
+            }
+            Err(e) => {
+                error!(network_log, "Failed to load banned peers"; "error" => format!("{:?}", e))
+            }
+        }
+
+        // Dial the best of the peers which were known to be good before the last shutdown, so
+        // that we don't rely solely on the boot nodes to rebuild our peer set.
+        let known_peers = load_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
+        debug!(
+            network_log,
+            "Dialing known good peers"; "known_peers" => known_peers.len(), "to_dial" => cmp::min(known_peers.len(), config.target_peers)
+        );
+        for peer in known_peers.into_iter().take(config.target_peers) {
+            libp2p.swarm.dial_known_peer(peer.enr);
+        }
+
+        // Subscribe to the topics introduced by the current fork and any before it.
+        let current_slot = beacon_chain
+            .slot()
+            .unwrap_or_else(|_| beacon_chain.spec.genesis_slot);
+        let fork_digest = libp2p.swarm.enr_fork_id().fork_digest;
+        let mut fork_name = Some(
+            beacon_chain
+                .spec
+                .fork_name_at_slot::<T::EthSpec>(current_slot),
+        );
+        while let Some(name) = fork_name {
+            for kind in fork_topics(name) {
+                libp2p.swarm.subscribe_fork_topic(kind, fork_digest);
+            }
+            fork_name = name.previous_fork();
+        }
+
+        // attempt to construct external port mappings
+        if config.upnp_enabled {
+            *network_globals.upnp_status.write() = UPnPStatus::Pending;
+            let upnp_config = nat::UPnPConfig::from(config);
+            let upnp_send = network_send.clone();
+            let upnp_log = network_log.new(o!("service" => "UPnP"));
+            executor.spawn_blocking(
+                move || nat::construct_upnp_mappings(upnp_config, upnp_send, upnp_log),
+                "UPnP",
+            );
+        }
+
         // launch derived network services
 
         // router task
+        #[cfg(feature = "fault-injection")]
+        let router_send = Router::spawn(
+            beacon_chain.clone(),
+            network_globals.clone(),
+            network_send.clone(),
+            executor.clone(),
+            config.serve_invalid_blocks,
+            network_log.clone(),
+        )?;
+        #[cfg(not(feature = "fault-injection"))]
         let router_send = Router::spawn(
             beacon_chain.clone(),
             network_globals.clone(),
@@ -155,6 +289,11 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         // create a timer for updating network metrics
         let metrics_update = tokio::time::interval(Duration::from_secs(METRIC_UPDATE_INTERVAL));
 
+        // create a timer for updating the gossipsub score parameters once per epoch
+        let gossipsub_parameter_update = tokio::time::interval(Duration::from_millis(
+            beacon_chain.spec.milliseconds_per_slot * T::EthSpec::slots_per_epoch(),
+        ));
+
         // create the network service and spawn the task
         let network_log = network_log.new(o!("service" => "network"));
         let network_service = NetworkService {
@@ -163,10 +302,20 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             attestation_service,
             network_recv,
             router_send,
+            response_streams: SelectAll::new(),
             store,
             network_globals: network_globals.clone(),
             next_fork_update,
+            next_fork_subscriptions,
+            next_unsubscribe: None,
             metrics_update,
+            gossipsub_parameter_update,
+            gossipsub_parameter_error: None,
+            upnp_mappings: (None, None),
+            #[cfg(feature = "fault-injection")]
+            gossip_delay: config.gossip_delay,
+            #[cfg(feature = "fault-injection")]
+            executor: executor.clone(),
             log: network_log,
         };
 
@@ -176,6 +325,46 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     }
 }
 
+impl<T: BeaconChainTypes> NetworkService<T> {
+    /// Sends a received gossip message to the router.
+    ///
+    /// With the `fault-injection` feature, the message is sent after the gossip delay if one is
+    /// set.
+    fn route_pubsub_message(&self, message: RouterMessage<T::EthSpec>) {
+        #[cfg(feature = "fault-injection")]
+        {
+            if let Some(delay) = self.gossip_delay {
+                let router_send = self.router_send.clone();
+                let log = self.log.clone();
+                self.executor.spawn(
+                    async move {
+                        tokio::time::delay_for(delay).await;
+                        let _ = router_send.send(message).map_err(|_| {
+                            debug!(log, "Failed to send pubsub message to router");
+                        });
+                    },
+                    "delayed_gossip",
+                );
+                return;
+            }
+        }
+
+        let _ = self.router_send.send(message).map_err(|_| {
+            debug!(self.log, "Failed to send pubsub message to router");
+        });
+    }
+
+    /// Writes the user's ban list to the store, so that it is restored after a restart.
+    fn persist_bans(&self) {
+        let bans = PersistedBans::from_peer_db(&self.network_globals.peers.read());
+        if let Err(e) =
+            persist_bans::<T::EthSpec, T::HotStore, T::ColdStore>(self.store.clone(), &bans)
+        {
+            error!(self.log, "Failed to persist banned peers"; "error" => format!("{:?}", e));
+        }
+    }
+}
+
 fn spawn_service<T: BeaconChainTypes>(
     executor: environment::TaskExecutor,
     mut service: NetworkService<T>,
@@ -212,6 +401,45 @@ fn spawn_service<T: BeaconChainTypes>(
                         ),
                     }
 
+                    let known_peers = service.libp2p.swarm.known_good_peers();
+                    match persist_peers::<T::EthSpec, T::HotStore, T::ColdStore>(service.store.clone(), known_peers) {
+                        Err(e) => error!(
+                            service.log,
+                            "Failed to persist known peers";
+                            "error" => format!("{:?}", e)
+                        ),
+                        Ok(count) => info!(
+                            service.log,
+                            "Saved known peers";
+                            "count" => count,
+                        ),
+                    }
+
+                    // inform our peers that we are shutting down, allowing a short time for the
+                    // goodbye messages to be sent
+                    service.libp2p.swarm.goodbye_all_peers(GoodbyeReason::ClientShutdown);
+                    let _ = tokio::time::timeout(
+                        Duration::from_millis(SHUTDOWN_GOODBYE_TIMEOUT),
+                        async {
+                            loop {
+                                service.libp2p.next_event().await;
+                            }
+                        },
+                    )
+                    .await;
+
+                    // remove any UPnP port mappings, off the async runtime since the gateway
+                    // requests block
+                    let (tcp_port, udp_port) = service.upnp_mappings;
+                    let upnp_log = service.log.clone();
+                    if let Err(e) = tokio::task::spawn_blocking(move || {
+                        nat::remove_mappings(tcp_port, udp_port, &upnp_log)
+                    })
+                    .await
+                    {
+                        debug!(service.log, "Failed to remove UPnP mappings"; "error" => e.to_string());
+                    }
+
                     info!(service.log, "Network service shutdown");
                     return;
                 }
@@ -226,6 +454,51 @@ fn spawn_service<T: BeaconChainTypes>(
                     }
                     update_gossip_metrics::<T::EthSpec>(&service.libp2p.swarm.gs());
                 }
+                _ = service.gossipsub_parameter_update.next() => {
+                    let active_validators = service.beacon_chain.with_head(|head| {
+                        head.beacon_state
+                            .get_cached_active_validator_indices(RelativeEpoch::Current)
+                            .map(|indices| indices.len())
+                            .map_err(BeaconChainError::BeaconStateError)
+                    });
+                    match (active_validators, service.beacon_chain.slot()) {
+                        (Ok(active_validators), Ok(slot)) => {
+                            match service
+                                .libp2p
+                                .swarm
+                                .update_gossipsub_parameters(active_validators, slot)
+                            {
+                                Ok(()) => service.gossipsub_parameter_error = None,
+                                Err(e) => {
+                                    let error = e.to_string();
+                                    if service.gossipsub_parameter_error.as_ref() == Some(&error) {
+                                        debug!(
+                                            service.log,
+                                            "Failed to update gossipsub parameters";
+                                            "error" => &error
+                                        );
+                                    } else {
+                                        error!(
+                                            service.log,
+                                            "Failed to update gossipsub parameters";
+                                            "error" => &error
+                                        );
+                                        service.gossipsub_parameter_error = Some(error);
+                                    }
+                                }
+                            }
+                        }
+                        (Err(e), _) | (_, Err(e)) => debug!(
+                            service.log,
+                            "Unable to update gossipsub parameters";
+                            "error" => format!("{:?}", e)
+                        ),
+                    }
+                }
+                // send the next response of a response stream
+                Some((peer_id, id, response)) = service.response_streams.next(), if !service.response_streams.is_empty() => {
+                    service.libp2p.send_response(peer_id, id, response);
+                }
                 // handle a message sent to the network
                 Some(message) = service.network_recv.recv() => {
                     match message {
@@ -235,6 +508,11 @@ fn spawn_service<T: BeaconChainTypes>(
                         NetworkMessage::SendResponse{ peer_id, response, id } => {
                             service.libp2p.send_response(peer_id, id, response);
                         }
+                        NetworkMessage::SendResponseStream{ peer_id, responses, id } => {
+                            service.response_streams.push(Box::pin(
+                                responses.map(move |response| (peer_id.clone(), id, response)),
+                            ));
+                        }
                         NetworkMessage::SendError{ peer_id, error, id, reason } => {
                             service.libp2p.respond_with_error(peer_id, id, error, reason);
                         }
@@ -272,6 +550,35 @@ fn spawn_service<T: BeaconChainTypes>(
                         }
                         NetworkMessage::ReportPeer { peer_id, action } => service.libp2p.report_peer(&peer_id, action),
                         NetworkMessage::GoodbyePeer { peer_id, reason } => service.libp2p.goodbye_peer(&peer_id, reason),
+                        NetworkMessage::Ban(target) => {
+                            info!(service.log, "Banning"; "target" => target.to_string());
+                            service.libp2p.swarm.ban(target);
+                            service.persist_bans();
+                        }
+                        NetworkMessage::Unban(target) => {
+                            if service.libp2p.swarm.unban(&target) {
+                                info!(service.log, "Unbanning"; "target" => target.to_string());
+                                service.persist_bans();
+                            } else {
+                                warn!(service.log, "Cannot unban, not on the ban list"; "target" => target.to_string());
+                            }
+                        }
+                        NetworkMessage::UpdateGossipFilter(filter) => {
+                            info!(service.log, "Updating gossip filter"; "blocks_only" => filter.blocks_only, "disabled_subnets" => format!("{:?}", filter.disabled_subnets));
+                            service.libp2p.swarm.update_gossip_filter(filter);
+                        }
+                        NetworkMessage::UPnPMappingEstablished { tcp_socket, udp_socket } => {
+                            service.upnp_mappings = (
+                                tcp_socket.map(|socket| socket.port()),
+                                udp_socket.map(|socket| socket.port()),
+                            );
+                            *service.network_globals.upnp_status.write() =
+                                UPnPStatus::Established { tcp_socket, udp_socket };
+                        }
+                        NetworkMessage::UPnPMappingFailed { reason } => {
+                            *service.network_globals.upnp_status.write() =
+                                UPnPStatus::Failed { reason };
+                        }
                         NetworkMessage::Subscribe { subscriptions } => {
                             if let Err(e) = service
                                 .attestation_service
@@ -289,7 +596,7 @@ fn spawn_service<T: BeaconChainTypes>(
                             service.libp2p.swarm.subscribe_to_subnet(subnet_id);
                         }
                         AttServiceMessage::Unsubscribe(subnet_id) => {
-                            service.libp2p.swarm.subscribe_to_subnet(subnet_id);
+                            service.libp2p.swarm.unsubscribe_from_subnet(subnet_id);
                         }
                         AttServiceMessage::EnrAdd(subnet_id) => {
                             service.libp2p.swarm.update_enr_subnet(subnet_id, true);
@@ -379,21 +686,11 @@ fn spawn_service<T: BeaconChainTypes>(
                                             subnet,
                                             attestation,
                                         );
-                                        let _ = service
-                                            .router_send
-                                            .send(RouterMessage::PubsubMessage(id, source, message, should_process))
-                                            .map_err(|_| {
-                                                debug!(service.log, "Failed to send pubsub message to router");
-                                            });
+                                        service.route_pubsub_message(RouterMessage::PubsubMessage(id, source, message, should_process));
                                     }
                                     _ => {
                                         // all else is sent to the router
-                                        let _ = service
-                                            .router_send
-                                            .send(RouterMessage::PubsubMessage(id, source, message, true))
-                                            .map_err(|_| {
-                                                debug!(service.log, "Failed to send pubsub message to router");
-                                            });
+                                        service.route_pubsub_message(RouterMessage::PubsubMessage(id, source, message, true));
                                     }
                                 }
                             }
@@ -412,13 +709,40 @@ fn spawn_service<T: BeaconChainTypes>(
                 }
             }
 
+            if let Some(delay) = &service.next_fork_subscriptions {
+                if delay.is_elapsed() {
+                    if let Some(fork_digest) = service.beacon_chain.next_fork_digest() {
+                        info!(service.log, "Subscribing to new fork topics"; "fork_digest" => hex::encode(fork_digest));
+                        service.libp2p.swarm.subscribe_new_fork_topics(fork_digest);
+
+                        let current_slot = service.beacon_chain.slot().unwrap_or_else(|_| service.beacon_chain.spec.genesis_slot);
+                        if let Some((fork_name, _)) = service.beacon_chain.spec.next_fork_epoch::<T::EthSpec>(current_slot) {
+                            for kind in fork_topics(fork_name) {
+                                service.libp2p.swarm.subscribe_fork_topic(kind, fork_digest);
+                            }
+                        }
+                    }
+                    service.next_fork_subscriptions = None;
+                }
+            }
+
             if let Some(delay) = &service.next_fork_update {
                 if delay.is_elapsed() {
-                    service
-                        .libp2p
-                        .swarm
-                        .update_fork_version(service.beacon_chain.enr_fork_id());
+                    let old_fork_digest = service.libp2p.swarm.enr_fork_id().fork_digest;
+                    let enr_fork_id = service.beacon_chain.enr_fork_id();
+                    info!(service.log, "Transitioned to new fork"; "old_fork_digest" => hex::encode(old_fork_digest), "new_fork_digest" => hex::encode(enr_fork_id.fork_digest));
+                    service.libp2p.swarm.update_fork_version(enr_fork_id);
                     service.next_fork_update = next_fork_delay(&service.beacon_chain);
+                    service.next_fork_subscriptions = next_fork_subscriptions_delay(&service.beacon_chain);
+                    service.next_unsubscribe = Some((unsubscribe_delay(&service.beacon_chain), old_fork_digest));
+                }
+            }
+
+            if let Some((delay, fork_digest)) = &service.next_unsubscribe {
+                if delay.is_elapsed() {
+                    info!(service.log, "Unsubscribing from old fork topics"; "fork_digest" => hex::encode(fork_digest));
+                    service.libp2p.swarm.unsubscribe_from_fork_topics(*fork_digest);
+                    service.next_unsubscribe = None;
                 }
             }
         }
@@ -427,6 +751,15 @@ fn spawn_service<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Returns the gossip topics which are introduced by `fork_name`, in addition to those of the forks
+/// before it.
+fn fork_topics(fork_name: ForkName) -> Vec<GossipKind> {
+    match fork_name {
+        ForkName::Base => vec![],
+        ForkName::Altair => vec![GossipKind::SignedContributionAndProof],
+    }
+}
+
 /// Returns a `Delay` that triggers shortly after the next change in the beacon chain fork version.
 /// If there is no scheduled fork, `None` is returned.
 fn next_fork_delay<T: BeaconChainTypes>(
@@ -439,6 +772,26 @@ fn next_fork_delay<T: BeaconChainTypes>(
     })
 }
 
+/// Returns a `Delay` that triggers `SUBSCRIBE_DELAY_SLOTS` before the next fork, when the topics
+/// of the new fork should be subscribed to. If there is no scheduled fork, `None` is returned.
+fn next_fork_subscriptions_delay<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+) -> Option<tokio::time::Delay> {
+    beacon_chain.duration_to_next_fork().map(|until_fork| {
+        let ahead = beacon_chain.slot_clock.slot_duration() * SUBSCRIBE_DELAY_SLOTS as u32;
+        tokio::time::delay_until(
+            tokio::time::Instant::now() + until_fork.checked_sub(ahead).unwrap_or_default(),
+        )
+    })
+}
+
+/// Returns a `Delay` that triggers once the topics of the previous fork may be unsubscribed from.
+fn unsubscribe_delay<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) -> tokio::time::Delay {
+    let grace_period = beacon_chain.slot_clock.slot_duration()
+        * (T::EthSpec::slots_per_epoch() * UNSUBSCRIBE_DELAY_EPOCHS) as u32;
+    tokio::time::delay_until(tokio::time::Instant::now() + grace_period)
+}
+
 /// Inspects the `messages` that were being sent to the network and updates Prometheus metrics.
 fn expose_publish_metrics<T: EthSpec>(messages: &[PubsubMessage<T>]) {
     for message in messages {
@@ -490,6 +843,9 @@ fn update_gossip_metrics<T: EthSpec>(gossipsub: &eth2_libp2p::Gossipsub) {
     let _ = metrics::AVG_GOSSIPSUB_PEER_SCORE_PER_SUBNET_TOPIC
         .as_ref()
         .map(|gauge| gauge.reset());
+    let _ = metrics::GOSSIPSUB_PEERS_BELOW_SCORE_THRESHOLD
+        .as_ref()
+        .map(|gauge| gauge.reset());
 
     // reset the mesh peers, showing all subnets
     for subnet_id in 0..T::default_spec().attestation_subnet_count {
@@ -618,6 +974,28 @@ fn update_gossip_metrics<T: EthSpec>(gossipsub: &eth2_libp2p::Gossipsub) {
         }
     }
 
+    // peer scores relative to the thresholds
+    let thresholds = lighthouse_gossip_thresholds();
+    for (peer_id, _) in gossipsub.all_peers() {
+        if let Some(score) = gossipsub.peer_score(peer_id) {
+            for (threshold, value) in &[
+                ("zero", 0.0),
+                ("gossip", thresholds.gossip_threshold),
+                ("publish", thresholds.publish_threshold),
+                ("graylist", thresholds.graylist_threshold),
+            ] {
+                if score < *value {
+                    if let Some(v) = metrics::get_int_gauge(
+                        &metrics::GOSSIPSUB_PEERS_BELOW_SCORE_THRESHOLD,
+                        &[threshold],
+                    ) {
+                        v.inc()
+                    };
+                }
+            }
+        }
+    }
+
     // protocol peers
     let mut peers_per_protocol: HashMap<String, i64> = HashMap::new();
     for (_peer, protocol) in gossipsub.peer_protocol() {
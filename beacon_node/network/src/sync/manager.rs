@@ -40,7 +40,7 @@ use super::RequestId;
 use crate::beacon_processor::{ProcessId, WorkEvent as BeaconWorkEvent};
 use crate::service::NetworkMessage;
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
-use eth2_libp2p::rpc::{methods::MAX_REQUEST_BLOCKS, BlocksByRootRequest, GoodbyeReason};
+use eth2_libp2p::rpc::{methods::MAX_REQUEST_BLOCKS, BlocksByRootRequest};
 use eth2_libp2p::types::NetworkGlobals;
 use eth2_libp2p::{PeerAction, PeerId};
 use fnv::FnvHashMap;
@@ -51,6 +51,7 @@ use ssz_types::VariableList;
 use std::boxed::Box;
 use std::ops::Sub;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -64,6 +65,8 @@ const PARENT_FAIL_TOLERANCE: usize = 5;
 /// canonical chain to its head once the peer connects. A chain should not appear where it's depth
 /// is further back than the most recent head slot.
 const PARENT_DEPTH_TOLERANCE: usize = SLOT_IMPORT_TOLERANCE * 2;
+/// The interval at which pending range sync batches are checked for slow peers.
+const SLOW_BATCH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug)]
 /// A message than can be sent to the sync manager thread.
@@ -463,9 +466,10 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         // verify the hash is correct and try and process the block
         if expected_block_hash != block.canonical_root() {
             // The peer that sent this, sent us the wrong block.
-            // We do not tolerate this behaviour. The peer is instantly disconnected and banned.
+            // We do not tolerate this behaviour. The peer's score is set such that it is
+            // disconnected and banned.
             warn!(self.log, "Peer sent incorrect block for single block lookup"; "peer_id" => format!("{}", peer_id));
-            self.network.goodbye_peer(peer_id, GoodbyeReason::Fault);
+            self.network.report_peer(peer_id, PeerAction::Fatal);
             return;
         }
 
@@ -858,70 +862,78 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
     /// The main driving future for the sync manager.
     async fn main(&mut self) {
+        let mut slow_batch_check = tokio::time::interval(SLOW_BATCH_CHECK_INTERVAL);
+
         // process any inbound messages
         loop {
-            if let Some(sync_message) = self.input_channel.recv().await {
-                match sync_message {
-                    SyncMessage::AddPeer(peer_id, info) => {
-                        self.add_peer(peer_id, info);
-                    }
-                    SyncMessage::BlocksByRangeResponse {
-                        peer_id,
-                        request_id,
-                        beacon_block,
-                    } => {
-                        self.range_sync.blocks_by_range_response(
-                            &mut self.network,
+            tokio::select! {
+                Some(sync_message) = self.input_channel.recv() => {
+                    match sync_message {
+                        SyncMessage::AddPeer(peer_id, info) => {
+                            self.add_peer(peer_id, info);
+                        }
+                        SyncMessage::BlocksByRangeResponse {
                             peer_id,
                             request_id,
-                            beacon_block.map(|b| *b),
-                        );
-                    }
-                    SyncMessage::BlocksByRootResponse {
-                        peer_id,
-                        request_id,
-                        beacon_block,
-                    } => {
-                        self.blocks_by_root_response(peer_id, request_id, beacon_block.map(|b| *b))
-                            .await;
-                    }
-                    SyncMessage::UnknownBlock(peer_id, block) => {
-                        self.add_unknown_block(peer_id, *block);
-                    }
-                    SyncMessage::UnknownBlockHash(peer_id, block_hash) => {
-                        self.search_for_block(peer_id, block_hash);
-                    }
-                    SyncMessage::Disconnect(peer_id) => {
-                        self.peer_disconnect(&peer_id);
-                    }
-                    SyncMessage::RPCError(peer_id, request_id) => {
-                        self.inject_error(peer_id, request_id);
-                    }
-                    SyncMessage::BatchProcessed {
-                        chain_id,
-                        epoch,
-                        downloaded_blocks,
-                        result,
-                    } => {
-                        self.range_sync.handle_block_process_result(
-                            &mut self.network,
+                            beacon_block,
+                        } => {
+                            self.range_sync.blocks_by_range_response(
+                                &mut self.network,
+                                peer_id,
+                                request_id,
+                                beacon_block.map(|b| *b),
+                            );
+                        }
+                        SyncMessage::BlocksByRootResponse {
+                            peer_id,
+                            request_id,
+                            beacon_block,
+                        } => {
+                            self.blocks_by_root_response(peer_id, request_id, beacon_block.map(|b| *b))
+                                .await;
+                        }
+                        SyncMessage::UnknownBlock(peer_id, block) => {
+                            self.add_unknown_block(peer_id, *block);
+                        }
+                        SyncMessage::UnknownBlockHash(peer_id, block_hash) => {
+                            self.search_for_block(peer_id, block_hash);
+                        }
+                        SyncMessage::Disconnect(peer_id) => {
+                            self.peer_disconnect(&peer_id);
+                        }
+                        SyncMessage::RPCError(peer_id, request_id) => {
+                            self.inject_error(peer_id, request_id);
+                        }
+                        SyncMessage::BatchProcessed {
                             chain_id,
                             epoch,
                             downloaded_blocks,
                             result,
-                        );
-                    }
-                    SyncMessage::ParentLookupFailed {
-                        chain_head,
-                        peer_id,
-                    } => {
-                        // A peer sent an object (block or attestation) that referenced a parent.
-                        // The processing of this chain failed.
-                        self.failed_chains.insert(chain_head);
-                        self.network
-                            .report_peer(peer_id, PeerAction::MidToleranceError);
+                        } => {
+                            self.range_sync.handle_block_process_result(
+                                &mut self.network,
+                                chain_id,
+                                epoch,
+                                downloaded_blocks,
+                                result,
+                            );
+                        }
+                        SyncMessage::ParentLookupFailed {
+                            chain_head,
+                            peer_id,
+                        } => {
+                            // A peer sent an object (block or attestation) that referenced a parent.
+                            // The processing of this chain failed.
+                            self.failed_chains.insert(chain_head);
+                            self.network
+                                .report_peer(peer_id, PeerAction::MidToleranceError);
+                        }
                     }
                 }
+                _ = slow_batch_check.tick() => {
+                    // move range sync batches stuck on slow peers to faster peers
+                    self.range_sync.reassign_slow_batches(&mut self.network);
+                }
             }
         }
     }
@@ -101,7 +101,7 @@ impl<T: EthSpec> SyncNetworkContext<T> {
         self.send_rpc_request(peer_id, Request::BlocksByRoot(request))
     }
 
-    pub fn goodbye_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
+    pub fn _goodbye_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
         self.network_send
             .send(NetworkMessage::GoodbyePeer { peer_id, reason })
             .unwrap_or_else(|_| {
@@ -9,6 +9,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Sub;
+use std::time::Instant;
 use types::{Epoch, EthSpec, SignedBeaconBlock, Slot};
 
 /// A collection of sequential blocks that are requested from peers in a single RPC request.
@@ -30,6 +31,8 @@ pub struct Batch<T: EthSpec> {
     pub reprocess_retries: u8,
     /// The blocks that have been downloaded.
     pub downloaded_blocks: Vec<SignedBeaconBlock<T>>,
+    /// The time the current request for this batch was sent, if any.
+    pub request_time: Option<Instant>,
 }
 
 /// Represents a peer's attempt and providing the result for this batch.
@@ -43,6 +46,25 @@ pub struct Attempt {
     pub hash: u64,
 }
 
+/// A request for a batch which has been reassigned to a faster peer.
+///
+/// The request is kept until the slow peer responds, such that its response can still complete
+/// the batch if it arrives before the response to the request the batch was reassigned to.
+pub struct ReassignedRequest<T: EthSpec> {
+    /// The epoch of the requested batch.
+    pub start_epoch: Epoch,
+    /// The peer serving the request.
+    pub peer_id: PeerId,
+    /// The number of slots requested.
+    pub count: u64,
+    /// The time the request was sent.
+    pub request_time: Instant,
+    /// The id of the request the batch was reassigned to.
+    pub replacement_id: usize,
+    /// The blocks that have been downloaded.
+    pub downloaded_blocks: Vec<SignedBeaconBlock<T>>,
+}
+
 impl<T: EthSpec> Eq for Batch<T> {}
 
 impl<T: EthSpec> Batch<T> {
@@ -55,6 +77,7 @@ impl<T: EthSpec> Batch<T> {
             retries: 0,
             reprocess_retries: 0,
             downloaded_blocks: Vec::new(),
+            request_time: None,
         }
     }
 
@@ -157,10 +180,22 @@ impl<T: EthSpec> PendingBatches<T> {
         Some(())
     }
 
-    /// Returns true if there the peer does not exist in the peer_requests mapping. Indicating it
-    /// has no pending outgoing requests.
-    pub fn peer_is_idle(&self, peer_id: &PeerId) -> bool {
-        self.peer_requests.get(peer_id).is_none()
+    /// Returns the pending batch for a given request id, if it exists.
+    pub fn get(&self, request_id: usize) -> Option<&Batch<T>> {
+        self.batches.get(&request_id)
+    }
+
+    /// Iterates over all pending batches and their request ids.
+    pub fn iter(&self) -> impl Iterator<Item = (&usize, &Batch<T>)> {
+        self.batches.iter()
+    }
+
+    /// Returns the number of pending outgoing batch requests to the peer.
+    pub fn peer_request_count(&self, peer_id: &PeerId) -> usize {
+        self.peer_requests
+            .get(peer_id)
+            .map(|requests| requests.len())
+            .unwrap_or(0)
     }
 
     /// Removes a batch for a given peer.
@@ -1,4 +1,4 @@
-use super::batch::{Batch, PendingBatches};
+use super::batch::{Batch, PendingBatches, ReassignedRequest};
 use crate::beacon_processor::ProcessId;
 use crate::beacon_processor::WorkEvent as BeaconWorkEvent;
 use crate::sync::RequestId;
@@ -7,8 +7,10 @@ use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{PeerAction, PeerId};
 use rand::prelude::*;
 use slog::{crit, debug, error, warn};
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -31,6 +33,27 @@ const BATCH_BUFFER_SIZE: u8 = 5;
 /// be reported negatively.
 const INVALID_BATCH_LOOKUP_ATTEMPTS: u8 = 3;
 
+/// The maximum number of concurrent batch requests sent to a single peer. Faster peers are
+/// assigned more concurrent requests, up to this limit.
+const MAX_BATCHES_PER_PEER: usize = 3;
+
+/// The weight given to the latest measurement when updating a peer's throughput.
+const THROUGHPUT_SMOOTHING_FACTOR: f64 = 0.3;
+
+/// The factor a peer's throughput is multiplied by when a batch request to it fails or is
+/// reassigned for being too slow.
+const THROUGHPUT_FAILURE_PENALTY: f64 = 0.5;
+
+/// The throughput, in slots per second, assumed for peers before any batch has been downloaded.
+const DEFAULT_THROUGHPUT: f64 = 1.0;
+
+/// A pending batch is considered slow if it takes this many times longer than expected given the
+/// average throughput of the chain's peers.
+const SLOW_BATCH_FACTOR: f64 = 4.0;
+
+/// Pending batches are never considered slow before this duration has elapsed.
+const SLOW_BATCH_MIN_DURATION: Duration = Duration::from_secs(10);
+
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
 /// required.
@@ -73,6 +96,13 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// and thus available to download this chain from.
     pub peer_pool: HashSet<PeerId>,
 
+    /// The measured download throughput, in slots per second, of peers that have served batches
+    /// of this chain. This is used to distribute batch requests across the peer pool.
+    peer_throughput: HashMap<PeerId, f64>,
+
+    /// Requests to slow peers whose batches have been reassigned to faster peers.
+    reassigned_requests: HashMap<RequestId, ReassignedRequest<T::EthSpec>>,
+
     /// Starting epoch of the next batch that needs to be downloaded.
     to_be_downloaded: Epoch,
 
@@ -128,6 +158,8 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             completed_batches: Vec::new(),
             processed_batches: Vec::new(),
             peer_pool,
+            peer_throughput: HashMap::new(),
+            reassigned_requests: HashMap::new(),
             to_be_downloaded: start_epoch,
             processing_target: start_epoch,
             state: ChainSyncingState::Stopped,
@@ -158,17 +190,80 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         request_id: RequestId,
         beacon_block: &Option<SignedBeaconBlock<T::EthSpec>>,
     ) -> Option<()> {
+        if self.reassigned_requests.contains_key(&request_id) {
+            return self.on_reassigned_block_response(network, request_id, beacon_block);
+        }
+
         if let Some(block) = beacon_block {
             // This is not a stream termination, simply add the block to the request
             self.pending_batches.add_block(request_id, block.clone())
         } else {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
             let batch = self.pending_batches.remove(request_id)?;
+            if let Some(request_time) = batch.request_time {
+                self.update_peer_throughput(
+                    &batch.current_peer,
+                    batch.to_blocks_by_range_request().count,
+                    request_time.elapsed(),
+                );
+            }
             self.handle_completed_batch(network, batch);
             Some(())
         }
     }
 
+    /// A batch of blocks has been received for a request whose batch was reassigned to another
+    /// peer.
+    ///
+    /// If the request the batch was reassigned to is still pending, the slow peer's response
+    /// completes the batch and the response to the other request is ignored instead.
+    fn on_reassigned_block_response(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        request_id: RequestId,
+        beacon_block: &Option<SignedBeaconBlock<T::EthSpec>>,
+    ) -> Option<()> {
+        if let Some(block) = beacon_block {
+            self.reassigned_requests
+                .get_mut(&request_id)?
+                .downloaded_blocks
+                .push(block.clone());
+            return Some(());
+        }
+
+        let reassigned = self.reassigned_requests.remove(&request_id)?;
+        self.update_peer_throughput(
+            &reassigned.peer_id,
+            reassigned.count,
+            reassigned.request_time.elapsed(),
+        );
+
+        if let Some(mut batch) = self.pending_batches.remove(reassigned.replacement_id) {
+            debug!(self.log, "Reassigned batch completed by slow peer";
+                "batch_epoch" => batch.start_epoch,
+                "peer" => reassigned.peer_id.to_string(),
+                "ignored_peer" => batch.current_peer.to_string());
+            // the response to the replacement request is no longer required
+            if let Some(request_time) = batch.request_time {
+                self.reassigned_requests.insert(
+                    reassigned.replacement_id,
+                    ReassignedRequest {
+                        start_epoch: batch.start_epoch,
+                        peer_id: batch.current_peer.clone(),
+                        count: batch.to_blocks_by_range_request().count,
+                        request_time,
+                        replacement_id: request_id,
+                        downloaded_blocks: Vec::new(),
+                    },
+                );
+            }
+            batch.current_peer = reassigned.peer_id;
+            batch.downloaded_blocks = reassigned.downloaded_blocks;
+            self.handle_completed_batch(network, batch);
+        }
+        Some(())
+    }
+
     /// A completed batch has been received, process the batch.
     /// This will return `ProcessingResult::KeepChain` if the chain has not completed or
     /// failed indicating that further batches are required.
@@ -534,14 +629,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // attempt to find another peer to download the batch from (this potentially doubles up
         // requests on a single peer)
-        let current_peer = &batch.current_peer;
-        let new_peer = self
-            .peer_pool
-            .iter()
-            .find(|peer| *peer != current_peer)
-            .unwrap_or_else(|| current_peer);
-
-        batch.current_peer = new_peer.clone();
+        batch.current_peer = self.get_retry_peer(&batch.current_peer);
 
         debug!(self.log, "Re-requesting batch";
             "start_slot" => batch.start_slot(),
@@ -645,11 +733,46 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 "peer" => format!("{:?}", peer_id));
 
             Some(self.failed_batch(network, batch))
+        } else if let Some(reassigned) = self.reassigned_requests.remove(&request_id) {
+            // the batch has been reassigned to another peer, there is nothing to re-request
+            debug!(self.log, "Reassigned batch request failed";
+                "batch_epoch" => reassigned.start_epoch,
+                "peer" => format!("{:?}", peer_id));
+            Some(ProcessingResult::KeepChain)
         } else {
             None
         }
     }
 
+    /// Removes a peer from the peer pool, re-requesting any batches pending on it from other
+    /// peers.
+    ///
+    /// Returns `None` if the peer is not part of this chain and
+    /// `Some(ProcessingResult::RemoveChain)` if a batch could not be re-requested.
+    pub fn remove_peer(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        peer_id: &PeerId,
+    ) -> Option<ProcessingResult> {
+        if !self.peer_pool.remove(peer_id) {
+            return None;
+        }
+
+        let mut result = ProcessingResult::KeepChain;
+        while let Some(batch) = self.pending_batches.remove_batch_by_peer(peer_id) {
+            if let ProcessingResult::RemoveChain = self.failed_batch(network, batch) {
+                // a single batch failed, remove the chain
+                result = ProcessingResult::RemoveChain;
+                break;
+            }
+        }
+
+        self.peer_throughput.remove(peer_id);
+        self.reassigned_requests
+            .retain(|_, request| request.peer_id != *peer_id);
+        Some(result)
+    }
+
     /// A batch has failed. This occurs when a network timeout happens or the peer didn't respond.
     /// These events do not indicate a malicious peer, more likely simple networking issues.
     ///
@@ -667,15 +790,11 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             // chain is unrecoverable, remove it
             ProcessingResult::RemoveChain
         } else {
-            // try to re-process the request using a different peer, if possible
-            let current_peer = &batch.current_peer;
-            let new_peer = self
-                .peer_pool
-                .iter()
-                .find(|peer| *peer != current_peer)
-                .unwrap_or_else(|| current_peer);
+            // the peer failed to serve the batch, so assign it fewer batches in the future
+            self.penalize_peer_throughput(&batch.current_peer);
 
-            batch.current_peer = new_peer.clone();
+            // try to re-process the request using a different peer, if possible
+            batch.current_peer = self.get_retry_peer(&batch.current_peer);
             debug!(self.log, "Re-Requesting batch";
                 "start_slot" => batch.start_slot(),
                 "end_slot" => batch.end_slot -1, // The -1 shows inclusive blocks
@@ -721,21 +840,177 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         false
     }
 
-    /// Returns a peer if there exists a peer which does not currently have a pending request.
+    /// Returns the peer best suited to serve the next request, if any peer has spare capacity.
     ///
-    /// This is used to create the next request.
+    /// Each peer may serve a number of concurrent requests proportional to its measured
+    /// throughput. Of the peers with spare capacity, the one with the highest throughput per
+    /// pending request is chosen.
     fn get_next_peer(&self) -> Option<PeerId> {
-        // TODO: Optimize this by combining with above two functions.
-        // randomize the peers for load balancing
+        let average_throughput = self.average_throughput();
+        // randomize the peers so that ties are broken fairly
         let mut rng = rand::thread_rng();
         let mut peers = self.peer_pool.iter().collect::<Vec<_>>();
         peers.shuffle(&mut rng);
-        for peer in peers {
-            if self.pending_batches.peer_is_idle(peer) {
-                return Some(peer.clone());
+        peers
+            .into_iter()
+            .filter(|peer| {
+                self.peer_request_count(peer) < self.batch_capacity(peer, average_throughput)
+            })
+            .max_by(|a, b| self.compare_peer_load(a, b, average_throughput))
+            .cloned()
+    }
+
+    /// Returns the least loaded peer, other than `current_peer`, to re-request a batch from. If
+    /// `current_peer` is the only peer in the pool it is returned.
+    fn get_retry_peer(&self, current_peer: &PeerId) -> PeerId {
+        let average_throughput = self.average_throughput();
+        self.peer_pool
+            .iter()
+            .filter(|peer| *peer != current_peer)
+            .max_by(|a, b| self.compare_peer_load(a, b, average_throughput))
+            .unwrap_or(current_peer)
+            .clone()
+    }
+
+    /// Orders peers by their throughput per pending request, such that the peer able to serve
+    /// another request the soonest is the greatest.
+    fn compare_peer_load(&self, a: &PeerId, b: &PeerId, average_throughput: f64) -> Ordering {
+        let load = |peer: &PeerId| {
+            self.peer_throughput(peer, average_throughput)
+                / (self.peer_request_count(peer) + 1) as f64
+        };
+        load(a).partial_cmp(&load(b)).unwrap_or(Ordering::Equal)
+    }
+
+    /// Returns the number of outstanding requests to a peer, including requests whose batches have
+    /// been reassigned.
+    fn peer_request_count(&self, peer_id: &PeerId) -> usize {
+        self.pending_batches.peer_request_count(peer_id)
+            + self
+                .reassigned_requests
+                .values()
+                .filter(|request| request.peer_id == *peer_id)
+                .count()
+    }
+
+    /// Returns the number of concurrent requests a peer may serve. Peers with an average
+    /// throughput may serve two requests, slower peers one and faster peers up to
+    /// `MAX_BATCHES_PER_PEER`.
+    fn batch_capacity(&self, peer_id: &PeerId, average_throughput: f64) -> usize {
+        let relative_throughput = self.peer_throughput(peer_id, average_throughput)
+            / average_throughput.max(std::f64::EPSILON);
+        std::cmp::min(MAX_BATCHES_PER_PEER, 1 + relative_throughput as usize)
+    }
+
+    /// Returns the measured throughput of a peer. Peers that have not yet served a batch are
+    /// assumed to have the average throughput.
+    fn peer_throughput(&self, peer_id: &PeerId, average_throughput: f64) -> f64 {
+        self.peer_throughput
+            .get(peer_id)
+            .copied()
+            .unwrap_or(average_throughput)
+    }
+
+    /// Returns the average measured throughput of the peers in the peer pool.
+    fn average_throughput(&self) -> f64 {
+        let measured = self
+            .peer_pool
+            .iter()
+            .filter_map(|peer| self.peer_throughput.get(peer))
+            .collect::<Vec<_>>();
+        if measured.is_empty() {
+            DEFAULT_THROUGHPUT
+        } else {
+            measured.iter().copied().sum::<f64>() / measured.len() as f64
+        }
+    }
+
+    /// Updates the throughput of a peer which has served a request for `slots` slots.
+    fn update_peer_throughput(&mut self, peer_id: &PeerId, slots: u64, elapsed: Duration) {
+        let measured = slots as f64 / elapsed.as_secs_f64().max(std::f64::EPSILON);
+        self.peer_throughput
+            .entry(peer_id.clone())
+            .and_modify(|throughput| {
+                *throughput = THROUGHPUT_SMOOTHING_FACTOR * measured
+                    + (1.0 - THROUGHPUT_SMOOTHING_FACTOR) * *throughput
+            })
+            .or_insert(measured);
+    }
+
+    /// Reduces the throughput of a peer which has failed to serve a request in time.
+    fn penalize_peer_throughput(&mut self, peer_id: &PeerId) {
+        let throughput = self.peer_throughput(peer_id, self.average_throughput());
+        self.peer_throughput
+            .insert(peer_id.clone(), throughput * THROUGHPUT_FAILURE_PENALTY);
+    }
+
+    /// Re-requests pending batches that are taking much longer than expected from faster peers.
+    ///
+    /// The slow peer's throughput is penalized so that it is assigned fewer batches. The request to
+    /// the slow peer is kept, such that a late response can still complete the batch.
+    pub fn reassign_slow_batches(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        if let ChainSyncingState::Stopped = self.state {
+            return;
+        }
+
+        let average_throughput = self.average_throughput();
+        let slow_requests = self
+            .pending_batches
+            .iter()
+            .filter_map(|(request_id, batch)| {
+                let elapsed = batch.request_time?.elapsed();
+                let expected_secs = batch.to_blocks_by_range_request().count as f64
+                    / average_throughput.max(std::f64::EPSILON);
+                if elapsed > SLOW_BATCH_MIN_DURATION
+                    && elapsed.as_secs_f64() > expected_secs * SLOW_BATCH_FACTOR
+                {
+                    Some(*request_id)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for request_id in slow_requests {
+            let slow_peer = match self.pending_batches.get(request_id) {
+                Some(batch) => batch.current_peer.clone(),
+                None => continue,
+            };
+
+            // only reassign the batch if there is a faster peer to serve it
+            let new_peer = self.get_retry_peer(&slow_peer);
+            if self.peer_throughput(&new_peer, average_throughput)
+                <= self.peer_throughput(&slow_peer, average_throughput)
+            {
+                continue;
+            }
+
+            if let Some(mut batch) = self.pending_batches.remove(request_id) {
+                self.penalize_peer_throughput(&slow_peer);
+                let count = batch.to_blocks_by_range_request().count;
+                let request_time = batch.request_time.unwrap_or_else(Instant::now);
+                let downloaded_blocks = std::mem::replace(&mut batch.downloaded_blocks, Vec::new());
+                batch.current_peer = new_peer;
+                debug!(self.log, "Reassigning slow batch";
+                    "batch_epoch" => batch.start_epoch,
+                    "slow_peer" => slow_peer.to_string(),
+                    "peer" => batch.current_peer.to_string());
+                let start_epoch = batch.start_epoch;
+                if let Some(replacement_id) = self.send_batch(network, batch) {
+                    self.reassigned_requests.insert(
+                        request_id,
+                        ReassignedRequest {
+                            start_epoch,
+                            peer_id: slow_peer,
+                            count,
+                            request_time,
+                            replacement_id,
+                            downloaded_blocks,
+                        },
+                    );
+                }
             }
         }
-        None
     }
 
     /// Returns the next required batch from the chain if it exists. If there are no more batches
@@ -786,17 +1061,21 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     }
 
     /// Requests the provided batch from the provided peer.
+    ///
+    /// Returns the id of the request if it was sent.
     fn send_batch(
         &mut self,
         network: &mut SyncNetworkContext<T::EthSpec>,
-        batch: Batch<T::EthSpec>,
-    ) {
+        mut batch: Batch<T::EthSpec>,
+    ) -> Option<RequestId> {
+        batch.request_time = Some(Instant::now());
         let request = batch.to_blocks_by_range_request();
 
         match network.blocks_by_range_request(batch.current_peer.clone(), request) {
             Ok(request_id) => {
                 // add the batch to pending list
                 self.pending_batches.insert(request_id, batch);
+                Some(request_id)
             }
             Err(e) => {
                 warn!(self.log, "Batch request failed";
@@ -808,7 +1087,284 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     "error" => e,
                     "re-processes" =>  batch.reprocess_retries);
                 self.failed_batch(network, batch);
+                None
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::NetworkMessage;
+    use beacon_chain::{
+        builder::{BeaconChainBuilder, Witness},
+        eth1_chain::CachingEth1Backend,
+        events::NullEventHandler,
+        migrate::NullMigrator,
+    };
+    use eth2_libp2p::discovery::{build_enr, CombinedKey, Keypair};
+    use eth2_libp2p::{CombinedKeyExt, NetworkConfig, NetworkGlobals};
+    use genesis::{generate_deterministic_keypairs, interop_genesis_state};
+    use slog::Logger;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use slot_clock::{SlotClock, SystemTimeSlotClock};
+    use std::time::SystemTime;
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use tempfile::tempdir;
+    use types::{EnrForkId, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    type TestBeaconChainType = Witness<
+        NullMigrator,
+        SystemTimeSlotClock,
+        CachingEth1Backend<E>,
+        E,
+        NullEventHandler<E>,
+        MemoryStore<E>,
+        MemoryStore<E>,
+    >;
+
+    struct TestRig {
+        chain: SyncingChain<TestBeaconChainType>,
+        network: SyncNetworkContext<E>,
+        peers: Vec<PeerId>,
+        _network_recv: mpsc::UnboundedReceiver<NetworkMessage<E>>,
+        _beacon_processor_recv: mpsc::Receiver<BeaconWorkEvent<E>>,
+    }
+
+    fn get_logger() -> Logger {
+        NullLoggerBuilder.build().expect("logger should build")
+    }
+
+    fn build_beacon_chain(log: &Logger) -> Arc<BeaconChain<TestBeaconChainType>> {
+        let data_dir = tempdir().expect("should create temporary data_dir");
+        let spec = E::default_spec();
+        let keypairs = generate_deterministic_keypairs(1);
+        let store =
+            HotColdDB::open_ephemeral(StoreConfig::default(), spec.clone(), log.clone()).unwrap();
+        let genesis_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Arc::new(
+            BeaconChainBuilder::new(E)
+                .logger(log.clone())
+                .custom_spec(spec.clone())
+                .store(Arc::new(store))
+                .store_migrator(NullMigrator)
+                .data_dir(data_dir.path().to_path_buf())
+                .genesis_state(
+                    interop_genesis_state::<E>(&keypairs, 0, &spec)
+                        .expect("should generate interop state"),
+                )
+                .expect("should build state using recent genesis")
+                .dummy_eth1_backend()
+                .expect("should build dummy backend")
+                .null_event_handler()
+                .slot_clock(SystemTimeSlotClock::new(
+                    Slot::new(0),
+                    Duration::from_secs(genesis_time),
+                    Duration::from_secs(1),
+                ))
+                .build()
+                .expect("should build"),
+        )
+    }
+
+    /// Builds a syncing chain with a peer in its peer pool for each of the given measured
+    /// throughputs.
+    fn build_rig(throughputs: &[f64]) -> TestRig {
+        let log = get_logger();
+        let peers = throughputs
+            .iter()
+            .map(|_| PeerId::random())
+            .collect::<Vec<_>>();
+
+        let config = NetworkConfig::default();
+        let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+        let enr = build_enr::<E>(&enr_key, &config, EnrForkId::default()).unwrap();
+        let network_globals = Arc::new(NetworkGlobals::new(enr, 0, 0, vec![], &log));
+        let (network_send, network_recv) = mpsc::unbounded_channel();
+        let network = SyncNetworkContext::new(network_send, network_globals, log.clone());
+
+        let (beacon_processor_send, beacon_processor_recv) = mpsc::channel(16);
+        let mut chain = SyncingChain::new(
+            0,
+            Epoch::new(0),
+            Slot::new(1024),
+            Hash256::zero(),
+            peers[0].clone(),
+            beacon_processor_send,
+            build_beacon_chain(&log),
+            log,
+        );
+        for (peer_id, throughput) in peers.iter().zip(throughputs) {
+            chain.peer_pool.insert(peer_id.clone());
+            chain.peer_throughput.insert(peer_id.clone(), *throughput);
+        }
+
+        TestRig {
+            chain,
+            network,
+            peers,
+            _network_recv: network_recv,
+            _beacon_processor_recv: beacon_processor_recv,
+        }
+    }
+
+    /// Returns the request id of a batch pending on `peer_id`.
+    fn pending_request(chain: &SyncingChain<TestBeaconChainType>, peer_id: &PeerId) -> RequestId {
+        *chain
+            .pending_batches
+            .iter()
+            .find(|(_, batch)| batch.current_peer == *peer_id)
+            .expect("peer should have a pending batch")
+            .0
+    }
+
+    /// Marks a pending request as having been sent long enough ago to be considered slow.
+    fn make_slow(chain: &mut SyncingChain<TestBeaconChainType>, request_id: RequestId) {
+        let mut batch = chain.pending_batches.remove(request_id).unwrap();
+        batch.request_time = Instant::now().checked_sub(Duration::from_secs(600));
+        chain.pending_batches.insert(request_id, batch);
+    }
+
+    #[test]
+    fn batches_are_distributed_across_peers() {
+        let mut rig = build_rig(&[1.0, 1.0, 1.0]);
+        rig.chain.start_syncing(&mut rig.network, Epoch::new(0));
+
+        for peer_id in &rig.peers {
+            assert!(rig.chain.pending_batches.peer_request_count(peer_id) > 0);
+        }
+    }
+
+    #[test]
+    fn faster_peers_are_assigned_more_batches() {
+        let mut rig = build_rig(&[10.0, 1.0]);
+        rig.chain.start_syncing(&mut rig.network, Epoch::new(0));
+
+        let fast_requests = rig.chain.pending_batches.peer_request_count(&rig.peers[0]);
+        let slow_requests = rig.chain.pending_batches.peer_request_count(&rig.peers[1]);
+        assert!(slow_requests > 0);
+        assert!(fast_requests > slow_requests);
+    }
+
+    #[test]
+    fn slow_batch_is_reassigned_and_completed_by_late_response() {
+        let mut rig = build_rig(&[10.0, 1.0]);
+        let (fast_peer, slow_peer) = (rig.peers[0].clone(), rig.peers[1].clone());
+        rig.chain.start_syncing(&mut rig.network, Epoch::new(0));
+
+        let slow_request = pending_request(&rig.chain, &slow_peer);
+        let epoch = rig
+            .chain
+            .pending_batches
+            .get(slow_request)
+            .unwrap()
+            .start_epoch;
+        make_slow(&mut rig.chain, slow_request);
+        rig.chain.reassign_slow_batches(&mut rig.network);
+
+        // the batch is pending on the fast peer, the slow request is kept
+        let replacement = *rig
+            .chain
+            .pending_batches
+            .iter()
+            .find(|(_, batch)| batch.start_epoch == epoch)
+            .expect("batch should be pending")
+            .0;
+        assert_eq!(
+            rig.chain
+                .pending_batches
+                .get(replacement)
+                .unwrap()
+                .current_peer,
+            fast_peer
+        );
+        assert!(rig.chain.pending_batches.get(slow_request).is_none());
+        assert!(rig.chain.reassigned_requests.contains_key(&slow_request));
+        assert!(rig.chain.peer_throughput[&slow_peer] < 1.0);
+
+        // the late response of the slow peer completes the batch
+        assert_eq!(
+            rig.chain
+                .on_block_response(&mut rig.network, slow_request, &None),
+            Some(())
+        );
+        assert!(rig.chain.pending_batches.get(replacement).is_none());
+        assert!(!rig.chain.reassigned_requests.contains_key(&slow_request));
+        let completed = |chain: &SyncingChain<TestBeaconChainType>| {
+            chain
+                .completed_batches
+                .iter()
+                .chain(chain.current_processing_batch.iter())
+                .filter(|batch| batch.start_epoch == epoch)
+                .map(|batch| batch.current_peer.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(completed(&rig.chain), vec![slow_peer]);
+
+        // the response to the replacement request is ignored
+        assert_eq!(
+            rig.chain
+                .on_block_response(&mut rig.network, replacement, &None),
+            Some(())
+        );
+        assert!(rig.chain.reassigned_requests.is_empty());
+        assert_eq!(completed(&rig.chain).len(), 1);
+    }
+
+    #[test]
+    fn slow_batch_is_not_reassigned_to_slower_peer() {
+        let mut rig = build_rig(&[10.0, 1.0]);
+        let fast_peer = rig.peers[0].clone();
+        rig.chain.start_syncing(&mut rig.network, Epoch::new(0));
+
+        let request = pending_request(&rig.chain, &fast_peer);
+        make_slow(&mut rig.chain, request);
+        rig.chain.reassign_slow_batches(&mut rig.network);
+
+        assert_eq!(
+            rig.chain.pending_batches.get(request).unwrap().current_peer,
+            fast_peer
+        );
+        assert!(rig.chain.reassigned_requests.is_empty());
+    }
+
+    #[test]
+    fn remove_peer_clears_peer_state() {
+        let mut rig = build_rig(&[10.0, 1.0]);
+        let slow_peer = rig.peers[1].clone();
+        rig.chain.start_syncing(&mut rig.network, Epoch::new(0));
+
+        let slow_request = pending_request(&rig.chain, &slow_peer);
+        make_slow(&mut rig.chain, slow_request);
+        rig.chain.reassign_slow_batches(&mut rig.network);
+        assert!(rig.chain.reassigned_requests.contains_key(&slow_request));
+
+        assert!(
+            rig.chain.remove_peer(&mut rig.network, &slow_peer)
+                == Some(ProcessingResult::KeepChain)
+        );
+        assert!(!rig.chain.peer_pool.contains(&slow_peer));
+        assert!(!rig.chain.peer_throughput.contains_key(&slow_peer));
+        assert!(rig.chain.reassigned_requests.is_empty());
+        assert_eq!(rig.chain.pending_batches.peer_request_count(&slow_peer), 0);
+
+        // the late response is no longer attributed to this chain
+        assert_eq!(
+            rig.chain
+                .on_block_response(&mut rig.network, slow_request, &None),
+            None
+        );
+        assert!(rig
+            .chain
+            .remove_peer(&mut rig.network, &PeerId::random())
+            .is_none());
+    }
+}
@@ -500,6 +500,17 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         )
     }
 
+    /// Runs a function on all finalized and head chains.
+    pub fn head_finalized_for_each<F>(&mut self, func: F)
+    where
+        F: FnMut(&mut SyncingChain<T>),
+    {
+        self.finalized_chains
+            .iter_mut()
+            .chain(self.head_chains.iter_mut())
+            .for_each(func)
+    }
+
     /// Removes any outdated finalized or head chains.
     ///
     /// This removes chains with no peers, or chains whose start block slot is less than our current
@@ -365,21 +365,10 @@ impl<T: BeaconChainTypes> RangeSync<T> {
     /// for this peer. If so we mark the batch as failed. The batch may then hit it's maximum
     /// retries. In this case, we need to remove the chain and re-status all the peers.
     fn remove_peer(&mut self, network: &mut SyncNetworkContext<T::EthSpec>, peer_id: &PeerId) {
-        for (index, result) in self.chains.head_finalized_request_all(|chain| {
-            if chain.peer_pool.remove(peer_id) {
-                // this chain contained the peer
-                while let Some(batch) = chain.pending_batches.remove_batch_by_peer(peer_id) {
-                    if let ProcessingResult::RemoveChain = chain.failed_batch(network, batch) {
-                        // a single batch failed, remove the chain
-                        return Some(ProcessingResult::RemoveChain);
-                    }
-                }
-                // peer removed from chain, no batch failed
-                Some(ProcessingResult::KeepChain)
-            } else {
-                None
-            }
-        }) {
+        for (index, result) in self
+            .chains
+            .head_finalized_request_all(|chain| chain.remove_peer(network, peer_id))
+        {
             if result == ProcessingResult::RemoveChain {
                 // the chain needed to be removed
                 debug!(self.log, "Chain being removed due to failed batch");
@@ -388,6 +377,12 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         }
     }
 
+    /// Reassigns batches which are taking too long to download on all chains to faster peers.
+    pub fn reassign_slow_batches(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        self.chains
+            .head_finalized_for_each(|chain| chain.reassign_slow_batches(network));
+    }
+
     /// An RPC error has occurred.
     ///
     /// Check to see if the request corresponds to a pending batch. If so, re-request it if possible, if there have
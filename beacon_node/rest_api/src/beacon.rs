@@ -18,7 +18,7 @@ use std::sync::Arc;
 use slog::error;
 use types::{
     AttesterSlashing, BeaconState, EthSpec, Hash256, ProposerSlashing, PublicKeyBytes,
-    RelativeEpoch, SignedBeaconBlockHash, Slot,
+    RelativeEpoch, SignedBeaconBlockHash, SignedVoluntaryExit, Slot,
 };
 
 /// Returns a summary of the head of the beacon chain.
@@ -26,7 +26,7 @@ pub fn get_head<T: BeaconChainTypes>(
     ctx: Arc<Context<T>>,
 ) -> Result<CanonicalHeadResponse, ApiError> {
     let beacon_chain = &ctx.beacon_chain;
-    let chain_head = beacon_chain.head()?;
+    let chain_head = beacon_chain.cached_head()?;
 
     Ok(CanonicalHeadResponse {
         slot: chain_head.beacon_state.slot,
@@ -267,7 +267,10 @@ fn get_state_from_root_opt<T: BeaconChainTypes>(
             })?
             .ok_or_else(|| ApiError::NotFound(format!("No state exists with root: {}", state_root)))
     } else {
-        Ok(beacon_chain.head()?.beacon_state)
+        Ok(beacon_chain
+            .cached_head()?
+            .beacon_state
+            .clone_with_only_committee_caches())
     }
 }
 
@@ -367,7 +370,7 @@ pub fn get_state<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
 ) -> Result<StateResponse<T::EthSpec>, ApiError> {
-    let head_state = ctx.beacon_chain.head()?.beacon_state;
+    let head_slot = ctx.beacon_chain.head_info()?.slot;
 
     let (key, value) = match UrlQuery::from_request(&req) {
         Ok(query) => {
@@ -377,7 +380,7 @@ pub fn get_state<T: BeaconChainTypes>(
         }
         Err(ApiError::BadRequest(_)) => {
             // No parameters provided at all, use current slot.
-            (String::from("slot"), head_state.slot.to_string())
+            (String::from("slot"), head_slot.to_string())
         }
         Err(e) => {
             return Err(e);
@@ -460,6 +463,38 @@ pub fn proposer_slashing<T: BeaconChainTypes>(
     Ok(true)
 }
 
+/// Verifies a signed voluntary exit, publishing it to the network and adding it to the operation
+/// pool if it is valid.
+pub fn voluntary_exit<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<bool, ApiError> {
+    let body = req.into_body();
+    let exit = serde_json::from_slice::<SignedVoluntaryExit>(&body).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into SignedVoluntaryExit: {:?}",
+            e
+        ))
+    })?;
+
+    let obs_outcome = ctx
+        .beacon_chain
+        .verify_voluntary_exit_for_gossip(exit.clone())
+        .map_err(|e| {
+            ApiError::BadRequest(format!("Error while verifying voluntary exit: {:?}", e))
+        })?;
+
+    if let ObservationOutcome::New(verified_exit) = obs_outcome {
+        publish_voluntary_exit_to_network::<T>(&ctx.network_chan, exit)?;
+        ctx.beacon_chain.import_voluntary_exit(verified_exit);
+        Ok(true)
+    } else {
+        Err(ApiError::BadRequest(
+            "Voluntary exit for that validator index already known".into(),
+        ))
+    }
+}
+
 pub fn attester_slashing<T: BeaconChainTypes>(
     req: Request<Vec<u8>>,
     ctx: Arc<Context<T>>,
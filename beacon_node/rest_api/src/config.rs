@@ -41,6 +41,10 @@ pub struct Config {
     /// If something else than "", a 'Access-Control-Allow-Origin' header will be present in
     /// responses.  Put *, to allow any origin.
     pub allow_origin: String,
+    /// Allow snapshots of the database to be exported via the HTTP API.
+    ///
+    /// Each snapshot is a full copy of the database, so this is disabled by default.
+    pub allow_database_snapshots: bool,
 }
 
 impl Default for Config {
@@ -50,6 +54,7 @@ impl Default for Config {
             listen_address: Ipv4Addr::new(127, 0, 0, 1),
             port: 5052,
             allow_origin: "".to_string(),
+            allow_database_snapshots: false,
         }
     }
 }
@@ -7,7 +7,8 @@ use network::NetworkMessage;
 use ssz::Decode;
 use store::iter::AncestorIter;
 use types::{
-    BeaconState, CommitteeIndex, Epoch, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, Slot,
+    BeaconState, CommitteeIndex, Epoch, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock,
+    SignedVoluntaryExit, Slot,
 };
 
 /// Parse a slot.
@@ -120,10 +121,13 @@ pub fn state_at_slot<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     slot: Slot,
 ) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
-    let head = beacon_chain.head()?;
+    let head = beacon_chain.cached_head()?;
 
     if head.beacon_state.slot == slot {
-        Ok((head.beacon_state_root, head.beacon_state))
+        Ok((
+            head.beacon_state_root,
+            head.beacon_state.clone_with_only_committee_caches(),
+        ))
     } else {
         let root = state_root_at_slot(beacon_chain, slot, StateSkipConfig::WithStateRoots)?;
 
@@ -146,7 +150,8 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
     slot: Slot,
     config: StateSkipConfig,
 ) -> Result<Hash256, ApiError> {
-    let head_state = &beacon_chain.head()?.beacon_state;
+    let head = beacon_chain.cached_head()?;
+    let head_state = &head.beacon_state;
     let current_slot = beacon_chain
         .slot()
         .map_err(|_| ApiError::ServerError("Unable to read slot clock".to_string()))?;
@@ -170,7 +175,7 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
         // 2. The request slot is the same as the best block (head) slot.
         //
         // The head state root is stored in memory, return a reference.
-        Ok(beacon_chain.head()?.beacon_state_root)
+        Ok(head.beacon_state_root)
     } else if head_state.slot > slot {
         // 3. The request slot is prior to the head slot.
         //
@@ -190,7 +195,7 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
         //
         // Use `per_slot_processing` to advance the head state to the present slot,
         // assuming that all slots do not contain a block (i.e., they are skipped slots).
-        let mut state = beacon_chain.head()?.beacon_state;
+        let mut state = head_state.clone_with_only_committee_caches();
         let spec = &T::EthSpec::default_spec();
 
         let skip_state_root = match config {
@@ -229,6 +234,23 @@ pub fn publish_beacon_block_to_network<T: BeaconChainTypes + 'static>(
     Ok(())
 }
 
+pub fn publish_voluntary_exit_to_network<T: BeaconChainTypes + 'static>(
+    chan: &NetworkChannel<T::EthSpec>,
+    exit: SignedVoluntaryExit,
+) -> Result<(), ApiError> {
+    let messages = vec![PubsubMessage::VoluntaryExit(Box::new(exit))];
+
+    // Publish the exit to the p2p network via gossipsub.
+    if let Err(e) = chan.send(NetworkMessage::Publish { messages }) {
+        return Err(ApiError::ServerError(format!(
+            "Unable to send voluntary exit to network: {:?}",
+            e
+        )));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
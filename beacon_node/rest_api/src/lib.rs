@@ -70,6 +70,7 @@ pub fn start_server<T: BeaconChainTypes>(
         db_path,
         freezer_db_path,
         events,
+        snapshot_lock: Mutex::new(()),
     });
 
     // Define the function that will build the request handler.
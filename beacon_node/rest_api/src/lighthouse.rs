@@ -1,11 +1,37 @@
 //! This contains a collection of lighthouse specific HTTP endpoints.
 
 use crate::{ApiError, Context};
-use beacon_chain::BeaconChainTypes;
-use eth2_libp2p::PeerInfo;
+use beacon_chain::{eth1_chain::Eth1Health, BeaconChainTypes};
+use eth2_libp2p::{types::GossipFilter, BanTarget, PeerId, PeerInfo};
+use futures::executor::block_on;
+use hyper::body::Bytes;
+use hyper::{Body, Request};
+use network::NetworkMessage;
+use rest_types::{
+    BanList, BanRequest, DatabaseSnapshotResponse, SlasherAttestations, SlasherBlocks,
+    SlasherImportResponse,
+};
 use serde::Serialize;
+use slasher::{Slasher, SlasherEvent};
+use slog::error;
+use state_processing::per_block_processing::{is_valid_indexed_attestation, VerifySignatures};
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
-use types::EthSpec;
+use std::time::{SystemTime, UNIX_EPOCH};
+use store::metadata::{AnchorInfo, LEGACY_SCHEMA_VERSION};
+use store::snapshot::export_snapshot;
+use types::{EthSpec, Hash256, IndexedAttestation, Slot};
+
+/// The directory alongside the database which snapshots are written to.
+pub const SNAPSHOT_DIR: &str = "snapshots";
+/// The number of snapshots kept in the `snapshots` directory. Older snapshots are deleted when a
+/// new one is exported.
+pub const MAX_RETAINED_SNAPSHOTS: usize = 2;
+
+/// The prefix of the slasher endpoints, which are followed by a validator index.
+pub const SLASHER_ATTESTATIONS_PATH: &str = "/lighthouse/slasher/attestations/";
+pub const SLASHER_BLOCKS_PATH: &str = "/lighthouse/slasher/blocks/";
 
 /// Returns all known peers and corresponding information
 pub fn peers<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Vec<Peer<T::EthSpec>>, ApiError> {
@@ -37,6 +63,355 @@ pub fn connected_peers<T: BeaconChainTypes>(
         .collect())
 }
 
+/// Returns the health of the eth1 endpoints and the progress of the eth1 caches.
+pub fn eth1_health<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Eth1Health, ApiError> {
+    ctx.beacon_chain
+        .eth1_chain
+        .as_ref()
+        .and_then(|eth1_chain| eth1_chain.health())
+        .ok_or_else(|| {
+            ApiError::NotFound("The beacon node is not connected to an eth1 node".to_string())
+        })
+}
+
+/// Returns information about the database, including the range of history it contains.
+pub fn database_info<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<DatabaseInfo, ApiError> {
+    let store = &ctx.beacon_chain.store;
+    let schema_version = store
+        .load_schema_version()
+        .map_err(|e| ApiError::ServerError(format!("Unable to read schema version: {:?}", e)))?
+        .unwrap_or(LEGACY_SCHEMA_VERSION);
+    let split = store.get_split_info();
+
+    Ok(DatabaseInfo {
+        schema_version: schema_version.as_u64(),
+        split_slot: split.slot,
+        split_state_root: split.state_root,
+        anchor: store.get_anchor_info(),
+    })
+}
+
+/// Export a snapshot of the database to the `snapshots` directory, alongside the database.
+///
+/// Only one snapshot is exported at a time, and only the most recent `MAX_RETAINED_SNAPSHOTS`
+/// snapshots are kept.
+pub fn database_snapshot<T: BeaconChainTypes>(
+    ctx: Arc<Context<T>>,
+) -> Result<DatabaseSnapshotResponse, ApiError> {
+    if !ctx.config.allow_database_snapshots {
+        return Err(ApiError::MethodNotAllowed(
+            "Database snapshots are disabled, see --http-allow-snapshots".to_string(),
+        ));
+    }
+
+    let _snapshot_guard = ctx.snapshot_lock.try_lock().ok_or_else(|| {
+        ApiError::BadRequest("A database snapshot is already being exported".to_string())
+    })?;
+
+    let dir = ctx
+        .db_path
+        .parent()
+        .map(|beacon_dir| beacon_dir.join(SNAPSHOT_DIR))
+        .ok_or_else(|| ApiError::ServerError("Unable to locate the snapshot directory".into()))?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| ApiError::ServerError(format!("Unable to create {:?}: {:?}", dir, e)))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| ApiError::ServerError(format!("Unable to read system time: {:?}", e)))?
+        .as_secs();
+    let path = dir.join(format!("snapshot_{}.lhdb", timestamp));
+
+    let summary = export_snapshot(ctx.beacon_chain.store.as_ref(), &path)
+        .map_err(|e| ApiError::ServerError(format!("Unable to export snapshot: {:?}", e)))?;
+
+    prune_snapshots(&dir, MAX_RETAINED_SNAPSHOTS)?;
+
+    Ok(DatabaseSnapshotResponse {
+        path,
+        split_slot: summary.split_slot,
+        hot_entries: summary.hot_entries,
+        cold_entries: summary.cold_entries,
+    })
+}
+
+/// Delete all but the `retain` most recent snapshots in `dir`.
+fn prune_snapshots(dir: &Path, retain: usize) -> Result<(), ApiError> {
+    let mut snapshots = fs::read_dir(dir)
+        .map_err(|e| ApiError::ServerError(format!("Unable to read {:?}: {:?}", dir, e)))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let timestamp = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix("snapshot_")?
+                .strip_suffix(".lhdb")?
+                .parse::<u64>()
+                .ok()?;
+            Some((timestamp, path))
+        })
+        .collect::<Vec<_>>();
+    snapshots.sort_unstable_by(|a, b| b.cmp(a));
+
+    for (_, path) in snapshots.into_iter().skip(retain) {
+        fs::remove_file(&path)
+            .map_err(|e| ApiError::ServerError(format!("Unable to remove {:?}: {:?}", path, e)))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the peers and IP addresses banned by the user.
+pub fn bans<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<BanList, ApiError> {
+    let peer_db = ctx.network_globals.peers.read();
+    let mut bans = BanList {
+        peers: peer_db
+            .manually_banned_peers()
+            .map(|peer_id| peer_id.to_string())
+            .collect(),
+        ips: peer_db.manually_banned_ips().cloned().collect(),
+    };
+    bans.peers.sort();
+    bans.ips.sort();
+    Ok(bans)
+}
+
+/// Adds a peer or IP address to the ban list of the beacon node.
+pub fn add_ban<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    let target = parse_ban_request(req)?;
+    ctx.network_chan
+        .send(NetworkMessage::Ban(target))
+        .map_err(|e| ApiError::ServerError(format!("Unable to send ban to the network: {:?}", e)))
+}
+
+/// Removes a peer or IP address from the ban list of the beacon node.
+pub fn remove_ban<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    let target = parse_ban_request(req)?;
+    let is_banned = {
+        let peer_db = ctx.network_globals.peers.read();
+        match &target {
+            BanTarget::Peer(peer_id) => peer_db.manually_banned_peers().any(|p| p == peer_id),
+            BanTarget::Ip(ip) => peer_db.manually_banned_ips().any(|i| i == ip),
+        }
+    };
+    if !is_banned {
+        return Err(ApiError::NotFound(format!("{} is not banned", target)));
+    }
+    ctx.network_chan
+        .send(NetworkMessage::Unban(target))
+        .map_err(|e| ApiError::ServerError(format!("Unable to send unban to the network: {:?}", e)))
+}
+
+/// Returns the filter restricting which gossip topics the node subscribes to.
+pub fn gossip_filter<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<GossipFilter, ApiError> {
+    Ok(ctx.network_globals.gossip_filter.read().clone())
+}
+
+/// Replaces the filter restricting which gossip topics the node subscribes to.
+pub fn update_gossip_filter<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    let filter: GossipFilter = serde_json::from_slice(&req.into_body()).map_err(|e| {
+        ApiError::BadRequest(format!("Unable to parse JSON into GossipFilter: {:?}", e))
+    })?;
+
+    let subnet_count = ctx.beacon_chain.spec.attestation_subnet_count;
+    if let Some(subnet_id) = filter
+        .disabled_subnets
+        .iter()
+        .find(|subnet_id| **subnet_id >= subnet_count)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid subnet id {}, there are {} subnets",
+            subnet_id, subnet_count
+        )));
+    }
+
+    ctx.network_chan
+        .send(NetworkMessage::UpdateGossipFilter(filter))
+        .map_err(|e| {
+            ApiError::ServerError(format!(
+                "Unable to send gossip filter to the network: {:?}",
+                e
+            ))
+        })
+}
+
+/// Returns the attestations and attester slashings recorded by the slasher for the validator
+/// given in the path of `req`.
+pub fn slasher_attestations<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<SlasherAttestations<T::EthSpec>, ApiError> {
+    let validator_index = parse_validator_index(&req, SLASHER_ATTESTATIONS_PATH)?;
+    let slasher = get_slasher(&ctx)?;
+
+    Ok(SlasherAttestations {
+        attestations: slasher
+            .get_attestations_for_validator(validator_index)
+            .map_err(|e| ApiError::ServerError(format!("Unable to read attestations: {:?}", e)))?,
+        attester_slashings: slasher
+            .get_attester_slashings_for_validator(validator_index)
+            .map_err(|e| ApiError::ServerError(format!("Unable to read slashings: {:?}", e)))?,
+    })
+}
+
+/// Returns the block headers and proposer slashings recorded by the slasher for the validator
+/// given in the path of `req`.
+pub fn slasher_blocks<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<SlasherBlocks, ApiError> {
+    let validator_index = parse_validator_index(&req, SLASHER_BLOCKS_PATH)?;
+    let slasher = get_slasher(&ctx)?;
+
+    Ok(SlasherBlocks {
+        blocks: slasher
+            .get_blocks_for_proposer(validator_index)
+            .map_err(|e| ApiError::ServerError(format!("Unable to read blocks: {:?}", e)))?,
+        proposer_slashings: slasher
+            .get_proposer_slashings_for_validator(validator_index)
+            .map_err(|e| ApiError::ServerError(format!("Unable to read slashings: {:?}", e)))?,
+    })
+}
+
+/// Imports the JSON list of `IndexedAttestation`s in the body of `req` into the slasher, after
+/// verifying their signatures against the head state.
+pub fn import_slasher_attestations<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<SlasherImportResponse, ApiError> {
+    let attestations: Vec<IndexedAttestation<T::EthSpec>> =
+        serde_json::from_slice(&req.into_body()).map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to parse JSON into a list of IndexedAttestation: {:?}",
+                e
+            ))
+        })?;
+    let slasher = get_slasher(&ctx)?;
+
+    let head = ctx.beacon_chain.cached_head()?;
+    for (i, attestation) in attestations.iter().enumerate() {
+        is_valid_indexed_attestation(
+            &head.beacon_state,
+            attestation,
+            VerifySignatures::True,
+            &ctx.beacon_chain.spec,
+        )
+        .map_err(|e| {
+            ApiError::BadRequest(format!("Invalid attestation at index {}: {:?}", i, e))
+        })?;
+    }
+
+    let num_attestations = attestations.len();
+    let imported = slasher
+        .import_attestations(attestations, ctx.beacon_chain.epoch()?)
+        .map_err(|e| ApiError::ServerError(format!("Unable to import attestations: {:?}", e)))?;
+
+    Ok(SlasherImportResponse {
+        imported,
+        ignored: num_attestations - imported,
+    })
+}
+
+/// Streams each verified attestation and block to the client as a JSON `SlasherEvent`, for use
+/// by a slasher running in another process.
+pub fn stream_slasher_events<T: BeaconChainTypes>(ctx: Arc<Context<T>>) -> Result<Body, ApiError> {
+    let mut events = ctx
+        .beacon_chain
+        .slasher_stream
+        .as_ref()
+        .ok_or_else(|| {
+            ApiError::NotFound("The beacon node is not streaming to slashers".to_string())
+        })?
+        .lock()
+        .add_rx();
+    let (mut sender, body) = Body::channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            let chunk = match make_slasher_event_chunk(&event) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    error!(ctx.log, "Failed to make slasher SSE chunk"; "error" => e.to_string());
+                    sender.abort();
+                    break;
+                }
+            };
+            match block_on(sender.send_data(chunk)) {
+                Err(e) if e.is_closed() => break,
+                Err(e) => error!(ctx.log, "Couldn't stream slasher event {:?}", e),
+                Ok(_) => (),
+            }
+        }
+    });
+    Ok(body)
+}
+
+fn make_slasher_event_chunk<E: EthSpec>(event: &SlasherEvent<E>) -> std::io::Result<Bytes> {
+    let mut buffer = Vec::new();
+    {
+        let mut sse_message = uhttp_sse::SseMessage::new(&mut buffer);
+        serde_json::to_writer(sse_message.data()?, event)?;
+    }
+    let bytes: Bytes = buffer.into();
+    Ok(bytes)
+}
+
+/// Returns the slasher of the beacon node, or an error if it was started without one.
+fn get_slasher<T: BeaconChainTypes>(
+    ctx: &Context<T>,
+) -> Result<&Arc<Slasher<T::EthSpec>>, ApiError> {
+    ctx.beacon_chain
+        .slasher
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("The beacon node is not running a slasher".to_string()))
+}
+
+/// Parses the validator index which follows `prefix` in the path of `req`.
+fn parse_validator_index(req: &Request<Vec<u8>>, prefix: &str) -> Result<u64, ApiError> {
+    let index = req.uri().path().get(prefix.len()..).unwrap_or_default();
+    index
+        .parse()
+        .map_err(|e| ApiError::BadRequest(format!("Invalid validator index {}: {:?}", index, e)))
+}
+
+/// Parses a JSON `BanRequest` from the body of `req`.
+fn parse_ban_request(req: Request<Vec<u8>>) -> Result<BanTarget, ApiError> {
+    let request: BanRequest = serde_json::from_slice(&req.into_body()).map_err(|e| {
+        ApiError::BadRequest(format!("Unable to parse JSON into BanRequest: {:?}", e))
+    })?;
+
+    match (request.peer_id, request.ip) {
+        (Some(peer_id), None) => peer_id
+            .parse::<PeerId>()
+            .map(BanTarget::Peer)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid peer_id {}: {:?}", peer_id, e))),
+        (None, Some(ip)) => Ok(BanTarget::Ip(ip)),
+        _ => Err(ApiError::BadRequest(
+            "Exactly one of peer_id or ip must be provided".into(),
+        )),
+    }
+}
+
+/// Information returned by `database_info`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DatabaseInfo {
+    /// The version of the on-disk database format.
+    schema_version: u64,
+    /// States prior to this slot are stored in the freezer database.
+    split_slot: Slot,
+    split_state_root: Hash256,
+    /// The oldest available block and state, or `None` if the full history is available.
+    anchor: Option<AnchorInfo>,
+}
+
 /// Information returned by `peers` and `connected_peers`.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -46,3 +421,29 @@ pub struct Peer<T: EthSpec> {
     /// The PeerInfo associated with the peer.
     peer_info: PeerInfo<T>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn prune_snapshots_keeps_most_recent() {
+        let dir = tempdir().unwrap();
+        let snapshot = |timestamp: u64| dir.path().join(format!("snapshot_{}.lhdb", timestamp));
+        let other = dir.path().join("notes.txt");
+
+        for timestamp in &[9, 10, 1_000, 100] {
+            fs::write(snapshot(*timestamp), b"").unwrap();
+        }
+        fs::write(&other, b"").unwrap();
+
+        prune_snapshots(dir.path(), 2).unwrap();
+
+        assert!(snapshot(1_000).exists());
+        assert!(snapshot(100).exists());
+        assert!(!snapshot(10).exists());
+        assert!(!snapshot(9).exists());
+        assert!(other.exists(), "should not remove other files");
+    }
+}
@@ -29,6 +29,8 @@ pub struct Context<T: BeaconChainTypes> {
     pub db_path: PathBuf,
     pub freezer_db_path: PathBuf,
     pub events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    /// Held whilst a database snapshot is being exported.
+    pub snapshot_lock: Mutex<()>,
 }
 
 pub async fn on_http_request<T: BeaconChainTypes>(
@@ -210,6 +212,11 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(beacon::attester_slashing)
             .await?
             .serde_encodings(),
+        (Method::POST, "/beacon/voluntary_exit") => handler
+            .allow_body()
+            .in_blocking_task(beacon::voluntary_exit)
+            .await?
+            .serde_encodings(),
         (Method::POST, "/validator/duties") => handler
             .allow_body()
             .in_blocking_task(validator::post_validator_duties)
@@ -255,6 +262,16 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(validator::publish_aggregate_and_proofs)
             .await?
             .serde_encodings(),
+        (Method::POST, "/validator/sync_committee_messages") => handler
+            .allow_body()
+            .in_blocking_task(validator::publish_sync_committee_messages)
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/validator/contribution_and_proofs") => handler
+            .allow_body()
+            .in_blocking_task(validator::publish_contribution_and_proofs)
+            .await?
+            .serde_encodings(),
         (Method::GET, "/consensus/global_votes") => handler
             .allow_body()
             .in_blocking_task(consensus::get_vote_count)
@@ -307,6 +324,10 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(|_, ctx| Ok(ctx.network_globals.sync_state()))
             .await?
             .serde_encodings(),
+        (Method::GET, "/lighthouse/nat") => handler
+            .in_core_task(|_, ctx| Ok(ctx.network_globals.upnp_status()))
+            .await?
+            .serde_encodings(),
         (Method::GET, "/lighthouse/peers") => handler
             .in_blocking_task(|_, ctx| lighthouse::peers(ctx))
             .await?
@@ -315,6 +336,59 @@ async fn route<T: BeaconChainTypes>(
             .in_blocking_task(|_, ctx| lighthouse::connected_peers(ctx))
             .await?
             .serde_encodings(),
+        (Method::GET, "/lighthouse/bans") => handler
+            .in_blocking_task(|_, ctx| lighthouse::bans(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/bans/add") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::add_ban)
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/bans/remove") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::remove_ban)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/gossip_filter") => handler
+            .in_blocking_task(|_, ctx| lighthouse::gossip_filter(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/gossip_filter") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::update_gossip_filter)
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/eth1/health") => handler
+            .in_blocking_task(|_, ctx| lighthouse::eth1_health(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/database/info") => handler
+            .in_blocking_task(|_, ctx| lighthouse::database_info(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::POST, "/lighthouse/database/snapshot") => handler
+            .in_blocking_task(|_, ctx| lighthouse::database_snapshot(ctx))
+            .await?
+            .serde_encodings(),
+        (Method::GET, "/lighthouse/slasher/stream") => {
+            handler
+                .sse_stream(|_, ctx| lighthouse::stream_slasher_events(ctx))
+                .await
+        }
+        (Method::POST, "/lighthouse/slasher/attestations") => handler
+            .allow_body()
+            .in_blocking_task(lighthouse::import_slasher_attestations)
+            .await?
+            .serde_encodings(),
+        (Method::GET, path) if path.starts_with(lighthouse::SLASHER_ATTESTATIONS_PATH) => handler
+            .in_blocking_task(lighthouse::slasher_attestations)
+            .await?
+            .serde_encodings(),
+        (Method::GET, path) if path.starts_with(lighthouse::SLASHER_BLOCKS_PATH) => handler
+            .in_blocking_task(lighthouse::slasher_blocks)
+            .await?
+            .serde_encodings(),
         _ => Err(ApiError::NotFound(
             "Request path and/or method not found.".to_owned(),
         )),
@@ -2,7 +2,7 @@ use crate::helpers::{parse_hex_ssz_bytes, publish_beacon_block_to_network};
 use crate::{ApiError, Context, NetworkChannel, UrlQuery};
 use beacon_chain::{
     attestation_verification::Error as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
-    BlockError, ForkChoiceError, StateSkipConfig,
+    BlockError, ForkChoiceError, StateSkipConfig, SyncCommitteeError,
 };
 use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
@@ -14,7 +14,8 @@ use std::sync::Arc;
 use types::beacon_state::EthSpec;
 use types::{
     Attestation, AttestationData, BeaconBlock, BeaconState, Epoch, RelativeEpoch, SelectionProof,
-    SignedAggregateAndProof, SignedBeaconBlock, SubnetId,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SubnetId,
+    SyncCommitteeMessage,
 };
 
 /// HTTP Handler to retrieve the duties for a set of validators during a particular epoch. This
@@ -118,12 +119,12 @@ pub fn get_state_for_epoch<T: BeaconChainTypes>(
     config: StateSkipConfig,
 ) -> Result<BeaconState<T::EthSpec>, ApiError> {
     let slots_per_epoch = T::EthSpec::slots_per_epoch();
-    let head = beacon_chain.head()?;
+    let head = beacon_chain.cached_head()?;
     let current_epoch = beacon_chain.epoch()?;
     let head_epoch = head.beacon_state.current_epoch();
 
     if head_epoch == current_epoch && RelativeEpoch::from_epoch(current_epoch, epoch).is_ok() {
-        Ok(head.beacon_state)
+        Ok(head.beacon_state.clone_with_only_committee_caches())
     } else {
         // If epoch is ahead of current epoch, then it should be a "next epoch" request for
         // attestation duties. So, go to the start slot of the epoch prior to that,
@@ -165,20 +166,17 @@ fn return_validator_duties<T: BeaconChainTypes>(
     // Used for quickly determining the slot for a proposer.
     let validator_proposers = if epoch == state.current_epoch() {
         Some(
-            epoch
-                .slot_iter(T::EthSpec::slots_per_epoch())
-                .map(|slot| {
-                    state
-                        .get_beacon_proposer_index(slot, &beacon_chain.spec)
-                        .map(|i| (i, slot))
-                        .map_err(|e| {
-                            ApiError::ServerError(format!(
-                                "Unable to get proposer index for validator: {:?}",
-                                e
-                            ))
-                        })
-                })
-                .collect::<Result<Vec<_>, _>>()?,
+            beacon_chain
+                .canonical_beacon_proposers(&state)
+                .map_err(|e| {
+                    ApiError::ServerError(format!(
+                        "Unable to get proposer indices for epoch: {:?}",
+                        e
+                    ))
+                })?
+                .into_iter()
+                .zip(epoch.slot_iter(T::EthSpec::slots_per_epoch()))
+                .collect::<Vec<_>>(),
         )
     } else {
         None
@@ -337,7 +335,7 @@ pub fn publish_beacon_block<T: BeaconChainTypes>(
                             // - Excessive time between block produce and publish.
                             // - A validator is using another beacon node to produce blocks and
                             // submitting them here.
-                            if ctx.beacon_chain.head()?.beacon_block_root != block_root {
+                            if ctx.beacon_chain.head_info()?.block_root != block_root {
                                 warn!(
                                     ctx.log,
                                     "Block from validator is not head";
@@ -659,6 +657,173 @@ fn process_aggregated_attestation<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// HTTP Handler to publish a list of `SyncCommitteeMessage`, each paired with the sync committee
+/// subnet it should be published on.
+pub fn publish_sync_committee_messages<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    let body = req.into_body();
+
+    serde_json::from_slice(&body)
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to deserialize JSON into a list of sync committee messages: {:?}",
+                e
+            ))
+        })
+        // Process all of the messages _without_ exiting early if one fails.
+        .map(move |messages: Vec<(SyncCommitteeMessage, u64)>| {
+            messages
+                .into_iter()
+                .enumerate()
+                .map(|(i, (message, subnet_id))| {
+                    let verified = ctx
+                        .beacon_chain
+                        .verify_sync_committee_message_for_gossip(message.clone(), subnet_id)
+                        .map_err(|e| {
+                            handle_sync_committee_error(
+                                e,
+                                &format!("sync committee message {} failed verification", i),
+                                &ctx.log,
+                            )
+                        })?;
+
+                    publish_to_network(
+                        &ctx.network_chan,
+                        PubsubMessage::SyncCommitteeMessage(Box::new((subnet_id, message))),
+                        &format!("sync committee message {}", i),
+                    )?;
+
+                    verified.add_to_pool(&ctx.beacon_chain).map_err(|e| {
+                        handle_sync_committee_error(
+                            e,
+                            &format!("sync committee message {} was unable to be pooled", i),
+                            &ctx.log,
+                        )
+                    })?;
+
+                    Ok(())
+                })
+                .collect::<Vec<Result<_, _>>>()
+        })
+        // Iterate through all the results and return on the first `Err`.
+        //
+        // Note: this will only provide info about the _first_ failure, not all failures.
+        .and_then(|processing_results| processing_results.into_iter().try_for_each(|result| result))
+}
+
+/// HTTP Handler to publish a list of `SignedContributionAndProof`, which have been signed by
+/// sync committee aggregators.
+pub fn publish_contribution_and_proofs<T: BeaconChainTypes>(
+    req: Request<Vec<u8>>,
+    ctx: Arc<Context<T>>,
+) -> Result<(), ApiError> {
+    let body = req.into_body();
+
+    serde_json::from_slice(&body)
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to deserialize JSON into a list of SignedContributionAndProof: {:?}",
+                e
+            ))
+        })
+        // Process all of the contributions _without_ exiting early if one fails.
+        .map(
+            move |contributions: Vec<SignedContributionAndProof<T::EthSpec>>| {
+                contributions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, contribution)| {
+                        let verified = match ctx
+                            .beacon_chain
+                            .verify_sync_contribution_for_gossip(contribution.clone())
+                        {
+                            Ok(verified) => verified,
+                            // An identical contribution from the same aggregator has already
+                            // been published, there is nothing more to do.
+                            Err(SyncCommitteeError::AggregatorAlreadyKnown(_)) => return Ok(()),
+                            Err(e) => {
+                                return Err(handle_sync_committee_error(
+                                    e,
+                                    &format!("sync contribution {} failed verification", i),
+                                    &ctx.log,
+                                ))
+                            }
+                        };
+
+                        publish_to_network(
+                            &ctx.network_chan,
+                            PubsubMessage::SignedContributionAndProof(Box::new(contribution)),
+                            &format!("sync contribution {}", i),
+                        )?;
+
+                        verified.add_to_pool(&ctx.beacon_chain).map_err(|e| {
+                            handle_sync_committee_error(
+                                e,
+                                &format!("sync contribution {} was unable to be pooled", i),
+                                &ctx.log,
+                            )
+                        })?;
+
+                        Ok(())
+                    })
+                    .collect::<Vec<Result<_, _>>>()
+            },
+        )
+        // Iterate through all the results and return on the first `Err`.
+        //
+        // Note: this will only provide info about the _first_ failure, not all failures.
+        .and_then(|processing_results| processing_results.into_iter().try_for_each(|result| result))
+}
+
+/// Sends a single gossip message to the network service.
+fn publish_to_network<E: EthSpec>(
+    network_chan: &NetworkChannel<E>,
+    message: PubsubMessage<E>,
+    detail: &str,
+) -> Result<(), ApiError> {
+    network_chan
+        .send(NetworkMessage::Publish {
+            messages: vec![message],
+        })
+        .map_err(|e| {
+            ApiError::ServerError(format!("Unable to send {} to network: {:?}", detail, e))
+        })
+}
+
+/// Common handler for `SyncCommitteeError` during sync committee message verification.
+fn handle_sync_committee_error(e: SyncCommitteeError, detail: &str, log: &Logger) -> ApiError {
+    match e {
+        SyncCommitteeError::BeaconChainError(e) => {
+            error!(
+                log,
+                "Internal error verifying local sync message";
+                "detail" => detail,
+                "error" => format!("{:?}", e),
+            );
+
+            ApiError::ServerError(format!(
+                "Internal error verifying local sync message. Error: {:?}. Detail: {}",
+                e, detail
+            ))
+        }
+        e => {
+            error!(
+                log,
+                "Invalid local sync message";
+                "detail" => detail,
+                "reason" => format!("{:?}", e),
+            );
+
+            ApiError::ProcessingError(format!(
+                "Invalid local sync message. Error: {:?} Detail: {}",
+                e, detail
+            ))
+        }
+    }
+}
+
 /// Common handler for `AttnError` during attestation verification.
 fn handle_attestation_error(
     e: AttnError,
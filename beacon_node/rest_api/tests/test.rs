@@ -22,7 +22,7 @@ use types::{
     },
     BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, MinimalEthSpec, PublicKey,
     RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
-    SubnetId, Validator,
+    SubnetId, Validator, VoluntaryExit,
 };
 
 type E = MinimalEthSpec;
@@ -1136,6 +1136,80 @@ fn proposer_slashing() {
     assert_eq!(proposer_slashing, proposer_slashings[0]);
 }
 
+#[test]
+fn voluntary_exit() {
+    let mut env = build_env();
+    // Allow the genesis validators to exit straight away.
+    env.eth2_config.spec.shard_committee_period = 0;
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+
+    let state = chain
+        .head()
+        .expect("should have retrieved state")
+        .beacon_state;
+
+    let spec = &chain.spec;
+
+    // Check that there are no voluntary exits before insertion
+    assert_eq!(chain.op_pool.num_voluntary_exits(), 0);
+
+    let validator_index = 0;
+    let exit = VoluntaryExit {
+        epoch: state.current_epoch(),
+        validator_index,
+    };
+
+    let invalid_exit = exit.clone().sign(
+        &generate_deterministic_keypair(validator_index as usize + 1).sk,
+        &state.fork,
+        state.genesis_validators_root,
+        spec,
+    );
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().voluntary_exit(invalid_exit));
+    assert!(
+        result.is_err(),
+        "exit with an invalid signature should fail"
+    );
+
+    // Nothing should have been inserted
+    assert_eq!(chain.op_pool.num_voluntary_exits(), 0);
+
+    let valid_exit = exit.sign(
+        &generate_deterministic_keypair(validator_index as usize).sk,
+        &state.fork,
+        state.genesis_validators_root,
+        spec,
+    );
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().voluntary_exit(valid_exit.clone()))
+        .expect("should publish valid exit");
+    assert!(result, "valid exit should be accepted");
+
+    // Length should be just one as we've inserted only one exit
+    let voluntary_exits = chain.op_pool.get_voluntary_exits(&state, spec);
+    assert_eq!(voluntary_exits, vec![valid_exit.clone()]);
+
+    let result = env
+        .runtime()
+        .block_on(remote_node.http.beacon().voluntary_exit(valid_exit.clone()));
+    assert!(result.is_err(), "duplicate exit should fail");
+
+    // Length should still be one as the duplicate wasn't inserted
+    let voluntary_exits = chain.op_pool.get_voluntary_exits(&state, spec);
+    assert_eq!(voluntary_exits, vec![valid_exit]);
+}
+
 #[test]
 fn attester_slashing() {
     let mut env = build_env();
@@ -53,6 +53,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("9000")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("listen-address6")
+                .long("listen-address6")
+                .value_name("ADDRESS")
+                .help("An IPv6 address lighthouse will listen for TCP connections on, in addition to \
+                --listen-address. Use this to run in dual-stack mode. For IPv6-only hosts, set \
+                --listen-address to an IPv6 address instead.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("port6")
+                .long("port6")
+                .value_name("PORT")
+                .help("The TCP port to listen on for --listen-address6. Defaults to `port`")
+                .requires("listen-address6")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("discovery-port")
                 .long("discovery-port")
@@ -67,6 +84,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("50")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("max-inbound-per-ip")
+                .long("max-inbound-per-ip")
+                .value_name("COUNT")
+                .help("The maximum number of concurrent inbound connections accepted from a single IP address. \
+                       Connections from loopback addresses are not limited.")
+                .default_value("5")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("max-inbound-per-prefix")
+                .long("max-inbound-per-prefix")
+                .value_name("COUNT")
+                .help("The maximum number of concurrent inbound connections accepted from a single /24 (IPv4) \
+                       or /64 (IPv6) network prefix.")
+                .default_value("20")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("boot-nodes")
                 .long("boot-nodes")
@@ -102,12 +137,30 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("enr-udp-port")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("enr-address6")
+                .long("enr-address6")
+                .value_name("ADDRESS")
+                .help("The IPv6 address to broadcast to other peers in the `ip6` field of the local ENR. \
+                Set this only if you are sure other nodes can connect to your local node on this address.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("enr-tcp6-port")
+                .long("enr-tcp6-port")
+                .value_name("PORT")
+                .help("The TCP port broadcast in the `tcp6` field of the local ENR. Set this only if you are \
+                sure other nodes can connect to your local node on this port. The --port6 flag is used if \
+                this is not set.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enr-match")
                 .short("e")
                 .long("enr-match")
                 .help("Sets the local ENR IP address and port to match those set for lighthouse. \
-                Specifically, the IP address will be the value of --listen-address and the UDP port will be --discovery-port.")
+                Specifically, the IP address will be the value of --listen-address (and --listen-address6 if set) \
+                and the UDP port will be --discovery-port.")
         )
         .arg(
             Arg::with_name("disable-enr-auto-update")
@@ -115,7 +168,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("disable-enr-auto-update")
                 .help("Discovery automatically updates the nodes local ENR with an external IP address and port as seen by other peers on the network. \
                 This disables this feature, fixing the ENR's IP/PORT to those specified on boot.")
-                .takes_value(true),
+                .takes_value(false),
         )
         .arg(
             Arg::with_name("libp2p-addresses")
@@ -125,12 +178,78 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        without an ENR.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("trusted-peers")
+                .long("trusted-peers")
+                .value_name("TRUSTED_PEERS")
+                .help("One or more comma-delimited ENRs or multiaddrs (including the p2p protocol) of trusted peers. \
+                       Trusted peers are always dialed, are never scored down or banned and are exempt from pruning.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-discovery")
                 .long("disable-discovery")
                 .help("Disables the discv5 discovery protocol. The node will not search for new peers or participate in the discovery protocol.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("discovery-query-parallelism")
+                .long("discovery-query-parallelism")
+                .value_name("COUNT")
+                .help("The number of peers contacted in parallel during a discv5 query.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("discovery-session-cache-size")
+                .long("discovery-session-cache-size")
+                .value_name("COUNT")
+                .help("The maximum number of established discv5 sessions to cache. Nodes behind NATs \
+                       may benefit from a larger cache to avoid repeated session handshakes.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("discovery-disable-ip-limit")
+                .long("discovery-disable-ip-limit")
+                .help("Disables the discv5 routing table filter which limits the number of nodes \
+                       from the same /24 subnet. Useful for local testnets where many nodes share an IP range.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("discovery-disable-packet-filter")
+                .long("discovery-disable-packet-filter")
+                .help("Disables the discv5 packet filter which rate limits and bans misbehaving IP addresses.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("disable-listen")
+                .long("disable-listen")
+                .help("Outbound-only mode. The node will connect to peers but will not bind any inbound ports \
+                       or advertise an address in its ENR. Useful behind strict NATs or firewalls. Other nodes \
+                       will be unable to connect to this node, so it may find fewer peers.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("gossip-blocks-only")
+                .long("gossip-blocks-only")
+                .help("Only subscribe to the beacon block gossip topic. The node will follow the head of the \
+                       chain but will not receive or relay attestations, exits or slashings. Not suitable \
+                       for nodes serving validators.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("disable-subnets")
+                .long("disable-subnets")
+                .value_name("SUBNET_IDS")
+                .help("One or more comma-delimited attestation subnet ids which will never be subscribed to. \
+                       The gossip filter can also be changed at runtime via the HTTP API.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable-upnp")
+                .long("disable-upnp")
+                .help("Disables UPnP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
+                .takes_value(false),
+        )
 
         /* REST API related arguments */
         .arg(
@@ -163,6 +282,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-allow-snapshots")
+                .long("http-allow-snapshots")
+                .help("Allow database snapshots to be exported via the HTTP API (see `lighthouse db \
+                       snapshot export --beacon-node`). Each snapshot is a full copy of the \
+                       database, written alongside it. Disabled by default.")
+                .takes_value(false),
+        )
         /* Websocket related arguments */
         .arg(
             Arg::with_name("ws")
@@ -193,15 +320,22 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("eth1")
                 .long("eth1")
+                .value_name("BOOLEAN")
                 .help("If present the node will connect to an eth1 node. This is required for \
-                       block production, you must use this flag if you wish to serve a validator.")
-                .takes_value(false),
+                       block production, you must use this flag if you wish to serve a validator. \
+                       Use --eth1=false to produce blocks without eth1 at all: blocks vote for the \
+                       existing eth1 data and never include deposits. Intended for devnets.")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .possible_values(&["true", "false"]),
         )
         .arg(
             Arg::with_name("dummy-eth1")
                 .long("dummy-eth1")
                 .conflicts_with("eth1")
-                .help("If present, uses an eth1 backend that generates static dummy data.\
+                .help("If present, uses an eth1 backend that generates deterministic dummy data \
+                      for each eth1 voting period, without connecting to an eth1 node. \
                       Identical to the method used at the 2019 Canada interop.")
         )
         .arg(
@@ -211,13 +345,110 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies the server for a web3 connection to the Eth1 chain. Also enables the --eth1 flag. Defaults to http://127.0.0.1:8545.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("eth1-endpoints")
+                .long("eth1-endpoints")
+                .value_name("HTTP-ENDPOINTS")
+                .conflicts_with("eth1-endpoint")
+                .help("One or more comma-delimited servers for web3 connections to the Eth1 chain, \
+                       in order of preference. The first healthy server is used and the others \
+                       serve as fallbacks. Also enables the --eth1 flag.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-ws-endpoint")
+                .long("eth1-ws-endpoint")
+                .value_name("WS-ENDPOINT")
+                .help("Specifies a ws:// or wss:// server used to subscribe to new Eth1 blocks and \
+                       deposit logs, instead of polling the HTTP endpoints for them. Blocks and logs \
+                       are still downloaded from the HTTP endpoints. Polling resumes whenever the \
+                       subscription fails.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("deposit-snapshot")
+                .long("deposit-snapshot")
+                .value_name("PATH_OR_URL")
+                .help("Specifies a file or http(s) URL containing a finalized deposit tree snapshot \
+                       (EIP-4881 JSON). When starting without an eth1 cache on disk, the deposit \
+                       cache is initialized from the snapshot and only the deposit logs after the \
+                       snapshot are downloaded. Deposits prior to the snapshot cannot be included \
+                       in blocks.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-block-cache-margin")
+                .long("eth1-block-cache-margin")
+                .value_name("BLOCKS")
+                .help("The number of Eth1 blocks to retain in memory in addition to those which \
+                       may be voted upon (the follow distance plus one voting period). Older \
+                       blocks are pruned from the cache.")
+                .takes_value(true)
+                .default_value("256")
+        )
+        .arg(
+            Arg::with_name("eth1-follow-distance")
+                .long("eth1-follow-distance")
+                .value_name("BLOCKS")
+                .help("Overrides the ETH1_FOLLOW_DISTANCE of the chain spec, which determines how \
+                       far behind the Eth1 head the deposit and block caches follow and which Eth1 \
+                       blocks are voted for. Intended for testnets, where all nodes must use the \
+                       same value.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slots-per-restore-point")
                 .long("slots-per-restore-point")
                 .value_name("SLOT_COUNT")
                 .help("Specifies how often a freezer DB restore point should be stored. \
-                       DO NOT DECREASE AFTER INITIALIZATION. [default: 2048 (mainnet) or 64 (minimal)]")
+                       Lower values use more disk space but make historic states faster to load. \
+                       Must be a multiple of the slots per epoch and a divisor of \
+                       SLOTS_PER_HISTORICAL_ROOT. Cannot be changed after the database is \
+                       initialized. [default: 2048 (mainnet) or 64 (minimal)]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("restore-points-per-snapshot")
+                .long("restore-points-per-snapshot")
+                .value_name("COUNT")
+                .help("Specifies how many freezer DB restore points lie between each full state \
+                       snapshot. Other restore points are stored as a compact diff against the \
+                       previous restore point. Set to 1 to store all restore points in full.")
+                .default_value("16")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("auto-compact-db")
+                .long("auto-compact-db")
+                .value_name("BOOLEAN")
+                .help("Enable or disable automatic compaction of the database on finalization.")
+                .takes_value(true)
+                .possible_values(&["true", "false"])
+                .default_value("true")
+        )
+        .arg(
+            Arg::with_name("db-backend")
+                .long("db-backend")
+                .value_name("BACKEND")
+                .help("Specifies the key-value store used for the hot and freezer databases. \
+                       The sqlite backend is only available if Lighthouse was compiled with the \
+                       `sqlite` feature. An existing database can be converted with \
+                       `lighthouse db migrate-backend`.")
+                .takes_value(true)
+                .possible_values(&["leveldb", "sqlite"])
+                .default_value("leveldb")
+        )
+        .arg(
+            Arg::with_name("db-compression")
+                .long("db-compression")
+                .value_name("CODEC")
+                .help("Compresses newly written blocks and states in the database. Either a single \
+                       codec (none, snappy or zstd) to apply to all compressible columns, or a \
+                       comma-separated list of column=codec pairs, where the columns are blocks, \
+                       states and state-diffs (e.g. blocks=snappy,states=zstd). Existing values \
+                       remain readable if this setting is changed.")
                 .takes_value(true)
+                .default_value("none")
         )
         .arg(
             Arg::with_name("block-cache-size")
@@ -226,6 +457,15 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies how many blocks the database should cache in memory [default: 5]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("state-cache-size-mb")
+                .long("state-cache-size-mb")
+                .value_name("MEGABYTES")
+                .help("Specifies the approximate memory that the database may use to cache states, \
+                       in megabytes. The least recently used states are evicted once the limit is \
+                       reached. A value of 0 disables the cache [default: 128]")
+                .takes_value(true)
+        )
 
         /*
          * Purge.
@@ -233,7 +473,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("purge-db")
                 .long("purge-db")
-                .help("If present, the chain database will be deleted. Use with caution.")
+                .help("If present, the chain and freezer databases will be deleted before \
+                       starting. The network and validator keys are preserved. Use with caution.")
+        )
+        .arg(
+            Arg::with_name("read-only")
+                .long("read-only")
+                .help("Open an existing database without writing to it and serve the HTTP API, \
+                       without connecting to the network or an eth1 node. Intended for running \
+                       expensive queries against a snapshot of a node's database (see \
+                       `lighthouse db snapshot`), isolated from the node itself.")
+                .conflicts_with("purge-db")
         )
 
         /*
@@ -254,6 +504,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("max-skip-slots")
                 .help(
                     "Refuse to skip more than this many slots when processing a block or attestation. \
+                    This applies to the distance from a block to its parent and from an \
+                    attestation to its head and target blocks. \
                     This prevents nodes on minority forks from wasting our time and RAM, \
                     but might need to be raised or set to 'none' in times of extreme network \
                     outage."
@@ -262,4 +514,207 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("700")
         )
+        .arg(
+            Arg::with_name("genesis-state-url")
+                .long("genesis-state-url")
+                .value_name("URL")
+                .help("Download the SSZ-encoded genesis state from this URL when starting a new \
+                       database, rather than computing it from the eth1 chain. Takes precedence \
+                       over any genesis state in the testnet directory.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("genesis-state-root")
+                .long("genesis-state-root")
+                .value_name("ROOT")
+                .help("The 0x-prefixed tree hash root that the state downloaded from \
+                       --genesis-state-url must have. Strongly recommended, since the download \
+                       is otherwise trusted.")
+                .requires("genesis-state-url")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("wss-checkpoint")
+                .long("wss-checkpoint")
+                .help(
+                    "Specify a weak subjectivity checkpoint in `block_root:epoch` format to verify \
+                     the node's sync against. The block root should be 0x-prefixed. The node will \
+                     refuse to follow any chain that finalizes the checkpoint epoch without \
+                     including this block."
+                )
+                .value_name("WSS_CHECKPOINT")
+                .takes_value(true)
+        )
+        /*
+         * Chain alerts.
+         */
+        .arg(
+            Arg::with_name("reorg-alert-depth")
+                .long("reorg-alert-depth")
+                .help(
+                    "Raise a consensus alert when the head re-orgs to a chain that diverged more \
+                    than this many slots ago. Set to 'none' to disable."
+                )
+                .value_name("NUM_SLOTS")
+                .takes_value(true)
+                .default_value("2")
+        )
+        .arg(
+            Arg::with_name("finality-stall-alert-epochs")
+                .long("finality-stall-alert-epochs")
+                .help(
+                    "Raise a consensus alert when the finalized epoch is more than this many \
+                    epochs behind the current epoch. Set to 'none' to disable."
+                )
+                .value_name("NUM_EPOCHS")
+                .takes_value(true)
+                .default_value("4")
+        )
+        .arg(
+            Arg::with_name("justified-flip-alert-threshold")
+                .long("justified-flip-alert-threshold")
+                .help(
+                    "Raise a consensus alert when the justified checkpoint of the head regresses \
+                    or changes root this many times within a few epochs. Set to 'none' to disable."
+                )
+                .value_name("NUM_FLIPS")
+                .takes_value(true)
+                .default_value("2")
+        )
+        .arg(
+            Arg::with_name("reconstruct-historic-states")
+                .long("reconstruct-historic-states")
+                .help(
+                    "Rebuild the states prior to the state the database was initialized from, by \
+                    replaying blocks from genesis in the background. The blocks are downloaded \
+                    from the archive beacon node at this URL, and are checked against the \
+                    initial state before any historic state becomes available."
+                )
+                .value_name("URL")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("alert-webhook-url")
+                .long("alert-webhook-url")
+                .help(
+                    "POST each consensus alert (deep re-orgs, finality stalls and justification \
+                    flip-flopping) to this URL as JSON."
+                )
+                .value_name("URL")
+                .takes_value(true)
+        )
+        /*
+         * Slasher.
+         */
+        .arg(
+            Arg::with_name("slasher")
+                .long("slasher")
+                .help(
+                    "Run a slasher alongside the beacon node. It checks every attestation and \
+                    block received for slashable offences. It is currently only recommended \
+                    for expert users because of the immaturity of the slasher UX and the extra \
+                    resources required."
+                )
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .help(
+                    "Set the slasher's database directory."
+                )
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("slasher")
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .help(
+                    "Configure how many epochs of history the slasher keeps. Immutable after \
+                    initialization. Must be a multiple of the chunk size (16)."
+                )
+                .value_name("EPOCHS")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-max-db-size")
+                .long("slasher-max-db-size")
+                .help(
+                    "Maximum size of the slasher database in gigabytes. Writes to the database \
+                    fail once this size is reached."
+                )
+                .value_name("GIGABYTES")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-update-period")
+                .long("slasher-update-period")
+                .help(
+                    "Configure how often the slasher processes the attestations and blocks it \
+                    has queued, in seconds."
+                )
+                .value_name("SECONDS")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-chunk-size")
+                .long("slasher-chunk-size")
+                .help(
+                    "Number of epochs per validator per chunk stored on disk. Immutable after \
+                    initialization. Must divide the history length."
+                )
+                .value_name("EPOCHS")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-validator-chunk-size")
+                .long("slasher-validator-chunk-size")
+                .help(
+                    "Number of validators per chunk stored on disk. Immutable after \
+                    initialization."
+                )
+                .value_name("NUM_VALIDATORS")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-max-queued-attestations")
+                .long("slasher-max-queued-attestations")
+                .help(
+                    "Maximum number of attestations waiting to be processed by the slasher. If \
+                    the slasher falls behind, the oldest queued attestations are dropped."
+                )
+                .value_name("COUNT")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .help(
+                    "Set the database backend used by the slasher. The mdbx backend is only \
+                    available if Lighthouse was compiled with the slasher-mdbx feature."
+                )
+                .value_name("BACKEND")
+                .requires("slasher")
+                .takes_value(true)
+                .possible_values(&["lmdb", "mdbx"])
+                .default_value("lmdb")
+        )
+        .arg(
+            Arg::with_name("slasher-stream")
+                .long("slasher-stream")
+                .help(
+                    "Stream every verified attestation and block to slashers running in other \
+                    processes, via the /lighthouse/slasher/stream HTTP endpoint. Unlike the \
+                    other slasher flags this does not require --slasher, allowing the slasher \
+                    to run on different hardware using the `lighthouse slasher` command."
+                )
+                .takes_value(false)
+        )
 }
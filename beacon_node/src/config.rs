@@ -1,17 +1,17 @@
-use beacon_chain::builder::PUBKEY_CACHE_FILENAME;
+use beacon_chain::builder::{LEGACY_PUBKEY_CACHE_FILENAME, PUBKEY_CACHE_FILENAME};
 use clap::ArgMatches;
 use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{config::DEFAULT_DATADIR, ClientConfig, ClientGenesis};
-use eth2_libp2p::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig};
+use eth2_libp2p::{multiaddr::Protocol, Enr, EnrExt, Multiaddr, NetworkConfig};
 use eth2_testnet_config::Eth2TestnetConfig;
 use slog::{crit, info, Logger};
 use ssz::Encode;
 use std::cmp;
 use std::fs;
-use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
 use std::path::PathBuf;
-use types::{ChainSpec, EthSpec, GRAFFITI_BYTES_LEN};
+use types::{ChainSpec, Checkpoint, Epoch, EthSpec, Hash256, GRAFFITI_BYTES_LEN};
 
 pub const BEACON_NODE_DIR: &str = "beacon";
 pub const NETWORK_DIR: &str = "network";
@@ -34,32 +34,6 @@ pub fn get_config<E: EthSpec>(
 
     client_config.data_dir = get_data_dir(cli_args);
 
-    // If necessary, remove any existing database and configuration
-    if client_config.data_dir.exists() && cli_args.is_present("purge-db") {
-        // Remove the chain_db.
-        fs::remove_dir_all(
-            client_config
-                .get_db_path()
-                .ok_or_else(|| "Failed to get db_path".to_string())?,
-        )
-        .map_err(|err| format!("Failed to remove chain_db: {}", err))?;
-
-        // Remove the freezer db.
-        fs::remove_dir_all(
-            client_config
-                .get_freezer_db_path()
-                .ok_or_else(|| "Failed to get freezer db path".to_string())?,
-        )
-        .map_err(|err| format!("Failed to remove chain_db: {}", err))?;
-
-        // Remove the pubkey cache file if it exists
-        let pubkey_cache_file = client_config.data_dir.join(PUBKEY_CACHE_FILENAME);
-        if pubkey_cache_file.exists() {
-            fs::remove_file(&pubkey_cache_file)
-                .map_err(|e| format!("Failed to remove {:?}: {:?}", pubkey_cache_file, e))?;
-        }
-    }
-
     // Create `datadir` and any non-existing parent directories.
     fs::create_dir_all(&client_config.data_dir)
         .map_err(|e| format!("Failed to create data dir: {}", e))?;
@@ -83,6 +57,19 @@ pub fn get_config<E: EthSpec>(
         false,
     )?;
 
+    if let Some(subnet_id) = client_config
+        .network
+        .gossip_filter
+        .disabled_subnets
+        .iter()
+        .find(|subnet_id| **subnet_id >= spec.attestation_subnet_count)
+    {
+        return Err(format!(
+            "Invalid subnet id {}, there are {} subnets",
+            subnet_id, spec.attestation_subnet_count
+        ));
+    }
+
     /*
      * Http server
      */
@@ -112,6 +99,10 @@ pub fn get_config<E: EthSpec>(
         client_config.rest_api.allow_origin = allow_origin.to_string();
     }
 
+    if cli_args.is_present("http-allow-snapshots") {
+        client_config.rest_api.allow_database_snapshots = true;
+    }
+
     /*
      * Websocket server
      */
@@ -145,15 +136,51 @@ pub fn get_config<E: EthSpec>(
 
     // When present, attempt to sync to an eth1 node.
     //
-    // Required for block production.
-    if cli_args.is_present("eth1") {
-        client_config.sync_eth1_chain = true;
+    // Required for block production, unless eth1 is disabled with `--eth1=false`.
+    match cli_args.value_of("eth1") {
+        Some("false") => client_config.disable_eth1 = true,
+        _ if cli_args.is_present("eth1") => client_config.sync_eth1_chain = true,
+        _ => (),
     }
 
     // Defines the URL to reach the eth1 node.
     if let Some(val) = cli_args.value_of("eth1-endpoint") {
         client_config.sync_eth1_chain = true;
-        client_config.eth1.endpoint = val.to_string();
+        client_config.eth1.endpoints = vec![val.to_string()];
+    }
+
+    // Defines the URLs to reach the eth1 nodes, in order of preference.
+    if let Some(val) = cli_args.value_of("eth1-endpoints") {
+        client_config.sync_eth1_chain = true;
+        client_config.eth1.endpoints = val
+            .split(',')
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(String::from)
+            .collect();
+        if client_config.eth1.endpoints.is_empty() {
+            return Err("--eth1-endpoints must contain at least one endpoint".to_string());
+        }
+    }
+
+    // Defines the URL used to subscribe to eth1 notifications.
+    if let Some(val) = cli_args.value_of("eth1-ws-endpoint") {
+        if !(val.starts_with("ws://") || val.starts_with("wss://")) {
+            return Err(format!(
+                "--eth1-ws-endpoint must be a ws:// or wss:// URL, not {}",
+                val
+            ));
+        }
+        client_config.eth1.ws_endpoint = Some(val.to_string());
+    }
+
+    if client_config.disable_eth1 && client_config.sync_eth1_chain {
+        return Err("--eth1=false cannot be used with eth1 endpoints".to_string());
+    }
+
+    // Defines the snapshot used to initialize the deposit cache.
+    if let Some(val) = cli_args.value_of("deposit-snapshot") {
+        client_config.eth1.deposit_snapshot = Some(val.to_string());
     }
 
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
@@ -171,12 +198,33 @@ pub fn get_config<E: EthSpec>(
         );
     }
 
+    client_config.store.restore_points_per_snapshot =
+        clap_utils::parse_required(cli_args, "restore-points-per-snapshot")?;
+
+    client_config.store.compact_on_prune = clap_utils::parse_required(cli_args, "auto-compact-db")?;
+
+    client_config.store.backend = clap_utils::parse_required(cli_args, "db-backend")?;
+
+    client_config.store.compression = clap_utils::parse_required(cli_args, "db-compression")?;
+
     if let Some(block_cache_size) = cli_args.value_of("block-cache-size") {
         client_config.store.block_cache_size = block_cache_size
             .parse()
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    if let Some(state_cache_size_mb) = cli_args.value_of("state-cache-size-mb") {
+        client_config.store.state_cache_size_mb = state_cache_size_mb
+            .parse()
+            .map_err(|_| "state-cache-size-mb is not a valid integer".to_string())?;
+    }
+
+    if cli_args.is_present("read-only") {
+        client_config.store.read_only = true;
+        // Serving the HTTP API is the only purpose of a read-only node.
+        client_config.rest_api.enabled = true;
+    }
+
     if spec_constants != client_config.spec_constants {
         crit!(log, "Specification constants do not match.";
               "client_config" => client_config.spec_constants,
@@ -218,12 +266,29 @@ pub fn get_config<E: EthSpec>(
     client_config.eth1.lowest_cached_block_number =
         client_config.eth1.deposit_contract_deploy_block;
     client_config.eth1.follow_distance = spec.eth1_follow_distance;
+    // Only override the default (Goerli) eth1 network and chain id if the testnet specifies them.
+    if let Some(yaml_config) = &eth2_testnet_config.yaml_config {
+        if yaml_config.deposit_network_id().is_some() {
+            client_config.eth1.network_id = spec.deposit_network_id.into();
+        }
+        if yaml_config.deposit_chain_id().is_some() {
+            client_config.eth1.chain_id = Some(spec.deposit_chain_id);
+        }
+    }
+    client_config.eth1.block_cache_margin =
+        clap_utils::parse_required(cli_args, "eth1-block-cache-margin")?;
+    client_config.eth1.set_block_cache_truncation::<E>(spec);
 
     if let Some(mut boot_nodes) = eth2_testnet_config.boot_enr {
         client_config.network.boot_nodes_enr.append(&mut boot_nodes)
     }
 
-    if let Some(genesis_state) = eth2_testnet_config.genesis_state {
+    if let Some(url) = cli_args.value_of("genesis-state-url") {
+        client_config.genesis = ClientGenesis::GenesisStateUrl {
+            url: url.to_string(),
+            genesis_state_root: clap_utils::parse_ssz_optional(cli_args, "genesis-state-root")?,
+        };
+    } else if let Some(genesis_state) = eth2_testnet_config.genesis_state {
         // Note: re-serializing the genesis state is not so efficient, however it avoids adding
         // trait bounds to the `ClientGenesis` enum. This would have significant flow-on
         // effects.
@@ -261,9 +326,152 @@ pub fn get_config<E: EthSpec>(
         };
     }
 
+    if let Some(wss_checkpoint) = cli_args.value_of("wss-checkpoint") {
+        client_config.chain.weak_subjectivity_checkpoint =
+            Some(parse_wss_checkpoint(wss_checkpoint)?);
+    }
+
+    if let Some(reorg_alert_depth) = cli_args.value_of("reorg-alert-depth") {
+        client_config.chain.reorg_alert_depth = match reorg_alert_depth {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid reorg-alert-depth".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(stall_epochs) = cli_args.value_of("finality-stall-alert-epochs") {
+        client_config.chain.finality_stall_alert_epochs = match stall_epochs {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid finality-stall-alert-epochs".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(threshold) = cli_args.value_of("justified-flip-alert-threshold") {
+        client_config.chain.justified_flip_alert_threshold = match threshold {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid justified-flip-alert-threshold".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(url) = cli_args.value_of("alert-webhook-url") {
+        client_config.alert_webhook_url = Some(url.to_string());
+    }
+
+    if cli_args.is_present("slasher") {
+        let slasher_dir = if let Some(slasher_dir) = cli_args.value_of("slasher-dir") {
+            PathBuf::from(slasher_dir)
+        } else {
+            client_config.data_dir.join(slasher::DATABASE_DIR_NAME)
+        };
+
+        let mut slasher_config = slasher::Config::new(slasher_dir);
+
+        if let Some(history_length) =
+            clap_utils::parse_optional(cli_args, "slasher-history-length")?
+        {
+            slasher_config.history_length = history_length;
+        }
+
+        if let Some(update_period) = clap_utils::parse_optional(cli_args, "slasher-update-period")?
+        {
+            slasher_config.update_period = update_period;
+        }
+
+        if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
+            slasher_config.chunk_size = chunk_size;
+        }
+
+        if let Some(validator_chunk_size) =
+            clap_utils::parse_optional(cli_args, "slasher-validator-chunk-size")?
+        {
+            slasher_config.validator_chunk_size = validator_chunk_size;
+        }
+
+        if let Some(max_queued_attestations) =
+            clap_utils::parse_optional(cli_args, "slasher-max-queued-attestations")?
+        {
+            slasher_config.max_queued_attestations = max_queued_attestations;
+        }
+
+        if let Some(max_db_size_gbs) =
+            clap_utils::parse_optional::<usize>(cli_args, "slasher-max-db-size")?
+        {
+            slasher_config.max_db_size_mbs = max_db_size_gbs * 1024;
+        }
+
+        slasher_config.backend = clap_utils::parse_required(cli_args, "slasher-backend")?;
+
+        client_config.slasher = Some(slasher_config);
+    }
+
+    if cli_args.is_present("slasher-stream") {
+        client_config.slasher_stream = true;
+    }
+
+    if let Some(url) = cli_args.value_of("reconstruct-historic-states") {
+        client_config.reconstruct_historic_states = Some(url.to_string());
+    }
+
+    // If necessary, remove any existing database.
+    //
+    // This must happen after all the flags which determine database locations (e.g.,
+    // `--freezer-dir`) have been applied.
+    if cli_args.is_present("purge-db") {
+        for path in purge_db(&client_config)? {
+            info!(log, "Removed database files"; "path" => format!("{:?}", path));
+        }
+    }
+
     Ok(client_config)
 }
 
+/// Parses a weak subjectivity checkpoint in the `0x<block_root>:<epoch>` format.
+pub fn parse_wss_checkpoint(wss_checkpoint: &str) -> Result<Checkpoint, String> {
+    let mut split = wss_checkpoint.split(':');
+    let (root_str, epoch_str) = match (split.next(), split.next(), split.next()) {
+        (Some(root_str), Some(epoch_str), None) => (root_str, epoch_str),
+        _ => {
+            return Err(format!(
+                "Improperly formatted weak subjectivity checkpoint: {}, expected block_root:epoch",
+                wss_checkpoint
+            ))
+        }
+    };
+
+    if !root_str.starts_with("0x") {
+        return Err(
+            "Unable to parse weak subjectivity checkpoint root, must have 0x prefix".to_string(),
+        );
+    }
+
+    let root_bytes = hex::decode(&root_str[2..])
+        .map_err(|e| format!("Unable to parse weak subjectivity checkpoint root: {:?}", e))?;
+
+    if root_bytes.len() != 32 {
+        return Err(format!(
+            "Weak subjectivity checkpoint root must be 32 bytes, not {}",
+            root_bytes.len()
+        ));
+    }
+
+    let epoch = epoch_str
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid weak subjectivity checkpoint epoch: {}", epoch_str))?;
+
+    Ok(Checkpoint {
+        epoch: Epoch::new(epoch),
+        root: Hash256::from_slice(&root_bytes),
+    })
+}
+
 /// Sets the network config from the command line arguments
 pub fn set_network_config(
     config: &mut NetworkConfig,
@@ -286,12 +494,31 @@ pub fn set_network_config(
         config.listen_address = listen_address;
     }
 
+    if let Some(listen_address_str) = cli_args.value_of("listen-address6") {
+        let listen_address = listen_address_str
+            .parse()
+            .map_err(|_| format!("Invalid IPv6 listen address: {:?}", listen_address_str))?;
+        config.listen_address_ipv6 = Some(listen_address);
+    }
+
     if let Some(target_peers_str) = cli_args.value_of("target-peers") {
         config.target_peers = target_peers_str
             .parse::<usize>()
             .map_err(|_| format!("Invalid number of target peers: {}", target_peers_str))?;
     }
 
+    if let Some(limit_str) = cli_args.value_of("max-inbound-per-ip") {
+        config.max_inbound_connections_per_ip = limit_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid inbound connection limit per ip: {}", limit_str))?;
+    }
+
+    if let Some(limit_str) = cli_args.value_of("max-inbound-per-prefix") {
+        config.max_inbound_connections_per_prefix = limit_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid inbound connection limit per prefix: {}", limit_str))?;
+    }
+
     if let Some(port_str) = cli_args.value_of("port") {
         let port = port_str
             .parse::<u16>()
@@ -300,6 +527,13 @@ pub fn set_network_config(
         config.discovery_port = port;
     }
 
+    if let Some(port_str) = cli_args.value_of("port6") {
+        let port = port_str
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port: {}", port_str))?;
+        config.libp2p_port_ipv6 = Some(port);
+    }
+
     if let Some(port_str) = cli_args.value_of("discovery-port") {
         let port = port_str
             .parse::<u16>()
@@ -343,6 +577,31 @@ pub fn set_network_config(
             .collect::<Result<Vec<Multiaddr>, _>>()?;
     }
 
+    if let Some(trusted_peers_str) = cli_args.value_of("trusted-peers") {
+        let mut trusted_peers: Vec<Multiaddr> = vec![];
+        for peer in trusted_peers_str.split(',') {
+            match peer.parse::<Enr>() {
+                // only the TCP addresses of an ENR are relevant for dialing
+                Ok(enr) => {
+                    trusted_peers.extend(enr.multiaddr_p2p().into_iter().filter(|multi| {
+                        multi.iter().any(|proto| matches!(proto, Protocol::Tcp(_)))
+                    }))
+                }
+                Err(_) => {
+                    // parsing as ENR failed, try as Multiaddr
+                    let multi: Multiaddr = peer
+                        .parse()
+                        .map_err(|_| format!("Not valid as ENR nor Multiaddr: {}", peer))?;
+                    if !multi.iter().any(|proto| matches!(proto, Protocol::P2p(_))) {
+                        return Err(format!("Missing P2P in trusted peer Multiaddr: {}", multi));
+                    }
+                    trusted_peers.push(multi);
+                }
+            }
+        }
+        config.trusted_peers = trusted_peers;
+    }
+
     if let Some(enr_udp_port_str) = cli_args.value_of("enr-udp-port") {
         config.enr_udp_port = Some(
             enr_udp_port_str
@@ -359,13 +618,40 @@ pub fn set_network_config(
         );
     }
 
+    if let Some(enr_tcp6_port_str) = cli_args.value_of("enr-tcp6-port") {
+        config.enr_tcp6_port = Some(
+            enr_tcp6_port_str
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid ENR TCP6 port: {}", enr_tcp6_port_str))?,
+        );
+    }
+
+    if let Some(enr_address_str) = cli_args.value_of("enr-address6") {
+        config.enr_address_ipv6 = Some(
+            enr_address_str
+                .parse::<Ipv6Addr>()
+                .map_err(|_| format!("Invalid IPv6 ENR address: {}", enr_address_str))?,
+        );
+    }
+
     if cli_args.is_present("enr-match") {
-        // set the enr address to localhost if the address is 0.0.0.0
-        if config.listen_address == "0.0.0.0".parse::<IpAddr>().expect("valid ip addr") {
-            config.enr_address = Some("127.0.0.1".parse::<IpAddr>().expect("valid ip addr"));
+        // set the enr address to localhost if the address is unspecified
+        if config.listen_address.is_unspecified() {
+            config.enr_address = Some(if config.listen_address.is_ipv6() {
+                IpAddr::V6(Ipv6Addr::LOCALHOST)
+            } else {
+                "127.0.0.1".parse::<IpAddr>().expect("valid ip addr")
+            });
         } else {
             config.enr_address = Some(config.listen_address);
         }
+        if let Some(listen_address_ipv6) = config.listen_address_ipv6 {
+            config.enr_address_ipv6 = Some(if listen_address_ipv6.is_unspecified() {
+                Ipv6Addr::LOCALHOST
+            } else {
+                listen_address_ipv6
+            });
+        }
         config.enr_udp_port = Some(config.discovery_port);
     }
 
@@ -409,15 +695,159 @@ pub fn set_network_config(
         config.enr_address = Some(resolved_addr);
     }
 
-    if cli_args.is_present("disable_enr_auto_update") {
+    if cli_args.is_present("disable-enr-auto-update") {
         config.discv5_config.enr_update = false;
     }
 
+    if let Some(parallelism_str) = cli_args.value_of("discovery-query-parallelism") {
+        config.discv5_config.query_parallelism = parallelism_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid discovery query parallelism: {}", parallelism_str))?;
+    }
+
+    if let Some(cache_size_str) = cli_args.value_of("discovery-session-cache-size") {
+        config.discv5_config.session_cache_capacity = cache_size_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid discovery session cache size: {}", cache_size_str))?;
+    }
+
+    if cli_args.is_present("discovery-disable-ip-limit") {
+        config.discv5_config.ip_limit = false;
+    }
+
+    if cli_args.is_present("discovery-disable-packet-filter") {
+        config.discv5_config.enable_packet_filter = false;
+    }
+
     if cli_args.is_present("disable-discovery") {
         config.disable_discovery = true;
         slog::warn!(log, "Discovery is disabled. New peers will not be found");
     }
 
+    if cli_args.is_present("disable-upnp") {
+        config.upnp_enabled = false;
+    }
+
+    if cli_args.is_present("gossip-blocks-only") {
+        config.gossip_filter.blocks_only = true;
+    }
+
+    if let Some(subnets_str) = cli_args.value_of("disable-subnets") {
+        config.gossip_filter.disabled_subnets = subnets_str
+            .split(',')
+            .map(|subnet| {
+                subnet
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid subnet id: {}", subnet))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    if cli_args.is_present("disable-listen") {
+        config.disable_listen = true;
+        // there is nothing to map and our address must not be learned from other peers
+        config.upnp_enabled = false;
+        config.discv5_config.enr_update = false;
+    }
+
+    validate_enr_overrides(config, log)
+}
+
+/// Checks that the addresses and ports advertised in our ENR are consistent with the sockets we
+/// listen on.
+///
+/// The advertised endpoints may legitimately differ from the listening sockets when the node is
+/// behind a NAT or proxy, however they must use an address family we listen on and must not be
+/// set when we do not listen at all.
+fn validate_enr_overrides(config: &NetworkConfig, log: &Logger) -> Result<(), String> {
+    let has_overrides = config.enr_address.is_some()
+        || config.enr_udp_port.is_some()
+        || config.enr_tcp_port.is_some()
+        || config.enr_address_ipv6.is_some()
+        || config.enr_tcp6_port.is_some();
+
+    if config.disable_listen && has_overrides {
+        return Err(
+            "ENR addresses and ports cannot be advertised when listening is disabled".into(),
+        );
+    }
+
+    for (flag, port) in &[
+        ("enr-udp-port", config.enr_udp_port),
+        ("enr-tcp-port", config.enr_tcp_port),
+        ("enr-tcp6-port", config.enr_tcp6_port),
+    ] {
+        if *port == Some(0) {
+            return Err(format!("The {} must not be 0", flag));
+        }
+    }
+
+    if let Some(enr_address) = config.enr_address {
+        if enr_address.is_unspecified() || enr_address.is_multicast() {
+            return Err(format!(
+                "The ENR address {} is not a reachable unicast address",
+                enr_address
+            ));
+        }
+        if enr_address.is_ipv6() && !config.listens_on_ipv6() {
+            return Err(format!(
+                "The ENR address {} is IPv6 but the node does not listen on IPv6",
+                enr_address
+            ));
+        }
+        if let IpAddr::V4(ipv4) = enr_address {
+            if ipv4.is_private() {
+                slog::warn!(
+                    log,
+                    "The ENR address is a private address";
+                    "msg" => "peers outside the local network will be unable to connect",
+                    "enr_address" => enr_address.to_string(),
+                );
+            }
+        }
+    }
+
+    if let Some(enr_address_ipv6) = config.enr_address_ipv6 {
+        if enr_address_ipv6.is_unspecified() || enr_address_ipv6.is_multicast() {
+            return Err(format!(
+                "The IPv6 ENR address {} is not a reachable unicast address",
+                enr_address_ipv6
+            ));
+        }
+    }
+
+    if (config.enr_address_ipv6.is_some() || config.enr_tcp6_port.is_some())
+        && !config.listens_on_ipv6()
+    {
+        return Err(
+            "IPv6 ENR fields require the node to listen on IPv6, see --listen-address6".into(),
+        );
+    }
+
+    if let Some(enr_tcp_port) = config.enr_tcp_port {
+        if enr_tcp_port != config.libp2p_port {
+            info!(
+                log,
+                "ENR TCP port differs from the listening port";
+                "msg" => "ensure the advertised port is forwarded to the listening port",
+                "enr_tcp_port" => enr_tcp_port,
+                "listening_port" => config.libp2p_port,
+            );
+        }
+    }
+
+    if let Some(enr_udp_port) = config.enr_udp_port {
+        if enr_udp_port != config.discovery_port {
+            info!(
+                log,
+                "ENR UDP port differs from the discovery port";
+                "msg" => "ensure the advertised port is forwarded to the discovery port",
+                "enr_udp_port" => enr_udp_port,
+                "discovery_port" => config.discovery_port,
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -434,13 +864,51 @@ pub fn get_data_dir(cli_args: &ArgMatches) -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("."))
 }
 
-/// Try to parse the eth2 testnet config from the `testnet`, `testnet-dir` flags in that order.
+/// Returns the paths of the existing database files described by `client_config`: the chain and
+/// freezer databases and the validator pubkey cache.
+///
+/// The network directory (containing the node's private key and ENR) and any validator keys are
+/// never included.
+pub fn get_db_paths(client_config: &ClientConfig) -> Result<Vec<PathBuf>, String> {
+    let db_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Failed to get db_path".to_string())?;
+    let freezer_db_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Failed to get freezer db path".to_string())?;
+
+    let mut paths = vec![db_path, freezer_db_path];
+    for filename in &[PUBKEY_CACHE_FILENAME, LEGACY_PUBKEY_CACHE_FILENAME] {
+        paths.push(client_config.data_dir.join(filename));
+    }
+
+    Ok(paths.into_iter().filter(|path| path.exists()).collect())
+}
+
+/// Delete the database files described by `client_config` (see `get_db_paths`), returning the
+/// paths that were removed.
+pub fn purge_db(client_config: &ClientConfig) -> Result<Vec<PathBuf>, String> {
+    let paths = get_db_paths(client_config)?;
+
+    for path in &paths {
+        if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+        .map_err(|e| format!("Failed to remove {:?}: {:?}", path, e))?;
+    }
+
+    Ok(paths)
+}
+
+/// Try to parse the eth2 testnet config from the `network`, `testnet-dir` flags in that order.
 /// Returns the default hardcoded testnet if neither flags are set.
 pub fn get_eth2_testnet_config<E: EthSpec>(
     cli_args: &ArgMatches,
 ) -> Result<Eth2TestnetConfig<E>, String> {
-    let optional_testnet_config = if cli_args.is_present("testnet") {
-        clap_utils::parse_hardcoded_network(cli_args, "testnet")?
+    let optional_testnet_config = if cli_args.is_present("network") {
+        clap_utils::parse_hardcoded_network(cli_args, "network")?
     } else if cli_args.is_present("testnet-dir") {
         clap_utils::parse_testnet_dir(cli_args, "testnet-dir")?
     } else {
@@ -489,3 +957,148 @@ pub fn unused_port(transport: &str) -> Result<u16, String> {
     };
     Ok(local_addr.port())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_app;
+    use eth2_libp2p::discovery::{build_enr, CombinedKey, Keypair};
+    use eth2_libp2p::CombinedKeyExt;
+    use tempfile::tempdir;
+    use types::{EnrForkId, MainnetEthSpec};
+
+    fn null_logger() -> Logger {
+        slog::Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn network_config(args: &[&str]) -> Result<NetworkConfig, String> {
+        let matches = cli_app()
+            .get_matches_from_safe(std::iter::once("beacon_node").chain(args.iter().copied()))
+            .unwrap();
+        let mut config = NetworkConfig::default();
+        set_network_config(
+            &mut config,
+            &matches,
+            &PathBuf::from("."),
+            &null_logger(),
+            false,
+        )
+        .map(|()| config)
+    }
+
+    #[test]
+    fn disable_listen() {
+        let config = network_config(&["--disable-listen"]).unwrap();
+
+        assert!(config.disable_listen);
+        assert!(!config.upnp_enabled);
+        assert!(config.listen_multiaddrs().is_empty());
+        assert_eq!(config.discovery_listen_socket().port(), 0);
+
+        let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+        let enr = build_enr::<MainnetEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap();
+        assert_eq!(enr.ip(), None, "should not advertise an address");
+        assert_eq!(enr.tcp(), None, "should not advertise a tcp port");
+        assert_eq!(enr.udp(), None, "should not advertise a udp port");
+    }
+
+    #[test]
+    fn disable_listen_with_enr_address() {
+        assert!(network_config(&["--disable-listen", "--enr-address", "1.2.3.4"]).is_err());
+    }
+
+    #[test]
+    fn discovery_flags() {
+        let default = NetworkConfig::default();
+        assert!(default.discv5_config.ip_limit);
+        assert!(default.discv5_config.enable_packet_filter);
+
+        let config = network_config(&[
+            "--discovery-query-parallelism",
+            "7",
+            "--discovery-session-cache-size",
+            "2000",
+            "--discovery-disable-ip-limit",
+            "--discovery-disable-packet-filter",
+        ])
+        .unwrap();
+
+        assert_eq!(config.discv5_config.query_parallelism, 7);
+        assert_eq!(config.discv5_config.session_cache_capacity, 2000);
+        assert!(!config.discv5_config.ip_limit);
+        assert!(!config.discv5_config.enable_packet_filter);
+    }
+
+    #[test]
+    fn invalid_discovery_flags() {
+        assert!(network_config(&["--discovery-query-parallelism", "many"]).is_err());
+        assert!(network_config(&["--discovery-session-cache-size", "lots"]).is_err());
+    }
+
+    #[test]
+    fn purge_db_respects_freezer_dir() {
+        let datadir = tempdir().unwrap();
+        let freezer_dir = tempdir().unwrap();
+        let beacon_dir = datadir.path().join(BEACON_NODE_DIR);
+        let chain_db = beacon_dir.join("chain_db");
+        let network_dir = beacon_dir.join(NETWORK_DIR);
+        let freezer_db = freezer_dir.path().join("freezer_db");
+        for dir in &[&chain_db, &network_dir, &freezer_db] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        let matches = cli_app()
+            .get_matches_from_safe(vec![
+                "beacon_node",
+                "--datadir",
+                datadir.path().to_str().unwrap(),
+                "--freezer-dir",
+                freezer_db.to_str().unwrap(),
+                "--purge-db",
+            ])
+            .unwrap();
+        let spec_constants = ClientConfig::default().spec_constants;
+        let config = get_config::<MainnetEthSpec>(
+            &matches,
+            &spec_constants,
+            &ChainSpec::mainnet(),
+            null_logger(),
+        )
+        .unwrap();
+
+        assert_eq!(config.freezer_db_path, Some(freezer_db.clone()));
+        assert!(!chain_db.exists(), "chain db should be removed");
+        assert!(!freezer_db.exists(), "custom freezer db should be removed");
+        assert!(network_dir.exists(), "network dir should be preserved");
+    }
+
+    #[test]
+    fn disable_subnets_range_checked() {
+        let datadir = tempdir().unwrap();
+        let get_config_with_subnets = |subnets: &str| {
+            let matches = cli_app()
+                .get_matches_from_safe(vec![
+                    "beacon_node",
+                    "--datadir",
+                    datadir.path().to_str().unwrap(),
+                    "--disable-subnets",
+                    subnets,
+                ])
+                .unwrap();
+            get_config::<MainnetEthSpec>(
+                &matches,
+                &ClientConfig::default().spec_constants,
+                &ChainSpec::mainnet(),
+                null_logger(),
+            )
+        };
+
+        let config = get_config_with_subnets("0,63").unwrap();
+        assert_eq!(
+            config.network.gossip_filter.disabled_subnets,
+            vec![0, 63].into_iter().collect()
+        );
+        assert!(get_config_with_subnets("64").is_err());
+        assert!(get_config_with_subnets("one").is_err());
+    }
+}
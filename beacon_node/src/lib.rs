@@ -6,13 +6,15 @@ mod config;
 
 pub use beacon_chain;
 pub use cli::cli_app;
-pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
-pub use config::{get_data_dir, get_eth2_testnet_config, set_network_config};
+pub use client::{reconstruct_historic_states, Client, ClientBuilder, ClientConfig, ClientGenesis};
+pub use config::{
+    get_data_dir, get_db_paths, get_eth2_testnet_config, purge_db, set_network_config,
+};
 pub use eth2_config::Eth2Config;
 
 use beacon_chain::events::TeeEventHandler;
 use beacon_chain::migrate::BackgroundMigrator;
-use beacon_chain::store::LevelDB;
+use beacon_chain::store::BeaconNodeBackend;
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, slot_clock::SystemTimeSlotClock,
 };
@@ -26,13 +28,13 @@ use types::EthSpec;
 /// A type-alias to the tighten the definition of a production-intended `Client`.
 pub type ProductionClient<E> = Client<
     Witness<
-        BackgroundMigrator<E, LevelDB<E>, LevelDB<E>>,
+        BackgroundMigrator<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>,
         SystemTimeSlotClock,
         CachingEth1Backend<E>,
         E,
         TeeEventHandler<E>,
-        LevelDB<E>,
-        LevelDB<E>,
+        BeaconNodeBackend<E>,
+        BeaconNodeBackend<E>,
     >,
 >;
 
@@ -53,9 +55,17 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
     /// given `matches` and potentially configuration files on the local filesystem or other
     /// configurations hosted remotely.
     pub async fn new_from_cli(
-        context: RuntimeContext<E>,
+        mut context: RuntimeContext<E>,
         matches: &ArgMatches<'_>,
     ) -> Result<Self, String> {
+        // The follow distance is used by both the eth1 caches and the eth1 voting strategy, so it
+        // is overridden in the spec rather than in the eth1 config.
+        let follow_distance: Option<u64> =
+            clap_utils::parse_optional(matches, "eth1-follow-distance")?;
+        if let Some(follow_distance) = follow_distance {
+            context.eth2_config.spec.eth1_follow_distance = follow_distance;
+        }
+
         let client_config = get_config::<E>(
             &matches,
             &context.eth2_config.spec_constants,
@@ -93,11 +103,26 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let builder = builder
             .beacon_chain_builder(client_genesis, client_config_1)
             .await?;
-        let builder = if client_config.sync_eth1_chain && !client_config.dummy_eth1_backend {
+        let read_only = client_config.store.read_only;
+        let builder = if read_only {
+            info!(
+                log,
+                "Starting read-only beacon node";
+                "msg" => "the node will serve the HTTP API without joining the network"
+            );
+            builder.no_eth1_backend()?
+        } else if client_config.disable_eth1 {
+            warn!(
+                log,
+                "Block production impaired";
+                "reason" => "eth1 is disabled, no deposits will be included"
+            );
+            builder.disabled_eth1_backend()?
+        } else if client_config.sync_eth1_chain && !client_config.dummy_eth1_backend {
             info!(
                 log,
                 "Block production enabled";
-                "endpoint" => &client_config.eth1.endpoint,
+                "endpoints" => format!("{:?}", &client_config.eth1.endpoints),
                 "method" => "json rpc via http"
             );
             builder
@@ -126,11 +151,20 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         // Inject the executor into the discv5 network config.
         client_config.network.discv5_config.executor = Some(Box::new(executor));
 
-        let builder = builder
-            .build_beacon_chain()?
-            .network(&client_config.network)
-            .await?
-            .notifier()?;
+        let builder = builder.build_beacon_chain()?;
+        let builder = if read_only {
+            builder.offline_network(&client_config.network)?
+        } else {
+            builder.network(&client_config.network).await?.notifier()?
+        };
+
+        let builder = builder.slasher_service()?;
+
+        let builder = if let Some(url) = &client_config.reconstruct_historic_states {
+            builder.historic_state_reconstruction(url)?
+        } else {
+            builder
+        };
 
         let builder = if client_config.rest_api.enabled {
             builder.http_server(&client_config, &http_eth2_config, events)?
@@ -0,0 +1,403 @@
+//! Provides `BeaconNodeBackend`, a `KeyValueStore` which is backed by one of the on-disk
+//! databases supported by Lighthouse, as selected at runtime by `DatabaseBackend`.
+//!
+//! LevelDB is always available, whilst SQLite requires the `sqlite` feature.
+//!
+//! Values are compressed according to the `CompressionConfig` of the backend, see the
+//! `compression` module.
+//!
+//! A backend may be opened read-only, in which case all writes fail with `Error::ReadOnly`.
+use crate::compression::{self, CompressionConfig};
+use crate::config::DatabaseBackend;
+use crate::leveldb_store::LevelDB;
+use crate::metrics;
+#[cfg(feature = "sqlite")]
+use crate::sqlite_store::SqliteStore;
+use crate::{
+    get_key_for_col, ColumnKeyIter, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp,
+    RawEntryIter,
+};
+use std::path::Path;
+use types::EthSpec;
+
+/// The number of entries written in each batch when copying a database between backends.
+const COPY_BATCH_SIZE: usize = 1_024;
+
+/// Runs `$body` with `$db` bound to the inner store of `$backend`, whichever variant it is.
+macro_rules! with_backend {
+    ($backend: expr, $db: ident => $body: expr) => {
+        match &$backend.db {
+            BackendDB::LevelDb($db) => $body,
+            #[cfg(feature = "sqlite")]
+            BackendDB::Sqlite($db) => $body,
+        }
+    };
+}
+
+enum BackendDB<E: EthSpec> {
+    LevelDb(LevelDB<E>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteStore<E>),
+}
+
+/// An on-disk key-value store, backed by whichever `DatabaseBackend` it was opened with.
+pub struct BeaconNodeBackend<E: EthSpec> {
+    db: BackendDB<E>,
+    compression: CompressionConfig,
+    read_only: bool,
+}
+
+impl<E: EthSpec> BeaconNodeBackend<E> {
+    /// Open a database of the given `backend` kind at `path`, creating a new database if one does
+    /// not already exist.
+    ///
+    /// Values are written uncompressed unless a config is supplied with `Self::compression`.
+    pub fn open(backend: DatabaseBackend, path: &Path) -> Result<Self, Error> {
+        let db = match backend {
+            DatabaseBackend::LevelDb => BackendDB::LevelDb(LevelDB::open(path)?),
+            #[cfg(feature = "sqlite")]
+            DatabaseBackend::Sqlite => BackendDB::Sqlite(SqliteStore::open(path)?),
+            #[cfg(not(feature = "sqlite"))]
+            DatabaseBackend::Sqlite => return Err(Error::BackendUnavailable(backend)),
+        };
+
+        Ok(Self {
+            db,
+            compression: CompressionConfig::default(),
+            read_only: false,
+        })
+    }
+
+    /// Compress values written to the database according to `compression`.
+    ///
+    /// Values which are already in the database are unaffected, and can still be read.
+    pub fn compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Reject all writes to the database if `read_only` is `true`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Returns `true` if a database of the given `backend` kind exists at `path`.
+    pub fn exists(backend: DatabaseBackend, path: &Path) -> bool {
+        match backend {
+            DatabaseBackend::LevelDb => LevelDB::<E>::exists(path),
+            #[cfg(feature = "sqlite")]
+            DatabaseBackend::Sqlite => SqliteStore::<E>::exists(path),
+            #[cfg(not(feature = "sqlite"))]
+            DatabaseBackend::Sqlite => false,
+        }
+    }
+
+    /// Check that we aren't about to initialize an empty `backend` database at `path` whilst
+    /// there is a database of another kind present, which would silently discard the user's data.
+    pub fn check_path(backend: DatabaseBackend, path: &Path) -> Result<(), Error> {
+        if Self::exists(backend, path) {
+            return Ok(());
+        }
+
+        match DatabaseBackend::ALL
+            .iter()
+            .find(|other| **other != backend && Self::exists(**other, path))
+        {
+            Some(on_disk) => Err(Error::BackendMismatch {
+                path: path.to_path_buf(),
+                configured: backend,
+                on_disk: *on_disk,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for BeaconNodeBackend<E> {
+    fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let value = with_backend!(self, db => db.get_bytes(column, key))?
+            .map(compression::decompress)
+            .transpose()?;
+
+        metrics::inc_counter_vec(&metrics::DISK_DB_READ_COUNT_PER_COLUMN, &[column]);
+        if let Some(value) = &value {
+            metrics::inc_counter_vec_by(
+                &metrics::DISK_DB_READ_BYTES_PER_COLUMN,
+                &[column],
+                value.len() as i64,
+            );
+        }
+
+        Ok(value)
+    }
+
+    fn put_bytes(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.check_writable()?;
+        record_write(column, value);
+        let value = self.compress(column, key, value)?;
+        with_backend!(self, db => db.put_bytes(column, key, &value))
+    }
+
+    fn put_bytes_sync(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.check_writable()?;
+        record_write(column, value);
+        let value = self.compress(column, key, value)?;
+        with_backend!(self, db => db.put_bytes_sync(column, key, &value))
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        if self.read_only {
+            return Ok(());
+        }
+        with_backend!(self, db => db.sync())
+    }
+
+    fn key_exists(&self, column: &str, key: &[u8]) -> Result<bool, Error> {
+        with_backend!(self, db => db.key_exists(column, key))
+    }
+
+    fn key_delete(&self, column: &str, key: &[u8]) -> Result<(), Error> {
+        self.check_writable()?;
+        metrics::inc_counter_vec(&metrics::DISK_DB_DELETE_COUNT_PER_COLUMN, &[column]);
+        with_backend!(self, db => db.key_delete(column, key))
+    }
+
+    fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        self.check_writable()?;
+        let batch = batch
+            .into_iter()
+            .map(|op| match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => {
+                    record_write(metrics::column_label(&key), &value);
+                    let compression = self.compression.for_key(&key);
+                    compression::compress(compression, value)
+                        .map(|value| KeyValueStoreOp::PutKeyValue(key, value))
+                }
+                KeyValueStoreOp::DeleteKey(key) => {
+                    metrics::inc_counter_vec(
+                        &metrics::DISK_DB_DELETE_COUNT_PER_COLUMN,
+                        &[metrics::column_label(&key)],
+                    );
+                    Ok(KeyValueStoreOp::DeleteKey(key))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        with_backend!(self, db => db.do_atomically(batch))
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        self.check_writable()?;
+        with_backend!(self, db => db.compact())
+    }
+
+    /// Iterate over the raw entries, without decompressing them.
+    fn iter_raw_entries(&self) -> RawEntryIter<'_> {
+        with_backend!(self, db => db.iter_raw_entries())
+    }
+
+    fn iter_raw_snapshot(&self) -> Result<RawEntryIter<'_>, Error> {
+        with_backend!(self, db => db.iter_raw_snapshot())
+    }
+
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter<'_> {
+        with_backend!(self, db => db.iter_column_keys(column))
+    }
+}
+
+impl<E: EthSpec> BeaconNodeBackend<E> {
+    fn check_writable(&self) -> Result<(), Error> {
+        if self.read_only {
+            Err(Error::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn compress(&self, column: &str, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        let compression = self.compression.for_key(&get_key_for_col(column, key));
+        compression::compress(compression, value.to_vec())
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for BeaconNodeBackend<E> {}
+
+/// Record a write of the uncompressed `value` to `column` in the per-column metrics.
+fn record_write(column: &str, value: &[u8]) {
+    metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT_PER_COLUMN, &[column]);
+    metrics::inc_counter_vec_by(
+        &metrics::DISK_DB_WRITE_BYTES_PER_COLUMN,
+        &[column],
+        value.len() as i64,
+    );
+}
+
+/// Copy every entry of the database at `path` from the `from` backend to the `to` backend,
+/// returning the number of entries copied.
+///
+/// The source database is left untouched, so that the migration can be reverted by switching
+/// back to the `from` backend.
+pub fn migrate_backend<E: EthSpec>(
+    path: &Path,
+    from: DatabaseBackend,
+    to: DatabaseBackend,
+) -> Result<usize, Error> {
+    if BeaconNodeBackend::<E>::exists(to, path) {
+        return Err(Error::BackendMigrationTargetExists {
+            path: path.to_path_buf(),
+            backend: to,
+        });
+    }
+
+    let source = BeaconNodeBackend::<E>::open(from, path)?;
+    let target = BeaconNodeBackend::<E>::open(to, path)?;
+
+    let mut count = 0;
+    let mut batch = Vec::with_capacity(COPY_BATCH_SIZE);
+    for entry in source.iter_raw_entries() {
+        let (key, value) = entry?;
+        batch.push(KeyValueStoreOp::PutKeyValue(key, value));
+        count += 1;
+
+        if batch.len() >= COPY_BATCH_SIZE {
+            target.do_atomically(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(COPY_BATCH_SIZE),
+            ))?;
+        }
+    }
+    target.do_atomically(batch)?;
+    target.sync()?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn check_path_detects_other_backend() {
+        let dir = tempdir().unwrap();
+        let db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, dir.path()).unwrap();
+        db.put_bytes("col", b"key", b"value").unwrap();
+
+        assert!(BeaconNodeBackend::<E>::check_path(DatabaseBackend::LevelDb, dir.path()).is_ok());
+        assert!(matches!(
+            BeaconNodeBackend::<E>::check_path(DatabaseBackend::Sqlite, dir.path()),
+            Err(Error::BackendMismatch {
+                configured: DatabaseBackend::Sqlite,
+                on_disk: DatabaseBackend::LevelDb,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn compressed_values_round_trip() {
+        let dir = tempdir().unwrap();
+        let compression = "blocks=snappy,states=zstd".parse().unwrap();
+        let db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, dir.path())
+            .unwrap()
+            .compression(compression);
+        let value = vec![42; 1_024];
+
+        let block_col = DBColumn::BeaconBlock.into();
+        let state_col = DBColumn::BeaconState.into();
+        db.put_bytes(block_col, b"block", &value).unwrap();
+        db.do_atomically(vec![KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(state_col, b"state"),
+            value.clone(),
+        )])
+        .unwrap();
+
+        assert_eq!(
+            db.get_bytes(block_col, b"block").unwrap(),
+            Some(value.clone())
+        );
+        assert_eq!(
+            db.get_bytes(state_col, b"state").unwrap(),
+            Some(value.clone())
+        );
+
+        // The raw values on disk should be compressed.
+        for entry in db.iter_raw_entries() {
+            let (_, raw) = entry.unwrap();
+            assert!(compression::is_compressed(&raw));
+            assert!(raw.len() < value.len());
+        }
+    }
+
+    #[test]
+    fn read_only_rejects_writes() {
+        let dir = tempdir().unwrap();
+        BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, dir.path())
+            .unwrap()
+            .put_bytes("col", b"key", b"value")
+            .unwrap();
+
+        let db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, dir.path())
+            .unwrap()
+            .read_only(true);
+        assert_eq!(
+            db.get_bytes("col", b"key").unwrap(),
+            Some(b"value".to_vec())
+        );
+        assert!(matches!(
+            db.put_bytes("col", b"key", b"other"),
+            Err(Error::ReadOnly)
+        ));
+        assert!(matches!(
+            db.do_atomically(vec![KeyValueStoreOp::DeleteKey(get_key_for_col(
+                "col", b"key"
+            ))]),
+            Err(Error::ReadOnly)
+        ));
+        assert_eq!(
+            db.get_bytes("col", b"key").unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn migrate_leveldb_to_sqlite() {
+        let dir = tempdir().unwrap();
+        let entries = (0..3_000u32)
+            .map(|i| (i.to_le_bytes().to_vec(), vec![i as u8; 64]))
+            .collect::<Vec<_>>();
+
+        {
+            let db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, dir.path()).unwrap();
+            for (key, value) in &entries {
+                db.put_bytes("col", key, value).unwrap();
+            }
+        }
+
+        let copied = migrate_backend::<E>(
+            dir.path(),
+            DatabaseBackend::LevelDb,
+            DatabaseBackend::Sqlite,
+        )
+        .unwrap();
+        assert_eq!(copied, entries.len());
+
+        let db = BeaconNodeBackend::<E>::open(DatabaseBackend::Sqlite, dir.path()).unwrap();
+        for (key, value) in &entries {
+            assert_eq!(db.get_bytes("col", key).unwrap().as_ref(), Some(value));
+        }
+
+        assert!(matches!(
+            migrate_backend::<E>(
+                dir.path(),
+                DatabaseBackend::LevelDb,
+                DatabaseBackend::Sqlite
+            ),
+            Err(Error::BackendMigrationTargetExists { .. })
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+//! Transparent compression of the values stored in the on-disk database.
+//!
+//! Compressed values are prefixed with a header which identifies the codec, so that values
+//! written with different settings (or before compression was enabled) can always be read back.
+//! Values without the header are returned as-is.
+//!
+//! The header begins with `0xc0dec0de` followed by three `0xff` bytes. It cannot be confused with
+//! an uncompressed block (which begins with a small SSZ offset) or an uncompressed state (which
+//! begins with a `genesis_time` that would have to be greater than `2^55`).
+use crate::{DBColumn, Error};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Identifies a compressed value, see the module-level docs.
+const MAGIC: [u8; 7] = [0xc0, 0xde, 0xc0, 0xde, 0xff, 0xff, 0xff];
+/// The length of the header: the magic bytes followed by one byte identifying the codec.
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+/// The compression level used for zstd, which favours speed over ratio.
+const ZSTD_LEVEL: i32 = 3;
+
+/// A compression codec for values in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Snappy,
+    Zstd,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::None
+    }
+}
+
+impl Compression {
+    fn id(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Snappy => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Snappy),
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Compression::None),
+            "snappy" => Ok(Compression::Snappy),
+            "zstd" => Ok(Compression::Zstd),
+            other => Err(format!("Unknown compression codec: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "none"),
+            Compression::Snappy => write!(f, "snappy"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// The compression to apply to each of the columns which hold large values.
+///
+/// All other columns are always stored uncompressed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Compression for `DBColumn::BeaconBlock`.
+    pub blocks: Compression,
+    /// Compression for `DBColumn::BeaconState`, i.e. hot states and freezer restore points.
+    pub states: Compression,
+    /// Compression for `DBColumn::BeaconStateDiff`.
+    pub state_diffs: Compression,
+}
+
+impl CompressionConfig {
+    /// Returns the compression that should be applied to the value stored under `key`, where
+    /// `key` includes the column prefix.
+    pub fn for_key(&self, key: &[u8]) -> Compression {
+        let in_column = |column: DBColumn| key.starts_with(Into::<&str>::into(column).as_bytes());
+
+        if in_column(DBColumn::BeaconBlock) {
+            self.blocks
+        } else if in_column(DBColumn::BeaconState) {
+            self.states
+        } else if in_column(DBColumn::BeaconStateDiff) {
+            self.state_diffs
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Parses either a single codec which is applied to all columns (e.g., `zstd`), or a
+/// comma-separated list of `column=codec` pairs (e.g., `blocks=snappy,states=zstd`).
+impl FromStr for CompressionConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(compression) = s.parse::<Compression>() {
+            return Ok(Self {
+                blocks: compression,
+                states: compression,
+                state_diffs: compression,
+            });
+        }
+
+        let mut config = Self::default();
+        for pair in s.split(',') {
+            let mut split = pair.splitn(2, '=');
+            let column = split.next().unwrap_or("");
+            let compression = split
+                .next()
+                .ok_or_else(|| format!("Expected column=codec, got: {}", pair))?
+                .parse()?;
+
+            match column {
+                "blocks" => config.blocks = compression,
+                "states" => config.states = compression,
+                "state-diffs" => config.state_diffs = compression,
+                other => return Err(format!("Unknown compressible column: {}", other)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Returns `true` if `bytes` were produced by `compress` with a codec other than `None`.
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN && bytes[..MAGIC.len()] == MAGIC
+}
+
+/// Compress `bytes` with `compression`, prefixing the result with a header.
+///
+/// Values are returned unchanged if `compression` is `None` or if they are already compressed.
+pub fn compress(compression: Compression, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if compression == Compression::None || is_compressed(&bytes) {
+        return Ok(bytes);
+    }
+
+    let compressed = match compression {
+        Compression::None => unreachable!("handled above"),
+        Compression::Snappy => snap::raw::Encoder::new()
+            .compress_vec(&bytes)
+            .map_err(|e| Error::CompressionError(format!("{:?}", e)))?,
+        Compression::Zstd => zstd::stream::encode_all(bytes.as_slice(), ZSTD_LEVEL)
+            .map_err(|e| Error::CompressionError(format!("{:?}", e)))?,
+    };
+
+    let mut result = Vec::with_capacity(HEADER_LEN + compressed.len());
+    result.extend_from_slice(&MAGIC);
+    result.push(compression.id());
+    result.extend_from_slice(&compressed);
+    Ok(result)
+}
+
+/// Decompress a value produced by `compress`, or return it unchanged if it has no header.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if !is_compressed(&bytes) {
+        return Ok(bytes);
+    }
+
+    let payload = &bytes[HEADER_LEN..];
+    match Compression::from_id(bytes[MAGIC.len()]) {
+        Some(Compression::None) => Ok(payload.to_vec()),
+        Some(Compression::Snappy) => snap::raw::Decoder::new()
+            .decompress_vec(payload)
+            .map_err(|e| Error::CompressionError(format!("{:?}", e))),
+        Some(Compression::Zstd) => zstd::stream::decode_all(payload)
+            .map_err(|e| Error::CompressionError(format!("{:?}", e))),
+        None => Err(Error::CompressionError(format!(
+            "Unknown codec id: {}",
+            bytes[MAGIC.len()]
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        (0..4_096u32).map(|i| (i % 7) as u8).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for compression in &[Compression::None, Compression::Snappy, Compression::Zstd] {
+            let compressed = compress(*compression, sample()).unwrap();
+            if *compression != Compression::None {
+                assert!(is_compressed(&compressed));
+                assert!(compressed.len() < sample().len());
+            }
+            assert_eq!(decompress(compressed).unwrap(), sample());
+        }
+    }
+
+    #[test]
+    fn uncompressed_values_pass_through() {
+        assert_eq!(decompress(sample()).unwrap(), sample());
+        assert_eq!(decompress(vec![]).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn compressed_values_are_not_compressed_twice() {
+        let once = compress(Compression::Snappy, sample()).unwrap();
+        let twice = compress(Compression::Zstd, once.clone()).unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn parse_config() {
+        assert_eq!(
+            "zstd".parse::<CompressionConfig>(),
+            Ok(CompressionConfig {
+                blocks: Compression::Zstd,
+                states: Compression::Zstd,
+                state_diffs: Compression::Zstd,
+            })
+        );
+        assert_eq!(
+            "blocks=snappy,states=zstd".parse::<CompressionConfig>(),
+            Ok(CompressionConfig {
+                blocks: Compression::Snappy,
+                states: Compression::Zstd,
+                state_diffs: Compression::None,
+            })
+        );
+        assert!("blocks".parse::<CompressionConfig>().is_err());
+        assert!("ops=zstd".parse::<CompressionConfig>().is_err());
+    }
+
+    #[test]
+    fn config_for_key() {
+        let config = "blocks=snappy,states=zstd"
+            .parse::<CompressionConfig>()
+            .unwrap();
+        let key = |column: DBColumn| crate::get_key_for_col(column.into(), &[0; 32]);
+
+        assert_eq!(
+            config.for_key(&key(DBColumn::BeaconBlock)),
+            Compression::Snappy
+        );
+        assert_eq!(
+            config.for_key(&key(DBColumn::BeaconState)),
+            Compression::Zstd
+        );
+        assert_eq!(
+            config.for_key(&key(DBColumn::BeaconStateDiff)),
+            Compression::None
+        );
+        assert_eq!(config.for_key(&key(DBColumn::OpPool)), Compression::None);
+    }
+}
@@ -1,16 +1,97 @@
+use crate::compression::CompressionConfig;
+use crate::{DBColumn, Error, StoreItem};
 use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fmt;
+use std::str::FromStr;
 use types::{EthSpec, MinimalEthSpec};
 
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
+pub const DEFAULT_RESTORE_POINTS_PER_SNAPSHOT: u64 = 16;
 pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 5;
+pub const DEFAULT_STATE_CACHE_SIZE_MB: usize = 128;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StoreConfig {
     /// Number of slots to wait between storing restore points in the freezer database.
     pub slots_per_restore_point: u64,
+    /// Number of restore points between full state snapshots in the freezer database.
+    ///
+    /// Restore points which are not snapshots are stored as a diff against the previous restore
+    /// point. A value of 1 stores every restore point in full.
+    pub restore_points_per_snapshot: u64,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
+    /// Approximate maximum memory used by the in-memory state cache, in megabytes.
+    ///
+    /// A value of 0 disables the cache.
+    pub state_cache_size_mb: usize,
+    /// Whether to periodically compact the database after pruning and freezer migration.
+    pub compact_on_prune: bool,
+    /// The key-value store used to persist the hot and cold databases.
+    pub backend: DatabaseBackend,
+    /// Compression applied to newly written blocks and states.
+    pub compression: CompressionConfig,
+    /// Open an existing database without writing to it. Not stored on disk.
+    pub read_only: bool,
+}
+
+/// The key-value stores that may be used as the on-disk database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    LevelDb,
+    /// Only available if compiled with the `sqlite` feature.
+    Sqlite,
+}
+
+impl Default for DatabaseBackend {
+    fn default() -> Self {
+        DatabaseBackend::LevelDb
+    }
+}
+
+impl DatabaseBackend {
+    /// All known backends, whether or not they were compiled in.
+    pub const ALL: [DatabaseBackend; 2] = [DatabaseBackend::LevelDb, DatabaseBackend::Sqlite];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseBackend::LevelDb => "leveldb",
+            DatabaseBackend::Sqlite => "sqlite",
+        }
+    }
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "leveldb" => Ok(DatabaseBackend::LevelDb),
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            other => Err(format!("Unknown database backend: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct OnDiskStoreConfig {
+    pub slots_per_restore_point: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreConfigError {
+    MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
 }
 
 impl Default for StoreConfig {
@@ -18,7 +99,52 @@ impl Default for StoreConfig {
         Self {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
+            restore_points_per_snapshot: DEFAULT_RESTORE_POINTS_PER_SNAPSHOT,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            state_cache_size_mb: DEFAULT_STATE_CACHE_SIZE_MB,
+            compact_on_prune: true,
+            backend: DatabaseBackend::default(),
+            compression: CompressionConfig::default(),
+            read_only: false,
         }
     }
 }
+
+impl StoreConfig {
+    pub fn as_disk_config(&self) -> OnDiskStoreConfig {
+        OnDiskStoreConfig {
+            slots_per_restore_point: self.slots_per_restore_point,
+        }
+    }
+
+    /// Check that this config is compatible with the config of an existing database.
+    ///
+    /// The `slots_per_restore_point` cannot be changed once the database has been initialized,
+    /// since it determines the location of the restore points in the freezer.
+    pub fn check_compatibility(
+        &self,
+        on_disk_config: &OnDiskStoreConfig,
+    ) -> Result<(), StoreConfigError> {
+        if self.slots_per_restore_point != on_disk_config.slots_per_restore_point {
+            return Err(StoreConfigError::MismatchedSlotsPerRestorePoint {
+                config: self.slots_per_restore_point,
+                on_disk: on_disk_config.slots_per_restore_point,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl StoreItem for OnDiskStoreConfig {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
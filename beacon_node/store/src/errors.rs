@@ -1,6 +1,9 @@
 use crate::chunked_vector::ChunkError;
+use crate::config::{DatabaseBackend, StoreConfigError};
 use crate::hot_cold_store::HotColdDBError;
 use ssz::DecodeError;
+use state_processing::BlockReplayError;
+use std::path::PathBuf;
 use types::{BeaconStateError, Hash256, Slot};
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -12,11 +15,48 @@ pub enum Error {
     BeaconStateError(BeaconStateError),
     PartialBeaconStateError,
     HotColdDBError(HotColdDBError),
-    DBError { message: String },
+    DBError {
+        message: String,
+    },
     RlpError(String),
     BlockNotFound(Hash256),
     NoContinuationData,
     SplitPointModified(Slot, Slot),
+    ConfigError(StoreConfigError),
+    /// The backend was not compiled into this binary (see the cargo features of `store`).
+    BackendUnavailable(DatabaseBackend),
+    /// The configured backend has no database at `path`, but another backend does.
+    BackendMismatch {
+        path: PathBuf,
+        configured: DatabaseBackend,
+        on_disk: DatabaseBackend,
+    },
+    /// A value could not be compressed or decompressed.
+    CompressionError(String),
+    /// Refusing to migrate into a location which already contains a database.
+    BackendMigrationTargetExists {
+        path: PathBuf,
+        backend: DatabaseBackend,
+    },
+    /// The freezer database is configured at a different path to the one recorded in the hot
+    /// database, whilst the recorded path still exists (e.g., `--freezer-dir` was omitted).
+    FreezerPathMismatch {
+        recorded: PathBuf,
+        configured: PathBuf,
+    },
+    /// The hot database has already been used, but no freezer database exists at the configured
+    /// path (e.g., the freezer's disk is not mounted).
+    MissingFreezerDatabase(PathBuf),
+    /// The store cannot iterate over a consistent view of its entries whilst in use.
+    SnapshotUnsupported,
+    /// A snapshot archive is malformed or truncated.
+    InvalidSnapshot(String),
+    /// The database was opened read-only, and cannot be written to.
+    ReadOnly,
+    /// A read-only database was opened at `path`, but no database exists there.
+    MissingDatabase(PathBuf),
+    /// Refusing to import a snapshot into a location which already contains a database.
+    SnapshotTargetExists(PathBuf),
 }
 
 impl From<DecodeError> for Error {
@@ -37,6 +77,18 @@ impl From<HotColdDBError> for Error {
     }
 }
 
+impl From<BlockReplayError> for Error {
+    fn from(e: BlockReplayError) -> Error {
+        Error::HotColdDBError(HotColdDBError::BlockReplayError(e))
+    }
+}
+
+impl From<StoreConfigError> for Error {
+    fn from(e: StoreConfigError) -> Error {
+        Error::ConfigError(e)
+    }
+}
+
 impl From<BeaconStateError> for Error {
     fn from(e: BeaconStateError) -> Error {
         Error::BeaconStateError(e)
@@ -1,13 +1,19 @@
+use crate::backend::BeaconNodeBackend;
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, RandaoMixes, StateRoots,
 };
-use crate::config::StoreConfig;
+use crate::config::{OnDiskStoreConfig, StoreConfig};
 use crate::forwards_iter::HybridForwardsBlockRootsIterator;
 use crate::impls::beacon_state::{get_full_state, store_full_state};
-use crate::iter::{ParentRootBlockIterator, StateRootsIterator};
-use crate::leveldb_store::LevelDB;
+use crate::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
 use crate::memory_store::MemoryStore;
+use crate::metadata::{
+    AnchorInfo, FreezerPath, SchemaVersion, ANCHOR_INFO_KEY, CURRENT_SCHEMA_VERSION,
+    FREEZER_PATH_KEY, SCHEMA_VERSION_KEY,
+};
 use crate::metrics;
+use crate::state_cache::StateCache;
+use crate::state_diff::PartialBeaconStateDiff;
 use crate::{
     get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
     StoreOp,
@@ -17,11 +23,9 @@ use parking_lot::{Mutex, RwLock};
 use slog::{debug, error, info, trace, warn, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
-use state_processing::{
-    per_block_processing, per_slot_processing, BlockProcessingError, BlockSignatureStrategy,
-    SlotProcessingError,
-};
+use state_processing::{BlockReplayError, BlockReplayer, StateRootStrategy};
 use std::convert::TryInto;
+use std::fs;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
@@ -29,16 +33,8 @@ use types::*;
 
 /// 32-byte key for accessing the `split` of the freezer DB.
 pub const SPLIT_DB_KEY: &str = "FREEZERDBSPLITFREEZERDBSPLITFREE";
-
-/// Defines how blocks should be replayed on states.
-#[derive(PartialEq)]
-pub enum BlockReplay {
-    /// Perform all transitions faithfully to the specification.
-    Accurate,
-    /// Don't compute state roots, eventually computing an invalid beacon state that can only be
-    /// used for obtaining shuffling.
-    InconsistentStateRoots,
-}
+/// 32-byte key for accessing the `OnDiskStoreConfig` of the database.
+pub const CONFIG_DB_KEY: &str = "FREEZERDBCONFIGFREEZERDBCONFIGFR";
 
 /// On-disk database that stores finalized states efficiently.
 ///
@@ -49,19 +45,27 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     /// The slot and state root at the point where the database is split between hot and cold.
     ///
     /// States with slots less than `split.slot` are in the cold DB, while states with slots
-    /// greater than or equal are in the hot DB.
-    split: RwLock<Split>,
+    /// greater than or equal are in the hot DB. The same applies to canonical blocks, whilst
+    /// blocks from abandoned forks are only ever stored in the hot DB.
+    pub(crate) split: RwLock<Split>,
+    /// The oldest block and state available, if the database was not initialized from genesis.
+    anchor_info: RwLock<Option<AnchorInfo>>,
+    /// Serializes writes of restore points to the freezer, which read and update shared chunks.
+    pub(crate) freezer_lock: Mutex<()>,
     config: StoreConfig,
     /// Cold database containing compact historical data.
     pub(crate) cold_db: Cold,
     /// Hot database containing duplicated but quick-to-access recent data.
     ///
-    /// The hot database also contains all blocks.
+    /// The hot database also contains all blocks that have not yet been frozen.
     pub(crate) hot_db: Hot,
     /// LRU cache of deserialized blocks. Updated whenever a block is loaded.
     block_cache: Mutex<LruCache<Hash256, SignedBeaconBlock<E>>>,
+    /// LRU cache of states, keyed by state root and bounded by memory usage. Updated whenever a
+    /// state is loaded by `get_state`.
+    state_cache: Mutex<StateCache<E>>,
     /// Chain spec.
-    spec: ChainSpec,
+    pub(crate) spec: ChainSpec,
     /// Logger.
     pub(crate) log: Logger,
     /// Mere vessel for E.
@@ -87,14 +91,50 @@ pub enum HotColdDBError {
     HotStateSummaryError(BeaconStateError),
     RestorePointDecodeError(ssz::DecodeError),
     BlockReplayBeaconError(BeaconStateError),
-    BlockReplaySlotError(SlotProcessingError),
-    BlockReplayBlockError(BlockProcessingError),
+    BlockReplayError(BlockReplayError),
     InvalidSlotsPerRestorePoint {
         slots_per_restore_point: u64,
         slots_per_historical_root: u64,
         slots_per_epoch: u64,
     },
     RestorePointBlockHashError(BeaconStateError),
+    InvalidRestorePointsPerSnapshot(u64),
+    InvalidStateDiff(Hash256),
+    /// The database was written by a newer version of Lighthouse, and cannot be downgraded.
+    SchemaVersionTooNew {
+        on_disk: SchemaVersion,
+        supported: SchemaVersion,
+    },
+    MissingSchemaMigration(SchemaVersion),
+    /// The requested block or state pre-dates the `AnchorInfo` of the database.
+    HistoryUnavailable {
+        requested_slot: Slot,
+        oldest_available_slot: Slot,
+    },
+    /// The database has no `AnchorInfo`, so it already contains every historic state.
+    StateReconstructionNotRequired,
+    /// The block stored under the zero hash, which commits to the anchor state, is missing.
+    MissingAnchorBlock,
+    /// State reconstruction must begin from a state at the genesis slot.
+    ReconstructionNotFromGenesis(Slot),
+    /// Blocks must be supplied to state reconstruction in slot order, and prior to the anchor.
+    ReconstructionBlockOutOfOrder {
+        block_slot: Slot,
+        state_slot: Slot,
+        anchor_slot: Slot,
+    },
+    /// Applying a block during state reconstruction did not produce the state root it commits to.
+    ReconstructionStateRootMismatch {
+        slot: Slot,
+        block_state_root: Hash256,
+        computed_state_root: Hash256,
+    },
+    /// The reconstructed state at the anchor slot differs from the state the database was
+    /// initialized from.
+    ReconstructionAnchorMismatch {
+        anchor_state_root: Hash256,
+        computed_state_root: Hash256,
+    },
 }
 
 impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
@@ -104,12 +144,16 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
         log: Logger,
     ) -> Result<HotColdDB<E, MemoryStore<E>, MemoryStore<E>>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_restore_points_per_snapshot(config.restore_points_per_snapshot)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
+            anchor_info: RwLock::new(None),
+            freezer_lock: Mutex::new(()),
             cold_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            state_cache: Mutex::new(StateCache::new(config.state_cache_size_mb * 1024 * 1024)),
             config,
             spec,
             log,
@@ -120,33 +164,84 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
     }
 }
 
-impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
+impl<E: EthSpec> HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>> {
     /// Open a new or existing database, with the given paths to the hot and cold DBs.
     ///
-    /// The `slots_per_restore_point` parameter must be a divisor of `SLOTS_PER_HISTORICAL_ROOT`.
+    /// The `slots_per_restore_point` parameter must be a divisor of `SLOTS_PER_HISTORICAL_ROOT`,
+    /// and must match the value that the database was initialized with (if any).
+    ///
+    /// Both databases are opened with the `backend` from the `config`. If the `config` is
+    /// read-only, both databases must already exist and are never written to.
     pub fn open(
         hot_path: &Path,
         cold_path: &Path,
         config: StoreConfig,
         spec: ChainSpec,
         log: Logger,
-    ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, Error> {
+    ) -> Result<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_restore_points_per_snapshot(config.restore_points_per_snapshot)?;
+
+        BeaconNodeBackend::<E>::check_path(config.backend, hot_path)?;
+        BeaconNodeBackend::<E>::check_path(config.backend, cold_path)?;
+
+        if config.read_only {
+            for path in &[hot_path, cold_path] {
+                if !BeaconNodeBackend::<E>::exists(config.backend, path) {
+                    return Err(Error::MissingDatabase(path.to_path_buf()));
+                }
+            }
+        }
+
+        let hot_db = BeaconNodeBackend::open(config.backend, hot_path)?
+            .compression(config.compression.clone())
+            .read_only(config.read_only);
+
+        // The freezer path must be checked before the freezer database is opened, since opening
+        // it at a mistaken path would create a new, empty database there.
+        let record_freezer_path = Self::check_freezer_path(&hot_db, cold_path, &config, &log)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            anchor_info: RwLock::new(None),
+            freezer_lock: Mutex::new(()),
+            cold_db: BeaconNodeBackend::open(config.backend, cold_path)?
+                .compression(config.compression.clone())
+                .read_only(config.read_only),
+            hot_db,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            state_cache: Mutex::new(StateCache::new(config.state_cache_size_mb * 1024 * 1024)),
             config,
             spec,
             log,
             _phantom: PhantomData,
         };
 
+        // Ensure that the config is compatible with the one the database was initialized with,
+        // and record it if the database is new.
+        if let Some(on_disk_config) = db.load_config()? {
+            db.config.check_compatibility(&on_disk_config)?;
+        } else {
+            db.store_config()?;
+        }
+
+        if record_freezer_path {
+            db.store_freezer_path(&canonical_freezer_path(cold_path))?;
+        }
+
+        *db.anchor_info.write() = db.load_anchor_info()?;
+
         // Load the previous split slot from the database (if any). This ensures we can
         // stop and restart correctly.
-        if let Some(split) = db.load_split()? {
+        let split = db.load_split()?;
+
+        // Record the schema version of a new database. Databases which have a split point but no
+        // schema version pre-date versioning, and are upgraded by `schema_change::migrate_schema`.
+        if !db.config.read_only && split.is_none() && db.load_schema_version()?.is_none() {
+            db.store_schema_version(CURRENT_SCHEMA_VERSION)?;
+        }
+
+        if let Some(split) = split {
             info!(
                 db.log,
                 "Hot-Cold DB initialized";
@@ -157,6 +252,60 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         }
         Ok(db)
     }
+
+    /// Check that the freezer database at `cold_path` is the one that `hot_db` was last used
+    /// with, returning `true` if `cold_path` should be recorded once the freezer is open.
+    ///
+    /// A freezer database which has been moved (i.e., the recorded path no longer exists) is
+    /// accepted at its new location, provided a database exists there. If the recorded path still
+    /// exists then the user has most likely forgotten `--freezer-dir`. If no freezer database
+    /// exists at `cold_path` for a hot database which has already been used (e.g., the freezer
+    /// disk is not mounted) the freezer is missing. Opening an empty freezer DB in either case
+    /// would corrupt the chain.
+    ///
+    /// A read-only database is checked, but its recorded path is never updated.
+    fn check_freezer_path(
+        hot_db: &BeaconNodeBackend<E>,
+        cold_path: &Path,
+        config: &StoreConfig,
+        log: &Logger,
+    ) -> Result<bool, Error> {
+        let configured = canonical_freezer_path(cold_path);
+        let cold_db_exists = BeaconNodeBackend::<E>::exists(config.backend, cold_path);
+        let hot_db_is_new = hot_db
+            .get::<OnDiskStoreConfig>(&Hash256::from_slice(CONFIG_DB_KEY.as_bytes()))?
+            .is_none();
+        let recorded =
+            hot_db.get::<FreezerPath>(&Hash256::from_slice(FREEZER_PATH_KEY.as_bytes()))?;
+
+        match recorded {
+            Some(recorded) if recorded == configured && cold_db_exists => Ok(false),
+            Some(recorded) if recorded.0.exists() => Err(Error::FreezerPathMismatch {
+                recorded: recorded.0,
+                configured: configured.0,
+            }),
+            Some(_) if !cold_db_exists => Err(Error::MissingFreezerDatabase(configured.0)),
+            None if !hot_db_is_new && !cold_db_exists => {
+                Err(Error::MissingFreezerDatabase(configured.0))
+            }
+            _ if config.read_only => Ok(false),
+            Some(recorded) => {
+                warn!(
+                    log,
+                    "Freezer database has moved";
+                    "previous_path" => format!("{:?}", recorded.0),
+                    "new_path" => format!("{:?}", configured.0),
+                );
+                Ok(true)
+            }
+            None => Ok(true),
+        }
+    }
+}
+
+/// The `FreezerPath` recorded for a freezer database at `cold_path`.
+fn canonical_freezer_path(cold_path: &Path) -> FreezerPath {
+    FreezerPath(fs::canonicalize(cold_path).unwrap_or_else(|_| cold_path.into()))
 }
 
 impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
@@ -170,11 +319,18 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.put(block_root, &block)?;
 
         // Update cache.
-        self.block_cache.lock().put(*block_root, block);
+        self.cache_block(*block_root, block);
 
         Ok(())
     }
 
+    /// Add a block to the LRU cache.
+    fn cache_block(&self, block_root: Hash256, block: SignedBeaconBlock<E>) {
+        let mut cache = self.block_cache.lock();
+        cache.put(block_root, block);
+        metrics::set_gauge(&metrics::BEACON_BLOCK_CACHE_SIZE, cache.len() as i64);
+    }
+
     /// Fetch a block from the store.
     pub fn get_block(&self, block_root: &Hash256) -> Result<Option<SignedBeaconBlock<E>>, Error> {
         metrics::inc_counter(&metrics::BEACON_BLOCK_GET_COUNT);
@@ -184,19 +340,52 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             metrics::inc_counter(&metrics::BEACON_BLOCK_CACHE_HIT_COUNT);
             return Ok(Some(block.clone()));
         }
+        metrics::inc_counter(&metrics::BEACON_BLOCK_CACHE_MISS_COUNT);
+
+        // Fetch from the hot database, falling back to the freezer for finalized blocks.
+        let column = DBColumn::BeaconBlock.into();
+        let key = block_root.as_bytes();
+        let block_bytes = match self.hot_db.get_bytes(column, key)? {
+            Some(block_bytes) => Some(block_bytes),
+            None => self.cold_db.get_bytes(column, key)?,
+        };
+
+        match block_bytes {
+            Some(block_bytes) => {
+                let block = SignedBeaconBlock::from_ssz_bytes(&block_bytes)?;
 
-        // Fetch from database.
-        match self.hot_db.get::<SignedBeaconBlock<E>>(block_root)? {
-            Some(block) => {
                 // Add to cache.
-                self.block_cache.lock().put(*block_root, block.clone());
+                self.cache_block(*block_root, block.clone());
                 Ok(Some(block))
             }
             None => Ok(None),
         }
     }
 
+    /// Returns the configuration of the database.
+    pub fn get_config(&self) -> &StoreConfig {
+        &self.config
+    }
+
+    /// Compact the hot database, reclaiming the space used by deleted blocks and states.
+    pub fn compact(&self) -> Result<(), Error> {
+        self.hot_db.compact()
+    }
+
+    /// Returns `true` if the block with `block_root` exists in either the hot or cold database.
+    pub fn block_exists(&self, block_root: &Hash256) -> Result<bool, Error> {
+        if self.block_cache.lock().contains(block_root) {
+            return Ok(true);
+        }
+
+        let column = DBColumn::BeaconBlock.into();
+        let key = block_root.as_bytes();
+        Ok(self.hot_db.key_exists(column, key)? || self.cold_db.key_exists(column, key)?)
+    }
+
     /// Delete a block from the store and the block cache.
+    ///
+    /// Only blocks in the hot database are deleted, frozen blocks are retained permanently.
     pub fn delete_block(&self, block_root: &Hash256) -> Result<(), Error> {
         self.block_cache.lock().pop(block_root);
         self.hot_db.delete::<SignedBeaconBlock<E>>(block_root)
@@ -238,24 +427,83 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ) -> Result<Option<BeaconState<E>>, Error> {
         metrics::inc_counter(&metrics::BEACON_STATE_GET_COUNT);
 
-        if let Some(slot) = slot {
+        if let Some(state) = self.get_cached_state(state_root) {
+            return Ok(Some(state));
+        }
+
+        let state = if let Some(slot) = slot {
             if slot < self.get_split_slot() {
                 // Although we could avoid a DB lookup by shooting straight for the
                 // frozen state using `load_cold_state_by_slot`, that would be incorrect
                 // in the case where the caller provides a `state_root` that's off the canonical
                 // chain. This way we avoid returning a state that doesn't match `state_root`.
-                self.load_cold_state(state_root)
+                self.load_cold_state(state_root)?
             } else {
-                self.load_hot_state(state_root, BlockReplay::Accurate)
+                self.load_hot_state(state_root, StateRootStrategy::Accurate)?
             }
         } else {
-            match self.load_hot_state(state_root, BlockReplay::Accurate)? {
-                Some(state) => Ok(Some(state)),
-                None => self.load_cold_state(state_root),
+            match self.load_hot_state(state_root, StateRootStrategy::Accurate)? {
+                Some(state) => Some(state),
+                None => self.load_cold_state(state_root)?,
+            }
+        };
+
+        if let Some(state) = &state {
+            self.cache_state(*state_root, state);
+        }
+
+        Ok(state)
+    }
+
+    /// Return a copy of the state with `state_root` from the state cache, if present.
+    fn get_cached_state(&self, state_root: &Hash256) -> Option<BeaconState<E>> {
+        let mut cache = self.state_cache.lock();
+        if cache.is_disabled() {
+            return None;
+        }
+
+        match cache.get(state_root) {
+            Some(state) => {
+                metrics::inc_counter(&metrics::BEACON_STATE_CACHE_HIT_COUNT);
+                let _timer = metrics::start_timer(&metrics::BEACON_STATE_CACHE_CLONE_TIME);
+                Some(state.clone_with(CloneConfig::committee_caches_only()))
+            }
+            None => {
+                metrics::inc_counter(&metrics::BEACON_STATE_CACHE_MISS_COUNT);
+                None
             }
         }
     }
 
+    /// Add a copy of `state` to the state cache.
+    fn cache_state(&self, state_root: Hash256, state: &BeaconState<E>) {
+        let mut cache = self.state_cache.lock();
+        if cache.is_disabled() {
+            return;
+        }
+
+        cache.put(
+            state_root,
+            state.clone_with(CloneConfig::committee_caches_only()),
+        );
+        Self::update_state_cache_metrics(&cache);
+    }
+
+    /// Remove the state with `state_root` from the state cache.
+    fn uncache_state(&self, state_root: &Hash256) {
+        let mut cache = self.state_cache.lock();
+        cache.pop(state_root);
+        Self::update_state_cache_metrics(&cache);
+    }
+
+    fn update_state_cache_metrics(cache: &StateCache<E>) {
+        metrics::set_gauge(&metrics::BEACON_STATE_CACHE_SIZE, cache.len() as i64);
+        metrics::set_gauge(
+            &metrics::BEACON_STATE_CACHE_BYTES,
+            cache.total_bytes() as i64,
+        );
+    }
+
     /// Fetch a state from the store, but don't compute all of the values when replaying blocks
     /// upon that state (e.g., state roots). Additionally, only states from the hot store are
     /// returned.
@@ -276,7 +524,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         if slot.map_or(false, |slot| slot < self.get_split_slot()) {
             Ok(None)
         } else {
-            self.load_hot_state(state_root, BlockReplay::InconsistentStateRoots)
+            self.load_hot_state(state_root, StateRootStrategy::Inconsistent)
         }
     }
 
@@ -287,6 +535,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// (which are frozen, and won't be deleted), or valid descendents of the finalized checkpoint
     /// (which will be deleted by this function but shouldn't be).
     pub fn delete_state(&self, state_root: &Hash256, slot: Slot) -> Result<(), Error> {
+        self.uncache_state(state_root);
+
         // Delete the state summary.
         self.hot_db
             .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())?;
@@ -307,6 +557,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         end_block_root: Hash256,
         spec: &ChainSpec,
     ) -> Result<impl Iterator<Item = Result<(Hash256, Slot), Error>>, Error> {
+        store.check_block_history(start_slot)?;
         HybridForwardsBlockRootsIterator::new(store, start_slot, end_state, end_block_root, spec)
     }
 
@@ -324,10 +575,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         {
             // NOTE: minor inefficiency here because we load an unnecessary hot state summary
             //
-            // `BlockReplay` should be irrelevant here since we never replay blocks for an epoch
+            // `StateRootStrategy` should be irrelevant here since we never replay blocks for an epoch
             // boundary state in the hot DB.
             let state = self
-                .load_hot_state(&epoch_boundary_state_root, BlockReplay::Accurate)?
+                .load_hot_state(&epoch_boundary_state_root, StateRootStrategy::Accurate)?
                 .ok_or_else(|| {
                     HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root)
                 })?;
@@ -385,6 +636,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                     key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
                 }
 
+                StoreOp::PutStateTemporaryFlag(state_hash) => {
+                    let untyped_hash: Hash256 = (*state_hash).into();
+                    key_value_batch.push(TemporaryFlag.as_kv_store_op(untyped_hash));
+                }
+
+                StoreOp::DeleteStateTemporaryFlag(state_hash) => {
+                    let untyped_hash: Hash256 = (*state_hash).into();
+                    let key = get_key_for_col(
+                        DBColumn::BeaconStateTemporary.into(),
+                        untyped_hash.as_bytes(),
+                    );
+                    key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
+                }
+
                 StoreOp::DeleteState(state_hash, slot) => {
                     let untyped_hash: Hash256 = (*state_hash).into();
                     let state_summary_key = get_key_for_col(
@@ -419,9 +684,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                     guard.pop(&untyped_hash);
                 }
 
-                StoreOp::DeleteState(_, _) => (),
+                StoreOp::DeleteState(state_hash, _) => {
+                    self.uncache_state(&(*state_hash).into());
+                }
+
+                StoreOp::PutStateTemporaryFlag(_) => (),
+
+                StoreOp::DeleteStateTemporaryFlag(_) => (),
             }
         }
+        metrics::set_gauge(&metrics::BEACON_BLOCK_CACHE_SIZE, guard.len() as i64);
         Ok(())
     }
     /// Store a post-finalization state efficiently in the hot database.
@@ -461,7 +733,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     pub fn load_hot_state(
         &self,
         state_root: &Hash256,
-        block_replay: BlockReplay,
+        state_root_strategy: StateRootStrategy,
     ) -> Result<Option<BeaconState<E>>, Error> {
         metrics::inc_counter(&metrics::BEACON_STATE_HOT_GET_COUNT);
 
@@ -483,7 +755,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             } else {
                 let blocks =
                     self.load_blocks_to_replay(boundary_state.slot, slot, latest_block_root)?;
-                self.replay_blocks(boundary_state, blocks, slot, block_replay)?
+                self.replay_blocks(boundary_state, blocks, slot, state_root_strategy)?
             };
 
             Ok(Some(state))
@@ -519,10 +791,29 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "state_root" => format!("{:?}", state_root)
         );
 
-        // 1. Convert to PartialBeaconState and store that in the DB.
-        let partial_state = PartialBeaconState::from_state_forgetful(state);
-        let op = partial_state.as_kv_store_op(*state_root);
-        ops.push(op);
+        let restore_point_index = state.slot.as_u64() / self.config.slots_per_restore_point;
+        let timer = metrics::start_timer(&metrics::BEACON_RESTORE_POINT_WRITE_TIMES);
+
+        // 1. Convert to PartialBeaconState and store that in the DB, either in full or as a diff
+        // against the previous restore point.
+        //
+        // If the previous restore point hasn't been stored yet (e.g., because several restore
+        // points are being frozen at once), then a full snapshot is stored instead.
+        let diff_base = if restore_point_index % self.config.restore_points_per_snapshot != 0 {
+            self.load_restore_point_hash(restore_point_index - 1).ok()
+        } else {
+            None
+        };
+
+        if let Some(base_state_root) = diff_base {
+            let base_state = self.load_restore_point_partial_state(&base_state_root)?;
+            let diff = PartialBeaconStateDiff::new(base_state_root, &base_state.validators, state);
+            ops.push(diff.as_kv_store_op(*state_root));
+        } else {
+            let partial_state = PartialBeaconState::from_state_forgetful(state);
+            ops.push(partial_state.as_kv_store_op(*state_root));
+        }
+        metrics::stop_timer(timer);
 
         // 2. Store updated vector entries.
         let db = &self.cold_db;
@@ -532,7 +823,6 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
 
         // 3. Store restore point.
-        let restore_point_index = state.slot.as_u64() / self.config.slots_per_restore_point;
         self.store_restore_point_hash(restore_point_index, *state_root, ops);
 
         Ok(())
@@ -552,6 +842,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// Will reconstruct the state if it lies between restore points.
     pub fn load_cold_state_by_slot(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
+        self.check_state_history(slot)?;
+        metrics::inc_counter(&metrics::BEACON_STATE_COLD_GET_COUNT);
+
         if slot % self.config.slots_per_restore_point == 0 {
             let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
             self.load_restore_point_by_index(restore_point_idx)
@@ -561,11 +854,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load a restore point state by its `state_root`.
-    fn load_restore_point(&self, state_root: &Hash256) -> Result<BeaconState<E>, Error> {
-        let mut partial_state: PartialBeaconState<E> = self
-            .cold_db
-            .get(state_root)?
-            .ok_or_else(|| HotColdDBError::MissingRestorePoint(*state_root))?;
+    pub(crate) fn load_restore_point(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<BeaconState<E>, Error> {
+        let _timer = metrics::start_timer(&metrics::BEACON_RESTORE_POINT_READ_TIMES);
+        let mut partial_state = self.load_restore_point_partial_state(state_root)?;
 
         // Fill in the fields of the partial state.
         partial_state.load_block_roots(&self.cold_db, &self.spec)?;
@@ -576,6 +870,44 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(partial_state.try_into()?)
     }
 
+    /// Load the `PartialBeaconState` of a restore point by its `state_root`, without any of its
+    /// vector fields.
+    ///
+    /// If the restore point is stored as a diff, the chain of diffs is followed back to the most
+    /// recent full snapshot and then applied in order.
+    fn load_restore_point_partial_state(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<PartialBeaconState<E>, Error> {
+        let mut diffs = vec![];
+        let mut next_state_root = *state_root;
+
+        let mut partial_state = loop {
+            if let Some(partial_state) = self
+                .cold_db
+                .get::<PartialBeaconState<E>>(&next_state_root)?
+            {
+                break partial_state;
+            }
+
+            let diff: PartialBeaconStateDiff<E> = self
+                .cold_db
+                .get(&next_state_root)?
+                .ok_or_else(|| HotColdDBError::MissingRestorePoint(next_state_root))?;
+            next_state_root = diff.base_state_root;
+            diffs.push((next_state_root, diff));
+        };
+
+        let _timer = metrics::start_timer(&metrics::BEACON_RESTORE_POINT_DIFF_APPLY_TIMES);
+        for (base_state_root, diff) in diffs.into_iter().rev() {
+            partial_state = diff
+                .apply(&partial_state.validators)
+                .ok_or_else(|| HotColdDBError::InvalidStateDiff(base_state_root))?;
+        }
+
+        Ok(partial_state)
+    }
+
     /// Load a restore point state by its `restore_point_index`.
     fn load_restore_point_by_index(
         &self,
@@ -614,7 +946,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         )?;
 
         // 3. Replay the blocks on top of the low restore point.
-        self.replay_blocks(low_restore_point, blocks, slot, BlockReplay::Accurate)
+        let _timer = metrics::start_timer(&metrics::BEACON_COLD_STATE_REPLAY_TIMES);
+        self.replay_blocks(low_restore_point, blocks, slot, StateRootStrategy::Accurate)
     }
 
     /// Get a suitable block root for backtracking from `high_restore_point` to the state at `slot`.
@@ -670,78 +1003,167 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// to have any caches built, beyond those immediately required by block processing.
     fn replay_blocks(
         &self,
-        mut state: BeaconState<E>,
-        mut blocks: Vec<SignedBeaconBlock<E>>,
+        state: BeaconState<E>,
+        blocks: Vec<SignedBeaconBlock<E>>,
         target_slot: Slot,
-        block_replay: BlockReplay,
+        state_root_strategy: StateRootStrategy,
     ) -> Result<BeaconState<E>, Error> {
-        if block_replay == BlockReplay::InconsistentStateRoots {
-            for i in 0..blocks.len() {
-                blocks[i].message.state_root = Hash256::zero();
-                if i > 0 {
-                    blocks[i].message.parent_root = blocks[i - 1].canonical_root()
-                }
+        BlockReplayer::new(state, &self.spec)
+            .state_root_strategy(state_root_strategy)
+            .no_signature_verification()
+            .apply_blocks(blocks, Some(target_slot))
+            .map(BlockReplayer::into_state)
+            .map_err(|e| HotColdDBError::BlockReplayError(e).into())
+    }
+
+    /// Fetch a copy of the current split slot from memory.
+    pub fn get_split_slot(&self) -> Slot {
+        self.split.read().slot
+    }
+
+    /// Fetch a copy of the current split slot and state root from memory.
+    pub fn get_split_info(&self) -> Split {
+        *self.split.read()
+    }
+
+    /// Fetch the slot of the most recently stored restore point.
+    pub fn get_latest_restore_point_slot(&self) -> Slot {
+        (self.get_split_slot() - 1) / self.config.slots_per_restore_point
+            * self.config.slots_per_restore_point
+    }
+
+    /// Load the database config from disk, if it has been stored.
+    fn load_config(&self) -> Result<Option<OnDiskStoreConfig>, Error> {
+        let key = Hash256::from_slice(CONFIG_DB_KEY.as_bytes());
+        self.hot_db.get(&key)
+    }
+
+    /// Write the config of this database to disk.
+    fn store_config(&self) -> Result<(), Error> {
+        let key = Hash256::from_slice(CONFIG_DB_KEY.as_bytes());
+        self.hot_db.put_sync(&key, &self.config.as_disk_config())
+    }
+
+    /// Load the path of the freezer database from disk, if it has been stored.
+    pub fn load_freezer_path(&self) -> Result<Option<FreezerPath>, Error> {
+        let key = Hash256::from_slice(FREEZER_PATH_KEY.as_bytes());
+        self.hot_db.get(&key)
+    }
+
+    /// Write the path of the freezer database to disk.
+    fn store_freezer_path(&self, freezer_path: &FreezerPath) -> Result<(), Error> {
+        let key = Hash256::from_slice(FREEZER_PATH_KEY.as_bytes());
+        self.hot_db.put_sync(&key, freezer_path)
+    }
+
+    /// Delete any states which are still flagged as temporary, i.e., states which were written
+    /// during the import of a block that never completed (e.g., due to a crash).
+    pub fn delete_temp_states(&self) -> Result<(), Error> {
+        let mut ops = vec![];
+        let mut count = 0;
+
+        for state_root in self.hot_db.iter_column_keys(DBColumn::BeaconStateTemporary) {
+            let state_root = state_root?;
+            for column in &[
+                DBColumn::BeaconState,
+                DBColumn::BeaconStateSummary,
+                DBColumn::BeaconStateTemporary,
+            ] {
+                let key = get_key_for_col((*column).into(), &state_root);
+                ops.push(KeyValueStoreOp::DeleteKey(key));
             }
+            count += 1;
         }
 
-        let state_root_from_prev_block = |i: usize, state: &BeaconState<E>| {
-            if i > 0 {
-                let prev_block = &blocks[i - 1].message;
-                if prev_block.slot == state.slot {
-                    Some(prev_block.state_root)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        if count > 0 {
+            self.hot_db.do_atomically(ops)?;
+            info!(self.log, "Deleted temporary states"; "count" => count);
+        }
+
+        Ok(())
+    }
+
+    /// Get the anchor of the database, or `None` if it contains the full history of the chain.
+    pub fn get_anchor_info(&self) -> Option<AnchorInfo> {
+        *self.anchor_info.read()
+    }
+
+    /// Record that the database was initialized from the state at `anchor_slot` rather than
+    /// genesis, such that no blocks or states prior to `anchor_slot` are available.
+    ///
+    /// The `oldest_block_parent` is the parent root of the block at `anchor_slot`.
+    pub fn init_anchor_info(
+        &self,
+        anchor_slot: Slot,
+        oldest_block_parent: Hash256,
+    ) -> Result<(), Error> {
+        let anchor_info = AnchorInfo {
+            anchor_slot,
+            oldest_block_slot: anchor_slot,
+            oldest_block_parent,
+            oldest_state_slot: anchor_slot,
         };
+        self.hot_db.put_sync(
+            &Hash256::from_slice(ANCHOR_INFO_KEY.as_bytes()),
+            &anchor_info,
+        )?;
+        *self.anchor_info.write() = Some(anchor_info);
+        Ok(())
+    }
 
-        for (i, block) in blocks.iter().enumerate() {
-            if block.message.slot <= state.slot {
-                continue;
-            }
+    /// Delete the anchor of the database, once it contains the full history of the chain.
+    pub fn clear_anchor_info(&self) -> Result<(), Error> {
+        let key = Hash256::from_slice(ANCHOR_INFO_KEY.as_bytes());
+        self.hot_db
+            .key_delete(DBColumn::BeaconMeta.into(), key.as_bytes())?;
+        *self.anchor_info.write() = None;
+        Ok(())
+    }
 
-            while state.slot < block.message.slot {
-                let state_root = match block_replay {
-                    BlockReplay::Accurate => state_root_from_prev_block(i, &state),
-                    BlockReplay::InconsistentStateRoots => Some(Hash256::zero()),
-                };
-                per_slot_processing(&mut state, state_root, &self.spec)
-                    .map_err(HotColdDBError::BlockReplaySlotError)?;
-            }
+    /// Load the anchor of the database from disk, if it has been stored.
+    fn load_anchor_info(&self) -> Result<Option<AnchorInfo>, Error> {
+        let key = Hash256::from_slice(ANCHOR_INFO_KEY.as_bytes());
+        self.hot_db.get(&key)
+    }
 
-            per_block_processing(
-                &mut state,
-                &block,
-                None,
-                BlockSignatureStrategy::NoVerification,
-                &self.spec,
-            )
-            .map_err(HotColdDBError::BlockReplayBlockError)?;
+    /// Return a `HistoryUnavailable` error if the block at `slot` pre-dates the anchor.
+    pub fn check_block_history(&self, slot: Slot) -> Result<(), Error> {
+        match self.get_anchor_info() {
+            Some(anchor) if slot < anchor.oldest_block_slot => {
+                Err(HotColdDBError::HistoryUnavailable {
+                    requested_slot: slot,
+                    oldest_available_slot: anchor.oldest_block_slot,
+                }
+                .into())
+            }
+            _ => Ok(()),
         }
+    }
 
-        while state.slot < target_slot {
-            let state_root = match block_replay {
-                BlockReplay::Accurate => state_root_from_prev_block(blocks.len(), &state),
-                BlockReplay::InconsistentStateRoots => Some(Hash256::zero()),
-            };
-            per_slot_processing(&mut state, state_root, &self.spec)
-                .map_err(HotColdDBError::BlockReplaySlotError)?;
+    /// Return a `HistoryUnavailable` error if the state at `slot` pre-dates the anchor.
+    pub fn check_state_history(&self, slot: Slot) -> Result<(), Error> {
+        match self.get_anchor_info() {
+            Some(anchor) if slot < anchor.oldest_state_slot => {
+                Err(HotColdDBError::HistoryUnavailable {
+                    requested_slot: slot,
+                    oldest_available_slot: anchor.oldest_state_slot,
+                }
+                .into())
+            }
+            _ => Ok(()),
         }
-
-        Ok(state)
     }
 
-    /// Fetch a copy of the current split slot from memory.
-    pub fn get_split_slot(&self) -> Slot {
-        self.split.read().slot
+    /// Load the schema version of the database from disk, if it has been stored.
+    pub fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
+        let key = Hash256::from_slice(SCHEMA_VERSION_KEY.as_bytes());
+        self.hot_db.get(&key)
     }
 
-    /// Fetch the slot of the most recently stored restore point.
-    pub fn get_latest_restore_point_slot(&self) -> Slot {
-        (self.get_split_slot() - 1) / self.config.slots_per_restore_point
-            * self.config.slots_per_restore_point
+    /// Write the schema version of the database to disk.
+    pub fn store_schema_version(&self, schema_version: SchemaVersion) -> Result<(), Error> {
+        let key = Hash256::from_slice(SCHEMA_VERSION_KEY.as_bytes());
+        self.hot_db.put_sync(&key, &schema_version)
     }
 
     /// Load the split point from disk.
@@ -806,6 +1228,18 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// (2) ensures that restore points align with hot state summaries, making it
     /// quick to migrate hot to cold.
+    fn verify_restore_points_per_snapshot(
+        restore_points_per_snapshot: u64,
+    ) -> Result<(), HotColdDBError> {
+        if restore_points_per_snapshot > 0 {
+            Ok(())
+        } else {
+            Err(HotColdDBError::InvalidRestorePointsPerSnapshot(
+                restore_points_per_snapshot,
+            ))
+        }
+    }
+
     fn verify_slots_per_restore_point(slots_per_restore_point: u64) -> Result<(), HotColdDBError> {
         let slots_per_historical_root = E::SlotsPerHistoricalRoot::to_u64();
         let slots_per_epoch = E::slots_per_epoch();
@@ -824,7 +1258,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 }
 
-/// Advance the split point of the store, moving new finalized states to the freezer.
+/// Advance the split point of the store, moving new finalized states and blocks to the freezer.
 pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     store: Arc<HotColdDB<E, Hot, Cold>>,
     frozen_head_root: Hash256,
@@ -866,6 +1300,8 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
 
         let mut cold_db_ops: Vec<KeyValueStoreOp> = Vec::new();
 
+        let _freezer_lock = store.freezer_lock.lock();
+
         if slot % store.config.slots_per_restore_point == 0 {
             let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root)?
                 .ok_or_else(|| HotColdDBError::MissingStateToFreeze(state_root))?;
@@ -887,6 +1323,37 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         hot_db_ops.push(StoreOp::DeleteState(state_root.into(), slot));
     }
 
+    // 2. Copy all of the canonical blocks prior to the new split slot from the hot DB to the cold
+    // DB.
+    //
+    // Blocks at skipped slots share the root of a prior block, and the block prior to
+    // `current_split_slot` may already have been frozen, so only blocks still present in the hot
+    // DB are copied.
+    let mut cold_db_block_ops: Vec<KeyValueStoreOp> = Vec::new();
+    let mut prev_block_root = None;
+    let block_root_iter = BlockRootsIterator::new(store.clone(), frozen_head);
+    for maybe_pair in block_root_iter.take_while(|result| match result {
+        Ok((_, slot)) => slot >= &current_split_slot,
+        Err(_) => true,
+    }) {
+        let (block_root, _) = maybe_pair?;
+
+        if prev_block_root == Some(block_root) {
+            continue;
+        }
+        prev_block_root = Some(block_root);
+
+        let column = DBColumn::BeaconBlock.into();
+        if let Some(block_bytes) = store.hot_db.get_bytes(column, block_root.as_bytes())? {
+            cold_db_block_ops.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(column, block_root.as_bytes()),
+                block_bytes,
+            ));
+            hot_db_ops.push(StoreOp::DeleteBlock(block_root.into()));
+        }
+    }
+    store.cold_db.do_atomically(cold_db_block_ops)?;
+
     // Warning: Critical section.  We have to take care not to put any of the two databases in an
     //          inconsistent state if the OS process dies at any point during the freezeing
     //          procedure.
@@ -897,7 +1364,7 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     // and will remain there forever.  Since dying in these particular few lines should be an
     // exceedingly rare event, this should be an acceptable tradeoff.
 
-    // Flush to disk all the states that have just been migrated to the cold store.
+    // Flush to disk all the states and blocks that have just been migrated to the cold store.
     store.cold_db.sync()?;
 
     {
@@ -937,7 +1404,7 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         *split_guard = split;
     }
 
-    // Delete the states from the hot database if we got this far.
+    // Delete the states and blocks from the hot database if we got this far.
     store.do_atomically(hot_db_ops)?;
 
     debug!(
@@ -949,11 +1416,29 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     Ok(())
 }
 
+/// Marks a state as temporary, see `StoreOp::PutStateTemporaryFlag`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemporaryFlag;
+
+impl StoreItem for TemporaryFlag {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateTemporary
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        vec![]
+    }
+
+    fn from_store_bytes(_: &[u8]) -> Result<Self, Error> {
+        Ok(TemporaryFlag)
+    }
+}
+
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 pub struct Split {
-    slot: Slot,
-    state_root: Hash256,
+    pub slot: Slot,
+    pub state_root: Hash256,
 }
 
 impl StoreItem for Split {
@@ -1019,8 +1504,8 @@ impl HotStateSummary {
 
 /// Struct for summarising a state in the freezer database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
-struct ColdStateSummary {
-    slot: Slot,
+pub(crate) struct ColdStateSummary {
+    pub(crate) slot: Slot,
 }
 
 impl StoreItem for ColdStateSummary {
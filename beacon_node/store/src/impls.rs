@@ -3,6 +3,7 @@ use ssz::{Decode, Encode};
 
 pub mod beacon_state;
 pub mod partial_beacon_state;
+pub mod state_diff;
 
 impl<T: EthSpec> StoreItem for SignedBeaconBlock<T> {
     fn db_column() -> DBColumn {
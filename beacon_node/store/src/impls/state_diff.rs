@@ -0,0 +1,17 @@
+use crate::state_diff::PartialBeaconStateDiff;
+use crate::*;
+use ssz::{Decode, Encode};
+
+impl<T: EthSpec> StoreItem for PartialBeaconStateDiff<T> {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateDiff
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
@@ -2,9 +2,11 @@ use super::*;
 use crate::metrics;
 use db_key::Key;
 use leveldb::database::batch::{Batch, Writebatch};
+use leveldb::database::compaction::Compaction;
 use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
+use leveldb::iterator::{Iterable, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
 use std::marker::PhantomData;
 use std::path::Path;
@@ -16,6 +18,11 @@ pub struct LevelDB<E: EthSpec> {
 }
 
 impl<E: EthSpec> LevelDB<E> {
+    /// Returns `true` if a LevelDB database exists at `path`.
+    pub fn exists(path: &Path) -> bool {
+        path.join("CURRENT").exists()
+    }
+
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
         let mut options = Options::new();
@@ -138,6 +145,54 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         self.db.write(self.write_options(), &leveldb_batch)?;
         Ok(())
     }
+
+    /// Compact the columns which see frequent deletions, i.e. blocks and states.
+    fn compact(&self) -> Result<(), Error> {
+        let endpoints = |column: DBColumn| {
+            (
+                BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_bytes())),
+                BytesKey::from_vec(get_key_for_col(
+                    column.into(),
+                    Hash256::repeat_byte(0xff).as_bytes(),
+                )),
+            )
+        };
+
+        for (start_key, end_key) in vec![
+            endpoints(DBColumn::BeaconState),
+            endpoints(DBColumn::BeaconStateSummary),
+            endpoints(DBColumn::BeaconBlock),
+        ] {
+            self.db.compact(&start_key, &end_key);
+        }
+        Ok(())
+    }
+
+    fn iter_raw_entries(&self) -> RawEntryIter<'_> {
+        Box::new(
+            self.db
+                .iter(self.read_options())
+                .map(|(key, value)| Ok((key.key, value))),
+        )
+    }
+
+    /// LevelDB iterators read from an implicit snapshot of the database, taken when the iterator
+    /// is created.
+    fn iter_raw_snapshot(&self) -> Result<RawEntryIter<'_>, Error> {
+        Ok(self.iter_raw_entries())
+    }
+
+    /// Iterate over the keys in `column`, seeking to the start of the column and stopping at its
+    /// end.
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter<'_> {
+        let prefix = column.as_str().as_bytes();
+        let iter = self.db.keys_iter(self.read_options());
+        iter.seek(&BytesKey::from_u8(prefix));
+        Box::new(
+            iter.take_while(move |key| key.key.starts_with(prefix))
+                .map(move |key| Ok(key.key[prefix.len()..].to_vec())),
+        )
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
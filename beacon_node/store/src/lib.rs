@@ -2,7 +2,7 @@
 //!
 //! Provides the following stores:
 //!
-//! - `HotColdDB`: an on-disk store backed by leveldb. Used in production.
+//! - `HotColdDB`: an on-disk store backed by leveldb (or optionally SQLite). Used in production.
 //! - `MemoryStore`: an in-memory store backed by a hash-map. Used for testing.
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
@@ -10,8 +10,10 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod backend;
 pub mod chunked_iter;
 pub mod chunked_vector;
+pub mod compression;
 pub mod config;
 pub mod errors;
 mod forwards_iter;
@@ -19,23 +21,43 @@ pub mod hot_cold_store;
 mod impls;
 mod leveldb_store;
 mod memory_store;
+pub mod metadata;
 mod metrics;
 mod partial_beacon_state;
+pub mod reconstruct;
+pub mod schema_change;
+pub mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+mod state_cache;
+mod state_diff;
 
 pub mod iter;
 
 use std::borrow::Cow;
+use std::str::FromStr;
 
-pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{BlockReplay, HotColdDB, HotStateSummary, Split};
+pub use self::backend::BeaconNodeBackend;
+pub use self::config::{DatabaseBackend, StoreConfig};
+pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
+pub use self::reconstruct::StateReconstruction;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite_store::SqliteStore;
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
 pub use metrics::scrape_for_metrics;
 pub use types::*;
 
+/// An iterator over every raw `(key, value)` pair in a `KeyValueStore`, including the column
+/// prefix of each key.
+pub type RawEntryIter<'a> = Box<dyn Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>> + 'a>;
+
+/// An iterator over the keys of a single column of a `KeyValueStore`, without the column prefix.
+pub type ColumnKeyIter<'a> = Box<dyn Iterator<Item = Result<Vec<u8>, Error>> + 'a>;
+
 pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Retrieve some bytes in `column` with `key`.
     fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
@@ -59,6 +81,37 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Execute either all of the operations in `batch` or none at all, returning an error.
     fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error>;
+
+    /// Compact the database, freeing space used by deleted items.
+    fn compact(&self) -> Result<(), Error>;
+
+    /// Iterate over all raw entries in the database, across all columns.
+    ///
+    /// Used to copy a database between backends.
+    fn iter_raw_entries(&self) -> RawEntryIter<'_>;
+
+    /// Iterate over all raw entries in the database as they were when this method was called,
+    /// unaffected by any concurrent writes.
+    ///
+    /// Used to export a snapshot of a database which is in use.
+    fn iter_raw_snapshot(&self) -> Result<RawEntryIter<'_>, Error> {
+        Err(Error::SnapshotUnsupported)
+    }
+
+    /// Iterate over the keys in `column`, with the column prefix removed.
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter<'_> {
+        let prefix = column.as_str().as_bytes();
+        Box::new(
+            self.iter_raw_entries()
+                .filter_map(move |entry| match entry {
+                    Ok((key, _)) if key.starts_with(prefix) => {
+                        Some(Ok(key[prefix.len()..].to_vec()))
+                    }
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e)),
+                }),
+        )
+    }
 }
 
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
@@ -127,6 +180,9 @@ pub enum StoreOp<'a, E: EthSpec> {
     PutStateSummary(BeaconStateHash, HotStateSummary),
     DeleteBlock(SignedBeaconBlockHash),
     DeleteState(BeaconStateHash, Slot),
+    /// Flag a state as temporary, such that it is deleted at startup unless the flag is removed.
+    PutStateTemporaryFlag(BeaconStateHash),
+    DeleteStateTemporaryFlag(BeaconStateHash),
 }
 
 /// A unique column identifier.
@@ -136,6 +192,8 @@ pub enum DBColumn {
     BeaconMeta,
     BeaconBlock,
     BeaconState,
+    /// For restore points that are stored as a diff against a prior restore point.
+    BeaconStateDiff,
     /// For persisting in-memory state to the database.
     BeaconChain,
     OpPool,
@@ -145,11 +203,19 @@ pub enum DBColumn {
     BeaconRestorePoint,
     /// For the mapping from state roots to their slots or summaries.
     BeaconStateSummary,
+    /// For flags marking states as temporary, see `StoreOp::PutStateTemporaryFlag`.
+    BeaconStateTemporary,
     BeaconBlockRoots,
     BeaconStateRoots,
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
     DhtEnrs,
+    /// For the known good peers which are persisted across restarts.
+    KnownPeers,
+    /// For the peers and IP addresses banned by the user.
+    BanList,
+    /// For the best `LightClientUpdate` of each sync committee period.
+    LightClientUpdate,
 }
 
 impl Into<&'static str> for DBColumn {
@@ -159,21 +225,73 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconMeta => "bma",
             DBColumn::BeaconBlock => "blk",
             DBColumn::BeaconState => "ste",
+            DBColumn::BeaconStateDiff => "bsd",
             DBColumn::BeaconChain => "bch",
             DBColumn::OpPool => "opo",
             DBColumn::Eth1Cache => "etc",
             DBColumn::ForkChoice => "frk",
             DBColumn::BeaconRestorePoint => "brp",
             DBColumn::BeaconStateSummary => "bss",
+            DBColumn::BeaconStateTemporary => "bst",
             DBColumn::BeaconBlockRoots => "bbr",
             DBColumn::BeaconStateRoots => "bsr",
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
             DBColumn::DhtEnrs => "dht",
+            DBColumn::KnownPeers => "kps",
+            DBColumn::BanList => "ban",
+            DBColumn::LightClientUpdate => "lcu",
         }
     }
 }
 
+impl DBColumn {
+    /// Every column, in the order they are declared.
+    pub const ALL: [DBColumn; 19] = [
+        DBColumn::BeaconMeta,
+        DBColumn::BeaconBlock,
+        DBColumn::BeaconState,
+        DBColumn::BeaconStateDiff,
+        DBColumn::BeaconChain,
+        DBColumn::OpPool,
+        DBColumn::Eth1Cache,
+        DBColumn::ForkChoice,
+        DBColumn::BeaconRestorePoint,
+        DBColumn::BeaconStateSummary,
+        DBColumn::BeaconStateTemporary,
+        DBColumn::BeaconBlockRoots,
+        DBColumn::BeaconStateRoots,
+        DBColumn::BeaconHistoricalRoots,
+        DBColumn::BeaconRandaoMixes,
+        DBColumn::DhtEnrs,
+        DBColumn::KnownPeers,
+        DBColumn::BanList,
+        DBColumn::LightClientUpdate,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        self.into()
+    }
+
+    /// Returns the length of the prefix which every column adds to its keys.
+    pub fn key_prefix_len() -> usize {
+        DBColumn::BeaconMeta.as_str().len()
+    }
+}
+
+/// Parses a column from the identifier produced by `DBColumn::as_str`.
+impl FromStr for DBColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|column| column.as_str() == s)
+            .ok_or_else(|| format!("Unknown column: {}", s))
+    }
+}
+
 /// An item that may stored in a `Store` by serializing and deserializing from bytes.
 pub trait StoreItem: Sized {
     /// Identifies which column this item should be placed in.
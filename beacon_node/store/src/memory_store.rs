@@ -1,4 +1,4 @@
-use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp, RawEntryIter};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -87,6 +87,26 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
         }
         Ok(())
     }
+
+    fn compact(&self) -> Result<(), Error> {
+        // no-op
+        Ok(())
+    }
+
+    fn iter_raw_entries(&self) -> RawEntryIter<'_> {
+        let entries = self
+            .db
+            .read()
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), value.clone())))
+            .collect::<Vec<_>>();
+        Box::new(entries.into_iter())
+    }
+
+    /// The entries are copied up front, so `iter_raw_entries` is already a snapshot.
+    fn iter_raw_snapshot(&self) -> Result<RawEntryIter<'_>, Error> {
+        Ok(self.iter_raw_entries())
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
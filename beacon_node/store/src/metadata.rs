@@ -0,0 +1,137 @@
+use crate::{DBColumn, Error, StoreItem};
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::path::PathBuf;
+use types::{Hash256, Slot};
+
+/// The version of the on-disk database format written by this version of Lighthouse.
+///
+/// Increment this (and add a migration to `schema_change`) whenever the format changes.
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(2);
+
+/// The schema version of databases created before schema versions were recorded.
+pub const LEGACY_SCHEMA_VERSION: SchemaVersion = SchemaVersion(1);
+
+/// 32-byte key for accessing the `SchemaVersion` of the database.
+pub const SCHEMA_VERSION_KEY: &str = "SCHEMAVERSIONSCHEMAVERSIONSCHEMA";
+
+/// 32-byte key for accessing the `FreezerPath` of the database.
+pub const FREEZER_PATH_KEY: &str = "FREEZERDBPATHFREEZERDBPATHFREEZE";
+
+/// 32-byte key for accessing the `AnchorInfo` of the database.
+pub const ANCHOR_INFO_KEY: &str = "ANCHORINFOANCHORINFOANCHORINFOAN";
+
+/// 32-byte key for accessing the `ReconstructionProgress` of the database.
+pub const RECONSTRUCTION_PROGRESS_KEY: &str = "RECONPROGRESSRECONPROGRESSRECONP";
+
+/// The version of the on-disk database format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaVersion(pub u64);
+
+impl SchemaVersion {
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl StoreItem for SchemaVersion {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(SchemaVersion(u64::from_ssz_bytes(bytes)?))
+    }
+}
+
+/// The location of the freezer database, as recorded in the hot database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreezerPath(pub PathBuf);
+
+impl StoreItem for FreezerPath {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.to_string_lossy().as_bytes().to_vec()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let path = String::from_utf8(bytes.to_vec()).map_err(|e| Error::DBError {
+            message: format!("Invalid freezer path: {:?}", e),
+        })?;
+        Ok(FreezerPath(PathBuf::from(path)))
+    }
+}
+
+/// The oldest block and state available in a database which was not initialized from genesis.
+///
+/// A database without an `AnchorInfo` contains the full history of the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct AnchorInfo {
+    /// The slot of the state that the database was initialized from.
+    pub anchor_slot: Slot,
+    /// The slot of the oldest block in the database.
+    pub oldest_block_slot: Slot,
+    /// The parent root of the oldest block in the database, which is the next block to backfill.
+    pub oldest_block_parent: Hash256,
+    /// The slot of the oldest state which can be loaded from the database.
+    pub oldest_state_slot: Slot,
+}
+
+impl StoreItem for AnchorInfo {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+/// The most recent restore point stored by an unfinished state reconstruction, from which it can be
+/// resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct ReconstructionProgress {
+    pub restore_point_slot: Slot,
+    pub restore_point_state_root: Hash256,
+}
+
+impl StoreItem for ReconstructionProgress {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+/// A raw key-value pair from the database, as stored in a `MetadataBackup`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct MetadataBackupEntry {
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+/// A copy of the critical metadata in the hot database, written to disk before running schema
+/// migrations so that it can be recovered if a migration fails part-way through.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct MetadataBackup {
+    pub schema_version: u64,
+    pub entries: Vec<MetadataBackupEntry>,
+}
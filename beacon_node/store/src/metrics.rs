@@ -1,5 +1,6 @@
 pub use lighthouse_metrics::{set_gauge, try_create_int_gauge, *};
 
+use crate::DBColumn;
 use std::fs;
 use std::path::Path;
 
@@ -43,6 +44,34 @@ lazy_static! {
         "store_disk_db_delete_count_total",
         "Total number of deletions from the hot on-disk DB"
     );
+    /*
+     * Per-column
+     */
+    pub static ref DISK_DB_READ_COUNT_PER_COLUMN: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_read_count_per_column_total",
+        "Total number of reads from the on-disk DBs, per column",
+        &["col"]
+    );
+    pub static ref DISK_DB_READ_BYTES_PER_COLUMN: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_read_bytes_per_column_total",
+        "Number of bytes read from the on-disk DBs, per column",
+        &["col"]
+    );
+    pub static ref DISK_DB_WRITE_COUNT_PER_COLUMN: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_write_count_per_column_total",
+        "Total number of writes to the on-disk DBs, per column",
+        &["col"]
+    );
+    pub static ref DISK_DB_WRITE_BYTES_PER_COLUMN: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_write_bytes_per_column_total",
+        "Number of bytes written to the on-disk DBs (before compression), per column",
+        &["col"]
+    );
+    pub static ref DISK_DB_DELETE_COUNT_PER_COLUMN: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_delete_count_per_column_total",
+        "Total number of deletions from the on-disk DBs, per column",
+        &["col"]
+    );
     /*
      * Beacon State
      */
@@ -58,9 +87,21 @@ lazy_static! {
         "store_beacon_state_cache_hit_total",
         "Number of hits to the store's state cache"
     );
+    pub static ref BEACON_STATE_CACHE_MISS_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_beacon_state_cache_miss_total",
+        "Number of misses to the store's state cache"
+    );
+    pub static ref BEACON_STATE_CACHE_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "store_beacon_state_cache_size",
+        "Number of states in the store's state cache"
+    );
+    pub static ref BEACON_STATE_CACHE_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "store_beacon_state_cache_bytes",
+        "Approximate memory used by the states in the store's state cache (bytes)"
+    );
     pub static ref BEACON_STATE_CACHE_CLONE_TIME: Result<Histogram> = try_create_histogram(
         "store_beacon_state_cache_clone_time",
-        "Time to load a beacon block from the block cache"
+        "Time to load a beacon state from the state cache"
     );
     pub static ref BEACON_STATE_READ_TIMES: Result<Histogram> = try_create_histogram(
         "store_beacon_state_read_seconds",
@@ -90,6 +131,26 @@ lazy_static! {
         "store_beacon_state_write_bytes_total",
         "Total number of beacon state bytes written to the DB"
     );
+    pub static ref BEACON_STATE_COLD_GET_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_beacon_state_cold_get_total",
+        "Total number of frozen beacon states requested from the store"
+    );
+    pub static ref BEACON_RESTORE_POINT_READ_TIMES: Result<Histogram> = try_create_histogram(
+        "store_beacon_restore_point_read_seconds",
+        "Total time required to load a restore point from the freezer DB"
+    );
+    pub static ref BEACON_RESTORE_POINT_DIFF_APPLY_TIMES: Result<Histogram> = try_create_histogram(
+        "store_beacon_restore_point_diff_apply_seconds",
+        "Time taken to apply the diffs of a restore point to its base snapshot"
+    );
+    pub static ref BEACON_RESTORE_POINT_WRITE_TIMES: Result<Histogram> = try_create_histogram(
+        "store_beacon_restore_point_write_seconds",
+        "Time taken to encode a restore point (and its diff) for the freezer DB"
+    );
+    pub static ref BEACON_COLD_STATE_REPLAY_TIMES: Result<Histogram> = try_create_histogram(
+        "store_beacon_cold_state_replay_seconds",
+        "Time taken to replay blocks on a restore point to reach an intermediate frozen state"
+    );
     /*
      * Beacon Block
      */
@@ -101,6 +162,14 @@ lazy_static! {
         "store_beacon_block_cache_hit_total",
         "Number of hits to the store's block cache"
     );
+    pub static ref BEACON_BLOCK_CACHE_MISS_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_beacon_block_cache_miss_total",
+        "Number of misses to the store's block cache"
+    );
+    pub static ref BEACON_BLOCK_CACHE_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "store_beacon_block_cache_size",
+        "Number of blocks in the store's block cache"
+    );
     pub static ref BEACON_BLOCK_READ_TIMES: Result<Histogram> = try_create_histogram(
         "store_beacon_block_read_overhead_seconds",
         "Overhead on reading a beacon block from the DB (e.g., decoding)"
@@ -127,6 +196,13 @@ lazy_static! {
     );
 }
 
+/// Returns the label used by the per-column metrics for the column of `key`, which must include
+/// the column prefix.
+pub fn column_label(key: &[u8]) -> &str {
+    let prefix_len = std::cmp::min(DBColumn::key_prefix_len(), key.len());
+    std::str::from_utf8(&key[..prefix_len]).unwrap_or("unknown")
+}
+
 /// Updates the global metrics registry with store-related information.
 pub fn scrape_for_metrics(db_path: &Path, freezer_db_path: &Path) {
     let db_size = size_of_dir(db_path);
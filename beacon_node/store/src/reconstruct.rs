@@ -0,0 +1,288 @@
+//! Reconstruction of the historic states of a database which was not initialized from genesis.
+use crate::hot_cold_store::{ColdStateSummary, HotColdDB, HotColdDBError};
+use crate::metadata::{ReconstructionProgress, RECONSTRUCTION_PROGRESS_KEY};
+use crate::{DBColumn, Error, ItemStore, KeyValueStoreOp, StoreItem};
+use slog::info;
+use state_processing::BlockReplayer;
+use std::ops::Range;
+use types::{BeaconState, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// The progress of rebuilding the freezer states prior to the anchor of a database, by replaying
+/// blocks from genesis.
+///
+/// Blocks are supplied in batches via `apply_blocks`, in slot order, ending with the anchor block.
+/// The state root of each block is used as-is, so only the states at restore points and skipped
+/// slots are hashed.
+/// Each block's parent root is checked against the replayed state, so a bad block or state root
+/// is detected no later than `finish`, which only removes the anchor of the database once the
+/// replayed state matches the state the database was initialized from. Until then, none of the
+/// replayed states are available.
+///
+/// Each stored restore point is recorded in the database, so that an interrupted reconstruction
+/// can continue from it via `resume_state_reconstruction`.
+pub struct StateReconstruction<E: EthSpec> {
+    state: BeaconState<E>,
+    anchor_slot: Slot,
+    anchor_block: SignedBeaconBlock<E>,
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
+    /// Begin reconstructing the states prior to the anchor of the database from `genesis_state`.
+    pub fn start_state_reconstruction(
+        &self,
+        genesis_state: BeaconState<E>,
+    ) -> Result<StateReconstruction<E>, Error> {
+        if genesis_state.slot != self.spec.genesis_slot {
+            return Err(HotColdDBError::ReconstructionNotFromGenesis(genesis_state.slot).into());
+        }
+
+        let reconstruction = self.state_reconstruction_from(genesis_state)?;
+
+        info!(
+            self.log,
+            "Starting historic state reconstruction";
+            "anchor_slot" => reconstruction.anchor_slot,
+            "anchor_state_root" => format!("{:?}", reconstruction.anchor_block.message.state_root),
+        );
+
+        Ok(reconstruction)
+    }
+
+    /// Continue an interrupted state reconstruction from the last restore point it stored.
+    ///
+    /// Return `None` if no state reconstruction has stored a restore point.
+    pub fn resume_state_reconstruction(&self) -> Result<Option<StateReconstruction<E>>, Error> {
+        let progress = match self.load_reconstruction_progress()? {
+            Some(progress) => progress,
+            None => return Ok(None),
+        };
+
+        let state = self.load_restore_point(&progress.restore_point_state_root)?;
+        let reconstruction = self.state_reconstruction_from(state)?;
+
+        info!(
+            self.log,
+            "Resuming historic state reconstruction";
+            "slot" => progress.restore_point_slot,
+            "anchor_slot" => reconstruction.anchor_slot,
+        );
+
+        Ok(Some(reconstruction))
+    }
+
+    /// Prepare to replay blocks atop `state` towards the anchor of the database.
+    fn state_reconstruction_from(
+        &self,
+        state: BeaconState<E>,
+    ) -> Result<StateReconstruction<E>, Error> {
+        let anchor = self
+            .get_anchor_info()
+            .ok_or(HotColdDBError::StateReconstructionNotRequired)?;
+
+        // The beacon chain builder stores the anchor block under the zero hash. It commits to the
+        // anchor state, which the replayed state is eventually checked against.
+        let anchor_block = self
+            .get_block(&Hash256::zero())?
+            .ok_or(HotColdDBError::MissingAnchorBlock)?;
+
+        Ok(StateReconstruction {
+            state,
+            anchor_slot: anchor.anchor_slot,
+            anchor_block,
+        })
+    }
+
+    /// Store a block prior to the anchor in the freezer, ready to be replayed.
+    pub fn store_reconstructed_block(&self, block: &SignedBeaconBlock<E>) -> Result<(), Error> {
+        self.cold_db.put(&block.canonical_root(), block)
+    }
+
+    /// Store a replayed restore point `state`, along with the summaries of the states since the
+    /// previous restore point, and record it as the progress of the reconstruction.
+    ///
+    /// States which don't lie on a restore point are ignored.
+    fn store_reconstructed_state(&self, state: &mut BeaconState<E>) -> Result<(), Error> {
+        let slots_per_restore_point = self.get_config().slots_per_restore_point;
+        if state.slot % slots_per_restore_point != 0 {
+            return Ok(());
+        }
+
+        let state_root = state.update_tree_hash_cache()?;
+        let slot = state.slot.as_u64();
+        let mut ops = reconstructed_summary_ops(
+            state,
+            slot.saturating_sub(slots_per_restore_point - 1)..slot,
+        )?;
+        ops.push(ColdStateSummary { slot: state.slot }.as_kv_store_op(state_root));
+
+        {
+            let _freezer_lock = self.freezer_lock.lock();
+            self.store_cold_state(&state_root, state, &mut ops)?;
+            self.cold_db.do_atomically(ops)?;
+        }
+
+        // The restore point must be on disk before the progress refers to it.
+        self.cold_db.sync()?;
+        self.hot_db.put(
+            &Hash256::from_slice(RECONSTRUCTION_PROGRESS_KEY.as_bytes()),
+            &ReconstructionProgress {
+                restore_point_slot: state.slot,
+                restore_point_state_root: state_root,
+            },
+        )
+    }
+
+    /// Load the progress of an unfinished state reconstruction, if any.
+    fn load_reconstruction_progress(&self) -> Result<Option<ReconstructionProgress>, Error> {
+        self.hot_db
+            .get(&Hash256::from_slice(RECONSTRUCTION_PROGRESS_KEY.as_bytes()))
+    }
+
+    /// Delete the progress of a finished state reconstruction.
+    fn clear_reconstruction_progress(&self) -> Result<(), Error> {
+        self.hot_db.key_delete(
+            DBColumn::BeaconMeta.into(),
+            RECONSTRUCTION_PROGRESS_KEY.as_bytes(),
+        )
+    }
+}
+
+impl<E: EthSpec> StateReconstruction<E> {
+    /// The slot of the most recently replayed state.
+    pub fn slot(&self) -> Slot {
+        self.state.slot
+    }
+
+    /// The slot of the state the database was initialized from.
+    pub fn anchor_slot(&self) -> Slot {
+        self.anchor_slot
+    }
+
+    /// The block committing to the state the database was initialized from, which must be the
+    /// last block applied.
+    pub fn anchor_block(&self) -> &SignedBeaconBlock<E> {
+        &self.anchor_block
+    }
+
+    /// Apply `blocks` in slot order, storing the restore points passed through in the freezer.
+    ///
+    /// The blocks must already have been stored with `store_reconstructed_block`. Every block
+    /// which lies on a restore point is checked against the state root it commits to.
+    pub fn apply_blocks<Hot: ItemStore<E>, Cold: ItemStore<E>>(
+        mut self,
+        store: &HotColdDB<E, Hot, Cold>,
+        blocks: Vec<SignedBeaconBlock<E>>,
+    ) -> Result<Self, Error> {
+        let mut state_slot = self.state.slot;
+        for block in &blocks {
+            let block_slot = block.message.slot;
+            if block_slot <= state_slot || block_slot > self.anchor_slot {
+                return Err(HotColdDBError::ReconstructionBlockOutOfOrder {
+                    block_slot,
+                    state_slot,
+                    anchor_slot: self.anchor_slot,
+                }
+                .into());
+            }
+            state_slot = block_slot;
+        }
+
+        let slots_per_restore_point = store.get_config().slots_per_restore_point;
+        self.state = BlockReplayer::<E, Error>::new(self.state, &store.spec)
+            .no_signature_verification()
+            .pre_slot_hook(Box::new(|state: &mut BeaconState<E>| {
+                store.store_reconstructed_state(state)
+            }))
+            .post_block_hook(Box::new(
+                |state: &mut BeaconState<E>, block: &SignedBeaconBlock<E>| {
+                    if block.message.slot % slots_per_restore_point == 0 {
+                        check_state_root(state, block)?;
+                    }
+                    Ok(())
+                },
+            ))
+            .apply_blocks(blocks, None)?
+            .into_state();
+
+        Ok(self)
+    }
+
+    /// Advance to the anchor slot and check the replayed state against the anchor state, removing
+    /// the anchor of the database if they match.
+    pub fn finish<Hot: ItemStore<E>, Cold: ItemStore<E>>(
+        self,
+        store: &HotColdDB<E, Hot, Cold>,
+    ) -> Result<(), Error> {
+        let mut state = BlockReplayer::<E, Error>::new(self.state, &store.spec)
+            .no_signature_verification()
+            .pre_slot_hook(Box::new(|state: &mut BeaconState<E>| {
+                store.store_reconstructed_state(state)
+            }))
+            .apply_blocks(vec![], Some(self.anchor_slot))?
+            .into_state();
+
+        let anchor_state_root = self.anchor_block.message.state_root;
+        let computed_state_root = state.update_tree_hash_cache()?;
+        if computed_state_root != anchor_state_root {
+            return Err(HotColdDBError::ReconstructionAnchorMismatch {
+                anchor_state_root,
+                computed_state_root,
+            }
+            .into());
+        }
+
+        // Summarise the states since the last restore point prior to the anchor.
+        let slots_per_restore_point = store.get_config().slots_per_restore_point;
+        let anchor_slot = self.anchor_slot.as_u64();
+        let last_restore_point =
+            anchor_slot.saturating_sub(1) / slots_per_restore_point * slots_per_restore_point;
+        store.cold_db.do_atomically(reconstructed_summary_ops(
+            &state,
+            last_restore_point + 1..anchor_slot,
+        )?)?;
+
+        store.cold_db.sync()?;
+        store.clear_anchor_info()?;
+        store.clear_reconstruction_progress()?;
+
+        info!(
+            store.log,
+            "Historic state reconstruction complete";
+            "anchor_slot" => self.anchor_slot,
+        );
+
+        Ok(())
+    }
+}
+
+/// The operations storing the summaries of the states at `slots`, using the state roots recorded
+/// in a later `state`.
+fn reconstructed_summary_ops<E: EthSpec>(
+    state: &BeaconState<E>,
+    slots: Range<u64>,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    slots
+        .map(|slot| {
+            let slot = Slot::new(slot);
+            let state_root = *state.get_state_root(slot)?;
+            Ok(ColdStateSummary { slot }.as_kv_store_op(state_root))
+        })
+        .collect()
+}
+
+/// Check that applying `block` to produce `state` resulted in the state root it commits to.
+fn check_state_root<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    block: &SignedBeaconBlock<E>,
+) -> Result<(), Error> {
+    let computed_state_root = state.update_tree_hash_cache()?;
+    if computed_state_root != block.message.state_root {
+        return Err(HotColdDBError::ReconstructionStateRootMismatch {
+            slot: block.message.slot,
+            block_state_root: block.message.state_root,
+            computed_state_root,
+        }
+        .into());
+    }
+    Ok(())
+}
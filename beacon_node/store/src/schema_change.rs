@@ -0,0 +1,164 @@
+//! Upgrades the on-disk database format between schema versions.
+//!
+//! Migrations are run in order at startup, each moving the database forward by exactly one
+//! version. Before any migration runs, the critical metadata in the hot database is written to a
+//! backup file so that it can be recovered if a migration fails part-way through.
+use crate::hot_cold_store::{HotColdDB, HotColdDBError};
+use crate::iter::BlockRootsIterator;
+use crate::metadata::{
+    MetadataBackup, MetadataBackupEntry, SchemaVersion, CURRENT_SCHEMA_VERSION,
+    LEGACY_SCHEMA_VERSION,
+};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use slog::{debug, info};
+use ssz::Encode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use types::EthSpec;
+
+/// The columns of the hot database which are backed up before running a migration.
+const BACKUP_COLUMNS: [DBColumn; 3] = [
+    DBColumn::BeaconMeta,
+    DBColumn::BeaconChain,
+    DBColumn::ForkChoice,
+];
+
+/// The number of blocks moved in each batch by `migrate_v1_to_v2`.
+const BLOCK_BATCH_SIZE: usize = 1_024;
+
+/// Migrate the database from its on-disk schema version to `CURRENT_SCHEMA_VERSION`.
+///
+/// Databases which pre-date schema versioning are assumed to be at `LEGACY_SCHEMA_VERSION`. The
+/// metadata backup is written to `backup_dir`.
+pub fn migrate_schema<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: Arc<HotColdDB<E, Hot, Cold>>,
+    backup_dir: &Path,
+) -> Result<(), Error> {
+    let from = db.load_schema_version()?.unwrap_or(LEGACY_SCHEMA_VERSION);
+    let to = CURRENT_SCHEMA_VERSION;
+
+    if from == to {
+        return Ok(());
+    } else if from > to {
+        return Err(HotColdDBError::SchemaVersionTooNew {
+            on_disk: from,
+            supported: to,
+        }
+        .into());
+    }
+
+    let backup_path = backup_metadata(&db, from, backup_dir)?;
+
+    info!(
+        db.log,
+        "Migrating database schema";
+        "from" => from.as_u64(),
+        "to" => to.as_u64(),
+        "metadata_backup" => format!("{:?}", backup_path),
+    );
+
+    for version in from.as_u64()..to.as_u64() {
+        match SchemaVersion(version) {
+            SchemaVersion(1) => migrate_v1_to_v2(db.clone())?,
+            other => return Err(HotColdDBError::MissingSchemaMigration(other).into()),
+        }
+
+        db.store_schema_version(SchemaVersion(version + 1))?;
+        debug!(db.log, "Database schema migrated"; "version" => version + 1);
+    }
+
+    info!(db.log, "Database schema migration complete"; "version" => to.as_u64());
+
+    Ok(())
+}
+
+/// Write the critical metadata of the hot database to a file in `backup_dir`, returning its path.
+fn backup_metadata<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: &HotColdDB<E, Hot, Cold>,
+    version: SchemaVersion,
+    backup_dir: &Path,
+) -> Result<PathBuf, Error> {
+    let entries = db
+        .hot_db
+        .iter_raw_entries()
+        .filter(|entry| {
+            entry.as_ref().map_or(true, |(key, _)| {
+                BACKUP_COLUMNS
+                    .iter()
+                    .any(|column| key.starts_with(Into::<&str>::into(*column).as_bytes()))
+            })
+        })
+        .map(|entry| entry.map(|(key, value)| MetadataBackupEntry { key, value }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let backup = MetadataBackup {
+        schema_version: version.as_u64(),
+        entries,
+    };
+
+    let path = backup_dir.join(format!("schema_v{}_metadata_backup.ssz", version.as_u64()));
+    fs::create_dir_all(backup_dir)
+        .and_then(|()| fs::write(&path, backup.as_ssz_bytes()))
+        .map_err(|e| Error::DBError {
+            message: format!("Unable to write metadata backup to {:?}: {:?}", path, e),
+        })?;
+
+    Ok(path)
+}
+
+/// Schema v2 stores finalized canonical blocks in the freezer database rather than the hot
+/// database. Move any blocks prior to the split slot which are still in the hot database.
+fn migrate_v1_to_v2<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Result<(), Error> {
+    let split = db.get_split_info();
+    let split_state = db
+        .get_state(&split.state_root, Some(split.slot))?
+        .ok_or_else(|| HotColdDBError::MissingSplitState(split.state_root, split.slot))?;
+
+    let column = DBColumn::BeaconBlock.into();
+    let mut prev_block_root = None;
+    let mut moved = 0;
+    let mut cold_ops = vec![];
+    let mut hot_ops = vec![];
+
+    for maybe_pair in BlockRootsIterator::owned(db.clone(), split_state) {
+        let (block_root, _) = maybe_pair?;
+
+        if prev_block_root == Some(block_root) {
+            continue;
+        }
+        prev_block_root = Some(block_root);
+
+        if let Some(block_bytes) = db.hot_db.get_bytes(column, block_root.as_bytes())? {
+            let key = get_key_for_col(column, block_root.as_bytes());
+            cold_ops.push(KeyValueStoreOp::PutKeyValue(key.clone(), block_bytes));
+            hot_ops.push(KeyValueStoreOp::DeleteKey(key));
+        }
+
+        if cold_ops.len() >= BLOCK_BATCH_SIZE {
+            moved += cold_ops.len();
+            move_blocks(&db, &mut cold_ops, &mut hot_ops)?;
+        }
+    }
+    moved += cold_ops.len();
+    move_blocks(&db, &mut cold_ops, &mut hot_ops)?;
+
+    info!(db.log, "Moved finalized blocks to the freezer database"; "count" => moved);
+
+    Ok(())
+}
+
+/// Write `cold_ops` to the freezer database before applying `hot_ops`, so that no block is lost if
+/// the process dies in between.
+fn move_blocks<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: &HotColdDB<E, Hot, Cold>,
+    cold_ops: &mut Vec<KeyValueStoreOp>,
+    hot_ops: &mut Vec<KeyValueStoreOp>,
+) -> Result<(), Error> {
+    db.cold_db
+        .do_atomically(std::mem::replace(cold_ops, vec![]))?;
+    db.cold_db.sync()?;
+    db.hot_db.do_atomically(std::mem::replace(hot_ops, vec![]))
+}
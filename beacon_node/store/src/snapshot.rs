@@ -0,0 +1,337 @@
+//! Point-in-time archives of the hot and freezer databases, used to back up or clone a node.
+//!
+//! A snapshot is taken from a consistent view of each database (see
+//! `KeyValueStore::iter_raw_snapshot`), so it can be exported whilst the node is running. The hot
+//! database is captured before the freezer database, and the split point is held whilst both
+//! views are taken. Since the freezer migration writes to the freezer before advancing the split,
+//! the snapshot is always equivalent to a database that was stopped abruptly, which the node can
+//! start from.
+//!
+//! The archive consists of `MAGIC`, a version byte, and a sequence of entries of the form:
+//!
+//! `[database: u8][key length: u32][key][value length: u32][value]`
+//!
+//! followed by `END_TAG` and the number of hot and freezer entries (both `u64`), which allows a
+//! truncated archive to be detected. All integers are little-endian. Values are copied verbatim,
+//! so compressed values remain compressed.
+use crate::backend::BeaconNodeBackend;
+use crate::hot_cold_store::{HotColdDB, SPLIT_DB_KEY};
+use crate::metadata::FREEZER_PATH_KEY;
+use crate::{
+    get_key_for_col, DBColumn, DatabaseBackend, Error, ItemStore, KeyValueStore, KeyValueStoreOp,
+    RawEntryIter, Split,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use types::{EthSpec, Hash256, Slot};
+
+/// Identifies a Lighthouse database snapshot.
+const MAGIC: [u8; 8] = *b"LHDBSNAP";
+/// The version of the archive format.
+const VERSION: u8 = 1;
+
+/// Precedes an entry of the hot database.
+const HOT_TAG: u8 = 0;
+/// Precedes an entry of the freezer database.
+const COLD_TAG: u8 = 1;
+/// Follows the last entry.
+const END_TAG: u8 = 0xff;
+
+/// The number of entries written in each batch when importing a snapshot.
+const IMPORT_BATCH_SIZE: usize = 1_024;
+
+/// A summary of an exported or imported snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    /// The split slot of the database at the time of the snapshot.
+    pub split_slot: Slot,
+    /// The number of entries from the hot database.
+    pub hot_entries: u64,
+    /// The number of entries from the freezer database.
+    pub cold_entries: u64,
+}
+
+/// Write a snapshot of `db` to a new file at `path`.
+///
+/// The archive is written to a temporary file alongside `path`, and only moved into place once
+/// it is complete.
+pub fn export_snapshot<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    db: &HotColdDB<E, Hot, Cold>,
+    path: &Path,
+) -> Result<SnapshotSummary, Error> {
+    if path.exists() {
+        return Err(io_error(path, "file already exists"));
+    }
+
+    let (split, hot_entries, cold_entries) = {
+        let split = db.split.read();
+        let hot_entries = db.hot_db.iter_raw_snapshot()?;
+        let cold_entries = db.cold_db.iter_raw_snapshot()?;
+        (*split, hot_entries, cold_entries)
+    };
+
+    let partial_path = partial_path(path);
+    let file = File::create(&partial_path).map_err(|e| io_error(&partial_path, e))?;
+    let mut writer = BufWriter::new(file);
+
+    let mut write = |bytes: &[u8]| {
+        writer
+            .write_all(bytes)
+            .map_err(|e| io_error(&partial_path, e))
+    };
+
+    write(&MAGIC)?;
+    write(&[VERSION])?;
+    let hot_count = write_entries(HOT_TAG, hot_entries, &mut write)?;
+    let cold_count = write_entries(COLD_TAG, cold_entries, &mut write)?;
+    write(&[END_TAG])?;
+    write(&hot_count.to_le_bytes())?;
+    write(&cold_count.to_le_bytes())?;
+
+    writer
+        .into_inner()
+        .map_err(|e| io_error(&partial_path, e))?
+        .sync_all()
+        .map_err(|e| io_error(&partial_path, e))?;
+    fs::rename(&partial_path, path).map_err(|e| io_error(path, e))?;
+
+    Ok(SnapshotSummary {
+        split_slot: split.slot,
+        hot_entries: hot_count,
+        cold_entries: cold_count,
+    })
+}
+
+/// Create new `backend` databases at `hot_path` and `cold_path` from the snapshot at `path`.
+///
+/// The path of the freezer database recorded by the exporting node is not imported, so that the
+/// imported databases may be opened at any location. If the import fails, the partially imported
+/// databases are left in place and must be deleted before trying again.
+pub fn import_snapshot<E: EthSpec>(
+    path: &Path,
+    hot_path: &Path,
+    cold_path: &Path,
+    backend: DatabaseBackend,
+) -> Result<SnapshotSummary, Error> {
+    for db_path in &[hot_path, cold_path] {
+        if DatabaseBackend::ALL
+            .iter()
+            .any(|backend| BeaconNodeBackend::<E>::exists(*backend, db_path))
+        {
+            return Err(Error::SnapshotTargetExists(db_path.to_path_buf()));
+        }
+    }
+
+    let file = File::open(path).map_err(|e| io_error(path, e))?;
+    let mut reader = BufReader::new(file);
+    let mut read = |len: usize| {
+        let mut bytes = vec![0; len];
+        reader
+            .read_exact(&mut bytes)
+            .map(|()| bytes)
+            .map_err(|e| Error::InvalidSnapshot(format!("unable to read {:?}: {:?}", path, e)))
+    };
+
+    if read(MAGIC.len())? != MAGIC {
+        return Err(Error::InvalidSnapshot("not a database snapshot".into()));
+    }
+    let version = read(1)?[0];
+    if version != VERSION {
+        return Err(Error::InvalidSnapshot(format!(
+            "unsupported version: {}",
+            version
+        )));
+    }
+
+    let hot_db = BeaconNodeBackend::<E>::open(backend, hot_path)?;
+    let cold_db = BeaconNodeBackend::<E>::open(backend, cold_path)?;
+    let freezer_path_key =
+        get_key_for_col(DBColumn::BeaconMeta.into(), FREEZER_PATH_KEY.as_bytes());
+
+    let mut hot_batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut cold_batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut hot_count = 0u64;
+    let mut cold_count = 0u64;
+
+    loop {
+        let tag = read(1)?[0];
+        if tag == END_TAG {
+            break;
+        }
+
+        let key_len = read_u32(&mut read)? as usize;
+        let key = read(key_len)?;
+        let value_len = read_u32(&mut read)? as usize;
+        let value = read(value_len)?;
+
+        let (db, batch) = match tag {
+            HOT_TAG => {
+                hot_count += 1;
+                if key == freezer_path_key {
+                    continue;
+                }
+                (&hot_db, &mut hot_batch)
+            }
+            COLD_TAG => {
+                cold_count += 1;
+                (&cold_db, &mut cold_batch)
+            }
+            other => {
+                return Err(Error::InvalidSnapshot(format!(
+                    "unknown database tag: {}",
+                    other
+                )))
+            }
+        };
+
+        batch.push(KeyValueStoreOp::PutKeyValue(key, value));
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            db.do_atomically(std::mem::replace(
+                batch,
+                Vec::with_capacity(IMPORT_BATCH_SIZE),
+            ))?;
+        }
+    }
+
+    let expected_hot = read_u64(&mut read)?;
+    let expected_cold = read_u64(&mut read)?;
+    if (hot_count, cold_count) != (expected_hot, expected_cold) {
+        return Err(Error::InvalidSnapshot(format!(
+            "expected {} hot and {} freezer entries, found {} and {}",
+            expected_hot, expected_cold, hot_count, cold_count
+        )));
+    }
+
+    cold_db.do_atomically(cold_batch)?;
+    cold_db.sync()?;
+    hot_db.do_atomically(hot_batch)?;
+    hot_db.sync()?;
+
+    let split = hot_db
+        .get::<Split>(&Hash256::from_slice(SPLIT_DB_KEY.as_bytes()))?
+        .unwrap_or_default();
+
+    Ok(SnapshotSummary {
+        split_slot: split.slot,
+        hot_entries: hot_count,
+        cold_entries: cold_count,
+    })
+}
+
+/// Write each entry of `entries`, preceded by `tag`, returning the number of entries written.
+fn write_entries(
+    tag: u8,
+    entries: RawEntryIter,
+    write: &mut impl FnMut(&[u8]) -> Result<(), Error>,
+) -> Result<u64, Error> {
+    let mut count = 0;
+    for entry in entries {
+        let (key, value) = entry?;
+        write(&[tag])?;
+        write(&(key.len() as u32).to_le_bytes())?;
+        write(&key)?;
+        write(&(value.len() as u32).to_le_bytes())?;
+        write(&value)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn read_u32(read: &mut impl FnMut(usize) -> Result<Vec<u8>, Error>) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&read(4)?);
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(read: &mut impl FnMut(usize) -> Result<Vec<u8>, Error>) -> Result<u64, Error> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&read(8)?);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// The path to which a snapshot is written before it is complete.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".partial");
+    path.with_file_name(file_name)
+}
+
+fn io_error(path: &Path, e: impl std::fmt::Debug) -> Error {
+    Error::DBError {
+        message: format!("Snapshot I/O error for {:?}: {:?}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryStore, StoreConfig};
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use tempfile::tempdir;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn store() -> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
+        let log = NullLoggerBuilder.build().unwrap();
+        HotColdDB::open_ephemeral(StoreConfig::default(), E::default_spec(), log).unwrap()
+    }
+
+    #[test]
+    fn export_and_import() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("snapshot");
+        let db = store();
+        let entries = (0..3_000u32)
+            .map(|i| (i.to_le_bytes().to_vec(), vec![i as u8; 64]))
+            .collect::<Vec<_>>();
+        for (key, value) in &entries {
+            db.hot_db.put_bytes("hot", key, value).unwrap();
+            db.cold_db.put_bytes("cld", key, value).unwrap();
+        }
+
+        let exported = export_snapshot(&db, &archive).unwrap();
+        assert_eq!(exported.hot_entries, entries.len() as u64);
+        assert_eq!(exported.cold_entries, entries.len() as u64);
+        assert!(!partial_path(&archive).exists());
+        assert!(export_snapshot(&db, &archive).is_err());
+
+        let (hot_path, cold_path) = (dir.path().join("hot"), dir.path().join("cold"));
+        let imported =
+            import_snapshot::<E>(&archive, &hot_path, &cold_path, DatabaseBackend::LevelDb)
+                .unwrap();
+        assert_eq!(imported, exported);
+
+        let hot_db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, &hot_path).unwrap();
+        let cold_db = BeaconNodeBackend::<E>::open(DatabaseBackend::LevelDb, &cold_path).unwrap();
+        for (key, value) in &entries {
+            assert_eq!(hot_db.get_bytes("hot", key).unwrap().as_ref(), Some(value));
+            assert_eq!(cold_db.get_bytes("cld", key).unwrap().as_ref(), Some(value));
+            assert_eq!(hot_db.get_bytes("cld", key).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn import_rejects_truncated_snapshot() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("snapshot");
+        let db = store();
+        db.hot_db.put_bytes("hot", b"key", &[42; 64]).unwrap();
+        export_snapshot(&db, &archive).unwrap();
+
+        let bytes = fs::read(&archive).unwrap();
+        fs::write(&archive, &bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(matches!(
+            import_snapshot::<E>(
+                &archive,
+                &dir.path().join("hot"),
+                &dir.path().join("cold"),
+                DatabaseBackend::LevelDb
+            ),
+            Err(Error::InvalidSnapshot(_))
+        ));
+    }
+}
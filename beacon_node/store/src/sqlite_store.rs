@@ -0,0 +1,235 @@
+use super::*;
+use crate::metrics;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension, NO_PARAMS};
+use std::fs;
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// The name of the SQLite database file within the database directory.
+pub const SQLITE_DB_FILENAME: &str = "lighthouse.sqlite";
+
+/// A key-value store backed by a single SQLite table.
+///
+/// SQLite is slower than LevelDB on fast disks, but performs better on spinning disks and
+/// low-powered devices where LevelDB's background compactions are expensive.
+pub struct SqliteStore<E: EthSpec> {
+    conn: Mutex<Connection>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> SqliteStore<E> {
+    /// Returns `true` if a SQLite database exists in the directory at `path`.
+    pub fn exists(path: &Path) -> bool {
+        path.join(SQLITE_DB_FILENAME).exists()
+    }
+
+    /// Open a database in the directory at `path`, creating a new database if one does not
+    /// already exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        fs::create_dir_all(path).map_err(|e| Error::DBError {
+            message: format!("Unable to create {:?}: {:?}", path, e),
+        })?;
+
+        let conn = Connection::open(path.join(SQLITE_DB_FILENAME))?;
+
+        // `auto_vacuum` only takes effect if it is set before the table is created.
+        conn.execute_batch(
+            "PRAGMA auto_vacuum = INCREMENTAL;
+             PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS kv (
+                 key BLOB PRIMARY KEY,
+                 value BLOB NOT NULL
+             ) WITHOUT ROWID;",
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for SqliteStore<E> {
+    /// Store some `value` in `column`, indexed with `key`.
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_WRITE_COUNT);
+        metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as i64);
+        let timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+
+        self.conn.lock().execute(
+            "INSERT OR REPLACE INTO kv (key, value) VALUES (?1, ?2)",
+            params![column_key, val],
+        )?;
+
+        metrics::stop_timer(timer);
+        Ok(())
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes(col, key, val)?;
+        self.sync()
+    }
+
+    /// Checkpoint the write-ahead log into the main database file.
+    fn sync(&self) -> Result<(), Error> {
+        self.conn
+            .lock()
+            .execute_batch("PRAGMA wal_checkpoint(FULL);")
+            .map_err(Into::into)
+    }
+
+    /// Retrieve some bytes in `column` with `key`.
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_READ_COUNT);
+        let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+
+        let opt: Option<Vec<u8>> = self
+            .conn
+            .lock()
+            .query_row(
+                "SELECT value FROM kv WHERE key = ?1",
+                params![column_key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(bytes) = &opt {
+            metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as i64);
+            metrics::stop_timer(timer);
+        }
+
+        Ok(opt)
+    }
+
+    /// Return `true` if `key` exists in `column`.
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_EXISTS_COUNT);
+
+        self.conn
+            .lock()
+            .query_row(
+                "SELECT 1 FROM kv WHERE key = ?1",
+                params![column_key],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|opt| opt.is_some())
+            .map_err(Into::into)
+    }
+
+    /// Removes `key` from `column`.
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_DELETE_COUNT);
+
+        self.conn
+            .lock()
+            .execute("DELETE FROM kv WHERE key = ?1", params![column_key])?;
+        Ok(())
+    }
+
+    fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for op in ops_batch {
+            match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO kv (key, value) VALUES (?1, ?2)",
+                        params![key, value],
+                    )?;
+                }
+
+                KeyValueStoreOp::DeleteKey(key) => {
+                    tx.execute("DELETE FROM kv WHERE key = ?1", params![key])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Return the pages freed by deletions to the file system.
+    fn compact(&self) -> Result<(), Error> {
+        self.conn
+            .lock()
+            .execute_batch("PRAGMA incremental_vacuum;")
+            .map_err(Into::into)
+    }
+
+    /// Iterate over all entries in key order.
+    ///
+    /// The keys are read up front so that the connection is not held for the lifetime of the
+    /// iterator, whilst the (much larger) values are read lazily.
+    fn iter_raw_entries(&self) -> RawEntryIter<'_> {
+        let keys = {
+            let conn = self.conn.lock();
+            let keys = conn
+                .prepare("SELECT key FROM kv ORDER BY key")
+                .and_then(|mut stmt| {
+                    let rows = stmt.query_map(NO_PARAMS, |row| row.get::<_, Vec<u8>>(0))?;
+                    rows.collect::<Result<Vec<_>, _>>()
+                });
+            keys
+        };
+
+        match keys {
+            Ok(keys) => Box::new(keys.into_iter().filter_map(move |key| {
+                self.conn
+                    .lock()
+                    .query_row("SELECT value FROM kv WHERE key = ?1", params![key], |row| {
+                        row.get::<_, Vec<u8>>(0)
+                    })
+                    .optional()
+                    .map_err(Error::from)
+                    // Skip any keys deleted since the iterator was created.
+                    .transpose()
+                    .map(|res| res.map(|value| (key, value)))
+            })),
+            Err(e) => Box::new(std::iter::once(Err(e.into()))),
+        }
+    }
+
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter<'_> {
+        let prefix = column.as_str().as_bytes();
+        let keys = {
+            let conn = self.conn.lock();
+            let keys = conn
+                .prepare("SELECT key FROM kv WHERE substr(key, 1, ?2) = ?1 ORDER BY key")
+                .and_then(|mut stmt| {
+                    let rows = stmt.query_map(params![prefix, prefix.len() as i64], |row| {
+                        row.get::<_, Vec<u8>>(0)
+                    })?;
+                    rows.collect::<Result<Vec<_>, _>>()
+                });
+            keys
+        };
+
+        match keys {
+            Ok(keys) => Box::new(
+                keys.into_iter()
+                    .map(move |key| Ok(key[prefix.len()..].to_vec())),
+            ),
+            Err(e) => Box::new(std::iter::once(Err(e.into()))),
+        }
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for SqliteStore<E> {}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Error {
+        Error::DBError {
+            message: format!("{:?}", e),
+        }
+    }
+}
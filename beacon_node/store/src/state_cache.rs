@@ -0,0 +1,154 @@
+//! An LRU cache of `BeaconState`s which is bounded by the approximate memory used by its states,
+//! rather than by the number of states.
+//!
+//! The size of a state varies greatly between networks (and grows with the validator set), so a
+//! fixed count of states is either wasteful on small networks or dangerous on large ones.
+use lru::LruCache;
+use ssz::Encode;
+use std::mem::size_of;
+use types::{BeaconState, EthSpec, Hash256};
+
+/// The number of bytes per validator which are used by the committee caches of a state, which
+/// are retained by cached states but not included in their SSZ length.
+///
+/// Each of the previous, current and next committee caches holds a shuffling and a position for
+/// every validator.
+const COMMITTEE_CACHE_BYTES_PER_VALIDATOR: usize = 3 * 2 * size_of::<usize>();
+
+/// Returns the approximate number of bytes of memory used by `state`.
+pub fn approximate_state_size<E: EthSpec>(state: &BeaconState<E>) -> usize {
+    state.ssz_bytes_len() + state.validators.len() * COMMITTEE_CACHE_BYTES_PER_VALIDATOR
+}
+
+#[derive(Debug)]
+pub struct StateCache<E: EthSpec> {
+    /// Each state is stored alongside its approximate size, which is computed once on insertion.
+    states: LruCache<Hash256, (BeaconState<E>, usize)>,
+    /// The sum of the approximate sizes of all states in the cache.
+    total_bytes: usize,
+    /// The cache will evict states until `total_bytes` is no greater than this value.
+    max_bytes: usize,
+}
+
+impl<E: EthSpec> StateCache<E> {
+    /// Create a cache which holds up to `max_bytes` of states. A value of 0 disables the cache.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            states: LruCache::unbounded(),
+            total_bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Returns `true` if the cache has been disabled.
+    pub fn is_disabled(&self) -> bool {
+        self.max_bytes == 0
+    }
+
+    /// Get the state with `state_root`, marking it as the most recently used.
+    pub fn get(&mut self, state_root: &Hash256) -> Option<&BeaconState<E>> {
+        self.states.get(state_root).map(|(state, _)| state)
+    }
+
+    /// Insert `state`, evicting the least recently used states to make room for it.
+    ///
+    /// States which are larger than the entire cache are not inserted.
+    pub fn put(&mut self, state_root: Hash256, state: BeaconState<E>) {
+        let size = approximate_state_size(&state);
+        if size > self.max_bytes {
+            return;
+        }
+
+        self.pop(&state_root);
+        while self.total_bytes + size > self.max_bytes {
+            match self.states.pop_lru() {
+                Some((_, (_, evicted_size))) => self.total_bytes -= evicted_size,
+                None => break,
+            }
+        }
+
+        self.states.put(state_root, (state, size));
+        self.total_bytes += size;
+    }
+
+    /// Remove the state with `state_root`, if present.
+    pub fn pop(&mut self, state_root: &Hash256) {
+        if let Some((_, size)) = self.states.pop(state_root) {
+            self.total_bytes -= size;
+        }
+    }
+
+    /// The number of states in the cache.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns `true` if there are no states in the cache.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// The approximate number of bytes used by the states in the cache.
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Eth1Data, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn state(genesis_time: u64) -> BeaconState<E> {
+        BeaconState::new(genesis_time, Eth1Data::default(), &E::default_spec())
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let size = approximate_state_size(&state(0));
+        let mut cache = StateCache::new(2 * size);
+        let root = Hash256::from_low_u64_be;
+
+        cache.put(root(0), state(0));
+        cache.put(root(1), state(1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.total_bytes(), 2 * size);
+
+        // Touch the first state so that the second is evicted instead.
+        assert!(cache.get(&root(0)).is_some());
+        cache.put(root(2), state(2));
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.total_bytes(), 2 * size);
+        assert!(cache.get(&root(1)).is_none());
+        assert_eq!(cache.get(&root(0)).map(|s| s.genesis_time), Some(0));
+        assert_eq!(cache.get(&root(2)).map(|s| s.genesis_time), Some(2));
+
+        cache.pop(&root(0));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.total_bytes(), size);
+    }
+
+    #[test]
+    fn oversized_states_are_not_cached() {
+        let size = approximate_state_size(&state(0));
+        let mut cache = StateCache::new(size - 1);
+
+        cache.put(Hash256::zero(), state(0));
+        assert!(cache.is_empty());
+        assert_eq!(cache.total_bytes(), 0);
+    }
+
+    #[test]
+    fn replacing_a_state_does_not_leak_bytes() {
+        let size = approximate_state_size(&state(0));
+        let mut cache = StateCache::new(4 * size);
+
+        cache.put(Hash256::zero(), state(0));
+        cache.put(Hash256::zero(), state(0));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.total_bytes(), size);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::partial_beacon_state::PartialBeaconState;
+use ssz_derive::{Decode, Encode};
+use types::*;
+
+/// A restore point that is stored as a diff against a prior restore point, rather than in full.
+///
+/// The validator registry accounts for the vast majority of the size of a `PartialBeaconState`
+/// and very few validators change between restore points, so only the validators which differ
+/// from the base state are stored. All other fields are stored as-is.
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+pub struct PartialBeaconStateDiff<T: EthSpec> {
+    /// The state root of the restore point that this diff should be applied to.
+    pub base_state_root: Hash256,
+    /// The length of the validator registry after the diff is applied.
+    pub validators_len: u64,
+    /// The indices of the validators which differ from the base state (in ascending order).
+    pub changed_validator_indices: Vec<u64>,
+    /// The new values of the validators at `changed_validator_indices`.
+    pub changed_validators: Vec<Validator>,
+    /// The restore point state, with an empty validator registry.
+    pub state: PartialBeaconState<T>,
+}
+
+impl<T: EthSpec> PartialBeaconStateDiff<T> {
+    /// Compute the diff of `state` against `base_validators`, the validator registry of the
+    /// restore point with `base_state_root`.
+    pub fn new(
+        base_state_root: Hash256,
+        base_validators: &[Validator],
+        state: &BeaconState<T>,
+    ) -> Self {
+        let mut partial_state = PartialBeaconState::from_state_forgetful(state);
+        let validators = std::mem::replace(&mut partial_state.validators, VariableList::empty());
+
+        let (changed_validator_indices, changed_validators) = validators
+            .iter()
+            .enumerate()
+            .filter(|(i, validator)| base_validators.get(*i) != Some(validator))
+            .map(|(i, validator)| (i as u64, validator.clone()))
+            .unzip();
+
+        Self {
+            base_state_root,
+            validators_len: validators.len() as u64,
+            changed_validator_indices,
+            changed_validators,
+            state: partial_state,
+        }
+    }
+
+    /// Apply the diff to `base_validators`, returning the full `PartialBeaconState`.
+    ///
+    /// Returns `None` if the diff is inconsistent with `base_validators`.
+    pub fn apply(self, base_validators: &[Validator]) -> Option<PartialBeaconState<T>> {
+        let validators_len = self.validators_len as usize;
+        if validators_len < base_validators.len()
+            || self.changed_validator_indices.len() != self.changed_validators.len()
+        {
+            return None;
+        }
+
+        let mut validators = base_validators.to_vec();
+        for (index, validator) in self
+            .changed_validator_indices
+            .into_iter()
+            .zip(self.changed_validators)
+        {
+            let index = index as usize;
+            if index < validators.len() {
+                validators[index] = validator;
+            } else if index == validators.len() {
+                validators.push(validator);
+            } else {
+                return None;
+            }
+        }
+
+        if validators.len() != validators_len {
+            return None;
+        }
+
+        let mut state = self.state;
+        state.validators = VariableList::new(validators).ok()?;
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn diff_roundtrip() {
+        let spec = E::default_spec();
+        let (base_state, _) =
+            TestingBeaconStateBuilder::<E>::from_deterministic_keypairs(8, &spec).build();
+
+        let mut state = base_state.clone();
+        state.validators[3].effective_balance += 1;
+        let mut new_validator = state.validators[0].clone();
+        new_validator.withdrawal_credentials = Hash256::repeat_byte(42);
+        state.validators.push(new_validator).unwrap();
+        state.balances.push(0).unwrap();
+
+        let diff = PartialBeaconStateDiff::new(Hash256::zero(), &base_state.validators, &state);
+        assert_eq!(diff.changed_validator_indices, vec![3, 8]);
+
+        let partial_state = diff
+            .clone()
+            .apply(&base_state.validators)
+            .expect("diff should apply");
+        assert_eq!(
+            partial_state,
+            PartialBeaconState::from_state_forgetful(&state)
+        );
+
+        assert_eq!(
+            diff.apply(&state.validators[..2]),
+            None,
+            "diff should not apply to an inconsistent base"
+        );
+    }
+}
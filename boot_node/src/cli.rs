@@ -50,6 +50,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("The UDP port of the boot node's ENR. This is the port that external peers will dial to reach this boot node. Set this only if the external port differs from the listening port.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("network-dir")
+                .long("network-dir")
+                .value_name("DIR")
+                .help("Data directory for the boot node's private key and ENR. The key and ENR are \
+                created on first start and re-used afterwards so the boot node keeps a stable identity.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enable-enr-auto-update")
                 .short("x")
@@ -2,11 +2,12 @@ use beacon_node::{get_data_dir, set_network_config};
 use clap::ArgMatches;
 use discv5::{enr::CombinedKey, Enr};
 use eth2_libp2p::{
-    discovery::{create_enr_builder_from_config, use_or_load_enr},
+    discovery::{create_enr_builder_from_config, save_enr_to_disk, use_or_load_enr},
     load_private_key, CombinedKeyExt, NetworkConfig,
 };
 use std::convert::TryFrom;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 /// A set of configuration parameters for the bootnode, established from CLI arguments.
 pub struct BootNodeConfig {
@@ -16,6 +17,8 @@ pub struct BootNodeConfig {
     pub local_enr: Enr,
     pub local_key: CombinedKey,
     pub auto_update: bool,
+    /// The directory in which the private key and ENR are persisted.
+    pub network_dir: PathBuf,
 }
 
 impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
@@ -39,6 +42,9 @@ impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
 
         use_or_load_enr(&local_key, &mut local_enr, &network_config, &logger)?;
 
+        // Persist the ENR so that its sequence number is preserved across restarts.
+        save_enr_to_disk(&network_config.network_dir, &local_enr, &logger);
+
         let boot_nodes = {
             if let Some(boot_nodes) = matches.value_of("boot-nodes") {
                 boot_nodes
@@ -50,7 +56,7 @@ impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
             }
         };
 
-        let auto_update = matches.is_present("enable-enr_auto_update");
+        let auto_update = matches.is_present("enable-enr-auto-update");
 
         // the address to listen on
         let listen_socket =
@@ -62,6 +68,7 @@ impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
             local_enr,
             local_key,
             auto_update,
+            network_dir: network_config.network_dir,
         })
     }
 }
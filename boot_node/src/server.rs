@@ -2,7 +2,7 @@
 
 use super::BootNodeConfig;
 use discv5::{Discv5, Discv5ConfigBuilder, Discv5Event};
-use eth2_libp2p::EnrExt;
+use eth2_libp2p::{discovery::save_enr_to_disk, EnrExt};
 use futures::prelude::*;
 use slog::info;
 
@@ -84,6 +84,8 @@ pub async fn run(config: BootNodeConfig, log: slog::Logger) {
                     Discv5Event::NodeInserted { .. } => {} // Ignore
                     Discv5Event::SocketUpdated(socket_addr) => {
                         info!(log, "External socket address updated"; "socket_addr" => format!("{:?}", socket_addr));
+                        // The local ENR has been updated, persist it.
+                        save_enr_to_disk(&config.network_dir, &discv5.local_enr(), &log);
                     }
                 }
             }
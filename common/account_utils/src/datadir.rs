@@ -0,0 +1,358 @@
+//! Versioning of the layout of the Lighthouse data directory (`~/.lighthouse` by default).
+//!
+//! The root of the data directory contains a `DATADIR_VERSION_FILENAME` file, recording the
+//! version of the layout which it uses. Data directories created before the file was introduced
+//! are assigned the current version, unless they contain validator data in the legacy layout.
+//!
+//! In the legacy layout, validator directories, the validator definitions and the slashing
+//! protection database are stored in the root of the data directory rather than in the
+//! `VALIDATORS_DIR` directory, and keystore passwords are stored in the root rather than in the
+//! `SECRETS_DIR` directory. This layout is created when the data directory of the beacon node is
+//! also used as the data directory of the validator client. That is a valid way to run the
+//! validator client, so the layout is only reported by `check_datadir`. It may be migrated with
+//! `migrate_datadir` so that the validator client finds its keys in their default location.
+
+use crate::validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs;
+use std::path::{Path, PathBuf};
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+/// The file in the root of the data directory which contains the layout version.
+pub const DATADIR_VERSION_FILENAME: &str = "datadir_version";
+/// The version of the layout created by this version of Lighthouse.
+pub const CURRENT_DATADIR_VERSION: u64 = 1;
+/// The directory which contains validator directories, relative to the data directory.
+pub const VALIDATORS_DIR: &str = "validators";
+/// The directory which contains keystore passwords, relative to the data directory.
+pub const SECRETS_DIR: &str = "secrets";
+
+/// A file or directory in the root of the data directory which belongs elsewhere in the current
+/// layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegacyItem {
+    pub path: PathBuf,
+    pub destination: PathBuf,
+}
+
+/// Returns the layout version recorded in `datadir`, or `None` if it has not been recorded.
+pub fn read_datadir_version<P: AsRef<Path>>(datadir: P) -> Result<Option<u64>, String> {
+    let path = datadir.as_ref().join(DATADIR_VERSION_FILENAME);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|e| format!("Unable to parse {:?}: {:?}", path, e))
+}
+
+/// Records the current layout version in `datadir`.
+fn write_datadir_version<P: AsRef<Path>>(datadir: P) -> Result<(), String> {
+    let path = datadir.as_ref().join(DATADIR_VERSION_FILENAME);
+
+    fs::write(&path, format!("{}\n", CURRENT_DATADIR_VERSION))
+        .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
+
+/// Returns an error if `datadir` was created by a newer version of Lighthouse.
+fn check_not_newer<P: AsRef<Path>>(datadir: P) -> Result<Option<u64>, String> {
+    let version = read_datadir_version(&datadir)?;
+
+    match version {
+        Some(version) if version > CURRENT_DATADIR_VERSION => Err(format!(
+            "The data directory {:?} uses layout version {}, but this version of Lighthouse \
+             only supports versions up to {}. Upgrade Lighthouse or use a different --datadir.",
+            datadir.as_ref(),
+            version,
+            CURRENT_DATADIR_VERSION
+        )),
+        version => Ok(version),
+    }
+}
+
+/// Returns the files and directories in the root of `datadir` which belong in the
+/// `VALIDATORS_DIR` or `SECRETS_DIR` directories.
+pub fn find_legacy_items<P: AsRef<Path>>(datadir: P) -> Result<Vec<LegacyItem>, String> {
+    let datadir = datadir.as_ref();
+    let mut items = vec![];
+
+    for entry in
+        fs::read_dir(datadir).map_err(|e| format!("Unable to read {:?}: {:?}", datadir, e))?
+    {
+        let entry = entry.map_err(|e| format!("Unable to read {:?}: {:?}", datadir, e))?;
+        let path = entry.path();
+        let name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+
+        let destination_dir = if name == CONFIG_FILENAME || name == SLASHING_PROTECTION_FILENAME {
+            VALIDATORS_DIR
+        } else if name.starts_with("0x") && path.join(VOTING_KEYSTORE_FILE).exists() {
+            VALIDATORS_DIR
+        } else if name.starts_with("0x") && path.is_file() {
+            SECRETS_DIR
+        } else {
+            continue;
+        };
+
+        items.push(LegacyItem {
+            destination: datadir.join(destination_dir).join(&name),
+            path,
+        });
+    }
+
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(items)
+}
+
+/// Checks that `datadir` can be used by this version of Lighthouse, recording the current layout
+/// version if none is recorded.
+///
+/// Returns the items of `datadir` in the legacy layout, which the caller should warn about. The
+/// version is not recorded whilst any remain, so they continue to be reported until migrated.
+pub fn check_datadir<P: AsRef<Path>>(datadir: P) -> Result<Vec<LegacyItem>, String> {
+    let datadir = datadir.as_ref();
+
+    if !datadir.exists() {
+        return Ok(vec![]);
+    }
+
+    let version = check_not_newer(datadir)?;
+
+    let legacy_items = find_legacy_items(datadir)?;
+
+    if version.is_none() && legacy_items.is_empty() {
+        write_datadir_version(datadir)?;
+    }
+
+    Ok(legacy_items)
+}
+
+/// Acquires the exclusive lock on each slashing protection database in `datadir`, proving that
+/// no validator client is using it. The locks are held until the returned databases are dropped.
+fn lock_slashing_protection(datadir: &Path) -> Result<Vec<SlashingDatabase>, String> {
+    let paths = vec![
+        datadir.join(SLASHING_PROTECTION_FILENAME),
+        datadir
+            .join(VALIDATORS_DIR)
+            .join(SLASHING_PROTECTION_FILENAME),
+    ];
+
+    paths
+        .into_iter()
+        .filter(|path| path.exists())
+        .map(|path| {
+            let db = SlashingDatabase::open(&path)
+                .and_then(|db| db.lock_exclusive().map(|()| db))
+                .map_err(|e| {
+                    format!(
+                        "Unable to lock the slashing protection database at {:?}, ensure the \
+                         validator client is not running: {:?}",
+                        path, e
+                    )
+                })?;
+            Ok(db)
+        })
+        .collect()
+}
+
+/// Moves each legacy item in `datadir` into the current layout, updating the paths of any moved
+/// keystores or passwords in the validator definitions. Returns the items which were moved.
+///
+/// No items are moved if any of them would overwrite an existing file, or if a validator client
+/// is using a slashing protection database in `datadir`.
+pub fn migrate_datadir<P: AsRef<Path>>(datadir: P) -> Result<Vec<LegacyItem>, String> {
+    let datadir = datadir.as_ref();
+
+    check_not_newer(datadir)?;
+
+    // Prevent a validator client from starting whilst its files are moved.
+    let _slashing_protection_locks = lock_slashing_protection(datadir)?;
+
+    let items = find_legacy_items(datadir)?;
+
+    if let Some(item) = items.iter().find(|item| item.destination.exists()) {
+        return Err(format!(
+            "Unable to move {:?} because {:?} already exists",
+            item.path, item.destination
+        ));
+    }
+
+    for dir in &[VALIDATORS_DIR, SECRETS_DIR] {
+        let path = datadir.join(dir);
+        fs::create_dir_all(&path).map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+    }
+
+    for item in &items {
+        fs::rename(&item.path, &item.destination).map_err(|e| {
+            format!(
+                "Unable to move {:?} to {:?}: {:?}",
+                item.path, item.destination, e
+            )
+        })?;
+    }
+
+    let validators_dir = datadir.join(VALIDATORS_DIR);
+    if validators_dir.join(CONFIG_FILENAME).exists() {
+        let mut defs = ValidatorDefinitions::open(&validators_dir)
+            .map_err(|e| format!("Unable to open validator definitions: {:?}", e))?;
+
+        for def in defs.as_mut_slice() {
+            match &mut def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    voting_keystore_password_path,
+                    ..
+                } => {
+                    if let Some(path) = migrated_path(voting_keystore_path, &items) {
+                        *voting_keystore_path = path;
+                    }
+
+                    if let Some(password_path) = voting_keystore_password_path {
+                        if let Some(path) = migrated_path(password_path, &items) {
+                            *password_path = path;
+                        }
+                    }
+                }
+            }
+        }
+
+        defs.save(&validators_dir)
+            .map_err(|e| format!("Unable to save validator definitions: {:?}", e))?;
+    }
+
+    write_datadir_version(datadir)?;
+
+    Ok(items)
+}
+
+/// Returns the new location of `path` if it is, or is within, one of the moved `items`.
+fn migrated_path(path: &Path, items: &[LegacyItem]) -> Option<PathBuf> {
+    items.iter().find_map(|item| {
+        let relative = path.strip_prefix(&item.path).ok()?;
+
+        if relative.as_os_str().is_empty() {
+            Some(item.destination.clone())
+        } else {
+            Some(item.destination.join(relative))
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn check_datadir_records_version() {
+        let datadir = tempdir().unwrap();
+        fs::create_dir(datadir.path().join(VALIDATORS_DIR)).unwrap();
+
+        assert_eq!(read_datadir_version(datadir.path()), Ok(None));
+        check_datadir(datadir.path()).unwrap();
+        assert_eq!(
+            read_datadir_version(datadir.path()),
+            Ok(Some(CURRENT_DATADIR_VERSION))
+        );
+    }
+
+    #[test]
+    fn check_datadir_rejects_newer_version() {
+        let datadir = tempdir().unwrap();
+        fs::write(
+            datadir.path().join(DATADIR_VERSION_FILENAME),
+            format!("{}", CURRENT_DATADIR_VERSION + 1),
+        )
+        .unwrap();
+
+        assert!(check_datadir(datadir.path()).is_err());
+        assert!(migrate_datadir(datadir.path()).is_err());
+    }
+
+    #[test]
+    fn migrate_legacy_layout() {
+        let datadir = tempdir().unwrap();
+        let root = datadir.path();
+        let pubkey = format!("0x{}", "ab".repeat(48));
+
+        fs::create_dir(root.join(&pubkey)).unwrap();
+        fs::write(root.join(&pubkey).join(VOTING_KEYSTORE_FILE), "{}").unwrap();
+        SlashingDatabase::create(&root.join(SLASHING_PROTECTION_FILENAME)).unwrap();
+        // Password files are named after the public key of their keystore.
+        fs::write(root.join("0xpassword"), "").unwrap();
+        fs::create_dir(root.join(SECRETS_DIR)).unwrap();
+        fs::create_dir(root.join("beacon")).unwrap();
+
+        // The legacy layout is reported, but the version isn't recorded until it is migrated.
+        assert_eq!(check_datadir(root).unwrap().len(), 3);
+        assert_eq!(read_datadir_version(root), Ok(None));
+
+        let moved = migrate_datadir(root).unwrap();
+        assert_eq!(moved.len(), 3);
+
+        assert!(root
+            .join(VALIDATORS_DIR)
+            .join(&pubkey)
+            .join(VOTING_KEYSTORE_FILE)
+            .exists());
+        assert!(root
+            .join(VALIDATORS_DIR)
+            .join(SLASHING_PROTECTION_FILENAME)
+            .exists());
+        assert!(root.join(SECRETS_DIR).join("0xpassword").exists());
+        assert!(root.join("beacon").exists());
+
+        assert_eq!(check_datadir(root), Ok(vec![]));
+        assert_eq!(
+            read_datadir_version(root),
+            Ok(Some(CURRENT_DATADIR_VERSION))
+        );
+    }
+
+    #[test]
+    fn migrate_refuses_database_in_use() {
+        let datadir = tempdir().unwrap();
+        let root = datadir.path();
+        let pubkey = format!("0x{}", "ab".repeat(48));
+
+        fs::create_dir(root.join(&pubkey)).unwrap();
+        fs::write(root.join(&pubkey).join(VOTING_KEYSTORE_FILE), "{}").unwrap();
+
+        // Hold the lock on the database, as a running validator client would.
+        let db = SlashingDatabase::create(&root.join(SLASHING_PROTECTION_FILENAME)).unwrap();
+        db.lock_exclusive().unwrap();
+
+        assert!(migrate_datadir(root).is_err());
+        assert!(root.join(&pubkey).exists(), "nothing should be moved");
+        assert!(!root.join(VALIDATORS_DIR).exists());
+
+        drop(db);
+        assert_eq!(migrate_datadir(root).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn migrated_paths() {
+        let items = vec![LegacyItem {
+            path: PathBuf::from("/data/0xabc"),
+            destination: PathBuf::from("/data/validators/0xabc"),
+        }];
+
+        assert_eq!(
+            migrated_path(Path::new("/data/0xabc/voting-keystore.json"), &items),
+            Some(PathBuf::from("/data/validators/0xabc/voting-keystore.json"))
+        );
+        assert_eq!(
+            migrated_path(Path::new("/data/0xabc"), &items),
+            Some(PathBuf::from("/data/validators/0xabc"))
+        );
+        assert_eq!(migrated_path(Path::new("/data/0xabcd"), &items), None);
+    }
+}
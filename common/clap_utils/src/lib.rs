@@ -19,9 +19,18 @@ pub fn parse_testnet_dir<E: EthSpec>(
     name: &'static str,
 ) -> Result<Option<Eth2TestnetConfig<E>>, String> {
     let path = parse_required::<PathBuf>(matches, name)?;
-    Eth2TestnetConfig::load(path.clone())
-        .map_err(|e| format!("Unable to open testnet dir at {:?}: {}", path, e))
-        .map(Some)
+
+    if !path.exists() {
+        return Err(format!("Testnet dir {:?} does not exist", path));
+    }
+
+    let testnet_config = Eth2TestnetConfig::load(path.clone())
+        .map_err(|e| format!("Unable to open testnet dir at {:?}: {}", path, e))?;
+    testnet_config
+        .validate()
+        .map_err(|e| format!("Invalid testnet dir at {:?}: {}", path, e))?;
+
+    Ok(Some(testnet_config))
 }
 
 /// Attempts to load a hardcoded network config if `name` is in `matches`, returning an error if
@@ -31,7 +40,9 @@ pub fn parse_hardcoded_network<E: EthSpec>(
     name: &str,
 ) -> Result<Option<Eth2TestnetConfig<E>>, String> {
     let network_name = parse_required::<String>(matches, name)?;
-    Eth2TestnetConfig::constant(network_name.as_str())
+    Eth2TestnetConfig::constant(network_name.as_str())?
+        .ok_or_else(|| format!("Unknown network {}", network_name))
+        .map(Some)
 }
 
 /// If `name` is in `matches`, parses the value as a path. Otherwise, attempts to find the user's
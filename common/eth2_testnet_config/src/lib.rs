@@ -55,6 +55,8 @@ const ALTONA: HardcodedNet = define_net!(altona, include_altona_file);
 const MEDALLA: HardcodedNet = define_net!(medalla, include_medalla_file);
 
 const HARDCODED_NETS: &[HardcodedNet] = &[ALTONA, MEDALLA];
+/// The names of the networks built into the binary, which may be selected with `--network`.
+pub const HARDCODED_NET_NAMES: &[&str] = &["altona", "medalla"];
 pub const DEFAULT_HARDCODED_TESTNET: &str = "medalla";
 
 /// Specifies an Eth2 testnet.
@@ -233,6 +235,42 @@ impl<E: EthSpec> Eth2TestnetConfig<E> {
         })
     }
 
+    /// Checks that the files of the testnet are consistent with each other and with `E`, returning
+    /// an error describing the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        self.deposit_contract_address()
+            .map_err(|e| format!("Invalid {}: {}", ADDRESS_FILE, e))?;
+
+        let yaml_config = self
+            .yaml_config
+            .as_ref()
+            .ok_or_else(|| format!("The testnet directory must contain a {}", YAML_CONFIG_FILE))?;
+
+        let spec = yaml_config
+            .apply_to_chain_spec::<E>(&E::default_spec())
+            .ok_or_else(|| {
+                format!(
+                    "The {} is not compatible with the {} spec",
+                    YAML_CONFIG_FILE,
+                    E::spec_name()
+                )
+            })?;
+
+        if let Some(genesis_state) = &self.genesis_state {
+            if genesis_state.fork.current_version != spec.genesis_fork_version {
+                return Err(format!(
+                    "The {} has fork version {:?}, but the {} specifies {:?}",
+                    GENESIS_STATE_FILE,
+                    genesis_state.fork.current_version,
+                    YAML_CONFIG_FILE,
+                    spec.genesis_fork_version
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn deposit_contract_address(&self) -> Result<Address, String> {
         if self.deposit_contract_address.starts_with("0x") {
             self.deposit_contract_address[2..]
@@ -252,6 +290,15 @@ mod tests {
 
     type E = MainnetEthSpec;
 
+    #[test]
+    fn hard_coded_net_names_match() {
+        let names = HARDCODED_NETS
+            .iter()
+            .map(|net| net.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, HARDCODED_NET_NAMES);
+    }
+
     #[test]
     fn hard_coded_nets_work() {
         for net in HARDCODED_NETS {
@@ -305,4 +352,42 @@ mod tests {
 
         assert_eq!(testnet, decoded, "should decode as encoded");
     }
+
+    #[test]
+    fn validate() {
+        let spec = &E::default_spec();
+
+        let eth1_data = Eth1Data {
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: Hash256::zero(),
+        };
+
+        let mut testnet: Eth2TestnetConfig<E> = Eth2TestnetConfig {
+            deposit_contract_address: "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".to_string(),
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state: Some(BeaconState::new(42, eth1_data, spec)),
+            yaml_config: Some(YamlConfig::from_spec::<E>(spec)),
+        };
+        assert_eq!(testnet.validate(), Ok(()));
+
+        let mut other_spec = spec.clone();
+        other_spec.genesis_fork_version = [1, 2, 3, 4];
+        testnet.yaml_config = Some(YamlConfig::from_spec::<E>(&other_spec));
+        assert!(
+            testnet.validate().is_err(),
+            "should reject a genesis state from another fork"
+        );
+
+        testnet.yaml_config = None;
+        assert!(testnet.validate().is_err(), "should require a yaml config");
+
+        testnet.yaml_config = Some(YamlConfig::from_spec::<E>(spec));
+        testnet.deposit_contract_address = "BB9bc244D798123fDe783fCc1C72d3Bb8C189413".to_string();
+        assert!(
+            testnet.validate().is_err(),
+            "should reject an address without a 0x prefix"
+        );
+    }
 }
@@ -11,17 +11,19 @@ use std::marker::PhantomData;
 use std::time::Duration;
 use types::{
     Attestation, AttestationData, AttesterSlashing, BeaconBlock, BeaconState, CommitteeIndex,
-    Epoch, EthSpec, Fork, Graffiti, Hash256, ProposerSlashing, PublicKey, PublicKeyBytes,
-    Signature, SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
+    Epoch, EthSpec, Fork, Graffiti, Hash256, IndexedAttestation, ProposerSlashing, PublicKey,
+    PublicKeyBytes, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit,
+    Slot, SubnetId,
 };
 use url::Url;
 
 pub use operation_pool::PersistedOperationPool;
 pub use proto_array::core::ProtoArray;
 pub use rest_types::{
-    CanonicalHeadResponse, Committee, HeadBeaconBlock, Health, IndividualVotesRequest,
-    IndividualVotesResponse, SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes,
-    ValidatorRequest, ValidatorResponse, ValidatorSubscription,
+    BanList, BanRequest, CanonicalHeadResponse, Committee, DatabaseSnapshotResponse,
+    HeadBeaconBlock, Health, IndividualVotesRequest, IndividualVotesResponse, SlasherAttestations,
+    SlasherBlocks, SlasherImportResponse, SyncingResponse, ValidatorDutiesRequest,
+    ValidatorDutyBytes, ValidatorRequest, ValidatorResponse, ValidatorSubscription,
 };
 
 // Setting a long timeout for debug ensures that crypto-heavy operations can still succeed.
@@ -111,6 +113,10 @@ impl<E: EthSpec> HttpClient<E> {
         Consensus(self.clone())
     }
 
+    pub fn lighthouse(&self) -> Lighthouse<E> {
+        Lighthouse(self.clone())
+    }
+
     fn url(&self, path: &str) -> Result<Url, Error> {
         self.url.join(path).map_err(|e| e.into())
     }
@@ -576,6 +582,15 @@ impl<E: EthSpec> Beacon<E> {
         let success = error_for_status(response).await.map_err(Error::from)?;
         success.json().await.map_err(Error::from)
     }
+
+    pub async fn voluntary_exit(&self, exit: SignedVoluntaryExit) -> Result<bool, Error> {
+        let client = self.0.clone();
+
+        let url = self.url("voluntary_exit")?;
+        let response = client.json_post::<_>(url, exit).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
 }
 
 /// Provides the functions on the `/spec` endpoint of the node.
@@ -691,6 +706,87 @@ impl<E: EthSpec> Consensus<E> {
     }
 }
 
+/// Provides the functions on the `/lighthouse` endpoint of the node.
+#[derive(Clone)]
+pub struct Lighthouse<E>(HttpClient<E>);
+
+impl<E: EthSpec> Lighthouse<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("lighthouse/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Exports a snapshot of the node's database to the node's file system.
+    pub async fn database_snapshot(&self) -> Result<DatabaseSnapshotResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("database/snapshot")?;
+        let response = client
+            .client
+            .post(&url.to_string())
+            .send()
+            .await
+            .map_err(Error::from)?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Returns the peers and IP addresses banned by the user.
+    pub async fn bans(&self) -> Result<BanList, Error> {
+        let client = self.0.clone();
+        let url = self.url("bans")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Adds a peer or IP address to the node's ban list.
+    pub async fn add_ban(&self, request: BanRequest) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url("bans/add")?;
+        let response = client.json_post::<_>(url, request).await?;
+        error_for_status(response).await.map(|_| ())
+    }
+
+    /// Removes a peer or IP address from the node's ban list.
+    pub async fn remove_ban(&self, request: BanRequest) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url("bans/remove")?;
+        let response = client.json_post::<_>(url, request).await?;
+        error_for_status(response).await.map(|_| ())
+    }
+
+    /// Returns the attestations and attester slashings recorded by the node's slasher for
+    /// `validator_index`.
+    pub async fn slasher_attestations(
+        &self,
+        validator_index: u64,
+    ) -> Result<SlasherAttestations<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("slasher/attestations/{}", validator_index))?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Imports `attestations` into the node's slasher.
+    pub async fn import_slasher_attestations(
+        &self,
+        attestations: Vec<IndexedAttestation<E>>,
+    ) -> Result<SlasherImportResponse, Error> {
+        let client = self.0.clone();
+        let url = self.url("slasher/attestations")?;
+        let response = client.json_post::<_>(url, attestations).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Returns the block headers and proposer slashings recorded by the node's slasher for
+    /// `validator_index`.
+    pub async fn slasher_blocks(&self, validator_index: u64) -> Result<SlasherBlocks, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("slasher/blocks/{}", validator_index))?;
+        client.json_get(url, vec![]).await
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(bound = "T: EthSpec")]
 pub struct BlockResponse<T: EthSpec> {
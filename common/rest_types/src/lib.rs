@@ -6,6 +6,7 @@ mod api_error;
 mod beacon;
 mod consensus;
 mod handler;
+mod lighthouse;
 mod node;
 mod validator;
 
@@ -16,6 +17,10 @@ pub use beacon::{
 };
 pub use consensus::{IndividualVote, IndividualVotesRequest, IndividualVotesResponse};
 pub use handler::{ApiEncodingFormat, Handler};
+pub use lighthouse::{
+    BanList, BanRequest, DatabaseSnapshotResponse, SlasherAttestations, SlasherBlocks,
+    SlasherImportResponse,
+};
 pub use node::{Health, SyncingResponse, SyncingStatus};
 pub use validator::{
     ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
@@ -0,0 +1,69 @@
+//! Collection of types for the /lighthouse HTTP
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use types::{
+    AttesterSlashing, EthSpec, IndexedAttestation, ProposerSlashing, SignedBeaconBlockHeader, Slot,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The response for the /lighthouse/database/snapshot HTTP POST.
+pub struct DatabaseSnapshotResponse {
+    /// The path of the snapshot on the beacon node's file system.
+    pub path: PathBuf,
+    /// The split slot of the database at the time of the snapshot.
+    pub split_slot: Slot,
+    /// The number of entries from the hot database.
+    pub hot_entries: u64,
+    /// The number of entries from the freezer database.
+    pub cold_entries: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+/// The response for the /lighthouse/bans HTTP GET.
+pub struct BanList {
+    /// The base58 encoded ids of the peers banned by the user.
+    pub peers: Vec<String>,
+    /// The IP addresses banned by the user.
+    pub ips: Vec<IpAddr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The body of the /lighthouse/bans/add and /lighthouse/bans/remove HTTP POSTs.
+///
+/// Exactly one of `peer_id` or `ip` must be provided.
+pub struct BanRequest {
+    /// The base58 encoded id of a peer.
+    pub peer_id: Option<String>,
+    /// An IP address.
+    pub ip: Option<IpAddr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec")]
+/// The response for the /lighthouse/slasher/attestations/{validator_index} HTTP GET.
+pub struct SlasherAttestations<T: EthSpec> {
+    /// The attestations signed by the validator which are within the slasher's history length.
+    pub attestations: Vec<IndexedAttestation<T>>,
+    /// Every attester slashing the slasher has found for the validator.
+    pub attester_slashings: Vec<AttesterSlashing<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The response for the /lighthouse/slasher/blocks/{validator_index} HTTP GET.
+pub struct SlasherBlocks {
+    /// The block headers signed by the validator which are within the slasher's history length.
+    pub blocks: Vec<SignedBeaconBlockHeader>,
+    /// Every proposer slashing the slasher has found for the validator.
+    pub proposer_slashings: Vec<ProposerSlashing>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The response for the /lighthouse/slasher/attestations HTTP POST.
+pub struct SlasherImportResponse {
+    /// The number of attestations recorded by the slasher.
+    pub imported: usize,
+    /// The number of attestations which were duplicates, outside the slasher's history length or
+    /// had a future target epoch.
+    pub ignored: usize,
+}
@@ -52,6 +52,16 @@ pub trait SlotClock: Send + Sync + Sized {
     /// Returns the first slot to be returned at the genesis time.
     fn genesis_slot(&self) -> Slot;
 
+    /// Returns the duration between the UNIX epoch and the start of `slot`.
+    fn start_of(&self, slot: Slot) -> Option<Duration>;
+
+    /// Returns the delay between the start of `slot` and the present time.
+    ///
+    /// Returns `None` if `slot` has not yet started or the system clock cannot be read.
+    fn seconds_from_slot_start(&self, slot: Slot) -> Option<Duration> {
+        self.now_duration()?.checked_sub(self.start_of(slot)?)
+    }
+
     /// Returns the slot if the internal clock were advanced by `duration`.
     fn now_with_future_tolerance(&self, tolerance: Duration) -> Option<Slot> {
         self.slot_of(self.now_duration()?.checked_add(tolerance)?)
@@ -147,6 +147,10 @@ impl SlotClock for ManualSlotClock {
         self.duration_to_slot(slot, *self.current_time.read())
     }
 
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        ManualSlotClock::start_of(self, slot)
+    }
+
     fn genesis_slot(&self) -> Slot {
         self.genesis_slot
     }
@@ -3,6 +3,7 @@ use bls::get_withdrawal_credentials;
 use deposit_contract::{encode_eth1_tx_data, Error as DepositError};
 use eth2_keystore::{Error as KeystoreError, Keystore, KeystoreBuilder, PlainText};
 use rand::{distributions::Alphanumeric, Rng};
+use ssz::Encode;
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::os::unix::fs::PermissionsExt;
@@ -20,6 +21,7 @@ pub const VOTING_KEYSTORE_FILE: &str = "voting-keystore.json";
 pub const WITHDRAWAL_KEYSTORE_FILE: &str = "withdrawal-keystore.json";
 pub const ETH1_DEPOSIT_DATA_FILE: &str = "eth1-deposit-data.rlp";
 pub const ETH1_DEPOSIT_AMOUNT_FILE: &str = "eth1-deposit-gwei.txt";
+pub const DEPOSIT_DATA_SSZ_FILE: &str = "deposit-data.ssz";
 
 #[derive(Debug)]
 pub enum Error {
@@ -175,6 +177,24 @@ impl<'a> Builder<'a> {
 
                 deposit_data.signature = deposit_data.create_signature(&voting_keypair.sk, &spec);
 
+                // Save `DEPOSIT_DATA_SSZ_FILE` to file.
+                //
+                // This allows the deposit to be submitted by tools other than Lighthouse, e.g. by
+                // converting it to the format used by the launchpad.
+                let path = dir.join(DEPOSIT_DATA_SSZ_FILE);
+                if path.exists() {
+                    return Err(Error::DepositDataAlreadyExists(path));
+                } else {
+                    OpenOptions::new()
+                        .write(true)
+                        .read(true)
+                        .create(true)
+                        .open(path)
+                        .map_err(Error::UnableToSaveDepositData)?
+                        .write_all(&deposit_data.as_ssz_bytes())
+                        .map_err(Error::UnableToSaveDepositData)?
+                }
+
                 let deposit_data =
                     encode_eth1_tx_data(&deposit_data).map_err(Error::UnableToEncodeDeposit)?;
 
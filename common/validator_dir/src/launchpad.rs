@@ -0,0 +1,36 @@
+use serde_derive::{Deserialize, Serialize};
+use ssz::Encode;
+use tree_hash::TreeHash;
+use types::{ChainSpec, DepositData};
+
+/// The name of the file which `LaunchpadDeposit`s are saved to by the account manager.
+pub const LAUNCHPAD_DEPOSIT_DATA_FILE: &str = "deposit_data.json";
+
+/// A deposit in the JSON format produced by the `eth2.0-deposit-cli` and accepted by the Eth2
+/// launchpad.
+///
+/// All byte arrays are hex-encoded, without a `0x` prefix.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchpadDeposit {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+}
+
+impl LaunchpadDeposit {
+    pub fn new(deposit_data: &DepositData, spec: &ChainSpec) -> Self {
+        Self {
+            pubkey: hex::encode(deposit_data.pubkey.as_ssz_bytes()),
+            withdrawal_credentials: hex::encode(deposit_data.withdrawal_credentials),
+            amount: deposit_data.amount,
+            signature: hex::encode(deposit_data.signature.as_ssz_bytes()),
+            deposit_message_root: hex::encode(deposit_data.as_deposit_message().tree_hash_root()),
+            deposit_data_root: hex::encode(deposit_data.tree_hash_root()),
+            fork_version: hex::encode(spec.genesis_fork_version),
+        }
+    }
+}
@@ -1,13 +1,18 @@
 #![cfg(not(debug_assertions))]
 
 use eth2_keystore::{Keystore, KeystoreBuilder, PlainText};
+use ssz::Decode;
 use std::fs::{self, File};
 use std::path::Path;
 use tempfile::{tempdir, TempDir};
-use types::{test_utils::generate_deterministic_keypair, EthSpec, Keypair, MainnetEthSpec};
+use tree_hash::TreeHash;
+use types::{
+    test_utils::generate_deterministic_keypair, DepositData, EthSpec, Keypair, MainnetEthSpec,
+};
 use validator_dir::{
-    Builder, BuilderError, ValidatorDir, ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE,
-    VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
+    Builder, BuilderError, Eth1DepositData, LaunchpadDeposit, ValidatorDir, DEPOSIT_DATA_SSZ_FILE,
+    ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE, VOTING_KEYSTORE_FILE,
+    WITHDRAWAL_KEYSTORE_FILE,
 };
 
 /// A very weak password with which to encrypt the keystores.
@@ -32,6 +37,44 @@ impl Default for BuildConfig {
     }
 }
 
+/// Check that the launchpad JSON for `data` is consistent with it and survives a round trip.
+fn check_launchpad_deposit(data: &Eth1DepositData) {
+    let spec = MainnetEthSpec::default_spec();
+    let deposit = LaunchpadDeposit::new(&data.deposit_data, &spec);
+
+    // All byte arrays are hex-encoded without a `0x` prefix.
+    let decode = |field: &str, len: usize| {
+        assert!(
+            !field.starts_with("0x"),
+            "launchpad fields have no 0x prefix"
+        );
+        let bytes = hex::decode(field).unwrap();
+        assert_eq!(bytes.len(), len);
+        bytes
+    };
+    decode(&deposit.pubkey, 48);
+    decode(&deposit.signature, 96);
+    decode(&deposit.fork_version, 4);
+    assert_eq!(
+        decode(&deposit.withdrawal_credentials, 32),
+        data.deposit_data.withdrawal_credentials.as_bytes()
+    );
+    assert_eq!(
+        decode(&deposit.deposit_message_root, 32),
+        data.deposit_data
+            .as_deposit_message()
+            .tree_hash_root()
+            .as_bytes()
+    );
+    assert_eq!(decode(&deposit.deposit_data_root, 32), data.root.as_bytes());
+    assert_eq!(deposit.amount, data.deposit_data.amount);
+
+    // The launchpad expects a JSON list of deposits.
+    let json = serde_json::to_string(&vec![deposit.clone()]).unwrap();
+    let decoded: Vec<LaunchpadDeposit> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, vec![deposit]);
+}
+
 /// Check that a keystore exists and can be decrypted with a password in password_dir
 fn check_keystore<P: AsRef<Path>>(path: P, password_dir: P) -> Keypair {
     let mut file = File::open(path).unwrap();
@@ -154,9 +197,19 @@ impl Harness {
                     .unwrap();
             assert!(hex.starts_with("0x"), "deposit data should have 0x prefix");
             hex::decode(&hex[2..]).unwrap();
+
+            // Check that the SSZ deposit data file matches the eth1 deposit data.
+            let ssz_bytes = fs::read(validator.dir().join(DEPOSIT_DATA_SSZ_FILE)).unwrap();
+            assert_eq!(
+                DepositData::from_ssz_bytes(&ssz_bytes).unwrap(),
+                data.deposit_data
+            );
+
+            check_launchpad_deposit(&data);
         } else {
             // If there was no deposit then we should return `Ok(None)`.
             assert!(validator.eth1_deposit_data().unwrap().is_none());
+            assert!(!validator.dir().join(DEPOSIT_DATA_SSZ_FILE).exists());
         }
 
         let tx_hash_path = validator.dir().join(ETH1_DEPOSIT_TX_HASH_FILE);
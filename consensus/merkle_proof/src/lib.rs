@@ -27,6 +27,10 @@ pub enum MerkleTree {
     ///
     /// It represents a Merkle tree of 2^depth zero leaves.
     Zero(usize),
+    /// Full subtree of which only the root hash is known.
+    ///
+    /// Finalized subtrees cannot be modified and proofs cannot be generated for their leaves.
+    Finalized(H256),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -39,6 +43,8 @@ pub enum MerkleTreeError {
     Invalid,
     // Incorrect Depth provided
     DepthTooSmall,
+    // The number of finalized hashes does not match the number of leaves
+    InvalidFinalizedHashes,
     // Overflow occurred
     ArithError,
 }
@@ -78,6 +84,53 @@ impl MerkleTree {
         }
     }
 
+    /// Create a new Merkle tree of the given depth from a finalized snapshot of its first `count`
+    /// leaves.
+    ///
+    /// The `finalized` hashes are the roots of the full subtrees which contain the `count` leaves,
+    /// ordered from left to right (i.e., from the largest subtree to the smallest). There is one
+    /// hash for each bit set in `count`.
+    pub fn from_finalized(
+        finalized: &[H256],
+        count: usize,
+        depth: usize,
+    ) -> Result<Self, MerkleTreeError> {
+        use MerkleTree::*;
+
+        if count > 2usize.pow(depth as u32) {
+            return Err(MerkleTreeError::MerkleTreeFull);
+        }
+        if finalized.len() != count.count_ones() as usize {
+            return Err(MerkleTreeError::InvalidFinalizedHashes);
+        }
+
+        if count == 0 {
+            return Ok(Zero(depth));
+        } else if count == 2usize.pow(depth as u32) {
+            return Ok(Finalized(finalized[0]));
+        }
+
+        // The tree is neither empty nor full, so `depth` must be at least one.
+        let subtree_capacity = 2usize.pow(depth as u32 - 1);
+        let (left_subtree, right_subtree) = if count > subtree_capacity {
+            (
+                Finalized(finalized[0]),
+                MerkleTree::from_finalized(&finalized[1..], count - subtree_capacity, depth - 1)?,
+            )
+        } else {
+            (
+                MerkleTree::from_finalized(finalized, count, depth - 1)?,
+                Zero(depth - 1),
+            )
+        };
+        let hash = H256::from_slice(&hash32_concat(
+            left_subtree.hash().as_bytes(),
+            right_subtree.hash().as_bytes(),
+        ));
+
+        Ok(Node(hash, Box::new(left_subtree), Box::new(right_subtree)))
+    }
+
     /// Push an element in the MerkleTree.
     /// MerkleTree and depth must be correct, as the algorithm expects valid data.
     pub fn push_leaf(&mut self, elem: H256, depth: usize) -> Result<(), MerkleTreeError> {
@@ -89,6 +142,7 @@ impl MerkleTree {
 
         match self {
             Leaf(_) => return Err(MerkleTreeError::LeafReached),
+            Finalized(_) => return Err(MerkleTreeError::MerkleTreeFull),
             Zero(_) => {
                 *self = MerkleTree::create(&[elem], depth);
             }
@@ -97,9 +151,11 @@ impl MerkleTree {
                 let right: &mut MerkleTree = &mut *right;
                 match (&*left, &*right) {
                     // Tree is full
-                    (Leaf(_), Leaf(_)) => return Err(MerkleTreeError::MerkleTreeFull),
+                    (Leaf(_), Leaf(_)) | (Finalized(_), Leaf(_)) | (Finalized(_), Finalized(_)) => {
+                        return Err(MerkleTreeError::MerkleTreeFull)
+                    }
                     // There is a right node so insert in right node
-                    (Node(_, _, _), Node(_, _, _)) => {
+                    (Node(_, _, _), Node(_, _, _)) | (Finalized(_), Node(_, _, _)) => {
                         if let Err(e) = right.push_leaf(elem, depth - 1) {
                             return Err(e);
                         }
@@ -109,7 +165,7 @@ impl MerkleTree {
                         *left = MerkleTree::create(&[elem], depth - 1);
                     }
                     // Leaf on left branch and zero on right branch, insert on right side
-                    (Leaf(_), Zero(_)) => {
+                    (Leaf(_), Zero(_)) | (Finalized(_), Zero(_)) => {
                         *right = MerkleTree::create(&[elem], depth - 1);
                     }
                     // Try inserting on the left node -> if it fails because it is full, insert in right side.
@@ -142,13 +198,14 @@ impl MerkleTree {
             MerkleTree::Leaf(h) => h,
             MerkleTree::Node(h, _, _) => h,
             MerkleTree::Zero(depth) => H256::from_slice(&ZERO_HASHES[depth]),
+            MerkleTree::Finalized(h) => h,
         }
     }
 
     /// Get a reference to the left and right subtrees if they exist.
     pub fn left_and_right_branches(&self) -> Option<(&Self, &Self)> {
         match *self {
-            MerkleTree::Leaf(_) | MerkleTree::Zero(0) => None,
+            MerkleTree::Leaf(_) | MerkleTree::Zero(0) | MerkleTree::Finalized(_) => None,
             MerkleTree::Node(_, ref l, ref r) => Some((l, r)),
             MerkleTree::Zero(depth) => Some((&ZERO_NODES[depth - 1], &ZERO_NODES[depth - 1])),
         }
@@ -166,6 +223,8 @@ impl MerkleTree {
     ///
     /// The Merkle proof is in "bottom-up" order, starting with a leaf node
     /// and moving up the tree. Its length will be exactly equal to `depth`.
+    ///
+    /// Panics if the leaf at `index` is within a finalized subtree.
     pub fn generate_proof(&self, index: usize, depth: usize) -> (H256, Vec<H256>) {
         let mut proof = vec![];
         let mut current_node = self;
@@ -173,7 +232,9 @@ impl MerkleTree {
         while current_depth > 0 {
             let ith_bit = (index >> (current_depth - 1)) & 0x01;
             // Note: unwrap is safe because leaves are only ever constructed at depth == 0.
-            let (left, right) = current_node.left_and_right_branches().unwrap();
+            let (left, right) = current_node
+                .left_and_right_branches()
+                .expect("cannot generate a proof for a finalized leaf");
 
             // Go right, include the left branch in the proof.
             if ith_bit == 1 {
@@ -286,6 +347,84 @@ mod tests {
         TestResult::from_bool(proofs_ok)
     }
 
+    /// Returns the roots of the full subtrees containing `leaves`, from left to right.
+    fn finalized_hashes(leaves: &[H256], depth: usize) -> Vec<H256> {
+        let mut start = 0;
+        (0..=depth)
+            .rev()
+            .filter(|i| leaves.len() & (1 << i) != 0)
+            .map(|i| {
+                let end = start + (1 << i);
+                let root = MerkleTree::create(&leaves[start..end], i).hash();
+                start = end;
+                root
+            })
+            .collect()
+    }
+
+    /// Check that we can:
+    /// 1. Build a MerkleTree from a finalized snapshot of some leaves.
+    /// 2. Push the remaining leaves and generate valid proofs for them.
+    #[quickcheck]
+    fn quickcheck_from_finalized_and_verify(
+        int_leaves: Vec<u64>,
+        finalized_count: usize,
+        depth: usize,
+    ) -> TestResult {
+        if depth == 0
+            || depth > MAX_TREE_DEPTH
+            || int_leaves.len() > 2usize.pow(depth as u32)
+            || finalized_count > int_leaves.len()
+        {
+            return TestResult::discard();
+        }
+
+        let leaves: Vec<_> = int_leaves.into_iter().map(H256::from_low_u64_be).collect();
+        let finalized = finalized_hashes(&leaves[..finalized_count], depth);
+
+        let mut merkle_tree = MerkleTree::from_finalized(&finalized, finalized_count, depth)
+            .expect("should build tree from finalized hashes");
+        if merkle_tree.hash() != MerkleTree::create(&leaves[..finalized_count], depth).hash() {
+            return TestResult::failed();
+        }
+
+        let proofs_ok = leaves
+            .iter()
+            .enumerate()
+            .skip(finalized_count)
+            .all(|(i, leaf)| {
+                assert_eq!(merkle_tree.push_leaf(*leaf, depth), Ok(()));
+                let (stored_leaf, branch) = merkle_tree.generate_proof(i, depth);
+                stored_leaf == *leaf
+                    && verify_merkle_proof(*leaf, &branch, depth, i, merkle_tree.hash())
+            });
+
+        TestResult::from_bool(
+            proofs_ok && merkle_tree.hash() == MerkleTree::create(&leaves, depth).hash(),
+        )
+    }
+
+    #[test]
+    fn from_finalized_invalid() {
+        let leaf = H256::from([0xAA; 32]);
+
+        assert_eq!(
+            MerkleTree::from_finalized(&[leaf], 2, 2),
+            Err(MerkleTreeError::InvalidFinalizedHashes)
+        );
+        assert_eq!(
+            MerkleTree::from_finalized(&[leaf], 8, 2),
+            Err(MerkleTreeError::MerkleTreeFull)
+        );
+
+        let mut full_tree =
+            MerkleTree::from_finalized(&[leaf], 4, 2).expect("should build a full tree");
+        assert_eq!(
+            full_tree.push_leaf(leaf, 2),
+            Err(MerkleTreeError::MerkleTreeFull)
+        );
+    }
+
     #[test]
     fn sparse_zero_correct() {
         let depth = 2;
@@ -0,0 +1,258 @@
+use crate::{
+    per_block_processing, per_epoch_processing::EpochProcessingSummary, per_slot_processing,
+    BlockProcessingError, BlockSignatureStrategy, SlotProcessingError,
+};
+use std::marker::PhantomData;
+use types::{BeaconState, BeaconStateError, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+type PreBlockHook<'a, E, Error> =
+    Box<dyn FnMut(&mut BeaconState<E>, &SignedBeaconBlock<E>) -> Result<(), Error> + 'a>;
+type PostBlockHook<'a, E, Error> = PreBlockHook<'a, E, Error>;
+type PreSlotHook<'a, E, Error> = Box<dyn FnMut(&mut BeaconState<E>) -> Result<(), Error> + 'a>;
+type PostSlotHook<'a, E, Error> = Box<
+    dyn FnMut(&mut BeaconState<E>, Option<EpochProcessingSummary>, bool) -> Result<(), Error> + 'a,
+>;
+
+/// Defines how state roots should be computed whilst replaying blocks.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StateRootStrategy {
+    /// Perform all transitions faithfully to the specification.
+    Accurate,
+    /// Don't compute state roots, eventually computing an invalid beacon state that can only be
+    /// used for obtaining shuffling.
+    Inconsistent,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BlockReplayError {
+    SlotProcessing(SlotProcessingError),
+    BlockProcessing(BlockProcessingError),
+    BeaconState(BeaconStateError),
+}
+
+impl From<SlotProcessingError> for BlockReplayError {
+    fn from(e: SlotProcessingError) -> Self {
+        Self::SlotProcessing(e)
+    }
+}
+
+impl From<BlockProcessingError> for BlockReplayError {
+    fn from(e: BlockProcessingError) -> Self {
+        Self::BlockProcessing(e)
+    }
+}
+
+impl From<BeaconStateError> for BlockReplayError {
+    fn from(e: BeaconStateError) -> Self {
+        Self::BeaconState(e)
+    }
+}
+
+/// Efficiently apply blocks to a state while configuring various parameters.
+///
+/// Usage follows a builder pattern: configure the replayer with `new` and the setter methods, then
+/// call `apply_blocks` and retrieve the resulting state with `into_state`.
+///
+/// The hooks allow callers to observe (or modify) each intermediate state. They are invoked:
+///
+/// - `pre_slot_hook`: before each call to `per_slot_processing`.
+/// - `post_slot_hook`: after each call to `per_slot_processing`, with the epoch processing summary
+///   (if any) and a flag which is `true` if a block is about to be applied to the state.
+/// - `pre_block_hook`: before each call to `per_block_processing`.
+/// - `post_block_hook`: after each call to `per_block_processing`.
+pub struct BlockReplayer<'a, E: EthSpec, Error = BlockReplayError> {
+    state: BeaconState<E>,
+    spec: &'a ChainSpec,
+    state_root_strategy: StateRootStrategy,
+    block_sig_strategy: BlockSignatureStrategy,
+    pre_block_hook: Option<PreBlockHook<'a, E, Error>>,
+    post_block_hook: Option<PostBlockHook<'a, E, Error>>,
+    pre_slot_hook: Option<PreSlotHook<'a, E, Error>>,
+    post_slot_hook: Option<PostSlotHook<'a, E, Error>>,
+    _phantom: PhantomData<Error>,
+}
+
+impl<'a, E, Error> BlockReplayer<'a, E, Error>
+where
+    E: EthSpec,
+    Error: From<BlockReplayError>,
+{
+    /// Create a new replayer that will apply blocks upon `state`.
+    ///
+    /// Defaults:
+    ///
+    /// - Full (bulk) signature verification
+    /// - Accurate state roots
+    /// - No hooks
+    pub fn new(state: BeaconState<E>, spec: &'a ChainSpec) -> Self {
+        Self {
+            state,
+            spec,
+            state_root_strategy: StateRootStrategy::Accurate,
+            block_sig_strategy: BlockSignatureStrategy::VerifyBulk,
+            pre_block_hook: None,
+            post_block_hook: None,
+            pre_slot_hook: None,
+            post_slot_hook: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set the replayer's state root strategy (default is `Accurate`).
+    pub fn state_root_strategy(mut self, state_root_strategy: StateRootStrategy) -> Self {
+        self.state_root_strategy = state_root_strategy;
+        self
+    }
+
+    /// Set the replayer's block signature verification strategy (default is `VerifyBulk`).
+    pub fn block_signature_strategy(mut self, block_sig_strategy: BlockSignatureStrategy) -> Self {
+        self.block_sig_strategy = block_sig_strategy;
+        self
+    }
+
+    /// Disable signature verification during replay.
+    ///
+    /// If you are truly _replaying_ blocks then you will almost certainly want to disable
+    /// signature checks for performance.
+    pub fn no_signature_verification(self) -> Self {
+        self.block_signature_strategy(BlockSignatureStrategy::NoVerification)
+    }
+
+    /// Supply a function that will be run on the pre-state of each block.
+    pub fn pre_block_hook(mut self, hook: PreBlockHook<'a, E, Error>) -> Self {
+        self.pre_block_hook = Some(hook);
+        self
+    }
+
+    /// Supply a function that will be run on the post-state of each block.
+    pub fn post_block_hook(mut self, hook: PostBlockHook<'a, E, Error>) -> Self {
+        self.post_block_hook = Some(hook);
+        self
+    }
+
+    /// Supply a function that will be run on the pre-state of each slot transition.
+    pub fn pre_slot_hook(mut self, hook: PreSlotHook<'a, E, Error>) -> Self {
+        self.pre_slot_hook = Some(hook);
+        self
+    }
+
+    /// Supply a function that will be run on the post-state of each slot transition.
+    pub fn post_slot_hook(mut self, hook: PostSlotHook<'a, E, Error>) -> Self {
+        self.post_slot_hook = Some(hook);
+        self
+    }
+
+    /// Compute the state root for `slot` as efficiently as possible.
+    ///
+    /// The `blocks` should be the full list of blocks being applied and `i` should be the index of
+    /// the next block that will be applied, or `blocks.len()` if all blocks have already been
+    /// applied.
+    fn get_state_root(
+        &self,
+        slot: Slot,
+        blocks: &[SignedBeaconBlock<E>],
+        i: usize,
+    ) -> Option<Hash256> {
+        // If we don't care about state roots then return immediately.
+        if self.state_root_strategy == StateRootStrategy::Inconsistent {
+            return Some(Hash256::zero());
+        }
+
+        // Otherwise try to source a root from the previous block.
+        i.checked_sub(1)
+            .and_then(|prev_i| blocks.get(prev_i))
+            .filter(|prev_block| prev_block.message.slot == slot)
+            .map(|prev_block| prev_block.message.state_root)
+    }
+
+    /// Advance the state by a single slot, running the slot hooks.
+    ///
+    /// `next_block_slot` is the slot of the next block that will be applied, if any.
+    fn advance_slot(
+        &mut self,
+        blocks: &[SignedBeaconBlock<E>],
+        i: usize,
+        next_block_slot: Option<Slot>,
+    ) -> Result<(), Error> {
+        if let Some(ref mut pre_slot_hook) = self.pre_slot_hook {
+            pre_slot_hook(&mut self.state)?;
+        }
+
+        let state_root = self.get_state_root(self.state.slot, blocks, i);
+        let summary = per_slot_processing(&mut self.state, state_root, self.spec)
+            .map_err(BlockReplayError::from)?;
+
+        if let Some(ref mut post_slot_hook) = self.post_slot_hook {
+            let block_is_next = next_block_slot == Some(self.state.slot);
+            post_slot_hook(&mut self.state, summary, block_is_next)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply `blocks` atop `self.state`, taking care of slot processing.
+    ///
+    /// Blocks with a slot less than or equal to the slot of the state are ignored, which allows a
+    /// block at the start slot to be supplied solely for its state root.
+    ///
+    /// If `target_slot` is provided then the state will be advanced through any skip slots
+    /// following the last block until it reaches `target_slot`.
+    pub fn apply_blocks(
+        mut self,
+        mut blocks: Vec<SignedBeaconBlock<E>>,
+        target_slot: Option<Slot>,
+    ) -> Result<Self, Error> {
+        if self.state_root_strategy == StateRootStrategy::Inconsistent {
+            // Zero the state roots and re-link the blocks so that the block header checks pass
+            // despite the states having bogus roots.
+            let mut prev_block_root = None;
+            for block in blocks.iter_mut() {
+                block.message.state_root = Hash256::zero();
+                if let Some(parent_root) = prev_block_root {
+                    block.message.parent_root = parent_root;
+                }
+                prev_block_root = Some(block.canonical_root());
+            }
+        }
+
+        for (i, block) in blocks.iter().enumerate() {
+            if block.message.slot <= self.state.slot {
+                continue;
+            }
+
+            while self.state.slot < block.message.slot {
+                self.advance_slot(&blocks, i, Some(block.message.slot))?;
+            }
+
+            if let Some(ref mut pre_block_hook) = self.pre_block_hook {
+                pre_block_hook(&mut self.state, block)?;
+            }
+
+            per_block_processing(
+                &mut self.state,
+                block,
+                None,
+                self.block_sig_strategy,
+                self.spec,
+            )
+            .map_err(BlockReplayError::from)?;
+
+            if let Some(ref mut post_block_hook) = self.post_block_hook {
+                post_block_hook(&mut self.state, block)?;
+            }
+        }
+
+        if let Some(target_slot) = target_slot {
+            while self.state.slot < target_slot {
+                self.advance_slot(&blocks, blocks.len(), None)?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Return the state that results from replaying the blocks.
+    pub fn into_state(self) -> BeaconState<E> {
+        self.state
+    }
+}
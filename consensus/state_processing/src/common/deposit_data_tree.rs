@@ -21,6 +21,21 @@ impl DepositDataTree {
         }
     }
 
+    /// Create a new Merkle tree from a finalized snapshot of the first `deposit_count` leaves.
+    ///
+    /// See `MerkleTree::from_finalized` for the format of `finalized`.
+    pub fn from_finalized(
+        finalized: &[Hash256],
+        deposit_count: usize,
+        depth: usize,
+    ) -> Result<Self, MerkleTreeError> {
+        Ok(Self {
+            tree: MerkleTree::from_finalized(finalized, deposit_count, depth)?,
+            mix_in_length: deposit_count,
+            depth,
+        })
+    }
+
     /// Returns 32 bytes representing the "mix in length" for the merkle root of this tree.
     fn length_bytes(&self) -> Vec<u8> {
         int_to_bytes32(self.mix_in_length as u64)
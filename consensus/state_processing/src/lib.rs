@@ -3,6 +3,7 @@
 #[macro_use]
 mod macros;
 
+pub mod block_replayer;
 pub mod common;
 pub mod genesis;
 pub mod per_block_processing;
@@ -11,6 +12,7 @@ pub mod per_slot_processing;
 pub mod test_utils;
 pub mod verify_operation;
 
+pub use block_replayer::{BlockReplayError, BlockReplayer, StateRootStrategy};
 pub use genesis::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
     process_activations,
@@ -139,6 +139,40 @@ where
         }
     }
 
+    /// Includes all signatures (except the deposit signatures) from each of the given `blocks` for
+    /// verification.
+    ///
+    /// The signature sets for each block are produced in parallel using `rayon`, which makes this
+    /// function significantly faster than calling `Self::include_all_signatures` for each block
+    /// when verifying a long chain of blocks.
+    ///
+    /// All blocks must be able to be verified using `self.state` (i.e., they must all be in the
+    /// same epoch as `self.state`).
+    pub fn include_all_signatures_from_blocks(
+        &mut self,
+        blocks: &'a [(Hash256, SignedBeaconBlock<T>)],
+    ) -> Result<()>
+    where
+        F: Send + Sync,
+    {
+        let state = self.state;
+        let spec = self.spec;
+        let get_pubkey = &self.get_pubkey;
+
+        let sets = blocks
+            .par_iter()
+            .map(|(block_root, block)| {
+                let mut verifier = Self::new(state, get_pubkey.clone(), spec);
+                verifier.include_all_signatures(block, Some(*block_root))?;
+                Ok(verifier.sets)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.sets.extend(sets.into_iter().flatten());
+
+        Ok(())
+    }
+
     /// Includes all signatures on the block (except the deposit signatures) for verification.
     pub fn include_all_signatures(
         &mut self,
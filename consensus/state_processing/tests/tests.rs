@@ -226,3 +226,137 @@ mod signatures_minimal {
         );
     }
 }
+
+mod block_replayer {
+    use super::*;
+    use state_processing::{per_slot_processing, BlockReplayError, BlockReplayer};
+    use std::cell::Cell;
+    use types::test_utils::{TestingBeaconBlockBuilder, TestingBeaconStateBuilder};
+
+    /// Returns a state at `block_slot - 1` and a signed block at `block_slot` which is valid on
+    /// top of it.
+    fn get_pre_state_and_block(
+        block_slot: Slot,
+        spec: &ChainSpec,
+    ) -> (BeaconState<TestEthSpec>, SignedBeaconBlock<TestEthSpec>) {
+        let mut state_builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(VALIDATOR_COUNT, spec);
+        state_builder.teleport_to_slot(block_slot - 1);
+        state_builder.build_caches(spec).unwrap();
+        let (pre_state, keypairs) = state_builder.build();
+
+        let mut state = pre_state.clone();
+        per_slot_processing(&mut state, None, spec).unwrap();
+        let proposer_index = state.get_beacon_proposer_index(block_slot, spec).unwrap();
+        let sk = &keypairs[proposer_index].sk;
+
+        let mut builder = TestingBeaconBlockBuilder::new(spec);
+        builder.set_slot(block_slot);
+        builder.set_proposer_index(proposer_index as u64);
+        builder.set_randao_reveal(sk, &state.fork, state.genesis_validators_root, spec);
+        builder.set_parent_root(state.latest_block_header.canonical_root());
+        let block = builder.build(sk, &state.fork, state.genesis_validators_root, spec);
+
+        (pre_state, block)
+    }
+
+    fn block_slot() -> Slot {
+        Slot::new(TestEthSpec::slots_per_epoch() * 3 - 4)
+    }
+
+    #[test]
+    fn applies_blocks_and_skip_slots() {
+        let spec = &TestEthSpec::default_spec();
+        let (pre_state, block) = get_pre_state_and_block(block_slot(), spec);
+        let target_slot = block_slot() + 2;
+
+        let state = BlockReplayer::<TestEthSpec>::new(pre_state, spec)
+            .block_signature_strategy(BlockSignatureStrategy::VerifyIndividual)
+            .apply_blocks(vec![block.clone()], Some(target_slot))
+            .expect("should apply block")
+            .into_state();
+
+        assert_eq!(state.slot, target_slot);
+        assert_eq!(state.latest_block_header.slot, block_slot());
+        assert_eq!(state.latest_block_header.parent_root, block.parent_root());
+    }
+
+    #[test]
+    fn runs_hooks() {
+        let spec = &TestEthSpec::default_spec();
+        let (pre_state, block) = get_pre_state_and_block(block_slot(), spec);
+        let target_slot = block_slot() + 2;
+
+        let pre_slots = Cell::new(0);
+        let post_slots = Cell::new(0);
+        let block_next = Cell::new(0);
+        let pre_blocks = Cell::new(0);
+        let post_blocks = Cell::new(0);
+
+        BlockReplayer::<TestEthSpec>::new(pre_state, spec)
+            .no_signature_verification()
+            .pre_slot_hook(Box::new(|_| {
+                pre_slots.set(pre_slots.get() + 1);
+                Ok(())
+            }))
+            .post_slot_hook(Box::new(|state, _, block_is_next| {
+                post_slots.set(post_slots.get() + 1);
+                if block_is_next {
+                    assert_eq!(state.slot, block_slot());
+                    block_next.set(block_next.get() + 1);
+                }
+                Ok(())
+            }))
+            .pre_block_hook(Box::new(|state, block| {
+                assert_eq!(state.slot, block.slot());
+                pre_blocks.set(pre_blocks.get() + 1);
+                Ok(())
+            }))
+            .post_block_hook(Box::new(|state, block| {
+                assert_eq!(state.latest_block_header.slot, block.slot());
+                post_blocks.set(post_blocks.get() + 1);
+                Ok(())
+            }))
+            .apply_blocks(vec![block], Some(target_slot))
+            .expect("should apply block");
+
+        // One slot up to the block, then two skip slots.
+        assert_eq!(pre_slots.get(), 3);
+        assert_eq!(post_slots.get(), 3);
+        assert_eq!(block_next.get(), 1);
+        assert_eq!(pre_blocks.get(), 1);
+        assert_eq!(post_blocks.get(), 1);
+    }
+
+    #[test]
+    fn ignores_blocks_not_after_the_state() {
+        let spec = &TestEthSpec::default_spec();
+        let (mut state, block) = get_pre_state_and_block(block_slot(), spec);
+        per_slot_processing(&mut state, None, spec).unwrap();
+        let latest_block_header = state.latest_block_header.clone();
+
+        let state = BlockReplayer::<TestEthSpec>::new(state, spec)
+            .apply_blocks(vec![block], None)
+            .expect("should skip block")
+            .into_state();
+
+        assert_eq!(state.slot, block_slot());
+        assert_eq!(state.latest_block_header, latest_block_header);
+    }
+
+    #[test]
+    fn rejects_invalid_blocks() {
+        let spec = &TestEthSpec::default_spec();
+        let (pre_state, mut block) = get_pre_state_and_block(block_slot(), spec);
+        block.signature = sig();
+
+        let result = BlockReplayer::<TestEthSpec>::new(pre_state, spec)
+            .block_signature_strategy(BlockSignatureStrategy::VerifyIndividual)
+            .apply_blocks(vec![block], None);
+
+        assert!(matches!(
+            result.map(BlockReplayer::into_state),
+            Err(BlockReplayError::BlockProcessing(_))
+        ));
+    }
+}
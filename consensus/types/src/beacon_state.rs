@@ -7,6 +7,7 @@ use cached_tree_hash::{CacheArena, CachedTreeHash};
 use compare_fields_derive::CompareFields;
 use eth2_hashing::hash;
 use int_to_bytes::{int_to_bytes4, int_to_bytes8};
+use merkle_proof::MerkleTree;
 use pubkey_cache::PubkeyCache;
 use safe_arith::{ArithError, SafeArith};
 use serde_derive::{Deserialize, Serialize};
@@ -486,6 +487,55 @@ impl<T: EthSpec> BeaconState<T> {
         }
     }
 
+    /// Compute the indices of the sync committee which is selected using the shuffling and
+    /// effective balances at `epoch`, in committee order. A validator may be selected more than
+    /// once.
+    ///
+    /// This is `get_next_sync_committee_indices` from the Altair spec, where `epoch` is the epoch
+    /// following the state's current epoch.
+    pub fn get_sync_committee_indices(
+        &self,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<Vec<usize>, Error> {
+        let active_validator_indices = self.get_active_validator_indices(epoch, spec)?;
+        if active_validator_indices.is_empty() {
+            return Err(Error::InsufficientValidators);
+        }
+        let active_validator_count = active_validator_indices.len();
+        let seed = self.get_seed(epoch, Domain::SyncCommittee, spec)?;
+
+        let mut i = 0;
+        let mut sync_committee_indices = Vec::with_capacity(T::sync_committee_size());
+        while sync_committee_indices.len() < T::sync_committee_size() {
+            let shuffled_index = compute_shuffled_index(
+                i.safe_rem(active_validator_count)?,
+                active_validator_count,
+                seed.as_bytes(),
+                spec.shuffle_round_count,
+            )
+            .ok_or(Error::UnableToShuffle)?;
+            let candidate_index = active_validator_indices[shuffled_index];
+            let random_byte = {
+                let mut preimage = seed.as_bytes().to_vec();
+                preimage.append(&mut int_to_bytes8(i.safe_div(32)? as u64));
+                let hash = hash(&preimage);
+                hash[i.safe_rem(32)?]
+            };
+            let effective_balance = self.validators[candidate_index].effective_balance;
+            if effective_balance.safe_mul(MAX_RANDOM_BYTE)?
+                >= spec
+                    .max_effective_balance
+                    .safe_mul(u64::from(random_byte))?
+            {
+                sync_committee_indices.push(candidate_index);
+            }
+            i.increment()?;
+        }
+
+        Ok(sync_committee_indices)
+    }
+
     /// Return `true` if the validator who produced `slot_signature` is eligible to aggregate.
     ///
     /// Spec v0.12.1
@@ -1055,6 +1105,36 @@ impl<T: EthSpec> BeaconState<T> {
         }
     }
 
+    /// Compute a Merkle proof of `self.finalized_checkpoint.root` against the tree hash root of
+    /// the state, at the generalized index `FINALIZED_ROOT_INDEX`.
+    ///
+    /// Initialize the tree hash cache if it isn't already initialized.
+    pub fn compute_finalized_root_proof(
+        &mut self,
+    ) -> Result<FixedVector<Hash256, FinalizedRootProofLen>, Error> {
+        self.initialize_tree_hash_cache();
+
+        let cache = self.tree_hash_cache.take();
+
+        if let Some(mut cache) = cache {
+            let leaves = cache.recalculate_tree_hash_leaves(&self)?;
+            self.tree_hash_cache = Some(cache);
+
+            // The root is the second field of the checkpoint, so the proof starts with the epoch
+            // and continues with the path from the checkpoint field to the state root.
+            let depth = FinalizedRootProofLen::to_usize() - 1;
+            let field_index = FINALIZED_ROOT_INDEX / 2 - (1 << depth);
+            let (_, state_proof) =
+                MerkleTree::create(&leaves, depth).generate_proof(field_index, depth);
+
+            let mut proof = vec![self.finalized_checkpoint.epoch.tree_hash_root()];
+            proof.extend(state_proof);
+            Ok(proof.into())
+        } else {
+            Err(Error::TreeHashCacheNotInitialized)
+        }
+    }
+
     /// Completely drops the tree hash cache, replacing it with a new, empty cache.
     pub fn drop_tree_hash_cache(&mut self) {
         self.tree_hash_cache = None;
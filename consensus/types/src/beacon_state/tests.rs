@@ -255,6 +255,56 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn finalized_root_proof() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use merkle_proof::verify_merkle_proof;
+    use tree_hash::TreeHash;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: FoundationBeaconState = BeaconState::random_for_test(&mut rng);
+
+    let proof = state.compute_finalized_root_proof().unwrap();
+    let depth = FinalizedRootProofLen::to_usize();
+
+    assert!(verify_merkle_proof(
+        state.finalized_checkpoint.root,
+        &proof,
+        depth,
+        FINALIZED_ROOT_INDEX - (1 << depth),
+        state.tree_hash_root(),
+    ));
+    assert!(!verify_merkle_proof(
+        state.current_justified_checkpoint.root,
+        &proof,
+        depth,
+        FINALIZED_ROOT_INDEX - (1 << depth),
+        state.tree_hash_root(),
+    ));
+}
+
+#[test]
+fn sync_committee_indices() {
+    let spec = MinimalEthSpec::default_spec();
+    let validator_count = 16;
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(validator_count, &spec);
+    let (mut state, _keypairs) = builder.build();
+    let epoch = state.current_epoch() + 1;
+
+    let indices = state.get_sync_committee_indices(epoch, &spec).unwrap();
+    assert_eq!(indices.len(), MinimalEthSpec::sync_committee_size());
+    assert!(indices.iter().all(|&i| i < validator_count));
+    assert_eq!(state.get_sync_committee_indices(epoch, &spec), Ok(indices));
+
+    // Validators without any effective balance are never selected.
+    state.validators[0].effective_balance = 0;
+    let indices = state.get_sync_committee_indices(epoch, &spec).unwrap();
+    assert_eq!(indices.len(), MinimalEthSpec::sync_committee_size());
+    assert!(!indices.contains(&0));
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
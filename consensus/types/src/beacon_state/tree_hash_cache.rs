@@ -141,6 +141,28 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
     /// The provided `state` should be a descendant of the last `state` given to this function, or
     /// the `Self::new` function.
     pub fn recalculate_tree_hash_root(&mut self, state: &BeaconState<T>) -> Result<Hash256, Error> {
+        let leaves = self.recalculate_tree_hash_leaves(state)?;
+
+        let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
+        for leaf in &leaves {
+            hasher.write(leaf.as_bytes())?;
+        }
+
+        let root = hasher.finish()?;
+
+        self.previous_state = Some((root, state.slot));
+
+        Ok(root)
+    }
+
+    /// Updates the cache and returns the tree hash root of each field of the given `state`, in
+    /// field order.
+    ///
+    /// The same restrictions on `state` apply as for `Self::recalculate_tree_hash_root`.
+    pub fn recalculate_tree_hash_leaves(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<Vec<Hash256>, Error> {
         // If this cache has previously produced a root, ensure that it is in the state root
         // history of this state.
         //
@@ -163,88 +185,42 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
             }
         }
 
-        let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
-
-        hasher.write(state.genesis_time.tree_hash_root().as_bytes())?;
-        hasher.write(state.genesis_validators_root.tree_hash_root().as_bytes())?;
-        hasher.write(state.slot.tree_hash_root().as_bytes())?;
-        hasher.write(state.fork.tree_hash_root().as_bytes())?;
-        hasher.write(state.latest_block_header.tree_hash_root().as_bytes())?;
-        hasher.write(
+        Ok(vec![
+            state.genesis_time.tree_hash_root(),
+            state.genesis_validators_root.tree_hash_root(),
+            state.slot.tree_hash_root(),
+            state.fork.tree_hash_root(),
+            state.latest_block_header.tree_hash_root(),
             state
                 .block_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?,
             state
                 .state_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?,
             state
                 .historical_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_data.tree_hash_root().as_bytes())?;
-        hasher.write(
-            self.eth1_data_votes
-                .recalculate_tree_hash_root(&state)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_deposit_index.tree_hash_root().as_bytes())?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?,
+            state.eth1_data.tree_hash_root(),
+            self.eth1_data_votes.recalculate_tree_hash_root(&state)?,
+            state.eth1_deposit_index.tree_hash_root(),
             self.validators
-                .recalculate_tree_hash_root(&state.validators[..])?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&state.validators[..])?,
             state
                 .balances
-                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?,
             state
                 .randao_mixes
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?,
             state
                 .slashings
-                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .previous_epoch_attestations
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.current_epoch_attestations.tree_hash_root().as_bytes())?;
-        hasher.write(state.justification_bits.tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .previous_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .current_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.finalized_checkpoint.tree_hash_root().as_bytes())?;
-
-        let root = hasher.finish()?;
-
-        self.previous_state = Some((root, state.slot));
-
-        Ok(root)
+                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?,
+            state.previous_epoch_attestations.tree_hash_root(),
+            state.current_epoch_attestations.tree_hash_root(),
+            state.justification_bits.tree_hash_root(),
+            state.previous_justified_checkpoint.tree_hash_root(),
+            state.current_justified_checkpoint.tree_hash_root(),
+            state.finalized_checkpoint.tree_hash_root(),
+        ])
     }
 
     /// Updates the cache and provides the root of the given `validators`.
@@ -21,6 +21,9 @@ pub enum Domain {
     VoluntaryExit,
     SelectionProof,
     AggregateAndProof,
+    SyncCommittee,
+    SyncCommitteeSelectionProof,
+    ContributionAndProof,
 }
 
 /// Holds all the "constants" for a BeaconChain.
@@ -103,6 +106,9 @@ pub struct ChainSpec {
     domain_voluntary_exit: u32,
     domain_selection_proof: u32,
     domain_aggregate_and_proof: u32,
+    domain_sync_committee: u32,
+    domain_sync_committee_selection_proof: u32,
+    domain_contribution_and_proof: u32,
 
     /*
      * Fork choice
@@ -114,6 +120,8 @@ pub struct ChainSpec {
      */
     pub eth1_follow_distance: u64,
     pub seconds_per_eth1_block: u64,
+    pub deposit_chain_id: u64,
+    pub deposit_network_id: u64,
 
     /*
      * Networking
@@ -126,30 +134,106 @@ pub struct ChainSpec {
     pub attestation_subnet_count: u64,
     pub random_subnets_per_validator: u64,
     pub epochs_per_random_subnet_subscription: u64,
+
+    /*
+     * Altair hard fork params
+     */
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    pub altair_fork_version: [u8; 4],
+    /// The Altair fork epoch is optional, with `None` representing "Altair never happens".
+    pub altair_fork_epoch: Option<Epoch>,
+
+    /*
+     * Sync committees
+     */
+    pub epochs_per_sync_committee_period: Epoch,
+    pub sync_committee_subnet_count: u64,
+    pub target_aggregators_per_sync_subcommittee: u64,
 }
 
 impl ChainSpec {
     /// Returns an `EnrForkId` for the given `slot`.
-    ///
-    /// Presently, we don't have any forks so we just ignore the slot. In the future this function
-    /// may return something different based upon the slot.
-    pub fn enr_fork_id(&self, _slot: Slot, genesis_validators_root: Hash256) -> EnrForkId {
+    pub fn enr_fork_id<T: EthSpec>(
+        &self,
+        slot: Slot,
+        genesis_validators_root: Hash256,
+    ) -> EnrForkId {
+        let current_fork = self.fork_name_at_slot::<T>(slot);
+        let (next_fork_version, next_fork_epoch) = match self.next_fork_epoch::<T>(slot) {
+            Some((next_fork, epoch)) => (self.fork_version_for_name(next_fork), epoch),
+            None => (
+                self.fork_version_for_name(current_fork),
+                self.far_future_epoch,
+            ),
+        };
+
         EnrForkId {
             fork_digest: Self::compute_fork_digest(
-                self.genesis_fork_version,
+                self.fork_version_for_name(current_fork),
                 genesis_validators_root,
             ),
-            next_fork_version: self.genesis_fork_version,
-            next_fork_epoch: self.far_future_epoch,
+            next_fork_version,
+            next_fork_epoch,
         }
     }
 
-    /// Returns the epoch of the next scheduled change in the `fork.current_version`.
+    /// Returns the next scheduled fork after the one active at `slot`, along with the epoch at
+    /// which it activates.
     ///
-    /// There are no future forks scheduled so this function always returns `None`. This may not
-    /// always be the case in the future, though.
-    pub fn next_fork_epoch(&self) -> Option<Epoch> {
-        None
+    /// Returns `None` if there are no forks scheduled after the one active at `slot`.
+    pub fn next_fork_epoch<T: EthSpec>(&self, slot: Slot) -> Option<(ForkName, Epoch)> {
+        let next_fork = self.fork_name_at_slot::<T>(slot).next_fork()?;
+        let fork_epoch = self.fork_epoch(next_fork)?;
+        Some((next_fork, fork_epoch))
+    }
+
+    /// Returns the name of the fork which is active at `slot`.
+    pub fn fork_name_at_slot<T: EthSpec>(&self, slot: Slot) -> ForkName {
+        self.fork_name_at_epoch(slot.epoch(T::slots_per_epoch()))
+    }
+
+    /// Returns the name of the fork which is active at `epoch`.
+    pub fn fork_name_at_epoch(&self, epoch: Epoch) -> ForkName {
+        match self.altair_fork_epoch {
+            Some(fork_epoch) if epoch >= fork_epoch => ForkName::Altair,
+            _ => ForkName::Base,
+        }
+    }
+
+    /// Returns the fork version for a named fork.
+    pub fn fork_version_for_name(&self, fork_name: ForkName) -> [u8; 4] {
+        match fork_name {
+            ForkName::Base => self.genesis_fork_version,
+            ForkName::Altair => self.altair_fork_version,
+        }
+    }
+
+    /// Returns the epoch at which `fork_name` activates, or `None` if it is not scheduled.
+    pub fn fork_epoch(&self, fork_name: ForkName) -> Option<Epoch> {
+        match fork_name {
+            ForkName::Base => Some(Epoch::new(0)),
+            ForkName::Altair => self.altair_fork_epoch,
+        }
+    }
+
+    /// Returns the `Fork` which is active at `epoch` according to the fork schedule.
+    ///
+    /// Unlike the `fork` of a `BeaconState`, this does not depend on which state is at hand, so it
+    /// is suitable for verifying messages which may be signed on either side of a fork.
+    pub fn fork_at_epoch(&self, epoch: Epoch) -> Fork {
+        let current_fork = self.fork_name_at_epoch(epoch);
+        let previous_fork = current_fork.previous_fork().unwrap_or(current_fork);
+
+        Fork {
+            previous_version: self.fork_version_for_name(previous_fork),
+            current_version: self.fork_version_for_name(current_fork),
+            epoch: self
+                .fork_epoch(current_fork)
+                .unwrap_or_else(|| Epoch::new(0)),
+        }
     }
 
     /// Get the domain number, unmodified by the fork.
@@ -164,6 +248,9 @@ impl ChainSpec {
             Domain::VoluntaryExit => self.domain_voluntary_exit,
             Domain::SelectionProof => self.domain_selection_proof,
             Domain::AggregateAndProof => self.domain_aggregate_and_proof,
+            Domain::SyncCommittee => self.domain_sync_committee,
+            Domain::SyncCommitteeSelectionProof => self.domain_sync_committee_selection_proof,
+            Domain::ContributionAndProof => self.domain_contribution_and_proof,
         }
     }
 
@@ -313,6 +400,9 @@ impl ChainSpec {
             domain_voluntary_exit: 4,
             domain_selection_proof: 5,
             domain_aggregate_and_proof: 6,
+            domain_sync_committee: 7,
+            domain_sync_committee_selection_proof: 8,
+            domain_contribution_and_proof: 9,
 
             /*
              * Fork choice
@@ -324,6 +414,8 @@ impl ChainSpec {
              */
             eth1_follow_distance: 1_024,
             seconds_per_eth1_block: 14,
+            deposit_chain_id: 1,
+            deposit_network_id: 1,
 
             /*
              * Network specific
@@ -336,6 +428,19 @@ impl ChainSpec {
             maximum_gossip_clock_disparity_millis: 500,
             target_aggregators_per_committee: 16,
             epochs_per_random_subnet_subscription: 256,
+
+            /*
+             * Altair hard fork params
+             */
+            altair_fork_version: [0x01, 0x00, 0x00, 0x00],
+            altair_fork_epoch: None,
+
+            /*
+             * Sync committees
+             */
+            epochs_per_sync_committee_period: Epoch::new(256),
+            sync_committee_subnet_count: 4,
+            target_aggregators_per_sync_subcommittee: 16,
         }
     }
 
@@ -355,9 +460,13 @@ impl ChainSpec {
             genesis_fork_version: [0x00, 0x00, 0x00, 0x01],
             shard_committee_period: 64,
             genesis_delay: 300,
+            epochs_per_sync_committee_period: Epoch::new(8),
             milliseconds_per_slot: 6_000,
             safe_slots_to_update_justified: 2,
             network_id: 2, // lighthouse testnet network id
+            // Goerli
+            deposit_chain_id: 5,
+            deposit_network_id: 5,
             boot_nodes,
             ..ChainSpec::mainnet()
         }
@@ -434,6 +543,39 @@ mod tests {
             spec.domain_aggregate_and_proof,
             &spec,
         );
+        test_domain(Domain::SyncCommittee, spec.domain_sync_committee, &spec);
+        test_domain(
+            Domain::SyncCommitteeSelectionProof,
+            spec.domain_sync_committee_selection_proof,
+            &spec,
+        );
+        test_domain(
+            Domain::ContributionAndProof,
+            spec.domain_contribution_and_proof,
+            &spec,
+        );
+    }
+
+    #[test]
+    fn test_fork_at_epoch() {
+        let mut spec = ChainSpec::mainnet();
+
+        let genesis_fork = Fork {
+            previous_version: spec.genesis_fork_version,
+            current_version: spec.genesis_fork_version,
+            epoch: Epoch::new(0),
+        };
+        assert_eq!(spec.fork_at_epoch(Epoch::new(100)), genesis_fork);
+
+        spec.altair_fork_epoch = Some(Epoch::new(10));
+        let altair_fork = Fork {
+            previous_version: spec.genesis_fork_version,
+            current_version: spec.altair_fork_version,
+            epoch: Epoch::new(10),
+        };
+        assert_eq!(spec.fork_at_epoch(Epoch::new(9)), genesis_fork);
+        assert_eq!(spec.fork_at_epoch(Epoch::new(10)), altair_fork);
+        assert_eq!(spec.fork_at_epoch(Epoch::new(11)), altair_fork);
     }
 }
 
@@ -551,6 +693,23 @@ pub struct YamlConfig {
     random_subnets_per_validator: u64,
     epochs_per_random_subnet_subscription: u64,
     seconds_per_eth1_block: u64,
+
+    // Deposit contract
+    //
+    // Optional so that configs which do not specify the eth1 chain or network id keep the values
+    // of the base `ChainSpec`, rather than those of the default `YamlConfig`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deposit_chain_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deposit_network_id: Option<u64>,
+
+    // Altair
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    altair_fork_version: [u8; 4],
+    altair_fork_epoch: u64,
 }
 
 impl Default for YamlConfig {
@@ -631,9 +790,30 @@ impl YamlConfig {
             random_subnets_per_validator: spec.random_subnets_per_validator,
             epochs_per_random_subnet_subscription: spec.epochs_per_random_subnet_subscription,
             seconds_per_eth1_block: spec.seconds_per_eth1_block,
+
+            // Deposit contract
+            deposit_chain_id: Some(spec.deposit_chain_id),
+            deposit_network_id: Some(spec.deposit_network_id),
+
+            // Altair
+            altair_fork_version: spec.altair_fork_version,
+            altair_fork_epoch: spec
+                .altair_fork_epoch
+                .unwrap_or(spec.far_future_epoch)
+                .into(),
         }
     }
 
+    /// Returns the eth1 chain id of the deposit contract, if it is specified.
+    pub fn deposit_chain_id(&self) -> Option<u64> {
+        self.deposit_chain_id
+    }
+
+    /// Returns the eth1 network id of the deposit contract, if it is specified.
+    pub fn deposit_network_id(&self) -> Option<u64> {
+        self.deposit_network_id
+    }
+
     pub fn from_file(filename: &Path) -> Result<Self, String> {
         let f = File::open(filename)
             .map_err(|e| format!("Error opening spec at {}: {:?}", filename.display(), e))?;
@@ -705,6 +885,13 @@ impl YamlConfig {
             boot_nodes: chain_spec.boot_nodes.clone(),
             genesis_fork_version: self.genesis_fork_version,
             eth1_follow_distance: self.eth1_follow_distance,
+            deposit_chain_id: self.deposit_chain_id.unwrap_or(chain_spec.deposit_chain_id),
+            deposit_network_id: self
+                .deposit_network_id
+                .unwrap_or(chain_spec.deposit_network_id),
+            altair_fork_version: self.altair_fork_version,
+            altair_fork_epoch: Some(Epoch::from(self.altair_fork_epoch))
+                .filter(|epoch| *epoch != Epoch::from(self.far_future_epoch)),
             ..*chain_spec
         })
     }
@@ -779,4 +966,71 @@ mod yaml_tests {
             .expect("should have applied spec");
         assert_eq!(new_spec, ChainSpec::minimal());
     }
+
+    #[test]
+    fn apply_altair_fork_epoch() {
+        let spec = ChainSpec::minimal();
+        let mut yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+        assert_eq!(yamlconfig.altair_fork_epoch, spec.far_future_epoch.as_u64());
+
+        yamlconfig.altair_fork_epoch = 10;
+        let new_spec = yamlconfig
+            .apply_to_chain_spec::<MinimalEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(new_spec.altair_fork_epoch, Some(Epoch::new(10)));
+        assert_eq!(new_spec.fork_name_at_epoch(Epoch::new(9)), ForkName::Base);
+        assert_eq!(
+            new_spec.fork_name_at_epoch(Epoch::new(10)),
+            ForkName::Altair
+        );
+        assert_eq!(
+            new_spec.next_fork_epoch::<MinimalEthSpec>(Slot::new(0)),
+            Some((ForkName::Altair, Epoch::new(10)))
+        );
+        assert_eq!(
+            new_spec.next_fork_epoch::<MinimalEthSpec>(
+                Epoch::new(10).start_slot(MinimalEthSpec::slots_per_epoch())
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn apply_deposit_chain_and_network_id() {
+        let spec = ChainSpec::minimal();
+        let mut yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+        assert_eq!(yamlconfig.deposit_chain_id, Some(5));
+        assert_eq!(yamlconfig.deposit_network_id, Some(5));
+
+        yamlconfig.deposit_chain_id = Some(1337);
+        yamlconfig.deposit_network_id = Some(42);
+        let new_spec = yamlconfig
+            .apply_to_chain_spec::<MinimalEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(new_spec.deposit_chain_id, 1337);
+        assert_eq!(new_spec.deposit_network_id, 42);
+    }
+
+    #[test]
+    fn missing_deposit_chain_and_network_id() {
+        let spec = ChainSpec::minimal();
+        let mut yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+        yamlconfig.deposit_chain_id = None;
+        yamlconfig.deposit_network_id = None;
+
+        let yaml = serde_yaml::to_string(&yamlconfig).expect("failed to serialize");
+        assert!(!yaml.contains("DEPOSIT_CHAIN_ID"));
+        assert!(!yaml.contains("DEPOSIT_NETWORK_ID"));
+
+        let from: YamlConfig = serde_yaml::from_str(&yaml).expect("error while deserializing");
+        assert_eq!(from.deposit_chain_id(), None);
+        assert_eq!(from.deposit_network_id(), None);
+
+        // The ids of the base spec are retained, rather than the mainnet defaults.
+        let new_spec = from
+            .apply_to_chain_spec::<MinimalEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(new_spec.deposit_chain_id, spec.deposit_chain_id);
+        assert_eq!(new_spec.deposit_network_id, spec.deposit_network_id);
+    }
 }
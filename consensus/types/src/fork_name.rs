@@ -0,0 +1,115 @@
+use crate::{ChainSpec, Epoch};
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Identifies a hard fork of the beacon chain, and therefore the shape of the consensus
+/// containers (`BeaconBlock`, `BeaconState`, etc.) that are valid at a given epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub enum ForkName {
+    Base,
+    Altair,
+}
+
+impl ForkName {
+    /// Returns all known forks, in the order in which they are activated.
+    pub fn list_all() -> Vec<ForkName> {
+        vec![ForkName::Base, ForkName::Altair]
+    }
+
+    /// Returns the fork that precedes `self`, or `None` if `self` is the genesis fork.
+    pub fn previous_fork(self) -> Option<ForkName> {
+        match self {
+            ForkName::Base => None,
+            ForkName::Altair => Some(ForkName::Base),
+        }
+    }
+
+    /// Returns the fork that follows `self`, or `None` if no such fork is known.
+    pub fn next_fork(self) -> Option<ForkName> {
+        match self {
+            ForkName::Base => Some(ForkName::Altair),
+            ForkName::Altair => None,
+        }
+    }
+
+    /// Returns a copy of `spec` modified so that `self` is active from genesis and all later forks
+    /// are disabled.
+    ///
+    /// Primarily useful for testing.
+    pub fn make_genesis_spec(self, mut spec: ChainSpec) -> ChainSpec {
+        match self {
+            ForkName::Base => {
+                spec.altair_fork_epoch = None;
+            }
+            ForkName::Altair => {
+                spec.altair_fork_epoch = Some(Epoch::new(0));
+            }
+        }
+        spec
+    }
+}
+
+impl FromStr for ForkName {
+    type Err = String;
+
+    fn from_str(fork_name: &str) -> Result<Self, String> {
+        Ok(match fork_name.to_lowercase().as_ref() {
+            "phase0" | "base" => ForkName::Base,
+            "altair" => ForkName::Altair,
+            _ => return Err(format!("unknown fork name: {}", fork_name)),
+        })
+    }
+}
+
+impl Display for ForkName {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ForkName::Base => "phase0".fmt(f),
+            ForkName::Altair => "altair".fmt(f),
+        }
+    }
+}
+
+impl From<ForkName> for String {
+    fn from(fork: ForkName) -> String {
+        fork.to_string()
+    }
+}
+
+impl TryFrom<String> for ForkName {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fork_name_round_trip() {
+        for fork in ForkName::list_all() {
+            assert_eq!(ForkName::from_str(&fork.to_string()), Ok(fork));
+        }
+    }
+
+    #[test]
+    fn previous_and_next_fork_consistent() {
+        for fork in ForkName::list_all() {
+            if let Some(next) = fork.next_fork() {
+                assert_eq!(next.previous_fork(), Some(fork));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_fork_name() {
+        assert!(ForkName::from_str("bellatrix").is_err());
+    }
+}
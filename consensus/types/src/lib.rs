@@ -20,6 +20,7 @@ pub mod beacon_committee;
 pub mod beacon_state;
 pub mod chain_spec;
 pub mod checkpoint;
+pub mod contribution_and_proof;
 pub mod deposit;
 pub mod deposit_data;
 pub mod deposit_message;
@@ -28,9 +29,11 @@ pub mod eth1_data;
 pub mod eth_spec;
 pub mod fork;
 pub mod fork_data;
+pub mod fork_name;
 pub mod free_attestation;
 pub mod historical_batch;
 pub mod indexed_attestation;
+pub mod light_client_update;
 pub mod pending_attestation;
 pub mod proposer_slashing;
 pub mod relative_epoch;
@@ -38,8 +41,15 @@ pub mod selection_proof;
 pub mod signed_aggregate_and_proof;
 pub mod signed_beacon_block;
 pub mod signed_beacon_block_header;
+pub mod signed_contribution_and_proof;
 pub mod signed_voluntary_exit;
 pub mod signing_data;
+pub mod sync_aggregate;
+pub mod sync_aggregator_selection_data;
+pub mod sync_committee;
+pub mod sync_committee_contribution;
+pub mod sync_committee_message;
+pub mod sync_selection_proof;
 pub mod utils;
 pub mod validator;
 pub mod voluntary_exit;
@@ -66,6 +76,7 @@ pub use crate::beacon_committee::{BeaconCommittee, OwnedBeaconCommittee};
 pub use crate::beacon_state::{BeaconTreeHashCache, Error as BeaconStateError, *};
 pub use crate::chain_spec::{ChainSpec, Domain, YamlConfig};
 pub use crate::checkpoint::Checkpoint;
+pub use crate::contribution_and_proof::ContributionAndProof;
 pub use crate::deposit::{Deposit, DEPOSIT_TREE_DEPTH};
 pub use crate::deposit_data::DepositData;
 pub use crate::deposit_message::DepositMessage;
@@ -73,9 +84,14 @@ pub use crate::enr_fork_id::EnrForkId;
 pub use crate::eth1_data::Eth1Data;
 pub use crate::fork::Fork;
 pub use crate::fork_data::ForkData;
+pub use crate::fork_name::ForkName;
 pub use crate::free_attestation::FreeAttestation;
 pub use crate::historical_batch::HistoricalBatch;
 pub use crate::indexed_attestation::IndexedAttestation;
+pub use crate::light_client_update::{
+    FinalizedRootProofLen, LightClientUpdate, NextSyncCommitteeProofLen, FINALIZED_ROOT_INDEX,
+    NEXT_SYNC_COMMITTEE_INDEX,
+};
 pub use crate::pending_attestation::PendingAttestation;
 pub use crate::proposer_slashing::ProposerSlashing;
 pub use crate::relative_epoch::{Error as RelativeEpochError, RelativeEpoch};
@@ -83,10 +99,19 @@ pub use crate::selection_proof::SelectionProof;
 pub use crate::signed_aggregate_and_proof::SignedAggregateAndProof;
 pub use crate::signed_beacon_block::{SignedBeaconBlock, SignedBeaconBlockHash};
 pub use crate::signed_beacon_block_header::SignedBeaconBlockHeader;
+pub use crate::signed_contribution_and_proof::SignedContributionAndProof;
 pub use crate::signed_voluntary_exit::SignedVoluntaryExit;
 pub use crate::signing_data::{SignedRoot, SigningData};
 pub use crate::slot_epoch::{Epoch, Slot};
 pub use crate::subnet_id::SubnetId;
+pub use crate::sync_aggregate::{Error as SyncAggregateError, SyncAggregate};
+pub use crate::sync_aggregator_selection_data::SyncAggregatorSelectionData;
+pub use crate::sync_committee::SyncCommittee;
+pub use crate::sync_committee_contribution::{
+    Error as SyncCommitteeContributionError, SyncCommitteeContribution,
+};
+pub use crate::sync_committee_message::SyncCommitteeMessage;
+pub use crate::sync_selection_proof::SyncSelectionProof;
 pub use crate::validator::Validator;
 pub use crate::voluntary_exit::VoluntaryExit;
 
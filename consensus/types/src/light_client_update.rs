@@ -0,0 +1,89 @@
+use crate::test_utils::TestRandom;
+use crate::utils::{fork_from_hex_str, fork_to_hex_str};
+use crate::{
+    BeaconBlockHeader, ChainSpec, EthSpec, FixedVector, Hash256, SyncAggregate, SyncCommittee,
+};
+use safe_arith::{ArithError, SafeArith};
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::typenum::{U5, U6};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// The generalized index of `finalized_checkpoint.root` within a `BeaconState`.
+pub const FINALIZED_ROOT_INDEX: usize = 105;
+/// The generalized index of `next_sync_committee` within a `BeaconState`.
+pub const NEXT_SYNC_COMMITTEE_INDEX: usize = 55;
+
+/// `floorlog2(FINALIZED_ROOT_INDEX)`.
+pub type FinalizedRootProofLen = U6;
+/// `floorlog2(NEXT_SYNC_COMMITTEE_INDEX)`.
+pub type NextSyncCommitteeProofLen = U5;
+
+/// A header signed by a sync committee, along with the proofs required for a light client to
+/// verify it and advance to the next sync committee period.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct LightClientUpdate<T: EthSpec> {
+    /// The beacon block header that is attested to by the sync committee.
+    pub header: BeaconBlockHeader,
+    /// The next sync committee, proven against `header.state_root`.
+    pub next_sync_committee: SyncCommittee<T>,
+    pub next_sync_committee_branch: FixedVector<Hash256, NextSyncCommitteeProofLen>,
+    /// The finalized beacon block header, proven against `header.state_root`.
+    ///
+    /// Set to the default header if the update does not prove finality.
+    pub finalized_header: BeaconBlockHeader,
+    pub finality_branch: FixedVector<Hash256, FinalizedRootProofLen>,
+    /// The sync committee aggregate signature over `header`.
+    pub sync_committee_aggregate: SyncAggregate<T>,
+    /// The fork version against which `sync_committee_aggregate` was signed.
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    pub fork_version: [u8; 4],
+}
+
+impl<T: EthSpec> LightClientUpdate<T> {
+    /// Returns the sync committee period of `self.header`.
+    pub fn sync_committee_period(&self, spec: &ChainSpec) -> Result<u64, ArithError> {
+        self.header
+            .slot
+            .epoch(T::slots_per_epoch())
+            .as_u64()
+            .safe_div(spec.epochs_per_sync_committee_period.as_u64())
+    }
+
+    /// Returns `true` if `self` proves a finalized header.
+    pub fn is_finality_update(&self) -> bool {
+        self.finalized_header != BeaconBlockHeader::default()
+    }
+
+    /// Returns `true` if `self` should be preferred over `other` as the best update for a sync
+    /// committee period.
+    ///
+    /// Updates that prove finality are always preferred, followed by those with the most sync
+    /// committee participants.
+    pub fn is_better_than(&self, other: &Self) -> bool {
+        match (self.is_finality_update(), other.is_finality_update()) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => {
+                self.sync_committee_aggregate.num_set_bits()
+                    > other.sync_committee_aggregate.num_set_bits()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    ssz_and_tree_hash_tests!(LightClientUpdate<MainnetEthSpec>);
+}
@@ -0,0 +1,87 @@
+use crate::test_utils::TestRandom;
+use crate::{AggregateSignature, BitVector, EthSpec, SyncCommitteeContribution};
+use safe_arith::SafeArith;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    SszTypesError(ssz_types::Error),
+    ArithError(safe_arith::ArithError),
+}
+
+impl From<safe_arith::ArithError> for Error {
+    fn from(e: safe_arith::ArithError) -> Error {
+        Error::ArithError(e)
+    }
+}
+
+/// The aggregate of all sync committee signatures over a single block root, as included in a
+/// block.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct SyncAggregate<T: EthSpec> {
+    pub sync_committee_bits: BitVector<T::SyncCommitteeSize>,
+    pub sync_committee_signature: AggregateSignature,
+}
+
+impl<T: EthSpec> SyncAggregate<T> {
+    /// New aggregate to be used as the seed for aggregating other signatures.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            sync_committee_bits: BitVector::default(),
+            sync_committee_signature: AggregateSignature::infinity(),
+        }
+    }
+
+    /// Create a `SyncAggregate` from a slice of `SyncCommitteeContribution`s.
+    ///
+    /// Equivalent to `process_sync_committee_contributions` from the spec.
+    pub fn from_contributions(
+        contributions: &[SyncCommitteeContribution<T>],
+    ) -> Result<SyncAggregate<T>, Error> {
+        let mut sync_aggregate = Self::new();
+        let sync_subcommittee_size = T::sync_subcommittee_size();
+
+        for contribution in contributions {
+            let offset =
+                (contribution.subcommittee_index as usize).safe_mul(sync_subcommittee_size)?;
+
+            for (index_in_subcommittee, participated) in
+                contribution.aggregation_bits.iter().enumerate()
+            {
+                if participated {
+                    let participant_index = index_in_subcommittee.safe_add(offset)?;
+                    sync_aggregate
+                        .sync_committee_bits
+                        .set(participant_index, true)
+                        .map_err(Error::SszTypesError)?;
+                }
+            }
+            sync_aggregate
+                .sync_committee_signature
+                .add_assign_aggregate(&contribution.signature);
+        }
+
+        Ok(sync_aggregate)
+    }
+
+    /// Returns the number of sync committee members that contributed to this aggregate.
+    pub fn num_set_bits(&self) -> usize {
+        self.sync_committee_bits.num_set_bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    ssz_and_tree_hash_tests!(SyncAggregate<MainnetEthSpec>);
+}
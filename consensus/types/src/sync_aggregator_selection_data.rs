@@ -0,0 +1,29 @@
+use crate::test_utils::TestRandom;
+use crate::{SignedRoot, Slot};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// The message signed by a validator to determine whether it is an aggregator for a sync
+/// subcommittee.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct SyncAggregatorSelectionData {
+    pub slot: Slot,
+    pub subcommittee_index: u64,
+}
+
+impl SignedRoot for SyncAggregatorSelectionData {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(SyncAggregatorSelectionData);
+}
@@ -0,0 +1,25 @@
+use crate::test_utils::TestRandom;
+use crate::{EthSpec, FixedVector, PublicKeyBytes};
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// The public keys of the validators selected to sign block roots for a sync committee period.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct SyncCommittee<T: EthSpec> {
+    pub pubkeys: FixedVector<PublicKeyBytes, T::SyncCommitteeSize>,
+    pub aggregate_pubkey: PublicKeyBytes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    ssz_and_tree_hash_tests!(SyncCommittee<MainnetEthSpec>);
+}
@@ -0,0 +1,94 @@
+use crate::test_utils::TestRandom;
+use crate::{AggregateSignature, BitVector, EthSpec, Hash256, Slot, SyncCommitteeMessage};
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    SszTypesError(ssz_types::Error),
+    AlreadySigned(usize),
+}
+
+/// An aggregation of `SyncCommitteeMessage`s from a single sync subcommittee.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct SyncCommitteeContribution<T: EthSpec> {
+    pub slot: Slot,
+    pub beacon_block_root: Hash256,
+    pub subcommittee_index: u64,
+    pub aggregation_bits: BitVector<T::SyncSubcommitteeSize>,
+    pub signature: AggregateSignature,
+}
+
+impl<T: EthSpec> SyncCommitteeContribution<T> {
+    /// Create a `SyncCommitteeContribution` from a single `SyncCommitteeMessage`.
+    ///
+    /// `validator_sync_committee_index` is the position of the signing validator within the
+    /// `subcommittee_index`'th subcommittee.
+    pub fn from_message(
+        message: &SyncCommitteeMessage,
+        subcommittee_index: u64,
+        validator_sync_committee_index: usize,
+    ) -> Result<Self, Error> {
+        let mut bits = BitVector::new();
+        bits.set(validator_sync_committee_index, true)
+            .map_err(Error::SszTypesError)?;
+
+        let mut signature = AggregateSignature::infinity();
+        signature.add_assign(&message.signature);
+
+        Ok(Self {
+            slot: message.slot,
+            beacon_block_root: message.beacon_block_root,
+            subcommittee_index,
+            aggregation_bits: bits,
+            signature,
+        })
+    }
+
+    /// Returns `true` if `self` and `other` have no signers in common.
+    pub fn signers_disjoint_from(&self, other: &Self) -> bool {
+        !self
+            .aggregation_bits
+            .iter()
+            .zip(other.aggregation_bits.iter())
+            .any(|(a, b)| a && b)
+    }
+
+    /// Aggregate another `SyncCommitteeContribution` into this one.
+    ///
+    /// The aggregation bitfields must be disjoint, and the slot, block root and subcommittee must
+    /// be the same.
+    pub fn aggregate(&mut self, other: &Self) {
+        debug_assert_eq!(self.slot, other.slot);
+        debug_assert_eq!(self.beacon_block_root, other.beacon_block_root);
+        debug_assert_eq!(self.subcommittee_index, other.subcommittee_index);
+        debug_assert!(self.signers_disjoint_from(other));
+
+        for (i, bit) in other.aggregation_bits.iter().enumerate() {
+            if bit {
+                // Both bitfields have the same fixed length, so this index is always in bounds.
+                let _ = self.aggregation_bits.set(i, true);
+            }
+        }
+        self.signature.add_assign_aggregate(&other.signature);
+    }
+
+    /// Returns the number of participants in this contribution.
+    pub fn num_set_bits(&self) -> usize {
+        self.aggregation_bits.num_set_bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    ssz_and_tree_hash_tests!(SyncCommitteeContribution<MainnetEthSpec>);
+}
@@ -0,0 +1,48 @@
+use crate::test_utils::TestRandom;
+use crate::{ChainSpec, Domain, EthSpec, Fork, Hash256, SecretKey, Signature, SignedRoot, Slot};
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A signature over the head block root from a member of the current sync committee.
+///
+/// Spec v1.1.0
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+pub struct SyncCommitteeMessage {
+    pub slot: Slot,
+    pub beacon_block_root: Hash256,
+    pub validator_index: u64,
+    pub signature: Signature,
+}
+
+impl SyncCommitteeMessage {
+    /// Produces a new `SyncCommitteeMessage` by signing `beacon_block_root` with `secret_key`.
+    pub fn new<E: EthSpec>(
+        slot: Slot,
+        beacon_block_root: Hash256,
+        validator_index: u64,
+        secret_key: &SecretKey,
+        fork: &Fork,
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) -> Self {
+        let epoch = slot.epoch(E::slots_per_epoch());
+        let domain = spec.get_domain(epoch, Domain::SyncCommittee, fork, genesis_validators_root);
+        let message = beacon_block_root.signing_root(domain);
+        Self {
+            slot,
+            beacon_block_root,
+            validator_index,
+            signature: secret_key.sign(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(SyncCommitteeMessage);
+}
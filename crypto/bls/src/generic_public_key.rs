@@ -10,6 +10,9 @@ use tree_hash::TreeHash;
 /// The byte-length of a BLS public key when serialized in compressed form.
 pub const PUBLIC_KEY_BYTES_LEN: usize = 48;
 
+/// The byte-length of a BLS public key when serialized in uncompressed form.
+pub const PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN: usize = 96;
+
 /// Represents the public key at infinity.
 pub const INFINITY_PUBLIC_KEY: [u8; PUBLIC_KEY_BYTES_LEN] = [
     0xc0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -24,6 +27,14 @@ pub trait TPublicKey: Sized + Clone {
 
     /// Deserialize `self` from compressed bytes.
     fn deserialize(bytes: &[u8]) -> Result<Self, Error>;
+
+    /// Serialize `self` as uncompressed bytes.
+    fn serialize_uncompressed(&self) -> [u8; PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN];
+
+    /// Deserialize `self` from uncompressed bytes.
+    ///
+    /// This is significantly faster than `Self::deserialize` since it avoids point decompression.
+    fn deserialize_uncompressed(bytes: &[u8]) -> Result<Self, Error>;
 }
 
 /// A BLS aggregate public key that is generic across some BLS point (`Pub`).
@@ -68,6 +79,21 @@ where
             is_infinity: bytes == &INFINITY_PUBLIC_KEY[..],
         })
     }
+
+    /// Serialize `self` as uncompressed bytes.
+    pub fn serialize_uncompressed(&self) -> [u8; PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN] {
+        self.point.serialize_uncompressed()
+    }
+
+    /// Deserialize `self` from uncompressed bytes.
+    ///
+    /// Avoids the cost of point decompression, so it should be preferred when reading keys that
+    /// were previously written by `Self::serialize_uncompressed`.
+    pub fn deserialize_uncompressed(bytes: &[u8]) -> Result<Self, Error> {
+        let point = Pub::deserialize_uncompressed(bytes)?;
+        let is_infinity = point.serialize()[..] == INFINITY_PUBLIC_KEY[..];
+        Ok(Self { point, is_infinity })
+    }
 }
 
 impl<Pub: TPublicKey> Eq for GenericPublicKey<Pub> {}
@@ -46,6 +46,14 @@ macro_rules! test_suite {
             ssz_round_trip(agg_sig);
         }
 
+        #[test]
+        fn uncompressed_pubkey_round_trip() {
+            let pubkey = secret_from_u64(42).public_key();
+            let bytes = pubkey.serialize_uncompressed();
+            assert_eq!(PublicKey::deserialize_uncompressed(&bytes).unwrap(), pubkey);
+            assert!(PublicKey::deserialize_uncompressed(&pubkey.serialize()).is_err());
+        }
+
         #[test]
         fn ssz_round_trip_sig_empty() {
             ssz_round_trip(Signature::empty())
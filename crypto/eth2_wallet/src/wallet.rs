@@ -285,3 +285,21 @@ pub fn recover_validator_secret(
 
     Ok((destination.secret().to_vec().into(), path))
 }
+
+/// Returns `(secret, path)` for the `key_type` for the validator at `index`, derived from the
+/// BIP-39 `seed` of a mnemonic.
+///
+/// This function is used for recovering keys without an existing wallet. The same caveats as
+/// `recover_validator_secret` apply.
+pub fn recover_validator_secret_from_mnemonic(
+    seed: &[u8],
+    index: u32,
+    key_type: KeyType,
+) -> Result<(PlainText, ValidatorPath), Error> {
+    let path = ValidatorPath::new(index, key_type);
+    let master = DerivedKey::from_seed(seed).map_err(|()| Error::EmptySeed)?;
+
+    let destination = path.iter_nodes().fold(master, |dk, i| dk.child(*i));
+
+    Ok((destination.secret().to_vec().into(), path))
+}
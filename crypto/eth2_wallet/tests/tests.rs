@@ -2,7 +2,8 @@
 
 use eth2_wallet::{
     bip39::{Language, Mnemonic, Seed},
-    recover_validator_secret, DerivedKey, Error, KeyType, KeystoreError, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_from_mnemonic, DerivedKey, Error, KeyType,
+    KeystoreError, Wallet, WalletBuilder,
 };
 use std::fs::OpenOptions;
 use tempfile::tempdir;
@@ -209,6 +210,46 @@ fn empty_keystore_password() {
     assert_eq!(wallet.nextaccount(), 0, "next account should not update");
 }
 
+#[test]
+fn key_recovery_from_seed() {
+    for i in 0..4 {
+        let (voting, voting_path) =
+            recover_validator_secret_from_mnemonic(SEED, i, KeyType::Voting)
+                .expect("should recover voting secret");
+        let (withdrawal, withdrawal_path) =
+            recover_validator_secret_from_mnemonic(SEED, i, KeyType::Withdrawal)
+                .expect("should recover withdrawal secret");
+
+        assert_eq!(
+            format!("{}", voting_path),
+            format!("m/12381/3600/{}/0/0", i),
+            "voting path should match"
+        );
+        assert_eq!(
+            format!("{}", withdrawal_path),
+            format!("m/12381/3600/{}/0", i),
+            "withdrawal path should match"
+        );
+
+        assert_eq!(
+            voting.as_bytes(),
+            &manually_derived_voting_key(i)[..],
+            "voting secret should match manually derived"
+        );
+        assert_eq!(
+            withdrawal.as_bytes(),
+            &manually_derived_withdrawal_key(i)[..],
+            "withdrawal secret should match manually derived"
+        );
+    }
+
+    assert_eq!(
+        recover_validator_secret_from_mnemonic(&[], 0, KeyType::Voting).err(),
+        Some(Error::EmptySeed),
+        "should fail with empty seed"
+    );
+}
+
 #[test]
 fn key_derivation_from_seed() {
     let mut wallet = wallet_from_seed();
@@ -0,0 +1,1128 @@
+use beacon_node::{get_data_dir, get_db_paths, reconstruct_historic_states, ClientConfig};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_libp2p::PeerId;
+use network::persisted_bans::{load_bans, persist_bans, PersistedBans};
+use remote_beacon_node::{BanList, BanRequest, RemoteBeaconNode};
+use serde::Serialize;
+use slasher::Slasher;
+use slog::info;
+use ssz::Encode;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use store::{
+    backend::migrate_backend,
+    config::DEFAULT_SLOTS_PER_RESTORE_POINT,
+    snapshot::{export_snapshot, import_snapshot},
+    BeaconNodeBackend, DBColumn, DatabaseBackend, HotColdDB, KeyValueStore,
+};
+use types::{EthSpec, Hash256, IndexedAttestation};
+
+pub const CMD: &str = "database_manager";
+pub const COMPACT_CMD: &str = "compact";
+pub const MIGRATE_BACKEND_CMD: &str = "migrate-backend";
+pub const MIGRATE_SLASHER_BACKEND_CMD: &str = "migrate-slasher-backend";
+pub const IMPORT_SLASHER_ATTESTATIONS_CMD: &str = "import-slasher-attestations";
+pub const INSPECT_CMD: &str = "inspect";
+pub const PURGE_CMD: &str = "purge";
+pub const RECONSTRUCT_STATES_CMD: &str = "reconstruct-states";
+pub const SNAPSHOT_CMD: &str = "snapshot";
+pub const SNAPSHOT_EXPORT_CMD: &str = "export";
+pub const SNAPSHOT_IMPORT_CMD: &str = "import";
+pub const BANS_CMD: &str = "bans";
+pub const BANS_LIST_CMD: &str = "list";
+pub const BANS_ADD_CMD: &str = "add";
+pub const BANS_REMOVE_CMD: &str = "remove";
+
+/// The text that must be entered to confirm a purge.
+const PURGE_CONFIRMATION: &str = "delete";
+
+/// The time allowed for a running beacon node to export a snapshot.
+const SNAPSHOT_REQUEST_TIMEOUT_SECS: u64 = 6 * 60 * 60;
+
+pub fn compact_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(COMPACT_CMD).about(
+        "Compacts the hot database, reclaiming the disk space used by deleted blocks and \
+         states. The beacon node must not be running.",
+    )
+}
+
+pub fn migrate_backend_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(MIGRATE_BACKEND_CMD)
+        .about(
+            "Copies the hot and freezer databases from the backend given by --db-backend to \
+             another backend. The original databases are left in place. The beacon node must \
+             not be running.",
+        )
+        .arg(
+            Arg::with_name("to")
+                .long("to")
+                .value_name("BACKEND")
+                .help("The backend to copy the databases to.")
+                .takes_value(true)
+                .required(true)
+                .possible_values(&["leveldb", "sqlite"]),
+        )
+}
+
+pub fn migrate_slasher_backend_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(MIGRATE_SLASHER_BACKEND_CMD)
+        .about(
+            "Copies the slasher database to another backend. The original database is left in \
+             place. The beacon node must not be running.",
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("DIR")
+                .help("Data directory for the slasher database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("from")
+                .long("from")
+                .value_name("BACKEND")
+                .help("The backend currently used by the slasher database.")
+                .takes_value(true)
+                .possible_values(&["lmdb", "mdbx"])
+                .default_value("lmdb"),
+        )
+        .arg(
+            Arg::with_name("to")
+                .long("to")
+                .value_name("BACKEND")
+                .help("The backend to copy the slasher database to.")
+                .takes_value(true)
+                .required(true)
+                .possible_values(&["lmdb", "mdbx"]),
+        )
+}
+
+pub fn import_slasher_attestations_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(IMPORT_SLASHER_ATTESTATIONS_CMD)
+        .about(
+            "Imports a JSON list of indexed attestations into the slasher database, so that they \
+             are checked for slashings. The attestations must come from a trusted source, as \
+             their signatures are not verified. The beacon node must not be running.",
+        )
+        .arg(
+            Arg::with_name("file")
+                .long("file")
+                .value_name("FILE")
+                .help("Path to the JSON file of indexed attestations.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("DIR")
+                .help("Data directory for the slasher database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .value_name("BACKEND")
+                .help("The backend used by the slasher database.")
+                .takes_value(true)
+                .possible_values(&["lmdb", "mdbx"])
+                .default_value("lmdb"),
+        )
+}
+
+pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(INSPECT_CMD)
+        .about(
+            "Inspects the contents of the database. By default, reports the number of entries \
+             and their total size for each column. The beacon node must not be running.",
+        )
+        .arg(
+            Arg::with_name("freezer")
+                .long("freezer")
+                .help("Inspect the freezer database rather than the hot database.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("column")
+                .long("column")
+                .value_name("COLUMN")
+                .help(
+                    "List the keys of the given column, identified by its three-letter \
+                     prefix (e.g. blk for blocks, ste for states).",
+                )
+                .takes_value(true)
+                .conflicts_with_all(&["block-root", "state-root"]),
+        )
+        .arg(
+            Arg::with_name("block-root")
+                .long("block-root")
+                .value_name("ROOT")
+                .help("Dump the block with the given root.")
+                .takes_value(true)
+                .conflicts_with("state-root"),
+        )
+        .arg(
+            Arg::with_name("state-root")
+                .long("state-root")
+                .value_name("ROOT")
+                .help("Dump the state with the given root.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("The format in which to dump a block or state.")
+                .takes_value(true)
+                .possible_values(&["json", "ssz"])
+                .default_value("json"),
+        )
+        .arg(
+            Arg::with_name("output-file")
+                .long("output-file")
+                .value_name("PATH")
+                .help("Write a dumped block or state to this file, rather than stdout.")
+                .takes_value(true),
+        )
+}
+
+pub fn purge_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(PURGE_CMD)
+        .about(
+            "Deletes the chain and freezer databases, so that the beacon node will sync from \
+             scratch. The network and validator keys are preserved. The beacon node must not be \
+             running.",
+        )
+        .arg(
+            Arg::with_name("confirm")
+                .long("confirm")
+                .help("Delete the databases without prompting for confirmation.")
+                .takes_value(false),
+        )
+}
+
+pub fn reconstruct_states_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(RECONSTRUCT_STATES_CMD)
+        .about(
+            "Rebuilds the freezer states prior to the state the database was initialized from, \
+             by replaying blocks from genesis. The blocks are downloaded from an archive beacon \
+             node and checked against the initial state before any historic state becomes \
+             available. The beacon node must not be running; use its \
+             --reconstruct-historic-states flag to reconstruct in the background instead.",
+        )
+        .arg(
+            Arg::with_name("beacon-node")
+                .long("beacon-node")
+                .value_name("URL")
+                .help("The HTTP API of an archive beacon node to download blocks from.")
+                .takes_value(true)
+                .required(true),
+        )
+}
+
+pub fn snapshot_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(SNAPSHOT_CMD)
+        .about(
+            "Exports or imports a point-in-time archive of the hot and freezer databases, which \
+             can be used to back up a beacon node or to clone it to another machine.",
+        )
+        .subcommand(
+            App::new(SNAPSHOT_EXPORT_CMD)
+                .about(
+                    "Exports a snapshot of the database. With --beacon-node, the snapshot is \
+                     taken by the running beacon node and written to the \"snapshots\" \
+                     directory alongside its database. Otherwise, the database is read \
+                     directly and the beacon node must not be running.",
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("The file to write the snapshot to. Must not already exist.")
+                        .takes_value(true)
+                        .required_unless("beacon-node")
+                        .conflicts_with("beacon-node"),
+                )
+                .arg(
+                    Arg::with_name("beacon-node")
+                        .long("beacon-node")
+                        .value_name("URL")
+                        .help(
+                            "The HTTP API of a running beacon node to take the snapshot. The \
+                             beacon node must be started with --http-allow-snapshots.",
+                        )
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new(SNAPSHOT_IMPORT_CMD)
+                .about(
+                    "Creates the hot and freezer databases from a snapshot. There must be no \
+                     existing database. The beacon node must not be running.",
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .value_name("FILE")
+                        .help("The snapshot to import.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+}
+
+fn ban_beacon_node_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("beacon-node")
+        .long("beacon-node")
+        .value_name("URL")
+        .help(
+            "The HTTP API of a running beacon node. If not provided, the database is read \
+             directly and the beacon node must not be running.",
+        )
+        .takes_value(true)
+}
+
+fn ban_target_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("peer-id")
+            .long("peer-id")
+            .value_name("PEER_ID")
+            .help("The base58 encoded id of a peer.")
+            .takes_value(true)
+            .required_unless("ip")
+            .conflicts_with("ip"),
+    )
+    .arg(
+        Arg::with_name("ip")
+            .long("ip")
+            .value_name("IP_ADDRESS")
+            .help("An IPv4 or IPv6 address.")
+            .takes_value(true),
+    )
+    .arg(ban_beacon_node_arg())
+}
+
+pub fn bans_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(BANS_CMD)
+        .about(
+            "Manages the peers and IP addresses banned by the user. Unlike bans resulting from a \
+             peer's behaviour, these bans never expire and are kept across restarts.",
+        )
+        .subcommand(
+            App::new(BANS_LIST_CMD)
+                .about("Lists the banned peers and IP addresses.")
+                .arg(ban_beacon_node_arg()),
+        )
+        .subcommand(ban_target_args(App::new(BANS_ADD_CMD).about(
+            "Bans a peer or IP address, disconnecting any affected peers.",
+        )))
+        .subcommand(ban_target_args(
+            App::new(BANS_REMOVE_CMD).about("Removes a peer or IP address from the ban list."),
+        ))
+}
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["db"])
+        .about("Utilities for managing a Lighthouse beacon node database.")
+        .arg(
+            Arg::with_name("slots-per-restore-point")
+                .long("slots-per-restore-point")
+                .value_name("SLOT_COUNT")
+                .help(
+                    "Specifies how often a freezer DB restore point should be stored. \
+                     Must match the value used by the beacon node.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("freezer-dir")
+                .long("freezer-dir")
+                .value_name("DIR")
+                .help("Data directory for the freezer database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("db-backend")
+                .long("db-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used by the beacon node's databases.")
+                .takes_value(true)
+                .possible_values(&["leveldb", "sqlite"])
+                .default_value("leveldb"),
+        )
+        .subcommand(compact_cli_app())
+        .subcommand(migrate_backend_cli_app())
+        .subcommand(migrate_slasher_backend_cli_app())
+        .subcommand(import_slasher_attestations_cli_app())
+        .subcommand(inspect_cli_app())
+        .subcommand(purge_cli_app())
+        .subcommand(reconstruct_states_cli_app())
+        .subcommand(snapshot_cli_app())
+        .subcommand(bans_cli_app())
+}
+
+fn parse_client_config<E: EthSpec>(cli_args: &ArgMatches) -> Result<ClientConfig, String> {
+    let mut client_config = ClientConfig::default();
+
+    client_config.data_dir = get_data_dir(cli_args);
+
+    if let Some(freezer_dir) = clap_utils::parse_optional::<PathBuf>(cli_args, "freezer-dir")? {
+        client_config.freezer_db_path = Some(freezer_dir);
+    }
+
+    client_config.store.slots_per_restore_point =
+        clap_utils::parse_optional(cli_args, "slots-per-restore-point")?.unwrap_or_else(|| {
+            cmp::min(
+                E::slots_per_historical_root() as u64,
+                DEFAULT_SLOTS_PER_RESTORE_POINT,
+            )
+        });
+
+    client_config.store.backend = clap_utils::parse_required(cli_args, "db-backend")?;
+
+    Ok(client_config)
+}
+
+/// Open the hot and cold databases described by `client_config` and compact the hot database.
+pub fn compact_db<E: EthSpec>(
+    client_config: ClientConfig,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let spec = env.eth2_config.spec.clone();
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        client_config.store,
+        spec,
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    info!(log, "Compacting database"; "path" => format!("{:?}", hot_path));
+    db.compact()
+        .map_err(|e| format!("Unable to compact database: {:?}", e))?;
+    info!(log, "Database compaction complete");
+
+    Ok(())
+}
+
+/// Copy the hot and cold databases described by `client_config` to the `to` backend.
+pub fn migrate_db_backend<E: EthSpec>(
+    client_config: ClientConfig,
+    to: DatabaseBackend,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let from = client_config.store.backend;
+
+    if from == to {
+        return Err(format!("The database is already using the {} backend", to));
+    }
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    for path in &[hot_path, cold_path] {
+        if !BeaconNodeBackend::<E>::exists(from, path) {
+            return Err(format!("No {} database found at {:?}", from, path));
+        }
+
+        info!(
+            log,
+            "Migrating database";
+            "path" => format!("{:?}", path),
+            "from" => from.as_str(),
+            "to" => to.as_str(),
+        );
+        let count = migrate_backend::<E>(path, from, to)
+            .map_err(|e| format!("Unable to migrate database at {:?}: {:?}", path, e))?;
+        info!(log, "Database migrated"; "entries" => count);
+    }
+
+    info!(
+        log,
+        "Database migration complete";
+        "msg" => format!("start the beacon node with --db-backend {} to use the new databases", to),
+    );
+
+    Ok(())
+}
+
+/// Copy the slasher database in the directory given by `cli_args` (or the default slasher
+/// directory of `client_config`) to another backend.
+pub fn migrate_slasher_backend<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+
+    let slasher_dir = clap_utils::parse_optional::<PathBuf>(cli_args, "slasher-dir")?
+        .unwrap_or_else(|| client_config.data_dir.join(slasher::DATABASE_DIR_NAME));
+    let from: slasher::DatabaseBackend = clap_utils::parse_required(cli_args, "from")?;
+    let to: slasher::DatabaseBackend = clap_utils::parse_required(cli_args, "to")?;
+
+    if from == to {
+        return Err(format!(
+            "The slasher database is already using the {} backend",
+            to
+        ));
+    }
+
+    let slasher_config = slasher::Config {
+        backend: from,
+        ..slasher::Config::new(slasher_dir.clone())
+    };
+
+    info!(
+        log,
+        "Migrating slasher database";
+        "path" => format!("{:?}", slasher_dir),
+        "from" => from.as_str(),
+        "to" => to.as_str(),
+    );
+    let count = slasher::migrate_backend(&slasher_config, to).map_err(|e| {
+        format!(
+            "Unable to migrate slasher database at {:?}: {:?}",
+            slasher_dir, e
+        )
+    })?;
+
+    info!(
+        log,
+        "Slasher database migration complete";
+        "entries" => count,
+        "msg" => format!("start the beacon node with --slasher-backend {} to use the new database", to),
+    );
+
+    Ok(())
+}
+
+/// Import the indexed attestations in the file given by `cli_args` into the slasher database.
+pub fn import_slasher_attestations<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+
+    let path: PathBuf = clap_utils::parse_required(cli_args, "file")?;
+    let slasher_dir = clap_utils::parse_optional::<PathBuf>(cli_args, "slasher-dir")?
+        .unwrap_or_else(|| client_config.data_dir.join(slasher::DATABASE_DIR_NAME));
+    let backend = clap_utils::parse_required(cli_args, "slasher-backend")?;
+
+    let file = fs::File::open(&path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+    let attestations: Vec<IndexedAttestation<E>> = serde_json::from_reader(file)
+        .map_err(|e| format!("Unable to parse attestations from {:?}: {:?}", path, e))?;
+    let max_target_epoch = attestations
+        .iter()
+        .map(|attestation| attestation.data.target.epoch)
+        .max()
+        .ok_or_else(|| format!("No attestations found in {:?}", path))?;
+
+    let config = slasher::Config {
+        backend,
+        ..slasher::Config::new(slasher_dir.clone())
+    };
+    let slasher = match Slasher::<E>::open(config.clone(), log.clone()) {
+        // Use the chunk sizes and history length of an existing database.
+        Err(slasher::Error::ConfigIncompatible { on_disk_config, .. }) => Slasher::open(
+            slasher::Config {
+                database_path: config.database_path,
+                backend: config.backend,
+                ..on_disk_config
+            },
+            log.clone(),
+        ),
+        result => result,
+    }
+    .map_err(|e| {
+        format!(
+            "Unable to open slasher database at {:?}: {:?}",
+            slasher_dir, e
+        )
+    })?;
+
+    info!(
+        log,
+        "Importing attestations into slasher";
+        "path" => format!("{:?}", path),
+        "count" => attestations.len(),
+    );
+    slasher
+        .import_attestations(attestations, max_target_epoch)
+        .map_err(|e| format!("Unable to import attestations: {:?}", e))?;
+
+    Ok(())
+}
+
+/// Parse a 32-byte root, with or without a `0x` prefix.
+fn parse_root(cli_args: &ArgMatches, name: &str) -> Result<Option<Hash256>, String> {
+    cli_args
+        .value_of(name)
+        .map(|s| {
+            let bytes = hex::decode(s.trim_start_matches("0x"))
+                .map_err(|e| format!("Invalid {}: {:?}", name, e))?;
+            if bytes.len() == 32 {
+                Ok(Hash256::from_slice(&bytes))
+            } else {
+                Err(format!("Invalid {}: expected 32 bytes", name))
+            }
+        })
+        .transpose()
+}
+
+/// Write `item` in the given `format` to `output_file`, or stdout if no file is given.
+fn dump_item<T: Encode + Serialize>(
+    item: &T,
+    format: &str,
+    output_file: Option<PathBuf>,
+) -> Result<(), String> {
+    let bytes = match format {
+        "ssz" => item.as_ssz_bytes(),
+        _ => serde_json::to_vec_pretty(item)
+            .map_err(|e| format!("Unable to serialize as JSON: {:?}", e))?,
+    };
+
+    match output_file {
+        Some(path) => {
+            fs::write(&path, &bytes).map_err(|e| format!("Unable to write to {:?}: {:?}", path, e))
+        }
+        None => io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| format!("Unable to write to stdout: {:?}", e)),
+    }
+}
+
+/// Inspect the database described by `client_config`, as configured by the `inspect` subcommand
+/// arguments in `cli_args`.
+pub fn inspect_db<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let spec = env.eth2_config.spec.clone();
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if !BeaconNodeBackend::<E>::exists(client_config.store.backend, &hot_path) {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let block_root = parse_root(cli_args, "block-root")?;
+    let state_root = parse_root(cli_args, "state-root")?;
+
+    // Blocks and states are loaded via the `HotColdDB`, which knows where to find them.
+    if block_root.is_some() || state_root.is_some() {
+        let format = cli_args.value_of("format").unwrap_or("json");
+        let output_file = clap_utils::parse_optional(cli_args, "output-file")?;
+        let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+            &hot_path,
+            &cold_path,
+            client_config.store,
+            spec,
+            log,
+        )
+        .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+        if let Some(root) = block_root {
+            let block = db
+                .get_block(&root)
+                .map_err(|e| format!("Unable to read block: {:?}", e))?
+                .ok_or_else(|| format!("Block {:?} not found", root))?;
+            return dump_item(&block, format, output_file);
+        } else if let Some(root) = state_root {
+            let state = db
+                .get_state(&root, None)
+                .map_err(|e| format!("Unable to read state: {:?}", e))?
+                .ok_or_else(|| format!("State {:?} not found", root))?;
+            return dump_item(&state, format, output_file);
+        }
+    }
+
+    let path = if cli_args.is_present("freezer") {
+        cold_path
+    } else {
+        hot_path
+    };
+    let db = BeaconNodeBackend::<E>::open(client_config.store.backend, &path)
+        .map_err(|e| format!("Unable to open database at {:?}: {:?}", path, e))?;
+    let entries = db.iter_raw_entries();
+
+    // List the keys of a single column.
+    if let Some(column) = clap_utils::parse_optional::<DBColumn>(cli_args, "column")? {
+        let prefix = column.as_str().as_bytes();
+        for entry in entries {
+            let (key, _) = entry.map_err(|e| format!("Unable to read database: {:?}", e))?;
+            if key.starts_with(prefix) {
+                println!("0x{}", hex::encode(&key[prefix.len()..]));
+            }
+        }
+        return Ok(());
+    }
+
+    // Otherwise, report the number and size of the entries in each column.
+    let mut sizes: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for entry in entries {
+        let (key, value) = entry.map_err(|e| format!("Unable to read database: {:?}", e))?;
+        let prefix_len = cmp::min(DBColumn::key_prefix_len(), key.len());
+        let column = String::from_utf8_lossy(&key[..prefix_len]).into_owned();
+        let (count, size) = sizes.entry(column).or_default();
+        *count += 1;
+        *size += (key.len() + value.len()) as u64;
+    }
+
+    println!(
+        "{:<8} {:<24} {:>12} {:>16}",
+        "column", "name", "entries", "bytes"
+    );
+    for (column, (count, size)) in &sizes {
+        let name = column
+            .parse::<DBColumn>()
+            .map(|column| format!("{:?}", column))
+            .unwrap_or_else(|_| "unknown".to_string());
+        println!("{:<8} {:<24} {:>12} {:>16}", column, name, count, size);
+    }
+    println!(
+        "{:<8} {:<24} {:>12} {:>16}",
+        "total",
+        "",
+        sizes.values().map(|(count, _)| count).sum::<u64>(),
+        sizes.values().map(|(_, size)| size).sum::<u64>()
+    );
+
+    Ok(())
+}
+
+/// Delete the databases described by `client_config`, after confirmation from the user (unless
+/// `--confirm` is present in `cli_args`).
+pub fn purge_db<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+
+    let paths = get_db_paths(&client_config)?;
+    if paths.is_empty() {
+        info!(log, "No database found"; "datadir" => format!("{:?}", client_config.data_dir));
+        return Ok(());
+    }
+
+    eprintln!("The following will be permanently deleted:");
+    for path in &paths {
+        eprintln!("  {:?}", path);
+    }
+
+    if !cli_args.is_present("confirm") {
+        eprintln!("Enter \"{}\" to confirm:", PURGE_CONFIRMATION);
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| format!("Unable to read from stdin: {:?}", e))?;
+
+        if input.trim() != PURGE_CONFIRMATION {
+            return Err("Purge cancelled".to_string());
+        }
+    }
+
+    for path in beacon_node::purge_db(&client_config)? {
+        info!(log, "Removed database files"; "path" => format!("{:?}", path));
+    }
+
+    Ok(())
+}
+
+/// Rebuild the historic states of the database described by `client_config`, using the blocks of
+/// the archive beacon node at `--beacon-node`.
+pub fn reconstruct_states<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let spec = env.eth2_config.spec.clone();
+    let url: String = clap_utils::parse_required(cli_args, "beacon-node")?;
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        client_config.store,
+        spec,
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    if db.get_anchor_info().is_none() {
+        info!(log, "Historic states are already available");
+        return Ok(());
+    }
+
+    env.runtime()
+        .block_on(reconstruct_historic_states(Arc::new(db), url, log))
+}
+
+/// Export a snapshot of the database described by `client_config`, either directly or via the
+/// running beacon node at `--beacon-node`.
+pub fn export_db_snapshot<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+
+    if let Some(url) = cli_args.value_of("beacon-node") {
+        info!(log, "Requesting database snapshot"; "beacon_node" => url);
+        let node = RemoteBeaconNode::<E>::new_with_timeout(
+            url.to_string(),
+            Duration::from_secs(SNAPSHOT_REQUEST_TIMEOUT_SECS),
+        )?;
+        let snapshot = env
+            .runtime()
+            .block_on(node.http.lighthouse().database_snapshot())
+            .map_err(|e| format!("Unable to export snapshot: {:?}", e))?;
+
+        info!(
+            log,
+            "Database snapshot exported by beacon node";
+            "path" => format!("{:?}", snapshot.path),
+            "split_slot" => snapshot.split_slot,
+            "hot_entries" => snapshot.hot_entries,
+            "freezer_entries" => snapshot.cold_entries,
+        );
+        return Ok(());
+    }
+
+    let output: PathBuf = clap_utils::parse_required(cli_args, "output")?;
+    let spec = env.eth2_config.spec.clone();
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        client_config.store,
+        spec,
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    info!(log, "Exporting database snapshot"; "path" => format!("{:?}", output));
+    let summary =
+        export_snapshot(&db, &output).map_err(|e| format!("Unable to export snapshot: {:?}", e))?;
+    info!(
+        log,
+        "Database snapshot exported";
+        "split_slot" => summary.split_slot,
+        "hot_entries" => summary.hot_entries,
+        "freezer_entries" => summary.cold_entries,
+    );
+
+    Ok(())
+}
+
+/// Create the databases described by `client_config` from the snapshot at `--input`.
+pub fn import_db_snapshot<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let input: PathBuf = clap_utils::parse_required(cli_args, "input")?;
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    info!(
+        log,
+        "Importing database snapshot";
+        "snapshot" => format!("{:?}", input),
+        "path" => format!("{:?}", hot_path),
+        "freezer_path" => format!("{:?}", cold_path),
+    );
+    let summary = import_snapshot::<E>(&input, &hot_path, &cold_path, client_config.store.backend)
+        .map_err(|e| {
+            format!(
+                "Unable to import snapshot: {:?}. Any partially imported database must be \
+                 removed with `lighthouse db {}` before trying again.",
+                e, PURGE_CMD
+            )
+        })?;
+    info!(
+        log,
+        "Database snapshot imported";
+        "split_slot" => summary.split_slot,
+        "hot_entries" => summary.hot_entries,
+        "freezer_entries" => summary.cold_entries,
+    );
+
+    Ok(())
+}
+
+/// Open the ban list persisted in the database described by `client_config`.
+fn open_ban_list<E: EthSpec>(
+    client_config: ClientConfig,
+    env: &mut Environment<E>,
+) -> Result<
+    (
+        Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
+        PersistedBans,
+    ),
+    String,
+> {
+    let log = env.core_context().log().clone();
+    let spec = env.eth2_config.spec.clone();
+
+    let hot_path = client_config
+        .get_db_path()
+        .ok_or_else(|| "Unable to locate the database directory".to_string())?;
+    let cold_path = client_config
+        .get_freezer_db_path()
+        .ok_or_else(|| "Unable to locate the freezer database directory".to_string())?;
+
+    if !hot_path.exists() {
+        return Err(format!("No database found at {:?}", hot_path));
+    }
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        client_config.store,
+        spec,
+        log,
+    )
+    .map(Arc::new)
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    let bans =
+        load_bans(db.clone()).map_err(|e| format!("Unable to read the ban list: {:?}", e))?;
+
+    Ok((db, bans))
+}
+
+/// Parse the `--peer-id` or `--ip` to ban or unban.
+fn parse_ban_request(cli_args: &ArgMatches) -> Result<BanRequest, String> {
+    Ok(BanRequest {
+        peer_id: cli_args.value_of("peer-id").map(String::from),
+        ip: clap_utils::parse_optional(cli_args, "ip")?,
+    })
+}
+
+/// List, add or remove bans, either via the running beacon node at `--beacon-node` or directly
+/// in the database described by `client_config`.
+pub fn manage_bans<E: EthSpec>(
+    client_config: ClientConfig,
+    cli_args: &ArgMatches,
+    env: &mut Environment<E>,
+) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let (command, sub_matches) = match cli_args.subcommand() {
+        (command, Some(sub_matches)) => (command, sub_matches),
+        (unknown, None) => {
+            return Err(format!(
+                "{} is not a valid {} {} command. See --help.",
+                unknown, CMD, BANS_CMD
+            ))
+        }
+    };
+
+    if let Some(url) = sub_matches.value_of("beacon-node") {
+        let node = RemoteBeaconNode::<E>::new(url.to_string())?;
+        let lighthouse = node.http.lighthouse();
+        return match command {
+            BANS_LIST_CMD => {
+                let bans = env
+                    .runtime()
+                    .block_on(lighthouse.bans())
+                    .map_err(|e| format!("Unable to list bans: {:?}", e))?;
+                print_ban_list(&bans)
+            }
+            BANS_ADD_CMD => {
+                let request = parse_ban_request(sub_matches)?;
+                env.runtime()
+                    .block_on(lighthouse.add_ban(request))
+                    .map_err(|e| format!("Unable to add ban: {:?}", e))?;
+                info!(log, "Ban added"; "beacon_node" => url);
+                Ok(())
+            }
+            BANS_REMOVE_CMD => {
+                let request = parse_ban_request(sub_matches)?;
+                env.runtime()
+                    .block_on(lighthouse.remove_ban(request))
+                    .map_err(|e| format!("Unable to remove ban: {:?}", e))?;
+                info!(log, "Ban removed"; "beacon_node" => url);
+                Ok(())
+            }
+            unknown => Err(format!(
+                "{} is not a valid {} {} command. See --help.",
+                unknown, CMD, BANS_CMD
+            )),
+        };
+    }
+
+    let (db, mut bans) = open_ban_list(client_config, env)?;
+
+    if command == BANS_LIST_CMD {
+        return print_ban_list(&BanList {
+            peers: bans
+                .peers
+                .iter()
+                .map(|peer_id| peer_id.to_string())
+                .collect(),
+            ips: bans.ips,
+        });
+    }
+
+    let request = parse_ban_request(sub_matches)?;
+    let peer_id = request
+        .peer_id
+        .as_ref()
+        .map(|peer_id| {
+            peer_id
+                .parse::<PeerId>()
+                .map_err(|e| format!("Invalid peer id {}: {:?}", peer_id, e))
+        })
+        .transpose()?;
+
+    match command {
+        BANS_ADD_CMD => {
+            if let Some(peer_id) = peer_id {
+                if !bans.peers.contains(&peer_id) {
+                    bans.peers.push(peer_id);
+                }
+            }
+            if let Some(ip) = request.ip {
+                if !bans.ips.contains(&ip) {
+                    bans.ips.push(ip);
+                }
+            }
+        }
+        BANS_REMOVE_CMD => {
+            let (peers, ips) = (bans.peers.len(), bans.ips.len());
+            bans.peers.retain(|p| Some(p) != peer_id.as_ref());
+            bans.ips.retain(|ip| Some(*ip) != request.ip);
+            if bans.peers.len() == peers && bans.ips.len() == ips {
+                return Err("The peer or IP address is not banned".to_string());
+            }
+        }
+        unknown => {
+            return Err(format!(
+                "{} is not a valid {} {} command. See --help.",
+                unknown, CMD, BANS_CMD
+            ))
+        }
+    }
+
+    persist_bans(db, &bans).map_err(|e| format!("Unable to write the ban list: {:?}", e))?;
+    info!(
+        log,
+        "Ban list updated";
+        "banned_peers" => bans.peers.len(),
+        "banned_ips" => bans.ips.len(),
+    );
+
+    Ok(())
+}
+
+fn print_ban_list(bans: &BanList) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(bans)
+        .map_err(|e| format!("Unable to serialize the ban list: {:?}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Run the database manager, returning an error if the operation did not succeed.
+pub fn run<E: EthSpec>(cli_args: &ArgMatches<'_>, mut env: Environment<E>) -> Result<(), String> {
+    let client_config = parse_client_config::<E>(cli_args)?;
+
+    match cli_args.subcommand() {
+        (COMPACT_CMD, Some(_)) => compact_db(client_config, &mut env),
+        (MIGRATE_BACKEND_CMD, Some(sub_matches)) => {
+            let to = clap_utils::parse_required(sub_matches, "to")?;
+            migrate_db_backend(client_config, to, &mut env)
+        }
+        (MIGRATE_SLASHER_BACKEND_CMD, Some(sub_matches)) => {
+            migrate_slasher_backend(client_config, sub_matches, &mut env)
+        }
+        (IMPORT_SLASHER_ATTESTATIONS_CMD, Some(sub_matches)) => {
+            import_slasher_attestations(client_config, sub_matches, &mut env)
+        }
+        (INSPECT_CMD, Some(sub_matches)) => inspect_db(client_config, sub_matches, &mut env),
+        (PURGE_CMD, Some(sub_matches)) => purge_db(client_config, sub_matches, &mut env),
+        (RECONSTRUCT_STATES_CMD, Some(sub_matches)) => {
+            reconstruct_states(client_config, sub_matches, &mut env)
+        }
+        (SNAPSHOT_CMD, Some(sub_matches)) => match sub_matches.subcommand() {
+            (SNAPSHOT_EXPORT_CMD, Some(export_matches)) => {
+                export_db_snapshot(client_config, export_matches, &mut env)
+            }
+            (SNAPSHOT_IMPORT_CMD, Some(import_matches)) => {
+                import_db_snapshot(client_config, import_matches, &mut env)
+            }
+            (unknown, _) => Err(format!(
+                "{} is not a valid {} {} command. See --help.",
+                unknown, CMD, SNAPSHOT_CMD
+            )),
+        },
+        (BANS_CMD, Some(sub_matches)) => manage_bans(client_config, sub_matches, &mut env),
+        (unknown, _) => Err(format!(
+            "{} is not a valid {} command. See --help.",
+            unknown, CMD
+        )),
+    }
+}
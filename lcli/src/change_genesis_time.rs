@@ -31,6 +31,9 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
 
     state.genesis_time = genesis_time;
 
+    // The genesis time is part of the state, so nodes will compute a new genesis state root.
+    info!("New genesis state root: {:?}", state.canonical_root());
+
     let mut file = File::create(path).map_err(|e| format!("Unable to create file: {}", e))?;
 
     file.write_all(&state.as_ssz_bytes())
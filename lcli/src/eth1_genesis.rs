@@ -1,6 +1,6 @@
 use clap::ArgMatches;
 use environment::Environment;
-use eth2_testnet_config::Eth2TestnetConfig;
+use eth2_testnet_config::{Eth2TestnetConfig, GENESIS_STATE_FILE};
 use genesis::{Eth1Config, Eth1GenesisService};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -40,7 +40,7 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches<'_>) -> Res
         })?;
 
     let mut config = Eth1Config::default();
-    config.endpoint = endpoint.to_string();
+    config.endpoints = vec![endpoint.to_string()];
     config.deposit_contract_address = eth2_testnet_config.deposit_contract_address.clone();
     config.deposit_contract_deploy_block = eth2_testnet_config.deposit_contract_deploy_block;
     config.lowest_cached_block_number = eth2_testnet_config.deposit_contract_deploy_block;
@@ -49,19 +49,26 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches<'_>) -> Res
     let genesis_service =
         Eth1GenesisService::new(config, env.core_context().log().clone(), spec.clone());
 
-    env.runtime().block_on(async {
-        let _ = genesis_service
+    info!("Starting service to produce genesis BeaconState from eth1");
+    info!("Connecting to eth1 http endpoint: {}", endpoint);
+
+    let genesis_state = env.runtime().block_on(async {
+        genesis_service
             .wait_for_genesis_state(ETH1_GENESIS_UPDATE_INTERVAL, spec)
             .await
-            .map(move |genesis_state| {
-                eth2_testnet_config.genesis_state = Some(genesis_state);
-                eth2_testnet_config.force_write_to_file(testnet_dir)
-            })
-            .map_err(|e| format!("Failed to find genesis: {}", e))?;
+            .map_err(|e| format!("Failed to find genesis: {}", e))
+    })?;
+
+    info!(
+        "Found genesis state with {} validators and genesis time {}",
+        genesis_state.validators.len(),
+        genesis_state.genesis_time
+    );
+
+    eth2_testnet_config.genesis_state = Some(genesis_state);
+    eth2_testnet_config.force_write_to_file(testnet_dir.clone())?;
 
-        info!("Starting service to produce genesis BeaconState from eth1");
-        info!("Connecting to eth1 http endpoint: {}", endpoint);
+    info!("Wrote {} to {:?}", GENESIS_STATE_FILE, testnet_dir);
 
-        Ok(())
-    })
+    Ok(())
 }
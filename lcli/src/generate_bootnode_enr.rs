@@ -17,6 +17,11 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let output_dir: PathBuf = clap_utils::parse_required(matches, "output-dir")?;
     let genesis_fork_version: [u8; 4] =
         clap_utils::parse_ssz_required(matches, "genesis-fork-version")?;
+    // The genesis validators root is unknown before genesis, in which case the fork digest is
+    // computed using the zero hash.
+    let genesis_validators_root: Hash256 =
+        clap_utils::parse_ssz_optional(matches, "genesis-validators-root")?
+            .unwrap_or_else(Hash256::zero);
 
     if output_dir.exists() {
         return Err(format!(
@@ -33,7 +38,7 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let local_keypair = Keypair::generate_secp256k1();
     let enr_key = CombinedKey::from_libp2p(&local_keypair)?;
     let enr_fork_id = EnrForkId {
-        fork_digest: ChainSpec::compute_fork_digest(genesis_fork_version, Hash256::zero()),
+        fork_digest: ChainSpec::compute_fork_digest(genesis_fork_version, genesis_validators_root),
         next_fork_version: genesis_fork_version,
         next_fork_epoch: Epoch::max_value(), // FAR_FUTURE_EPOCH
     };
@@ -42,10 +47,12 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
 
     fs::create_dir_all(&output_dir).map_err(|e| format!("Unable to create output-dir: {:?}", e))?;
 
+    let enr_base64 = enr.to_base64();
+
     let mut enr_file = File::create(output_dir.join(ENR_FILENAME))
         .map_err(|e| format!("Unable to create {}: {:?}", ENR_FILENAME, e))?;
     enr_file
-        .write_all(&enr.to_base64().as_bytes())
+        .write_all(enr_base64.as_bytes())
         .map_err(|e| format!("Unable to write ENR to {}: {:?}", ENR_FILENAME, e))?;
 
     let secret_bytes = match local_keypair {
@@ -59,5 +66,9 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         .write_all(&secret_bytes)
         .map_err(|e| format!("Unable to write key to {}: {:?}", NETWORK_KEY_FILENAME, e))?;
 
+    info!("Node ID: {}", enr.node_id());
+    info!("Files written to {:?}", output_dir);
+    println!("{}", enr_base64);
+
     Ok(())
 }
@@ -1,12 +1,29 @@
+use account_utils::{
+    validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
+    ZeroizeString,
+};
 use clap::ArgMatches;
 use std::fs;
 use std::path::PathBuf;
-use validator_dir::Builder as ValidatorBuilder;
+use types::test_utils::generate_deterministic_keypair;
+use validator_dir::{
+    insecure_keys::INSECURE_PASSWORD, Builder as ValidatorBuilder, VOTING_KEYSTORE_FILE,
+};
 
 pub fn run(matches: &ArgMatches) -> Result<(), String> {
     let validator_count: usize = clap_utils::parse_required(matches, "count")?;
-    let validators_dir: PathBuf = clap_utils::parse_required(matches, "validators-dir")?;
-    let secrets_dir: PathBuf = clap_utils::parse_required(matches, "secrets-dir")?;
+    let first_index: usize = clap_utils::parse_required(matches, "first-index")?;
+    let plaintext_passwords = matches.is_present("plaintext-passwords");
+
+    let (validators_dir, secrets_dir) =
+        if let Some(base_dir) = clap_utils::parse_optional::<PathBuf>(matches, "base-dir")? {
+            (base_dir.join("validators"), base_dir.join("secrets"))
+        } else {
+            (
+                clap_utils::parse_required(matches, "validators-dir")?,
+                clap_utils::parse_required(matches, "secrets-dir")?,
+            )
+        };
 
     if !validators_dir.exists() {
         fs::create_dir_all(&validators_dir)
@@ -18,16 +35,46 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
             .map_err(|e| format!("Unable to create secrets dir: {:?}", e))?;
     }
 
-    for i in 0..validator_count {
-        println!("Validator {}/{}", i + 1, validator_count);
+    let mut defs = ValidatorDefinitions::open_or_create(&validators_dir)
+        .map_err(|e| format!("Unable to open validator definitions: {:?}", e))?;
+
+    for i in first_index..first_index + validator_count {
+        println!("Validator {}/{}", i - first_index + 1, validator_count);
 
-        ValidatorBuilder::new(validators_dir.clone(), secrets_dir.clone())
+        let validator_dir = ValidatorBuilder::new(validators_dir.clone(), secrets_dir.clone())
             .store_withdrawal_keystore(false)
             .insecure_voting_keypair(i)
             .map_err(|e| format!("Unable to generate keys: {:?}", e))?
             .build()
             .map_err(|e| format!("Unable to build validator: {:?}", e))?;
+
+        let voting_public_key = generate_deterministic_keypair(i).pk;
+
+        // Storing the password in the definition allows the secrets dir to be discarded.
+        let (voting_keystore_password_path, voting_keystore_password) = if plaintext_passwords {
+            let password = String::from_utf8(INSECURE_PASSWORD.to_vec())
+                .map_err(|e| format!("Insecure password is not utf-8: {:?}", e))?;
+            (None, Some(ZeroizeString::from(password)))
+        } else {
+            (
+                Some(secrets_dir.join(voting_public_key.to_hex_string())),
+                None,
+            )
+        };
+
+        defs.push(ValidatorDefinition {
+            enabled: true,
+            voting_public_key,
+            signing_definition: SigningDefinition::LocalKeystore {
+                voting_keystore_path: validator_dir.dir().join(VOTING_KEYSTORE_FILE),
+                voting_keystore_password_path,
+                voting_keystore_password,
+            },
+        });
     }
 
+    defs.save(&validators_dir)
+        .map_err(|e| format!("Unable to save validator definitions: {:?}", e))?;
+
     Ok(())
 }
@@ -1,11 +1,11 @@
 use clap::ArgMatches;
 use clap_utils::parse_ssz_optional;
 use environment::Environment;
-use eth2_testnet_config::Eth2TestnetConfig;
+use eth2_testnet_config::{Eth2TestnetConfig, GENESIS_STATE_FILE};
 use genesis::interop_genesis_state;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
-use types::{test_utils::generate_deterministic_keypairs, EthSpec};
+use types::{test_utils::generate_deterministic_keypairs, EthSpec, YamlConfig};
 
 pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
     let validator_count = matches
@@ -52,13 +52,22 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
 
     if let Some(v) = parse_ssz_optional(matches, "genesis-fork-version")? {
         spec.genesis_fork_version = v;
+        // Nodes using the testnet dir must agree with the fork version of the genesis state.
+        eth2_testnet_config.yaml_config = Some(YamlConfig::from_spec::<T>(&spec));
     }
 
     let keypairs = generate_deterministic_keypairs(validator_count);
     let genesis_state = interop_genesis_state(&keypairs, genesis_time, &spec)?;
 
+    info!(
+        "Created interop genesis state with {} validators and genesis time {}",
+        validator_count, genesis_time
+    );
+
     eth2_testnet_config.genesis_state = Some(genesis_state);
-    eth2_testnet_config.force_write_to_file(testnet_dir)?;
+    eth2_testnet_config.force_write_to_file(testnet_dir.clone())?;
+
+    info!("Wrote {} to {:?}", GENESIS_STATE_FILE, testnet_dir);
 
     Ok(())
 }
@@ -9,6 +9,8 @@ mod insecure_validators;
 mod interop_genesis;
 mod new_testnet;
 mod parse_hex;
+mod parse_ssz;
+mod patch_config;
 mod refund_deposit_contract;
 mod skip_slots;
 mod transition_blocks;
@@ -81,23 +83,46 @@ fn main() {
         )
         .subcommand(
             SubCommand::with_name("skip-slots")
-                .about("Performs a state transition from some state across some number of skip slots")
+                .about(
+                    "Performs a state transition from some state across some number of skip \
+                    slots. The pre-state is read from a SSZ file, or downloaded from a beacon node.",
+                )
                 .arg(
                     Arg::with_name("pre-state")
+                        .long("pre-state")
                         .value_name("BEACON_STATE")
                         .takes_value(true)
-                        .required(true)
+                        .required_unless("beacon-url")
+                        .conflicts_with("beacon-url")
                         .help("Path to a SSZ file of the pre-state."),
                 )
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .requires("state-slot")
+                        .help("HTTP API address of a beacon node from which to download the pre-state."),
+                )
+                .arg(
+                    Arg::with_name("state-slot")
+                        .long("state-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .requires("beacon-url")
+                        .help("The slot of the state to download from the beacon node."),
+                )
                 .arg(
                     Arg::with_name("slots")
+                        .long("slots")
                         .value_name("SLOT_COUNT")
                         .takes_value(true)
                         .required(true)
-                        .help("Number of slots to skip before outputting a state.."),
+                        .help("Number of slots to skip before outputting a state."),
                 )
                 .arg(
                     Arg::with_name("output")
+                        .long("output")
                         .value_name("SSZ_FILE")
                         .takes_value(true)
                         .required(true)
@@ -107,23 +132,65 @@ fn main() {
         )
         .subcommand(
             SubCommand::with_name("transition-blocks")
-                .about("Performs a state transition given a pre-state and block")
+                .about(
+                    "Performs a state transition given a pre-state and block. These are read from \
+                    SSZ files, or downloaded from a beacon node.",
+                )
                 .arg(
                     Arg::with_name("pre-state")
+                        .long("pre-state")
                         .value_name("BEACON_STATE")
                         .takes_value(true)
-                        .required(true)
+                        .required_unless("beacon-url")
+                        .conflicts_with("beacon-url")
+                        .requires("block")
                         .help("Path to a SSZ file of the pre-state."),
                 )
                 .arg(
                     Arg::with_name("block")
+                        .long("block")
                         .value_name("BEACON_BLOCK")
                         .takes_value(true)
-                        .required(true)
+                        .required_unless("beacon-url")
+                        .conflicts_with("beacon-url")
+                        .requires("pre-state")
                         .help("Path to a SSZ file of the block to apply to pre-state."),
                 )
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .requires("block-slot")
+                        .help(
+                            "HTTP API address of a beacon node from which to download the block \
+                            and the post-state of its parent.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("block-slot")
+                        .long("block-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .requires("beacon-url")
+                        .help("The slot of the block to download from the beacon node."),
+                )
+                .arg(
+                    Arg::with_name("no-signature-verification")
+                        .long("no-signature-verification")
+                        .help("Apply the block without verifying any of its signatures."),
+                )
+                .arg(
+                    Arg::with_name("inconsistent-state-roots")
+                        .long("inconsistent-state-roots")
+                        .help(
+                            "Skip computing the state roots of intermediate states. This is \
+                            faster, but the post-state will not match the specification.",
+                        ),
+                )
                 .arg(
                     Arg::with_name("output")
+                        .long("output")
                         .value_name("SSZ_FILE")
                         .takes_value(true)
                         .required(true)
@@ -150,6 +217,35 @@ fn main() {
                         .help("SSZ encoded as 0x-prefixed hex"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("pretty-ssz")
+                .about("Parses a file of SSZ bytes and prints it as JSON or YAML")
+                .arg(
+                    Arg::with_name("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(parse_ssz::TYPES)
+                        .help("The schema of the supplied SSZ."),
+                )
+                .arg(
+                    Arg::with_name("ssz-file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("A file of SSZ bytes"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(parse_ssz::FORMATS)
+                        .default_value("json")
+                        .help("The output format."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("deploy-deposit-contract")
                 .about(
@@ -219,7 +315,8 @@ fn main() {
         .subcommand(
             SubCommand::with_name("eth1-genesis")
                 .about(
-                    "Listens to the eth1 chain and finds the genesis beacon state",
+                    "Listens to the eth1 chain and finds the genesis beacon state, which is \
+                    written to the genesis.ssz file of the testnet dir.",
                 )
                 .arg(
                     Arg::with_name("eth1-endpoint")
@@ -234,7 +331,8 @@ fn main() {
         .subcommand(
             SubCommand::with_name("interop-genesis")
                 .about(
-                    "Produces an interop-compatible genesis state using deterministic keypairs",
+                    "Produces an interop-compatible genesis state using deterministic keypairs, \
+                    which is written to the genesis.ssz file of the testnet dir.",
                 )
                 .arg(
                     Arg::with_name("validator-count")
@@ -258,14 +356,15 @@ fn main() {
                         .long("genesis-fork-version")
                         .value_name("HEX")
                         .takes_value(true)
-                        .help("Used to avoid reply attacks between testnets. Recommended to set to
+                        .help("Used to avoid replay attacks between testnets. Recommended to set to
                               non-default."),
                 )
         )
         .subcommand(
             SubCommand::with_name("change-genesis-time")
                 .about(
-                    "Loads a file with an SSZ-encoded BeaconState and modifies the genesis time.",
+                    "Loads a file with an SSZ-encoded BeaconState and modifies the genesis time. \
+                    The validators are unchanged, but the genesis state root will differ.",
                 )
                 .arg(
                     Arg::with_name("ssz-state")
@@ -297,77 +396,37 @@ fn main() {
                         .takes_value(false)
                         .help("Overwrites any previous testnet configurations"),
                 )
+                .args(&new_testnet::spec_config_args())
                 .arg(
-                    Arg::with_name("min-genesis-time")
-                        .long("min-genesis-time")
-                        .value_name("UNIX_SECONDS")
-                        .takes_value(true)
-                        .help("The minimum permitted genesis time. For non-eth1 testnets will be
-                              the genesis time. Defaults to now."),
-                )
-                .arg(
-                    Arg::with_name("min-genesis-active-validator-count")
-                        .long("min-genesis-active-validator-count")
-                        .value_name("INTEGER")
-                        .takes_value(true)
-                        .help("The number of validators required to trigger eth2 genesis."),
-                )
-                .arg(
-                    Arg::with_name("genesis-delay")
-                        .long("genesis-delay")
-                        .value_name("SECONDS")
-                        .takes_value(true)
-                        .help("The delay between sufficient eth1 deposits and eth2 genesis."),
-                )
-                .arg(
-                    Arg::with_name("min-deposit-amount")
-                        .long("min-deposit-amount")
-                        .value_name("GWEI")
-                        .takes_value(true)
-                        .help("The minimum permitted deposit amount."),
-                )
-                .arg(
-                    Arg::with_name("max-effective-balance")
-                        .long("max-effective-balance")
-                        .value_name("GWEI")
-                        .takes_value(true)
-                        .help("The amount required to become a validator."),
-                )
-                .arg(
-                    Arg::with_name("effective-balance-increment")
-                        .long("effective-balance-increment")
-                        .value_name("GWEI")
-                        .takes_value(true)
-                        .help("The steps in effective balance calculation."),
-                )
-                .arg(
-                    Arg::with_name("ejection-balance")
-                        .long("ejection-balance")
-                        .value_name("GWEI")
+                    Arg::with_name("deposit-contract-address")
+                        .long("deposit-contract-address")
+                        .value_name("ETH1_ADDRESS")
                         .takes_value(true)
-                        .help("The balance at which a validator gets ejected."),
+                        .required(true)
+                        .help("The address of the deposit contract."),
                 )
                 .arg(
-                    Arg::with_name("eth1-follow-distance")
-                        .long("eth1-follow-distance")
-                        .value_name("ETH1_BLOCKS")
+                    Arg::with_name("deposit-contract-deploy-block")
+                        .long("deposit-contract-deploy-block")
+                        .value_name("ETH1_BLOCK_NUMBER")
                         .takes_value(true)
-                        .help("The distance to follow behind the eth1 chain head."),
+                        .default_value("0")
+                        .help("The block the deposit contract was deployed. Setting this is a huge
+                              optimization for nodes, please do it."),
                 )
-                .arg(
-                    Arg::with_name("genesis-fork-version")
-                        .long("genesis-fork-version")
-                        .value_name("HEX")
-                        .takes_value(true)
-                        .help("Used to avoid reply attacks between testnets. Recommended to set to
-                              non-default."),
+        )
+        .subcommand(
+            SubCommand::with_name("patch-config")
+                .about(
+                    "Updates the config of an existing testnet directory. Only the values of the \
+                    supplied flags are changed; the genesis state and boot nodes are retained.",
                 )
+                .args(&new_testnet::spec_config_args())
                 .arg(
                     Arg::with_name("deposit-contract-address")
                         .long("deposit-contract-address")
                         .value_name("ETH1_ADDRESS")
                         .takes_value(true)
-                        .required(true)
                         .help("The address of the deposit contract."),
                 )
                 .arg(
@@ -375,9 +434,7 @@ fn main() {
                         .long("deposit-contract-deploy-block")
                         .value_name("ETH1_BLOCK_NUMBER")
                         .takes_value(true)
-                        .default_value("0")
-                        .help("The block the deposit contract was deployed. Setting this is a huge
-                              optimization for nodes, please do it."),
+                        .help("The block the deposit contract was deployed."),
                 )
         )
         .subcommand(
@@ -406,7 +463,8 @@ fn main() {
         .subcommand(
             SubCommand::with_name("generate-bootnode-enr")
                 .about(
-                    "Generates an ENR address to be used as a pre-genesis boot node.",
+                    "Generates an ENR address and network key to be used by a boot node. The \
+                    ENR is also printed to stdout.",
                 )
                 .arg(
                     Arg::with_name("ip")
@@ -446,27 +504,56 @@ fn main() {
                         .value_name("HEX")
                         .takes_value(true)
                         .required(true)
-                        .help("Used to avoid reply attacks between testnets. Recommended to set to
+                        .help("Used to avoid replay attacks between testnets. Recommended to set to
                               non-default."),
                 )
+                .arg(
+                    Arg::with_name("genesis-validators-root")
+                        .long("genesis-validators-root")
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .help("The genesis validators root of the network, used to compute the fork \
+                              digest. Only required for networks which have already reached \
+                              genesis."),
+                )
         )
         .subcommand(
             SubCommand::with_name("insecure-validators")
                 .about(
-                    "Produces validator directories with INSECURE, deterministic keypairs.",
+                    "Produces validator directories with INSECURE, deterministic keypairs, along \
+                    with the validator definitions required by the validator client.",
                 )
                 .arg(
                     Arg::with_name("count")
                         .long("count")
                         .value_name("COUNT")
                         .takes_value(true)
-                        .help("Produces validators in the range of 0..count."),
+                        .required(true)
+                        .help("The number of validators to produce."),
+                )
+                .arg(
+                    Arg::with_name("first-index")
+                        .long("first-index")
+                        .value_name("INDEX")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Produces validators in the range of first-index..first-index + count."),
+                )
+                .arg(
+                    Arg::with_name("base-dir")
+                        .long("base-dir")
+                        .value_name("BASE_DIR")
+                        .takes_value(true)
+                        .conflicts_with_all(&["validators-dir", "secrets-dir"])
+                        .help("Stores validators and secrets in the validators and secrets \
+                              directories within this directory."),
                 )
                 .arg(
                     Arg::with_name("validators-dir")
                         .long("validators-dir")
                         .value_name("VALIDATOR_DIR")
                         .takes_value(true)
+                        .required_unless("base-dir")
                         .help("The directory for storing validators."),
                 )
                 .arg(
@@ -474,8 +561,15 @@ fn main() {
                         .long("secrets-dir")
                         .value_name("SECRETS_DIR")
                         .takes_value(true)
+                        .required_unless("base-dir")
                         .help("The directory for storing secrets."),
                 )
+                .arg(
+                    Arg::with_name("plaintext-passwords")
+                        .long("plaintext-passwords")
+                        .help("Stores the keystore passwords in the validator definitions, so the \
+                              secrets directory is not required by the validator client."),
+                )
         )
         .get_matches();
 
@@ -553,14 +647,17 @@ fn run<T: EthSpec>(
             info!("Genesis state YAML file created. Exiting successfully.");
             Ok(())
         }
-        ("transition-blocks", Some(matches)) => run_transition_blocks::<T>(matches)
+        ("transition-blocks", Some(matches)) => run_transition_blocks::<T>(env, matches)
             .map_err(|e| format!("Failed to transition blocks: {}", e)),
         ("skip-slots", Some(matches)) => {
-            skip_slots::run::<T>(matches).map_err(|e| format!("Failed to skip slots: {}", e))
+            skip_slots::run::<T>(env, matches).map_err(|e| format!("Failed to skip slots: {}", e))
         }
         ("pretty-hex", Some(matches)) => {
             run_parse_hex::<T>(matches).map_err(|e| format!("Failed to pretty print hex: {}", e))
         }
+        ("pretty-ssz", Some(matches)) => {
+            parse_ssz::run::<T>(matches).map_err(|e| format!("Failed to pretty print SSZ: {}", e))
+        }
         ("deploy-deposit-contract", Some(matches)) => {
             deploy_deposit_contract::run::<T>(env, matches)
                 .map_err(|e| format!("Failed to run deploy-deposit-contract command: {}", e))
@@ -577,6 +674,8 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run change-genesis-time command: {}", e)),
         ("new-testnet", Some(matches)) => new_testnet::run::<T>(matches)
             .map_err(|e| format!("Failed to run new_testnet command: {}", e)),
+        ("patch-config", Some(matches)) => patch_config::run::<T>(matches)
+            .map_err(|e| format!("Failed to run patch-config command: {}", e)),
         ("check-deposit-data", Some(matches)) => check_deposit_data::run::<T>(matches)
             .map_err(|e| format!("Failed to run check-deposit-data command: {}", e)),
         ("generate-bootnode-enr", Some(matches)) => generate_bootnode_enr::run::<T>(matches)
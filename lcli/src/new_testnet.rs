@@ -1,10 +1,10 @@
-use clap::ArgMatches;
+use clap::{Arg, ArgMatches};
 use clap_utils::{
     parse_optional, parse_path_with_default_in_home_dir, parse_required, parse_ssz_optional,
 };
 use eth2_testnet_config::Eth2TestnetConfig;
 use std::path::PathBuf;
-use types::{Address, EthSpec, YamlConfig};
+use types::{Address, ChainSpec, EthSpec, YamlConfig};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let testnet_dir_path = parse_path_with_default_in_home_dir(
@@ -25,8 +25,79 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     }
 
     let mut spec = T::default_spec();
+    update_spec_from_matches(&mut spec, matches)?;
 
-    // Update the spec value if the flag was defined. Otherwise, leave it as the default.
+    let testnet: Eth2TestnetConfig<T> = Eth2TestnetConfig {
+        deposit_contract_address: format!("{:?}", deposit_contract_address),
+        deposit_contract_deploy_block,
+        boot_enr: Some(vec![]),
+        genesis_state: None,
+        yaml_config: Some(YamlConfig::from_spec::<T>(&spec)),
+    };
+
+    testnet.write_to_file(testnet_dir_path, overwrite_files)
+}
+
+/// Returns the flags read by `update_spec_from_matches`, shared by `new-testnet` and
+/// `patch-config`.
+pub fn spec_config_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("min-genesis-time")
+            .long("min-genesis-time")
+            .value_name("UNIX_SECONDS")
+            .takes_value(true)
+            .help(
+                "The minimum permitted genesis time. For non-eth1 testnets will be the genesis \
+                time.",
+            ),
+        Arg::with_name("min-genesis-active-validator-count")
+            .long("min-genesis-active-validator-count")
+            .value_name("INTEGER")
+            .takes_value(true)
+            .help("The number of validators required to trigger eth2 genesis."),
+        Arg::with_name("genesis-delay")
+            .long("genesis-delay")
+            .value_name("SECONDS")
+            .takes_value(true)
+            .help("The delay between sufficient eth1 deposits and eth2 genesis."),
+        Arg::with_name("min-deposit-amount")
+            .long("min-deposit-amount")
+            .value_name("GWEI")
+            .takes_value(true)
+            .help("The minimum permitted deposit amount."),
+        Arg::with_name("max-effective-balance")
+            .long("max-effective-balance")
+            .value_name("GWEI")
+            .takes_value(true)
+            .help("The amount required to become a validator."),
+        Arg::with_name("effective-balance-increment")
+            .long("effective-balance-increment")
+            .value_name("GWEI")
+            .takes_value(true)
+            .help("The steps in effective balance calculation."),
+        Arg::with_name("ejection-balance")
+            .long("ejection-balance")
+            .value_name("GWEI")
+            .takes_value(true)
+            .help("The balance at which a validator gets ejected."),
+        Arg::with_name("eth1-follow-distance")
+            .long("eth1-follow-distance")
+            .value_name("ETH1_BLOCKS")
+            .takes_value(true)
+            .help("The distance to follow behind the eth1 chain head."),
+        Arg::with_name("genesis-fork-version")
+            .long("genesis-fork-version")
+            .value_name("HEX")
+            .takes_value(true)
+            .help(
+                "Used to avoid replay attacks between testnets. Recommended to set to non-default.",
+            ),
+    ]
+}
+
+/// Updates each value of `spec` which has a flag in `matches`. Values without a flag are left
+/// unchanged.
+pub fn update_spec_from_matches(spec: &mut ChainSpec, matches: &ArgMatches) -> Result<(), String> {
     macro_rules! maybe_update {
         ($flag: tt, $var: ident) => {
             if let Some(val) = parse_optional(matches, $flag)? {
@@ -51,13 +122,5 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         spec.genesis_fork_version = v;
     }
 
-    let testnet: Eth2TestnetConfig<T> = Eth2TestnetConfig {
-        deposit_contract_address: format!("{:?}", deposit_contract_address),
-        deposit_contract_deploy_block,
-        boot_enr: Some(vec![]),
-        genesis_state: None,
-        yaml_config: Some(YamlConfig::from_spec::<T>(&spec)),
-    };
-
-    testnet.write_to_file(testnet_dir_path, overwrite_files)
+    Ok(())
 }
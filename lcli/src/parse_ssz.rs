@@ -1,17 +1,37 @@
-use crate::helpers::parse_path;
 use clap::ArgMatches;
 use serde::Serialize;
 use ssz::Decode;
 use std::fs::File;
 use std::io::Read;
-use types::{EthSpec, SignedBeaconBlock};
+use std::path::PathBuf;
+use types::{
+    Attestation, AttesterSlashing, BeaconBlock, BeaconState, Deposit, EthSpec, IndexedAttestation,
+    ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit,
+};
+
+/// The names of the types which may be decoded by `pretty-ssz`.
+pub const TYPES: &[&str] = &[
+    "SignedBeaconBlock",
+    "BeaconBlock",
+    "BeaconState",
+    "Attestation",
+    "IndexedAttestation",
+    "SignedAggregateAndProof",
+    "AttesterSlashing",
+    "ProposerSlashing",
+    "SignedVoluntaryExit",
+    "Deposit",
+];
+
+/// The output formats supported by `pretty-ssz`.
+pub const FORMATS: &[&str] = &["json", "yaml"];
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
-    let type_str = matches
-        .value_of("type")
-        .ok_or_else(|| "No type supplied".to_string())?;
-    let path = parse_path(matches, "path")?;
+    let type_str: String = clap_utils::parse_required(matches, "type")?;
+    let path: PathBuf = clap_utils::parse_required(matches, "ssz-file")?;
+    let format: String = clap_utils::parse_required(matches, "format")?;
 
+    info!("Using {} spec", T::spec_name());
     info!("Type: {:?}", type_str);
 
     let mut bytes = vec![];
@@ -19,22 +39,38 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     file.read_to_end(&mut bytes)
         .map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
 
-    match type_str {
-        "SignedBeaconBlock" => decode_and_print::<SignedBeaconBlock<T>>(&bytes)?,
+    let format = format.as_str();
+    match type_str.as_str() {
+        "SignedBeaconBlock" => decode_and_print::<SignedBeaconBlock<T>>(&bytes, format)?,
+        "BeaconBlock" => decode_and_print::<BeaconBlock<T>>(&bytes, format)?,
+        "BeaconState" => decode_and_print::<BeaconState<T>>(&bytes, format)?,
+        "Attestation" => decode_and_print::<Attestation<T>>(&bytes, format)?,
+        "IndexedAttestation" => decode_and_print::<IndexedAttestation<T>>(&bytes, format)?,
+        "SignedAggregateAndProof" => {
+            decode_and_print::<SignedAggregateAndProof<T>>(&bytes, format)?
+        }
+        "AttesterSlashing" => decode_and_print::<AttesterSlashing<T>>(&bytes, format)?,
+        "ProposerSlashing" => decode_and_print::<ProposerSlashing>(&bytes, format)?,
+        "SignedVoluntaryExit" => decode_and_print::<SignedVoluntaryExit>(&bytes, format)?,
+        "Deposit" => decode_and_print::<Deposit>(&bytes, format)?,
         other => return Err(format!("Unknown type: {}", other)),
     };
 
     Ok(())
 }
 
-fn decode_and_print<T: Decode + Serialize>(bytes: &[u8]) -> Result<(), String> {
+fn decode_and_print<T: Decode + Serialize>(bytes: &[u8], format: &str) -> Result<(), String> {
     let item = T::from_ssz_bytes(&bytes).map_err(|e| format!("Ssz decode failed: {:?}", e))?;
 
-    println!(
-        "{}",
-        serde_yaml::to_string(&item)
-            .map_err(|e| format!("Unable to write object to YAML: {:?}", e))?
-    );
+    let output = match format {
+        "json" => serde_json::to_string_pretty(&item)
+            .map_err(|e| format!("Unable to write object to JSON: {:?}", e))?,
+        "yaml" => serde_yaml::to_string(&item)
+            .map_err(|e| format!("Unable to write object to YAML: {:?}", e))?,
+        other => return Err(format!("Unknown output format: {}", other)),
+    };
+
+    println!("{}", output);
 
     Ok(())
 }
@@ -0,0 +1,60 @@
+use crate::new_testnet::update_spec_from_matches;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_path_with_default_in_home_dir};
+use eth2_testnet_config::Eth2TestnetConfig;
+use std::path::PathBuf;
+use types::{Address, EthSpec, YamlConfig};
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let testnet_dir_path = parse_path_with_default_in_home_dir(
+        matches,
+        "testnet-dir",
+        PathBuf::from(".lighthouse/testnet"),
+    )?;
+
+    let mut testnet: Eth2TestnetConfig<T> = Eth2TestnetConfig::load(testnet_dir_path.clone())?;
+
+    let mut spec = testnet
+        .yaml_config
+        .as_ref()
+        .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?
+        .apply_to_chain_spec::<T>(&T::default_spec())
+        .ok_or_else(|| {
+            format!(
+                "The loaded config is not compatible with the {} spec",
+                T::spec_name()
+            )
+        })?;
+
+    let genesis_fork_version = spec.genesis_fork_version;
+    update_spec_from_matches(&mut spec, matches)?;
+
+    // The fork version is baked into the genesis state, so changing it would leave the testnet
+    // dir unusable.
+    if testnet.genesis_state.is_some() && spec.genesis_fork_version != genesis_fork_version {
+        return Err(format!(
+            "Unable to change the genesis fork version of {:?} as it contains a genesis state. \
+             Remove the genesis state or create a new testnet dir instead",
+            testnet_dir_path
+        ));
+    }
+
+    testnet.yaml_config = Some(YamlConfig::from_spec::<T>(&spec));
+
+    if let Some(address) = parse_optional::<Address>(matches, "deposit-contract-address")? {
+        testnet.deposit_contract_address = format!("{:?}", address);
+    }
+
+    if let Some(block) = parse_optional(matches, "deposit-contract-deploy-block")? {
+        testnet.deposit_contract_deploy_block = block;
+    }
+
+    testnet
+        .validate()
+        .map_err(|e| format!("The patched testnet dir is invalid: {}", e))?;
+    testnet.force_write_to_file(testnet_dir_path.clone())?;
+
+    info!("Updated testnet dir at {:?}", testnet_dir_path);
+
+    Ok(())
+}
@@ -1,36 +1,42 @@
-use crate::transition_blocks::load_from_ssz;
+use crate::transition_blocks::{load_from_ssz, remote_beacon_node};
 use clap::ArgMatches;
+use environment::Environment;
 use ssz::Encode;
 use state_processing::per_slot_processing;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
-use types::{BeaconState, EthSpec};
-
-pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
-    let pre_state_path = matches
-        .value_of("pre-state")
-        .ok_or_else(|| "No pre-state file supplied".to_string())?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse pre-state path: {}", e))?;
-
-    let slots = matches
-        .value_of("slots")
-        .ok_or_else(|| "No slots supplied".to_string())?
-        .parse::<usize>()
-        .map_err(|e| format!("Failed to parse slots: {}", e))?;
-
-    let output_path = matches
-        .value_of("output")
-        .ok_or_else(|| "No output file supplied".to_string())?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse output path: {}", e))?;
+use types::{BeaconState, EthSpec, Slot};
+
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let slots: usize = clap_utils::parse_required(matches, "slots")?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, "output")?;
+    let beacon_url: Option<String> = clap_utils::parse_optional(matches, "beacon-url")?;
 
     info!("Using {} spec", T::spec_name());
-    info!("Pre-state path: {:?}", pre_state_path);
     info!("Slots: {:?}", slots);
 
-    let mut state: BeaconState<T> = load_from_ssz(pre_state_path)?;
+    let (mut state, mut state_root) = if let Some(beacon_url) = beacon_url {
+        let state_slot = Slot::new(clap_utils::parse_required(matches, "state-slot")?);
+
+        info!("Beacon node: {}", beacon_url);
+        info!("State slot: {}", state_slot);
+
+        let beacon_node = remote_beacon_node::<T>(beacon_url)?;
+        let (state, state_root) = env
+            .runtime()
+            .block_on(beacon_node.http.beacon().get_state_by_slot(state_slot))
+            .map_err(|e| format!("Unable to get state at slot {}: {:?}", state_slot, e))?;
+
+        (state, Some(state_root))
+    } else {
+        let pre_state_path: PathBuf = clap_utils::parse_required(matches, "pre-state")?;
+
+        info!("Pre-state path: {:?}", pre_state_path);
+
+        let state: BeaconState<T> = load_from_ssz(pre_state_path)?;
+        (state, None)
+    };
 
     let spec = &T::default_spec();
 
@@ -38,9 +44,10 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         .build_all_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
 
-    // Transition the parent state to the block slot.
+    // Transition the parent state to the block slot. The root of the state downloaded from the
+    // beacon node is already known, which saves hashing it in the first slot.
     for i in 0..slots {
-        per_slot_processing(&mut state, None, spec)
+        per_slot_processing(&mut state, state_root.take(), spec)
             .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
     }
 
@@ -1,38 +1,60 @@
 use clap::ArgMatches;
+use environment::Environment;
+use remote_beacon_node::RemoteBeaconNode;
 use ssz::{Decode, Encode};
-use state_processing::{per_block_processing, per_slot_processing, BlockSignatureStrategy};
+use state_processing::{
+    per_block_processing, BlockReplayError, BlockReplayer, BlockSignatureStrategy,
+    StateRootStrategy,
+};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
-use types::{BeaconState, EthSpec, SignedBeaconBlock};
-
-pub fn run_transition_blocks<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
-    let pre_state_path = matches
-        .value_of("pre-state")
-        .ok_or_else(|| "No pre-state file supplied".to_string())?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse pre-state path: {}", e))?;
-
-    let block_path = matches
-        .value_of("block")
-        .ok_or_else(|| "No block file supplied".to_string())?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse block path: {}", e))?;
-
-    let output_path = matches
-        .value_of("output")
-        .ok_or_else(|| "No output file supplied".to_string())?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse output path: {}", e))?;
+use std::time::Duration;
+use types::{BeaconState, EthSpec, SignedBeaconBlock, Slot};
+
+/// States are large, so allow plenty of time to download them from the beacon node.
+const BEACON_NODE_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub fn run_transition_blocks<T: EthSpec>(
+    mut env: Environment<T>,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let output_path: PathBuf = clap_utils::parse_required(matches, "output")?;
+    let beacon_url: Option<String> = clap_utils::parse_optional(matches, "beacon-url")?;
+
+    let block_sig_strategy = if matches.is_present("no-signature-verification") {
+        BlockSignatureStrategy::NoVerification
+    } else {
+        BlockSignatureStrategy::VerifyIndividual
+    };
+    let state_root_strategy = if matches.is_present("inconsistent-state-roots") {
+        StateRootStrategy::Inconsistent
+    } else {
+        StateRootStrategy::Accurate
+    };
 
     info!("Using {} spec", T::spec_name());
-    info!("Pre-state path: {:?}", pre_state_path);
-    info!("Block path: {:?}", block_path);
 
-    let pre_state: BeaconState<T> = load_from_ssz(pre_state_path)?;
-    let block: SignedBeaconBlock<T> = load_from_ssz(block_path)?;
+    let (pre_state, block) = if let Some(beacon_url) = beacon_url {
+        let block_slot = Slot::new(clap_utils::parse_required(matches, "block-slot")?);
 
-    let post_state = do_transition(pre_state, block)?;
+        info!("Beacon node: {}", beacon_url);
+        info!("Block slot: {}", block_slot);
+
+        let beacon_node = remote_beacon_node::<T>(beacon_url)?;
+        env.runtime()
+            .block_on(fetch_block_and_pre_state(&beacon_node, block_slot))?
+    } else {
+        let pre_state_path: PathBuf = clap_utils::parse_required(matches, "pre-state")?;
+        let block_path: PathBuf = clap_utils::parse_required(matches, "block")?;
+
+        info!("Pre-state path: {:?}", pre_state_path);
+        info!("Block path: {:?}", block_path);
+
+        (load_from_ssz(pre_state_path)?, load_from_ssz(block_path)?)
+    };
+
+    let post_state = do_transition(pre_state, block, block_sig_strategy, state_root_strategy)?;
 
     let mut output_file =
         File::create(output_path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
@@ -44,36 +66,81 @@ pub fn run_transition_blocks<T: EthSpec>(matches: &ArgMatches) -> Result<(), Str
     Ok(())
 }
 
+/// Downloads the block at `block_slot` and the post-state of its parent from `beacon_node`.
+async fn fetch_block_and_pre_state<T: EthSpec>(
+    beacon_node: &RemoteBeaconNode<T>,
+    block_slot: Slot,
+) -> Result<(BeaconState<T>, SignedBeaconBlock<T>), String> {
+    let beacon = beacon_node.http.beacon();
+
+    let (block, _) = beacon
+        .get_block_by_slot(block_slot)
+        .await
+        .map_err(|e| format!("Unable to get block at slot {}: {:?}", block_slot, e))?;
+
+    // The beacon node returns the prior block when the slot is skipped.
+    if block.slot() != block_slot {
+        return Err(format!("There is no block at slot {}", block_slot));
+    }
+
+    let (parent_block, _) = beacon
+        .get_block_by_root(block.parent_root())
+        .await
+        .map_err(|e| format!("Unable to get parent block: {:?}", e))?;
+
+    let (pre_state, _) = beacon
+        .get_state_by_root(parent_block.state_root())
+        .await
+        .map_err(|e| format!("Unable to get parent state: {:?}", e))?;
+
+    Ok((pre_state, block))
+}
+
 fn do_transition<T: EthSpec>(
     mut pre_state: BeaconState<T>,
     block: SignedBeaconBlock<T>,
+    block_sig_strategy: BlockSignatureStrategy,
+    state_root_strategy: StateRootStrategy,
 ) -> Result<BeaconState<T>, String> {
     let spec = &T::default_spec();
 
-    pre_state
-        .build_all_caches(spec)
-        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-
-    // Transition the parent state to the block slot.
-    for i in pre_state.slot.as_u64()..block.slot().as_u64() {
-        per_slot_processing(&mut pre_state, None, spec)
-            .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
+    if block.slot() < pre_state.slot {
+        return Err(format!(
+            "Block slot {} is earlier than pre-state slot {}",
+            block.slot(),
+            pre_state.slot
+        ));
     }
 
     pre_state
         .build_all_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
 
-    per_block_processing(
-        &mut pre_state,
-        &block,
-        None,
-        BlockSignatureStrategy::VerifyIndividual,
-        spec,
-    )
-    .map_err(|e| format!("State transition failed: {:?}", e))?;
+    // The pre-state has already been advanced to the block slot (e.g., by `lcli skip-slots`), so
+    // there are no slots to process. The replayer ignores blocks at the state's slot, so apply the
+    // block directly.
+    if block.slot() == pre_state.slot {
+        per_block_processing(&mut pre_state, &block, None, block_sig_strategy, spec)
+            .map_err(|e| format!("State transition failed: {:?}", e))?;
+        return Ok(pre_state);
+    }
+
+    // Transition the parent state to the block slot and apply the block.
+    BlockReplayer::<T, BlockReplayError>::new(pre_state, spec)
+        .block_signature_strategy(block_sig_strategy)
+        .state_root_strategy(state_root_strategy)
+        .pre_block_hook(Box::new(|state, _| {
+            state.build_all_caches(spec).map_err(Into::into)
+        }))
+        .apply_blocks(vec![block], None)
+        .map(BlockReplayer::into_state)
+        .map_err(|e| format!("State transition failed: {:?}", e))
+}
 
-    Ok(pre_state)
+/// Returns a client for the beacon node at `beacon_url` which will wait long enough to download
+/// a `BeaconState`.
+pub fn remote_beacon_node<T: EthSpec>(beacon_url: String) -> Result<RemoteBeaconNode<T>, String> {
+    RemoteBeaconNode::new_with_timeout(beacon_url, BEACON_NODE_TIMEOUT)
 }
 
 pub fn load_from_ssz<T: Decode>(path: PathBuf) -> Result<T, String> {
@@ -320,6 +320,26 @@ impl<E: EthSpec> RuntimeContext<E> {
         }
     }
 
+    /// Returns a sub-context of this context with its own exit signal, such that the services
+    /// spawned on it can be shut down independently by firing the returned `Signal`.
+    ///
+    /// This is intended for simulations which start and stop several nodes in a single process.
+    /// The services are not notified when the rest of the environment exits.
+    pub fn service_context_with_exit(&self, service_name: String) -> (Self, exit_future::Signal) {
+        let (signal, exit) = exit_future::signal();
+        let context = Self {
+            executor: TaskExecutor {
+                handle: self.executor.handle.clone(),
+                signal_tx: self.executor.signal_tx.clone(),
+                exit,
+                log: self.executor.log.new(o!("service" => service_name)),
+            },
+            eth_spec_instance: self.eth_spec_instance.clone(),
+            eth2_config: self.eth2_config.clone(),
+        };
+        (context, signal)
+    }
+
     /// Returns the `eth2_config` for this service.
     pub fn eth2_config(&self) -> &Eth2Config {
         &self.eth2_config
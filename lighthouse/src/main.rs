@@ -2,9 +2,10 @@ use beacon_node::ProductionBeaconNode;
 use clap::{App, Arg, ArgMatches};
 use env_logger::{Builder, Env};
 use environment::EnvironmentBuilder;
-use eth2_testnet_config::{Eth2TestnetConfig, DEFAULT_HARDCODED_TESTNET};
+use eth2_testnet_config::{Eth2TestnetConfig, DEFAULT_HARDCODED_TESTNET, HARDCODED_NET_NAMES};
 use lighthouse_version::VERSION;
 use slog::{crit, info, warn};
+use standalone_slasher::StandaloneSlasher;
 use std::path::PathBuf;
 use std::process::exit;
 use types::EthSpec;
@@ -108,11 +109,16 @@ fn main() {
                 .global(true),
         )
         .arg(
-            Arg::with_name("testnet")
-                .long("testnet")
-                .value_name("testnet")
-                .help("Name of network lighthouse will connect to")
-                .possible_values(&["medalla", "altona"])
+            Arg::with_name("network")
+                .long("network")
+                .alias("testnet")
+                .value_name("network")
+                .help(
+                    "Name of the network lighthouse will connect to. The config, boot nodes and \
+                    genesis state of the network are built into the binary. --testnet is a \
+                    deprecated alias for this flag.",
+                )
+                .possible_values(HARDCODED_NET_NAMES)
                 .conflicts_with("testnet-dir")
                 .takes_value(true)
                 .global(true)
@@ -122,6 +128,8 @@ fn main() {
         .subcommand(boot_node::cli_app())
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
+        .subcommand(database_manager::cli_app())
+        .subcommand(standalone_slasher::cli_app())
         .get_matches();
 
     // boot node subcommand circumvents the environment
@@ -187,11 +195,11 @@ fn run<E: EthSpec>(
 
     let log_format = matches.value_of("log-format");
 
-    // Parse testnet config from the `testnet` and `testnet-dir` flag in that order
+    // Parse testnet config from the `network` and `testnet-dir` flag in that order
     // else, use the default
     let mut optional_testnet_config = None;
-    if matches.is_present("testnet") {
-        optional_testnet_config = clap_utils::parse_hardcoded_network(matches, "testnet")?;
+    if matches.is_present("network") {
+        optional_testnet_config = clap_utils::parse_hardcoded_network(matches, "network")?;
     };
     if matches.is_present("testnet-dir") {
         optional_testnet_config = clap_utils::parse_testnet_dir(matches, "testnet-dir")?;
@@ -225,16 +233,32 @@ fn run<E: EthSpec>(
     // Creating a command which can run both might be useful future works.
 
     // Print an indication of which network is currently in use.
-    let optional_testnet = clap_utils::parse_optional::<String>(matches, "testnet")?;
+    let optional_testnet = clap_utils::parse_optional::<String>(matches, "network")?;
     let optional_testnet_dir = clap_utils::parse_optional::<PathBuf>(matches, "testnet-dir")?;
 
     let testnet_name = match (optional_testnet, optional_testnet_dir) {
         (Some(testnet), None) => testnet,
         (None, Some(testnet_dir)) => format!("custom ({})", testnet_dir.display()),
         (None, None) => DEFAULT_HARDCODED_TESTNET.to_string(),
-        (Some(_), Some(_)) => panic!("CLI prevents both --testnet and --testnet-dir"),
+        (Some(_), Some(_)) => panic!("CLI prevents both --network and --testnet-dir"),
     };
 
+    if let Some(datadir) = root_datadir(matches)? {
+        let legacy_items = account_utils::datadir::check_datadir(&datadir)?;
+
+        if let Some(item) = legacy_items.first() {
+            warn!(
+                log,
+                "Validator files found in the root of the datadir";
+                "msg" => "if the validator client does not use this directory as its --datadir, \
+                          run `lighthouse account migrate-datadir` to move them",
+                "example" => format!("{:?}", item.path),
+                "count" => legacy_items.len(),
+                "datadir" => format!("{:?}", datadir),
+            );
+        }
+    }
+
     if let Some(sub_matches) = matches.subcommand_matches("account_manager") {
         eprintln!("Running account manager for {} testnet", testnet_name);
         // Pass the entire `environment` to the account manager so it can run blocking operations.
@@ -244,6 +268,14 @@ fn run<E: EthSpec>(
         return Ok(());
     };
 
+    if let Some(sub_matches) = matches.subcommand_matches(database_manager::CMD) {
+        eprintln!("Running database manager for {} testnet", testnet_name);
+        database_manager::run(sub_matches, environment)?;
+
+        // Exit as soon as database manager returns control.
+        return Ok(());
+    };
+
     warn!(
         log,
         "Ethereum 2.0 is pre-release. This software is experimental."
@@ -298,7 +330,23 @@ fn run<E: EthSpec>(
         None
     };
 
-    if beacon_node.is_none() && validator_client.is_none() {
+    let slasher = if let Some(sub_matches) = matches.subcommand_matches(standalone_slasher::CMD) {
+        let runtime_context = environment.core_context();
+
+        let slasher = environment
+            .runtime()
+            .block_on(StandaloneSlasher::new_from_cli(
+                runtime_context,
+                sub_matches,
+            ))
+            .map_err(|e| format!("Failed to start slasher: {}", e))?;
+
+        Some(slasher)
+    } else {
+        None
+    };
+
+    if beacon_node.is_none() && validator_client.is_none() && slasher.is_none() {
         crit!(log, "No subcommand supplied. See --help .");
         return Err("No subcommand supplied.".into());
     }
@@ -310,8 +358,37 @@ fn run<E: EthSpec>(
     environment.fire_signal();
     drop(beacon_node);
     drop(validator_client);
+    drop(slasher);
 
     // Shutdown the environment once all tasks have completed.
     environment.shutdown_on_idle();
     Ok(())
 }
+
+/// Returns the root of the data directory used by the subcommand in `matches`, or `None` if the
+/// layout of the data directory should not be checked.
+///
+/// The `--datadir` of the validator client and the standalone slasher is their own directory, so
+/// only the default data directory is checked for those subcommands. The layout is not checked
+/// before it is migrated by the account manager.
+fn root_datadir(matches: &ArgMatches) -> Result<Option<PathBuf>, String> {
+    let migrating = matches
+        .subcommand_matches("account_manager")
+        .and_then(|sub_matches| {
+            sub_matches.subcommand_matches(account_manager::migrate_datadir::CMD)
+        })
+        .is_some();
+    let datadir_is_root = matches.subcommand_matches("beacon_node").is_some()
+        || matches.subcommand_matches(database_manager::CMD).is_some();
+
+    if migrating || (matches.is_present("datadir") && !datadir_is_root) {
+        return Ok(None);
+    }
+
+    clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        "datadir",
+        PathBuf::from(DEFAULT_DATA_DIR),
+    )
+    .map(Some)
+}
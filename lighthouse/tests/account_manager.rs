@@ -1,23 +1,29 @@
 #![cfg(not(debug_assertions))]
 
 use account_manager::{
+    migrate_datadir::CMD as MIGRATE_DATADIR_CMD,
     validator::{
         create::*,
         import::{self, CMD as IMPORT_CMD},
+        modify_password::{self, CMD as MODIFY_PASSWORD_CMD},
+        recover::{CMD as VALIDATOR_RECOVER_CMD, FIRST_INDEX_FLAG},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
         create::{CMD as CREATE_CMD, *},
         list::CMD as LIST_CMD,
+        recover::{CMD as RECOVER_CMD, MNEMONIC_FLAG as RECOVER_MNEMONIC_FLAG},
         CMD as WALLET_CMD,
     },
     BASE_DIR_FLAG, CMD as ACCOUNT_CMD, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
+    datadir::{read_datadir_version, CURRENT_DATADIR_VERSION, SECRETS_DIR, VALIDATORS_DIR},
+    eth2_keystore::{json_keystore::Kdf, Keystore, KeystoreBuilder},
     validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
     ZeroizeString,
 };
+use slashing_protection::SLASHING_PROTECTION_FILENAME;
 use std::env;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
@@ -26,7 +32,7 @@ use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
 use tempfile::{tempdir, TempDir};
 use types::Keypair;
-use validator_dir::ValidatorDir;
+use validator_dir::{Builder as ValidatorDirBuilder, ValidatorDir, VOTING_KEYSTORE_FILE};
 
 // TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
 // access to an IPC endpoint during testing or adding support for deposit submission via HTTP and
@@ -202,6 +208,71 @@ fn wallet_create_and_list() {
     assert_eq!(list_wallets(wally.base_dir()).len(), 2);
 }
 
+/// Recover a wallet from a mnemonic file using the lighthouse CLI.
+fn recover_wallet<P: AsRef<Path>>(
+    name: &str,
+    base_dir: P,
+    password: P,
+    mnemonic: P,
+) -> Result<Output, String> {
+    output_result(
+        wallet_cmd()
+            .arg(format!("--{}", BASE_DIR_FLAG))
+            .arg(base_dir.as_ref().as_os_str())
+            .arg(RECOVER_CMD)
+            .arg(format!("--{}", NAME_FLAG))
+            .arg(&name)
+            .arg(format!("--{}", PASSPHRASE_FLAG))
+            .arg(password.as_ref().as_os_str())
+            .arg(format!("--{}", RECOVER_MNEMONIC_FLAG))
+            .arg(mnemonic.as_ref().as_os_str()),
+    )
+}
+
+#[test]
+fn wallet_recover() {
+    let base_dir = tempdir().unwrap();
+    let wally = TestWallet::new(base_dir.path(), "wally");
+    wally.create_expect_success();
+
+    let recovered_base_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+    recover_wallet(
+        "wally",
+        recovered_base_dir.path(),
+        &password_dir.path().join("password.pass"),
+        &wally.mnemonic_path(),
+    )
+    .unwrap();
+
+    assert!(password_dir.path().join("password.pass").exists());
+    assert_eq!(
+        list_wallets(recovered_base_dir.path()),
+        vec!["wally".to_string()]
+    );
+
+    // Should not recover a wallet with a duplicate name.
+    recover_wallet(
+        "wally",
+        recovered_base_dir.path(),
+        &password_dir.path().join("password.pass"),
+        &wally.mnemonic_path(),
+    )
+    .unwrap_err();
+
+    // Should not recover a wallet from an invalid mnemonic.
+    let bad_mnemonic_path = password_dir.path().join("bad_mnemonic");
+    fs::write(&bad_mnemonic_path, "not a valid mnemonic").unwrap();
+    let err = recover_wallet(
+        "wally2",
+        recovered_base_dir.path(),
+        &password_dir.path().join("password.pass"),
+        &bad_mnemonic_path,
+    )
+    .unwrap_err();
+    assert!(err.contains("Unable to derive mnemonic"));
+}
+
 /// Returns the `lighthouse account validator` command.
 fn validator_cmd() -> Command {
     let mut cmd = account_cmd();
@@ -375,6 +446,58 @@ fn validator_create() {
     assert_eq!(dir_child_count(validator_dir.path()), 6);
 }
 
+#[test]
+fn validator_recover() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+    let mnemonic_path = wallet.mnemonic_path();
+
+    let validator = TestValidator::new(validator_dir.path(), secrets_dir.path(), wallet);
+    let created_pubkeys = validator.create(COUNT_FLAG, 3, false).unwrap();
+
+    // Recover the last two validators from the mnemonic.
+    let recovered_validator_dir = tempdir().unwrap();
+    let recovered_secrets_dir = tempdir().unwrap();
+    let output = output_result(
+        validator_cmd()
+            .arg(VALIDATOR_RECOVER_CMD)
+            .arg(format!("--{}", FIRST_INDEX_FLAG))
+            .arg("1")
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg("2")
+            .arg(format!("--{}", RECOVER_MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(recovered_validator_dir.path().as_os_str())
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(recovered_secrets_dir.path().as_os_str()),
+    )
+    .unwrap();
+
+    let recovered_pubkeys = from_utf8(&output.stdout)
+        .expect("stdout is not utf8")
+        .lines()
+        .map(|line| {
+            let tab = line.find("\t").expect("line must have tab");
+            let (_, pubkey) = line.split_at(tab + 1);
+            pubkey.to_string()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(recovered_pubkeys, created_pubkeys[1..].to_vec());
+
+    for pubkey in &recovered_pubkeys {
+        ValidatorDir::open(recovered_validator_dir.path().join(pubkey))
+            .expect("should open validator dir")
+            .voting_keypair(recovered_secrets_dir.path())
+            .expect("should decrypt voting keypair");
+    }
+}
+
 /// Returns the `lighthouse account validator import` command.
 fn validator_import_cmd() -> Command {
     let mut cmd = validator_cmd();
@@ -467,4 +590,190 @@ fn validator_import_launchpad() {
         defs.as_slice() == &[expected_def],
         "validator defs file should be accurate"
     );
+
+    assert!(
+        dst_dir.path().join(SLASHING_PROTECTION_FILENAME).exists(),
+        "slashing protection database should be created"
+    );
+}
+
+#[test]
+fn migrate_datadir() {
+    const PASSWORD: &str = "cats";
+
+    let datadir = tempdir().unwrap();
+    let root = datadir.path();
+
+    let keypair = Keypair::random();
+    let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    let pubkey = format!("0x{}", keystore.pubkey());
+
+    // Create a validator in the legacy layout. Its password can't also be in the root of the
+    // datadir, as it would have the same name as the validator directory.
+    let secrets_dir = root.join(SECRETS_DIR);
+    fs::create_dir(&secrets_dir).unwrap();
+    ValidatorDirBuilder::new(root.to_path_buf(), secrets_dir.clone())
+        .voting_keystore(keystore.clone(), PASSWORD.as_bytes())
+        .store_withdrawal_keystore(false)
+        .build()
+        .unwrap();
+
+    let mut defs = ValidatorDefinitions::default();
+    defs.push(ValidatorDefinition {
+        enabled: true,
+        voting_public_key: keystore.public_key().unwrap(),
+        signing_definition: SigningDefinition::LocalKeystore {
+            voting_keystore_path: root.join(&pubkey).join(VOTING_KEYSTORE_FILE),
+            voting_keystore_password_path: Some(secrets_dir.join(&pubkey)),
+            voting_keystore_password: None,
+        },
+    });
+    defs.save(root).unwrap();
+
+    output_result(
+        account_cmd()
+            .arg("--datadir")
+            .arg(root.as_os_str())
+            .arg(MIGRATE_DATADIR_CMD),
+    )
+    .unwrap();
+
+    let validators_dir = root.join(VALIDATORS_DIR);
+    let voting_keystore_path = validators_dir.join(&pubkey).join(VOTING_KEYSTORE_FILE);
+    let voting_keystore_password_path = secrets_dir.join(&pubkey);
+
+    assert!(voting_keystore_path.exists(), "keystore should be moved");
+    assert!(
+        voting_keystore_password_path.exists(),
+        "password should be retained"
+    );
+    assert!(
+        !root.join(&pubkey).exists(),
+        "nothing should remain in the root of the datadir"
+    );
+
+    let defs = ValidatorDefinitions::open(&validators_dir).unwrap();
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path: path,
+            voting_keystore_password_path: password_path,
+            ..
+        } => {
+            assert_eq!(path, &voting_keystore_path);
+            assert_eq!(password_path, &Some(voting_keystore_password_path));
+        }
+    }
+
+    assert_eq!(
+        read_datadir_version(root),
+        Ok(Some(CURRENT_DATADIR_VERSION))
+    );
+}
+
+#[test]
+fn validator_modify_password() {
+    const OLD_PASSWORD: &str = "cats";
+    const NEW_PASSWORD: &str = "dogs";
+
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let keypair = Keypair::random();
+    let keystore = KeystoreBuilder::new(&keypair, OLD_PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    let pubkey = format!("0x{}", keystore.pubkey());
+
+    ValidatorDirBuilder::new(validator_dir.path().into(), secrets_dir.path().into())
+        .voting_keystore(keystore.clone(), OLD_PASSWORD.as_bytes())
+        .store_withdrawal_keystore(false)
+        .build()
+        .unwrap();
+
+    let voting_keystore_path = validator_dir
+        .path()
+        .join(&pubkey)
+        .join(VOTING_KEYSTORE_FILE);
+    let voting_keystore_password_path = secrets_dir.path().join(&pubkey);
+
+    let mut defs = ValidatorDefinitions::default();
+    defs.push(ValidatorDefinition {
+        enabled: true,
+        voting_public_key: keystore.public_key().unwrap(),
+        signing_definition: SigningDefinition::LocalKeystore {
+            voting_keystore_path: voting_keystore_path.clone(),
+            voting_keystore_password_path: Some(voting_keystore_password_path.clone()),
+            voting_keystore_password: None,
+        },
+    });
+    defs.save(validator_dir.path()).unwrap();
+
+    let mut child = validator_cmd()
+        .arg(MODIFY_PASSWORD_CMD)
+        .arg(format!("--{}", modify_password::STDIN_INPUTS_FLAG)) // Using tty does not work well with tests.
+        .arg(format!("--{}", modify_password::KEYSTORE_FLAG))
+        .arg(voting_keystore_path.as_os_str())
+        .arg(format!("--{}", modify_password::KDF_FLAG))
+        .arg(modify_password::PBKDF2)
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(validator_dir.path().as_os_str())
+        .arg(format!("--{}", SECRETS_DIR_FLAG))
+        .arg(secrets_dir.path().as_os_str())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stderr = child.stderr.as_mut().map(BufReader::new).unwrap().lines();
+    let stdin = child.stdin.as_mut().unwrap();
+
+    // The old password is read from the secrets dir, so only the new password is requested.
+    for prompt in &[
+        modify_password::NEW_PASSWORD_PROMPT,
+        modify_password::CONFIRM_PASSWORD_PROMPT,
+    ] {
+        loop {
+            if stderr.next().unwrap().unwrap() == *prompt {
+                break;
+            }
+        }
+
+        stdin
+            .write(format!("{}\n", NEW_PASSWORD).as_bytes())
+            .unwrap();
+    }
+
+    assert!(child.wait().unwrap().success());
+
+    let keystore = Keystore::from_json_file(&voting_keystore_path).unwrap();
+
+    assert!(
+        keystore.decrypt_keypair(OLD_PASSWORD.as_bytes()).is_err(),
+        "old password should not decrypt keystore"
+    );
+    assert_eq!(
+        keystore
+            .decrypt_keypair(NEW_PASSWORD.as_bytes())
+            .unwrap()
+            .pk,
+        keypair.pk
+    );
+    assert!(
+        matches!(keystore.kdf(), Kdf::Pbkdf2(_)),
+        "keystore should use the requested kdf"
+    );
+    assert_eq!(
+        fs::read_to_string(&voting_keystore_password_path).unwrap(),
+        NEW_PASSWORD,
+        "password file should be updated"
+    );
+    assert_eq!(
+        dir_child_count(secrets_dir.path()),
+        1,
+        "no temporary files should remain"
+    );
 }
@@ -0,0 +1,10 @@
+//! A service which periodically processes the attestations and blocks queued by the slasher, and
+//! broadcasts any slashings it finds.
+
+#[macro_use]
+extern crate lazy_static;
+
+mod metrics;
+mod service;
+
+pub use service::SlasherService;
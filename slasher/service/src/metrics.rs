@@ -0,0 +1,29 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    pub static ref SLASHER_ATTESTER_SLASHINGS_BROADCAST: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_attester_slashings_broadcast_total",
+            "Count of attester slashings found by the slasher which were added to the op pool and published"
+        );
+    pub static ref SLASHER_ATTESTER_SLASHINGS_INVALID: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_attester_slashings_invalid_total",
+            "Count of attester slashings found by the slasher which failed verification against the current state"
+        );
+    pub static ref SLASHER_PROPOSER_SLASHINGS_BROADCAST: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_proposer_slashings_broadcast_total",
+            "Count of proposer slashings found by the slasher which were added to the op pool and published"
+        );
+    pub static ref SLASHER_PROPOSER_SLASHINGS_INVALID: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_proposer_slashings_invalid_total",
+            "Count of proposer slashings found by the slasher which failed verification against the current state"
+        );
+    pub static ref SLASHER_UPDATES_SKIPPED: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_updates_skipped_total",
+            "Count of slasher updates skipped because the previous batch was still being processed"
+        );
+}
@@ -0,0 +1,262 @@
+use crate::metrics;
+use beacon_chain::{observed_operations::ObservationOutcome, BeaconChain, BeaconChainTypes};
+use environment::TaskExecutor;
+use eth2_libp2p::PubsubMessage;
+use futures::stream::StreamExt;
+use network::NetworkMessage;
+use slasher::Slasher;
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::{interval_at, Instant};
+use types::EthSpec;
+
+/// Drives the slasher of a `BeaconChain`, processing its queued attestations and blocks every
+/// `update_period` seconds.
+///
+/// Each slashing found is verified, added to the op pool for inclusion in our own blocks and
+/// published on gossip.
+pub struct SlasherService<T: BeaconChainTypes> {
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
+}
+
+impl<T: BeaconChainTypes> SlasherService<T> {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain<T>>,
+        network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
+    ) -> Self {
+        Self {
+            beacon_chain,
+            network_sender,
+        }
+    }
+
+    /// Starts the service, returning an error if the beacon chain has no slasher.
+    pub fn run(&self, executor: &TaskExecutor) -> Result<(), String> {
+        let log = executor.log().clone();
+        let slasher = self
+            .beacon_chain
+            .slasher
+            .as_ref()
+            .ok_or_else(|| "slasher service requires a slasher".to_string())?;
+        let update_period = slasher.config().update_period;
+        let batch_lock = slasher.batch_lock().clone();
+
+        // Warning: `interval_at` panics if `update_period` = 0.
+        let mut interval = interval_at(
+            Instant::now() + Duration::from_secs(update_period),
+            Duration::from_secs(update_period),
+        );
+
+        let beacon_chain = self.beacon_chain.clone();
+        let network_sender = self.network_sender.clone();
+        let inner_executor = executor.clone();
+        let service_future = async move {
+            while interval.next().await.is_some() {
+                // Skip this update if the previous batch is still being processed.
+                let batch_guard = match batch_lock.try_lock() {
+                    Some(guard) => guard,
+                    None => {
+                        metrics::inc_counter(&metrics::SLASHER_UPDATES_SKIPPED);
+                        debug!(log, "Slasher batch in progress, skipping update");
+                        continue;
+                    }
+                };
+
+                let beacon_chain = beacon_chain.clone();
+                let network_sender = network_sender.clone();
+                let log = log.clone();
+
+                // Processing a batch may take some time, so run it on a blocking thread.
+                inner_executor.spawn_blocking(
+                    move || {
+                        let _batch_guard = batch_guard;
+                        Self::process_queued(&beacon_chain, &network_sender, &log)
+                    },
+                    "slasher_batch",
+                );
+            }
+        };
+
+        executor.spawn(service_future, "slasher_service");
+        info!(
+            executor.log(),
+            "Slasher service started";
+            "update_period" => update_period,
+        );
+
+        Ok(())
+    }
+
+    /// Processes the slasher's queued messages, then broadcasts any slashings found.
+    fn process_queued(
+        beacon_chain: &BeaconChain<T>,
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        log: &Logger,
+    ) {
+        let slasher = match beacon_chain.slasher.as_ref() {
+            Some(slasher) => slasher,
+            None => return,
+        };
+
+        let current_epoch = match beacon_chain.slot_clock.now() {
+            Some(slot) => slot.epoch(T::EthSpec::slots_per_epoch()),
+            None => {
+                error!(log, "Slasher unable to read the current slot");
+                return;
+            }
+        };
+
+        match slasher.process_queued(current_epoch) {
+            Ok(()) => debug!(
+                log,
+                "Slasher processed queued messages";
+                "epoch" => current_epoch,
+            ),
+            Err(e) => {
+                error!(
+                    log,
+                    "Error during scheduled slasher processing";
+                    "error" => format!("{:?}", e),
+                    "epoch" => current_epoch,
+                );
+                return;
+            }
+        }
+
+        Self::process_attester_slashings(slasher, beacon_chain, network_sender, log);
+        Self::process_proposer_slashings(slasher, beacon_chain, network_sender, log);
+    }
+
+    fn process_attester_slashings(
+        slasher: &Slasher<T::EthSpec>,
+        beacon_chain: &BeaconChain<T>,
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        log: &Logger,
+    ) {
+        for slashing in slasher.get_attester_slashings() {
+            let slashed_indices = format!(
+                "{:?}",
+                slashing
+                    .attestation_1
+                    .attesting_indices
+                    .iter()
+                    .filter(|index| slashing.attestation_2.attesting_indices.contains(index))
+                    .collect::<Vec<_>>()
+            );
+
+            // Verify the slashing against the current state, which also filters out slashings of
+            // validators which have already been slashed.
+            let verified_slashing =
+                match beacon_chain.verify_attester_slashing_for_gossip(slashing.clone()) {
+                    Ok(ObservationOutcome::New(verified_slashing)) => verified_slashing,
+                    Ok(ObservationOutcome::AlreadyKnown) => {
+                        debug!(
+                            log,
+                            "Attester slashing already known";
+                            "validator_indices" => slashed_indices,
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        metrics::inc_counter(&metrics::SLASHER_ATTESTER_SLASHINGS_INVALID);
+                        warn!(
+                            log,
+                            "Attester slashing produced is invalid";
+                            "validator_indices" => slashed_indices,
+                            "error" => format!("{:?}", e),
+                        );
+                        continue;
+                    }
+                };
+
+            if let Err(e) = beacon_chain.import_attester_slashing(verified_slashing) {
+                error!(
+                    log,
+                    "Unable to add attester slashing to the op pool";
+                    "validator_indices" => &slashed_indices,
+                    "error" => format!("{:?}", e),
+                );
+            }
+
+            info!(
+                log,
+                "Broadcasting attester slashing";
+                "validator_indices" => slashed_indices,
+            );
+            metrics::inc_counter(&metrics::SLASHER_ATTESTER_SLASHINGS_BROADCAST);
+            Self::publish(
+                PubsubMessage::AttesterSlashing(Box::new(slashing)),
+                network_sender,
+                log,
+            );
+        }
+    }
+
+    fn process_proposer_slashings(
+        slasher: &Slasher<T::EthSpec>,
+        beacon_chain: &BeaconChain<T>,
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        log: &Logger,
+    ) {
+        for slashing in slasher.get_proposer_slashings() {
+            let proposer_index = slashing.signed_header_1.message.proposer_index;
+
+            let verified_slashing =
+                match beacon_chain.verify_proposer_slashing_for_gossip(slashing.clone()) {
+                    Ok(ObservationOutcome::New(verified_slashing)) => verified_slashing,
+                    Ok(ObservationOutcome::AlreadyKnown) => {
+                        debug!(
+                            log,
+                            "Proposer slashing already known";
+                            "validator_index" => proposer_index,
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        metrics::inc_counter(&metrics::SLASHER_PROPOSER_SLASHINGS_INVALID);
+                        warn!(
+                            log,
+                            "Proposer slashing produced is invalid";
+                            "validator_index" => proposer_index,
+                            "error" => format!("{:?}", e),
+                        );
+                        continue;
+                    }
+                };
+
+            beacon_chain.import_proposer_slashing(verified_slashing);
+
+            info!(
+                log,
+                "Broadcasting proposer slashing";
+                "validator_index" => proposer_index,
+            );
+            metrics::inc_counter(&metrics::SLASHER_PROPOSER_SLASHINGS_BROADCAST);
+            Self::publish(
+                PubsubMessage::ProposerSlashing(Box::new(slashing)),
+                network_sender,
+                log,
+            );
+        }
+    }
+
+    fn publish(
+        message: PubsubMessage<T::EthSpec>,
+        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        log: &Logger,
+    ) {
+        if let Err(e) = network_sender.send(NetworkMessage::Publish {
+            messages: vec![message],
+        }) {
+            error!(
+                log,
+                "Unable to publish slashing";
+                "error" => format!("{:?}", e),
+            );
+        }
+    }
+}
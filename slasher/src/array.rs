@@ -0,0 +1,295 @@
+//! The min-max target arrays used to detect surround votes.
+//!
+//! For each validator and each epoch `e` within the history length:
+//!
+//! - `min_targets[e]` is the minimum target epoch of the validator's attestations with a source
+//!   epoch greater than `e`. A new attestation `(s, t)` surrounds an existing attestation if
+//!   `min_targets[s] < t`.
+//! - `max_targets[e]` is the maximum target epoch of the validator's attestations with a source
+//!   epoch less than `e`. A new attestation `(s, t)` is surrounded by an existing attestation if
+//!   `max_targets[s] > t`.
+//!
+//! Each cell stores the distance from `e` to the target epoch as a `u16`. The arrays are stored
+//! as circular buffers of length `history_length`, split into chunks of `chunk_size` epochs for
+//! `validator_chunk_size` validators, so that processing an attestation only reads and writes a
+//! handful of chunks.
+
+use crate::database::{RwTransaction, MAX_TARGETS_DB, MIN_TARGETS_DB};
+use crate::{Config, Error, SlasherDB};
+use std::collections::{btree_map::Entry, BTreeMap};
+use types::{Epoch, EthSpec};
+
+/// The largest distance which may be stored in a cell, used by `min_targets` to represent the
+/// absence of any attestation.
+pub const MAX_DISTANCE: u16 = u16::max_value();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayKind {
+    MinTargets,
+    MaxTargets,
+}
+
+impl ArrayKind {
+    /// The value of a cell for which no attestation has been recorded.
+    ///
+    /// For `MaxTargets` this is a distance of zero, which also represents every target epoch less
+    /// than the cell's epoch. A new attestation's target is never less than its source, so this
+    /// does not affect the detection of surround votes.
+    pub fn neutral_element(self) -> u16 {
+        match self {
+            ArrayKind::MinTargets => MAX_DISTANCE,
+            ArrayKind::MaxTargets => 0,
+        }
+    }
+
+    fn db(self) -> &'static str {
+        match self {
+            ArrayKind::MinTargets => MIN_TARGETS_DB,
+            ArrayKind::MaxTargets => MAX_TARGETS_DB,
+        }
+    }
+}
+
+/// The chunks of a single target array for the validators in a single validator chunk, loaded
+/// from the database as they are required.
+pub struct TargetArray<'a, E: EthSpec> {
+    kind: ArrayKind,
+    validator_chunk_index: usize,
+    chunks: BTreeMap<usize, Vec<u16>>,
+    dirty: Vec<usize>,
+    db: &'a SlasherDB<E>,
+    config: &'a Config,
+}
+
+impl<'a, E: EthSpec> TargetArray<'a, E> {
+    pub fn new(
+        kind: ArrayKind,
+        validator_chunk_index: usize,
+        db: &'a SlasherDB<E>,
+        config: &'a Config,
+    ) -> Self {
+        Self {
+            kind,
+            validator_chunk_index,
+            chunks: BTreeMap::new(),
+            dirty: vec![],
+            db,
+            config,
+        }
+    }
+
+    fn chunk(&mut self, epoch: Epoch, txn: &mut RwTransaction<'_>) -> Result<&mut Vec<u16>, Error> {
+        let chunk_index = self.config.chunk_index(epoch);
+        match self.chunks.entry(chunk_index) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let disk_key = self
+                    .config
+                    .disk_key(self.validator_chunk_index, chunk_index);
+                let neutral_element = self.kind.neutral_element();
+                let chunk_len = self.config.chunk_len();
+                let chunk = self
+                    .db
+                    .get_chunk(self.kind.db(), disk_key, txn)?
+                    .unwrap_or_else(|| vec![neutral_element; chunk_len]);
+                Ok(entry.insert(chunk))
+            }
+        }
+    }
+
+    /// Returns the target epoch stored for `validator_index` at `epoch`, or `None` if the cell is
+    /// empty.
+    pub fn get_target(
+        &mut self,
+        validator_index: u64,
+        epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<Option<Epoch>, Error> {
+        let cell_index = self.config.cell_index(
+            self.config.validator_offset(validator_index),
+            self.config.chunk_offset(epoch),
+        );
+        let kind = self.kind;
+        let distance = self.chunk(epoch, txn)?[cell_index];
+
+        if kind == ArrayKind::MinTargets && distance == MAX_DISTANCE {
+            Ok(None)
+        } else {
+            Ok(Some(epoch + u64::from(distance)))
+        }
+    }
+
+    /// Stores `target_epoch` for `validator_index` at `epoch`, or empties the cell if
+    /// `target_epoch` is `None`.
+    pub fn set_target(
+        &mut self,
+        validator_index: u64,
+        epoch: Epoch,
+        target_epoch: Option<Epoch>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        let distance = match target_epoch {
+            Some(target_epoch) => {
+                let distance = target_epoch.as_u64().saturating_sub(epoch.as_u64());
+                if distance >= u64::from(MAX_DISTANCE) {
+                    return Err(Error::DistanceTooLarge {
+                        epoch,
+                        target_epoch,
+                    });
+                }
+                distance as u16
+            }
+            None => self.kind.neutral_element(),
+        };
+
+        let cell_index = self.config.cell_index(
+            self.config.validator_offset(validator_index),
+            self.config.chunk_offset(epoch),
+        );
+        let chunk_index = self.config.chunk_index(epoch);
+        self.chunk(epoch, txn)?[cell_index] = distance;
+
+        if !self.dirty.contains(&chunk_index) {
+            self.dirty.push(chunk_index);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the modified chunks to the database.
+    pub fn store(self, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        let db = self.kind.db();
+        for chunk_index in self.dirty {
+            let disk_key = self
+                .config
+                .disk_key(self.validator_chunk_index, chunk_index);
+            if let Some(chunk) = self.chunks.get(&chunk_index) {
+                self.db.put_chunk(db, disk_key, chunk, txn)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The min and max target arrays for the validators in a single validator chunk.
+pub struct TargetArrays<'a, E: EthSpec> {
+    pub min_targets: TargetArray<'a, E>,
+    pub max_targets: TargetArray<'a, E>,
+}
+
+impl<'a, E: EthSpec> TargetArrays<'a, E> {
+    pub fn new(validator_chunk_index: usize, db: &'a SlasherDB<E>, config: &'a Config) -> Self {
+        Self {
+            min_targets: TargetArray::new(ArrayKind::MinTargets, validator_chunk_index, db, config),
+            max_targets: TargetArray::new(ArrayKind::MaxTargets, validator_chunk_index, db, config),
+        }
+    }
+
+    /// Empties the cells of `validator_index` for the epochs after `previous_epoch`, up to and
+    /// including `current_epoch`.
+    ///
+    /// These cells either hold data from `history_length` epochs ago or have never been written,
+    /// and must be emptied before the validator's arrays are used at `current_epoch`.
+    pub fn advance_epoch(
+        &mut self,
+        validator_index: u64,
+        previous_epoch: Epoch,
+        current_epoch: Epoch,
+        config: &Config,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        let start_epoch = std::cmp::max(previous_epoch + 1, lowest_epoch(current_epoch, config));
+        let mut epoch = start_epoch;
+        while epoch <= current_epoch {
+            self.min_targets
+                .set_target(validator_index, epoch, None, txn)?;
+            self.max_targets
+                .set_target(validator_index, epoch, None, txn)?;
+            epoch += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the target epoch of an existing attestation surrounded by the new attestation
+    /// `(source_epoch, target_epoch)`, if any.
+    pub fn surrounded_target(
+        &mut self,
+        validator_index: u64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<Option<Epoch>, Error> {
+        Ok(self
+            .min_targets
+            .get_target(validator_index, source_epoch, txn)?
+            .filter(|min_target| *min_target < target_epoch))
+    }
+
+    /// Returns the target epoch of an existing attestation which surrounds the new attestation
+    /// `(source_epoch, target_epoch)`, if any.
+    pub fn surrounding_target(
+        &mut self,
+        validator_index: u64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<Option<Epoch>, Error> {
+        Ok(self
+            .max_targets
+            .get_target(validator_index, source_epoch, txn)?
+            .filter(|max_target| *max_target > target_epoch))
+    }
+
+    /// Records the attestation `(source_epoch, target_epoch)` in both arrays.
+    pub fn apply_attestation(
+        &mut self,
+        validator_index: u64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        current_epoch: Epoch,
+        config: &Config,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        // The min targets of the epochs prior to the source epoch are non-increasing as the epoch
+        // decreases, so the update stops at the first cell which is already small enough.
+        let lowest_epoch = lowest_epoch(current_epoch, config);
+        let mut epoch = source_epoch;
+        while epoch > lowest_epoch {
+            epoch -= 1;
+            match self.min_targets.get_target(validator_index, epoch, txn)? {
+                Some(min_target) if min_target <= target_epoch => break,
+                _ => {
+                    self.min_targets
+                        .set_target(validator_index, epoch, Some(target_epoch), txn)?
+                }
+            }
+        }
+
+        // Similarly, the max targets of the epochs after the source epoch are non-decreasing.
+        let mut epoch = source_epoch + 1;
+        while epoch <= current_epoch {
+            match self.max_targets.get_target(validator_index, epoch, txn)? {
+                Some(max_target) if max_target >= target_epoch => break,
+                _ => {
+                    self.max_targets
+                        .set_target(validator_index, epoch, Some(target_epoch), txn)?
+                }
+            }
+            epoch += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the modified chunks of both arrays to the database.
+    pub fn store(self, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        self.min_targets.store(txn)?;
+        self.max_targets.store(txn)
+    }
+}
+
+/// The lowest epoch which is covered by the target arrays at `current_epoch`.
+pub fn lowest_epoch(current_epoch: Epoch, config: &Config) -> Epoch {
+    let history_length = config.history_length as u64;
+    Epoch::new((current_epoch.as_u64() + 1).saturating_sub(history_length))
+}
@@ -0,0 +1,220 @@
+use crate::Error;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use types::Epoch;
+
+pub const DEFAULT_CHUNK_SIZE: usize = 16;
+pub const DEFAULT_VALIDATOR_CHUNK_SIZE: usize = 256;
+pub const DEFAULT_HISTORY_LENGTH: usize = 4096;
+pub const DEFAULT_UPDATE_PERIOD: u64 = 12;
+pub const DEFAULT_MAX_QUEUED_ATTESTATIONS: usize = 65_536;
+/// The default maximum size of the database, in megabytes (256 GiB).
+pub const DEFAULT_MAX_DB_SIZE: usize = 256 * 1024;
+
+/// The name of the directory containing the slasher database, within the beacon node's data
+/// directory.
+pub const DATABASE_DIR_NAME: &str = "slasher_db";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The directory containing the slasher database.
+    pub database_path: PathBuf,
+    /// The number of epochs stored in each chunk of the min-max target arrays.
+    pub chunk_size: usize,
+    /// The number of validators stored in each chunk of the min-max target arrays.
+    pub validator_chunk_size: usize,
+    /// The number of epochs of attestations which are checked for surround votes.
+    pub history_length: usize,
+    /// The interval between processing the queued attestations and blocks, in seconds.
+    pub update_period: u64,
+    /// The maximum number of attestations waiting to be processed. Once reached, the oldest
+    /// queued attestations are dropped to make room for new ones.
+    #[serde(default = "default_max_queued_attestations")]
+    pub max_queued_attestations: usize,
+    /// The maximum size of the database, in megabytes.
+    pub max_db_size_mbs: usize,
+    /// The embedded database used to store the slasher's records.
+    #[serde(default)]
+    pub backend: DatabaseBackend,
+}
+
+/// The embedded databases that may be used by the slasher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    Lmdb,
+    /// Only available if compiled with the `mdbx` feature.
+    Mdbx,
+}
+
+impl Default for DatabaseBackend {
+    fn default() -> Self {
+        DatabaseBackend::Lmdb
+    }
+}
+
+impl DatabaseBackend {
+    /// All known backends, whether or not they were compiled in.
+    pub const ALL: [DatabaseBackend; 2] = [DatabaseBackend::Lmdb, DatabaseBackend::Mdbx];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseBackend::Lmdb => "lmdb",
+            DatabaseBackend::Mdbx => "mdbx",
+        }
+    }
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lmdb" => Ok(DatabaseBackend::Lmdb),
+            "mdbx" => Ok(DatabaseBackend::Mdbx),
+            other => Err(format!("Unknown slasher database backend: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+fn default_max_queued_attestations() -> usize {
+    DEFAULT_MAX_QUEUED_ATTESTATIONS
+}
+
+impl Config {
+    pub fn new(database_path: PathBuf) -> Self {
+        Self {
+            database_path,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            validator_chunk_size: DEFAULT_VALIDATOR_CHUNK_SIZE,
+            history_length: DEFAULT_HISTORY_LENGTH,
+            update_period: DEFAULT_UPDATE_PERIOD,
+            max_queued_attestations: DEFAULT_MAX_QUEUED_ATTESTATIONS,
+            max_db_size_mbs: DEFAULT_MAX_DB_SIZE,
+            backend: DatabaseBackend::default(),
+        }
+    }
+
+    /// Returns an error if the chunk sizes are zero or do not evenly divide the history length.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.chunk_size == 0
+            || self.validator_chunk_size == 0
+            || self.history_length == 0
+            || self.update_period == 0
+            || self.max_queued_attestations == 0
+            || self.max_db_size_mbs == 0
+        {
+            Err(Error::ConfigInvalidZeroParameter {
+                config: self.clone(),
+            })
+        } else if self.history_length % self.chunk_size != 0 {
+            Err(Error::ConfigInvalidChunkSize {
+                chunk_size: self.chunk_size,
+                history_length: self.history_length,
+            })
+        } else if self.history_length > crate::array::MAX_DISTANCE as usize {
+            Err(Error::ConfigInvalidHistoryLength {
+                history_length: self.history_length,
+                max_history_length: crate::array::MAX_DISTANCE as usize,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `true` if the min-max target arrays stored using `other` can be read using `self`.
+    pub fn is_compatible(&self, other: &Config) -> bool {
+        self.chunk_size == other.chunk_size
+            && self.validator_chunk_size == other.validator_chunk_size
+            && self.history_length == other.history_length
+    }
+
+    /// The index of the chunk containing `epoch`, within a row of chunks for some validators.
+    pub fn chunk_index(&self, epoch: Epoch) -> usize {
+        (epoch.as_usize() % self.history_length) / self.chunk_size
+    }
+
+    /// The index of the row of chunks containing `validator_index`.
+    pub fn validator_chunk_index(&self, validator_index: u64) -> usize {
+        validator_index as usize / self.validator_chunk_size
+    }
+
+    /// The offset of `epoch` within its chunk.
+    pub fn chunk_offset(&self, epoch: Epoch) -> usize {
+        epoch.as_usize() % self.chunk_size
+    }
+
+    /// The offset of `validator_index` within its chunk.
+    pub fn validator_offset(&self, validator_index: u64) -> usize {
+        validator_index as usize % self.validator_chunk_size
+    }
+
+    /// The key of a chunk in the database.
+    pub fn disk_key(&self, validator_chunk_index: usize, chunk_index: usize) -> usize {
+        validator_chunk_index * (self.history_length / self.chunk_size) + chunk_index
+    }
+
+    /// The index of the cell for a validator and epoch, given their offsets within a chunk.
+    pub fn cell_index(&self, validator_offset: usize, chunk_offset: usize) -> usize {
+        validator_offset * self.chunk_size + chunk_offset
+    }
+
+    /// The number of cells in each chunk.
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_size * self.validator_chunk_size
+    }
+
+    /// The maximum size of the database, in bytes.
+    pub fn max_db_size_bytes(&self) -> usize {
+        self.max_db_size_mbs * 1024 * 1024
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let config = Config::new(PathBuf::new());
+        assert!(config.validate().is_ok());
+
+        let invalid_chunk_size = Config {
+            chunk_size: 15,
+            ..config.clone()
+        };
+        assert!(invalid_chunk_size.validate().is_err());
+
+        let zero = Config {
+            validator_chunk_size: 0,
+            ..config
+        };
+        assert!(zero.validate().is_err());
+    }
+
+    #[test]
+    fn chunk_indices() {
+        let config = Config {
+            chunk_size: 4,
+            validator_chunk_size: 2,
+            history_length: 16,
+            ..Config::new(PathBuf::new())
+        };
+
+        assert_eq!(config.chunk_index(Epoch::new(5)), 1);
+        assert_eq!(config.chunk_index(Epoch::new(21)), 1);
+        assert_eq!(config.chunk_offset(Epoch::new(5)), 1);
+        assert_eq!(config.validator_chunk_index(5), 2);
+        assert_eq!(config.validator_offset(5), 1);
+        assert_eq!(config.disk_key(2, 1), 9);
+        assert_eq!(config.cell_index(1, 1), 5);
+    }
+}
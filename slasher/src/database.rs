@@ -0,0 +1,652 @@
+mod interface;
+mod lmdb_impl;
+#[cfg(feature = "mdbx")]
+mod mdbx_impl;
+
+pub use interface::{Environment, ReadTransaction, RoTransaction, RwTransaction};
+
+use crate::array::lowest_epoch;
+use crate::{AttesterSlashingStatus, Config, DatabaseBackend, Error, ProposerSlashingStatus};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    AttesterSlashing, Epoch, EthSpec, Hash256, IndexedAttestation, ProposerSlashing,
+    SignedBeaconBlockHeader, Slot,
+};
+
+pub(crate) const INDEXED_ATTESTATION_DB: &str = "indexed_attestations";
+pub(crate) const ATTESTERS_DB: &str = "attesters";
+pub(crate) const MIN_TARGETS_DB: &str = "min_targets";
+pub(crate) const MAX_TARGETS_DB: &str = "max_targets";
+pub(crate) const CURRENT_EPOCHS_DB: &str = "current_epochs";
+pub(crate) const PROPOSERS_DB: &str = "proposers";
+pub(crate) const PROPOSER_SLOTS_DB: &str = "proposer_slots";
+pub(crate) const METADATA_DB: &str = "metadata";
+pub(crate) const ATTESTER_SLASHINGS_DB: &str = "attester_slashings";
+pub(crate) const PROPOSER_SLASHINGS_DB: &str = "proposer_slashings";
+
+/// The named databases within the environment.
+const ALL_DBS: [&str; 10] = [
+    INDEXED_ATTESTATION_DB,
+    ATTESTERS_DB,
+    MIN_TARGETS_DB,
+    MAX_TARGETS_DB,
+    CURRENT_EPOCHS_DB,
+    PROPOSERS_DB,
+    PROPOSER_SLOTS_DB,
+    METADATA_DB,
+    ATTESTER_SLASHINGS_DB,
+    PROPOSER_SLASHINGS_DB,
+];
+
+const CONFIG_KEY: &[u8] = b"config";
+const LATEST_EPOCH_KEY: &[u8] = b"latest_epoch";
+
+/// The attestation signed by a validator for some target epoch, stored in the `attesters` database.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct AttesterRecord {
+    /// The root of the `AttestationData` which was signed.
+    pub attestation_data_hash: Hash256,
+    /// The root of the `IndexedAttestation` in which the signature was first seen.
+    pub indexed_attestation_hash: Hash256,
+}
+
+impl AttesterRecord {
+    pub fn new<E: EthSpec>(
+        indexed_attestation: &IndexedAttestation<E>,
+        indexed_attestation_hash: Hash256,
+    ) -> Self {
+        Self {
+            attestation_data_hash: indexed_attestation.data.tree_hash_root(),
+            indexed_attestation_hash,
+        }
+    }
+}
+
+/// Key for the `attesters` database, ordered by target epoch so that old records can be pruned.
+fn attester_key(target_epoch: Epoch, validator_index: u64) -> Vec<u8> {
+    let mut key = target_epoch.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(&validator_index.to_be_bytes());
+    key
+}
+
+/// Key for the `indexed_attestations` database, ordered by target epoch.
+fn indexed_attestation_key(target_epoch: Epoch, indexed_attestation_hash: Hash256) -> Vec<u8> {
+    let mut key = target_epoch.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(indexed_attestation_hash.as_bytes());
+    key
+}
+
+/// Key for the `proposers` database, ordered by slot.
+fn proposer_key(slot: Slot, proposer_index: u64) -> Vec<u8> {
+    let mut key = slot.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(&proposer_index.to_be_bytes());
+    key
+}
+
+/// Key for the `proposer_slots` database, which indexes the `proposers` database by validator.
+fn proposer_slot_key(proposer_index: u64, slot: Slot) -> Vec<u8> {
+    let mut key = proposer_index.to_be_bytes().to_vec();
+    key.extend_from_slice(&slot.as_u64().to_be_bytes());
+    key
+}
+
+/// Key for the `attester_slashings` and `proposer_slashings` databases, ordered by validator.
+fn slashing_key(validator_index: u64, slashing_hash: Hash256) -> Vec<u8> {
+    let mut key = validator_index.to_be_bytes().to_vec();
+    key.extend_from_slice(slashing_hash.as_bytes());
+    key
+}
+
+/// Reads the big-endian `u64` at the start of `key`.
+fn key_prefix(key: &[u8]) -> Result<u64, Error> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(
+        key.get(..8)
+            .ok_or_else(|| Error::InvalidKeyLength { length: key.len() })?,
+    );
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// The on-disk store of the slasher, backed by one of the embedded databases supported by
+/// `DatabaseBackend`.
+///
+/// All reads and writes for a batch of attestations and blocks happen within a single write
+/// transaction, so a crash never leaves the min-max target arrays partially updated.
+#[derive(Debug)]
+pub struct SlasherDB<E: EthSpec> {
+    env: Environment,
+    config: Arc<Config>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> SlasherDB<E> {
+    /// Opens the database at `config.database_path`, creating it if it does not exist.
+    ///
+    /// Returns an error if the existing database was created with an incompatible config or
+    /// another backend.
+    pub fn open(config: Arc<Config>) -> Result<Self, Error> {
+        std::fs::create_dir_all(&config.database_path)?;
+        Environment::check_path(config.backend, &config.database_path)?;
+
+        let db = Self {
+            env: Environment::open(&config)?,
+            config,
+            _phantom: PhantomData,
+        };
+
+        let mut txn = db.begin_rw_txn()?;
+        db.check_and_store_config(&mut txn)?;
+        txn.commit()?;
+
+        Ok(db)
+    }
+
+    pub fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        self.env.begin_rw_txn()
+    }
+
+    /// Begins a read-only transaction, which neither blocks nor waits for write transactions.
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction<'_>, Error> {
+        self.env.begin_ro_txn()
+    }
+
+    /// Stores `self.config` in the database, or checks that it is compatible with the config
+    /// already stored there.
+    fn check_and_store_config(&self, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        if let Some(bytes) = txn.get(METADATA_DB, CONFIG_KEY)? {
+            let on_disk_config: Config = serde_json::from_slice(&bytes)
+                .map_err(|e| Error::ConfigSerializationError(format!("{:?}", e)))?;
+
+            if !self.config.is_compatible(&on_disk_config) {
+                return Err(Error::ConfigIncompatible {
+                    on_disk_config,
+                    config: (*self.config).clone(),
+                });
+            }
+        }
+
+        let bytes = serde_json::to_vec(&*self.config)
+            .map_err(|e| Error::ConfigSerializationError(format!("{:?}", e)))?;
+        txn.put(METADATA_DB, CONFIG_KEY, &bytes)
+    }
+
+    /// Returns the latest epoch at which the slasher has processed attestations, if ever.
+    pub fn get_latest_epoch(&self, txn: &impl ReadTransaction) -> Result<Option<Epoch>, Error> {
+        txn.get(METADATA_DB, LATEST_EPOCH_KEY)?
+            .map(|bytes| Epoch::from_ssz_bytes(&bytes).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Records `epoch` as the latest epoch processed, unless a later epoch has been processed.
+    pub fn update_latest_epoch(
+        &self,
+        epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        if self
+            .get_latest_epoch(txn)?
+            .map_or(true, |latest| epoch > latest)
+        {
+            txn.put(METADATA_DB, LATEST_EPOCH_KEY, &epoch.as_ssz_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the epoch at which the min-max target arrays of `validator_index` were last
+    /// updated, if ever.
+    pub fn get_current_epoch_for_validator(
+        &self,
+        validator_index: u64,
+        txn: &impl ReadTransaction,
+    ) -> Result<Option<Epoch>, Error> {
+        txn.get(CURRENT_EPOCHS_DB, &validator_index.to_be_bytes())?
+            .map(|bytes| Epoch::from_ssz_bytes(&bytes).map_err(Into::into))
+            .transpose()
+    }
+
+    pub fn update_current_epoch_for_validator(
+        &self,
+        validator_index: u64,
+        current_epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            CURRENT_EPOCHS_DB,
+            &validator_index.to_be_bytes(),
+            &current_epoch.as_ssz_bytes(),
+        )
+    }
+
+    /// Loads a chunk of the min or max target array, returning `None` if it has never been
+    /// stored.
+    pub fn get_chunk(
+        &self,
+        db: &'static str,
+        disk_key: usize,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<Option<Vec<u16>>, Error> {
+        txn.get(db, &(disk_key as u64).to_be_bytes())?
+            .map(|bytes| {
+                if bytes.len() != self.config.chunk_len() * 2 {
+                    return Err(Error::InvalidChunkLength {
+                        expected: self.config.chunk_len(),
+                        actual: bytes.len() / 2,
+                    });
+                }
+                Ok(bytes
+                    .chunks_exact(2)
+                    .map(|cell| u16::from_le_bytes([cell[0], cell[1]]))
+                    .collect())
+            })
+            .transpose()
+    }
+
+    pub fn put_chunk(
+        &self,
+        db: &'static str,
+        disk_key: usize,
+        chunk: &[u16],
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        let bytes = chunk
+            .iter()
+            .flat_map(|cell| cell.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+        txn.put(db, &(disk_key as u64).to_be_bytes(), &bytes)
+    }
+
+    /// Stores `indexed_attestation`, unless it is already stored.
+    pub fn store_indexed_attestation(
+        &self,
+        indexed_attestation_hash: Hash256,
+        indexed_attestation: &IndexedAttestation<E>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        let key = indexed_attestation_key(
+            indexed_attestation.data.target.epoch,
+            indexed_attestation_hash,
+        );
+        if txn.get(INDEXED_ATTESTATION_DB, &key)?.is_some() {
+            return Ok(());
+        }
+        txn.put(
+            INDEXED_ATTESTATION_DB,
+            &key,
+            &indexed_attestation.as_ssz_bytes(),
+        )
+    }
+
+    pub fn get_indexed_attestation(
+        &self,
+        target_epoch: Epoch,
+        indexed_attestation_hash: Hash256,
+        txn: &impl ReadTransaction,
+    ) -> Result<IndexedAttestation<E>, Error> {
+        let key = indexed_attestation_key(target_epoch, indexed_attestation_hash);
+        let bytes = txn.get(INDEXED_ATTESTATION_DB, &key)?.ok_or_else(|| {
+            Error::MissingIndexedAttestation {
+                root: indexed_attestation_hash,
+            }
+        })?;
+        Ok(IndexedAttestation::from_ssz_bytes(&bytes)?)
+    }
+
+    pub fn get_attester_record(
+        &self,
+        validator_index: u64,
+        target_epoch: Epoch,
+        txn: &impl ReadTransaction,
+    ) -> Result<Option<AttesterRecord>, Error> {
+        txn.get(ATTESTERS_DB, &attester_key(target_epoch, validator_index))?
+            .map(|bytes| AttesterRecord::from_ssz_bytes(&bytes).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Returns the attestation signed by `validator_index` with the given `target_epoch`.
+    ///
+    /// Returns an error if the validator has not signed an attestation with that target.
+    pub fn get_attestation_for_validator(
+        &self,
+        validator_index: u64,
+        target_epoch: Epoch,
+        txn: &impl ReadTransaction,
+    ) -> Result<IndexedAttestation<E>, Error> {
+        let record = self
+            .get_attester_record(validator_index, target_epoch, txn)?
+            .ok_or_else(|| Error::MissingAttesterRecord {
+                validator_index,
+                target_epoch,
+            })?;
+        self.get_indexed_attestation(target_epoch, record.indexed_attestation_hash, txn)
+    }
+
+    /// Checks whether `validator_index` has already signed a different attestation with the same
+    /// target as `indexed_attestation`, recording the attestation if it has not.
+    pub fn check_and_update_attester_record(
+        &self,
+        validator_index: u64,
+        indexed_attestation: &IndexedAttestation<E>,
+        record: AttesterRecord,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<AttesterSlashingStatus<E>, Error> {
+        let target_epoch = indexed_attestation.data.target.epoch;
+
+        if let Some(existing_record) =
+            self.get_attester_record(validator_index, target_epoch, txn)?
+        {
+            if existing_record.attestation_data_hash == record.attestation_data_hash {
+                return Ok(AttesterSlashingStatus::NotSlashable);
+            }
+
+            let existing_attestation = self.get_indexed_attestation(
+                target_epoch,
+                existing_record.indexed_attestation_hash,
+                txn,
+            )?;
+            return Ok(AttesterSlashingStatus::DoubleVote(Box::new(
+                existing_attestation,
+            )));
+        }
+
+        txn.put(
+            ATTESTERS_DB,
+            &attester_key(target_epoch, validator_index),
+            &record.as_ssz_bytes(),
+        )?;
+
+        Ok(AttesterSlashingStatus::NotSlashable)
+    }
+
+    /// Deletes the attestations with a target epoch before `min_target_epoch` and the blocks with a
+    /// slot before `min_slot`.
+    ///
+    /// Returns the number of attestation and block records deleted.
+    pub fn prune(
+        &self,
+        min_target_epoch: Epoch,
+        min_slot: Slot,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(usize, usize), Error> {
+        let num_attestations = txn.delete_while(INDEXED_ATTESTATION_DB, |key| {
+            Ok(key_prefix(key)? < min_target_epoch.as_u64())
+        })?;
+        txn.delete_while(ATTESTERS_DB, |key| {
+            Ok(key_prefix(key)? < min_target_epoch.as_u64())
+        })?;
+
+        let mut pruned_blocks = vec![];
+        txn.delete_while(PROPOSERS_DB, |key| {
+            let slot = key_prefix(key)?;
+            if slot < min_slot.as_u64() {
+                pruned_blocks.push(key.to_vec());
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })?;
+        for key in &pruned_blocks {
+            let proposer_index = key
+                .get(8..)
+                .ok_or_else(|| Error::InvalidKeyLength { length: key.len() })
+                .and_then(key_prefix)?;
+            let slot = Slot::new(key_prefix(key)?);
+            txn.delete(PROPOSER_SLOTS_DB, &proposer_slot_key(proposer_index, slot))?;
+        }
+
+        Ok((num_attestations, pruned_blocks.len()))
+    }
+
+    /// Deletes the attester slashings whose attestations both have a target epoch before
+    /// `min_target_epoch`, and the proposer slashings for a slot before `min_slot`.
+    ///
+    /// Returns the number of slashings deleted.
+    pub fn prune_slashings(
+        &self,
+        min_target_epoch: Epoch,
+        min_slot: Slot,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<usize, Error> {
+        // The slashings are ordered by validator, so each table is scanned in full. They are
+        // expected to be rare enough for this to be cheap.
+        let mut expired_attester_slashings = vec![];
+        txn.for_each(ATTESTER_SLASHINGS_DB, |key, value| {
+            let slashing = AttesterSlashing::<E>::from_ssz_bytes(value)?;
+            let target_epoch = std::cmp::max(
+                slashing.attestation_1.data.target.epoch,
+                slashing.attestation_2.data.target.epoch,
+            );
+            if target_epoch < min_target_epoch {
+                expired_attester_slashings.push(key.to_vec());
+            }
+            Ok(())
+        })?;
+
+        let mut expired_proposer_slashings = vec![];
+        txn.for_each(PROPOSER_SLASHINGS_DB, |key, value| {
+            let slashing = ProposerSlashing::from_ssz_bytes(value)?;
+            if slashing.signed_header_1.message.slot < min_slot {
+                expired_proposer_slashings.push(key.to_vec());
+            }
+            Ok(())
+        })?;
+
+        for key in &expired_attester_slashings {
+            txn.delete(ATTESTER_SLASHINGS_DB, key)?;
+        }
+        for key in &expired_proposer_slashings {
+            txn.delete(PROPOSER_SLASHINGS_DB, key)?;
+        }
+
+        Ok(expired_attester_slashings.len() + expired_proposer_slashings.len())
+    }
+
+    /// Returns the attestations signed by `validator_index` which are still within the history
+    /// length, in order of target epoch.
+    pub fn get_attestations_for_validator(
+        &self,
+        validator_index: u64,
+        txn: &impl ReadTransaction,
+    ) -> Result<Vec<IndexedAttestation<E>>, Error> {
+        let current_epoch = match self.get_current_epoch_for_validator(validator_index, txn)? {
+            Some(epoch) => epoch,
+            None => return Ok(vec![]),
+        };
+
+        let mut attestations = vec![];
+        let mut target_epoch = lowest_epoch(current_epoch, &self.config);
+        while target_epoch <= current_epoch {
+            if let Some(record) = self.get_attester_record(validator_index, target_epoch, txn)? {
+                attestations.push(self.get_indexed_attestation(
+                    target_epoch,
+                    record.indexed_attestation_hash,
+                    txn,
+                )?);
+            }
+            target_epoch += 1;
+        }
+        Ok(attestations)
+    }
+
+    /// Returns the block headers signed by `proposer_index` which are still within the history
+    /// length, in order of slot.
+    pub fn get_blocks_for_proposer(
+        &self,
+        proposer_index: u64,
+        txn: &impl ReadTransaction,
+    ) -> Result<Vec<SignedBeaconBlockHeader>, Error> {
+        let mut slots = vec![];
+        txn.for_each_with_prefix(
+            PROPOSER_SLOTS_DB,
+            &proposer_index.to_be_bytes(),
+            |key, _| {
+                let slot = key
+                    .get(8..)
+                    .ok_or_else(|| Error::InvalidKeyLength { length: key.len() })
+                    .and_then(key_prefix)?;
+                slots.push(Slot::new(slot));
+                Ok(())
+            },
+        )?;
+
+        let mut blocks = vec![];
+        for slot in slots {
+            if let Some(bytes) = txn.get(PROPOSERS_DB, &proposer_key(slot, proposer_index))? {
+                blocks.push(SignedBeaconBlockHeader::from_ssz_bytes(&bytes)?);
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// Records that `validator_index` is slashable by `slashing`.
+    pub fn store_attester_slashing(
+        &self,
+        validator_index: u64,
+        slashing: &AttesterSlashing<E>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            ATTESTER_SLASHINGS_DB,
+            &slashing_key(validator_index, slashing.tree_hash_root()),
+            &slashing.as_ssz_bytes(),
+        )
+    }
+
+    /// Records that the proposer of the blocks in `slashing` is slashable.
+    pub fn store_proposer_slashing(
+        &self,
+        slashing: &ProposerSlashing,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            PROPOSER_SLASHINGS_DB,
+            &slashing_key(
+                slashing.signed_header_1.message.proposer_index,
+                slashing.tree_hash_root(),
+            ),
+            &slashing.as_ssz_bytes(),
+        )
+    }
+
+    /// Returns the attester slashings found for `validator_index` which are still within the
+    /// history length.
+    pub fn get_attester_slashings_for_validator(
+        &self,
+        validator_index: u64,
+        txn: &impl ReadTransaction,
+    ) -> Result<Vec<AttesterSlashing<E>>, Error> {
+        let mut slashings = vec![];
+        txn.for_each_with_prefix(
+            ATTESTER_SLASHINGS_DB,
+            &validator_index.to_be_bytes(),
+            |_, value| {
+                slashings.push(AttesterSlashing::from_ssz_bytes(value)?);
+                Ok(())
+            },
+        )?;
+        Ok(slashings)
+    }
+
+    /// Returns the proposer slashings found for `proposer_index` which are still within the
+    /// history length.
+    pub fn get_proposer_slashings_for_validator(
+        &self,
+        proposer_index: u64,
+        txn: &impl ReadTransaction,
+    ) -> Result<Vec<ProposerSlashing>, Error> {
+        let mut slashings = vec![];
+        txn.for_each_with_prefix(
+            PROPOSER_SLASHINGS_DB,
+            &proposer_index.to_be_bytes(),
+            |_, value| {
+                slashings.push(ProposerSlashing::from_ssz_bytes(value)?);
+                Ok(())
+            },
+        )?;
+        Ok(slashings)
+    }
+
+    /// Checks whether the proposer of `block_header` has already signed a different block for
+    /// the same slot, recording the header if it has not.
+    pub fn check_or_insert_block_proposal(
+        &self,
+        block_header: SignedBeaconBlockHeader,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<ProposerSlashingStatus, Error> {
+        let key = proposer_key(
+            block_header.message.slot,
+            block_header.message.proposer_index,
+        );
+
+        if let Some(bytes) = txn.get(PROPOSERS_DB, &key)? {
+            let existing_header = SignedBeaconBlockHeader::from_ssz_bytes(&bytes)?;
+
+            return if existing_header.message == block_header.message {
+                Ok(ProposerSlashingStatus::NotSlashable)
+            } else {
+                Ok(ProposerSlashingStatus::DoubleProposal(Box::new(
+                    ProposerSlashing {
+                        signed_header_1: existing_header,
+                        signed_header_2: block_header,
+                    },
+                )))
+            };
+        }
+
+        txn.put(PROPOSERS_DB, &key, &block_header.as_ssz_bytes())?;
+        txn.put(
+            PROPOSER_SLOTS_DB,
+            &proposer_slot_key(
+                block_header.message.proposer_index,
+                block_header.message.slot,
+            ),
+            &[],
+        )?;
+
+        Ok(ProposerSlashingStatus::NotSlashable)
+    }
+}
+
+/// Copies the slasher database at `config.database_path` from `config.backend` to the `to`
+/// backend, leaving the original environment in place.
+///
+/// Returns the number of records copied.
+pub fn migrate_backend(config: &Config, to: DatabaseBackend) -> Result<usize, Error> {
+    let path = &config.database_path;
+    if !Environment::exists(config.backend, path) {
+        return Err(Error::BackendMigrationSourceMissing {
+            path: path.clone(),
+            backend: config.backend,
+        });
+    }
+    if Environment::exists(to, path) {
+        return Err(Error::BackendMigrationTargetExists {
+            path: path.clone(),
+            backend: to,
+        });
+    }
+
+    let target_config = Config {
+        backend: to,
+        ..config.clone()
+    };
+    let source = Environment::open(config)?;
+    let target = Environment::open(&target_config)?;
+
+    let source_txn = source.begin_ro_txn()?;
+    let mut target_txn = target.begin_rw_txn()?;
+    let mut num_records = 0;
+
+    for &db in ALL_DBS.iter() {
+        source_txn.for_each(db, |key, value| {
+            num_records += 1;
+            target_txn.put(db, key, value)
+        })?;
+    }
+
+    target_txn.commit()?;
+    Ok(num_records)
+}
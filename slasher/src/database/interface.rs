@@ -0,0 +1,205 @@
+//! A minimal interface over the embedded databases supported by the slasher, as selected at
+//! runtime by `DatabaseBackend`.
+//!
+//! LMDB is always available, whilst MDBX requires the `mdbx` feature.
+//!
+//! Each backend stores the named databases listed in `ALL_DBS` within a single environment. The
+//! records of the slasher are written within write transactions, whilst queries which only read
+//! the records use read-only transactions, so that they do not block (or wait for) the slasher.
+
+use super::lmdb_impl;
+#[cfg(feature = "mdbx")]
+use super::mdbx_impl;
+use crate::{Config, DatabaseBackend, Error};
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Runs `$body` with `$inner` bound to the backend-specific value wrapped by `$value`.
+macro_rules! with_backend {
+    ($enum: ident, $value: expr, $inner: ident => $body: expr) => {
+        match $value {
+            $enum::Lmdb($inner) => $body,
+            #[cfg(feature = "mdbx")]
+            $enum::Mdbx($inner) => $body,
+        }
+    };
+}
+
+#[derive(Debug)]
+pub enum Environment {
+    Lmdb(lmdb_impl::Environment),
+    #[cfg(feature = "mdbx")]
+    Mdbx(mdbx_impl::Environment),
+}
+
+pub enum RwTransaction<'env> {
+    Lmdb(lmdb_impl::RwTransaction<'env>),
+    #[cfg(feature = "mdbx")]
+    Mdbx(mdbx_impl::RwTransaction<'env>),
+}
+
+pub enum RoTransaction<'env> {
+    Lmdb(lmdb_impl::RoTransaction<'env>),
+    #[cfg(feature = "mdbx")]
+    Mdbx(mdbx_impl::RoTransaction<'env>),
+}
+
+/// The reads supported by both read-only and write transactions.
+pub trait ReadTransaction {
+    /// Reads `key` from the database named `db`, returning `None` if it is not present.
+    fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error>;
+
+    /// Calls `f` with each key and value of the database named `db`, in key order.
+    fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error>;
+
+    /// Calls `f` with each key and value of the database named `db` whose key starts with
+    /// `prefix`, in key order.
+    ///
+    /// Only the entries with the prefix are visited, rather than the whole database.
+    fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error>;
+}
+
+impl Environment {
+    /// Opens the environment at `config.database_path` using `config.backend`, creating the
+    /// environment and its named databases if they do not exist.
+    pub fn open(config: &Config) -> Result<Self, Error> {
+        match config.backend {
+            DatabaseBackend::Lmdb => Ok(Environment::Lmdb(lmdb_impl::Environment::open(config)?)),
+            #[cfg(feature = "mdbx")]
+            DatabaseBackend::Mdbx => Ok(Environment::Mdbx(mdbx_impl::Environment::open(config)?)),
+            #[cfg(not(feature = "mdbx"))]
+            DatabaseBackend::Mdbx => Err(Error::BackendUnavailable(config.backend)),
+        }
+    }
+
+    /// Returns `true` if an environment of the given `backend` kind exists at `path`.
+    pub fn exists(backend: DatabaseBackend, path: &Path) -> bool {
+        match backend {
+            DatabaseBackend::Lmdb => lmdb_impl::Environment::exists(path),
+            #[cfg(feature = "mdbx")]
+            DatabaseBackend::Mdbx => mdbx_impl::Environment::exists(path),
+            #[cfg(not(feature = "mdbx"))]
+            DatabaseBackend::Mdbx => false,
+        }
+    }
+
+    /// Check that we aren't about to initialize an empty `backend` environment at `path` whilst
+    /// there is an environment of another kind present, which would silently discard the
+    /// slasher's records.
+    pub fn check_path(backend: DatabaseBackend, path: &Path) -> Result<(), Error> {
+        if Self::exists(backend, path) {
+            return Ok(());
+        }
+
+        match DatabaseBackend::ALL
+            .iter()
+            .find(|other| **other != backend && Self::exists(**other, path))
+        {
+            Some(on_disk) => Err(Error::BackendMismatch {
+                path: path.to_path_buf(),
+                configured: backend,
+                on_disk: *on_disk,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        match self {
+            Environment::Lmdb(env) => Ok(RwTransaction::Lmdb(env.begin_rw_txn()?)),
+            #[cfg(feature = "mdbx")]
+            Environment::Mdbx(env) => Ok(RwTransaction::Mdbx(env.begin_rw_txn()?)),
+        }
+    }
+
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction<'_>, Error> {
+        match self {
+            Environment::Lmdb(env) => Ok(RoTransaction::Lmdb(env.begin_ro_txn()?)),
+            #[cfg(feature = "mdbx")]
+            Environment::Mdbx(env) => Ok(RoTransaction::Mdbx(env.begin_ro_txn()?)),
+        }
+    }
+}
+
+impl<'env> ReadTransaction for RwTransaction<'env> {
+    fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        with_backend!(RwTransaction, self, txn => txn.get(db, key))
+    }
+
+    fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        with_backend!(RwTransaction, self, txn => txn.for_each(db, f))
+    }
+
+    fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        with_backend!(RwTransaction, self, txn => txn.for_each_with_prefix(db, prefix, f))
+    }
+}
+
+impl<'env> ReadTransaction for RoTransaction<'env> {
+    fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        with_backend!(RoTransaction, self, txn => txn.get(db, key))
+    }
+
+    fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        with_backend!(RoTransaction, self, txn => txn.for_each(db, f))
+    }
+
+    fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        with_backend!(RoTransaction, self, txn => txn.for_each_with_prefix(db, prefix, f))
+    }
+}
+
+impl<'env> RwTransaction<'env> {
+    /// Writes `value` to `key` in the database named `db`, replacing any existing value.
+    pub fn put(&mut self, db: &'static str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        with_backend!(RwTransaction, self, txn => txn.put(db, key, value))
+    }
+
+    /// Deletes `key` from the database named `db`, if it is present.
+    pub fn delete(&mut self, db: &'static str, key: &[u8]) -> Result<(), Error> {
+        with_backend!(RwTransaction, self, txn => txn.delete(db, key))
+    }
+
+    /// Deletes entries from the start of the database named `db`, in key order, for as long as
+    /// `predicate` returns `true` for their keys.
+    ///
+    /// Returns the number of entries deleted.
+    pub fn delete_while(
+        &mut self,
+        db: &'static str,
+        predicate: impl FnMut(&[u8]) -> Result<bool, Error>,
+    ) -> Result<usize, Error> {
+        with_backend!(RwTransaction, self, txn => txn.delete_while(db, predicate))
+    }
+
+    pub fn commit(self) -> Result<(), Error> {
+        with_backend!(RwTransaction, self, txn => txn.commit())
+    }
+}
@@ -0,0 +1,223 @@
+//! The LMDB backend of the slasher database.
+
+use super::ALL_DBS;
+use crate::{Config, Error};
+use lmdb::{Cursor, Database, DatabaseFlags, Transaction, WriteFlags};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The name of the file in which LMDB stores its data.
+const DATA_FILENAME: &str = "data.mdb";
+
+#[derive(Debug)]
+pub struct Environment {
+    env: lmdb::Environment,
+    dbs: HashMap<&'static str, Database>,
+}
+
+pub struct RwTransaction<'env> {
+    txn: lmdb::RwTransaction<'env>,
+    dbs: &'env HashMap<&'static str, Database>,
+}
+
+pub struct RoTransaction<'env> {
+    txn: lmdb::RoTransaction<'env>,
+    dbs: &'env HashMap<&'static str, Database>,
+}
+
+/// Converts an LMDB `NotFound` error into `None`.
+fn optional<T>(result: Result<T, lmdb::Error>) -> Result<Option<T>, Error> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl Environment {
+    pub fn open(config: &Config) -> Result<Self, Error> {
+        let env = lmdb::Environment::new()
+            .set_max_dbs(ALL_DBS.len() as u32)
+            .set_map_size(config.max_db_size_bytes())
+            .open(&config.database_path)?;
+
+        let mut dbs = HashMap::new();
+        for &name in ALL_DBS.iter() {
+            dbs.insert(name, env.create_db(Some(name), DatabaseFlags::default())?);
+        }
+
+        Ok(Self { env, dbs })
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        path.join(DATA_FILENAME).exists()
+    }
+
+    pub fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        Ok(RwTransaction {
+            txn: self.env.begin_rw_txn()?,
+            dbs: &self.dbs,
+        })
+    }
+
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction<'_>, Error> {
+        Ok(RoTransaction {
+            txn: self.env.begin_ro_txn()?,
+            dbs: &self.dbs,
+        })
+    }
+}
+
+fn db(dbs: &HashMap<&'static str, Database>, name: &'static str) -> Result<Database, Error> {
+    dbs.get(name)
+        .copied()
+        .ok_or_else(|| Error::MissingDatabase { name })
+}
+
+fn get<'txn>(
+    txn: &'txn impl Transaction,
+    db: Database,
+    key: &[u8],
+) -> Result<Option<Cow<'txn, [u8]>>, Error> {
+    Ok(optional(txn.get(db, &key))?.map(Cow::Borrowed))
+}
+
+fn for_each(
+    txn: &impl Transaction,
+    db: Database,
+    mut f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let cursor = txn.open_ro_cursor(db)?;
+    let mut entry = optional(cursor.get(None, None, lmdb_sys::MDB_FIRST))?;
+
+    while let Some((key, value)) = entry {
+        f(key.ok_or_else(|| Error::MissingKey)?, value)?;
+        entry = optional(cursor.get(None, None, lmdb_sys::MDB_NEXT))?;
+    }
+
+    Ok(())
+}
+
+fn for_each_with_prefix(
+    txn: &impl Transaction,
+    db: Database,
+    prefix: &[u8],
+    mut f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let cursor = txn.open_ro_cursor(db)?;
+    // Position the cursor at the first key which is greater than or equal to `prefix`.
+    let mut entry = optional(cursor.get(Some(prefix), None, lmdb_sys::MDB_SET_RANGE))?;
+
+    while let Some((key, value)) = entry {
+        let key = key.ok_or_else(|| Error::MissingKey)?;
+        if !key.starts_with(prefix) {
+            break;
+        }
+        f(key, value)?;
+        entry = optional(cursor.get(None, None, lmdb_sys::MDB_NEXT))?;
+    }
+
+    Ok(())
+}
+
+impl<'env> RoTransaction<'env> {
+    pub fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        get(&self.txn, self::db(self.dbs, db)?, key)
+    }
+
+    pub fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each(&self.txn, self::db(self.dbs, db)?, f)
+    }
+
+    pub fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each_with_prefix(&self.txn, self::db(self.dbs, db)?, prefix, f)
+    }
+}
+
+impl<'env> RwTransaction<'env> {
+    fn db(&self, name: &'static str) -> Result<Database, Error> {
+        db(self.dbs, name)
+    }
+
+    pub fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        get(&self.txn, self.db(db)?, key)
+    }
+
+    pub fn put(&mut self, db: &'static str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let db = self.db(db)?;
+        self.txn.put(db, &key, &value, WriteFlags::empty())?;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, db: &'static str, key: &[u8]) -> Result<(), Error> {
+        let db = self.db(db)?;
+        optional(self.txn.del(db, &key, None))?;
+        Ok(())
+    }
+
+    pub fn delete_while(
+        &mut self,
+        db: &'static str,
+        mut predicate: impl FnMut(&[u8]) -> Result<bool, Error>,
+    ) -> Result<usize, Error> {
+        let db = self.db(db)?;
+        let mut cursor = self.txn.open_rw_cursor(db)?;
+        let mut num_deleted = 0;
+
+        // Position the cursor at the first key, bailing out if the database is empty.
+        if optional(cursor.get(None, None, lmdb_sys::MDB_FIRST))?.is_none() {
+            return Ok(num_deleted);
+        }
+
+        loop {
+            let key = cursor
+                .get(None, None, lmdb_sys::MDB_GET_CURRENT)?
+                .0
+                .ok_or_else(|| Error::MissingKey)?;
+            if !predicate(key)? {
+                break;
+            }
+
+            cursor.del(WriteFlags::empty())?;
+            num_deleted += 1;
+
+            if optional(cursor.get(None, None, lmdb_sys::MDB_NEXT))?.is_none() {
+                break;
+            }
+        }
+
+        Ok(num_deleted)
+    }
+
+    pub fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each(&self.txn, self.db(db)?, f)
+    }
+
+    pub fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each_with_prefix(&self.txn, self.db(db)?, prefix, f)
+    }
+
+    pub fn commit(self) -> Result<(), Error> {
+        self.txn.commit()?;
+        Ok(())
+    }
+}
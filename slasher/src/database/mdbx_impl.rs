@@ -0,0 +1,201 @@
+//! The MDBX backend of the slasher database.
+//!
+//! Unlike LMDB, MDBX grows its data file as required, up to the configured maximum size.
+
+use super::ALL_DBS;
+use crate::{Config, Error};
+use libmdbx::{DatabaseFlags, Geometry, NoWriteMap, TransactionKind, WriteFlags, RO, RW};
+use std::borrow::Cow;
+use std::path::Path;
+
+/// The name of the file in which MDBX stores its data.
+const DATA_FILENAME: &str = "mdbx.dat";
+
+/// The amount by which the data file grows each time it runs out of space.
+const GROWTH_STEP: isize = 256 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct Environment {
+    env: libmdbx::Environment<NoWriteMap>,
+}
+
+pub struct RwTransaction<'env> {
+    txn: libmdbx::Transaction<'env, RW, NoWriteMap>,
+}
+
+pub struct RoTransaction<'env> {
+    txn: libmdbx::Transaction<'env, RO, NoWriteMap>,
+}
+
+impl Environment {
+    pub fn open(config: &Config) -> Result<Self, Error> {
+        let env = libmdbx::Environment::new()
+            .set_max_dbs(ALL_DBS.len())
+            .set_geometry(Geometry {
+                size: Some(0..config.max_db_size_bytes()),
+                growth_step: Some(GROWTH_STEP),
+                shrink_threshold: None,
+                page_size: None,
+            })
+            .open(&config.database_path)?;
+
+        let txn = env.begin_rw_txn()?;
+        for &name in ALL_DBS.iter() {
+            txn.create_db(Some(name), DatabaseFlags::default())?;
+        }
+        txn.commit()?;
+
+        Ok(Self { env })
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        path.join(DATA_FILENAME).exists()
+    }
+
+    pub fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        Ok(RwTransaction {
+            txn: self.env.begin_rw_txn()?,
+        })
+    }
+
+    pub fn begin_ro_txn(&self) -> Result<RoTransaction<'_>, Error> {
+        Ok(RoTransaction {
+            txn: self.env.begin_ro_txn()?,
+        })
+    }
+}
+
+fn get<'txn, K: TransactionKind>(
+    txn: &'txn libmdbx::Transaction<'_, K, NoWriteMap>,
+    db: &'static str,
+    key: &[u8],
+) -> Result<Option<Cow<'txn, [u8]>>, Error> {
+    let db = txn.open_db(Some(db))?;
+    Ok(txn.get(&db, key)?)
+}
+
+fn for_each<K: TransactionKind>(
+    txn: &libmdbx::Transaction<'_, K, NoWriteMap>,
+    db: &'static str,
+    mut f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let db = txn.open_db(Some(db))?;
+    let mut cursor = txn.cursor(&db)?;
+
+    let mut entry = cursor.first::<Cow<[u8]>, Cow<[u8]>>()?;
+    while let Some((key, value)) = entry {
+        f(&key, &value)?;
+        entry = cursor.next()?;
+    }
+
+    Ok(())
+}
+
+fn for_each_with_prefix<K: TransactionKind>(
+    txn: &libmdbx::Transaction<'_, K, NoWriteMap>,
+    db: &'static str,
+    prefix: &[u8],
+    mut f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let db = txn.open_db(Some(db))?;
+    let mut cursor = txn.cursor(&db)?;
+
+    // Position the cursor at the first key which is greater than or equal to `prefix`.
+    let mut entry = cursor.set_range::<Cow<[u8]>, Cow<[u8]>>(prefix)?;
+    while let Some((key, value)) = entry {
+        if !key.starts_with(prefix) {
+            break;
+        }
+        f(&key, &value)?;
+        entry = cursor.next()?;
+    }
+
+    Ok(())
+}
+
+impl<'env> RoTransaction<'env> {
+    pub fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        get(&self.txn, db, key)
+    }
+
+    pub fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each(&self.txn, db, f)
+    }
+
+    pub fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each_with_prefix(&self.txn, db, prefix, f)
+    }
+}
+
+impl<'env> RwTransaction<'env> {
+    pub fn get(&self, db: &'static str, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        get(&self.txn, db, key)
+    }
+
+    pub fn put(&mut self, db: &'static str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let db = self.txn.open_db(Some(db))?;
+        self.txn.put(&db, key, value, WriteFlags::empty())?;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, db: &'static str, key: &[u8]) -> Result<(), Error> {
+        let db = self.txn.open_db(Some(db))?;
+        self.txn.del(&db, key, None)?;
+        Ok(())
+    }
+
+    pub fn delete_while(
+        &mut self,
+        db: &'static str,
+        mut predicate: impl FnMut(&[u8]) -> Result<bool, Error>,
+    ) -> Result<usize, Error> {
+        let db = self.txn.open_db(Some(db))?;
+        let mut cursor = self.txn.cursor(&db)?;
+        let mut num_deleted = 0;
+
+        let mut entry = cursor.first::<Cow<[u8]>, Cow<[u8]>>()?;
+        while let Some((key, _)) = entry {
+            if !predicate(&key)? {
+                break;
+            }
+
+            cursor.del(WriteFlags::empty())?;
+            num_deleted += 1;
+
+            entry = cursor.next()?;
+        }
+
+        Ok(num_deleted)
+    }
+
+    pub fn for_each(
+        &self,
+        db: &'static str,
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each(&self.txn, db, f)
+    }
+
+    pub fn for_each_with_prefix(
+        &self,
+        db: &'static str,
+        prefix: &[u8],
+        f: impl FnMut(&[u8], &[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        for_each_with_prefix(&self.txn, db, prefix, f)
+    }
+
+    pub fn commit(self) -> Result<(), Error> {
+        self.txn.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Config, DatabaseBackend};
+use std::io;
+use std::path::PathBuf;
+use types::{Epoch, Hash256};
+
+#[derive(Debug)]
+pub enum Error {
+    DatabaseLmdbError(lmdb::Error),
+    #[cfg(feature = "mdbx")]
+    DatabaseMdbxError(libmdbx::Error),
+    DatabaseIOError(io::Error),
+    SszDecodeError(ssz::DecodeError),
+    ConfigInvalidChunkSize {
+        chunk_size: usize,
+        history_length: usize,
+    },
+    ConfigInvalidHistoryLength {
+        history_length: usize,
+        max_history_length: usize,
+    },
+    ConfigInvalidZeroParameter {
+        config: Config,
+    },
+    ConfigSerializationError(String),
+    /// The database was created using a config whose min-max target arrays have a different
+    /// layout.
+    ConfigIncompatible {
+        on_disk_config: Config,
+        config: Config,
+    },
+    /// A chunk read from the database does not have the expected number of cells.
+    InvalidChunkLength {
+        expected: usize,
+        actual: usize,
+    },
+    /// The distance from an epoch to a target epoch does not fit in a chunk cell.
+    DistanceTooLarge {
+        epoch: Epoch,
+        target_epoch: Epoch,
+    },
+    /// The min-max target arrays refer to an attestation which is not in the database.
+    MissingAttesterRecord {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+    MissingIndexedAttestation {
+        root: Hash256,
+    },
+    /// A database cursor did not return the key of its current entry.
+    MissingKey,
+    /// A database key is too short to begin with an epoch or slot.
+    InvalidKeyLength {
+        length: usize,
+    },
+    /// One of the named databases was not created when the environment was opened.
+    MissingDatabase {
+        name: &'static str,
+    },
+    /// The configured backend was not enabled at compile time.
+    BackendUnavailable(DatabaseBackend),
+    /// The slasher directory contains a database created by a different backend.
+    BackendMismatch {
+        path: PathBuf,
+        configured: DatabaseBackend,
+        on_disk: DatabaseBackend,
+    },
+    BackendMigrationSourceMissing {
+        path: PathBuf,
+        backend: DatabaseBackend,
+    },
+    BackendMigrationTargetExists {
+        path: PathBuf,
+        backend: DatabaseBackend,
+    },
+}
+
+impl From<lmdb::Error> for Error {
+    fn from(e: lmdb::Error) -> Self {
+        match e {
+            lmdb::Error::Other(os_error) => {
+                Error::DatabaseIOError(io::Error::from_raw_os_error(os_error))
+            }
+            _ => Error::DatabaseLmdbError(e),
+        }
+    }
+}
+
+#[cfg(feature = "mdbx")]
+impl From<libmdbx::Error> for Error {
+    fn from(e: libmdbx::Error) -> Self {
+        match e {
+            libmdbx::Error::Other(os_error) => {
+                Error::DatabaseIOError(io::Error::from_raw_os_error(os_error))
+            }
+            _ => Error::DatabaseMdbxError(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::DatabaseIOError(e)
+    }
+}
+
+impl From<ssz::DecodeError> for Error {
+    fn from(e: ssz::DecodeError) -> Self {
+        Error::SszDecodeError(e)
+    }
+}
@@ -0,0 +1,80 @@
+//! Detects slashable attestations and blocks by recording every attestation and block received
+//! by the beacon node in an on-disk database.
+//!
+//! Double votes and double proposals are detected by indexing the records by validator and target
+//! epoch (or slot). Surround votes are detected using the min-max target arrays described in the
+//! `array` module.
+
+#[macro_use]
+extern crate lazy_static;
+
+mod array;
+mod config;
+mod database;
+mod error;
+mod metrics;
+mod slasher;
+
+pub use crate::slasher::{BatchGuard, BatchLock, Slasher};
+pub use config::{Config, DatabaseBackend, DATABASE_DIR_NAME};
+pub use database::{migrate_backend, AttesterRecord, SlasherDB};
+pub use error::Error;
+
+use serde_derive::{Deserialize, Serialize};
+use types::{
+    AttesterSlashing, EthSpec, IndexedAttestation, ProposerSlashing, SignedBeaconBlockHeader,
+};
+
+/// A verified message which should be checked by a slasher.
+///
+/// These are streamed by the beacon node to slashers running in a separate process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub enum SlasherEvent<E: EthSpec> {
+    Attestation(Box<IndexedAttestation<E>>),
+    Block(SignedBeaconBlockHeader),
+}
+
+/// The outcome of checking an attestation against the attestations already recorded for one of
+/// its attesters.
+#[derive(Debug, PartialEq)]
+pub enum AttesterSlashingStatus<E: EthSpec> {
+    NotSlashable,
+    /// The validator signed the existing attestation, which has the same target epoch.
+    DoubleVote(Box<IndexedAttestation<E>>),
+    /// The new attestation surrounds the existing attestation.
+    SurroundsExisting(Box<IndexedAttestation<E>>),
+    /// The new attestation is surrounded by the existing attestation.
+    SurroundedByExisting(Box<IndexedAttestation<E>>),
+}
+
+impl<E: EthSpec> AttesterSlashingStatus<E> {
+    /// Returns the `AttesterSlashing` proving the new attestation is slashable, if it is.
+    pub fn into_slashing(
+        self,
+        new_attestation: &IndexedAttestation<E>,
+    ) -> Option<AttesterSlashing<E>> {
+        use AttesterSlashingStatus::*;
+
+        // The surrounding attestation must be `attestation_1`.
+        match self {
+            NotSlashable => None,
+            DoubleVote(existing) | SurroundedByExisting(existing) => Some(AttesterSlashing {
+                attestation_1: *existing,
+                attestation_2: new_attestation.clone(),
+            }),
+            SurroundsExisting(existing) => Some(AttesterSlashing {
+                attestation_1: new_attestation.clone(),
+                attestation_2: *existing,
+            }),
+        }
+    }
+}
+
+/// The outcome of checking a block header against the block headers already recorded for its
+/// proposer.
+#[derive(Debug, PartialEq)]
+pub enum ProposerSlashingStatus {
+    NotSlashable,
+    DoubleProposal(Box<ProposerSlashing>),
+}
@@ -0,0 +1,44 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    pub static ref SLASHER_PROCESS_BATCH_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_process_batch_time",
+        "Time taken to process a batch of queued attestations and blocks"
+    );
+    pub static ref SLASHER_PRUNE_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_prune_time",
+        "Time taken to prune the records older than the history length"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_VALID: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_valid",
+        "Number of attestations in the most recent batch which were checked for slashings"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_DEFERRED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_deferred",
+        "Number of attestations with a future target epoch which were deferred to a later batch"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_DROPPED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_dropped",
+        "Number of attestations in the most recent batch which were older than the history length"
+    );
+    pub static ref SLASHER_ATTESTATION_QUEUE_LENGTH: Result<IntGauge> = try_create_int_gauge(
+        "slasher_attestation_queue_length",
+        "Number of attestations waiting to be processed at the start of the most recent batch"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_QUEUE_FULL: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_attestations_queue_full_total",
+        "Count of queued attestations dropped because the queue was full"
+    );
+    pub static ref SLASHER_NUM_BLOCKS_PROCESSED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_blocks_processed",
+        "Number of blocks in the most recent batch"
+    );
+    pub static ref SLASHER_NUM_ATTESTER_SLASHINGS: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_attester_slashings_total",
+        "Count of attester slashings found by the slasher"
+    );
+    pub static ref SLASHER_NUM_PROPOSER_SLASHINGS: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_proposer_slashings_total",
+        "Count of proposer slashings found by the slasher"
+    );
+}
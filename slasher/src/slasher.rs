@@ -0,0 +1,576 @@
+use crate::array::{lowest_epoch, TargetArrays};
+use crate::database::RwTransaction;
+use crate::metrics;
+use crate::{
+    AttesterRecord, AttesterSlashingStatus, Config, Error, ProposerSlashingStatus, SlasherDB,
+    SlasherEvent,
+};
+use parking_lot::Mutex;
+use slog::{debug, info, warn, Logger};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    AttesterSlashing, Epoch, EthSpec, Hash256, IndexedAttestation, ProposerSlashing,
+    SignedBeaconBlockHeader,
+};
+
+/// An attestation which has been stored in the database, along with its root.
+type StoredAttestation<E> = Arc<(IndexedAttestation<E>, Hash256)>;
+
+/// The outcome of `Slasher::process_attestation_batch`.
+struct ProcessedBatch<E: EthSpec> {
+    /// The number of distinct attestations checked for slashings.
+    num_valid: usize,
+    /// The attestations with a future target epoch, which were not processed.
+    deferred: Vec<IndexedAttestation<E>>,
+}
+
+/// Ensures that at most one batch of queued messages is processed at a time.
+///
+/// Clones share the same lock.
+#[derive(Debug, Clone, Default)]
+pub struct BatchLock(Arc<AtomicBool>);
+
+impl BatchLock {
+    /// Acquires the lock, returning `None` if a batch is already being processed.
+    ///
+    /// The lock is released when the returned guard is dropped.
+    pub fn try_lock(&self) -> Option<BatchGuard> {
+        self.0
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| BatchGuard(self.0.clone()))
+    }
+}
+
+/// Releases its `BatchLock` when dropped.
+#[derive(Debug)]
+pub struct BatchGuard(Arc<AtomicBool>);
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Records attestations and blocks, detecting any which are slashable.
+///
+/// Attestations and blocks are queued as they are received and processed in batches by
+/// `Self::process_queued`, which should be called periodically. At most
+/// `config.max_queued_attestations` attestations are queued, with the oldest dropped first if the
+/// slasher falls behind.
+#[derive(Debug)]
+pub struct Slasher<E: EthSpec> {
+    db: SlasherDB<E>,
+    attestation_queue: Mutex<VecDeque<IndexedAttestation<E>>>,
+    /// The number of queued attestations dropped since the last batch was processed.
+    num_queue_dropped: AtomicUsize,
+    block_queue: Mutex<Vec<SignedBeaconBlockHeader>>,
+    attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
+    proposer_slashings: Mutex<Vec<ProposerSlashing>>,
+    batch_lock: BatchLock,
+    config: Arc<Config>,
+    log: Logger,
+}
+
+impl<E: EthSpec> Slasher<E> {
+    /// Opens the slasher database, creating it if it does not exist.
+    pub fn open(config: Config, log: Logger) -> Result<Self, Error> {
+        config.validate()?;
+        let config = Arc::new(config);
+        let db = SlasherDB::open(config.clone())?;
+
+        Ok(Self {
+            db,
+            attestation_queue: Mutex::new(VecDeque::new()),
+            num_queue_dropped: AtomicUsize::new(0),
+            block_queue: Mutex::new(vec![]),
+            attester_slashings: Mutex::new(HashSet::new()),
+            proposer_slashings: Mutex::new(vec![]),
+            batch_lock: BatchLock::default(),
+            config,
+            log,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The lock which should be held whilst calling `Self::process_queued` periodically, so that
+    /// a new batch is not started before the previous one has finished.
+    pub fn batch_lock(&self) -> &BatchLock {
+        &self.batch_lock
+    }
+
+    /// Queues an attestation for processing.
+    ///
+    /// The signature of the attestation must have been verified.
+    pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
+        let mut queue = self.attestation_queue.lock();
+        queue.push_back(attestation);
+        self.drop_excess_attestations(&mut queue);
+    }
+
+    /// Drops the oldest attestations from `queue` until it is within the configured limit.
+    fn drop_excess_attestations(&self, queue: &mut VecDeque<IndexedAttestation<E>>) {
+        let excess = queue
+            .len()
+            .saturating_sub(self.config.max_queued_attestations);
+        if excess > 0 {
+            queue.drain(..excess);
+            self.num_queue_dropped.fetch_add(excess, Ordering::Relaxed);
+            metrics::inc_counter_by(&metrics::SLASHER_NUM_ATTESTATIONS_QUEUE_FULL, excess as i64);
+        }
+    }
+
+    /// Queues a block header for processing.
+    ///
+    /// The signature of the block must have been verified.
+    pub fn accept_block_header(&self, block_header: SignedBeaconBlockHeader) {
+        self.block_queue.lock().push(block_header);
+    }
+
+    /// Queues the attestation or block header contained in `event` for processing.
+    pub fn accept_event(&self, event: SlasherEvent<E>) {
+        match event {
+            SlasherEvent::Attestation(attestation) => self.accept_attestation(*attestation),
+            SlasherEvent::Block(block_header) => self.accept_block_header(block_header),
+        }
+    }
+
+    /// Removes and returns the attester slashings found since the last call.
+    pub fn get_attester_slashings(&self) -> HashSet<AttesterSlashing<E>> {
+        std::mem::take(&mut *self.attester_slashings.lock())
+    }
+
+    /// Removes and returns the proposer slashings found since the last call.
+    pub fn get_proposer_slashings(&self) -> Vec<ProposerSlashing> {
+        std::mem::take(&mut *self.proposer_slashings.lock())
+    }
+
+    /// Returns the attestations recorded for `validator_index` which are still within the
+    /// history length.
+    pub fn get_attestations_for_validator(
+        &self,
+        validator_index: u64,
+    ) -> Result<Vec<IndexedAttestation<E>>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db
+            .get_attestations_for_validator(validator_index, &txn)
+    }
+
+    /// Returns the attester slashings found for `validator_index` which are still within the
+    /// history length, including those which have already been removed by
+    /// `Self::get_attester_slashings`.
+    pub fn get_attester_slashings_for_validator(
+        &self,
+        validator_index: u64,
+    ) -> Result<Vec<AttesterSlashing<E>>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db
+            .get_attester_slashings_for_validator(validator_index, &txn)
+    }
+
+    /// Returns the block headers recorded for `proposer_index` which are still within the
+    /// history length.
+    pub fn get_blocks_for_proposer(
+        &self,
+        proposer_index: u64,
+    ) -> Result<Vec<SignedBeaconBlockHeader>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db.get_blocks_for_proposer(proposer_index, &txn)
+    }
+
+    /// Returns the proposer slashings found for `proposer_index` which are still within the
+    /// history length, including those which have already been removed by
+    /// `Self::get_proposer_slashings`.
+    pub fn get_proposer_slashings_for_validator(
+        &self,
+        proposer_index: u64,
+    ) -> Result<Vec<ProposerSlashing>, Error> {
+        let txn = self.db.begin_ro_txn()?;
+        self.db
+            .get_proposer_slashings_for_validator(proposer_index, &txn)
+    }
+
+    /// Processes all of the queued attestations and blocks, recording any slashings found.
+    ///
+    /// Attestations with a target epoch later than `current_epoch` remain queued.
+    pub fn process_queued(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::SLASHER_PROCESS_BATCH_TIME);
+
+        // Prune in its own transaction first, so that the space freed is available to the new
+        // records even if the database has filled up.
+        let mut txn = self.db.begin_rw_txn()?;
+        self.prune(current_epoch, &mut txn)?;
+        txn.commit()?;
+
+        let mut txn = self.db.begin_rw_txn()?;
+        self.process_blocks(&mut txn)?;
+        self.process_attestations(current_epoch, &mut txn)?;
+        self.db.update_latest_epoch(current_epoch, &mut txn)?;
+        txn.commit()?;
+
+        Ok(())
+    }
+
+    /// Checks `attestations` for slashings and records them immediately, bypassing the queue.
+    ///
+    /// This is intended for backfilling the database from a trusted source, such as another
+    /// slasher. As with `Self::accept_attestation`, the signatures of the attestations must have
+    /// been verified.
+    ///
+    /// The attestations are processed as of the later of `current_epoch` and the latest epoch
+    /// previously processed by the slasher, so that recent records are never overwritten.
+    /// Attestations which are outside the history length or have a future target epoch are
+    /// ignored.
+    ///
+    /// Returns the number of attestations imported.
+    pub fn import_attestations(
+        &self,
+        mut attestations: Vec<IndexedAttestation<E>>,
+        current_epoch: Epoch,
+    ) -> Result<usize, Error> {
+        let current_epoch = {
+            let txn = self.db.begin_ro_txn()?;
+            std::cmp::max(
+                current_epoch,
+                self.db.get_latest_epoch(&txn)?.unwrap_or(current_epoch),
+            )
+        };
+        let num_attestations = attestations.len();
+        let mut num_imported = 0;
+
+        // Process the attestations in batches no larger than the queue, to bound the size of
+        // each write transaction.
+        while !attestations.is_empty() {
+            let batch_size = std::cmp::min(attestations.len(), self.config.max_queued_attestations);
+            let batch = attestations.drain(..batch_size).collect::<Vec<_>>();
+
+            let mut txn = self.db.begin_rw_txn()?;
+            num_imported += self
+                .process_attestation_batch(batch, current_epoch, &mut txn)?
+                .num_valid;
+            self.db.update_latest_epoch(current_epoch, &mut txn)?;
+            txn.commit()?;
+        }
+
+        info!(
+            self.log,
+            "Imported attestations";
+            "imported" => num_imported,
+            "ignored" => num_attestations - num_imported,
+            "current_epoch" => current_epoch,
+        );
+
+        Ok(num_imported)
+    }
+
+    /// Deletes the attestations and blocks which are older than the history length.
+    fn prune(&self, current_epoch: Epoch, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::SLASHER_PRUNE_TIME);
+
+        let lowest_epoch = lowest_epoch(current_epoch, &self.config);
+        let lowest_slot = lowest_epoch.start_slot(E::slots_per_epoch());
+        let (num_attestations, num_blocks) = self.db.prune(lowest_epoch, lowest_slot, txn)?;
+        let num_slashings = self.db.prune_slashings(lowest_epoch, lowest_slot, txn)?;
+
+        if num_attestations > 0 || num_blocks > 0 || num_slashings > 0 {
+            debug!(
+                self.log,
+                "Pruned slasher database";
+                "attestations" => num_attestations,
+                "blocks" => num_blocks,
+                "slashings" => num_slashings,
+                "lowest_epoch" => lowest_epoch,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn process_blocks(&self, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        let blocks = std::mem::take(&mut *self.block_queue.lock());
+        let num_blocks = blocks.len();
+
+        let mut slashings = vec![];
+        for block_header in blocks {
+            if let ProposerSlashingStatus::DoubleProposal(slashing) =
+                self.db.check_or_insert_block_proposal(block_header, txn)?
+            {
+                if !slashings.contains(&*slashing) {
+                    self.db.store_proposer_slashing(&slashing, txn)?;
+                    slashings.push(*slashing);
+                }
+            }
+        }
+
+        metrics::set_gauge(&metrics::SLASHER_NUM_BLOCKS_PROCESSED, num_blocks as i64);
+
+        if !slashings.is_empty() {
+            info!(
+                self.log,
+                "Found proposer slashings";
+                "count" => slashings.len(),
+                "proposers" => format!(
+                    "{:?}",
+                    slashings
+                        .iter()
+                        .map(|slashing| slashing.signed_header_1.message.proposer_index)
+                        .collect::<Vec<_>>()
+                ),
+            );
+            metrics::inc_counter_by(
+                &metrics::SLASHER_NUM_PROPOSER_SLASHINGS,
+                slashings.len() as i64,
+            );
+
+            let mut proposer_slashings = self.proposer_slashings.lock();
+            for slashing in slashings {
+                if !proposer_slashings.contains(&slashing) {
+                    proposer_slashings.push(slashing);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_attestations(
+        &self,
+        current_epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        let attestations = std::mem::take(&mut *self.attestation_queue.lock());
+        metrics::set_gauge(
+            &metrics::SLASHER_ATTESTATION_QUEUE_LENGTH,
+            attestations.len() as i64,
+        );
+
+        let num_queue_dropped = self.num_queue_dropped.swap(0, Ordering::Relaxed);
+        if num_queue_dropped > 0 {
+            warn!(
+                self.log,
+                "Slasher attestation queue full";
+                "msg" => "the oldest attestations were dropped, the slasher may be falling behind",
+                "dropped" => num_queue_dropped,
+                "max_queued_attestations" => self.config.max_queued_attestations,
+            );
+        }
+
+        let processed = self.process_attestation_batch(attestations, current_epoch, txn)?;
+
+        // The deferred attestations are older than any queued since this batch began.
+        let mut queue = self.attestation_queue.lock();
+        for attestation in processed.deferred.into_iter().rev() {
+            queue.push_front(attestation);
+        }
+        self.drop_excess_attestations(&mut queue);
+
+        Ok(())
+    }
+
+    /// Checks each of `attestations` for slashings and records it, returning those with a target
+    /// epoch later than `current_epoch` unprocessed.
+    fn process_attestation_batch(
+        &self,
+        attestations: impl IntoIterator<Item = IndexedAttestation<E>>,
+        current_epoch: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<ProcessedBatch<E>, Error> {
+        let lowest_epoch = lowest_epoch(current_epoch, &self.config);
+        let mut deferred = vec![];
+        let mut num_valid = 0;
+        let mut num_dropped = 0;
+        let mut roots = HashSet::new();
+        // The attestations of each validator, grouped by validator chunk so that each chunk of
+        // the target arrays is only loaded and stored once.
+        let mut batch: BTreeMap<usize, Vec<(u64, StoredAttestation<E>)>> = BTreeMap::new();
+
+        for attestation in attestations {
+            let source_epoch = attestation.data.source.epoch;
+            let target_epoch = attestation.data.target.epoch;
+
+            if target_epoch > current_epoch {
+                deferred.push(attestation);
+                continue;
+            }
+            if source_epoch < lowest_epoch || source_epoch > target_epoch {
+                num_dropped += 1;
+                continue;
+            }
+
+            let root = attestation.tree_hash_root();
+            if !roots.insert(root) {
+                continue;
+            }
+            num_valid += 1;
+
+            self.db.store_indexed_attestation(root, &attestation, txn)?;
+
+            let stored = Arc::new((attestation, root));
+            for &validator_index in stored.0.attesting_indices.iter() {
+                batch
+                    .entry(self.config.validator_chunk_index(validator_index))
+                    .or_insert_with(Vec::new)
+                    .push((validator_index, stored.clone()));
+            }
+        }
+
+        metrics::set_gauge(&metrics::SLASHER_NUM_ATTESTATIONS_VALID, num_valid as i64);
+        metrics::set_gauge(&metrics::SLASHER_NUM_ATTESTATIONS_DROPPED, num_dropped);
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_ATTESTATIONS_DEFERRED,
+            deferred.len() as i64,
+        );
+
+        let mut slashings = HashSet::new();
+        for (validator_chunk_index, attestations) in batch {
+            let mut arrays = TargetArrays::new(validator_chunk_index, &self.db, &self.config);
+            let mut updated_validators = HashSet::new();
+
+            for (validator_index, stored) in attestations {
+                let (attestation, root) = &*stored;
+
+                if updated_validators.insert(validator_index) {
+                    self.update_current_epoch(validator_index, current_epoch, &mut arrays, txn)?;
+                }
+
+                let status = self.check_attestation(
+                    validator_index,
+                    attestation,
+                    *root,
+                    current_epoch,
+                    &mut arrays,
+                    txn,
+                )?;
+                if let Some(slashing) = status.into_slashing(attestation) {
+                    debug!(
+                        self.log,
+                        "Found attester slashing";
+                        "validator_index" => validator_index,
+                        "target_epoch" => attestation.data.target.epoch,
+                    );
+                    self.db
+                        .store_attester_slashing(validator_index, &slashing, txn)?;
+                    slashings.insert(slashing);
+                }
+            }
+
+            arrays.store(txn)?;
+        }
+
+        debug!(
+            self.log,
+            "Processed attestations";
+            "valid" => num_valid,
+            "dropped" => num_dropped,
+            "current_epoch" => current_epoch,
+        );
+
+        if !slashings.is_empty() {
+            info!(
+                self.log,
+                "Found attester slashings";
+                "count" => slashings.len(),
+            );
+            metrics::inc_counter_by(
+                &metrics::SLASHER_NUM_ATTESTER_SLASHINGS,
+                slashings.len() as i64,
+            );
+            self.attester_slashings.lock().extend(slashings);
+        }
+
+        Ok(ProcessedBatch {
+            num_valid,
+            deferred,
+        })
+    }
+
+    /// Empties the cells of the target arrays which `validator_index` has not used since the
+    /// previous epoch at which its arrays were updated.
+    fn update_current_epoch(
+        &self,
+        validator_index: u64,
+        current_epoch: Epoch,
+        arrays: &mut TargetArrays<'_, E>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        match self
+            .db
+            .get_current_epoch_for_validator(validator_index, txn)?
+        {
+            Some(previous_epoch) if previous_epoch >= current_epoch => return Ok(()),
+            Some(previous_epoch) => arrays.advance_epoch(
+                validator_index,
+                previous_epoch,
+                current_epoch,
+                &self.config,
+                txn,
+            )?,
+            // The cells of a validator which has never attested have never been written.
+            None => (),
+        }
+
+        self.db
+            .update_current_epoch_for_validator(validator_index, current_epoch, txn)
+    }
+
+    /// Checks whether `attestation` is slashable for `validator_index`, recording it if not.
+    fn check_attestation(
+        &self,
+        validator_index: u64,
+        attestation: &IndexedAttestation<E>,
+        root: Hash256,
+        current_epoch: Epoch,
+        arrays: &mut TargetArrays<'_, E>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<AttesterSlashingStatus<E>, Error> {
+        let record = AttesterRecord::new(attestation, root);
+        let status =
+            self.db
+                .check_and_update_attester_record(validator_index, attestation, record, txn)?;
+        if status != AttesterSlashingStatus::NotSlashable {
+            return Ok(status);
+        }
+
+        let source_epoch = attestation.data.source.epoch;
+        let target_epoch = attestation.data.target.epoch;
+
+        if let Some(existing_target) =
+            arrays.surrounded_target(validator_index, source_epoch, target_epoch, txn)?
+        {
+            let existing =
+                self.db
+                    .get_attestation_for_validator(validator_index, existing_target, txn)?;
+            return Ok(AttesterSlashingStatus::SurroundsExisting(Box::new(
+                existing,
+            )));
+        }
+
+        if let Some(existing_target) =
+            arrays.surrounding_target(validator_index, source_epoch, target_epoch, txn)?
+        {
+            let existing =
+                self.db
+                    .get_attestation_for_validator(validator_index, existing_target, txn)?;
+            return Ok(AttesterSlashingStatus::SurroundedByExisting(Box::new(
+                existing,
+            )));
+        }
+
+        arrays.apply_attestation(
+            validator_index,
+            source_epoch,
+            target_epoch,
+            current_epoch,
+            &self.config,
+            txn,
+        )?;
+
+        Ok(AttesterSlashingStatus::NotSlashable)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{CMD, DEFAULT_HTTP_SERVER};
+use clap::{App, Arg};
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Runs a slasher in its own process, checking the attestations and blocks streamed by \
+                a beacon node started with --slasher-stream. Slashings found are added to the \
+                beacon node's operation pool for inclusion in its blocks.",
+        )
+        .arg(
+            Arg::with_name("server")
+                .long("server")
+                .value_name("NETWORK_ADDRESS")
+                .help("Address of the beacon node's HTTP API.")
+                .default_value(&DEFAULT_HTTP_SERVER)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("DIR")
+                .help(
+                    "Set the slasher's database directory. Defaults to \
+                    ~/.lighthouse/slasher/slasher_db, or slasher_db within --datadir.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .value_name("EPOCHS")
+                .help(
+                    "Configure how many epochs of history the slasher keeps. Immutable after \
+                    initialization. Must be a multiple of --slasher-chunk-size.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-chunk-size")
+                .long("slasher-chunk-size")
+                .value_name("EPOCHS")
+                .help(
+                    "Number of epochs per validator per chunk stored on disk. Immutable after \
+                    initialization. Must divide the history length.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-validator-chunk-size")
+                .long("slasher-validator-chunk-size")
+                .value_name("NUM_VALIDATORS")
+                .help(
+                    "Number of validators per chunk stored on disk. Immutable after \
+                    initialization.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-max-db-size")
+                .long("slasher-max-db-size")
+                .value_name("GIGABYTES")
+                .help(
+                    "Maximum size of the slasher database in gigabytes. Writes to the database \
+                    fail once this size is reached.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-update-period")
+                .long("slasher-update-period")
+                .value_name("SECONDS")
+                .help(
+                    "Configure how often the slasher processes the attestations and blocks it \
+                    has queued, in seconds.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-max-queued-attestations")
+                .long("slasher-max-queued-attestations")
+                .value_name("COUNT")
+                .help(
+                    "Maximum number of attestations waiting to be processed by the slasher. If \
+                    the slasher falls behind, the oldest queued attestations are dropped.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .value_name("BACKEND")
+                .help(
+                    "Set the database backend used by the slasher. The mdbx backend is only \
+                    available if Lighthouse was compiled with the slasher-mdbx feature.",
+                )
+                .takes_value(true)
+                .possible_values(&["lmdb", "mdbx"])
+                .default_value("lmdb"),
+        )
+}
@@ -0,0 +1,513 @@
+//! Runs a `Slasher` in its own process, so that the resources it requires can be provided by
+//! different hardware to the beacon node.
+//!
+//! The slasher follows the `/lighthouse/slasher/stream` endpoint of a beacon node started with
+//! `--slasher-stream`, and submits the slashings it finds to the same beacon node.
+
+mod cli;
+mod sse;
+
+pub use cli::cli_app;
+
+use clap::ArgMatches;
+use environment::{RuntimeContext, TaskExecutor};
+use futures::StreamExt;
+use remote_beacon_node::RemoteBeaconNode;
+use slasher::{Config, Slasher, SlasherEvent, DATABASE_DIR_NAME};
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{delay_for, interval_at, Instant};
+use types::{AttesterSlashing, EthSpec, ProposerSlashing};
+use url::Url;
+
+pub const CMD: &str = "slasher";
+pub const DEFAULT_HTTP_SERVER: &str = "http://localhost:5052/";
+/// The path of the beacon node's slasher stream, relative to the root of its HTTP API.
+pub const STREAM_PATH: &str = "lighthouse/slasher/stream";
+/// The delay before retrying a failed connection to the beacon node.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// A slasher which is fed by a remote beacon node.
+pub struct StandaloneSlasher<E: EthSpec> {
+    slasher: Arc<Slasher<E>>,
+}
+
+impl<E: EthSpec> StandaloneSlasher<E> {
+    /// Instantiates the slasher using the supplied `cli_args`, then starts its services.
+    pub async fn new_from_cli(
+        context: RuntimeContext<E>,
+        cli_args: &ArgMatches<'_>,
+    ) -> Result<Self, String> {
+        let server = clap_utils::parse_required(cli_args, "server")?;
+        let config = parse_config(cli_args)?;
+        Self::new(context, server, config).await
+    }
+
+    /// Opens the slasher database, waits for the beacon node at `server` to respond, then starts
+    /// following its slasher stream.
+    pub async fn new(
+        context: RuntimeContext<E>,
+        server: String,
+        config: Config,
+    ) -> Result<Self, String> {
+        let log = context.log().clone();
+
+        info!(
+            log,
+            "Starting standalone slasher";
+            "beacon_node" => &server,
+            "database_path" => format!("{:?}", config.database_path),
+        );
+
+        let stream_url = Url::parse(&server)
+            .and_then(|url| url.join(STREAM_PATH))
+            .map_err(|e| format!("Invalid beacon node address {}: {:?}", server, e))?;
+        let beacon_node = RemoteBeaconNode::new(server)?;
+        let slot_clock = wait_for_node(&beacon_node, &context, &log).await?;
+
+        let slasher = Arc::new(
+            Slasher::open(config, log.clone())
+                .map_err(|e| format!("Failed to open slasher: {:?}", e))?,
+        );
+
+        let executor = context.executor;
+        executor.spawn(
+            follow_stream(slasher.clone(), stream_url, log),
+            "slasher_stream",
+        );
+        spawn_update_service(slasher.clone(), beacon_node, slot_clock, &executor);
+
+        Ok(Self { slasher })
+    }
+
+    pub fn slasher(&self) -> &Arc<Slasher<E>> {
+        &self.slasher
+    }
+}
+
+/// Returns the slasher config given by `cli_args`.
+fn parse_config(cli_args: &ArgMatches) -> Result<Config, String> {
+    let slasher_dir =
+        if let Some(slasher_dir) = clap_utils::parse_optional(cli_args, "slasher-dir")? {
+            slasher_dir
+        } else {
+            clap_utils::parse_path_with_default_in_home_dir(
+                cli_args,
+                "datadir",
+                PathBuf::from(".lighthouse").join(CMD),
+            )?
+            .join(DATABASE_DIR_NAME)
+        };
+
+    let mut config = Config::new(slasher_dir);
+
+    if let Some(history_length) = clap_utils::parse_optional(cli_args, "slasher-history-length")? {
+        config.history_length = history_length;
+    }
+
+    if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
+        config.chunk_size = chunk_size;
+    }
+
+    if let Some(validator_chunk_size) =
+        clap_utils::parse_optional(cli_args, "slasher-validator-chunk-size")?
+    {
+        config.validator_chunk_size = validator_chunk_size;
+    }
+
+    if let Some(update_period) = clap_utils::parse_optional(cli_args, "slasher-update-period")? {
+        config.update_period = update_period;
+    }
+
+    if let Some(max_queued_attestations) =
+        clap_utils::parse_optional(cli_args, "slasher-max-queued-attestations")?
+    {
+        config.max_queued_attestations = max_queued_attestations;
+    }
+
+    if let Some(max_db_size_gbs) =
+        clap_utils::parse_optional::<usize>(cli_args, "slasher-max-db-size")?
+    {
+        config.max_db_size_mbs = max_db_size_gbs * 1024;
+    }
+
+    config.backend = clap_utils::parse_required(cli_args, "slasher-backend")?;
+
+    Ok(config)
+}
+
+/// Requests the spec and genesis time from the beacon node, retrying until it responds, and
+/// returns a slot clock which agrees with the beacon node.
+async fn wait_for_node<E: EthSpec>(
+    beacon_node: &RemoteBeaconNode<E>,
+    context: &RuntimeContext<E>,
+    log: &Logger,
+) -> Result<SystemTimeSlotClock, String> {
+    loop {
+        let result = async {
+            let eth2_config = beacon_node.http.spec().get_eth2_config().await?;
+            let genesis_time = beacon_node.http.beacon().get_genesis_time().await?;
+            Ok::<_, remote_beacon_node::Error>((eth2_config, genesis_time))
+        }
+        .await;
+
+        match result {
+            Ok((eth2_config, genesis_time)) => {
+                // Do not permit a connection to a beacon node using different spec constants.
+                if context.eth2_config.spec_constants != eth2_config.spec_constants {
+                    return Err(format!(
+                        "Beacon node is using an incompatible spec. Got {}, expected {}",
+                        eth2_config.spec_constants, context.eth2_config.spec_constants
+                    ));
+                }
+
+                info!(
+                    log,
+                    "Connected to beacon node";
+                    "genesis_time" => genesis_time,
+                );
+
+                return Ok(SystemTimeSlotClock::new(
+                    eth2_config.spec.genesis_slot,
+                    Duration::from_secs(genesis_time),
+                    Duration::from_millis(eth2_config.spec.milliseconds_per_slot),
+                ));
+            }
+            Err(e) => {
+                error!(
+                    log,
+                    "Unable to connect to beacon node";
+                    "error" => format!("{:?}", e),
+                );
+                delay_for(RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Queues each attestation and block from the slasher stream at `url` in `slasher`, reconnecting
+/// whenever the stream ends.
+///
+/// Attestations and blocks verified by the beacon node while disconnected are not checked.
+async fn follow_stream<E: EthSpec>(slasher: Arc<Slasher<E>>, url: Url, log: Logger) {
+    // The stream never completes, so the client must not time out.
+    let client = reqwest::Client::new();
+
+    loop {
+        match read_stream(&client, &url, &slasher, &log).await {
+            Ok(()) => warn!(
+                log,
+                "Slasher stream closed by beacon node";
+                "msg" => "reconnecting",
+            ),
+            Err(e) => error!(
+                log,
+                "Unable to read slasher stream";
+                "error" => e,
+                "url" => url.as_str(),
+            ),
+        }
+
+        delay_for(RETRY_DELAY).await;
+    }
+}
+
+async fn read_stream<E: EthSpec>(
+    client: &reqwest::Client,
+    url: &Url,
+    slasher: &Slasher<E>,
+    log: &Logger,
+) -> Result<(), String> {
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("{:?}", e))?;
+
+    info!(log, "Following slasher stream"; "url" => url.as_str());
+
+    let mut buffer = vec![];
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("{:?}", e))? {
+        buffer.extend_from_slice(&chunk);
+
+        for data in sse::take_events(&mut buffer) {
+            match serde_json::from_str::<SlasherEvent<E>>(&data) {
+                Ok(event) => slasher.accept_event(event),
+                Err(e) => warn!(
+                    log,
+                    "Invalid event in slasher stream";
+                    "error" => format!("{:?}", e),
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Processes the slasher's queued messages every `update_period` seconds, submitting any
+/// slashings found to the beacon node.
+fn spawn_update_service<E: EthSpec>(
+    slasher: Arc<Slasher<E>>,
+    beacon_node: RemoteBeaconNode<E>,
+    slot_clock: SystemTimeSlotClock,
+    executor: &TaskExecutor,
+) {
+    let log = executor.log().clone();
+    let update_period = slasher.config().update_period;
+
+    // Warning: `interval_at` panics if `update_period` = 0.
+    let mut interval = interval_at(
+        Instant::now() + Duration::from_secs(update_period),
+        Duration::from_secs(update_period),
+    );
+
+    let batch_lock = slasher.batch_lock().clone();
+    let inner_executor = executor.clone();
+    let service_future = async move {
+        while interval.next().await.is_some() {
+            // Skip this update if the previous batch is still being processed.
+            let batch_guard = match batch_lock.try_lock() {
+                Some(guard) => guard,
+                None => {
+                    debug!(log, "Slasher batch in progress, skipping update");
+                    continue;
+                }
+            };
+
+            let current_epoch = match slot_clock.now() {
+                Some(slot) => slot.epoch(E::slots_per_epoch()),
+                None => {
+                    error!(log, "Slasher unable to read the current slot");
+                    continue;
+                }
+            };
+
+            let slasher = slasher.clone();
+            let beacon_node = beacon_node.clone();
+            let log = log.clone();
+            let executor = inner_executor.clone();
+
+            // Processing a batch may take some time, so run it on a blocking thread.
+            inner_executor.spawn_blocking(
+                move || {
+                    let _batch_guard = batch_guard;
+                    if let Err(e) = slasher.process_queued(current_epoch) {
+                        error!(
+                            log,
+                            "Error during scheduled slasher processing";
+                            "error" => format!("{:?}", e),
+                            "epoch" => current_epoch,
+                        );
+                        return;
+                    }
+                    debug!(
+                        log,
+                        "Slasher processed queued messages";
+                        "epoch" => current_epoch,
+                    );
+
+                    let attester_slashings = slasher.get_attester_slashings();
+                    let proposer_slashings = slasher.get_proposer_slashings();
+                    if !attester_slashings.is_empty() || !proposer_slashings.is_empty() {
+                        executor.spawn(
+                            submit_slashings(
+                                attester_slashings.into_iter().collect(),
+                                proposer_slashings,
+                                beacon_node,
+                                log,
+                            ),
+                            "slasher_submit",
+                        );
+                    }
+                },
+                "slasher_batch",
+            );
+        }
+    };
+
+    executor.spawn(service_future, "slasher_service");
+    info!(
+        executor.log(),
+        "Slasher service started";
+        "update_period" => update_period,
+    );
+}
+
+/// Submits slashings to the beacon node, which verifies them before adding them to its operation
+/// pool.
+async fn submit_slashings<E: EthSpec>(
+    attester_slashings: Vec<AttesterSlashing<E>>,
+    proposer_slashings: Vec<ProposerSlashing>,
+    beacon_node: RemoteBeaconNode<E>,
+    log: Logger,
+) {
+    for slashing in attester_slashings {
+        let slashed_indices = format!(
+            "{:?}",
+            slashing
+                .attestation_1
+                .attesting_indices
+                .iter()
+                .filter(|index| slashing.attestation_2.attesting_indices.contains(index))
+                .collect::<Vec<_>>()
+        );
+
+        match beacon_node.http.beacon().attester_slashing(slashing).await {
+            Ok(_) => info!(
+                log,
+                "Submitted attester slashing";
+                "validator_indices" => slashed_indices,
+            ),
+            Err(e) => error!(
+                log,
+                "Unable to submit attester slashing";
+                "validator_indices" => slashed_indices,
+                "error" => format!("{:?}", e),
+            ),
+        }
+    }
+
+    for slashing in proposer_slashings {
+        let proposer_index = slashing.signed_header_1.message.proposer_index;
+
+        match beacon_node.http.beacon().proposer_slashing(slashing).await {
+            Ok(_) => info!(
+                log,
+                "Submitted proposer slashing";
+                "validator_index" => proposer_index,
+            ),
+            Err(e) => error!(
+                log,
+                "Unable to submit proposer slashing";
+                "validator_index" => proposer_index,
+                "error" => format!("{:?}", e),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slasher::DatabaseBackend;
+    use types::{
+        AggregateSignature, AttestationData, BeaconBlockHeader, Checkpoint, Epoch, Hash256,
+        IndexedAttestation, MainnetEthSpec, Signature, SignedBeaconBlockHeader, Slot,
+    };
+
+    type E = MainnetEthSpec;
+
+    fn config_from_args(args: &[&str]) -> Result<Config, String> {
+        let matches = cli_app()
+            .get_matches_from_safe(std::iter::once(CMD).chain(args.iter().copied()))
+            .map_err(|e| e.to_string())?;
+        parse_config(&matches)
+    }
+
+    #[test]
+    fn parse_config_defaults() {
+        let config = config_from_args(&["--slasher-dir", "/tmp/slasher"]).unwrap();
+        assert_eq!(config, Config::new(PathBuf::from("/tmp/slasher")));
+    }
+
+    #[test]
+    fn parse_config_all_flags() {
+        let config = config_from_args(&[
+            "--slasher-dir",
+            "/tmp/slasher",
+            "--slasher-history-length",
+            "64",
+            "--slasher-chunk-size",
+            "32",
+            "--slasher-validator-chunk-size",
+            "128",
+            "--slasher-update-period",
+            "6",
+            "--slasher-max-queued-attestations",
+            "1024",
+            "--slasher-max-db-size",
+            "2",
+            "--slasher-backend",
+            "mdbx",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                database_path: PathBuf::from("/tmp/slasher"),
+                chunk_size: 32,
+                validator_chunk_size: 128,
+                history_length: 64,
+                update_period: 6,
+                max_queued_attestations: 1024,
+                max_db_size_mbs: 2048,
+                backend: DatabaseBackend::Mdbx,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_config_invalid_chunk_size() {
+        assert!(config_from_args(&["--slasher-chunk-size", "sixteen"]).is_err());
+    }
+
+    /// Checks that events encoded as the beacon node's slasher stream does are decoded intact,
+    /// even if the stream is split at arbitrary points.
+    #[test]
+    fn stream_events_round_trip() {
+        let events: Vec<SlasherEvent<E>> = vec![
+            SlasherEvent::Attestation(Box::new(IndexedAttestation {
+                attesting_indices: vec![1, 2, 3].into(),
+                data: AttestationData {
+                    slot: Slot::new(33),
+                    index: 2,
+                    beacon_block_root: Hash256::from_low_u64_be(1),
+                    source: Checkpoint {
+                        epoch: Epoch::new(0),
+                        root: Hash256::from_low_u64_be(2),
+                    },
+                    target: Checkpoint {
+                        epoch: Epoch::new(1),
+                        root: Hash256::from_low_u64_be(3),
+                    },
+                },
+                signature: AggregateSignature::empty(),
+            })),
+            SlasherEvent::Block(SignedBeaconBlockHeader {
+                message: BeaconBlockHeader {
+                    slot: Slot::new(34),
+                    proposer_index: 7,
+                    parent_root: Hash256::from_low_u64_be(4),
+                    state_root: Hash256::from_low_u64_be(5),
+                    body_root: Hash256::from_low_u64_be(6),
+                },
+                signature: Signature::empty(),
+            }),
+        ];
+
+        let stream = events
+            .iter()
+            .map(|event| format!("data: {}\n\n", serde_json::to_string(event).unwrap()))
+            .collect::<String>()
+            .into_bytes();
+
+        for chunk_size in &[1, 7, stream.len()] {
+            let mut buffer = vec![];
+            let mut decoded = vec![];
+            for chunk in stream.chunks(*chunk_size) {
+                buffer.extend_from_slice(chunk);
+                for data in sse::take_events(&mut buffer) {
+                    decoded.push(serde_json::from_str::<SlasherEvent<E>>(&data).unwrap());
+                }
+            }
+            assert_eq!(decoded, events);
+            assert!(buffer.is_empty());
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! A minimal reader for the `text/event-stream` format used by the beacon node's SSE endpoints.
+
+/// Removes each complete event from the front of `buffer`, returning the data of each.
+///
+/// Events are terminated by a blank line, so an incomplete event at the end of `buffer` is left
+/// in place until the rest of it is received. Fields other than `data` are ignored, and the
+/// values of multiple `data` fields in one event are joined by newlines.
+pub fn take_events(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut events = vec![];
+
+    while let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+        let event = buffer.drain(..end + 2).collect::<Vec<_>>();
+        let data = String::from_utf8_lossy(&event)
+            .lines()
+            .filter(|line| line.starts_with("data:"))
+            .map(|line| {
+                let value = &line["data:".len()..];
+                // A single leading space is not part of the value.
+                if value.starts_with(' ') {
+                    value[1..].to_string()
+                } else {
+                    value.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !data.is_empty() {
+            events.push(data.join("\n"));
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_events() {
+        let mut buffer = b"data: {\"a\":1}\n\ndata:{\"b\":2}\n\n".to_vec();
+        assert_eq!(
+            take_events(&mut buffer),
+            vec!["{\"a\":1}".to_string(), "{\"b\":2}".to_string()]
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn partial_event_is_kept() {
+        let mut buffer = b"data: first\n\ndata: sec".to_vec();
+        assert_eq!(take_events(&mut buffer), vec!["first".to_string()]);
+        assert_eq!(buffer, b"data: sec".to_vec());
+
+        buffer.extend_from_slice(b"ond\n\n");
+        assert_eq!(take_events(&mut buffer), vec!["second".to_string()]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn multi_line_data_and_other_fields() {
+        let mut buffer = b": comment\nevent: slasher\ndata: a\ndata: b\n\n\n\n".to_vec();
+        assert_eq!(take_events(&mut buffer), vec!["a\nb".to_string()]);
+        assert!(buffer.is_empty());
+    }
+}
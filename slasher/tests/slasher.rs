@@ -0,0 +1,534 @@
+use slasher::{Config, DatabaseBackend, Error, Slasher};
+use sloggers::{null::NullLoggerBuilder, Build};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use tempfile::tempdir;
+use types::{
+    AggregateSignature, AttestationData, AttesterSlashing, BeaconBlockHeader, Checkpoint, Epoch,
+    Hash256, IndexedAttestation, MainnetEthSpec, Signature, SignedBeaconBlockHeader, Slot,
+};
+
+type E = MainnetEthSpec;
+
+fn indexed_att(
+    attesting_indices: Vec<u64>,
+    source_epoch: u64,
+    target_epoch: u64,
+    target_root: u64,
+) -> IndexedAttestation<E> {
+    IndexedAttestation {
+        attesting_indices: attesting_indices.into(),
+        data: AttestationData {
+            slot: Slot::new(0),
+            index: 0,
+            beacon_block_root: Hash256::zero(),
+            source: Checkpoint {
+                epoch: Epoch::new(source_epoch),
+                root: Hash256::from_low_u64_be(0),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target_epoch),
+                root: Hash256::from_low_u64_be(target_root),
+            },
+        },
+        signature: AggregateSignature::empty(),
+    }
+}
+
+fn block(slot: u64, proposer_index: u64, block_root: u64) -> SignedBeaconBlockHeader {
+    SignedBeaconBlockHeader {
+        message: BeaconBlockHeader {
+            slot: Slot::new(slot),
+            proposer_index,
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::from_low_u64_be(block_root),
+        },
+        signature: Signature::empty(),
+    }
+}
+
+fn open_slasher(config: Config) -> Slasher<E> {
+    let log = NullLoggerBuilder.build().unwrap();
+    Slasher::open(config, log).unwrap()
+}
+
+/// Processes `batches` of attestations in order, returning the attester slashings found.
+fn attester_slashings(
+    batches: Vec<Vec<IndexedAttestation<E>>>,
+    current_epoch: u64,
+) -> Vec<AttesterSlashing<E>> {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+
+    for batch in batches {
+        for attestation in batch {
+            slasher.accept_attestation(attestation);
+        }
+        slasher.process_queued(Epoch::new(current_epoch)).unwrap();
+    }
+
+    let slashings = slasher
+        .get_attester_slashings()
+        .into_iter()
+        .collect::<Vec<_>>();
+    for slashing in &slashings {
+        assert!(
+            slashing
+                .attestation_1
+                .is_double_vote(&slashing.attestation_2)
+                || slashing
+                    .attestation_1
+                    .is_surround_vote(&slashing.attestation_2),
+            "invalid slashing: {:?}",
+            slashing
+        );
+    }
+    slashings
+}
+
+#[test]
+fn no_slashing() {
+    let atts = vec![
+        indexed_att(vec![0, 1], 0, 1, 0),
+        indexed_att(vec![0, 1], 1, 2, 0),
+        indexed_att(vec![0, 1], 2, 3, 0),
+    ];
+    assert!(attester_slashings(vec![atts], 3).is_empty());
+}
+
+#[test]
+fn repeated_attestation() {
+    let att = indexed_att(vec![0, 1], 0, 1, 0);
+    assert!(attester_slashings(vec![vec![att.clone()], vec![att]], 1).is_empty());
+}
+
+#[test]
+fn double_vote() {
+    let att1 = indexed_att(vec![0, 1], 0, 1, 0);
+    let att2 = indexed_att(vec![1, 2], 0, 1, 1);
+
+    let slashings = attester_slashings(vec![vec![att1.clone(), att2.clone()]], 1);
+    assert_eq!(
+        slashings,
+        vec![AttesterSlashing {
+            attestation_1: att1,
+            attestation_2: att2,
+        }]
+    );
+}
+
+#[test]
+fn surrounds_existing() {
+    let surrounded = indexed_att(vec![0], 1, 2, 0);
+    let surrounding = indexed_att(vec![0], 0, 3, 0);
+
+    let slashings =
+        attester_slashings(vec![vec![surrounded.clone()], vec![surrounding.clone()]], 3);
+    assert_eq!(
+        slashings,
+        vec![AttesterSlashing {
+            attestation_1: surrounding,
+            attestation_2: surrounded,
+        }]
+    );
+}
+
+#[test]
+fn surrounded_by_existing() {
+    let surrounding = indexed_att(vec![0], 0, 3, 0);
+    let surrounded = indexed_att(vec![0], 1, 2, 0);
+
+    let slashings =
+        attester_slashings(vec![vec![surrounding.clone()], vec![surrounded.clone()]], 3);
+    assert_eq!(
+        slashings,
+        vec![AttesterSlashing {
+            attestation_1: surrounding,
+            attestation_2: surrounded,
+        }]
+    );
+}
+
+#[test]
+fn surround_across_validator_chunks() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        validator_chunk_size: 2,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    slasher.accept_attestation(indexed_att(vec![1, 2, 5], 0, 3, 0));
+    slasher.process_queued(Epoch::new(3)).unwrap();
+    slasher.accept_attestation(indexed_att(vec![5], 1, 2, 0));
+    slasher.process_queued(Epoch::new(3)).unwrap();
+
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+}
+
+#[test]
+fn future_attestation_deferred() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+
+    slasher.accept_attestation(indexed_att(vec![0], 0, 1, 0));
+    slasher.accept_attestation(indexed_att(vec![0], 0, 2, 0));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+    slasher.accept_attestation(indexed_att(vec![0], 0, 2, 1));
+    slasher.process_queued(Epoch::new(2)).unwrap();
+
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+}
+
+#[test]
+fn double_proposal() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(1, 1, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    assert!(slasher.get_proposer_slashings().is_empty());
+
+    slasher.accept_block_header(block(1, 0, 1));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    let slashings = slasher.get_proposer_slashings();
+    assert_eq!(slashings.len(), 1);
+    assert_eq!(slashings[0].signed_header_1, block(1, 0, 0));
+    assert_eq!(slashings[0].signed_header_2, block(1, 0, 1));
+}
+
+#[test]
+fn reopen_with_incompatible_config() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    drop(open_slasher(config.clone()));
+
+    let incompatible = Config {
+        chunk_size: 32,
+        ..config
+    };
+    let log = NullLoggerBuilder.build().unwrap();
+    assert!(Slasher::<E>::open(incompatible, log).is_err());
+}
+
+#[test]
+fn blocks_pruned_after_history_length() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        history_length: 16,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    slasher.process_queued(Epoch::new(20)).unwrap();
+
+    // The original block has been pruned, so the conflicting block is not detected.
+    slasher.accept_block_header(block(1, 0, 1));
+    slasher.process_queued(Epoch::new(20)).unwrap();
+    assert!(slasher.get_proposer_slashings().is_empty());
+}
+
+#[test]
+fn attestations_dropped_after_history_length() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        history_length: 16,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    slasher.accept_attestation(indexed_att(vec![0], 0, 1, 0));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+
+    slasher.accept_attestation(indexed_att(vec![0], 0, 1, 1));
+    slasher.process_queued(Epoch::new(20)).unwrap();
+    assert!(slasher.get_attester_slashings().is_empty());
+}
+
+#[test]
+fn slashings_pruned_after_history_length() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        history_length: 16,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(1, 0, 1));
+    slasher.accept_attestation(indexed_att(vec![1], 0, 1, 0));
+    slasher.accept_attestation(indexed_att(vec![1], 0, 1, 1));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+    assert_eq!(
+        slasher
+            .get_proposer_slashings_for_validator(0)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        slasher
+            .get_attester_slashings_for_validator(1)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    slasher.process_queued(Epoch::new(20)).unwrap();
+    assert!(slasher
+        .get_proposer_slashings_for_validator(0)
+        .unwrap()
+        .is_empty());
+    assert!(slasher
+        .get_attester_slashings_for_validator(1)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn reopen_with_other_backend() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    drop(open_slasher(config.clone()));
+
+    let other_backend = Config {
+        backend: DatabaseBackend::Mdbx,
+        ..config
+    };
+    let log = NullLoggerBuilder.build().unwrap();
+    assert!(matches!(
+        Slasher::<E>::open(other_backend, log),
+        Err(Error::BackendMismatch { .. })
+    ));
+}
+
+#[test]
+#[cfg(feature = "mdbx")]
+fn migrate_lmdb_to_mdbx() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let slasher = open_slasher(config.clone());
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    drop(slasher);
+
+    assert!(slasher::migrate_backend(&config, DatabaseBackend::Mdbx).unwrap() > 0);
+
+    // The migrated database still knows about the original block.
+    let slasher = open_slasher(Config {
+        backend: DatabaseBackend::Mdbx,
+        ..config
+    });
+    slasher.accept_block_header(block(1, 0, 1));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    assert_eq!(slasher.get_proposer_slashings().len(), 1);
+}
+
+#[test]
+fn query_attestations_and_slashings() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+
+    let att1 = indexed_att(vec![0, 1], 0, 1, 0);
+    let att2 = indexed_att(vec![1], 1, 2, 0);
+    let att3 = indexed_att(vec![1], 1, 2, 1);
+    for att in vec![att1.clone(), att2.clone(), att3.clone()] {
+        slasher.accept_attestation(att);
+    }
+    slasher.process_queued(Epoch::new(2)).unwrap();
+
+    assert_eq!(
+        slasher.get_attestations_for_validator(0).unwrap(),
+        vec![att1.clone()]
+    );
+    assert_eq!(
+        slasher.get_attestations_for_validator(1).unwrap(),
+        vec![att1, att2.clone()]
+    );
+    assert!(slasher
+        .get_attestations_for_validator(2)
+        .unwrap()
+        .is_empty());
+
+    // Slashings remain queryable after being taken for broadcast.
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+    assert!(slasher
+        .get_attester_slashings_for_validator(0)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        slasher.get_attester_slashings_for_validator(1).unwrap(),
+        vec![AttesterSlashing {
+            attestation_1: att2,
+            attestation_2: att3,
+        }]
+    );
+}
+
+#[test]
+fn query_blocks_and_slashings() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(2, 0, 0));
+    slasher.accept_block_header(block(2, 1, 0));
+    slasher.accept_block_header(block(2, 1, 1));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    assert_eq!(
+        slasher.get_blocks_for_proposer(0).unwrap(),
+        vec![block(1, 0, 0), block(2, 0, 0)]
+    );
+    assert_eq!(
+        slasher.get_blocks_for_proposer(1).unwrap(),
+        vec![block(2, 1, 0)]
+    );
+
+    assert!(slasher
+        .get_proposer_slashings_for_validator(0)
+        .unwrap()
+        .is_empty());
+    let slashings = slasher.get_proposer_slashings_for_validator(1).unwrap();
+    assert_eq!(slashings.len(), 1);
+    assert_eq!(slashings[0].signed_header_2, block(2, 1, 1));
+}
+
+#[test]
+fn query_blocks_after_pruning() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        history_length: 16,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    slasher.accept_block_header(block(1, 0, 0));
+    slasher.accept_block_header(block(1, 1, 0));
+    slasher.accept_block_header(block(17 * 32, 0, 0));
+    slasher.process_queued(Epoch::new(17)).unwrap();
+    assert_eq!(
+        slasher.get_blocks_for_proposer(0).unwrap(),
+        vec![block(1, 0, 0), block(17 * 32, 0, 0)]
+    );
+
+    // Only the block within the history length remains.
+    slasher.process_queued(Epoch::new(20)).unwrap();
+    assert_eq!(
+        slasher.get_blocks_for_proposer(0).unwrap(),
+        vec![block(17 * 32, 0, 0)]
+    );
+    assert!(slasher.get_blocks_for_proposer(1).unwrap().is_empty());
+}
+
+#[test]
+fn full_queue_drops_oldest_attestations() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        max_queued_attestations: 2,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    // The first attestation is dropped, so the double vote is not detected.
+    slasher.accept_attestation(indexed_att(vec![0], 0, 1, 0));
+    slasher.accept_attestation(indexed_att(vec![0], 0, 1, 1));
+    slasher.accept_attestation(indexed_att(vec![1], 0, 1, 0));
+    slasher.process_queued(Epoch::new(1)).unwrap();
+    assert!(slasher.get_attester_slashings().is_empty());
+
+    assert_eq!(
+        slasher.get_attestations_for_validator(0).unwrap(),
+        vec![indexed_att(vec![0], 0, 1, 1)]
+    );
+}
+
+#[test]
+fn import_attestations() {
+    let tempdir = tempdir().unwrap();
+    let config = Config {
+        max_queued_attestations: 2,
+        ..Config::new(tempdir.path().into())
+    };
+    let slasher = open_slasher(config);
+
+    let surrounding = indexed_att(vec![0], 0, 3, 0);
+    let imported = slasher
+        .import_attestations(
+            vec![
+                indexed_att(vec![1], 0, 1, 0),
+                indexed_att(vec![1], 1, 2, 0),
+                surrounding.clone(),
+                // Future attestations are ignored.
+                indexed_att(vec![2], 0, 4, 0),
+            ],
+            Epoch::new(3),
+        )
+        .unwrap();
+    assert_eq!(imported, 3);
+    assert!(slasher
+        .get_attestations_for_validator(2)
+        .unwrap()
+        .is_empty());
+
+    // The imported attestations are checked against those which arrive later.
+    let surrounded = indexed_att(vec![0], 1, 2, 0);
+    slasher.accept_attestation(surrounded.clone());
+    slasher.process_queued(Epoch::new(3)).unwrap();
+    assert_eq!(
+        slasher
+            .get_attester_slashings()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![AttesterSlashing {
+            attestation_1: surrounding,
+            attestation_2: surrounded,
+        }]
+    );
+}
+
+#[test]
+fn batch_lock_held_until_guard_dropped() {
+    let tempdir = tempdir().unwrap();
+    let slasher = open_slasher(Config::new(tempdir.path().into()));
+    let batch_lock = slasher.batch_lock().clone();
+
+    let guard = batch_lock.try_lock().expect("lock should be free");
+    // Clones share the lock, including the slasher's own.
+    assert!(batch_lock.try_lock().is_none());
+    assert!(slasher.batch_lock().try_lock().is_none());
+
+    drop(guard);
+    assert!(slasher.batch_lock().try_lock().is_some());
+}
+
+#[test]
+fn batch_lock_released_by_other_thread() {
+    let tempdir = tempdir().unwrap();
+    let slasher = Arc::new(open_slasher(Config::new(tempdir.path().into())));
+    let guard = slasher
+        .batch_lock()
+        .try_lock()
+        .expect("lock should be free");
+
+    let (sender, receiver) = mpsc::channel();
+    let thread_slasher = slasher.clone();
+    let handle = thread::spawn(move || {
+        let _guard = guard;
+        receiver.recv().unwrap();
+        thread_slasher.process_queued(Epoch::new(0)).unwrap();
+    });
+
+    // Updates are skipped while the batch is being processed on the other thread.
+    assert!(slasher.batch_lock().try_lock().is_none());
+    sender.send(()).unwrap();
+    handle.join().unwrap();
+    assert!(slasher.batch_lock().try_lock().is_some());
+}
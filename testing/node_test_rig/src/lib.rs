@@ -13,7 +13,7 @@ use validator_dir::insecure_keys::build_deterministic_validator_dirs;
 
 pub use beacon_node::{ClientConfig, ClientGenesis, ProductionClient};
 pub use environment;
-pub use remote_beacon_node::RemoteBeaconNode;
+pub use remote_beacon_node::{BanRequest, RemoteBeaconNode};
 pub use validator_client::Config as ValidatorConfig;
 
 /// Provides a beacon node that is running in the current process on a given tokio executor (it
@@ -31,12 +31,23 @@ impl<E: EthSpec> LocalBeaconNode<E> {
     /// The node created is using the same types as the node we use in production.
     pub async fn production(
         context: RuntimeContext<E>,
-        mut client_config: ClientConfig,
+        client_config: ClientConfig,
     ) -> Result<Self, String> {
         // Creates a temporary directory that will be deleted once this `TempDir` is dropped.
         let datadir = TempDir::new("lighthouse_node_test_rig")
             .expect("should create temp directory for client datadir");
 
+        Self::production_with_datadir(context, client_config, datadir).await
+    }
+
+    /// Starts a new, production beacon node which stores its data in `datadir`.
+    ///
+    /// Providing the `datadir` of a node that has been shut down restarts that node.
+    pub async fn production_with_datadir(
+        context: RuntimeContext<E>,
+        mut client_config: ClientConfig,
+        datadir: TempDir,
+    ) -> Result<Self, String> {
         client_config.data_dir = datadir.path().into();
         client_config.network.network_dir = PathBuf::from(datadir.path()).join("network");
 
@@ -151,3 +151,27 @@ pub async fn verify_full_block_production_up_to<E: EthSpec>(
     }
     Ok(())
 }
+
+/// Verifies that all beacon nodes in the given `network` agree on the head block and the
+/// finalized checkpoint.
+pub async fn verify_consistent_heads<E: EthSpec>(network: &LocalNetwork<E>) -> Result<(), String> {
+    let mut heads = Vec::new();
+    for remote_node in network.remote_nodes()? {
+        let head = remote_node
+            .http
+            .beacon()
+            .get_head()
+            .await
+            .map_err(|e| format!("Get head via http failed: {:?}", e))?;
+        heads.push((head.block_root, head.finalized_block_root));
+    }
+
+    if heads.windows(2).any(|pair| pair[0] != pair[1]) {
+        Err(format!(
+            "Nodes disagree on the head or finalized block. (Head, finalized) roots: {:?}",
+            heads
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -105,4 +105,52 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .help("Sync verification strategy to run."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("fault-sim")
+                .about("Run scenarios which inject network faults and verify that nodes recover")
+                .arg(
+                    Arg::with_name("speedup")
+                        .short("s")
+                        .long("speedup")
+                        .takes_value(true)
+                        .default_value("15")
+                        .help("Speed up factor for slot production"),
+                )
+                .arg(
+                    Arg::with_name("initial_delay")
+                        .short("i")
+                        .long("initial_delay")
+                        .takes_value(true)
+                        .default_value("4")
+                        .help("Epoch delay before each fault is injected"),
+                )
+                .arg(
+                    Arg::with_name("fault_duration")
+                        .long("fault_duration")
+                        .takes_value(true)
+                        .default_value("2")
+                        .help("Duration of each fault in epochs"),
+                )
+                .arg(
+                    Arg::with_name("recovery_timeout")
+                        .long("recovery_timeout")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("Number of epochs after which all nodes must agree on the head"),
+                )
+                .arg(
+                    Arg::with_name("scenario")
+                        .long("scenario")
+                        .takes_value(true)
+                        .default_value("all")
+                        .possible_values(&[
+                            "partition",
+                            "delayed-gossip",
+                            "invalid-blocks",
+                            "restart-mid-sync",
+                            "all",
+                        ])
+                        .help("Fault scenario to run."),
+                ),
+        )
 }
@@ -100,7 +100,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         let mut beacon_config = testing_client_config();
 
         beacon_config.genesis = ClientGenesis::DepositContract;
-        beacon_config.eth1.endpoint = eth1_endpoint;
+        beacon_config.eth1.endpoints = vec![eth1_endpoint];
         beacon_config.eth1.deposit_contract_address = deposit_contract_address;
         beacon_config.eth1.deposit_contract_deploy_block = 0;
         beacon_config.eth1.lowest_cached_block_number = 0;
@@ -108,6 +108,8 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         beacon_config.dummy_eth1_backend = false;
         beacon_config.sync_eth1_chain = true;
         beacon_config.eth1.network_id = Eth1NetworkId::Custom(network_id);
+        // Ganache does not serve a chain id which matches its network id.
+        beacon_config.eth1.chain_id = None;
 
         beacon_config.network.enr_address = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
 
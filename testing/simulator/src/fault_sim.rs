@@ -0,0 +1,303 @@
+//! Runs scripted scenarios which inject faults into a local network and then verify that all of
+//! the nodes eventually agree on the head of the chain.
+//!
+//! The available scenarios are:
+//!
+//! - `partition`: the network is split into two groups of nodes, each with half of the
+//!   validators, which cannot communicate for a number of epochs.
+//! - `delayed-gossip`: a single node processes all gossip messages a slot after receiving them
+//!   for a number of epochs.
+//! - `invalid-blocks`: a node which joins late has to sync from a peer which serves blocks with
+//!   invalid signatures.
+//! - `restart-mid-sync`: a node which joins late is restarted before it has finished syncing.
+//!
+//! Partitions are created through the user ban list of each node, so nodes are disconnected at the
+//! libp2p level rather than by dropping packets. Delayed gossip and invalid blocks are injected by
+//! restarting a node with the corresponding fault enabled in its network config.
+
+use crate::checks::{epoch_delay, verify_consistent_heads};
+use crate::local_network::LocalNetwork;
+use crate::sync_sim::check_still_syncing;
+use clap::ArgMatches;
+use futures::prelude::*;
+use node_test_rig::{
+    environment::EnvironmentBuilder, testing_client_config, ClientConfig, ClientGenesis,
+    ValidatorConfig, ValidatorFiles,
+};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{Epoch, EthSpec, Slot};
+
+/// The number of beacon nodes in the network, including the boot node.
+const NODE_COUNT: usize = 4;
+/// The total number of validators, split evenly between the boot node and the third node.
+const VALIDATOR_COUNT: usize = 8;
+
+pub fn run_fault_sim(matches: &ArgMatches) -> Result<(), String> {
+    let speed_up_factor = value_t!(matches, "speedup", u64).unwrap();
+    let initial_delay = value_t!(matches, "initial_delay", u64).unwrap();
+    let fault_duration = value_t!(matches, "fault_duration", u64).unwrap();
+    let recovery_timeout = value_t!(matches, "recovery_timeout", u64).unwrap();
+    let scenario = value_t!(matches, "scenario", String).unwrap();
+
+    println!("Fault Injection Simulator:");
+    println!(" speed up factor:{}", speed_up_factor);
+    println!(" initial_delay:{}", initial_delay);
+    println!(" fault duration:{}", fault_duration);
+    println!(" recovery timeout:{}", recovery_timeout);
+    println!(" scenario:{}", scenario);
+
+    let log_level = "debug";
+    let log_format = None;
+
+    let mut env = EnvironmentBuilder::minimal()
+        .async_logger(log_level, log_format)?
+        .multi_threaded_tokio_runtime()?
+        .build()?;
+
+    let spec = &mut env.eth2_config.spec;
+    spec.milliseconds_per_slot /= speed_up_factor;
+
+    let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+    let context = env.core_context();
+    let mut beacon_config = testing_client_config();
+
+    let genesis_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| "should get system time")?
+        + Duration::from_secs(5);
+    beacon_config.genesis = ClientGenesis::Interop {
+        validator_count: VALIDATOR_COUNT,
+        genesis_time: genesis_time.as_secs(),
+    };
+    beacon_config.dummy_eth1_backend = true;
+    beacon_config.sync_eth1_chain = true;
+
+    beacon_config.network.enr_address = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+
+    // Half of the validators run on the boot node and half on the third node, such that both
+    // groups of a partition keep producing blocks.
+    let first_half = (0..VALIDATOR_COUNT / 2).collect::<Vec<_>>();
+    let second_half = (VALIDATOR_COUNT / 2..VALIDATOR_COUNT).collect::<Vec<_>>();
+    let first_validator_files = ValidatorFiles::with_keystores(&first_half)?;
+    let second_validator_files = ValidatorFiles::with_keystores(&second_half)?;
+
+    let main_future = async {
+        let network = LocalNetwork::new(context, beacon_config.clone()).await?;
+        for _ in 1..NODE_COUNT {
+            network.add_beacon_node(beacon_config.clone()).await?;
+        }
+        network
+            .add_validator_client(ValidatorConfig::default(), 0, first_validator_files)
+            .await?;
+        network
+            .add_validator_client(ValidatorConfig::default(), 2, second_validator_files)
+            .await?;
+
+        let scenarios = FaultScenario {
+            network: network.clone(),
+            beacon_config,
+            slot_duration,
+            initial_delay,
+            fault_duration,
+            recovery_timeout,
+        };
+        match scenario.as_str() {
+            "partition" => scenarios.partition().await?,
+            "delayed-gossip" => scenarios.delayed_gossip().await?,
+            "invalid-blocks" => scenarios.invalid_blocks().await?,
+            "restart-mid-sync" => scenarios.restart_mid_sync().await?,
+            "all" => {
+                scenarios.partition().await?;
+                println!("Completed partition scenario");
+                scenarios.delayed_gossip().await?;
+                println!("Completed delayed gossip scenario");
+                scenarios.invalid_blocks().await?;
+                println!("Completed invalid blocks scenario");
+                scenarios.restart_mid_sync().await?;
+                println!("Completed restart mid-sync scenario");
+            }
+            _ => return Err("Invalid scenario".into()),
+        }
+
+        println!(
+            "Simulation complete. Finished with {} beacon nodes and {} validator clients",
+            network.beacon_node_count(),
+            network.validator_client_count()
+        );
+
+        // Be explicit about dropping the network, as this kills all the nodes.
+        drop(network);
+        Ok::<(), String>(())
+    };
+
+    env.runtime().block_on(main_future)
+}
+
+/// The parameters shared by all fault scenarios.
+struct FaultScenario<E: EthSpec> {
+    network: LocalNetwork<E>,
+    beacon_config: ClientConfig,
+    slot_duration: Duration,
+    /// The number of epochs to run the network for before injecting a fault.
+    initial_delay: u64,
+    /// The number of epochs a fault lasts.
+    fault_duration: u64,
+    /// The number of epochs within which the network must recover from a fault.
+    recovery_timeout: u64,
+}
+
+impl<E: EthSpec> FaultScenario<E> {
+    /// Splits the network into two groups which each have half of the validators, then heals the
+    /// partition and verifies that all nodes agree on a single chain.
+    async fn partition(&self) -> Result<(), String> {
+        self.delay_epochs(self.initial_delay).await;
+
+        println!("Partitioning the network");
+        self.network.set_partition(&[0, 1], true).await?;
+        self.delay_epochs(self.fault_duration).await;
+
+        println!("Healing the partition");
+        self.network.set_partition(&[0, 1], false).await?;
+        self.wait_for_consistency()
+            .map_err(|e| format!("Partition scenario error: {}", e))
+            .await
+    }
+
+    /// Restarts the last node such that it processes gossip a slot late, verifies that it keeps
+    /// following the chain and that it agrees with the rest of the network once the delay is
+    /// removed.
+    async fn delayed_gossip(&self) -> Result<(), String> {
+        self.delay_epochs(self.initial_delay).await;
+
+        let delayed_node = NODE_COUNT - 1;
+        println!("Delaying gossip on node {}", delayed_node);
+        let mut delayed_config = self.beacon_config.clone();
+        delayed_config.network.gossip_delay = Some(self.slot_duration);
+        self.network
+            .restart_beacon_node(delayed_node, delayed_config, self.slot_duration * 2)
+            .await?;
+        let start_slot = self.head_slot(delayed_node).await?;
+        self.delay_epochs(self.fault_duration).await;
+
+        let end_slot = self.head_slot(delayed_node).await?;
+        if end_slot <= start_slot {
+            return Err(format!(
+                "Delayed gossip scenario error: head of node {} did not advance from slot {}",
+                delayed_node, start_slot
+            ));
+        }
+
+        println!("Removing the gossip delay on node {}", delayed_node);
+        self.network
+            .restart_beacon_node(
+                delayed_node,
+                self.beacon_config.clone(),
+                self.slot_duration * 2,
+            )
+            .await?;
+        self.wait_for_consistency()
+            .map_err(|e| format!("Delayed gossip scenario error: {}", e))
+            .await
+    }
+
+    /// Restarts the second node such that it serves blocks with invalid signatures, then adds a
+    /// node which has to sync and verifies that it syncs to the same chain as the rest of the
+    /// network.
+    async fn invalid_blocks(&self) -> Result<(), String> {
+        let faulty_node = 1;
+        println!("Serving invalid blocks from node {}", faulty_node);
+        let mut faulty_config = self.beacon_config.clone();
+        faulty_config.network.serve_invalid_blocks = true;
+        self.network
+            .restart_beacon_node(faulty_node, faulty_config, self.slot_duration * 2)
+            .await?;
+        self.delay_epochs(self.initial_delay).await;
+
+        self.network
+            .add_beacon_node(self.beacon_config.clone())
+            .await?;
+        self.wait_for_consistency()
+            .map_err(|e| format!("Invalid blocks scenario error: {}", e))
+            .await?;
+        if check_still_syncing(&self.network).await? {
+            return Err("Invalid blocks scenario error: node is still syncing".into());
+        }
+
+        println!("Serving valid blocks from node {}", faulty_node);
+        self.network
+            .restart_beacon_node(
+                faulty_node,
+                self.beacon_config.clone(),
+                self.slot_duration * 2,
+            )
+            .await
+    }
+
+    /// Adds a node once the chain has progressed, restarts it whilst it is syncing and verifies
+    /// that it completes syncing.
+    async fn restart_mid_sync(&self) -> Result<(), String> {
+        self.delay_epochs(self.initial_delay).await;
+
+        let syncing_node = self.network.beacon_node_count();
+        self.network
+            .add_beacon_node(self.beacon_config.clone())
+            .await?;
+        // allow the node to find peers and start syncing
+        tokio::time::delay_for(self.slot_duration * 2).await;
+        if !check_still_syncing(&self.network).await? {
+            println!(
+                "Warning: node {} finished syncing before the restart",
+                syncing_node
+            );
+        }
+
+        self.network
+            .restart_beacon_node(
+                syncing_node,
+                self.beacon_config.clone(),
+                self.slot_duration * 2,
+            )
+            .await?;
+        self.wait_for_consistency()
+            .map_err(|e| format!("Restart mid-sync scenario error: {}", e))
+            .await?;
+
+        if check_still_syncing(&self.network).await? {
+            return Err("Restart mid-sync scenario error: node is still syncing".into());
+        }
+        Ok(())
+    }
+
+    /// Checks once per epoch whether all nodes agree on the head and finalized blocks, returning
+    /// an error if they still disagree after `recovery_timeout` epochs.
+    async fn wait_for_consistency(&self) -> Result<(), String> {
+        let mut result = Err("Recovery timeout is zero".to_string());
+        for _ in 0..self.recovery_timeout {
+            self.delay_epochs(1).await;
+            result = verify_consistent_heads(&self.network).await;
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Returns the slot of the head block of the beacon node at `index`.
+    async fn head_slot(&self, index: usize) -> Result<Slot, String> {
+        self.network
+            .remote_nodes()?
+            .get(index)
+            .ok_or_else(|| format!("No beacon node at index {}", index))?
+            .http
+            .beacon()
+            .get_head()
+            .await
+            .map(|head| head.slot)
+            .map_err(|e| format!("Get head via http failed: {:?}", e))
+    }
+
+    async fn delay_epochs(&self, epochs: u64) {
+        epoch_delay(Epoch::new(epochs), self.slot_duration, E::slots_per_epoch()).await
+    }
+}
@@ -1,10 +1,11 @@
 use node_test_rig::{
-    environment::RuntimeContext, ClientConfig, LocalBeaconNode, LocalValidatorClient,
+    environment::RuntimeContext, BanRequest, ClientConfig, LocalBeaconNode, LocalValidatorClient,
     RemoteBeaconNode, ValidatorConfig, ValidatorFiles,
 };
 use parking_lot::RwLock;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 use types::{Epoch, EthSpec};
 
 const BOOTNODE_PORT: u16 = 42424;
@@ -14,6 +15,9 @@ pub struct Inner<E: EthSpec> {
     pub context: RuntimeContext<E>,
     pub beacon_nodes: RwLock<Vec<LocalBeaconNode<E>>>,
     pub validator_clients: RwLock<Vec<LocalValidatorClient<E>>>,
+    /// The exit signals of the beacon nodes which can be shut down independently, indexed like
+    /// `beacon_nodes`. The boot node has no such signal.
+    beacon_node_exits: RwLock<Vec<Option<exit_future::Signal>>>,
 }
 
 /// Represents a set of interconnected `LocalBeaconNode` and `LocalValidatorClient`.
@@ -57,6 +61,7 @@ impl<E: EthSpec> LocalNetwork<E> {
                 context,
                 beacon_nodes: RwLock::new(vec![beacon_node]),
                 validator_clients: RwLock::new(vec![]),
+                beacon_node_exits: RwLock::new(vec![None]),
             }),
         })
     }
@@ -81,27 +86,111 @@ impl<E: EthSpec> LocalNetwork<E> {
     pub async fn add_beacon_node(&self, mut beacon_config: ClientConfig) -> Result<(), String> {
         let self_1 = self.clone();
         println!("Adding beacon node..");
-        {
-            let read_lock = self.beacon_nodes.read();
-
-            let boot_node = read_lock.first().expect("should have at least one node");
-
-            beacon_config.network.boot_nodes_enr.push(
-                boot_node
-                    .client
-                    .enr()
-                    .expect("bootnode must have a network"),
-            );
-        }
+        self.add_boot_node_enr(&mut beacon_config);
 
         let index = self.beacon_nodes.read().len();
 
-        let beacon_node = LocalBeaconNode::production(
-            self.context.service_context(format!("node_{}", index)),
-            beacon_config,
-        )
-        .await?;
+        let (context, exit_signal) = self
+            .context
+            .service_context_with_exit(format!("node_{}", index));
+        let beacon_node = LocalBeaconNode::production(context, beacon_config).await?;
         self_1.beacon_nodes.write().push(beacon_node);
+        self_1.beacon_node_exits.write().push(Some(exit_signal));
+        Ok(())
+    }
+
+    /// Shuts down the beacon node at `index` and starts it again with the same data directory, as
+    /// if its process had been restarted. The boot node cannot be restarted.
+    ///
+    /// The node is started again after `downtime`, which must be long enough for all of its
+    /// services to release the database.
+    pub async fn restart_beacon_node(
+        &self,
+        index: usize,
+        mut beacon_config: ClientConfig,
+        downtime: Duration,
+    ) -> Result<(), String> {
+        println!("Restarting beacon node {}..", index);
+        let exit_signal = self
+            .beacon_node_exits
+            .write()
+            .get_mut(index)
+            .and_then(Option::take)
+            .ok_or_else(|| format!("Beacon node {} cannot be restarted", index))?;
+        // The node is removed from the network whilst it is down, so that it is not queried by
+        // any checks.
+        let beacon_node = self.beacon_nodes.write().remove(index);
+        self.beacon_node_exits.write().remove(index);
+
+        exit_signal.fire();
+        let LocalBeaconNode { client, datadir } = beacon_node;
+        drop(client);
+        tokio::time::delay_for(downtime).await;
+
+        self.add_boot_node_enr(&mut beacon_config);
+        let (context, exit_signal) = self
+            .context
+            .service_context_with_exit(format!("node_{}", index));
+        let beacon_node =
+            LocalBeaconNode::production_with_datadir(context, beacon_config, datadir).await?;
+        self.beacon_nodes.write().insert(index, beacon_node);
+        self.beacon_node_exits
+            .write()
+            .insert(index, Some(exit_signal));
+        Ok(())
+    }
+
+    /// Adds the ENR of the 0'th beacon node to the boot nodes of `beacon_config`.
+    fn add_boot_node_enr(&self, beacon_config: &mut ClientConfig) {
+        let read_lock = self.beacon_nodes.read();
+
+        let boot_node = read_lock.first().expect("should have at least one node");
+
+        beacon_config.network.boot_nodes_enr.push(
+            boot_node
+                .client
+                .enr()
+                .expect("bootnode must have a network"),
+        );
+    }
+
+    /// Partitions the network in two by having every beacon node in `group` ban every beacon
+    /// node outside of it, and vice versa. If `partitioned` is false, the bans are removed and the
+    /// partition is healed.
+    pub async fn set_partition(&self, group: &[usize], partitioned: bool) -> Result<(), String> {
+        let peer_ids = self
+            .beacon_nodes
+            .read()
+            .iter()
+            .map(|beacon_node| {
+                beacon_node
+                    .client
+                    .local_peer_id()
+                    .map(|peer_id| peer_id.to_string())
+                    .ok_or_else(|| "Beacon node must have a network".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let remote_nodes = self.remote_nodes()?;
+
+        for (index, remote_node) in remote_nodes.iter().enumerate() {
+            let in_group = group.contains(&index);
+            for (other_index, peer_id) in peer_ids.iter().enumerate() {
+                if group.contains(&other_index) == in_group {
+                    continue;
+                }
+                let request = BanRequest {
+                    peer_id: Some(peer_id.clone()),
+                    ip: None,
+                };
+                let lighthouse = remote_node.http.lighthouse();
+                if partitioned {
+                    lighthouse.add_ban(request).await
+                } else {
+                    lighthouse.remove_ban(request).await
+                }
+                .map_err(|e| format!("Failed to update ban on node {}: {:?}", index, e))?;
+            }
+        }
         Ok(())
     }
 
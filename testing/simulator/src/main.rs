@@ -19,6 +19,7 @@ extern crate clap;
 mod checks;
 mod cli;
 mod eth1_sim;
+mod fault_sim;
 mod local_network;
 mod no_eth1_sim;
 mod sync_sim;
@@ -57,6 +58,13 @@ fn main() {
                 std::process::exit(1)
             }
         },
+        ("fault-sim", Some(matches)) => match fault_sim::run_fault_sim(matches) {
+            Ok(()) => println!("Simulation exited successfully"),
+            Err(e) => {
+                eprintln!("Simulation exited with error: {}", e);
+                std::process::exit(1)
+            }
+        },
         _ => {
             eprintln!("Invalid subcommand. Use --help to see available options");
             std::process::exit(1)
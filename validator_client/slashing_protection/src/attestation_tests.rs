@@ -166,6 +166,40 @@ fn invalid_source_exceeds_target() {
     .run()
 }
 
+#[test]
+fn invalid_source_below_lower_bound() {
+    StreamTest {
+        cases: vec![
+            Test::single(attestation_data_builder(2, 3)),
+            Test::single(attestation_data_builder(1, 2)).expect_invalid_att(
+                InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: Epoch::new(1),
+                    bound_epoch: Epoch::new(2),
+                },
+            ),
+        ],
+        ..StreamTest::default()
+    }
+    .run()
+}
+
+#[test]
+fn invalid_target_below_lower_bound() {
+    StreamTest {
+        cases: vec![
+            Test::single(attestation_data_builder(2, 5)),
+            Test::single(attestation_data_builder(2, 4)).expect_invalid_att(
+                InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: Epoch::new(4),
+                    bound_epoch: Epoch::new(5),
+                },
+            ),
+        ],
+        ..StreamTest::default()
+    }
+    .run()
+}
+
 #[test]
 fn invalid_unregistered_validator() {
     StreamTest {
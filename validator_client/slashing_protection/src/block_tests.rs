@@ -75,6 +75,21 @@ fn valid_same_block_different_validator() {
     .run()
 }
 
+#[test]
+fn invalid_slot_below_lower_bound() {
+    StreamTest {
+        cases: vec![
+            Test::single(block(5)),
+            Test::single(block(3)).expect_invalid_block(InvalidBlock::SlotViolatesLowerBound {
+                block_slot: Slot::new(3),
+                bound_slot: Slot::new(5),
+            }),
+        ],
+        ..StreamTest::default()
+    }
+    .run()
+}
+
 #[test]
 fn invalid_double_block_proposal() {
     let first_block = block(1);
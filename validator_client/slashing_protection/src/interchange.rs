@@ -0,0 +1,70 @@
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde_derive::Deserialize;
+use std::io::Read;
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+/// Slashing protection data in the interchange format defined by EIP-3076.
+///
+/// Only reading is supported, for checking the database against data exported by other clients.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<InterchangeData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InterchangeMetadata {
+    #[serde(deserialize_with = "quoted_u64")]
+    pub interchange_format_version: u64,
+    pub genesis_validators_root: Hash256,
+}
+
+/// The blocks and attestations signed by a single validator.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InterchangeData {
+    pub pubkey: PublicKey,
+    #[serde(default)]
+    pub signed_blocks: Vec<InterchangeBlock>,
+    #[serde(default)]
+    pub signed_attestations: Vec<InterchangeAttestation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InterchangeBlock {
+    #[serde(deserialize_with = "quoted_u64")]
+    pub slot: Slot,
+    #[serde(default)]
+    pub signing_root: Option<Hash256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InterchangeAttestation {
+    #[serde(deserialize_with = "quoted_u64")]
+    pub source_epoch: Epoch,
+    #[serde(deserialize_with = "quoted_u64")]
+    pub target_epoch: Epoch,
+    #[serde(default)]
+    pub signing_root: Option<Hash256>,
+}
+
+impl Interchange {
+    pub fn from_json_str(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn from_json_reader(reader: impl Read) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+}
+
+/// Integers are encoded as decimal strings in the interchange format.
+fn quoted_u64<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: From<u64>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse::<u64>()
+        .map(T::from)
+        .map_err(|e| D::Error::custom(format!("Invalid quoted integer {:?}: {}", s, e)))
+}
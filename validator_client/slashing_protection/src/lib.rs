@@ -1,11 +1,15 @@
 mod attestation_tests;
 mod block_tests;
+pub mod interchange;
+mod maintenance;
 mod parallel_tests;
 mod signed_attestation;
 mod signed_block;
 mod slashing_database;
 mod test_utils;
 
+pub use crate::interchange::Interchange;
+pub use crate::maintenance::{InvariantViolation, PruneSummary, ValidatorSummary};
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::SlashingDatabase;
@@ -14,6 +18,9 @@ use std::io::{Error as IOError, ErrorKind};
 use std::string::ToString;
 use types::{Hash256, PublicKey};
 
+/// The file name of the slashing protection database within the validator client's datadir.
+pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+
 /// The attestation or block is not safe to sign.
 ///
 /// This could be because it's slashable, or because an error occurred.
@@ -0,0 +1,111 @@
+use crate::SignedAttestation;
+use std::fmt;
+use types::{Epoch, Slot};
+
+/// A summary of the blocks and attestations recorded for a single validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorSummary {
+    /// The `0x`-prefixed hex public key of the validator.
+    pub public_key: String,
+    pub block_count: u64,
+    pub min_slot: Option<Slot>,
+    pub max_slot: Option<Slot>,
+    pub attestation_count: u64,
+    pub min_source_epoch: Option<Epoch>,
+    pub max_source_epoch: Option<Epoch>,
+    pub min_target_epoch: Option<Epoch>,
+    pub max_target_epoch: Option<Epoch>,
+}
+
+/// A record in the database which breaks one of the invariants upheld by `SlashingDatabase`, or
+/// a record in an interchange file which the database doesn't protect against.
+///
+/// Double votes and double block proposals are prevented by the database schema, so are not
+/// included.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantViolation {
+    /// The public key is registered more than once.
+    DuplicateValidator { public_key: String, count: u64 },
+    /// The attestation's source epoch is greater than its target epoch.
+    SourceExceedsTarget {
+        public_key: String,
+        attestation: SignedAttestation,
+    },
+    /// The validator signed an attestation which surrounds another of its attestations.
+    SurroundVote {
+        public_key: String,
+        surrounding: SignedAttestation,
+        surrounded: SignedAttestation,
+    },
+    /// A validator in the interchange is not registered in the database.
+    MissingValidator { public_key: String },
+    /// A block in the interchange is neither in the database nor below its lowest slot, so a
+    /// conflicting block could be signed.
+    UnprotectedBlock { public_key: String, slot: Slot },
+    /// An attestation in the interchange is neither in the database nor below its lowest source
+    /// and target epochs, so a conflicting attestation could be signed.
+    UnprotectedAttestation {
+        public_key: String,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::DuplicateValidator { public_key, count } => {
+                write!(f, "{} is registered {} times", public_key, count)
+            }
+            InvariantViolation::SourceExceedsTarget {
+                public_key,
+                attestation,
+            } => write!(
+                f,
+                "{} signed an attestation with source {} greater than target {}",
+                public_key, attestation.source_epoch, attestation.target_epoch
+            ),
+            InvariantViolation::SurroundVote {
+                public_key,
+                surrounding,
+                surrounded,
+            } => write!(
+                f,
+                "{} signed an attestation ({}, {}) surrounding another ({}, {})",
+                public_key,
+                surrounding.source_epoch,
+                surrounding.target_epoch,
+                surrounded.source_epoch,
+                surrounded.target_epoch
+            ),
+            InvariantViolation::MissingValidator { public_key } => write!(
+                f,
+                "{} is in the interchange but not registered in the database",
+                public_key
+            ),
+            InvariantViolation::UnprotectedBlock { public_key, slot } => write!(
+                f,
+                "{} signed a block at slot {} in the interchange which the database doesn't \
+                 protect",
+                public_key, slot
+            ),
+            InvariantViolation::UnprotectedAttestation {
+                public_key,
+                source_epoch,
+                target_epoch,
+            } => write!(
+                f,
+                "{} signed an attestation ({}, {}) in the interchange which the database \
+                 doesn't protect",
+                public_key, source_epoch, target_epoch
+            ),
+        }
+    }
+}
+
+/// The number of records deleted by `SlashingDatabase::prune`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PruneSummary {
+    pub blocks: usize,
+    pub attestations: usize,
+}
@@ -12,6 +12,12 @@ pub struct SignedBlock {
 #[derive(PartialEq, Debug)]
 pub enum InvalidBlock {
     DoubleBlockProposal(SignedBlock),
+    /// The block's slot is less than or equal to the lowest slot in the database, so it can't
+    /// be checked against blocks that may have been pruned.
+    SlotViolatesLowerBound {
+        block_slot: Slot,
+        bound_slot: Slot,
+    },
 }
 
 impl SignedBlock {
@@ -1,12 +1,15 @@
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
-use crate::{NotSafe, Safe, SignedAttestation, SignedBlock};
+use crate::{
+    hash256_from_row, Interchange, InvariantViolation, NotSafe, PruneSummary, Safe,
+    SignedAttestation, SignedBlock, ValidatorSummary,
+};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs::{File, OpenOptions};
 use std::path::Path;
 use std::time::Duration;
-use types::{AttestationData, BeaconBlockHeader, Hash256, PublicKey, SignedRoot};
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKey, SignedRoot, Slot};
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
@@ -129,6 +132,18 @@ impl SlashingDatabase {
     #[cfg(windows)]
     fn set_db_file_permissions(file: &File) -> Result<(), NotSafe> {}
 
+    /// Acquire the exclusive lock on the database, which is held until `self` is dropped.
+    ///
+    /// Returns an error if the lock is held by another process, such as a running validator
+    /// client.
+    pub fn lock_exclusive(&self) -> Result<(), NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        // In exclusive locking mode the lock is retained after the transaction ends.
+        conn.transaction_with_behavior(TransactionBehavior::Exclusive)?
+            .commit()?;
+        Ok(())
+    }
+
     /// Register a validator with the slashing protection database.
     ///
     /// This allows the validator to record their signatures in the database, and check
@@ -138,6 +153,8 @@ impl SlashingDatabase {
     }
 
     /// Register multiple validators with the slashing protection database.
+    ///
+    /// Validators which are already registered are ignored.
     pub fn register_validators<'a>(
         &self,
         public_keys: impl Iterator<Item = &'a PublicKey>,
@@ -148,7 +165,9 @@ impl SlashingDatabase {
             let mut stmt = txn.prepare("INSERT INTO validators (public_key) VALUES (?1)")?;
 
             for pubkey in public_keys {
-                stmt.execute(&[pubkey.to_hex_string()])?;
+                if Self::get_validator_id_opt(&txn, pubkey)?.is_none() {
+                    stmt.execute(&[pubkey.to_hex_string()])?;
+                }
             }
         }
         txn.commit()?;
@@ -161,13 +180,22 @@ impl SlashingDatabase {
     /// This is NOT the same as a validator index, and depends on the ordering that validators
     /// are registered with the slashing protection database (and may vary between machines).
     fn get_validator_id(txn: &Transaction, public_key: &PublicKey) -> Result<i64, NotSafe> {
-        txn.query_row(
-            "SELECT id FROM validators WHERE public_key = ?1",
-            params![&public_key.to_hex_string()],
-            |row| row.get(0),
-        )
-        .optional()?
-        .ok_or_else(|| NotSafe::UnregisteredValidator(public_key.clone()))
+        Self::get_validator_id_opt(txn, public_key)?
+            .ok_or_else(|| NotSafe::UnregisteredValidator(public_key.clone()))
+    }
+
+    /// Optional version of `get_validator_id`.
+    fn get_validator_id_opt(
+        txn: &Transaction,
+        public_key: &PublicKey,
+    ) -> Result<Option<i64>, NotSafe> {
+        Ok(txn
+            .query_row(
+                "SELECT id FROM validators WHERE public_key = ?1",
+                params![&public_key.to_hex_string()],
+                |row| row.get(0),
+            )
+            .optional()?)
     }
 
     /// Check a block proposal from `validator_pubkey` for slash safety.
@@ -195,15 +223,31 @@ impl SlashingDatabase {
         if let Some(existing_block) = existing_block {
             if existing_block.signing_root == block_header.signing_root(domain) {
                 // Same slot and same hash -> we're re-broadcasting a previously signed block
-                Ok(Safe::SameData)
+                return Ok(Safe::SameData);
             } else {
                 // Same epoch but not the same hash -> it's a DoubleBlockProposal
-                Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
+                return Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
                     existing_block,
-                )))
+                )));
             }
-        } else {
-            Ok(Safe::Valid)
+        }
+
+        // Refuse blocks at or below the lowest slot signed, as the blocks they conflict with may
+        // have been pruned.
+        let min_slot: Option<Slot> = txn.query_row(
+            "SELECT MIN(slot) FROM signed_blocks WHERE validator_id = ?1",
+            params![validator_id],
+            |row| row.get(0),
+        )?;
+
+        match min_slot {
+            Some(bound_slot) if block_header.slot <= bound_slot => Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound {
+                    block_slot: block_header.slot,
+                    bound_slot,
+                },
+            )),
+            _ => Ok(Safe::Valid),
         }
     }
 
@@ -299,6 +343,38 @@ impl SlashingDatabase {
             ));
         }
 
+        // 4. Check that the attestation is above the lowest source and target epochs signed, as
+        //    the attestations it conflicts with may have been pruned.
+        let (min_source, min_target): (Option<Epoch>, Option<Epoch>) = txn.query_row(
+            "SELECT MIN(source_epoch), MIN(target_epoch)
+             FROM signed_attestations
+             WHERE validator_id = ?1",
+            params![validator_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        if let Some(bound_epoch) = min_source {
+            if att_source_epoch < bound_epoch {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::SourceLessThanLowerBound {
+                        source_epoch: att_source_epoch,
+                        bound_epoch,
+                    },
+                ));
+            }
+        }
+
+        if let Some(bound_epoch) = min_target {
+            if att_target_epoch <= bound_epoch {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::TargetLessThanOrEqLowerBound {
+                        target_epoch: att_target_epoch,
+                        bound_epoch,
+                    },
+                ));
+            }
+        }
+
         // Everything has been checked, return Valid
         Ok(Safe::Valid)
     }
@@ -403,13 +479,250 @@ impl SlashingDatabase {
         txn.commit()?;
         Ok(safe)
     }
+
+    /// Returns a summary of the records held for each registered validator, in order of
+    /// registration.
+    pub fn validator_summaries(&self) -> Result<Vec<ValidatorSummary>, NotSafe> {
+        let conn = self.conn_pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT v.public_key,
+                (SELECT COUNT(*) FROM signed_blocks b WHERE b.validator_id = v.id),
+                (SELECT MIN(slot) FROM signed_blocks b WHERE b.validator_id = v.id),
+                (SELECT MAX(slot) FROM signed_blocks b WHERE b.validator_id = v.id),
+                (SELECT COUNT(*) FROM signed_attestations a WHERE a.validator_id = v.id),
+                (SELECT MIN(source_epoch) FROM signed_attestations a WHERE a.validator_id = v.id),
+                (SELECT MAX(source_epoch) FROM signed_attestations a WHERE a.validator_id = v.id),
+                (SELECT MIN(target_epoch) FROM signed_attestations a WHERE a.validator_id = v.id),
+                (SELECT MAX(target_epoch) FROM signed_attestations a WHERE a.validator_id = v.id)
+             FROM validators v
+             ORDER BY v.id",
+        )?;
+
+        let summaries = stmt
+            .query_map(params![], |row| {
+                Ok(ValidatorSummary {
+                    public_key: row.get(0)?,
+                    block_count: row.get::<_, i64>(1)? as u64,
+                    min_slot: row.get(2)?,
+                    max_slot: row.get(3)?,
+                    attestation_count: row.get::<_, i64>(4)? as u64,
+                    min_source_epoch: row.get(5)?,
+                    max_source_epoch: row.get(6)?,
+                    min_target_epoch: row.get(7)?,
+                    max_target_epoch: row.get(8)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(summaries)
+    }
+
+    /// Returns every record in the database which violates an invariant that should be upheld
+    /// by `check_and_insert_block_proposal` and `check_and_insert_attestation`.
+    ///
+    /// Such records may be present if the database was modified by other software.
+    pub fn check_invariants(&self) -> Result<Vec<InvariantViolation>, NotSafe> {
+        let conn = self.conn_pool.get()?;
+        let mut violations = vec![];
+
+        violations.extend(
+            conn.prepare(
+                "SELECT public_key, COUNT(*)
+                 FROM validators
+                 GROUP BY public_key
+                 HAVING COUNT(*) > 1",
+            )?
+            .query_map(params![], |row| {
+                Ok(InvariantViolation::DuplicateValidator {
+                    public_key: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        violations.extend(
+            conn.prepare(
+                "SELECT v.public_key, a.source_epoch, a.target_epoch, a.signing_root
+                 FROM signed_attestations a
+                 JOIN validators v ON v.id = a.validator_id
+                 WHERE a.source_epoch > a.target_epoch",
+            )?
+            .query_map(params![], |row| {
+                Ok(InvariantViolation::SourceExceedsTarget {
+                    public_key: row.get(0)?,
+                    attestation: SignedAttestation::new(
+                        row.get(1)?,
+                        row.get(2)?,
+                        hash256_from_row(3, row)?,
+                    ),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        violations.extend(
+            conn.prepare(
+                "SELECT v.public_key,
+                    a1.source_epoch, a1.target_epoch, a1.signing_root,
+                    a2.source_epoch, a2.target_epoch, a2.signing_root
+                 FROM signed_attestations a1
+                 JOIN signed_attestations a2
+                    ON a1.validator_id = a2.validator_id
+                    AND a1.source_epoch < a2.source_epoch
+                    AND a1.target_epoch > a2.target_epoch
+                 JOIN validators v ON v.id = a1.validator_id",
+            )?
+            .query_map(params![], |row| {
+                Ok(InvariantViolation::SurroundVote {
+                    public_key: row.get(0)?,
+                    surrounding: SignedAttestation::new(
+                        row.get(1)?,
+                        row.get(2)?,
+                        hash256_from_row(3, row)?,
+                    ),
+                    surrounded: SignedAttestation::new(
+                        row.get(4)?,
+                        row.get(5)?,
+                        hash256_from_row(6, row)?,
+                    ),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        Ok(violations)
+    }
+
+    /// Returns every record in `interchange` which the database would not prevent the validator
+    /// from signing a slashable counterpart to.
+    ///
+    /// A record is protected if the database contains it, or if it is below the lower bounds
+    /// enforced by `check_and_insert_block_proposal` and `check_and_insert_attestation`.
+    pub fn check_interchange(
+        &self,
+        interchange: &Interchange,
+    ) -> Result<Vec<InvariantViolation>, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        let mut violations = vec![];
+
+        for data in &interchange.data {
+            let public_key = data.pubkey.to_hex_string();
+
+            let validator_id = match Self::get_validator_id_opt(&txn, &data.pubkey)? {
+                Some(validator_id) => validator_id,
+                None => {
+                    violations.push(InvariantViolation::MissingValidator { public_key });
+                    continue;
+                }
+            };
+
+            let min_slot: Option<Slot> = txn.query_row(
+                "SELECT MIN(slot) FROM signed_blocks WHERE validator_id = ?1",
+                params![validator_id],
+                |row| row.get(0),
+            )?;
+            let (min_source, min_target): (Option<Epoch>, Option<Epoch>) = txn.query_row(
+                "SELECT MIN(source_epoch), MIN(target_epoch)
+                 FROM signed_attestations
+                 WHERE validator_id = ?1",
+                params![validator_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+            for block in &data.signed_blocks {
+                if min_slot.map_or(false, |bound| block.slot <= bound) {
+                    continue;
+                }
+
+                let exists = txn
+                    .prepare("SELECT 1 FROM signed_blocks WHERE validator_id = ?1 AND slot = ?2")?
+                    .exists(params![validator_id, block.slot])?;
+
+                if !exists {
+                    violations.push(InvariantViolation::UnprotectedBlock {
+                        public_key: public_key.clone(),
+                        slot: block.slot,
+                    });
+                }
+            }
+
+            for att in &data.signed_attestations {
+                // Any attestation conflicting with one at or below both lower bounds is refused.
+                if min_source.map_or(false, |bound| att.source_epoch <= bound)
+                    && min_target.map_or(false, |bound| att.target_epoch <= bound)
+                {
+                    continue;
+                }
+
+                let exists = txn
+                    .prepare(
+                        "SELECT 1 FROM signed_attestations
+                         WHERE validator_id = ?1 AND source_epoch = ?2 AND target_epoch = ?3",
+                    )?
+                    .exists(params![validator_id, att.source_epoch, att.target_epoch])?;
+
+                if !exists {
+                    violations.push(InvariantViolation::UnprotectedAttestation {
+                        public_key: public_key.clone(),
+                        source_epoch: att.source_epoch,
+                        target_epoch: att.target_epoch,
+                    });
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Deletes the blocks and attestations of each validator which are more than
+    /// `history_length` epochs older than its most recent block or attestation respectively.
+    ///
+    /// The most recent block and attestation of each validator are always retained.
+    pub fn prune(
+        &self,
+        history_length: Epoch,
+        slots_per_epoch: u64,
+    ) -> Result<PruneSummary, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+        let blocks = txn.execute(
+            "DELETE FROM signed_blocks
+             WHERE slot + ?1 < (
+                SELECT MAX(b.slot) FROM signed_blocks b
+                WHERE b.validator_id = signed_blocks.validator_id
+             )",
+            params![history_length.start_slot(slots_per_epoch)],
+        )?;
+
+        let attestations = txn.execute(
+            "DELETE FROM signed_attestations
+             WHERE target_epoch + ?1 < (
+                SELECT MAX(a.target_epoch) FROM signed_attestations a
+                WHERE a.validator_id = signed_attestations.validator_id
+             )",
+            params![history_length],
+        )?;
+
+        txn.commit()?;
+
+        Ok(PruneSummary {
+            blocks,
+            attestations,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::pubkey;
+    use crate::attestation_tests::attestation_data_builder;
+    use crate::block_tests::block;
+    use crate::test_utils::{pubkey, DEFAULT_DOMAIN};
     use tempfile::tempdir;
+    use types::Slot;
 
     #[test]
     fn open_non_existent_error() {
@@ -439,6 +752,293 @@ mod tests {
         SlashingDatabase::create(&file).unwrap_err();
     }
 
+    // Registering a validator more than once should not create duplicate records.
+    #[test]
+    fn double_register() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        db.register_validator(&pubkey(0)).unwrap();
+        db.register_validators(vec![pubkey(0), pubkey(1)].iter())
+            .unwrap();
+
+        let conn = db.conn_pool.get().unwrap();
+        let num_validators: i64 = conn
+            .query_row("SELECT COUNT(*) FROM validators", params![], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(num_validators, 2);
+    }
+
+    #[test]
+    fn validator_summaries() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        db.register_validators(vec![pubkey(0), pubkey(1)].iter())
+            .unwrap();
+        for slot in &[1, 3, 7] {
+            db.check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN)
+                .unwrap();
+        }
+        for (source, target) in &[(0, 1), (1, 2), (2, 4)] {
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(*source, *target),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        }
+
+        let summaries = db.validator_summaries().unwrap();
+        assert_eq!(
+            summaries,
+            vec![
+                ValidatorSummary {
+                    public_key: pubkey(0).to_hex_string(),
+                    block_count: 3,
+                    min_slot: Some(Slot::new(1)),
+                    max_slot: Some(Slot::new(7)),
+                    attestation_count: 3,
+                    min_source_epoch: Some(Epoch::new(0)),
+                    max_source_epoch: Some(Epoch::new(2)),
+                    min_target_epoch: Some(Epoch::new(1)),
+                    max_target_epoch: Some(Epoch::new(4)),
+                },
+                ValidatorSummary {
+                    public_key: pubkey(1).to_hex_string(),
+                    block_count: 0,
+                    min_slot: None,
+                    max_slot: None,
+                    attestation_count: 0,
+                    min_source_epoch: None,
+                    max_source_epoch: None,
+                    min_target_epoch: None,
+                    max_target_epoch: None,
+                }
+            ]
+        );
+    }
+
+    // Records inserted by other software should be checked for slashable data.
+    #[test]
+    fn check_invariants() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        db.register_validator(&pubkey(0)).unwrap();
+        db.check_and_insert_attestation(
+            &pubkey(0),
+            &attestation_data_builder(1, 2),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+        assert_eq!(db.check_invariants().unwrap(), vec![]);
+
+        let conn = db.conn_pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO validators (public_key) VALUES (?1)",
+            params![pubkey(0).to_hex_string()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO signed_attestations (validator_id, source_epoch, target_epoch, signing_root)
+             VALUES (1, 0, 3, ?1), (1, 5, 4, ?1)",
+            params![Hash256::zero().as_bytes()],
+        )
+        .unwrap();
+        drop(conn);
+
+        let violations = db.check_invariants().unwrap();
+        assert_eq!(violations.len(), 3);
+        assert!(
+            violations.contains(&InvariantViolation::DuplicateValidator {
+                public_key: pubkey(0).to_hex_string(),
+                count: 2,
+            })
+        );
+        assert!(violations.iter().any(|violation| matches!(
+            violation,
+            InvariantViolation::SourceExceedsTarget { attestation, .. }
+                if attestation.source_epoch == 5
+        )));
+        assert!(violations.iter().any(|violation| matches!(
+            violation,
+            InvariantViolation::SurroundVote { surrounding, surrounded, .. }
+                if surrounding.source_epoch == 0 && surrounded.source_epoch == 1
+        )));
+    }
+
+    #[test]
+    fn prune() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let slots_per_epoch = 8;
+
+        db.register_validators(vec![pubkey(0), pubkey(1)].iter())
+            .unwrap();
+        for slot in &[0, 16, 40] {
+            db.check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN)
+                .unwrap();
+        }
+        for target in 1..=10 {
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(target - 1, target),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        }
+        db.check_and_insert_attestation(
+            &pubkey(1),
+            &attestation_data_builder(0, 1),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+
+        let summary = db.prune(Epoch::new(3), slots_per_epoch).unwrap();
+        assert_eq!(
+            summary,
+            PruneSummary {
+                blocks: 1,
+                attestations: 6,
+            }
+        );
+
+        let summaries = db.validator_summaries().unwrap();
+        assert_eq!(summaries[0].min_slot, Some(Slot::new(16)));
+        assert_eq!(summaries[0].min_target_epoch, Some(Epoch::new(7)));
+        assert_eq!(summaries[0].max_target_epoch, Some(Epoch::new(10)));
+        // The most recent attestation of each validator is always retained.
+        assert_eq!(summaries[1].attestation_count, 1);
+
+        // Records below the pruned history can't be signed, even though the records they
+        // conflict with were deleted.
+        assert_eq!(
+            db.check_and_insert_block_proposal(&pubkey(0), &block(8), DEFAULT_DOMAIN),
+            Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound {
+                    block_slot: Slot::new(8),
+                    bound_slot: Slot::new(16),
+                }
+            ))
+        );
+        assert_eq!(
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(5, 6),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: Epoch::new(5),
+                    bound_epoch: Epoch::new(6),
+                }
+            ))
+        );
+        assert_eq!(
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(6, 6),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: Epoch::new(6),
+                    bound_epoch: Epoch::new(7),
+                }
+            ))
+        );
+
+        // Signing continues as normal above the retained history.
+        db.check_and_insert_block_proposal(&pubkey(0), &block(48), DEFAULT_DOMAIN)
+            .unwrap();
+        db.check_and_insert_attestation(
+            &pubkey(0),
+            &attestation_data_builder(10, 11),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn check_interchange() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        db.register_validator(&pubkey(0)).unwrap();
+        for slot in &[10, 20] {
+            db.check_and_insert_block_proposal(&pubkey(0), &block(*slot), DEFAULT_DOMAIN)
+                .unwrap();
+        }
+        for (source, target) in &[(3, 4), (4, 5)] {
+            db.check_and_insert_attestation(
+                &pubkey(0),
+                &attestation_data_builder(*source, *target),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        }
+
+        let json = format!(
+            r#"{{
+                "metadata": {{
+                    "interchange_format_version": "4",
+                    "genesis_validators_root": "0x{root}"
+                }},
+                "data": [
+                    {{
+                        "pubkey": "{pk0}",
+                        "signed_blocks": [
+                            {{ "slot": "5" }},
+                            {{ "slot": "20", "signing_root": "0x{root}" }},
+                            {{ "slot": "15" }}
+                        ],
+                        "signed_attestations": [
+                            {{ "source_epoch": "1", "target_epoch": "2" }},
+                            {{ "source_epoch": "4", "target_epoch": "5" }},
+                            {{ "source_epoch": "2", "target_epoch": "6" }}
+                        ]
+                    }},
+                    {{
+                        "pubkey": "{pk1}",
+                        "signed_blocks": [],
+                        "signed_attestations": []
+                    }}
+                ]
+            }}"#,
+            root = "00".repeat(32),
+            pk0 = pubkey(0).to_hex_string(),
+            pk1 = pubkey(1).to_hex_string(),
+        );
+        let interchange = Interchange::from_json_str(&json).unwrap();
+        assert_eq!(interchange.metadata.interchange_format_version, 4);
+
+        assert_eq!(
+            db.check_interchange(&interchange).unwrap(),
+            vec![
+                InvariantViolation::UnprotectedBlock {
+                    public_key: pubkey(0).to_hex_string(),
+                    slot: Slot::new(15),
+                },
+                InvariantViolation::UnprotectedAttestation {
+                    public_key: pubkey(0).to_hex_string(),
+                    source_epoch: Epoch::new(2),
+                    target_epoch: Epoch::new(6),
+                },
+                InvariantViolation::MissingValidator {
+                    public_key: pubkey(1).to_hex_string(),
+                },
+            ]
+        );
+    }
+
     // Check that both `open` and `create` apply the same connection settings.
     #[test]
     fn connection_settings_applied() {
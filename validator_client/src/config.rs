@@ -7,8 +7,7 @@ use types::{Graffiti, GRAFFITI_BYTES_LEN};
 pub const DEFAULT_HTTP_SERVER: &str = "http://localhost:5052/";
 pub const DEFAULT_DATA_DIR: &str = ".lighthouse/validators";
 pub const DEFAULT_SECRETS_DIR: &str = ".lighthouse/secrets";
-/// Path to the slashing protection database within the datadir.
-pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+pub use slashing_protection::SLASHING_PROTECTION_FILENAME;
 
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Serialize, Deserialize)]